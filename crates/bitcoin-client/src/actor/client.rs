@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::actor::messages::{
-    BroadcastTransactionMessage, GetBestBlockHashMessage, GetBlockHashMessage,
-    GetBlockHeaderInfoMessage, GetBlockMessage, GetChainTipsMessage, GetRawTransactionMessage,
-    GetTxOutMessage,
+    BroadcastTransactionMessage, EstimateSmartFeeMessage, GetBestBlockHashMessage,
+    GetBlockHashMessage, GetBlockHeaderInfoMessage, GetBlockMessage, GetChainTipsMessage,
+    GetRawTransactionMessage, GetTxOutMessage,
 };
+use crate::local::LocalBlockStore;
 use anyhow::Result;
 use async_trait::async_trait;
 use bitcoin::Transaction;
@@ -13,11 +14,14 @@ use bitcoincore_rpc::{bitcoin::Txid, json, Auth, Client, RpcApi};
 use coerce::actor::{context::ActorContext, message::Handler, Actor};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tracing::warn;
 
 pub struct BitcoinClientActor {
-    rpc_client: Client,
+    rpc_client: Option<Client>,
+    // the blocks are read from local files instead of the RPC if set
+    local_blocks: Option<Arc<LocalBlockStore>>,
     max_retries: u32,
     retry_delay: Duration,
     reorg_block_store_dir: Option<PathBuf>,
@@ -28,16 +32,23 @@ pub struct BitcoinClientConfig {
     pub btc_rpc_user_name: String,
     pub btc_rpc_password: String,
     pub local_block_store_dir: Option<PathBuf>,
+    /// The local Bitcoin blocks, see [`LocalBlockStore`].
+    /// If set, the blocks are read from it and the RPC is not used.
+    /// The store is shared by the clients because indexing the blocks is expensive.
+    pub local_blocks: Option<Arc<LocalBlockStore>>,
 }
 
 impl BitcoinClientConfig {
     pub fn build(&self) -> Result<BitcoinClientActor> {
-        BitcoinClientActor::new(
-            &self.btc_rpc_url,
-            &self.btc_rpc_user_name,
-            &self.btc_rpc_password,
-            self.local_block_store_dir.clone(),
-        )
+        match &self.local_blocks {
+            Some(local_blocks) => Ok(BitcoinClientActor::new_local(local_blocks.clone())),
+            None => BitcoinClientActor::new(
+                &self.btc_rpc_url,
+                &self.btc_rpc_user_name,
+                &self.btc_rpc_password,
+                self.local_block_store_dir.clone(),
+            ),
+        }
     }
 }
 
@@ -53,13 +64,31 @@ impl BitcoinClientActor {
             Auth::UserPass(btc_rpc_user_name.to_owned(), btc_rpc_password.to_owned()),
         )?;
         Ok(Self {
-            rpc_client,
+            rpc_client: Some(rpc_client),
+            local_blocks: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
             reorg_block_store_dir: local_block_store_dir,
         })
     }
 
+    /// Create a client which reads the blocks from the local block store, without bitcoind.
+    pub fn new_local(local_blocks: Arc<LocalBlockStore>) -> Self {
+        Self {
+            rpc_client: None,
+            local_blocks: Some(local_blocks),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+            reorg_block_store_dir: None,
+        }
+    }
+
+    fn rpc_client(&self) -> Result<&Client> {
+        self.rpc_client.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("The Bitcoin RPC is not available when reading blocks from local files")
+        })
+    }
+
     async fn retry<F, T>(&self, f: F) -> Result<T>
     where
         F: Fn() -> Result<T, bitcoincore_rpc::Error>,
@@ -99,8 +128,12 @@ impl Handler<GetBlockMessage> for BitcoinClientActor {
         _ctx: &mut ActorContext,
     ) -> Result<bitcoin::Block> {
         let GetBlockMessage { hash } = msg;
+        if let Some(local_blocks) = &self.local_blocks {
+            return local_blocks.block(&hash);
+        }
 
-        let rpc_ret = self.retry(|| self.rpc_client.get_block(&hash)).await;
+        let rpc_client = self.rpc_client()?;
+        let rpc_ret = self.retry(|| rpc_client.get_block(&hash)).await;
         if let Ok(block) = rpc_ret {
             return Ok(block);
         }
@@ -143,7 +176,11 @@ impl Handler<GetBestBlockHashMessage> for BitcoinClientActor {
         _ctx: &mut ActorContext,
     ) -> Result<bitcoin::BlockHash> {
         let GetBestBlockHashMessage {} = msg;
-        Ok(self.retry(|| self.rpc_client.get_best_block_hash()).await?)
+        if let Some(local_blocks) = &self.local_blocks {
+            return local_blocks.best_block_hash();
+        }
+        let rpc_client = self.rpc_client()?;
+        Ok(self.retry(|| rpc_client.get_best_block_hash()).await?)
     }
 }
 
@@ -155,9 +192,11 @@ impl Handler<GetBlockHashMessage> for BitcoinClientActor {
         _ctx: &mut ActorContext,
     ) -> Result<bitcoin::BlockHash> {
         let GetBlockHashMessage { height } = msg;
-        Ok(self
-            .retry(|| self.rpc_client.get_block_hash(height))
-            .await?)
+        if let Some(local_blocks) = &self.local_blocks {
+            return local_blocks.block_hash(height);
+        }
+        let rpc_client = self.rpc_client()?;
+        Ok(self.retry(|| rpc_client.get_block_hash(height)).await?)
     }
 }
 
@@ -169,8 +208,12 @@ impl Handler<GetBlockHeaderInfoMessage> for BitcoinClientActor {
         _ctx: &mut ActorContext,
     ) -> Result<json::GetBlockHeaderResult> {
         let GetBlockHeaderInfoMessage { hash } = msg;
+        if let Some(local_blocks) = &self.local_blocks {
+            return local_blocks.block_header_info(&hash);
+        }
+        let rpc_client = self.rpc_client()?;
         Ok(self
-            .retry(|| self.rpc_client.get_block_header_info(&hash))
+            .retry(|| rpc_client.get_block_header_info(&hash))
             .await?)
     }
 }
@@ -182,7 +225,8 @@ impl Handler<GetChainTipsMessage> for BitcoinClientActor {
         _msg: GetChainTipsMessage,
         _ctx: &mut ActorContext,
     ) -> Result<json::GetChainTipsResult> {
-        Ok(self.retry(|| self.rpc_client.get_chain_tips()).await?)
+        let rpc_client = self.rpc_client()?;
+        Ok(self.retry(|| rpc_client.get_chain_tips()).await?)
     }
 }
 
@@ -216,8 +260,9 @@ impl Handler<BroadcastTransactionMessage> for BitcoinClientActor {
         }
 
         // Make the RPC call
+        let rpc_client = self.rpc_client()?;
         let tx_id = self
-            .retry(|| self.rpc_client.call("sendrawtransaction", &params))
+            .retry(|| rpc_client.call("sendrawtransaction", &params))
             .await?;
         Ok(tx_id)
     }
@@ -235,8 +280,9 @@ impl Handler<GetTxOutMessage> for BitcoinClientActor {
             vout,
             include_mempool,
         } = msg;
+        let rpc_client = self.rpc_client()?;
         Ok(self
-            .retry(|| self.rpc_client.get_tx_out(&txid, vout, include_mempool))
+            .retry(|| rpc_client.get_tx_out(&txid, vout, include_mempool))
             .await?)
     }
 }
@@ -249,8 +295,27 @@ impl Handler<GetRawTransactionMessage> for BitcoinClientActor {
         _ctx: &mut ActorContext,
     ) -> Result<Transaction> {
         let GetRawTransactionMessage { txid } = msg;
+        let rpc_client = self.rpc_client()?;
+        Ok(self
+            .retry(|| rpc_client.get_raw_transaction(&txid, None))
+            .await?)
+    }
+}
+
+#[async_trait]
+impl Handler<EstimateSmartFeeMessage> for BitcoinClientActor {
+    async fn handle(
+        &mut self,
+        msg: EstimateSmartFeeMessage,
+        _ctx: &mut ActorContext,
+    ) -> Result<json::EstimateSmartFeeResult> {
+        let EstimateSmartFeeMessage {
+            conf_target,
+            estimate_mode,
+        } = msg;
+        let rpc_client = self.rpc_client()?;
         Ok(self
-            .retry(|| self.rpc_client.get_raw_transaction(&txid, None))
+            .retry(|| rpc_client.estimate_smart_fee(conf_target, estimate_mode))
             .await?)
     }
 }
@@ -89,3 +89,13 @@ pub struct GetRawTransactionMessage {
 impl Message for GetRawTransactionMessage {
     type Result = Result<Transaction>;
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EstimateSmartFeeMessage {
+    pub conf_target: u16,
+    pub estimate_mode: Option<json::EstimateMode>,
+}
+
+impl Message for EstimateSmartFeeMessage {
+    type Result = Result<json::EstimateSmartFeeResult>;
+}
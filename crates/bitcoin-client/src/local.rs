@@ -0,0 +1,655 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read Bitcoin blocks from local files instead of the bitcoind JSON-RPC.
+//!
+//! Two layouts are supported:
+//! * A Bitcoin Core data directory, or its `blocks` directory, with the `blk*.dat` files.
+//!   The files obfuscated by the `xor.dat` key are supported.
+//! * A directory of raw serialized blocks, one block per file, in binary or hex.
+//!
+//! The block index is rebuilt from the block headers when the store is opened,
+//! and the best chain is the chain with the most work, as bitcoind does.
+//!
+//! The height of the first block is read from its coinbase (BIP34) if the parent is not in the store,
+//! such as a pruned data directory. If the first block is before the BIP34 activation,
+//! the height is derived from the first descendant with a BIP34 height,
+//! so a pruned data directory has to keep at least one block after the activation,
+//! the height 227931 on mainnet and 21111 on testnet.
+
+use anyhow::{bail, ensure, format_err, Result};
+use bitcoin::block::Header;
+use bitcoin::consensus::encode;
+use bitcoin::consensus::Decodable;
+use bitcoin::hashes::Hash;
+use bitcoin::p2p::Magic;
+use bitcoin::{Block, BlockHash, Network, VarInt, Work};
+use bitcoincore_rpc::json;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const BLOCKS_DIR: &str = "blocks";
+const BLK_FILE_PREFIX: &str = "blk";
+const BLK_FILE_SUFFIX: &str = ".dat";
+const XOR_KEY_FILE: &str = "xor.dat";
+const XOR_KEY_SIZE: usize = 8;
+const BLK_RECORD_PREFIX_SIZE: u64 = 8;
+const HEADER_SIZE: usize = 80;
+/// The header and the compact size of the transaction count.
+const HEADER_WITH_TX_COUNT_SIZE: usize = HEADER_SIZE + 9;
+/// bitcoind calculates the median time over the last 11 blocks.
+const MEDIAN_TIME_SPAN: usize = 11;
+
+#[derive(Debug, Clone, Copy)]
+enum BlockLocation {
+    /// A block in a `blk*.dat` file, the offset points to the serialized block after the magic and the size.
+    BlkFile { file: usize, offset: u64, size: u32 },
+    /// A file which contains exactly one block.
+    RawFile { file: usize },
+}
+
+#[derive(Debug, Clone)]
+struct BlockIndexEntry {
+    header: Header,
+    tx_count: u64,
+    location: BlockLocation,
+    height: u64,
+    /// The accumulated work from the first block in the store.
+    chain_work: Work,
+}
+
+pub struct LocalBlockStore {
+    files: Vec<PathBuf>,
+    xor_key: Option<[u8; XOR_KEY_SIZE]>,
+    index: HashMap<BlockHash, BlockIndexEntry>,
+    /// The best chain, `best_chain[i]` is the block hash at height `start_height + i`.
+    best_chain: Vec<BlockHash>,
+    start_height: u64,
+}
+
+impl LocalBlockStore {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        ensure!(
+            dir.is_dir(),
+            "The blocks directory {:?} does not exist",
+            dir
+        );
+        let core_blocks_dir = dir.join(BLOCKS_DIR);
+        let dir = if list_blk_files(&core_blocks_dir)?.is_empty() {
+            dir.to_path_buf()
+        } else {
+            core_blocks_dir
+        };
+
+        let blk_files = list_blk_files(&dir)?;
+        let (mut store, order) = if blk_files.is_empty() {
+            let mut store = Self::new(list_raw_files(&dir)?, None);
+            let order = store.index_raw_files()?;
+            (store, order)
+        } else {
+            let mut store = Self::new(blk_files, read_xor_key(&dir)?);
+            let order = store.index_blk_files()?;
+            (store, order)
+        };
+        store.assign_heights(&order)?;
+        if let Some(tip) = store.best_tip(&order) {
+            store.build_best_chain(tip)?;
+        }
+        info!(
+            "Opened the local blocks in {:?}, blocks: {}, best chain: {:?}",
+            dir,
+            store.index.len(),
+            store
+                .tip_height()
+                .map(|height| (store.start_height, height)),
+        );
+        Ok(store)
+    }
+
+    fn new(files: Vec<PathBuf>, xor_key: Option<[u8; XOR_KEY_SIZE]>) -> Self {
+        Self {
+            files,
+            xor_key,
+            index: HashMap::new(),
+            best_chain: vec![],
+            start_height: 0,
+        }
+    }
+
+    /// The height of the best block, None if the store is empty.
+    pub fn tip_height(&self) -> Option<u64> {
+        (!self.best_chain.is_empty()).then(|| self.start_height + self.best_chain.len() as u64 - 1)
+    }
+
+    pub fn best_block_hash(&self) -> Result<BlockHash> {
+        self.best_chain
+            .last()
+            .copied()
+            .ok_or_else(|| format_err!("The local block store is empty"))
+    }
+
+    pub fn block_hash(&self, height: u64) -> Result<BlockHash> {
+        height
+            .checked_sub(self.start_height)
+            .and_then(|index| self.best_chain.get(index as usize))
+            .copied()
+            .ok_or_else(|| format_err!("Block at height {} not found in local blocks", height))
+    }
+
+    pub fn block_header_info(&self, hash: &BlockHash) -> Result<json::GetBlockHeaderResult> {
+        let entry = self.entry(hash)?;
+        let header = &entry.header;
+        let in_best_chain = self.is_in_best_chain(hash, entry.height);
+        let tip_height = self.tip_height().unwrap_or_default();
+        let confirmations = if in_best_chain {
+            (tip_height - entry.height + 1) as i64
+        } else {
+            -1
+        };
+        let next_block_hash = if in_best_chain && entry.height < tip_height {
+            Some(self.block_hash(entry.height + 1)?.to_string())
+        } else {
+            None
+        };
+        let previous_block_hash = (header.prev_blockhash != BlockHash::all_zeros())
+            .then(|| header.prev_blockhash.to_string());
+        let chain_work = entry
+            .chain_work
+            .to_be_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        // Build the header info in the bitcoind JSON-RPC format, so it is the same as the result of `getblockheader`
+        let value = serde_json::json!({
+            "hash": hash.to_string(),
+            "confirmations": confirmations,
+            "height": entry.height,
+            "version": header.version.to_consensus(),
+            "versionHex": format!("{:08x}", header.version.to_consensus() as u32),
+            "merkleroot": header.merkle_root.to_string(),
+            "time": header.time,
+            "mediantime": self.median_time(entry),
+            "nonce": header.nonce,
+            "bits": format!("{:08x}", header.bits.to_consensus()),
+            "difficulty": header.difficulty_float(),
+            "chainwork": chain_work,
+            "nTx": entry.tx_count,
+            "previousblockhash": previous_block_hash,
+            "nextblockhash": next_block_hash,
+        });
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn block(&self, hash: &BlockHash) -> Result<Block> {
+        let entry = self.entry(hash)?;
+        let block = match entry.location {
+            BlockLocation::BlkFile { file, offset, size } => {
+                let mut buf = vec![0u8; size as usize];
+                self.read_blk_file_at(file, offset, &mut buf)?;
+                encode::deserialize::<Block>(&buf)?
+            }
+            BlockLocation::RawFile { file } => decode_raw_block(&fs::read(&self.files[file])?)?,
+        };
+        ensure!(
+            block.block_hash() == *hash,
+            "The local block file of {} is modified after the store is opened",
+            hash
+        );
+        Ok(block)
+    }
+
+    fn entry(&self, hash: &BlockHash) -> Result<&BlockIndexEntry> {
+        self.index
+            .get(hash)
+            .ok_or_else(|| format_err!("Block {} not found in local blocks", hash))
+    }
+
+    fn is_in_best_chain(&self, hash: &BlockHash, height: u64) -> bool {
+        matches!(self.block_hash(height), Ok(best_hash) if best_hash == *hash)
+    }
+
+    fn median_time(&self, entry: &BlockIndexEntry) -> u32 {
+        let mut times = Vec::with_capacity(MEDIAN_TIME_SPAN);
+        let mut current = Some(entry);
+        while let Some(entry) = current {
+            if times.len() == MEDIAN_TIME_SPAN {
+                break;
+            }
+            times.push(entry.header.time);
+            current = self.index.get(&entry.header.prev_blockhash);
+        }
+        times.sort_unstable();
+        times[times.len() / 2]
+    }
+
+    /// Index the blocks in the blk files, return the block hashes in the order of the files.
+    fn index_blk_files(&mut self) -> Result<Vec<BlockHash>> {
+        let mut order = vec![];
+        for file_index in 0..self.files.len() {
+            let path = self.files[file_index].clone();
+            let file_len = fs::metadata(&path)?.len();
+            let mut offset = 0u64;
+            while offset + BLK_RECORD_PREFIX_SIZE <= file_len {
+                let mut prefix = [0u8; BLK_RECORD_PREFIX_SIZE as usize];
+                self.read_blk_file_at(file_index, offset, &mut prefix)?;
+                let magic = [prefix[0], prefix[1], prefix[2], prefix[3]];
+                // bitcoind pre-allocates the blk files with zeros
+                if magic == [0u8; 4] {
+                    break;
+                }
+                ensure!(
+                    Network::from_magic(Magic::from_bytes(magic)).is_some(),
+                    "Unknown network magic {:?} in {:?} at {}",
+                    magic,
+                    path,
+                    offset
+                );
+                let size = u32::from_le_bytes([prefix[4], prefix[5], prefix[6], prefix[7]]);
+                let block_offset = offset + BLK_RECORD_PREFIX_SIZE;
+                if block_offset + size as u64 > file_len {
+                    warn!(
+                        "The block in {:?} at {} is truncated, skip the rest of the file",
+                        path, offset
+                    );
+                    break;
+                }
+
+                let mut buf = vec![0u8; HEADER_WITH_TX_COUNT_SIZE.min(size as usize)];
+                self.read_blk_file_at(file_index, block_offset, &mut buf)?;
+                let mut reader = buf.as_slice();
+                let header = Header::consensus_decode(&mut reader)?;
+                let tx_count = VarInt::consensus_decode(&mut reader)?.0;
+                let location = BlockLocation::BlkFile {
+                    file: file_index,
+                    offset: block_offset,
+                    size,
+                };
+                if let Some(hash) = self.insert_entry(header, tx_count, location) {
+                    order.push(hash);
+                }
+                offset = block_offset + size as u64;
+            }
+        }
+        Ok(order)
+    }
+
+    fn index_raw_files(&mut self) -> Result<Vec<BlockHash>> {
+        let mut order = vec![];
+        for file_index in 0..self.files.len() {
+            let path = &self.files[file_index];
+            let block = decode_raw_block(&fs::read(path)?)
+                .map_err(|e| format_err!("Invalid block file {:?}: {}", path, e))?;
+            let location = BlockLocation::RawFile { file: file_index };
+            if let Some(hash) = self.insert_entry(block.header, block.txdata.len() as u64, location)
+            {
+                order.push(hash);
+            }
+        }
+        Ok(order)
+    }
+
+    /// Insert a block into the index, the height and the chain work are assigned later.
+    /// Return None if the block is duplicated.
+    fn insert_entry(
+        &mut self,
+        header: Header,
+        tx_count: u64,
+        location: BlockLocation,
+    ) -> Option<BlockHash> {
+        let hash = header.block_hash();
+        if self.index.contains_key(&hash) {
+            return None;
+        }
+        self.index.insert(
+            hash,
+            BlockIndexEntry {
+                header,
+                tx_count,
+                location,
+                height: 0,
+                chain_work: header.work(),
+            },
+        );
+        Some(hash)
+    }
+
+    /// Assign the height and the chain work of the blocks, from the blocks whose parent is not in the store.
+    /// The height of such a block is 0 for the genesis block, otherwise it is read from the coinbase (BIP34).
+    fn assign_heights(&mut self, order: &[BlockHash]) -> Result<()> {
+        let mut children: HashMap<BlockHash, Vec<BlockHash>> = HashMap::new();
+        let mut roots = vec![];
+        for hash in order {
+            let prev_blockhash = self.index[hash].header.prev_blockhash;
+            if self.index.contains_key(&prev_blockhash) {
+                children.entry(prev_blockhash).or_default().push(*hash);
+            } else {
+                roots.push(*hash);
+            }
+        }
+
+        let mut stack = vec![];
+        for root in roots {
+            let height = if self.index[&root].header.prev_blockhash == BlockHash::all_zeros() {
+                0
+            } else {
+                match self.block(&root)?.bip34_block_height() {
+                    Ok(height) => height,
+                    Err(e) => self.bip34_height_from_descendant(root, &children)?.ok_or_else(|| {
+                        format_err!(
+                            "Can not determine the height of block {}, its parent is not in local blocks \
+                             and no descendant has a BIP34 height: {}",
+                            root,
+                            e
+                        )
+                    })?,
+                }
+            };
+            if let Some(entry) = self.index.get_mut(&root) {
+                entry.height = height;
+            }
+            stack.push(root);
+        }
+
+        while let Some(parent) = stack.pop() {
+            let (parent_height, parent_work) = {
+                let entry = &self.index[&parent];
+                (entry.height, entry.chain_work)
+            };
+            for child in children.remove(&parent).unwrap_or_default() {
+                if let Some(entry) = self.index.get_mut(&child) {
+                    entry.height = parent_height + 1;
+                    entry.chain_work = parent_work + entry.header.work();
+                }
+                stack.push(child);
+            }
+        }
+        Ok(())
+    }
+
+    /// Derive the height of a block before the BIP34 activation from its first descendant with a BIP34 height.
+    fn bip34_height_from_descendant(
+        &self,
+        root: BlockHash,
+        children: &HashMap<BlockHash, Vec<BlockHash>>,
+    ) -> Result<Option<u64>> {
+        let mut depth = 0u64;
+        let mut current = root;
+        while let Some(child) = children.get(&current).and_then(|children| children.first()) {
+            depth += 1;
+            current = *child;
+            // The blocks before version 2 do not have the height in the coinbase
+            if self.index[&current].header.version.to_consensus() < 2 {
+                continue;
+            }
+            if let Ok(height) = self.block(&current)?.bip34_block_height() {
+                if height >= depth {
+                    return Ok(Some(height - depth));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The tip with the most work, the first seen block wins if the work is equal.
+    fn best_tip(&self, order: &[BlockHash]) -> Option<BlockHash> {
+        let mut tip: Option<(BlockHash, Work, u64)> = None;
+        for hash in order {
+            let entry = &self.index[hash];
+            let better = match tip {
+                Some((_, work, height)) => {
+                    entry.chain_work > work || (entry.chain_work == work && entry.height > height)
+                }
+                None => true,
+            };
+            if better {
+                tip = Some((*hash, entry.chain_work, entry.height));
+            }
+        }
+        tip.map(|(hash, _, _)| hash)
+    }
+
+    fn build_best_chain(&mut self, tip: BlockHash) -> Result<()> {
+        let mut chain = vec![tip];
+        let mut current = &self.index[&tip];
+        while let Some(parent) = self.index.get(&current.header.prev_blockhash) {
+            chain.push(current.header.prev_blockhash);
+            current = parent;
+        }
+        chain.reverse();
+        self.start_height = current.height;
+        ensure!(
+            self.start_height + chain.len() as u64 - 1 == self.index[&tip].height,
+            "The heights of local blocks are inconsistent"
+        );
+        self.best_chain = chain;
+        Ok(())
+    }
+
+    fn read_blk_file_at(&self, file: usize, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let mut f = File::open(&self.files[file])?;
+        f.seek(SeekFrom::Start(offset))?;
+        f.read_exact(buf)?;
+        if let Some(key) = &self.xor_key {
+            for (i, b) in buf.iter_mut().enumerate() {
+                *b ^= key[((offset + i as u64) % XOR_KEY_SIZE as u64) as usize];
+            }
+        }
+        Ok(())
+    }
+}
+
+fn list_blk_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_blk_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with(BLK_FILE_PREFIX) && name.ends_with(BLK_FILE_SUFFIX))
+            .unwrap_or(false);
+        if is_blk_file && path.is_file() {
+            files.push(path);
+        }
+    }
+    // The blk files are named by the sequence number with leading zeros, such as blk00000.dat
+    files.sort();
+    Ok(files)
+}
+
+fn list_raw_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(true);
+        if !is_hidden && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read the obfuscation key of the blk files, None if the blocks are not obfuscated.
+fn read_xor_key(dir: &Path) -> Result<Option<[u8; XOR_KEY_SIZE]>> {
+    let path = dir.join(XOR_KEY_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&path)?;
+    let key: [u8; XOR_KEY_SIZE] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| format_err!("Invalid xor key file {:?}", path))?;
+    Ok((key != [0u8; XOR_KEY_SIZE]).then_some(key))
+}
+
+/// Decode a block from a file, the file contains the serialized block in binary or hex.
+fn decode_raw_block(bytes: &[u8]) -> Result<Block> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        let text = text.trim();
+        if !text.is_empty() && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Ok(encode::deserialize_hex::<Block>(text)?);
+        }
+    }
+    if bytes.len() < HEADER_SIZE {
+        bail!("The block is too short, size: {}", bytes.len());
+    }
+    Ok(encode::deserialize::<Block>(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::blockdata::constants::genesis_block;
+    use std::io::Write;
+
+    fn child_block(parent: &Block, nonce: u32) -> Block {
+        let mut block = parent.clone();
+        block.header.prev_blockhash = parent.block_hash();
+        block.header.nonce = nonce;
+        block
+    }
+
+    fn write_blk_file(path: &Path, blocks: &[&Block], xor_key: Option<[u8; XOR_KEY_SIZE]>) {
+        let mut data = vec![];
+        for block in blocks {
+            let bytes = encode::serialize(*block);
+            data.extend_from_slice(&Network::Regtest.magic().to_bytes());
+            data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+        // pre-allocated space
+        data.extend_from_slice(&[0u8; 16]);
+        if let Some(key) = xor_key {
+            for (i, b) in data.iter_mut().enumerate() {
+                *b ^= key[i % XOR_KEY_SIZE];
+            }
+        }
+        File::create(path).unwrap().write_all(&data).unwrap();
+    }
+
+    #[test]
+    fn test_blk_files_with_fork() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocks_dir = dir.path().join(BLOCKS_DIR);
+        fs::create_dir_all(&blocks_dir).unwrap();
+        let xor_key = [1, 2, 3, 4, 5, 6, 7, 8];
+        fs::write(blocks_dir.join(XOR_KEY_FILE), xor_key).unwrap();
+
+        let genesis = genesis_block(Network::Regtest);
+        let block1 = child_block(&genesis, 1);
+        let fork1 = child_block(&genesis, 2);
+        let block2 = child_block(&block1, 3);
+        // The blocks are out of order in the blk files
+        write_blk_file(
+            &blocks_dir.join("blk00000.dat"),
+            &[&genesis, &fork1, &block2],
+            Some(xor_key),
+        );
+        write_blk_file(&blocks_dir.join("blk00001.dat"), &[&block1], Some(xor_key));
+
+        // Open the data directory, the blocks directory is detected
+        let store = LocalBlockStore::open(dir.path()).unwrap();
+        assert_eq!(store.tip_height(), Some(2));
+        assert_eq!(store.best_block_hash().unwrap(), block2.block_hash());
+        assert_eq!(store.block_hash(0).unwrap(), genesis.block_hash());
+        assert_eq!(store.block_hash(1).unwrap(), block1.block_hash());
+        assert!(store.block_hash(3).is_err());
+
+        let info = store.block_header_info(&block1.block_hash()).unwrap();
+        assert_eq!(info.height, 1);
+        assert_eq!(info.confirmations, 2);
+        assert_eq!(info.previous_block_hash, Some(genesis.block_hash()));
+        assert_eq!(info.next_block_hash, Some(block2.block_hash()));
+        assert_eq!(info.n_tx, 1);
+
+        let fork_info = store.block_header_info(&fork1.block_hash()).unwrap();
+        assert_eq!(fork_info.height, 1);
+        assert_eq!(fork_info.confirmations, -1);
+        assert_eq!(fork_info.next_block_hash, None);
+
+        let genesis_info = store.block_header_info(&genesis.block_hash()).unwrap();
+        assert_eq!(genesis_info.previous_block_hash, None);
+
+        assert_eq!(store.block(&block2.block_hash()).unwrap(), block2);
+        assert_eq!(store.block(&fork1.block_hash()).unwrap(), fork1);
+    }
+
+    #[test]
+    fn test_raw_block_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis = genesis_block(Network::Regtest);
+        let block1 = child_block(&genesis, 1);
+        let block2 = child_block(&block1, 2);
+        fs::write(
+            dir.path().join(genesis.block_hash().to_string()),
+            encode::serialize_hex(&genesis),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(block1.block_hash().to_string()),
+            encode::serialize_hex(&block1),
+        )
+        .unwrap();
+        fs::write(dir.path().join("block2.bin"), encode::serialize(&block2)).unwrap();
+
+        let store = LocalBlockStore::open(dir.path()).unwrap();
+        assert_eq!(store.tip_height(), Some(2));
+        assert_eq!(store.best_block_hash().unwrap(), block2.block_hash());
+        assert_eq!(store.block(&block1.block_hash()).unwrap(), block1);
+        let info = store.block_header_info(&block2.block_hash()).unwrap();
+        assert_eq!(info.confirmations, 1);
+        assert_eq!(info.next_block_hash, None);
+    }
+
+    #[test]
+    fn test_unknown_parent_without_bip34_height() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis = genesis_block(Network::Regtest);
+        let block1 = child_block(&genesis, 1);
+        fs::write(dir.path().join("block1.bin"), encode::serialize(&block1)).unwrap();
+        assert!(LocalBlockStore::open(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_pruned_before_bip34() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis = genesis_block(Network::Regtest);
+        // The first block in the store is before the BIP34 activation
+        let block100 = child_block(&genesis, 1);
+        let block101 = child_block(&block100, 2);
+        let mut block102 = child_block(&block101, 3);
+        block102.header.version = bitcoin::block::Version::TWO;
+        block102.txdata[0].input[0].script_sig =
+            bitcoin::script::Builder::new().push_int(102).into_script();
+        let block103 = child_block(&block102, 4);
+        for (i, block) in [&block100, &block101, &block102, &block103]
+            .into_iter()
+            .enumerate()
+        {
+            fs::write(
+                dir.path().join(format!("block{}.bin", i)),
+                encode::serialize(block),
+            )
+            .unwrap();
+        }
+
+        let store = LocalBlockStore::open(dir.path()).unwrap();
+        assert_eq!(store.tip_height(), Some(103));
+        assert_eq!(store.block_hash(100).unwrap(), block100.block_hash());
+        assert_eq!(store.block_hash(102).unwrap(), block102.block_hash());
+        assert!(store.block_hash(99).is_err());
+    }
+}
@@ -3,9 +3,9 @@
 
 use crate::actor::client::BitcoinClientActor;
 use crate::actor::messages::{
-    BroadcastTransactionMessage, GetBestBlockHashMessage, GetBlockHashMessage,
-    GetBlockHeaderInfoMessage, GetBlockMessage, GetChainTipsMessage, GetRawTransactionMessage,
-    GetTxOutMessage,
+    BroadcastTransactionMessage, EstimateSmartFeeMessage, GetBestBlockHashMessage,
+    GetBlockHashMessage, GetBlockHeaderInfoMessage, GetBlockMessage, GetChainTipsMessage,
+    GetRawTransactionMessage, GetTxOutMessage,
 };
 use anyhow::Result;
 use bitcoin::Transaction;
@@ -69,4 +69,18 @@ impl BitcoinClientProxy {
     pub async fn get_raw_transaction(&self, txid: Txid) -> Result<Transaction> {
         self.actor.send(GetRawTransactionMessage { txid }).await?
     }
+
+    /// Estimate the fee rate for a transaction to be confirmed within `conf_target` blocks
+    pub async fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        estimate_mode: Option<json::EstimateMode>,
+    ) -> Result<json::EstimateSmartFeeResult> {
+        self.actor
+            .send(EstimateSmartFeeMessage {
+                conf_target,
+                estimate_mode,
+            })
+            .await?
+    }
 }
@@ -0,0 +1,460 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::block::{Header, Version};
+use bitcoin::blockdata::constants::genesis_block;
+use bitcoin::hashes::Hash;
+use bitcoin::opcodes::OP_TRUE;
+use bitcoin::script::Builder;
+use bitcoin::transaction;
+use bitcoin::{
+    Amount, Block, BlockHash, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+    TxMerkleNode, TxOut, Txid, Witness,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The regtest block interval, the time of each mined block is increased by it.
+const BLOCK_INTERVAL: u32 = 600;
+const INITIAL_SUBSIDY: u64 = 50 * 100_000_000;
+const REGTEST_SUBSIDY_HALVING_INTERVAL: u64 = 150;
+
+#[derive(Debug, Clone)]
+struct MockBlock {
+    block: Block,
+    height: u64,
+}
+
+/// The output of a transaction, with the confirmation info as the `gettxout` result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockTxOut {
+    pub txout: TxOut,
+    /// 0 if the output is in the mempool
+    pub confirmations: u64,
+    pub coinbase: bool,
+}
+
+/// A scripted regtest chain served by the mock bitcoind RPC.
+///
+/// All blocks, including the blocks disconnected by a reorg, are kept and can be queried by hash,
+/// as bitcoind does. The mined blocks satisfy the regtest proof of work,
+/// so they can be executed by the Bitcoin framework as the real blocks.
+#[derive(Debug, Clone)]
+pub struct MockChain {
+    blocks: HashMap<BlockHash, MockBlock>,
+    /// The hashes of the active chain, indexed by height.
+    active: Vec<BlockHash>,
+    mempool: Vec<Transaction>,
+    coinbase_script: ScriptBuf,
+    /// Make every mined block unique, so a reorg does not mine the same block again.
+    extra_nonce: u64,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChain {
+    /// Create a regtest chain with only the genesis block.
+    pub fn new() -> Self {
+        let genesis = genesis_block(Network::Regtest);
+        let genesis_hash = genesis.block_hash();
+        let mut blocks = HashMap::new();
+        blocks.insert(
+            genesis_hash,
+            MockBlock {
+                block: genesis,
+                height: 0,
+            },
+        );
+        Self {
+            blocks,
+            active: vec![genesis_hash],
+            mempool: vec![],
+            coinbase_script: Builder::new().push_opcode(OP_TRUE).into_script(),
+            extra_nonce: 0,
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        Network::Regtest
+    }
+
+    /// Set the script of the coinbase output of the blocks mined later, the default is `OP_TRUE`.
+    pub fn set_coinbase_script(&mut self, script: ScriptBuf) {
+        self.coinbase_script = script;
+    }
+
+    pub fn tip_height(&self) -> u64 {
+        self.active.len() as u64 - 1
+    }
+
+    pub fn best_block_hash(&self) -> BlockHash {
+        *self.active.last().expect("The genesis block always exists")
+    }
+
+    /// The block hash at `height` of the active chain.
+    pub fn block_hash(&self, height: u64) -> Option<BlockHash> {
+        self.active.get(height as usize).copied()
+    }
+
+    pub fn block(&self, hash: &BlockHash) -> Option<&Block> {
+        self.blocks.get(hash).map(|block| &block.block)
+    }
+
+    pub fn block_height(&self, hash: &BlockHash) -> Option<u64> {
+        self.blocks.get(hash).map(|block| block.height)
+    }
+
+    /// Whether the block is in the active chain.
+    pub fn is_active(&self, hash: &BlockHash) -> bool {
+        self.block_height(hash)
+            .and_then(|height| self.block_hash(height))
+            .map(|active_hash| active_hash == *hash)
+            .unwrap_or(false)
+    }
+
+    /// The confirmations of the block, -1 if the block is not in the active chain.
+    pub fn confirmations(&self, hash: &BlockHash) -> Option<i64> {
+        let height = self.block_height(hash)?;
+        Some(if self.is_active(hash) {
+            (self.tip_height() - height + 1) as i64
+        } else {
+            -1
+        })
+    }
+
+    pub fn mempool(&self) -> &[Transaction] {
+        &self.mempool
+    }
+
+    /// Add the transaction to the mempool, it is included in the next mined block.
+    pub fn send_transaction(&mut self, tx: Transaction) -> Txid {
+        let txid = tx.compute_txid();
+        if !self.mempool.iter().any(|t| t.compute_txid() == txid) {
+            self.mempool.push(tx);
+        }
+        txid
+    }
+
+    /// Find the transaction in the active chain or in the mempool.
+    pub fn transaction(&self, txid: &Txid) -> Option<&Transaction> {
+        self.active
+            .iter()
+            .flat_map(|hash| self.blocks[hash].block.txdata.iter())
+            .chain(self.mempool.iter())
+            .find(|tx| tx.compute_txid() == *txid)
+    }
+
+    /// Mine a block on the tip with the transactions in the mempool.
+    pub fn mine_block(&mut self) -> BlockHash {
+        let txs = std::mem::take(&mut self.mempool);
+        self.mine_block_with_txs(txs)
+    }
+
+    pub fn mine_blocks(&mut self, count: usize) -> Vec<BlockHash> {
+        (0..count).map(|_| self.mine_block()).collect()
+    }
+
+    /// Mine a block on the tip with the given transactions, the mempool is not changed.
+    pub fn mine_block_with_txs(&mut self, txs: Vec<Transaction>) -> BlockHash {
+        let parent = self.best_block_hash();
+        let block = self.new_block(&parent, txs);
+        self.connect_block(block)
+    }
+
+    /// Disconnect the `depth` blocks from the tip, and mine `new_blocks` blocks on the fork point.
+    /// The transactions of the disconnected blocks are returned to the mempool, as bitcoind does,
+    /// so they are included in the new blocks. Return the hashes of the new blocks.
+    pub fn reorg(&mut self, depth: usize, new_blocks: usize) -> Result<Vec<BlockHash>> {
+        self.disconnect_blocks(depth)?;
+        Ok(self.mine_blocks(new_blocks))
+    }
+
+    /// Disconnect the `depth` blocks from the tip, the blocks are still queryable by hash.
+    pub fn disconnect_blocks(&mut self, depth: usize) -> Result<Vec<BlockHash>> {
+        ensure!(
+            depth < self.active.len(),
+            "Can not disconnect {} blocks, the tip height is {}",
+            depth,
+            self.tip_height()
+        );
+        let disconnected = self.active.split_off(self.active.len() - depth);
+        let mut returned_txs = vec![];
+        for hash in &disconnected {
+            let block = &self.blocks[hash].block;
+            returned_txs.extend(block.txdata.iter().skip(1).cloned());
+        }
+        returned_txs.append(&mut self.mempool);
+        self.mempool = returned_txs;
+        Ok(disconnected)
+    }
+
+    /// Make the block and its ancestors the active chain, such as `reconsiderblock` after a reorg.
+    pub fn activate_block(&mut self, hash: &BlockHash) -> Result<()> {
+        let mut chain = vec![];
+        let mut current = *hash;
+        while !self.is_active(&current) {
+            let block = self
+                .blocks
+                .get(&current)
+                .ok_or_else(|| format_err!("Block {} not found", current))?;
+            chain.push(current);
+            current = block.block.header.prev_blockhash;
+        }
+        let fork_height = self.blocks[&current].height;
+        self.disconnect_blocks((self.tip_height() - fork_height) as usize)?;
+        chain.reverse();
+        let included: HashSet<Txid> = chain
+            .iter()
+            .flat_map(|hash| self.blocks[hash].block.txdata.iter())
+            .map(|tx| tx.compute_txid())
+            .collect();
+        self.mempool
+            .retain(|tx| !included.contains(&tx.compute_txid()));
+        self.active.extend(chain);
+        Ok(())
+    }
+
+    /// The unspent output, `include_mempool` as the `gettxout`.
+    pub fn tx_out(&self, outpoint: &OutPoint, include_mempool: bool) -> Option<MockTxOut> {
+        let tip_height = self.tip_height();
+        let mut found = None;
+        for (height, hash) in self.active.iter().enumerate() {
+            for (index, tx) in self.blocks[hash].block.txdata.iter().enumerate() {
+                if tx
+                    .input
+                    .iter()
+                    .any(|input| input.previous_output == *outpoint)
+                {
+                    return None;
+                }
+                if tx.compute_txid() == outpoint.txid {
+                    found = tx
+                        .output
+                        .get(outpoint.vout as usize)
+                        .map(|txout| MockTxOut {
+                            txout: txout.clone(),
+                            confirmations: tip_height - height as u64 + 1,
+                            coinbase: index == 0,
+                        });
+                }
+            }
+        }
+        if !include_mempool {
+            return found;
+        }
+        for tx in &self.mempool {
+            if tx
+                .input
+                .iter()
+                .any(|input| input.previous_output == *outpoint)
+            {
+                return None;
+            }
+            if found.is_none() && tx.compute_txid() == outpoint.txid {
+                found = tx
+                    .output
+                    .get(outpoint.vout as usize)
+                    .map(|txout| MockTxOut {
+                        txout: txout.clone(),
+                        confirmations: 0,
+                        coinbase: false,
+                    });
+            }
+        }
+        found
+    }
+
+    /// The chain tips as `getchaintips`: the active tip and the tips of the stale branches,
+    /// each tip is `(height, hash, branch length, status)`.
+    pub fn chain_tips(&self) -> Vec<(u64, BlockHash, u64, &'static str)> {
+        let parents: HashSet<BlockHash> = self
+            .blocks
+            .values()
+            .map(|block| block.block.header.prev_blockhash)
+            .collect();
+        let mut tips = vec![(self.tip_height(), self.best_block_hash(), 0, "active")];
+        let mut stale_tips = self
+            .blocks
+            .iter()
+            .filter(|(hash, _)| !parents.contains(*hash) && !self.is_active(hash))
+            .map(|(hash, block)| {
+                let mut fork_point = block;
+                while !self.is_active(&fork_point.block.block_hash()) {
+                    fork_point = &self.blocks[&fork_point.block.header.prev_blockhash];
+                }
+                (
+                    block.height,
+                    *hash,
+                    block.height - fork_point.height,
+                    "valid-fork",
+                )
+            })
+            .collect::<Vec<_>>();
+        stale_tips.sort_by_key(|(height, hash, _, _)| (std::cmp::Reverse(*height), *hash));
+        tips.extend(stale_tips);
+        tips
+    }
+
+    /// The median time of the last 11 blocks to the block, as bitcoind.
+    pub fn median_time(&self, hash: &BlockHash) -> Option<u32> {
+        let mut times = vec![];
+        let mut current = self.blocks.get(hash);
+        while let Some(block) = current {
+            if times.len() == 11 {
+                break;
+            }
+            times.push(block.block.header.time);
+            current = self.blocks.get(&block.block.header.prev_blockhash);
+        }
+        times.sort_unstable();
+        times.get(times.len() / 2).copied()
+    }
+
+    fn new_block(&mut self, parent: &BlockHash, txs: Vec<Transaction>) -> Block {
+        let parent = &self.blocks[parent];
+        let height = parent.height + 1;
+        self.extra_nonce += 1;
+
+        let subsidy = INITIAL_SUBSIDY
+            .checked_shr((height / REGTEST_SUBSIDY_HALVING_INTERVAL) as u32)
+            .unwrap_or_default();
+        let coinbase = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                // BIP34 height and the extra nonce
+                script_sig: Builder::new()
+                    .push_int(height as i64)
+                    .push_int(self.extra_nonce as i64)
+                    .into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(subsidy),
+                script_pubkey: self.coinbase_script.clone(),
+            }],
+        };
+        let mut txdata = vec![coinbase];
+        txdata.extend(txs);
+
+        let mut block = Block {
+            header: Header {
+                version: Version::from_consensus(0x2000_0000),
+                prev_blockhash: parent.block.block_hash(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: parent.block.header.time + BLOCK_INTERVAL,
+                bits: parent.block.header.bits,
+                nonce: 0,
+            },
+            txdata,
+        };
+        block.header.merkle_root = block
+            .compute_merkle_root()
+            .expect("The block has the coinbase transaction");
+        // The regtest target is easy, it takes a few tries to satisfy it.
+        while block.header.validate_pow(block.header.target()).is_err() {
+            block.header.nonce += 1;
+        }
+        block
+    }
+
+    fn connect_block(&mut self, block: Block) -> BlockHash {
+        let hash = block.block_hash();
+        let height = self.blocks[&block.header.prev_blockhash].height + 1;
+        self.blocks.insert(hash, MockBlock { block, height });
+        self.active.push(hash);
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mine_and_reorg() {
+        let mut chain = MockChain::new();
+        let genesis_hash = chain.best_block_hash();
+        assert_eq!(genesis_hash, genesis_block(Network::Regtest).block_hash());
+
+        let old_blocks = chain.mine_blocks(3);
+        assert_eq!(chain.tip_height(), 3);
+        let block = chain.block(&old_blocks[2]).unwrap();
+        assert_eq!(block.bip34_block_height().unwrap(), 3);
+        assert!(block.header.validate_pow(block.header.target()).is_ok());
+        assert!(block.check_merkle_root());
+
+        let new_blocks = chain.reorg(2, 3).unwrap();
+        assert_eq!(chain.tip_height(), 4);
+        assert_eq!(chain.block_hash(1), Some(old_blocks[0]));
+        assert_eq!(chain.block_hash(2), Some(new_blocks[0]));
+        assert_eq!(chain.best_block_hash(), new_blocks[2]);
+        assert_ne!(old_blocks[1], new_blocks[0]);
+        assert_eq!(chain.confirmations(&old_blocks[2]), Some(-1));
+        assert_eq!(chain.confirmations(&new_blocks[0]), Some(3));
+        assert!(chain.block(&old_blocks[2]).is_some());
+
+        let tips = chain.chain_tips();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0], (4, new_blocks[2], 0, "active"));
+        assert_eq!(tips[1], (3, old_blocks[2], 2, "valid-fork"));
+
+        // Switch back to the old branch
+        chain.activate_block(&old_blocks[2]).unwrap();
+        assert_eq!(chain.best_block_hash(), old_blocks[2]);
+        assert_eq!(chain.confirmations(&new_blocks[0]), Some(-1));
+    }
+
+    #[test]
+    fn test_tx_out_and_mempool() {
+        let mut chain = MockChain::new();
+        let block_hash = chain.mine_block();
+        let coinbase = chain.block(&block_hash).unwrap().txdata[0].clone();
+        let coinbase_outpoint = OutPoint::new(coinbase.compute_txid(), 0);
+        let utxo = chain.tx_out(&coinbase_outpoint, true).unwrap();
+        assert!(utxo.coinbase);
+        assert_eq!(utxo.confirmations, 1);
+
+        let spend_tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: coinbase_outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        let txid = chain.send_transaction(spend_tx);
+        assert!(chain.tx_out(&coinbase_outpoint, false).is_some());
+        assert!(chain.tx_out(&coinbase_outpoint, true).is_none());
+        assert_eq!(
+            chain
+                .tx_out(&OutPoint::new(txid, 0), true)
+                .unwrap()
+                .confirmations,
+            0
+        );
+
+        let block_hash = chain.mine_block();
+        assert!(chain.mempool().is_empty());
+        assert_eq!(chain.block(&block_hash).unwrap().txdata.len(), 2);
+        assert!(chain.tx_out(&coinbase_outpoint, false).is_none());
+
+        // The spending tx returns to the mempool after the reorg
+        chain.disconnect_blocks(1).unwrap();
+        assert_eq!(chain.mempool().len(), 1);
+        assert!(chain.transaction(&txid).is_some());
+    }
+}
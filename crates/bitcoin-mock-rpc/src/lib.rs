@@ -0,0 +1,13 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-process mock of the bitcoind JSON-RPC, serving a scripted regtest chain.
+//!
+//! It is used to test the Bitcoin relayer and the reorg handling without a real bitcoind:
+//! mine blocks and make reorgs with [`MockChain`], and point the Bitcoin client to [`MockBitcoinRpcServer::url`].
+
+pub mod chain;
+pub mod server;
+
+pub use chain::{MockChain, MockTxOut};
+pub use server::MockBitcoinRpcServer;
@@ -0,0 +1,393 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chain::MockChain;
+use anyhow::Result;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use bitcoin::consensus::encode::{deserialize_hex, serialize_hex};
+use bitcoin::{Address, BlockHash, OutPoint, Transaction, Txid, Work};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+// The error codes of bitcoind
+const RPC_METHOD_NOT_FOUND: i64 = -32601;
+const RPC_INVALID_PARAMS: i64 = -32602;
+const RPC_PARSE_ERROR: i64 = -32700;
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+const RPC_INVALID_PARAMETER: i64 = -8;
+const RPC_DESERIALIZATION_ERROR: i64 = -22;
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+/// An in-process mock of the bitcoind JSON-RPC, which serves a [`MockChain`].
+///
+/// The chain can be scripted by [`MockBitcoinRpcServer::chain`] while the server is running,
+/// such as mining blocks or making a reorg. The server is stopped when it is dropped.
+pub struct MockBitcoinRpcServer {
+    addr: SocketAddr,
+    chain: Arc<Mutex<MockChain>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl MockBitcoinRpcServer {
+    /// Start the server on a random local port.
+    pub async fn start(chain: MockChain) -> Result<Self> {
+        let chain = Arc::new(Mutex::new(chain));
+        let app = Router::new()
+            .route("/", post(rpc_handler))
+            .route("/wallet/:wallet", post(rpc_handler))
+            .with_state(chain.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+        Ok(Self {
+            addr,
+            chain,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// The RPC url, the server accepts any user name and password.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn chain(&self) -> MutexGuard<'_, MockChain> {
+        self.chain
+            .lock()
+            .expect("The mock chain lock should not be poisoned")
+    }
+}
+
+impl Drop for MockBitcoinRpcServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+    }
+}
+
+async fn rpc_handler(State(chain): State<Arc<Mutex<MockChain>>>, body: Bytes) -> Json<Value> {
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return Json(response(Value::Null, Err((RPC_PARSE_ERROR, e.to_string())))),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let params = match request.get("params") {
+        Some(Value::Array(params)) => params.clone(),
+        _ => vec![],
+    };
+    debug!("Mock bitcoind RPC request: {} {:?}", method, params);
+    let mut chain = chain
+        .lock()
+        .expect("The mock chain lock should not be poisoned");
+    Json(response(id, handle_request(&mut chain, method, &params)))
+}
+
+fn response(id: Value, result: RpcResult) -> Value {
+    match result {
+        Ok(result) => json!({"result": result, "error": null, "id": id}),
+        Err((code, message)) => json!({
+            "result": null,
+            "error": {"code": code, "message": message},
+            "id": id,
+        }),
+    }
+}
+
+fn handle_request(chain: &mut MockChain, method: &str, params: &[Value]) -> RpcResult {
+    match method {
+        "getbestblockhash" => Ok(json!(chain.best_block_hash().to_string())),
+        "getblockcount" => Ok(json!(chain.tip_height())),
+        "getblockhash" => {
+            let height = param_u64(params, 0)?;
+            chain
+                .block_hash(height)
+                .map(|hash| json!(hash.to_string()))
+                .ok_or_else(|| {
+                    (
+                        RPC_INVALID_PARAMETER,
+                        "Block height out of range".to_owned(),
+                    )
+                })
+        }
+        "getblock" => {
+            let hash = param_block_hash(params, 0)?;
+            let verbosity = match params.get(1) {
+                Some(Value::Bool(verbose)) => *verbose as u64,
+                Some(value) => value.as_u64().unwrap_or(1),
+                None => 1,
+            };
+            let block = chain.block(&hash).ok_or_else(block_not_found)?;
+            if verbosity == 0 {
+                return Ok(json!(serialize_hex(block)));
+            }
+            let mut info = header_info(chain, &hash);
+            let txids = block
+                .txdata
+                .iter()
+                .map(|tx| tx.compute_txid().to_string())
+                .collect::<Vec<_>>();
+            info["tx"] = json!(txids);
+            info["size"] = json!(block.total_size());
+            info["strippedsize"] = json!(block.base_size());
+            info["weight"] = json!(block.weight().to_wu());
+            Ok(info)
+        }
+        "getblockheader" => {
+            let hash = param_block_hash(params, 0)?;
+            let verbose = params.get(1).and_then(Value::as_bool).unwrap_or(true);
+            let block = chain.block(&hash).ok_or_else(block_not_found)?;
+            if verbose {
+                Ok(header_info(chain, &hash))
+            } else {
+                Ok(json!(serialize_hex(&block.header)))
+            }
+        }
+        "getchaintips" => Ok(json!(chain
+            .chain_tips()
+            .into_iter()
+            .map(|(height, hash, branch_length, status)| json!({
+                "height": height,
+                "hash": hash.to_string(),
+                "branchlen": branch_length,
+                "status": status,
+            }))
+            .collect::<Vec<_>>())),
+        "gettxout" => {
+            let txid = param_txid(params, 0)?;
+            let vout = param_u64(params, 1)? as u32;
+            let include_mempool = params.get(2).and_then(Value::as_bool).unwrap_or(true);
+            let Some(utxo) = chain.tx_out(&OutPoint::new(txid, vout), include_mempool) else {
+                return Ok(Value::Null);
+            };
+            let script = &utxo.txout.script_pubkey;
+            let mut script_pub_key = json!({
+                "asm": script.to_asm_string(),
+                "hex": hex::encode(script.as_bytes()),
+            });
+            if let Ok(address) = Address::from_script(script, chain.network()) {
+                script_pub_key["address"] = json!(address.to_string());
+            }
+            Ok(json!({
+                "bestblock": chain.best_block_hash().to_string(),
+                "confirmations": utxo.confirmations,
+                "value": utxo.txout.value.to_btc(),
+                "scriptPubKey": script_pub_key,
+                "coinbase": utxo.coinbase,
+            }))
+        }
+        "sendrawtransaction" => {
+            let tx_hex = param_str(params, 0)?;
+            let tx = deserialize_hex::<Transaction>(tx_hex).map_err(|e| {
+                (
+                    RPC_DESERIALIZATION_ERROR,
+                    format!("TX decode failed: {}", e),
+                )
+            })?;
+            Ok(json!(chain.send_transaction(tx).to_string()))
+        }
+        "getrawtransaction" => {
+            let txid = param_txid(params, 0)?;
+            let tx = chain.transaction(&txid).ok_or_else(|| {
+                (
+                    RPC_INVALID_ADDRESS_OR_KEY,
+                    "No such mempool or blockchain transaction".to_owned(),
+                )
+            })?;
+            Ok(json!(serialize_hex(tx)))
+        }
+        // The mock chain has no fee statistics, as a fresh regtest node
+        "estimatesmartfee" => Ok(json!({
+            "errors": ["Insufficient data or no feerate found"],
+            "blocks": 0,
+        })),
+        _ => Err((
+            RPC_METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+/// The block header info in the bitcoind `getblockheader` format.
+fn header_info(chain: &MockChain, hash: &BlockHash) -> Value {
+    let block = chain.block(hash).expect("The block should exist");
+    let header = &block.header;
+    let height = chain.block_height(hash).unwrap_or_default();
+    let next_block_hash = if chain.is_active(hash) {
+        chain.block_hash(height + 1).map(|hash| hash.to_string())
+    } else {
+        None
+    };
+    let previous_block_hash = (height > 0).then(|| header.prev_blockhash.to_string());
+
+    let mut chain_work = Work::from_be_bytes([0u8; 32]);
+    let mut current = Some(block);
+    while let Some(block) = current {
+        chain_work = chain_work + block.header.work();
+        current = chain.block(&block.header.prev_blockhash);
+    }
+
+    json!({
+        "hash": hash.to_string(),
+        "confirmations": chain.confirmations(hash).unwrap_or(-1),
+        "height": height,
+        "version": header.version.to_consensus(),
+        "versionHex": format!("{:08x}", header.version.to_consensus() as u32),
+        "merkleroot": header.merkle_root.to_string(),
+        "time": header.time,
+        "mediantime": chain.median_time(hash),
+        "nonce": header.nonce,
+        "bits": format!("{:08x}", header.bits.to_consensus()),
+        "difficulty": header.difficulty_float(),
+        "chainwork": hex::encode(chain_work.to_be_bytes()),
+        "nTx": block.txdata.len(),
+        "previousblockhash": previous_block_hash,
+        "nextblockhash": next_block_hash,
+    })
+}
+
+fn block_not_found() -> (i64, String) {
+    (RPC_INVALID_ADDRESS_OR_KEY, "Block not found".to_owned())
+}
+
+fn param_str(params: &[Value], index: usize) -> std::result::Result<&str, (i64, String)> {
+    params
+        .get(index)
+        .and_then(Value::as_str)
+        .ok_or_else(|| (RPC_INVALID_PARAMS, format!("Invalid param {}", index)))
+}
+
+fn param_u64(params: &[Value], index: usize) -> std::result::Result<u64, (i64, String)> {
+    params
+        .get(index)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| (RPC_INVALID_PARAMS, format!("Invalid param {}", index)))
+}
+
+fn param_block_hash(
+    params: &[Value],
+    index: usize,
+) -> std::result::Result<BlockHash, (i64, String)> {
+    BlockHash::from_str(param_str(params, index)?)
+        .map_err(|e| (RPC_INVALID_PARAMETER, e.to_string()))
+}
+
+fn param_txid(params: &[Value], index: usize) -> std::result::Result<Txid, (i64, String)> {
+    Txid::from_str(param_str(params, index)?).map_err(|e| (RPC_INVALID_PARAMETER, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoincore_rpc::{Auth, Client, RpcApi};
+
+    fn rpc_client(server: &MockBitcoinRpcServer) -> Client {
+        Client::new(
+            &server.url(),
+            Auth::UserPass("user".to_owned(), "password".to_owned()),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rpc_with_reorg() {
+        let server = MockBitcoinRpcServer::start(MockChain::new()).await.unwrap();
+        let old_blocks = server.chain().mine_blocks(3);
+        let client = rpc_client(&server);
+
+        let (tip_block, second_block) = (old_blocks[2], old_blocks[1]);
+        let (best_hash, block_hash, block, header_info) = tokio::task::spawn_blocking(move || {
+            (
+                client.get_best_block_hash().unwrap(),
+                client.get_block_hash(2).unwrap(),
+                client.get_block(&tip_block).unwrap(),
+                client.get_block_header_info(&second_block).unwrap(),
+            )
+        })
+        .await
+        .unwrap();
+        assert_eq!(best_hash, old_blocks[2]);
+        assert_eq!(block_hash, old_blocks[1]);
+        assert_eq!(block.block_hash(), old_blocks[2]);
+        assert_eq!(header_info.height, 2);
+        assert_eq!(header_info.confirmations, 2);
+        assert_eq!(header_info.previous_block_hash, Some(old_blocks[0]));
+        assert_eq!(header_info.next_block_hash, Some(old_blocks[2]));
+
+        let new_blocks = server.chain().reorg(2, 3).unwrap();
+        let client = rpc_client(&server);
+        let stale_block = old_blocks[2];
+        let (best_hash, stale_info, chain_tips, stale_block_body) =
+            tokio::task::spawn_blocking(move || {
+                (
+                    client.get_best_block_hash().unwrap(),
+                    client.get_block_header_info(&stale_block).unwrap(),
+                    client.get_chain_tips().unwrap(),
+                    client.get_block(&stale_block).unwrap(),
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(best_hash, new_blocks[2]);
+        assert_eq!(stale_info.confirmations, -1);
+        assert_eq!(stale_info.next_block_hash, None);
+        assert_eq!(stale_block_body.block_hash(), stale_block);
+        assert_eq!(chain_tips.len(), 2);
+        assert_eq!(chain_tips[0].hash, new_blocks[2]);
+        assert_eq!(chain_tips[1].hash, stale_block);
+        assert_eq!(chain_tips[1].branch_length, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rpc_transactions() {
+        let server = MockBitcoinRpcServer::start(MockChain::new()).await.unwrap();
+        let block_hash = server.chain().mine_block();
+        let coinbase = server.chain().block(&block_hash).unwrap().txdata[0].clone();
+        let mut spend_tx = coinbase.clone();
+        spend_tx.input[0].previous_output = OutPoint::new(coinbase.compute_txid(), 0);
+        let client = rpc_client(&server);
+        let coinbase_txid = coinbase.compute_txid();
+
+        let (utxo, sent_txid, spent_utxo, missing_block) = tokio::task::spawn_blocking(move || {
+            let utxo = client.get_tx_out(&coinbase_txid, 0, Some(true)).unwrap();
+            let sent_txid = client.send_raw_transaction(&spend_tx).unwrap();
+            let spent_utxo = client.get_tx_out(&coinbase_txid, 0, Some(true)).unwrap();
+            let missing_block = client.get_block(
+                &BlockHash::from_str(
+                    "0000000000000000000000000000000000000000000000000000000000000001",
+                )
+                .unwrap(),
+            );
+            (utxo, sent_txid, spent_utxo, missing_block)
+        })
+        .await
+        .unwrap();
+        let utxo = utxo.unwrap();
+        assert!(utxo.coinbase);
+        assert_eq!(utxo.confirmations, 1);
+        assert_eq!(utxo.value, coinbase.output[0].value);
+        assert!(spent_utxo.is_none());
+        assert!(missing_block.is_err());
+        assert_eq!(server.chain().mempool().len(), 1);
+        assert_eq!(server.chain().mempool()[0].compute_txid(), sent_txid);
+    }
+}
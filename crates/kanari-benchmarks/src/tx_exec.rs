@@ -16,6 +16,9 @@ use crate::config::BenchTxConfig;
 use crate::config::TxType::{BtcBlock, BtcTx, Empty, Transfer, TransferLargeObject};
 use crate::tx::{create_btc_blk_tx, create_l2_tx, find_block_height, prepare_btc_block};
 
+/// The number of senders of the parallel execution benchmark, every batch has one tx of each sender.
+const PARALLEL_BATCH_SIZE: usize = 16;
+
 // pure execution, no validate, sequence
 pub fn tx_exec_benchmark(c: &mut Criterion) {
     let config = BenchTxConfig::load();
@@ -144,3 +147,65 @@ pub fn tx_exec_benchmark(c: &mut Criterion) {
     });
     group.finish();
 }
+
+// execute the same l2 txs one by one and in parallel, a batch of txs per iteration
+pub fn tx_exec_parallel_benchmark(c: &mut Criterion) {
+    let config = BenchTxConfig::load();
+
+    let tx_type = config.tx_type.clone().unwrap();
+    let (bench_id, batch_cnt) = match tx_type {
+        Transfer => ("l2_tx_transfer", 50),
+        Empty => ("l2_tx_empty", 60),
+        _ => {
+            info!("The parallel execution benchmark only supports the empty and transfer tx");
+            return;
+        }
+    };
+
+    let mut test_transaction_builders = (0..PARALLEL_BATCH_SIZE)
+        .map(|_| TestTransactionBuilder::new(KanariKeyPair::generate_secp256k1()))
+        .collect::<Vec<_>>();
+    let batches = (0..batch_cnt)
+        .map(|seq_num| {
+            test_transaction_builders
+                .iter_mut()
+                .map(|builder| create_l2_tx(builder, seq_num as u64, tx_type.clone()).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("bench_tx_exec");
+    group.sample_size(batch_cnt);
+    group.sampling_mode(SamplingMode::Flat);
+
+    let mut binding_test = binding_test::RustBindingTest::new_in_tokio().unwrap();
+    let mut batches_iter = batches.clone().into_iter();
+    group.bench_function(format!("{}_serial", bench_id), |b| {
+        b.iter(|| {
+            let batch = match batches_iter.next() {
+                Some(batch) => batch,
+                None => return,
+            };
+            for tx in batch {
+                binding_test.execute(tx).unwrap();
+            }
+        });
+    });
+
+    let mut binding_test = binding_test::RustBindingTest::new_in_tokio().unwrap();
+    let mut batches_iter = batches.into_iter();
+    group.bench_function(format!("{}_parallel", bench_id), |b| {
+        b.iter(|| {
+            let batch = match batches_iter.next() {
+                Some(batch) => batch,
+                None => return,
+            };
+            let txs = batch
+                .into_iter()
+                .map(|tx| binding_test.executor.validate_l2_tx(tx).unwrap())
+                .collect::<Vec<_>>();
+            binding_test.execute_verified_txs_in_parallel(txs).unwrap();
+        });
+    });
+    group.finish();
+}
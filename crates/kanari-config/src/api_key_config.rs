@@ -0,0 +1,180 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use anyhow::{bail, ensure, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// The API keys of the RPC server, loaded from a yaml file via `--api-key-config`.
+///
+/// ```yaml
+/// require_api_key: false
+/// keys:
+///   - name: explorer
+///     key: "<secret>"
+///     default_quota:
+///       per_second: 50
+///       burst_size: 100
+///     method_quotas:
+///       kanari_executeRawTransaction:
+///         per_second: 1
+///         burst_size: 5
+///       /graphql:
+///         per_second: 10
+///         burst_size: 20
+/// ```
+#[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    /// If true, requests without a valid API key are rejected.
+    #[serde(default)]
+    pub require_api_key: bool,
+    #[serde(default)]
+    pub keys: Vec<ApiKeyEntry>,
+}
+
+impl Config for ApiKeyConfig {}
+
+impl ApiKeyConfig {
+    /// Load the config from the yaml file and validate it.
+    pub fn load_and_validate<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let config = Self::load(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        // The key to the name of its entry, the key itself is a secret and never printed.
+        let mut keys = HashMap::new();
+        for entry in &self.keys {
+            ensure!(!entry.key.is_empty(), "The key of {} is empty", entry.name);
+            ensure!(
+                names.insert(entry.name.as_str()),
+                "The name {} is duplicated",
+                entry.name
+            );
+            if let Some(name) = keys.insert(entry.key.as_str(), entry.name.as_str()) {
+                bail!(
+                    "The key of {} is the same as the key of {}",
+                    entry.name,
+                    name
+                );
+            }
+            if let Some(quota) = &entry.default_quota {
+                quota.validate(&entry.name, "default_quota")?;
+            }
+            for (method, quota) in &entry.method_quotas {
+                quota.validate(&entry.name, method)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyEntry {
+    /// The name of the key, used as the metrics label instead of the key itself.
+    pub name: String,
+    pub key: String,
+    /// The quota of every method which is not in `method_quotas`, unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_quota: Option<QuotaConfig>,
+    /// The quotas by the full JSON-RPC method name, such as `kanari_queryEvents`,
+    /// or by the route path of the other routes, such as `/graphql` or `/content/:inscription_id`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub method_quotas: HashMap<String, QuotaConfig>,
+}
+
+impl ApiKeyEntry {
+    pub fn quota_of(&self, method: &str) -> Option<&QuotaConfig> {
+        self.method_quotas
+            .get(method)
+            .or(self.default_quota.as_ref())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    /// The number of requests replenished per second.
+    pub per_second: f64,
+    /// The max number of requests in a burst. **The burst_size must not be zero.**
+    pub burst_size: u32,
+}
+
+impl QuotaConfig {
+    fn validate(&self, key_name: &str, method: &str) -> Result<()> {
+        ensure!(
+            self.burst_size > 0,
+            "The burst_size of {} {} must not be zero",
+            key_name,
+            method
+        );
+        ensure!(
+            self.per_second.is_finite() && self.per_second >= 0.0,
+            "The per_second of {} {} must be a non-negative number",
+            key_name,
+            method
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_quota() {
+        let config: ApiKeyConfig = serde_yaml::from_str(
+            r#"
+keys:
+  - name: explorer
+    key: secret
+    default_quota:
+      per_second: 50
+      burst_size: 100
+    method_quotas:
+      kanari_executeRawTransaction:
+        per_second: 1
+        burst_size: 0
+"#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("burst_size"), "{}", err);
+
+        let mut config = config;
+        config.keys[0].method_quotas.clear();
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_duplicated_keys() {
+        let config: ApiKeyConfig = serde_yaml::from_str(
+            r#"
+keys:
+  - name: explorer
+    key: secret
+  - name: explorer
+    key: other-secret
+"#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("name explorer"), "{}", err);
+
+        let mut config = config;
+        config.keys[1].name = "wallet".to_string();
+        config.validate().unwrap();
+
+        config.keys[1].key = "secret".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(!err.to_string().contains("secret"), "{}", err);
+        assert!(err.to_string().contains("wallet"), "{}", err);
+    }
+}
@@ -20,6 +20,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::{fmt::Debug, path::Path, path::PathBuf};
 
+pub mod api_key_config;
 pub mod config;
 pub mod da_config;
 pub mod proposer_config;
@@ -138,6 +139,11 @@ pub struct KanariOpt {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(long)]
     pub btc_reorg_aware_height: Option<usize>,
+    /// The directory of local Bitcoin blocks to relay L1 block and transaction to L2 instead of the Bitcoin RPC.
+    /// It is a Bitcoin Core data directory with `blk*.dat` files, or a directory of raw serialized blocks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long, env = "BTC_BLOCKS_DIR")]
+    pub btc_blocks_dir: Option<PathBuf>,
 
     /// The address of the sequencer account
     #[clap(long)]
@@ -172,6 +178,45 @@ pub struct KanariOpt {
     #[clap(long, default_value_t, value_enum)]
     pub service_type: ServiceType,
 
+    /// The max depth of a GraphQL query, default is 12.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub graphql_max_depth: Option<usize>,
+
+    /// The max complexity of a GraphQL query, default is 10000.
+    /// The complexity of a paginated field is the page size times the complexity of its items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub graphql_max_complexity: Option<usize>,
+
+    /// The path of the API key config file, which defines the API keys and their per-method quotas.
+    /// If not set, API keys are not checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub api_key_config: Option<PathBuf>,
+
+    /// The max number of requests in a JSON-RPC batch, default is 50.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_max_batch_size: Option<usize>,
+
+    /// The max page size of paginated JSON-RPC methods, default is 200.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_max_page_size: Option<usize>,
+
+    /// The max bytes of a JSON-RPC response, default is 16MiB.
+    /// A page which exceeds it is shortened, and its `next_cursor` points to the last item returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_max_response_bytes: Option<usize>,
+
+    /// Enable the debug JSON-RPC methods, such as `debug_traceTransaction`.
+    /// They re-execute the transactions on the executor, so they should not be exposed on public nodes.
+    #[serde(default)]
+    #[clap(long)]
+    pub enable_debug_rpc: bool,
+
     #[serde(skip)]
     #[clap(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -203,6 +248,7 @@ impl KanariOpt {
             btc_sync_block_interval: None,
             btc_reorg_aware_block_store_dir: None,
             btc_reorg_aware_height: None,
+            btc_blocks_dir: None,
             sequencer_account: None,
             proposer_account: None,
             da: DAConfig::default(),
@@ -212,6 +258,13 @@ impl KanariOpt {
             traffic_burst_size: None,
             base: None,
             service_type: ServiceType::default(),
+            graphql_max_depth: None,
+            graphql_max_complexity: None,
+            api_key_config: None,
+            rpc_max_batch_size: None,
+            rpc_max_page_size: None,
+            rpc_max_response_bytes: None,
+            enable_debug_rpc: false,
         };
         opt.init()?;
         Ok(opt)
@@ -268,11 +321,13 @@ impl KanariOpt {
     }
 
     pub fn bitcoin_relayer_config(&self) -> Option<BitcoinRelayerConfig> {
-        self.btc_rpc_url.as_ref()?;
+        if self.btc_rpc_url.is_none() && self.btc_blocks_dir.is_none() {
+            return None;
+        }
         Some(BitcoinRelayerConfig {
-            btc_rpc_url: self.btc_rpc_url.clone().unwrap(),
-            btc_rpc_user_name: self.btc_rpc_username.clone().unwrap(),
-            btc_rpc_password: self.btc_rpc_password.clone().unwrap(),
+            btc_rpc_url: self.btc_rpc_url.clone().unwrap_or_default(),
+            btc_rpc_user_name: self.btc_rpc_username.clone().unwrap_or_default(),
+            btc_rpc_password: self.btc_rpc_password.clone().unwrap_or_default(),
             btc_end_block_height: self.btc_end_block_height,
             btc_sync_block_interval: self.btc_sync_block_interval,
             btc_reorg_aware_block_store_dir: self
@@ -286,6 +341,7 @@ impl KanariOpt {
             btc_reorg_aware_height: self
                 .btc_reorg_aware_height
                 .unwrap_or(DEFAULT_BTC_REORG_AWARE_HEIGHT),
+            btc_blocks_dir: self.btc_blocks_dir.clone(),
         })
     }
 
@@ -351,6 +407,8 @@ pub struct BitcoinRelayerConfig {
     pub btc_sync_block_interval: Option<u64>,
     pub btc_reorg_aware_block_store_dir: PathBuf,
     pub btc_reorg_aware_height: usize,
+    /// Read the blocks from the local directory instead of the Bitcoin RPC if set.
+    pub btc_blocks_dir: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1,157 +1,181 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Bound;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
 
-use cosmwasm_std::{Binary, ContractResult, Order, Record, SystemResult};
+use cosmwasm_std::{
+    from_json, to_json_binary, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty,
+    Order, QueryRequest, Record, SystemError, SystemResult, WasmQuery,
+};
 use cosmwasm_vm::{Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage};
+use serde::Deserialize;
 
-use move_core_types::value::MoveTypeLayout;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::gas_algebra::NumBytes;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::u256::U256;
 use move_core_types::vm_status::StatusCode;
 use move_vm_types::loaded_data::runtime_types::Type;
-use move_vm_types::values::Value;
+use move_vm_types::values::{Reference, Value};
 
-use moveos_object_runtime::runtime_object::RuntimeObject;
+use moveos_object_runtime::runtime::ObjectRuntime;
 use moveos_object_runtime::TypeLayoutLoader;
-use moveos_types::h256;
+use moveos_types::h256::{self, H256};
+use moveos_types::move_std::string::MoveString;
+use moveos_types::moveos_std::object::{named_object_id, DynamicField, ObjectID};
 use moveos_types::state::FieldKey;
-use moveos_types::state::MoveState;
-use moveos_types::state_resolver::StatelessResolver;
+use moveos_types::state::{MoveState, MoveType};
+
+use kanari_types::address::KanariAddress;
+use kanari_types::addresses::KANARI_NURSERY_ADDRESS;
+use kanari_types::framework::account_coin_store::AccountCoinStoreModule;
+use kanari_types::framework::coin_store::CoinStore;
+use kanari_types::framework::multi_coin_store::CoinStoreField;
 
 type IteratorItem = (FieldKey, Vec<u8>);
 type IteratorState = (Vec<IteratorItem>, usize);
 
-pub struct MoveStorage<'a> {
-    object: &'a mut RuntimeObject,
-    layout_loader: &'a dyn TypeLayoutLoader,
-    resolver: &'a dyn StatelessResolver,
+/// The field key of a key of the contract store.
+fn storage_key(key: &[u8]) -> FieldKey {
+    FieldKey::new(h256::sha3_256_of(key).into())
+}
+
+/// Collects the records of `fields` from `start` (included) to `end` (excluded) in the `order`,
+/// returns the records and the bytes of their values.
+fn scan_records<'a>(
+    fields: impl Iterator<Item = (&'a FieldKey, &'a Vec<u8>)>,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> (Vec<IteratorItem>, u64) {
+    let cursor = start.map(storage_key);
+    let mut records: Vec<IteratorItem> = fields
+        .filter(|(key, _)| cursor.is_none_or(|cursor| **key >= cursor))
+        .map(|(key, value)| (*key, value.clone()))
+        .collect();
+
+    if order == Order::Descending {
+        records.reverse();
+    }
+
+    // Apply end filter
+    if let Some(end_bytes) = end {
+        let end_key = storage_key(end_bytes);
+        records.retain(|(key, _)| match order {
+            Order::Ascending => *key < end_key,
+            Order::Descending => *key > end_key,
+        });
+    }
+
+    let bytes_len = records.iter().fold(0u64, |len, (_, value)| {
+        len.saturating_add(value.len() as u64)
+    });
+    (records, bytes_len)
+}
+
+fn next_record(
+    iterators: &mut HashMap<u32, IteratorState>,
+    iterator_id: u32,
+) -> BackendResult<Option<Record>> {
+    let (records, index) = match iterators.get_mut(&iterator_id) {
+        Some(it) => it,
+        None => {
+            return (
+                Err(BackendError::IteratorDoesNotExist { id: iterator_id }),
+                GasInfo::new(1, 0),
+            )
+        }
+    };
+    let record = records
+        .get(*index)
+        .map(|(key, value)| (key.0.to_vec(), value.clone()));
+    if record.is_some() {
+        *index += 1;
+    }
+    (Ok(record), GasInfo::new(1, 0))
+}
+
+/// The storage of a contract, whose fields are the values of the contract store object.
+///
+/// The storage owns a snapshot of the store fields, loaded through the `ObjectRuntime` before the
+/// call, so the instance does not borrow the object runtime. The writes of a call are kept in
+/// memory, and the caller writes them back to the store object by `ObjectRuntimeLoader::write_back`
+/// only if the call succeeds, so a failed call has no changes.
+pub struct MoveStorage {
+    fields: BTreeMap<FieldKey, Vec<u8>>,
     iterator_id_counter: u32,
     iterators: HashMap<u32, IteratorState>,
+    /// The pending writes of the call, `None` means the key is removed
+    pending_writes: BTreeMap<FieldKey, Option<Vec<u8>>>,
 }
 
-impl<'a> MoveStorage<'a> {
-    pub fn new(
-        object: &'a mut RuntimeObject,
-        layout_loader: &'a dyn TypeLayoutLoader,
-        resolver: &'a dyn StatelessResolver,
-    ) -> Self {
+impl MoveStorage {
+    pub fn new(fields: BTreeMap<FieldKey, Vec<u8>>) -> Self {
         MoveStorage {
-            object,
-            layout_loader,
-            resolver,
+            fields,
             iterator_id_counter: 0,
             iterators: HashMap::new(),
+            pending_writes: BTreeMap::new(),
         }
     }
 
-    fn serialize_value(
-        &self,
-        layout: &MoveTypeLayout,
-        value: &Value,
-    ) -> Result<Vec<u8>, BackendError> {
-        let bytes = match value.simple_serialize(layout) {
-            Some(bytes) => bytes,
-            None => return Err(BackendError::BadArgument {}),
-        };
-        Ok(bytes)
+    /// The pending writes of the call, `None` means the key is removed.
+    pub fn into_pending_writes(self) -> BTreeMap<FieldKey, Option<Vec<u8>>> {
+        self.pending_writes
     }
 
-    fn deserialize_value(
-        &self,
-        layout: &MoveTypeLayout,
-        bytes: &[u8],
-    ) -> Result<Value, BackendError> {
-        let value = match Value::simple_deserialize(bytes, layout) {
-            Some(value) => value,
-            None => return Err(BackendError::BadArgument {}),
-        };
-        Ok(value)
+    /// The undo log of the pending writes, which is the previous values of the written keys,
+    /// so writing it back reverts the writes. `None` means the key did not exist.
+    pub fn undo_log(&self) -> BTreeMap<FieldKey, Option<Vec<u8>>> {
+        self.pending_writes
+            .keys()
+            .map(|key| (*key, self.fields.get(key).cloned()))
+            .collect()
     }
 }
 
+/// Encodes the undo log of a call, which is kept by Move as `vector<u8>`.
+pub fn encode_undo_log(undo_log: &BTreeMap<FieldKey, Option<Vec<u8>>>) -> PartialVMResult<Vec<u8>> {
+    bcs::to_bytes(undo_log).map_err(|e| {
+        PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR).with_message(e.to_string())
+    })
+}
+
+pub fn decode_undo_log(bytes: &[u8]) -> PartialVMResult<BTreeMap<FieldKey, Option<Vec<u8>>>> {
+    bcs::from_bytes(bytes).map_err(deserialize_error)
+}
+
 impl Default for MockStorage {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'a> Storage for MoveStorage<'a> {
+impl Storage for MoveStorage {
     fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
-        let hash = h256::sha3_256_of(key);
-        let field_key = FieldKey::new(hash.into());
-        let move_layout = Vec::<u8>::type_layout();
-        let move_type = Type::Vector(Box::new(Type::U8));
-
-        match self
-            .object
-            .get_field(self.layout_loader, self.resolver, field_key, &move_type)
-        {
-            Ok(value) => match self.serialize_value(&move_layout, &value.0) {
-                Ok(bytes) => (Ok(Some(bytes)), GasInfo::new(1, 0)),
-                Err(e) => (Err(e), GasInfo::new(1, 0)),
-            },
-            Err(e) => {
-                if e.major_status() == StatusCode::RESOURCE_DOES_NOT_EXIST {
-                    (Ok(None), GasInfo::new(1, 0))
-                } else {
-                    (
-                        Err(BackendError::Unknown { msg: e.to_string() }),
-                        GasInfo::new(1, 0),
-                    )
-                }
-            }
-        }
+        let field_key = storage_key(key);
+        let value = match self.pending_writes.get(&field_key) {
+            Some(value) => value.clone(),
+            None => self.fields.get(&field_key).cloned(),
+        };
+        (Ok(value), GasInfo::new(1, 0))
     }
 
     fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
-        let hash = h256::sha3_256_of(key);
-        let field_key = FieldKey::new(hash.into());
-        let move_layout = Vec::<u8>::type_layout();
-        let move_type = Type::Vector(Box::new(Type::U8));
-
-        let deserialized_value = match self.deserialize_value(&move_layout, value) {
-            Ok(v) => v,
-            Err(e) => return (Err(e), GasInfo::new(1, 0)),
-        };
-
-        match self.object.add_field(
-            self.layout_loader,
-            self.resolver,
-            field_key,
-            &move_type,
-            deserialized_value,
-        ) {
-            Ok(_) => (Ok(()), GasInfo::new(1, 0)),
-            Err(e) => (
-                Err(BackendError::Unknown { msg: e.to_string() }),
-                GasInfo::new(1, 0),
-            ),
-        }
+        self.pending_writes
+            .insert(storage_key(key), Some(value.to_vec()));
+        (Ok(()), GasInfo::new(1, 0))
     }
 
     fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
-        let hash = h256::sha3_256_of(key);
-        let field_key = FieldKey::new(hash.into());
-        let move_type = Type::Vector(Box::new(Type::U8));
-
-        match self
-            .object
-            .remove_field(self.layout_loader, self.resolver, field_key, &move_type)
-        {
-            Ok(_) => (Ok(()), GasInfo::new(1, 0)),
-            Err(e) => {
-                if e.major_status() == StatusCode::RESOURCE_DOES_NOT_EXIST {
-                    (Ok(()), GasInfo::new(1, 0))
-                } else {
-                    (
-                        Err(BackendError::Unknown { msg: e.to_string() }),
-                        GasInfo::new(1, 0),
-                    )
-                }
-            }
-        }
+        self.pending_writes.insert(storage_key(key), None);
+        (Ok(()), GasInfo::new(1, 0))
     }
 
     fn scan(
@@ -160,136 +184,686 @@ impl<'a> Storage for MoveStorage<'a> {
         end: Option<&[u8]>,
         order: Order,
     ) -> BackendResult<u32> {
-        let cursor = start.map(|s| FieldKey::new(h256::sha3_256_of(s).into()));
-
-        match self.object.scan_fields(
-            self.layout_loader,
-            self.resolver,
-            cursor,
-            usize::MAX,
-            &Type::Vector(Box::new(Type::U8)), // Assuming values are Vec<u8>
-        ) {
-            Ok((values, bytes_len_opt)) => {
-                let move_layout = Vec::<u8>::type_layout();
-
-                let mut records: Vec<(FieldKey, Vec<u8>)> = values
-                    .into_iter()
-                    .filter_map(|(key, value)| {
-                        self.serialize_value(&move_layout, &value)
-                            .ok()
-                            .map(|bytes| (key, bytes))
-                    })
-                    .collect();
-
-                if order == Order::Descending {
-                    records.reverse();
-                }
+        // Merge the pending writes into the snapshot of the store
+        let mut fields = self.fields.clone();
+        for (key, value) in self.pending_writes.iter() {
+            match value {
+                Some(value) => fields.insert(*key, value.clone()),
+                None => fields.remove(key),
+            };
+        }
+        let (records, gas_used) = scan_records(fields.iter(), start, end, order);
 
-                // Apply end filter
-                if let Some(end_bytes) = end {
-                    let end_key = FieldKey::new(h256::sha3_256_of(end_bytes).into());
-                    records.retain(|(key, _)| match order {
-                        Order::Ascending => *key < end_key,
-                        Order::Descending => *key > end_key,
-                    });
-                }
+        let id = self.iterator_id_counter;
+        self.iterator_id_counter += 1;
+        self.iterators.insert(id, (records, 0));
+        (Ok(id), GasInfo::new(gas_used, 0))
+    }
 
-                let id = self.iterator_id_counter;
-                self.iterator_id_counter += 1;
-                self.iterators.insert(id, (records, 0));
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        next_record(&mut self.iterators, iterator_id)
+    }
+}
 
-                let gas_used = if let Some(bytes_len) = bytes_len_opt {
-                    bytes_len.into()
-                } else {
-                    0
-                };
-                (Ok(id), GasInfo::new(gas_used, 0))
-            }
-            Err(e) => (
-                Err(BackendError::Unknown { msg: e.to_string() }),
-                GasInfo::new(1, 0),
-            ),
-        }
+const GAS_COST_ADDR_API: u64 = 1;
+const GAS_COST_QUERY_BASE: u64 = 1;
+const GAS_COST_QUERY_PER_BYTE: u64 = 1;
+
+/// The address API of contracts, the human readable address is the Kanari bech32 address,
+/// and the canonical address is the 32 bytes of the address.
+#[derive(Clone)]
+pub struct MoveBackendApi;
+
+impl BackendApi for MoveBackendApi {
+    fn addr_validate(&self, human: &str) -> BackendResult<()> {
+        let result = match canonicalize_address(human) {
+            // the address must be in the normalized (lowercase) form
+            Ok(address) if address.to_bech32() == human => Ok(()),
+            Ok(_) => Err(BackendError::UserErr {
+                msg: format!("Address is not normalized: {}", human),
+            }),
+            Err(e) => Err(e),
+        };
+        (result, GasInfo::new(GAS_COST_ADDR_API, 0))
     }
 
-    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
-        let (records, index) = match self.iterators.get_mut(&iterator_id) {
-            Some(it) => it,
-            None => {
-                return (
-                    Err(BackendError::IteratorDoesNotExist { id: iterator_id }),
-                    GasInfo::new(1, 0),
-                )
-            }
+    fn addr_canonicalize(&self, human: &str) -> BackendResult<Vec<u8>> {
+        (
+            canonicalize_address(human).map(|address| address.to_vec()),
+            GasInfo::new(GAS_COST_ADDR_API, 0),
+        )
+    }
+
+    fn addr_humanize(&self, canonical: &[u8]) -> BackendResult<String> {
+        let result = if canonical.len() == KanariAddress::LENGTH {
+            Ok(KanariAddress(H256::from_slice(canonical)).to_bech32())
+        } else {
+            Err(BackendError::UserErr {
+                msg: format!("Invalid canonical address length: {}", canonical.len()),
+            })
         };
+        (result, GasInfo::new(GAS_COST_ADDR_API, 0))
+    }
+}
 
-        if *index >= records.len() {
-            return (Ok(None), GasInfo::new(1, 0));
-        }
+fn canonicalize_address(human: &str) -> Result<KanariAddress, BackendError> {
+    KanariAddress::from_bech32(human).map_err(|e| BackendError::UserErr {
+        msg: format!("Invalid address {}: {}", human, e),
+    })
+}
 
-        let (key, value) = &records[*index];
-        *index += 1;
+pub const CONTRACT_REGISTRY_MODULE_NAME: &IdentStr = ident_str!("cosmwasm_vm");
+pub const CONTRACT_REGISTRY_STRUCT_NAME: &IdentStr = ident_str!("ContractRegistry");
+pub const INSTANCE_STRUCT_NAME: &IdentStr = ident_str!("Instance");
 
-        let key_bytes: [u8; 32] = key.0;
+/// The ObjectID of the named `kanari_nursery::cosmwasm_vm::ContractRegistry` object,
+/// whose fields map the contract addresses to the ObjectIDs of the `Object<Instance>`s.
+pub fn contract_registry_id() -> ObjectID {
+    named_object_id(&StructTag {
+        address: KANARI_NURSERY_ADDRESS,
+        module: CONTRACT_REGISTRY_MODULE_NAME.to_owned(),
+        name: CONTRACT_REGISTRY_STRUCT_NAME.to_owned(),
+        type_params: vec![],
+    })
+}
 
-        (
-            Ok(Some((key_bytes.to_vec(), value.clone()))),
-            GasInfo::new(1, 0),
+fn instance_struct_tag() -> StructTag {
+    StructTag {
+        address: KANARI_NURSERY_ADDRESS,
+        module: CONTRACT_REGISTRY_MODULE_NAME.to_owned(),
+        name: INSTANCE_STRUCT_NAME.to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// The BCS form of `kanari_nursery::cosmwasm_vm::Instance`.
+#[derive(Debug, Clone, Deserialize)]
+struct InstanceValue {
+    code_checksum: Vec<u8>,
+    // the handle of the `Table` of the contract store
+    store: ObjectID,
+    // the `AccountCap` of the contract account, whose address is the contract address
+    _account: AccountAddress,
+}
+
+/// A contract registered in the `ContractRegistry`, with the snapshot of its store fields.
+#[derive(Debug, Clone)]
+pub struct RegisteredContract {
+    pub code_checksum: Vec<u8>,
+    pub store_fields: BTreeMap<FieldKey, Vec<u8>>,
+}
+
+/// Runs the `query` entry point of a contract for `WasmQuery::Smart`,
+/// implemented by the owner of the WASM cache.
+pub trait WasmSmartQuerier {
+    fn query_smart(
+        &self,
+        contract_addr: &str,
+        contract: &RegisteredContract,
+        msg: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>>;
+}
+
+/// A key of the state read by the queries of contracts.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QueryStateKey {
+    /// The contract registered at the address
+    Contract(AccountAddress),
+    /// The balance of the coin type of the account
+    Balance(AccountAddress, StructTag),
+}
+
+/// The state read by the queries of contracts, loaded by the caller through the `ObjectRuntime`,
+/// so the queries see the changes of the current transaction.
+///
+/// The state can not be loaded while a contract is running, so a query of a key which is not
+/// loaded records the key as missing and fails the call with a backend error, which the contract
+/// can not handle. The caller loads the missing keys and runs the call again.
+#[derive(Default)]
+pub struct QueryState {
+    contracts: BTreeMap<AccountAddress, Option<RegisteredContract>>,
+    balances: BTreeMap<(AccountAddress, StructTag), (U256, usize)>,
+    missing: Mutex<BTreeSet<QueryStateKey>>,
+}
+
+impl QueryState {
+    pub fn insert_contract(
+        &mut self,
+        address: AccountAddress,
+        contract: Option<RegisteredContract>,
+    ) {
+        self.contracts.insert(address, contract);
+    }
+
+    pub fn insert_balance(
+        &mut self,
+        owner: AccountAddress,
+        coin_type: StructTag,
+        balance: U256,
+        loaded_bytes: usize,
+    ) {
+        self.balances
+            .insert((owner, coin_type), (balance, loaded_bytes));
+    }
+
+    /// Takes the keys which are queried but not loaded.
+    pub fn take_missing(&mut self) -> BTreeSet<QueryStateKey> {
+        std::mem::take(
+            self.missing
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
         )
     }
+
+    fn missing_error(&self, key: QueryStateKey) -> BackendError {
+        let msg = format!("The query state {:?} is not loaded", key);
+        self.missing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key);
+        BackendError::Unknown { msg }
+    }
+
+    fn contract(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<&RegisteredContract>, BackendError> {
+        match self.contracts.get(&address) {
+            Some(contract) => Ok(contract.as_ref()),
+            None => Err(self.missing_error(QueryStateKey::Contract(address))),
+        }
+    }
+
+    fn balance(
+        &self,
+        owner: AccountAddress,
+        coin_type: &StructTag,
+    ) -> Result<(U256, usize), BackendError> {
+        match self.balances.get(&(owner, coin_type.clone())) {
+            Some(balance) => Ok(*balance),
+            None => Err(self.missing_error(QueryStateKey::Balance(owner, coin_type.clone()))),
+        }
+    }
 }
 
-// Implement BackendApi
-#[derive(Clone)]
-pub struct MoveBackendApi;
+/// The querier of contracts, which reads the `QueryState` loaded by the caller.
+///
+/// * `BankQuery::Balance`: the denom is the coin type, such as `0x3::gas_coin::GasCoin`,
+///   the amount is the balance of the account `CoinStore` plus the `MultiCoinStore`.
+/// * `WasmQuery::Raw` and `WasmQuery::Smart`: the contract address is the address of the
+///   contract account, and the contract must be registered in the `ContractRegistry`,
+///   which records its code checksum and store outside the contract store.
+pub struct MoveBackendQuerier {
+    state: Arc<QueryState>,
+    smart_querier: Option<Arc<dyn WasmSmartQuerier + Send + Sync>>,
+}
 
-impl BackendApi for MoveBackendApi {
-    fn addr_validate(&self, _human: &str) -> BackendResult<()> {
-        // Implement address validation logic
-        (Ok(()), GasInfo::new(1, 0))
+impl MoveBackendQuerier {
+    pub fn new(
+        state: Arc<QueryState>,
+        smart_querier: Option<Arc<dyn WasmSmartQuerier + Send + Sync>>,
+    ) -> Self {
+        MoveBackendQuerier {
+            state,
+            smart_querier,
+        }
     }
 
-    fn addr_canonicalize(&self, human: &str) -> BackendResult<Vec<u8>> {
-        // Implement address canonicalization logic
-        (Ok(human.as_bytes().to_vec()), GasInfo::new(1, 0))
+    fn get_contract(&self, contract_addr: &str) -> Result<&RegisteredContract, QueryError> {
+        let address = AccountAddress::from(canonicalize_address(contract_addr)?);
+        self.state.contract(address)?.ok_or_else(|| {
+            QueryError::System(SystemError::NoSuchContract {
+                addr: contract_addr.to_string(),
+            })
+        })
     }
 
-    fn addr_humanize(&self, canonical: &[u8]) -> BackendResult<String> {
-        // Implement address humanization logic
-        match String::from_utf8(canonical.to_vec()) {
-            Ok(human) => (Ok(human), GasInfo::new(1, 0)),
-            Err(_) => (Err(BackendError::InvalidUtf8 {}), GasInfo::new(1, 0)),
+    fn query_balance(&self, address: &str, denom: &str) -> Result<QueryOutput, QueryError> {
+        let owner = AccountAddress::from(canonicalize_address(address)?);
+        let coin_type = StructTag::from_str(denom).map_err(|e| QueryError::Contract {
+            error: format!("Invalid denom {}: {}", denom, e),
+            loaded_bytes: 0,
+        })?;
+
+        let (balance, loaded_bytes) = self.state.balance(owner, &coin_type)?;
+        let amount = u128::try_from(balance).map_err(|_| QueryError::Contract {
+            error: format!("Balance of {} overflows Uint128: {}", denom, balance),
+            loaded_bytes,
+        })?;
+        let response = BalanceResponse::new(Coin::new(amount, denom));
+        let data = to_json_binary(&response).map_err(unknown_error)?;
+        Ok(QueryOutput::Data { data, loaded_bytes })
+    }
+
+    fn query_raw_field(&self, contract_addr: &str, key: &[u8]) -> Result<QueryOutput, QueryError> {
+        let contract = self.get_contract(contract_addr)?;
+        let value = contract
+            .store_fields
+            .get(&storage_key(key))
+            .cloned()
+            .unwrap_or_default();
+        let loaded_bytes = value.len();
+        Ok(QueryOutput::Data {
+            data: Binary::from(value),
+            loaded_bytes,
+        })
+    }
+
+    fn query_smart(
+        &self,
+        contract_addr: &str,
+        msg: &[u8],
+        gas_limit: u64,
+    ) -> Result<QueryOutput, QueryError> {
+        let smart_querier = self.smart_querier.as_ref().ok_or_else(|| {
+            QueryError::System(SystemError::UnsupportedRequest {
+                kind: "wasm smart".to_string(),
+            })
+        })?;
+        let contract = self.get_contract(contract_addr)?;
+        let (result, gas_info) = smart_querier.query_smart(contract_addr, contract, msg, gas_limit);
+        Ok(QueryOutput::Delegated {
+            result: result?,
+            gas_info,
+        })
+    }
+}
+
+/// The output of a query before the gas is charged.
+enum QueryOutput {
+    Data {
+        data: Binary,
+        loaded_bytes: usize,
+    },
+    Delegated {
+        result: SystemResult<ContractResult<Binary>>,
+        gas_info: GasInfo,
+    },
+}
+
+enum QueryError {
+    Backend(BackendError),
+    System(SystemError),
+    Contract { error: String, loaded_bytes: usize },
+}
+
+impl From<BackendError> for QueryError {
+    fn from(e: BackendError) -> Self {
+        match e {
+            // the invalid addresses are the errors of the querying contract, not of the backend
+            BackendError::UserErr { msg } => QueryError::Contract {
+                error: msg,
+                loaded_bytes: 0,
+            },
+            e => QueryError::Backend(e),
         }
     }
 }
 
-// Implement Querier
-#[derive(Clone)]
-pub struct MoveBackendQuerier;
+fn unknown_error(e: impl std::fmt::Display) -> BackendError {
+    BackendError::Unknown { msg: e.to_string() }
+}
+
+fn query_gas(loaded_bytes: usize) -> u64 {
+    GAS_COST_QUERY_BASE.saturating_add(GAS_COST_QUERY_PER_BYTE.saturating_mul(loaded_bytes as u64))
+}
 
 impl Querier for MoveBackendQuerier {
     fn query_raw(
         &self,
-        _request: &[u8],
+        request: &[u8],
         gas_limit: u64,
     ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
-        // Implement query logic
+        let base_gas_info = GasInfo::with_externally_used(query_gas(0));
+        if base_gas_info.externally_used > gas_limit {
+            return (
+                Err(BackendError::OutOfGas {}),
+                GasInfo::with_externally_used(gas_limit),
+            );
+        }
+
+        let request_result = match from_json::<QueryRequest<Empty>>(request) {
+            Ok(QueryRequest::Bank(BankQuery::Balance { address, denom })) => {
+                self.query_balance(&address, &denom)
+            }
+            Ok(QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })) => {
+                self.query_raw_field(&contract_addr, key.as_slice())
+            }
+            Ok(QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })) => self.query_smart(
+                &contract_addr,
+                msg.as_slice(),
+                gas_limit - base_gas_info.externally_used,
+            ),
+            Ok(QueryRequest::Bank(_)) => Err(QueryError::System(SystemError::UnsupportedRequest {
+                kind: "bank".to_string(),
+            })),
+            Ok(QueryRequest::Wasm(_)) => Err(QueryError::System(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            })),
+            Ok(_) => Err(QueryError::System(SystemError::UnsupportedRequest {
+                kind: "unknown".to_string(),
+            })),
+            Err(e) => Err(QueryError::System(SystemError::InvalidRequest {
+                error: format!("Parsing query request: {}", e),
+                request: Binary::from(request),
+            })),
+        };
+
+        let (result, gas_info) = match request_result {
+            Ok(QueryOutput::Data { data, loaded_bytes }) => (
+                Ok(SystemResult::Ok(ContractResult::Ok(data))),
+                GasInfo::with_externally_used(query_gas(loaded_bytes)),
+            ),
+            Ok(QueryOutput::Delegated { result, gas_info }) => (
+                Ok(result),
+                GasInfo::new(
+                    gas_info.cost,
+                    gas_info
+                        .externally_used
+                        .saturating_add(base_gas_info.externally_used),
+                ),
+            ),
+            Err(QueryError::Contract {
+                error,
+                loaded_bytes,
+            }) => (
+                Ok(SystemResult::Ok(ContractResult::Err(error))),
+                GasInfo::with_externally_used(query_gas(loaded_bytes)),
+            ),
+            Err(QueryError::System(e)) => (Ok(SystemResult::Err(e)), base_gas_info),
+            Err(QueryError::Backend(e)) => (Err(e), base_gas_info),
+        };
+
+        if gas_info.cost.saturating_add(gas_info.externally_used) > gas_limit {
+            (
+                Err(BackendError::OutOfGas {}),
+                GasInfo::with_externally_used(gas_limit),
+            )
+        } else {
+            (result, gas_info)
+        }
+    }
+}
+
+/// The read-only storage of a queried contract over the snapshot of its store fields,
+/// which runs the `query` of the contract for the `WasmQuery::Smart` of other contracts.
+pub struct StateStorage {
+    fields: BTreeMap<FieldKey, Vec<u8>>,
+    iterator_id_counter: u32,
+    iterators: HashMap<u32, IteratorState>,
+}
+
+impl StateStorage {
+    pub fn new(fields: BTreeMap<FieldKey, Vec<u8>>) -> Self {
+        StateStorage {
+            fields,
+            iterator_id_counter: 0,
+            iterators: HashMap::new(),
+        }
+    }
+}
+
+impl Storage for StateStorage {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
         (
-            Ok(SystemResult::Ok(ContractResult::Ok(Binary::from(vec![])))),
-            GasInfo::with_externally_used(gas_limit),
+            Ok(self.fields.get(&storage_key(key)).cloned()),
+            GasInfo::new(1, 0),
         )
     }
+
+    fn set(&mut self, _key: &[u8], _value: &[u8]) -> BackendResult<()> {
+        (
+            Err(BackendError::Unknown {
+                msg: "The storage of a queried contract is read-only".to_string(),
+            }),
+            GasInfo::new(1, 0),
+        )
+    }
+
+    fn remove(&mut self, _key: &[u8]) -> BackendResult<()> {
+        (
+            Err(BackendError::Unknown {
+                msg: "The storage of a queried contract is read-only".to_string(),
+            }),
+            GasInfo::new(1, 0),
+        )
+    }
+
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let (records, gas_used) = scan_records(self.fields.iter(), start, end, order);
+        let id = self.iterator_id_counter;
+        self.iterator_id_counter += 1;
+        self.iterators.insert(id, (records, 0));
+        (Ok(id), GasInfo::new(gas_used, 0))
+    }
+
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        next_record(&mut self.iterators, iterator_id)
+    }
 }
 
-pub fn build_move_backend<'a, 'b: 'a>(
-    object: &'a mut RuntimeObject,
-    layout_loader: &'b dyn TypeLayoutLoader,
-    resolver: &'b dyn StatelessResolver,
-) -> Backend<MoveBackendApi, MoveStorage<'a>, MoveBackendQuerier> {
+/// Loads the state of the contracts through the `ObjectRuntime`, so the loaded state has the
+/// changes of the current transaction, and writes the changes of the contract stores back.
+/// The load costs of the objects are recorded for the gas of the native call.
+pub struct ObjectRuntimeLoader<'a, 'r> {
+    object_runtime: &'a mut ObjectRuntime<'r>,
+    layout_loader: &'a dyn TypeLayoutLoader,
+    load_costs: Vec<Option<Option<NumBytes>>>,
+}
+
+impl<'a, 'r> ObjectRuntimeLoader<'a, 'r> {
+    pub fn new(
+        object_runtime: &'a mut ObjectRuntime<'r>,
+        layout_loader: &'a dyn TypeLayoutLoader,
+    ) -> Self {
+        ObjectRuntimeLoader {
+            object_runtime,
+            layout_loader,
+            load_costs: vec![],
+        }
+    }
+
+    /// The load costs of the objects loaded so far.
+    pub fn load_costs(&self) -> &[Option<Option<NumBytes>>] {
+        &self.load_costs
+    }
+
+    /// Loads the fields of the contract store object.
+    pub fn load_store_fields(
+        &mut self,
+        store_id: &ObjectID,
+    ) -> PartialVMResult<BTreeMap<FieldKey, Vec<u8>>> {
+        let resolver = self.object_runtime.resolver();
+        let (store, load_cost) = self
+            .object_runtime
+            .load_object(self.layout_loader, store_id)?;
+        self.load_costs.push(load_cost);
+        let (keys, keys_load_cost) = store.list_field_keys(resolver, None, usize::MAX)?;
+        self.load_costs.push(keys_load_cost);
+
+        let value_type = Vec::<u8>::type_tag();
+        let mut fields = BTreeMap::new();
+        for key in keys {
+            let field_key = FieldKey::from(key);
+            // the removed fields are still listed from the state, and do not exist
+            if let Some(bytes) = self.read_object(&store_id.child_id(field_key), &value_type)? {
+                let value = Vec::<u8>::from_bytes(bytes).map_err(deserialize_error)?;
+                fields.insert(field_key, value);
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Writes the pending writes of a successful call back to the contract store object.
+    pub fn write_back(
+        &mut self,
+        store_id: &ObjectID,
+        pending_writes: BTreeMap<FieldKey, Option<Vec<u8>>>,
+    ) -> PartialVMResult<()> {
+        let resolver = self.object_runtime.resolver();
+        let (store, load_cost) = self
+            .object_runtime
+            .load_object(self.layout_loader, store_id)?;
+        self.load_costs.push(load_cost);
+        let move_type = Type::Vector(Box::new(Type::U8));
+        for (field_key, value) in pending_writes {
+            // overwrite the previous value of the key
+            match store.remove_field(self.layout_loader, resolver, field_key, &move_type) {
+                Ok((_, load_cost)) => self.load_costs.push(load_cost),
+                Err(e) if e.major_status() == StatusCode::RESOURCE_DOES_NOT_EXIST => {}
+                Err(e) => return Err(e),
+            }
+            if let Some(value) = value {
+                let (_, load_cost) = store.add_field(
+                    self.layout_loader,
+                    resolver,
+                    field_key,
+                    &move_type,
+                    Value::vector_u8(value),
+                )?;
+                self.load_costs.push(load_cost);
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the contract registered at the address, `None` if it is not registered.
+    pub fn load_contract(
+        &mut self,
+        address: AccountAddress,
+    ) -> PartialVMResult<Option<RegisteredContract>> {
+        let registry_id = contract_registry_id();
+        if !self.object_exists(&registry_id)? {
+            return Ok(None);
+        }
+        let field_id = registry_id.child_id(FieldKey::derive_from_address(&address));
+        let instance_id = match self.read_object(
+            &field_id,
+            &DynamicField::<AccountAddress, ObjectID>::type_tag(),
+        )? {
+            Some(bytes) => {
+                DynamicField::<AccountAddress, ObjectID>::from_bytes(bytes)
+                    .map_err(deserialize_error)?
+                    .value
+            }
+            None => return Ok(None),
+        };
+        let instance_type = TypeTag::Struct(Box::new(instance_struct_tag()));
+        let instance = match self.read_object(&instance_id, &instance_type)? {
+            Some(bytes) => bcs::from_bytes::<InstanceValue>(&bytes).map_err(deserialize_error)?,
+            None => return Ok(None),
+        };
+        let store_fields = self.load_store_fields(&instance.store)?;
+        Ok(Some(RegisteredContract {
+            code_checksum: instance.code_checksum,
+            store_fields,
+        }))
+    }
+
+    /// Loads the balance of the coin type of the account, which is the balance of the account
+    /// `CoinStore` plus the `MultiCoinStore`, with the loaded bytes.
+    pub fn load_balance(
+        &mut self,
+        owner: AccountAddress,
+        coin_type: &StructTag,
+    ) -> PartialVMResult<(U256, usize)> {
+        let mut loaded_bytes = 0;
+        let mut balance = U256::zero();
+        let coin_store_id = AccountCoinStoreModule::account_coin_store_id(owner, coin_type.clone());
+        let coin_store_type = TypeTag::Struct(Box::new(CoinStore::struct_tag_with_coin_type(
+            coin_type.clone(),
+        )));
+        if let Some(bytes) = self.read_object(&coin_store_id, &coin_store_type)? {
+            loaded_bytes += bytes.len();
+            let coin_store = bcs::from_bytes::<CoinStore>(&bytes).map_err(deserialize_error)?;
+            balance += coin_store.balance();
+        }
+
+        let multi_coin_store_id = AccountCoinStoreModule::multi_coin_store_id(owner);
+        if self.object_exists(&multi_coin_store_id)? {
+            let field_key = FieldKey::derive_from_string(coin_type.to_canonical_string().as_str());
+            let field_type = DynamicField::<MoveString, CoinStoreField>::type_tag();
+            if let Some(bytes) =
+                self.read_object(&multi_coin_store_id.child_id(field_key), &field_type)?
+            {
+                loaded_bytes += bytes.len();
+                let field = DynamicField::<MoveString, CoinStoreField>::from_bytes(bytes)
+                    .map_err(deserialize_error)?;
+                balance += field.value.balance();
+            }
+        }
+        Ok((balance, loaded_bytes))
+    }
+
+    /// Loads the keys of the query state.
+    pub fn load_query_state(
+        &mut self,
+        state: &mut QueryState,
+        keys: BTreeSet<QueryStateKey>,
+    ) -> PartialVMResult<()> {
+        for key in keys {
+            match key {
+                QueryStateKey::Contract(address) => {
+                    let contract = self.load_contract(address)?;
+                    state.insert_contract(address, contract);
+                }
+                QueryStateKey::Balance(owner, coin_type) => {
+                    let (balance, loaded_bytes) = self.load_balance(owner, &coin_type)?;
+                    state.insert_balance(owner, coin_type, balance, loaded_bytes);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn object_exists(&mut self, id: &ObjectID) -> PartialVMResult<bool> {
+        let (object, load_cost) = self.object_runtime.load_object(self.layout_loader, id)?;
+        self.load_costs.push(load_cost);
+        object.exists()
+    }
+
+    /// Reads the BCS bytes of the object value, `None` if the object does not exist with the type.
+    fn read_object(
+        &mut self,
+        id: &ObjectID,
+        value_type: &TypeTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        let (object, load_cost) = self.object_runtime.load_object(self.layout_loader, id)?;
+        self.load_costs.push(load_cost);
+        if !object.exists_with_type(value_type)? {
+            return Ok(None);
+        }
+        let value = object
+            .borrow_value(Some(value_type))?
+            .value_as::<Reference>()?
+            .read_ref()?;
+        let layout = self.layout_loader.get_type_layout(value_type)?;
+        value
+            .simple_serialize(&layout)
+            .map(Some)
+            .ok_or_else(|| PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR))
+    }
+}
+
+fn deserialize_error(e: impl std::fmt::Display) -> PartialVMError {
+    PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE).with_message(e.to_string())
+}
+
+pub fn build_move_backend(
+    store_fields: BTreeMap<FieldKey, Vec<u8>>,
+    query_state: Arc<QueryState>,
+    smart_querier: Option<Arc<dyn WasmSmartQuerier + Send + Sync>>,
+) -> Backend<MoveBackendApi, MoveStorage, MoveBackendQuerier> {
     Backend {
         api: MoveBackendApi,
-        storage: MoveStorage::new(object, layout_loader, resolver),
-        querier: MoveBackendQuerier,
+        storage: MoveStorage::new(store_fields),
+        querier: MoveBackendQuerier::new(query_state, smart_querier),
     }
 }
 
@@ -370,10 +944,225 @@ impl Storage for MockStorage {
     }
 }
 
-pub fn build_mock_backend() -> Backend<MoveBackendApi, MockStorage, MoveBackendQuerier> {
-    Backend {
-        api: MoveBackendApi,
-        storage: MockStorage::new(),
-        querier: MoveBackendQuerier,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::to_json_vec;
+
+    /// Builds a contract whose store has the `count` field, returns its address.
+    fn add_contract(state: &mut QueryState, checksum: &[u8], count: &[u8]) -> String {
+        let address = AccountAddress::random();
+        let store_fields = BTreeMap::from([(storage_key(b"count"), count.to_vec())]);
+        state.insert_contract(
+            address,
+            Some(RegisteredContract {
+                code_checksum: checksum.to_vec(),
+                store_fields,
+            }),
+        );
+        KanariAddress::from(address).to_bech32()
+    }
+
+    /// Answers the `{"count":{}}` query with the code checksum and the `count` of the contract.
+    struct CountQuerier;
+
+    impl WasmSmartQuerier for CountQuerier {
+        fn query_smart(
+            &self,
+            _contract_addr: &str,
+            contract: &RegisteredContract,
+            msg: &[u8],
+            _gas_limit: u64,
+        ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+            assert_eq!(msg, br#"{"count":{}}"#);
+            let storage = StateStorage::new(contract.store_fields.clone());
+            let count = storage.get(b"count").0.unwrap().unwrap();
+            let data = [contract.code_checksum.clone(), count].concat();
+            (
+                Ok(SystemResult::Ok(ContractResult::Ok(Binary::from(data)))),
+                GasInfo::with_cost(10),
+            )
+        }
+    }
+
+    fn query(
+        querier: &MoveBackendQuerier,
+        request: QueryRequest<Empty>,
+    ) -> SystemResult<ContractResult<Binary>> {
+        let (result, _) =
+            querier.query_raw(&to_json_vec(&request).unwrap(), DEFAULT_QUERY_GAS_LIMIT);
+        result.unwrap()
+    }
+
+    const DEFAULT_QUERY_GAS_LIMIT: u64 = 1000;
+
+    #[test]
+    fn test_wasm_query_between_contracts() {
+        let mut state = QueryState::default();
+        let contract_a = add_contract(&mut state, &[1u8; 32], b"1");
+        let contract_b = add_contract(&mut state, &[2u8; 32], b"2");
+        let unknown = AccountAddress::random();
+        state.insert_contract(unknown, None);
+        let state = Arc::new(state);
+
+        let querier = MoveBackendQuerier::new(state.clone(), Some(Arc::new(CountQuerier)));
+
+        // contract A queries contract B
+        let request = QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract_b.clone(),
+            msg: Binary::from(br#"{"count":{}}"#.to_vec()),
+        });
+        let (result, gas_info) =
+            querier.query_raw(&to_json_vec(&request).unwrap(), DEFAULT_QUERY_GAS_LIMIT);
+        let expected = [vec![2u8; 32], b"2".to_vec()].concat();
+        assert_eq!(
+            result.unwrap(),
+            SystemResult::Ok(ContractResult::Ok(Binary::from(expected)))
+        );
+        assert_eq!(gas_info.cost, 10);
+        assert_eq!(gas_info.externally_used, GAS_COST_QUERY_BASE);
+
+        let raw = query(
+            &querier,
+            QueryRequest::Wasm(WasmQuery::Raw {
+                contract_addr: contract_a,
+                key: Binary::from(b"count".to_vec()),
+            }),
+        );
+        assert_eq!(
+            raw,
+            SystemResult::Ok(ContractResult::Ok(Binary::from(b"1".to_vec())))
+        );
+
+        let unknown = KanariAddress::from(unknown).to_bech32();
+        let result = query(
+            &querier,
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: unknown.clone(),
+                msg: Binary::from(br#"{"count":{}}"#.to_vec()),
+            }),
+        );
+        assert_eq!(
+            result,
+            SystemResult::Err(SystemError::NoSuchContract { addr: unknown })
+        );
+
+        // the smart queries are not supported without the smart querier
+        let querier = MoveBackendQuerier::new(state, None);
+        let result = query(
+            &querier,
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: contract_b,
+                msg: Binary::from(br#"{"count":{}}"#.to_vec()),
+            }),
+        );
+        assert!(matches!(
+            result,
+            SystemResult::Err(SystemError::UnsupportedRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_query_missing_state() {
+        let owner = AccountAddress::random();
+        let coin_type = StructTag::from_str("0x3::gas_coin::GasCoin").unwrap();
+        let contract = AccountAddress::random();
+        let request = QueryRequest::Bank(BankQuery::Balance {
+            address: KanariAddress::from(owner).to_bech32(),
+            denom: coin_type.to_canonical_string(),
+        });
+
+        // the queries of the state which is not loaded fail, and record the missing keys
+        let mut state = Arc::new(QueryState::default());
+        let querier = MoveBackendQuerier::new(state.clone(), None);
+        let (result, _) =
+            querier.query_raw(&to_json_vec(&request).unwrap(), DEFAULT_QUERY_GAS_LIMIT);
+        assert!(matches!(result, Err(BackendError::Unknown { .. })));
+        let raw_request = QueryRequest::<Empty>::Wasm(WasmQuery::Raw {
+            contract_addr: KanariAddress::from(contract).to_bech32(),
+            key: Binary::from(b"count".to_vec()),
+        });
+        let (result, _) =
+            querier.query_raw(&to_json_vec(&raw_request).unwrap(), DEFAULT_QUERY_GAS_LIMIT);
+        assert!(matches!(result, Err(BackendError::Unknown { .. })));
+        drop(querier);
+
+        let state_mut = Arc::get_mut(&mut state).unwrap();
+        assert_eq!(
+            state_mut.take_missing(),
+            BTreeSet::from([
+                QueryStateKey::Contract(contract),
+                QueryStateKey::Balance(owner, coin_type.clone()),
+            ])
+        );
+        assert!(state_mut.take_missing().is_empty());
+
+        // the queries succeed once the missing keys are loaded
+        state_mut.insert_balance(owner, coin_type.clone(), U256::from(100u64), 32);
+        let querier = MoveBackendQuerier::new(state, None);
+        let result = query(&querier, request);
+        let expected = BalanceResponse::new(Coin::new(100u128, coin_type.to_canonical_string()));
+        assert_eq!(
+            result,
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&expected).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_move_storage_pending_writes() {
+        let fields = BTreeMap::from([
+            (storage_key(b"a"), b"1".to_vec()),
+            (storage_key(b"b"), b"2".to_vec()),
+        ]);
+        let mut storage = MoveStorage::new(fields);
+        storage.set(b"a", b"3").0.unwrap();
+        storage.remove(b"b").0.unwrap();
+        storage.set(b"c", b"4").0.unwrap();
+        assert_eq!(storage.get(b"a").0.unwrap(), Some(b"3".to_vec()));
+        assert_eq!(storage.get(b"b").0.unwrap(), None);
+
+        // the scan merges the pending writes into the snapshot
+        let id = storage.scan(None, None, Order::Ascending).0.unwrap();
+        let mut values = vec![];
+        while let Some((_, value)) = storage.next(id).0.unwrap() {
+            values.push(value);
+        }
+        values.sort();
+        assert_eq!(values, vec![b"3".to_vec(), b"4".to_vec()]);
+
+        assert_eq!(
+            storage.undo_log(),
+            BTreeMap::from([
+                (storage_key(b"a"), Some(b"1".to_vec())),
+                (storage_key(b"b"), Some(b"2".to_vec())),
+                (storage_key(b"c"), None),
+            ])
+        );
+        assert_eq!(
+            storage.into_pending_writes(),
+            BTreeMap::from([
+                (storage_key(b"a"), Some(b"3".to_vec())),
+                (storage_key(b"b"), None),
+                (storage_key(b"c"), Some(b"4".to_vec())),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_addr_api() {
+        let api = MoveBackendApi;
+        let address = KanariAddress(H256::repeat_byte(1));
+        let human = address.to_bech32();
+
+        let (canonical, gas_info) = api.addr_canonicalize(&human);
+        assert_eq!(canonical.unwrap(), address.to_vec());
+        assert_eq!(gas_info.cost, GAS_COST_ADDR_API);
+        assert_eq!(api.addr_humanize(&address.to_vec()).0.unwrap(), human);
+        assert!(api.addr_validate(&human).0.is_ok());
+
+        assert!(api.addr_validate(&human.to_uppercase()).0.is_err());
+        assert!(api.addr_validate(&address.to_hex_literal()).0.is_err());
+        assert!(api.addr_canonicalize("kanari1invalid").0.is_err());
+        assert!(api.addr_humanize(&[1u8; 20]).0.is_err());
     }
 }
@@ -3,8 +3,10 @@
 
 use super::messages::{
     ConvertL2TransactionData, DryRunTransactionMessage, DryRunTransactionResult,
-    ExecuteTransactionMessage, ExecuteTransactionResult, GetRootMessage, SaveStateChangeSetMessage,
-    ValidateL1BlockMessage, ValidateL1TxMessage, ValidateL2TxMessage,
+    ExecuteTransactionMessage, ExecuteTransactionResult, ExecuteTransactionsInParallelMessage,
+    ExecuteTransactionsInParallelResult, GetRootMessage, SaveStateChangeSetMessage,
+    TraceTransactionMessage, TraceTransactionResult, ValidateL1BlockMessage, ValidateL1TxMessage,
+    ValidateL2TxMessage,
 };
 use crate::metrics::ExecutorMetrics;
 use anyhow::Result;
@@ -18,16 +20,21 @@ use moveos::vm::vm_status_explainer::explain_vm_status;
 use moveos_eventbus::bus::EventData;
 use moveos_store::MoveOSStore;
 use moveos_types::function_return_value::FunctionResult;
+use moveos_types::h256::H256;
 use moveos_types::module_binding::MoveFunctionCaller;
 use moveos_types::move_std::option::MoveOption;
+use moveos_types::moveos_std::module_store::ModuleStore;
 use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::moveos_std::tx_context::TxContext;
 use moveos_types::moveos_std::tx_meta::TxMeta;
 use moveos_types::state::{ObjectState, StateChangeSetExt};
 use moveos_types::state_resolver::RootObjectResolver;
-use moveos_types::transaction::{FunctionCall, MoveOSTransaction, VerifiedMoveAction};
+use moveos_types::transaction::{
+    FunctionCall, MoveOSTransaction, RawTransactionOutput, VerifiedMoveAction,
+};
 use moveos_types::transaction::{MoveAction, VerifiedMoveOSTransaction};
 use prometheus::Registry;
+use rayon::prelude::*;
 use kanari_genesis::FrameworksGasParameters;
 use kanari_notify::actor::NotifyActor;
 use kanari_notify::event::GasUpgradeEvent;
@@ -48,8 +55,9 @@ use kanari_types::multichain_id::KanariMultiChainID;
 use kanari_types::transaction::authenticator::AUTH_PAYLOAD_SIZE;
 use kanari_types::transaction::{
     AuthenticatorInfo, L1Block, L1BlockWithBody, L1Transaction, KanariTransaction,
-    KanariTransactionData,
+    KanariTransactionData, LedgerTransaction, LedgerTxData,
 };
+use std::collections::BTreeSet;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -119,6 +127,10 @@ impl ExecutorActor {
         &self.moveos
     }
 
+    pub fn metrics(&self) -> &ExecutorMetrics {
+        &self.metrics
+    }
+
     #[named]
     pub fn execute(&mut self, tx: VerifiedMoveOSTransaction) -> Result<ExecuteTransactionResult> {
         let fn_name = function_name!();
@@ -130,15 +142,160 @@ impl ExecutorActor {
         let tx_hash = tx.ctx.tx_hash();
         let size = tx.ctx.tx_size;
         let (raw_output, _) = self.moveos.execute_only(tx)?;
-        let is_gas_upgrade = raw_output.is_gas_upgrade;
+        self.metrics
+            .executor_execute_tx_bytes
+            .with_label_values(&[fn_name])
+            .observe(size as f64);
+        self.commit_tx_output(tx_hash, raw_output)
+    }
 
-        let (output, execution_info) = self.moveos_store.handle_tx_output(tx_hash, raw_output)?;
+    /// Execute the transactions in parallel, the results are identical to executing them one by one.
+    /// The batch is split into segments at the module publishing transactions, which are executed
+    /// serially because publishing modules changes the loader cache shared by all the transactions.
+    /// The results of the committed transactions are pushed to `results` in order, so the caller
+    /// can still process them if a transaction in the middle of the batch fails.
+    #[named]
+    pub fn execute_in_parallel(
+        &mut self,
+        txs: Vec<VerifiedMoveOSTransaction>,
+        results: &mut Vec<ExecuteTransactionResult>,
+    ) -> Result<()> {
+        let fn_name = function_name!();
+        let _timer = self
+            .metrics
+            .executor_execute_tx_latency_seconds
+            .with_label_values(&[fn_name])
+            .start_timer();
+        let mut segment = Vec::new();
+        for mut tx in txs {
+            if matches!(tx.action, VerifiedMoveAction::ModuleBundle { .. }) {
+                self.execute_segment_in_parallel(std::mem::take(&mut segment), fn_name, results)?;
+                tx.root = self.root.clone();
+                results.push(self.execute_and_commit(tx, fn_name)?);
+            } else {
+                segment.push(tx);
+            }
+        }
+        self.execute_segment_in_parallel(segment, fn_name, results)
+    }
 
-        self.root = execution_info.root_metadata();
+    /// All the transactions are executed on the current root optimistically, then the outputs are
+    /// committed in the given order. A transaction is executed again on the latest root if it read
+    /// the objects changed by the transactions committed before it in the segment.
+    fn execute_segment_in_parallel(
+        &mut self,
+        txs: Vec<VerifiedMoveOSTransaction>,
+        fn_name: &str,
+        results: &mut Vec<ExecuteTransactionResult>,
+    ) -> Result<()> {
+        if txs.is_empty() {
+            return Ok(());
+        }
+        let base_root = self.root.clone();
+        let moveos = &self.moveos;
+        let speculative_results: Vec<_> = txs
+            .par_iter()
+            .map(|tx| {
+                let mut tx = tx.clone();
+                tx.root = base_root.clone();
+                moveos.execute_and_track_reads(tx)
+            })
+            .collect();
+        self.metrics
+            .executor_parallel_execute_tx_count
+            .inc_by(txs.len() as u64);
+
+        // A function call can publish modules too. Its modules may have been loaded into the
+        // shared loader cache while the other transactions were executing, so none of the
+        // speculative results is trusted and the segment is executed serially on a clean cache.
+        let module_store_id = ModuleStore::object_id();
+        let touched_modules = speculative_results.iter().any(|result| match result {
+            Ok((raw_output, _, read_set)) => {
+                raw_output.is_upgrade
+                    || read_set.objects.contains(&module_store_id)
+                    || raw_output
+                        .changeset
+                        .changed_object_ids()
+                        .contains(&module_store_id)
+            }
+            Err(_) => true,
+        });
+        if touched_modules {
+            self.moveos.flush_module_cache(true)?;
+            self.metrics
+                .executor_parallel_re_execute_tx_count
+                .inc_by(txs.len() as u64);
+            for mut tx in txs {
+                tx.root = self.root.clone();
+                results.push(self.execute_and_commit(tx, fn_name)?);
+            }
+            return Ok(());
+        }
+
+        let mut changed_object_ids = BTreeSet::new();
+        // The gas schedule is not tracked in the read set,
+        // so all the transactions after a gas upgrade are executed again.
+        let mut gas_upgraded = false;
+        for (mut tx, speculative_result) in txs.into_iter().zip(speculative_results) {
+            let tx_hash = tx.ctx.tx_hash();
+            let size = tx.ctx.tx_size;
+            let raw_output = match speculative_result {
+                Ok((mut raw_output, _, read_set))
+                    if !gas_upgraded && !read_set.conflicts_with(&changed_object_ids) =>
+                {
+                    raw_output.changeset.rebase(&base_root, &self.root);
+                    raw_output
+                }
+                _ => {
+                    self.metrics.executor_parallel_re_execute_tx_count.inc();
+                    tx.root = self.root.clone();
+                    let (raw_output, _) = self.moveos.execute_only(tx)?;
+                    raw_output
+                }
+            };
+            if raw_output.is_gas_upgrade {
+                gas_upgraded = true;
+                self.moveos.flush_cost_table();
+            }
+            changed_object_ids.extend(raw_output.changeset.changed_object_ids());
+
+            self.metrics
+                .executor_execute_tx_bytes
+                .with_label_values(&[fn_name])
+                .observe(size as f64);
+            results.push(self.commit_tx_output(tx_hash, raw_output)?);
+        }
+        Ok(())
+    }
+
+    fn execute_and_commit(
+        &mut self,
+        tx: VerifiedMoveOSTransaction,
+        fn_name: &str,
+    ) -> Result<ExecuteTransactionResult> {
+        let tx_hash = tx.ctx.tx_hash();
+        let size = tx.ctx.tx_size;
+        let (raw_output, _) = self.moveos.execute_only(tx)?;
+        if raw_output.is_gas_upgrade {
+            self.moveos.flush_cost_table();
+        }
         self.metrics
             .executor_execute_tx_bytes
             .with_label_values(&[fn_name])
             .observe(size as f64);
+        self.commit_tx_output(tx_hash, raw_output)
+    }
+
+    fn commit_tx_output(
+        &mut self,
+        tx_hash: H256,
+        raw_output: RawTransactionOutput,
+    ) -> Result<ExecuteTransactionResult> {
+        let is_gas_upgrade = raw_output.is_gas_upgrade;
+
+        let (output, execution_info) = self.moveos_store.handle_tx_output(tx_hash, raw_output)?;
+
+        self.root = execution_info.root_metadata();
 
         if is_gas_upgrade {
             if let Some(notify_actor) = self.notify_actor.clone() {
@@ -153,17 +310,30 @@ impl ExecutorActor {
     }
 
     #[named]
-    pub fn dry_run(&mut self, tx: VerifiedMoveOSTransaction) -> Result<DryRunTransactionResult> {
+    pub fn dry_run(
+        &mut self,
+        tx: VerifiedMoveOSTransaction,
+        gas_profile: bool,
+    ) -> Result<DryRunTransactionResult> {
         let fn_name = function_name!();
         let _timer = self
             .metrics
             .executor_execute_tx_latency_seconds
             .with_label_values(&[fn_name])
             .start_timer();
+        if gas_profile {
+            let (raw_output, vm_error_info, gas_log) = self.moveos.execute_with_gas_profile(tx)?;
+            return Ok(DryRunTransactionResult {
+                raw_output,
+                vm_error_info,
+                gas_log: Some(gas_log),
+            });
+        }
         let (raw_output, vm_error_info) = self.moveos.execute_only(tx)?;
         Ok(DryRunTransactionResult {
             raw_output,
             vm_error_info,
+            gas_log: None,
         })
     }
 
@@ -278,8 +448,17 @@ impl ExecutorActor {
         result
     }
 
+    pub fn validate_l2_tx(&self, tx: KanariTransaction) -> Result<VerifiedMoveOSTransaction> {
+        self.validate_l2_tx_with_root(tx, self.root.clone())
+    }
+
+    /// Validate the l2 tx on the given state root, it is used to re-execute the historical transactions.
     #[named]
-    pub fn validate_l2_tx(&self, mut tx: KanariTransaction) -> Result<VerifiedMoveOSTransaction> {
+    pub fn validate_l2_tx_with_root(
+        &self,
+        mut tx: KanariTransaction,
+        root: ObjectMeta,
+    ) -> Result<VerifiedMoveOSTransaction> {
         let fn_name = function_name!();
         let _timer = self
             .metrics
@@ -291,9 +470,14 @@ impl ExecutorActor {
         tracing::debug!("executor validate_l2_tx: {:?}, sender: {}", tx_hash, sender);
 
         let authenticator = tx.authenticator_info();
-        let mut moveos_tx: MoveOSTransaction = tx.into_moveos_transaction(self.root.clone());
+        let mut moveos_tx: MoveOSTransaction = tx.into_moveos_transaction(root.clone());
         let tx_size = moveos_tx.ctx.tx_size;
-        let tx_result = self.validate_authenticator(&moveos_tx.ctx, authenticator);
+        let caller = StateRootCaller {
+            moveos: &self.moveos,
+            root: root.clone(),
+        };
+        let tx_result =
+            self.validate_authenticator_with_caller(&caller, &moveos_tx.ctx, authenticator);
         let result = match tx_result {
             Ok(vm_result) => match vm_result {
                 Ok(tx_validate_result) => {
@@ -317,7 +501,7 @@ impl ExecutorActor {
                     }
                 }
                 Err(e) => {
-                    let resolver = RootObjectResolver::new(self.root.clone(), &self.moveos_store);
+                    let resolver = RootObjectResolver::new(root, &self.moveos_store);
                     let status_view = explain_vm_status(&resolver, e.clone())?;
                     tracing::warn!(
                         "transaction validate vm error, tx_hash: {:?}, error:{:?}",
@@ -345,11 +529,20 @@ impl ExecutorActor {
         result
     }
 
-    #[named]
     pub fn validate_authenticator(
         &self,
         ctx: &TxContext,
         authenticator: AuthenticatorInfo,
+    ) -> Result<ValidateAuthenticatorResult> {
+        self.validate_authenticator_with_caller(self, ctx, authenticator)
+    }
+
+    #[named]
+    fn validate_authenticator_with_caller<C: MoveFunctionCaller>(
+        &self,
+        caller: &C,
+        ctx: &TxContext,
+        authenticator: AuthenticatorInfo,
     ) -> Result<ValidateAuthenticatorResult> {
         let fn_name = function_name!();
         let _timer = self
@@ -357,7 +550,7 @@ impl ExecutorActor {
             .executor_validate_tx_latency_seconds
             .with_label_values(&[fn_name])
             .start_timer();
-        let tx_validator = self.as_module_binding::<TransactionValidator>();
+        let tx_validator = caller.as_module_binding::<TransactionValidator>();
         let tx_validate_function_result = tx_validator
             .validate(ctx, authenticator.clone())?
             .into_result();
@@ -367,7 +560,8 @@ impl ExecutorActor {
                 let auth_validator_option = tx_validate_result.auth_validator();
                 match auth_validator_option {
                     Some(auth_validator) => {
-                        let auth_validator_caller = AuthValidatorCaller::new(self, auth_validator);
+                        let auth_validator_caller =
+                            AuthValidatorCaller::new(caller, auth_validator);
                         let auth_validator_function_result = auth_validator_caller
                             .validate(ctx, authenticator.authenticator.payload)?
                             .into_result();
@@ -441,6 +635,52 @@ impl ExecutorActor {
         ))
     }
 
+    /// Re-execute the transaction on the state root before it under the call tracer.
+    /// The transaction is validated again on the state root, as it is in the pipeline.
+    #[named]
+    pub fn trace_transaction(
+        &self,
+        tx: LedgerTransaction,
+        pre_root: ObjectMeta,
+    ) -> Result<TraceTransactionResult> {
+        let fn_name = function_name!();
+        let _timer = self
+            .metrics
+            .executor_execute_tx_latency_seconds
+            .with_label_values(&[fn_name])
+            .start_timer();
+        let LedgerTransaction {
+            data,
+            sequence_info,
+        } = tx;
+        let mut moveos_tx = match data {
+            LedgerTxData::L2Tx(l2_tx) => self.validate_l2_tx_with_root(l2_tx, pre_root)?,
+            LedgerTxData::L1Tx(l1_tx) => {
+                // The l1 tx has been executed on the pre_root, so skip the executed check.
+                let mut moveos_tx = self.validate_l1_tx(l1_tx, true)?;
+                moveos_tx.root = pre_root;
+                moveos_tx
+            }
+            LedgerTxData::L1Block(block) => {
+                return Err(anyhow::anyhow!(
+                    "Can not trace the L1 block transaction of block {}, the block body is not stored",
+                    block.block_height
+                ));
+            }
+        };
+        // The same as the pipeline, the Move contract can get the sequence info from the tx context.
+        moveos_tx.ctx.add(sequence_info)?;
+
+        let (raw_output, vm_error_info, call_trace, read_set) =
+            self.moveos.execute_with_trace(moveos_tx)?;
+        Ok(TraceTransactionResult {
+            raw_output,
+            vm_error_info,
+            call_trace,
+            read_set,
+        })
+    }
+
     pub fn refresh_state(&mut self, root: ObjectMeta, is_upgrade: bool) -> Result<()> {
         self.root = root;
         self.moveos.flush_module_cache(is_upgrade)
@@ -510,6 +750,19 @@ impl Handler<ExecuteTransactionMessage> for ExecutorActor {
     }
 }
 
+#[async_trait]
+impl Handler<ExecuteTransactionsInParallelMessage> for ExecutorActor {
+    async fn handle(
+        &mut self,
+        msg: ExecuteTransactionsInParallelMessage,
+        _ctx: &mut ActorContext,
+    ) -> ExecuteTransactionsInParallelResult {
+        let mut results = Vec::with_capacity(msg.txs.len());
+        let error = self.execute_in_parallel(msg.txs, &mut results).err();
+        ExecuteTransactionsInParallelResult { results, error }
+    }
+}
+
 #[async_trait]
 impl Handler<GetRootMessage> for ExecutorActor {
     async fn handle(
@@ -540,6 +793,20 @@ impl MoveFunctionCaller for ExecutorActor {
     }
 }
 
+/// Call the Move functions on the given state root instead of the latest one.
+struct StateRootCaller<'a> {
+    moveos: &'a MoveOS,
+    root: ObjectMeta,
+}
+
+impl MoveFunctionCaller for StateRootCaller<'_> {
+    fn call_function(&self, ctx: &TxContext, call: FunctionCall) -> Result<FunctionResult> {
+        Ok(self
+            .moveos
+            .execute_readonly_function(self.root.clone(), ctx, call))
+    }
+}
+
 #[async_trait]
 impl Handler<EventData> for ExecutorActor {
     async fn handle(&mut self, message: EventData, _ctx: &mut ActorContext) -> Result<()> {
@@ -579,6 +846,17 @@ impl Handler<DryRunTransactionMessage> for ExecutorActor {
         msg: DryRunTransactionMessage,
         _ctx: &mut ActorContext,
     ) -> Result<DryRunTransactionResult> {
-        self.dry_run(msg.tx)
+        self.dry_run(msg.tx, msg.gas_profile)
+    }
+}
+
+#[async_trait]
+impl Handler<TraceTransactionMessage> for ExecutorActor {
+    async fn handle(
+        &mut self,
+        msg: TraceTransactionMessage,
+        _ctx: &mut ActorContext,
+    ) -> Result<TraceTransactionResult> {
+        self.trace_transaction(msg.tx, msg.pre_root)
     }
 }
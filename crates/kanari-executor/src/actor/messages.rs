@@ -4,12 +4,14 @@
 use anyhow::Result;
 use coerce::actor::message::Message;
 use move_core_types::account_address::AccountAddress;
+use moveos::vm::tracer::CallTrace;
+use moveos_gas_profiling::log::TransactionGasLog;
 use moveos_types::access_path::AccessPath;
 use moveos_types::function_return_value::AnnotatedFunctionResult;
 use moveos_types::h256::H256;
 use moveos_types::moveos_std::event::{AnnotatedEvent, Event, EventID};
 use moveos_types::moveos_std::object::{ObjectID, ObjectMeta};
-use moveos_types::state::{AnnotatedState, FieldKey, ObjectState, StateChangeSetExt};
+use moveos_types::state::{AnnotatedState, FieldKey, ObjectState, ReadSet, StateChangeSetExt};
 use moveos_types::state_resolver::{AnnotatedStateKV, StateKV};
 use moveos_types::transaction::TransactionExecutionInfo;
 use moveos_types::transaction::TransactionOutput;
@@ -17,7 +19,7 @@ use moveos_types::transaction::VerifiedMoveOSTransaction;
 use moveos_types::transaction::{FunctionCall, RawTransactionOutput, VMErrorInfo};
 use kanari_types::address::MultiChainAddress;
 use kanari_types::transaction::{
-    L1BlockWithBody, L1Transaction, KanariTransaction, KanariTransactionData,
+    L1BlockWithBody, L1Transaction, KanariTransaction, KanariTransactionData, LedgerTransaction,
 };
 use serde::{Deserialize, Serialize};
 
@@ -64,15 +66,43 @@ impl Message for ExecuteTransactionMessage {
     type Result = Result<ExecuteTransactionResult>;
 }
 
+/// Execute a batch of transactions in parallel, the results are in the order of the transactions.
+#[derive(Debug)]
+pub struct ExecuteTransactionsInParallelMessage {
+    pub txs: Vec<VerifiedMoveOSTransaction>,
+}
+
+/// The results of the committed transactions, and the error of the transaction that failed, if any.
+/// The transactions after the failed one are not executed.
+#[derive(Debug)]
+pub struct ExecuteTransactionsInParallelResult {
+    pub results: Vec<ExecuteTransactionResult>,
+    pub error: Option<anyhow::Error>,
+}
+
+impl Message for ExecuteTransactionsInParallelMessage {
+    type Result = ExecuteTransactionsInParallelResult;
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExecuteViewFunctionMessage {
     pub call: FunctionCall,
+    pub state_root: Option<H256>,
 }
 
 impl Message for ExecuteViewFunctionMessage {
     type Result = Result<AnnotatedFunctionResult, anyhow::Error>;
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContainsStateRootMessage {
+    pub state_root: H256,
+}
+
+impl Message for ContainsStateRootMessage {
+    type Result = Result<bool>;
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StatesMessage {
     pub state_root: Option<H256>,
@@ -233,6 +263,8 @@ impl Message for ConvertL2TransactionData {
 #[derive(Debug)]
 pub struct DryRunTransactionMessage {
     pub tx: VerifiedMoveOSTransaction,
+    /// If true, the transaction is executed under the gas profiler.
+    pub gas_profile: bool,
 }
 impl Message for DryRunTransactionMessage {
     type Result = Result<DryRunTransactionResult>;
@@ -242,4 +274,25 @@ impl Message for DryRunTransactionMessage {
 pub struct DryRunTransactionResult {
     pub raw_output: RawTransactionOutput,
     pub vm_error_info: Option<VMErrorInfo>,
+    pub gas_log: Option<TransactionGasLog>,
+}
+
+/// Re-execute an executed transaction on the state root before it under the call tracer.
+#[derive(Debug)]
+pub struct TraceTransactionMessage {
+    pub tx: LedgerTransaction,
+    /// The state root after the previous transaction.
+    pub pre_root: ObjectMeta,
+}
+
+impl Message for TraceTransactionMessage {
+    type Result = Result<TraceTransactionResult>;
+}
+
+#[derive(Debug)]
+pub struct TraceTransactionResult {
+    pub raw_output: RawTransactionOutput,
+    pub vm_error_info: Option<VMErrorInfo>,
+    pub call_trace: CallTrace,
+    pub read_set: ReadSet,
 }
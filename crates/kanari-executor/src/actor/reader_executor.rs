@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::messages::{
-    AnnotatedStatesMessage, CheckStateChangeSetsMessage, ExecuteViewFunctionMessage,
-    GetAnnotatedEventsByEventHandleMessage, GetAnnotatedEventsByEventIDsMessage,
-    GetEventsByEventHandleMessage, GetStateChangeSetsMessage, RefreshStateMessage, StatesMessage,
+    AnnotatedStatesMessage, CheckStateChangeSetsMessage, ContainsStateRootMessage,
+    ExecuteViewFunctionMessage, GetAnnotatedEventsByEventHandleMessage,
+    GetAnnotatedEventsByEventIDsMessage, GetEventsByEventHandleMessage, GetStateChangeSetsMessage,
+    RefreshStateMessage, StatesMessage,
 };
 use crate::actor::messages::{
     GetEventsByEventIDsMessage, GetTxExecutionInfosByHashMessage, ListAnnotatedStatesMessage,
@@ -113,10 +114,12 @@ impl Handler<ExecuteViewFunctionMessage> for ReaderExecutorActor {
         msg: ExecuteViewFunctionMessage,
         _ctx: &mut ActorContext,
     ) -> Result<AnnotatedFunctionResult, anyhow::Error> {
-        let resolver = RootObjectResolver::new(self.root.clone(), &self.moveos_store);
-        let function_result = self
-            .moveos()
-            .execute_view_function(self.root.clone(), msg.call);
+        let root = match msg.state_root {
+            Some(state_root) => ObjectMeta::root_metadata(state_root, 0),
+            None => self.root.clone(),
+        };
+        let resolver = RootObjectResolver::new(root.clone(), &self.moveos_store);
+        let function_result = self.moveos().execute_view_function(root, msg.call);
 
         Ok(AnnotatedFunctionResult {
             vm_status: function_result.vm_status,
@@ -139,6 +142,17 @@ impl Handler<ExecuteViewFunctionMessage> for ReaderExecutorActor {
     }
 }
 
+#[async_trait]
+impl Handler<ContainsStateRootMessage> for ReaderExecutorActor {
+    async fn handle(
+        &mut self,
+        msg: ContainsStateRootMessage,
+        _ctx: &mut ActorContext,
+    ) -> Result<bool> {
+        self.moveos_store.contains_state_root(msg.state_root)
+    }
+}
+
 #[async_trait]
 impl Handler<StatesMessage> for ReaderExecutorActor {
     async fn handle(
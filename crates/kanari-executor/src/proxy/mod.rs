@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::actor::messages::{
-    CheckStateChangeSetsMessage, ConvertL2TransactionData, DryRunTransactionResult,
-    GetAnnotatedEventsByEventIDsMessage, GetEventsByEventHandleMessage, GetEventsByEventIDsMessage,
-    GetStateChangeSetsMessage, GetTxExecutionInfosByHashMessage, ListAnnotatedStatesMessage,
-    ListStatesMessage, RefreshStateMessage, SaveStateChangeSetMessage, ValidateL1BlockMessage,
-    ValidateL1TxMessage,
+    CheckStateChangeSetsMessage, ContainsStateRootMessage, ConvertL2TransactionData,
+    DryRunTransactionResult, GetAnnotatedEventsByEventIDsMessage, GetEventsByEventHandleMessage,
+    GetEventsByEventIDsMessage, GetStateChangeSetsMessage, GetTxExecutionInfosByHashMessage,
+    ListAnnotatedStatesMessage, ListStatesMessage, RefreshStateMessage, SaveStateChangeSetMessage,
+    TraceTransactionResult, ValidateL1BlockMessage, ValidateL1TxMessage,
 };
 use crate::actor::reader_executor::ReaderExecutorActor;
 use crate::actor::{
@@ -39,7 +39,7 @@ use moveos_types::{
 use kanari_types::bitcoin::network::BitcoinNetwork;
 use kanari_types::framework::chain_id::ChainID;
 use kanari_types::transaction::{
-    L1BlockWithBody, L1Transaction, KanariTransaction, KanariTransactionData,
+    L1BlockWithBody, L1Transaction, KanariTransaction, KanariTransactionData, LedgerTransaction,
 };
 use tokio::runtime::Handle;
 
@@ -105,23 +105,63 @@ impl ExecutorProxy {
         Ok((result.output, result.transaction_info))
     }
 
+    /// Execute the transactions in parallel, the results are identical to executing them in order.
+    /// If a transaction fails, the results of the transactions before it are returned with the error.
+    pub async fn execute_transactions_in_parallel(
+        &self,
+        txs: Vec<VerifiedMoveOSTransaction>,
+    ) -> Result<(
+        Vec<(TransactionOutput, TransactionExecutionInfo)>,
+        Option<anyhow::Error>,
+    )> {
+        let result = self
+            .actor
+            .send(crate::actor::messages::ExecuteTransactionsInParallelMessage { txs })
+            .await?;
+        let results = result
+            .results
+            .into_iter()
+            .map(|result| (result.output, result.transaction_info))
+            .collect();
+        Ok((results, result.error))
+    }
+
     pub async fn dry_run_transaction(
         &self,
         tx: VerifiedMoveOSTransaction,
+        gas_profile: bool,
     ) -> Result<DryRunTransactionResult> {
         let result = self
             .actor
-            .send(crate::actor::messages::DryRunTransactionMessage { tx })
+            .send(crate::actor::messages::DryRunTransactionMessage { tx, gas_profile })
             .await??;
         Ok(result)
     }
 
+    /// Re-execute the transaction on the state root before it, and trace the execution.
+    pub async fn trace_transaction(
+        &self,
+        tx: LedgerTransaction,
+        pre_root: ObjectMeta,
+    ) -> Result<TraceTransactionResult> {
+        self.actor
+            .send(crate::actor::messages::TraceTransactionMessage { tx, pre_root })
+            .await?
+    }
+
     pub async fn execute_view_function(
         &self,
         call: FunctionCall,
+        state_root: Option<H256>,
     ) -> Result<AnnotatedFunctionResult> {
         self.reader_actor
-            .send(ExecuteViewFunctionMessage { call })
+            .send(ExecuteViewFunctionMessage { call, state_root })
+            .await?
+    }
+
+    pub async fn contains_state_root(&self, state_root: H256) -> Result<bool> {
+        self.reader_actor
+            .send(ContainsStateRootMessage { state_root })
             .await?
     }
 
@@ -339,7 +379,7 @@ impl MoveFunctionCaller for ExecutorProxy {
         let executor = self.clone();
         let function_result = tokio::task::block_in_place(|| {
             Handle::current()
-                .block_on(async move { executor.execute_view_function(function_call).await })
+                .block_on(async move { executor.execute_view_function(function_call, None).await })
         })?;
         function_result.try_into()
     }
@@ -290,6 +290,23 @@ impl RustBindingTest {
         Ok(result)
     }
 
+    pub fn execute_verified_txs_in_parallel(
+        &mut self,
+        txs: Vec<VerifiedMoveOSTransaction>,
+    ) -> Result<Vec<ExecuteTransactionResult>> {
+        let mut results = Vec::with_capacity(txs.len());
+        self.executor.execute_in_parallel(txs, &mut results)?;
+        if let Some(last) = results.last() {
+            self.root = last.transaction_info.root_metadata();
+        }
+        for result in results.iter() {
+            self.events.extend(result.output.events.clone());
+        }
+        self.reader_executor
+            .refresh_state(self.root.clone(), false)?;
+        Ok(results)
+    }
+
     /// Directly apply a change set to the state and update root
     pub fn apply_changes(&mut self, changes: Vec<ObjectChange>) -> Result<()> {
         let mut change_set = StateChangeSet::new(self.root.state_root(), self.root.size);
@@ -8,6 +8,7 @@ use bitcoin_client::proxy::BitcoinClientProxy;
 use framework_builder::stdlib_version::StdlibVersion;
 use move_core_types::{account_address::AccountAddress, u256::U256, vm_status::KeptVMStatus};
 use moveos_types::{
+    module_binding::MoveFunctionCaller,
     move_std::string::MoveString,
     moveos_std::{
         event::Event, module_store::ModuleStore, object::ObjectMeta,
@@ -26,7 +27,7 @@ use kanari_types::{
         inscription_updater::{
             InscriptionCreatedEvent, InscriptionTransferredEvent, InscriptionUpdaterEvent,
         },
-        ord::{Inscription, InscriptionID, SatPoint},
+        ord::{Inscription, InscriptionID, OrdModule, SatPoint},
         utxo::{self, BitcoinUTXOStore, UTXO},
     },
     genesis_config,
@@ -426,6 +427,36 @@ impl BitcoinBlockTester {
                 object_id
             );
         }
+
+        // Verify the delegate recorded in Move matches the delegate in the reveal envelope
+        let ord_module = self.binding_test.as_module_binding::<OrdModule>();
+        for tx in block_data.block.txdata.iter() {
+            let txid = tx.compute_txid().into_address();
+            let inscription_ids = block_data
+                .expect_inscriptions
+                .iter()
+                .filter(|inscription_id| inscription_id.txid == txid)
+                .collect::<Vec<_>>();
+            if inscription_ids.is_empty() {
+                continue;
+            }
+            let (_, delegates) =
+                ord_module.parse_inscription_from_tx_with_delegates(&tx.clone().into())?;
+            for inscription_id in inscription_ids {
+                let expect_delegate = delegates
+                    .get(inscription_id.index as usize)
+                    .copied()
+                    .flatten();
+                let delegate = ord_module.delegate(inscription_id)?;
+                ensure!(
+                    delegate == expect_delegate,
+                    "Inscription {} delegate not match: expect {:?}, move: {:?}",
+                    inscription_id,
+                    expect_delegate,
+                    delegate
+                );
+            }
+        }
         Ok(())
     }
 
@@ -765,7 +796,10 @@ impl TesterGenesisBuilder {
                 let object_id = utxo.object_id();
                 debug!("Add utxo: {}, {:?}", object_id, utxo);
                 let mut object_meta = ObjectMeta::genesis_meta(object_id, UTXO::type_tag());
-                object_meta.owner = kanari_pre_output.recipient_address.to_kanari_address().into();
+                object_meta.owner = kanari_pre_output
+                    .recipient_address
+                    .to_kanari_address()
+                    .into();
                 let utxo_obj = ObjectState::new_with_struct(object_meta, utxo)?;
 
                 self.utxo_store_change
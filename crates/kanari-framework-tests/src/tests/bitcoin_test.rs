@@ -6,16 +6,22 @@ use crate::tests::bitcoin_data::load_block;
 use bitcoin::consensus::deserialize;
 use bitcoin::hashes::Hash;
 use bitcoin::{Block, OutPoint, Transaction, TxOut};
+use framework_builder::stdlib_version::StdlibVersion;
 use hex::FromHex;
 use moveos_types::access_path::AccessPath;
 use moveos_types::module_binding::MoveFunctionCaller;
+use moveos_types::moveos_std::module_store::ModuleStore;
+use moveos_types::moveos_std::timestamp::Timestamp;
+use moveos_types::state::{MoveStructType, ObjectState};
 use moveos_types::state_resolver::StateReader;
 use kanari_types::bitcoin::network::Network;
 use kanari_types::bitcoin::ord;
 use kanari_types::bitcoin::ord::{Inscription, InscriptionID};
 use kanari_types::bitcoin::types::{self, Header};
 use kanari_types::bitcoin::utxo::{self, UTXO};
+use kanari_types::genesis_config;
 use kanari_types::into_address::IntoAddress;
+use kanari_types::kanari_network::{BuiltinChainID, KanariNetwork};
 use kanari_types::multichain_id::KanariMultiChainID;
 use kanari_types::transaction::L1BlockWithBody;
 use std::collections::HashMap;
@@ -210,3 +216,217 @@ async fn test_real_bocks() {
         test_block_process(blocks);
     }
 }
+
+// The mainnet blocks 1, 2 and 3, each block only contains the coinbase tx.
+const MAINNET_BLOCKS: [&str; 3] = [
+    "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e362990101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d0104ffffffff0100f2052a0100000043410496b538e853519c726a2c91e61ec11600ae1390813a627c66fb8be7947be63c52da7589379515d4e0a604f8141781e62294721166bf621e73a82cbf2342c858eeac00000000",
+    "010000004860eb18bf1b1620e37e9490fc8a427514416fd75159ab86688e9a8300000000d5fdcc541e25de1c7a5addedf24858b8bb665c9f36ef744ee42c316022c90f9bb0bc6649ffff001d08d2bd610101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d010bffffffff0100f2052a010000004341047211a824f55b505228e4c3d5194c1fcfaa15a456abdf37f9b9d97a4040afc073dee6c89064984f03385237d92167c13e236446b417ab79a0fcae412ae3316b77ac00000000",
+    "01000000bddd99ccfda39da1b108ce1a5d70038d0a967bacb68b6b63065f626a0000000044f672226090d85db9a9f2fbfe5f0f9609b387af7be5b7fbb7a1767c831c9e995dbe6649ffff001d05e0ed6d0101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d010effffffff0100f2052a0100000043410494b9d3e76c5b1629ecf97fff95d7a4bbdac87cc26099ada28066c6ff1eb9191223cd897194a08d0c2726c5747f1db49e8cf90e75dc3e3550ae9b30086f3cd5aaac00000000",
+];
+
+/// Create a binding test with the mainnet Bitcoin network, start from the Bitcoin genesis block.
+/// The Bitcoin mainnet genesis with the given chain, the header validation feature is only enabled
+/// at genesis on the local and dev chains.
+fn new_mainnet_binding_test(
+    chain_id: BuiltinChainID,
+    genesis_block: &Block,
+) -> binding_test::RustBindingTest {
+    let timestamp_milliseconds = (genesis_block.header.time as u64) * 1000;
+    let mut genesis_config = genesis_config::G_MAIN_CONFIG.clone();
+    genesis_config.bitcoin_block_hash = genesis_block.block_hash();
+    genesis_config.bitcoin_block_height = 0;
+    genesis_config.bitcoin_reorg_block_count = 0;
+    genesis_config.timestamp = timestamp_milliseconds;
+    genesis_config.stdlib_version = StdlibVersion::Latest;
+    genesis_config.genesis_objects = vec![
+        (
+            ObjectState::new_timestamp(Timestamp {
+                milliseconds: timestamp_milliseconds,
+            }),
+            Timestamp::type_layout(),
+        ),
+        (
+            ObjectState::genesis_module_store(),
+            ModuleStore::type_layout(),
+        ),
+    ];
+    let network = KanariNetwork::new(chain_id.chain_id(), genesis_config);
+    binding_test::RustBindingTest::new_with_network(network).unwrap()
+}
+
+#[tokio::test]
+async fn test_block_header_validation() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let genesis_block = bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin);
+    let blocks = MAINNET_BLOCKS
+        .iter()
+        .map(|block_hex| deserialize::<Block>(&Vec::<u8>::from_hex(block_hex).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+
+    // Replay the real mainnet headers, the linkage and the proof of work should pass.
+    let mut binding_test = new_mainnet_binding_test(BuiltinChainID::Local, &genesis_block);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(0, genesis_block.clone()))
+        .unwrap();
+    for (idx, block) in blocks.iter().enumerate() {
+        let height = idx as u64 + 1;
+        binding_test
+            .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+    let bitcoin_module = binding_test.as_module_binding::<kanari_types::bitcoin::BitcoinModule>();
+    let latest_block = bitcoin_module.get_latest_block().unwrap().unwrap();
+    assert_eq!(latest_block.block_height, 3);
+    assert_eq!(
+        bitcoin_module.get_block_by_height(3).unwrap().unwrap(),
+        Header::from(blocks[2].header)
+    );
+
+    // The block 2 is skipped, the block 3 does not link to the block 1.
+    let mut binding_test = new_mainnet_binding_test(BuiltinChainID::Local, &genesis_block);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(0, genesis_block.clone()))
+        .unwrap();
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(1, blocks[0].clone()))
+        .unwrap();
+    assert!(binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(2, blocks[2].clone()))
+        .is_err());
+
+    // The nonce is changed, the block hash does not satisfy the target.
+    let mut binding_test = new_mainnet_binding_test(BuiltinChainID::Local, &genesis_block);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(0, genesis_block.clone()))
+        .unwrap();
+    let mut invalid_pow_block = blocks[0].clone();
+    invalid_pow_block.header.nonce += 1;
+    assert!(binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            1,
+            invalid_pow_block.clone()
+        ))
+        .is_err());
+
+    // The header validation feature is not enabled on the main chain yet, the block is accepted.
+    let mut binding_test = new_mainnet_binding_test(BuiltinChainID::Main, &genesis_block);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(0, genesis_block.clone()))
+        .unwrap();
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(1, invalid_pow_block))
+        .unwrap();
+}
+
+fn mine_header(header: &mut bitcoin::block::Header) {
+    header.nonce = 0;
+    while header.validate_pow(header.target()).is_err() {
+        header.nonce += 1;
+    }
+}
+
+#[tokio::test]
+async fn test_block_header_median_time_past() {
+    let _ = tracing_subscriber::fmt::try_init();
+    // The block 12 is the first block with 11 previous blocks besides the genesis block.
+    let mut chain = bitcoin_mock_rpc::MockChain::new();
+    chain.mine_blocks(12);
+    let tip_height = chain.tip_height();
+    let prev_hash = chain.block_hash(tip_height - 1).unwrap();
+    let median_time_past = chain.median_time(&prev_hash).unwrap();
+
+    let new_binding_test = || {
+        let network = KanariNetwork::new(
+            BuiltinChainID::Local.chain_id(),
+            genesis_config::G_LOCAL_CONFIG.clone(),
+        );
+        let mut binding_test = binding_test::RustBindingTest::new_with_network(network).unwrap();
+        for height in 0..tip_height {
+            let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+            binding_test
+                .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+                .unwrap();
+        }
+        binding_test
+    };
+
+    // The regtest blocks mined by the mock chain are valid.
+    let mut binding_test = new_binding_test();
+    let tip_block = chain.block(&chain.block_hash(tip_height).unwrap()).unwrap();
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            tip_height,
+            tip_block.clone(),
+        ))
+        .unwrap();
+
+    // The block time must be greater than the median time past.
+    let mut binding_test = new_binding_test();
+    let mut invalid_time_block = tip_block.clone();
+    invalid_time_block.header.time = median_time_past;
+    mine_header(&mut invalid_time_block.header);
+    assert!(binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            tip_height,
+            invalid_time_block
+        ))
+        .is_err());
+
+    let mut binding_test = new_binding_test();
+    let mut valid_time_block = tip_block.clone();
+    valid_time_block.header.time = median_time_past + 1;
+    mine_header(&mut valid_time_block.header);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            tip_height,
+            valid_time_block,
+        ))
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_pending_block_reorg() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut chain = bitcoin_mock_rpc::MockChain::new();
+    let old_blocks = chain.mine_blocks(3);
+
+    let mut genesis_config = genesis_config::G_LOCAL_CONFIG.clone();
+    genesis_config.bitcoin_reorg_block_count = 2;
+    let network = KanariNetwork::new(BuiltinChainID::Local.chain_id(), genesis_config);
+    let mut binding_test = binding_test::RustBindingTest::new_with_network(network).unwrap();
+    for height in 0..=chain.tip_height() {
+        let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+        binding_test
+            .execute_l1_block(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+    let pending_block_module = binding_test
+        .as_module_binding::<kanari_types::bitcoin::pending_block::PendingBlockModule>();
+    let best_block = pending_block_module.get_best_block().unwrap().unwrap();
+    assert_eq!(best_block.block_height, 3);
+    assert_eq!(best_block.block_hash, old_blocks[2].into_address());
+    let ready_txs = pending_block_module
+        .get_ready_pending_txs()
+        .unwrap()
+        .unwrap();
+    assert_eq!(ready_txs.block_hash, old_blocks[0].into_address());
+
+    // Replace the blocks 2 and 3 with a longer fork, the pending blocks are not processed, so the reorg succeeds.
+    let new_blocks = chain.reorg(2, 3).unwrap();
+    for (height, block_hash) in (2..).zip(new_blocks.iter()) {
+        let block = chain.block(block_hash).unwrap();
+        binding_test
+            .execute_l1_block(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+    let pending_block_module = binding_test
+        .as_module_binding::<kanari_types::bitcoin::pending_block::PendingBlockModule>();
+    let best_block = pending_block_module.get_best_block().unwrap().unwrap();
+    assert_eq!(best_block.block_height, 4);
+    assert_eq!(best_block.block_hash, new_blocks[2].into_address());
+    let ready_txs = pending_block_module
+        .get_ready_pending_txs()
+        .unwrap()
+        .unwrap();
+    assert_eq!(ready_txs.block_hash, new_blocks[0].into_address());
+}
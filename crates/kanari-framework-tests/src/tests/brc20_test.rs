@@ -1,8 +1,22 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::binding_test;
+use bitcoin::absolute::LockTime;
 use bitcoin::consensus::Decodable;
+use bitcoin::opcodes::all::OP_CHECKSIG;
+use bitcoin::transaction::Version;
+use bitcoin::{Address, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+use bitcoin_mock_rpc::MockChain;
 use hex::FromHex;
+use move_core_types::u256::U256;
+use moveos_types::module_binding::MoveFunctionCaller;
+use kanari_types::address::BitcoinAddress;
+use kanari_types::bitcoin::brc20::BRC20Module;
+use kanari_types::bitcoin::ord::{InscriptionID, InscriptionRecord};
+use kanari_types::transaction::L1BlockWithBody;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 use tracing::debug;
 
 fn decode_tx(btx_tx_hex: &str) {
@@ -68,3 +82,256 @@ fn test_from_transaction() {
     let btc_tx_hex = "02000000000102bb5b2ae5dbf13f9b1ab69c74c44ca1e5063a5c3fe36348c5d1b57b5c054154880000000000ffffffff8eca9f7d2e369e650f439153f503e81dd9960f1030bfb54f9043884a4c63c8bc11000000171600141c6e0ecb1a039c8df94a664ddf130f6e3be90ba5ffffffff0210270000000000001976a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac073d0f000000000017a9142b5fd9fed263646d296cb196bc07747b4c41fdc787014091e2afbe0bf24467275bf90b2fa281e105c6cd0344cd1f0846a89a5369246634d12476f579271a7cadbf918f73de306486f70cc2368274740ea7779a9de4e6c402473044022024e761eeaf29864b4b9bef52f457d2c0302fa5c6b4003c67681cdb006d118404022023aef89d49e6700bca374d2059509cdcacba213c0b8b77a635095c79a63a124f012103b06845003ff20c9e8a1c529003fb32edb1a9d8894e7f2cd37a192c0cbd76fb8f00000000";
     decode_tx(btc_tx_hex);
 }
+
+const ALICE: &str = "bcrt1qzyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3lgth6c";
+const BOB: &str = "bcrt1qyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zs4w3j0";
+const CAROL: &str = "bcrt1qxvenxvenxvenxvenxvenxvenxvenxvenztev8a";
+
+fn regtest_address(address: &str) -> Address {
+    Address::from_str(address)
+        .unwrap()
+        .require_network(bitcoin::Network::Regtest)
+        .unwrap()
+}
+
+fn inscribe_tx(funding: OutPoint, owner: &str, body: &str) -> Transaction {
+    let mut inscription = InscriptionRecord::default();
+    inscription.set_content_type("text/plain;charset=utf-8".to_string());
+    inscription.body = body.as_bytes().to_vec();
+    let reveal_script = inscription
+        .append_reveal_script_to_builder(
+            ScriptBuf::builder()
+                .push_slice([0x42u8; 32])
+                .push_opcode(OP_CHECKSIG),
+        )
+        .into_script();
+    let mut control_block = vec![0xc1u8];
+    control_block.extend_from_slice(&[0x42u8; 32]);
+    // Kanari does not verify the signature, so a dummy signature is enough
+    let witness = Witness::from_slice(&[vec![0u8; 64], reveal_script.into_bytes(), control_block]);
+    new_tx(funding, witness, owner, 10_000)
+}
+
+fn send_tx(inscription_outpoint: OutPoint, to: &str) -> Transaction {
+    let witness = Witness::from_slice(&[vec![0u8; 71], vec![0x02u8; 33]]);
+    new_tx(inscription_outpoint, witness, to, 9_000)
+}
+
+fn new_tx(previous_output: OutPoint, witness: Witness, to: &str, value: u64) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness,
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: regtest_address(to).script_pubkey(),
+        }],
+    }
+}
+
+/// Execute the blocks from `start_height` to the tip, the BRC20 inscriptions are indexed when the blocks are executed
+fn execute_blocks(
+    binding_test: &mut binding_test::RustBindingTest,
+    chain: &MockChain,
+    start_height: u64,
+) -> u64 {
+    for height in start_height..=chain.tip_height() {
+        let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+        binding_test
+            .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+    chain.tip_height() + 1
+}
+
+fn to_kanari_address(address: &str) -> move_core_types::account_address::AccountAddress {
+    BitcoinAddress::from(regtest_address(address))
+        .to_kanari_address()
+        .into()
+}
+
+#[tokio::test]
+async fn test_brc20_transfer_inscriptions() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+    binding_test
+        .get_kari(binding_test.sequencer, U256::from(100_000_000_000u64))
+        .unwrap();
+
+    let mut chain = MockChain::new();
+    let mut fundings = chain
+        .mine_blocks(16)
+        .into_iter()
+        .map(|block_hash| {
+            let coinbase = &chain.block(&block_hash).unwrap().txdata[0];
+            OutPoint::new(coinbase.compute_txid(), 0)
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    let mut next_height = execute_blocks(&mut binding_test, &chain, 0);
+
+    let mut inscribe = |owner: &str, body: &str| inscribe_tx(fundings.next().unwrap(), owner, body);
+
+    chain.mine_block_with_txs(vec![
+        inscribe(
+            ALICE,
+            r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"2000","lim":"1000","dec":"0"}"#,
+        ),
+        inscribe(
+            BOB,
+            r#"{"p":"brc-20","op":"deploy","tick":"pepe","max":"21000000","lim":"1000"}"#,
+        ),
+        // The tick is case insensitive, so the duplicate deploy is ignored
+        inscribe(
+            CAROL,
+            r#"{"p":"brc-20","op":"deploy","tick":"ORDI","max":"100","lim":"100","dec":"0"}"#,
+        ),
+    ]);
+    next_height = execute_blocks(&mut binding_test, &chain, next_height);
+
+    chain.mine_block_with_txs(vec![
+        inscribe(
+            ALICE,
+            r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        ),
+        inscribe(
+            BOB,
+            r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        ),
+        // The supply is exhausted
+        inscribe(
+            CAROL,
+            r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        ),
+        inscribe(
+            ALICE,
+            r#"{"p":"brc-20","op":"mint","tick":"pepe","amt":"1000"}"#,
+        ),
+        // The tick is not deployed
+        inscribe(
+            CAROL,
+            r#"{"p":"brc-20","op":"mint","tick":"nope","amt":"1"}"#,
+        ),
+    ]);
+    next_height = execute_blocks(&mut binding_test, &chain, next_height);
+
+    let alice_transfer = inscribe(
+        ALICE,
+        r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"400"}"#,
+    );
+    let bob_transfer = inscribe(
+        BOB,
+        r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"300"}"#,
+    );
+    // The available balance of Alice is not enough after the first transfer inscription
+    let alice_invalid_transfer = inscribe(
+        ALICE,
+        r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"700"}"#,
+    );
+    let carol_invalid_transfer = inscribe(
+        CAROL,
+        r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"50"}"#,
+    );
+    let alice_transfer_txid = alice_transfer.compute_txid();
+    let bob_transfer_txid = bob_transfer.compute_txid();
+    let alice_invalid_transfer_txid = alice_invalid_transfer.compute_txid();
+    chain.mine_block_with_txs(vec![
+        alice_transfer,
+        bob_transfer,
+        alice_invalid_transfer,
+        carol_invalid_transfer,
+    ]);
+    next_height = execute_blocks(&mut binding_test, &chain, next_height);
+
+    let brc20_module = binding_test.as_module_binding::<BRC20Module>();
+    let alice_balances = brc20_module.get_balances(to_kanari_address(ALICE)).unwrap();
+    let alice_ordi = alice_balances
+        .iter()
+        .find(|balance| balance.tick.as_str() == "ordi")
+        .unwrap();
+    assert_eq!(alice_ordi.available, U256::from(600u64));
+    assert_eq!(alice_ordi.transferable, U256::from(400u64));
+    assert_eq!(alice_ordi.transfer_inscriptions.len(), 1);
+    assert_eq!(
+        alice_ordi.transfer_inscriptions[0].inscription_id,
+        InscriptionID::new(alice_transfer_txid, 0)
+    );
+
+    // Send the transfer inscription of Alice and the invalid one to Carol, Bob keeps the transfer inscription
+    chain.mine_block_with_txs(vec![
+        send_tx(OutPoint::new(alice_transfer_txid, 0), CAROL),
+        send_tx(OutPoint::new(alice_invalid_transfer_txid, 0), CAROL),
+    ]);
+    next_height = execute_blocks(&mut binding_test, &chain, next_height);
+
+    let brc20_module = binding_test.as_module_binding::<BRC20Module>();
+    let bob_balances = brc20_module.get_balances(to_kanari_address(BOB)).unwrap();
+    assert_eq!(bob_balances.len(), 1);
+    assert_eq!(
+        bob_balances[0].transfer_inscriptions[0].inscription_id,
+        InscriptionID::new(bob_transfer_txid, 0)
+    );
+    let ordi_info = brc20_module
+        .get_tick_info("ordi".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(ordi_info.supply, U256::from(2000u64));
+    assert!(brc20_module
+        .get_tick_info("nope".to_string())
+        .unwrap()
+        .is_none());
+
+    // Bob sends the transfer inscription to Carol, and Carol sends it to Alice in the same block,
+    // the amount goes to Carol who is the receiver of the first transfer
+    let bob_send = send_tx(OutPoint::new(bob_transfer_txid, 0), CAROL);
+    let carol_send = send_tx(OutPoint::new(bob_send.compute_txid(), 0), ALICE);
+    chain.mine_block_with_txs(vec![bob_send, carol_send]);
+    execute_blocks(&mut binding_test, &chain, next_height);
+
+    // The amounts are integers scaled by `10^dec`
+    let pepe_amount = U256::from_str("1000000000000000000000").unwrap();
+    let expected_balances = BTreeMap::from([
+        (
+            ALICE,
+            BTreeMap::from([
+                ("ordi".to_string(), (U256::from(600u64), U256::from(0u64))),
+                ("pepe".to_string(), (pepe_amount, U256::from(0u64))),
+            ]),
+        ),
+        (
+            BOB,
+            BTreeMap::from([("ordi".to_string(), (U256::from(700u64), U256::from(0u64)))]),
+        ),
+        (
+            CAROL,
+            BTreeMap::from([("ordi".to_string(), (U256::from(700u64), U256::from(0u64)))]),
+        ),
+    ]);
+    let brc20_module = binding_test.as_module_binding::<BRC20Module>();
+    for (address, expected) in expected_balances {
+        let balances = brc20_module
+            .get_balances(to_kanari_address(address))
+            .unwrap()
+            .into_iter()
+            .map(|balance| {
+                assert_eq!(
+                    balance.transferable,
+                    balance
+                        .transfer_inscriptions
+                        .iter()
+                        .fold(U256::zero(), |acc, inscription| acc + inscription.amt)
+                );
+                (
+                    balance.tick.to_string(),
+                    (balance.available, balance.transferable),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(balances, expected, "balances of {} mismatch", address);
+    }
+}
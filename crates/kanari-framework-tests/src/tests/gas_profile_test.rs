@@ -0,0 +1,60 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::binding_test;
+use move_core_types::u256::U256;
+use moveos_types::state::MoveStructType;
+use kanari_key::keystore::account_keystore::AccountKeystore;
+use kanari_key::keystore::memory_keystore::InMemKeystore;
+use kanari_types::framework::kari::KARI;
+use kanari_types::framework::transfer::TransferModule;
+use kanari_types::transaction::kanari::KanariTransactionData;
+
+#[tokio::test]
+async fn test_dry_run_with_gas_profile() {
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+
+    let keystore = InMemKeystore::new_insecure_for_tests(1);
+    let sender = keystore.addresses()[0];
+    binding_test
+        .get_kari(sender.into(), U256::from(100_000_000_000u64))
+        .unwrap();
+
+    let action = TransferModule::create_transfer_coin_action(
+        KARI::struct_tag(),
+        sender.into(),
+        U256::from(1000u64),
+    );
+    let tx_data = KanariTransactionData::new_for_test(sender, 0, action);
+    let tx = binding_test
+        .executor
+        .convert_to_verified_tx_for_dry_run(tx_data)
+        .unwrap();
+
+    let (raw_output, _) = binding_test
+        .executor
+        .moveos()
+        .execute_only(tx.clone())
+        .unwrap();
+    let (profiled_output, _, gas_log) = binding_test
+        .executor
+        .moveos()
+        .execute_with_gas_profile(tx)
+        .unwrap();
+    // The gas profiler should not change the execution result.
+    assert_eq!(raw_output.status, profiled_output.status);
+    assert_eq!(raw_output.gas_used, profiled_output.gas_used);
+
+    let aggregated = gas_log.exec_io.aggregate_gas_events();
+    assert!(!aggregated.ops.is_empty());
+    assert!(aggregated
+        .functions
+        .iter()
+        .any(|(name, _, _)| name.contains("transfer")));
+    assert!(u64::from(gas_log.exec_io.total) > 0);
+    assert!(gas_log
+        .exec_io
+        .to_flamegraph("test".to_string())
+        .unwrap()
+        .is_some());
+}
@@ -12,7 +12,11 @@ mod chain_id_test;
 mod check_tx_size;
 mod empty_tests;
 mod ethereum_test;
+mod gas_profile_test;
 mod multisign_account_tests;
 mod ord_test;
+mod parallel_execution_test;
+mod runes_test;
 mod session_validator_tests;
+mod trace_test;
 mod view_function_gas;
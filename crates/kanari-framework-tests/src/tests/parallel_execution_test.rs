@@ -0,0 +1,159 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::binding_test;
+use move_binary_format::file_format::empty_module;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::u256::U256;
+use moveos_types::h256::H256;
+use moveos_types::moveos_std::tx_context::TxContext;
+use moveos_types::state::MoveStructType;
+use moveos_types::transaction::{MoveAction, MoveOSTransaction, VerifiedMoveOSTransaction};
+use kanari_key::keystore::account_keystore::AccountKeystore;
+use kanari_key::keystore::memory_keystore::InMemKeystore;
+use kanari_types::framework::empty::Empty;
+use kanari_types::framework::kari::KARI;
+use kanari_types::framework::oracle::OracleModule;
+use kanari_types::framework::transfer::TransferModule;
+use kanari_types::transaction::kanari::KanariTransactionData;
+
+#[tokio::test]
+async fn test_parallel_execution_is_identical_to_serial() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+
+    let keystore = InMemKeystore::new_insecure_for_tests(8);
+    let senders = keystore.addresses();
+    for sender in senders.iter() {
+        binding_test
+            .get_kari((*sender).into(), U256::from(100_000_000_000u64))
+            .unwrap();
+    }
+    let recipient = senders[0];
+
+    // Half of the transactions transfer to the same recipient and conflict with each other,
+    // the others only touch the accounts of their senders.
+    let txs = senders
+        .iter()
+        .enumerate()
+        .map(|(i, sender)| {
+            let action = if i % 2 == 0 {
+                TransferModule::create_transfer_coin_action(
+                    KARI::struct_tag(),
+                    recipient.into(),
+                    U256::from(1000u64 + i as u64),
+                )
+            } else {
+                MoveAction::new_function_call(Empty::empty_function_id(), vec![], vec![])
+            };
+            let tx_data = KanariTransactionData::new_for_test(*sender, 0, action);
+            let tx = keystore.sign_transaction(sender, tx_data, None).unwrap();
+            binding_test.executor.validate_l2_tx(tx).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    assert_parallel_execution_is_identical_to_serial(&mut binding_test, txs);
+}
+
+#[tokio::test]
+async fn test_parallel_execution_without_conflicts() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+
+    // The L2 transactions all pay gas to the transaction fee pool, so they conflict with each other.
+    // The system call transactions do not pay gas, each of them only creates its own objects.
+    let txs = (0..2u64)
+        .map(|i| {
+            let action = OracleModule::create_oracle_action(
+                format!("oracle{}", i),
+                "https://example.com".to_string(),
+                "parallel".to_string(),
+            );
+            let ctx = TxContext::new_system_call_ctx(H256::from_low_u64_be(i + 1), 1);
+            let tx = MoveOSTransaction::new(binding_test.root().clone(), ctx, action);
+            binding_test.executor.moveos().verify(tx).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let metrics = binding_test.executor.metrics();
+    let re_execute_count = metrics.executor_parallel_re_execute_tx_count.get();
+    let results = binding_test
+        .execute_verified_txs_in_parallel(txs.clone())
+        .unwrap();
+    assert_eq!(results.len(), txs.len());
+    let metrics = binding_test.executor.metrics();
+    assert_eq!(
+        metrics.executor_parallel_re_execute_tx_count.get(),
+        re_execute_count
+    );
+    assert_eq!(
+        metrics.executor_parallel_execute_tx_count.get(),
+        txs.len() as u64
+    );
+}
+
+#[tokio::test]
+async fn test_parallel_execution_with_module_publishing() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+
+    let keystore = InMemKeystore::new_insecure_for_tests(4);
+    let senders = keystore.addresses();
+    for sender in senders.iter() {
+        binding_test
+            .get_kari((*sender).into(), U256::from(100_000_000_000u64))
+            .unwrap();
+    }
+
+    // The module publishing transaction is in the middle of the batch,
+    // the transactions around it are executed in parallel.
+    let txs = senders
+        .iter()
+        .enumerate()
+        .map(|(i, sender)| {
+            let action = if i == 1 {
+                let mut module = empty_module();
+                module.identifiers[0] = Identifier::new("parallel").unwrap();
+                module.address_identifiers[0] = AccountAddress::from(*sender);
+                let mut bytes = vec![];
+                module.serialize(&mut bytes).unwrap();
+                MoveAction::new_module_bundle(vec![bytes])
+            } else {
+                MoveAction::new_function_call(Empty::empty_function_id(), vec![], vec![])
+            };
+            let tx_data = KanariTransactionData::new_for_test(*sender, 0, action);
+            let tx = keystore.sign_transaction(sender, tx_data, None).unwrap();
+            binding_test.executor.validate_l2_tx(tx).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    assert_parallel_execution_is_identical_to_serial(&mut binding_test, txs);
+}
+
+fn assert_parallel_execution_is_identical_to_serial(
+    binding_test: &mut binding_test::RustBindingTest,
+    txs: Vec<VerifiedMoveOSTransaction>,
+) {
+    let base_root = binding_test.root().clone();
+    let parallel_results = binding_test
+        .execute_verified_txs_in_parallel(txs.clone())
+        .unwrap();
+    assert_eq!(parallel_results.len(), txs.len());
+
+    binding_test
+        .executor
+        .refresh_state(base_root.clone(), true)
+        .unwrap();
+    let mut root = base_root;
+    for (mut tx, parallel_result) in txs.into_iter().zip(parallel_results) {
+        tx.root = root;
+        let serial_result = binding_test.executor.execute(tx).unwrap();
+        root = serial_result.transaction_info.root_metadata();
+        assert_eq!(
+            serial_result.transaction_info,
+            parallel_result.transaction_info
+        );
+        assert_eq!(serial_result.output.events, parallel_result.output.events);
+    }
+}
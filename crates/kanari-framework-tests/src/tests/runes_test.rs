@@ -0,0 +1,291 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::binding_test;
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash;
+use bitcoin::opcodes::all::{OP_PUSHNUM_13, OP_RETURN};
+use bitcoin::script::{Builder, PushBytesBuf};
+use bitcoin::{
+    transaction, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, WPubkeyHash,
+    Witness,
+};
+use moveos_types::state_resolver::StateResolver;
+use kanari_types::bitcoin::runes::{self, RuneBalances, RuneEntry, RuneId};
+use kanari_types::genesis_config;
+use kanari_types::kanari_network::{BuiltinChainID, KanariNetwork};
+use kanari_types::transaction::L1BlockWithBody;
+
+const RESERVED_RUNE: u128 = 6402364363415443603228541259936211926;
+
+const TAG_BODY: u128 = 0;
+const TAG_FLAGS: u128 = 2;
+const TAG_PREMINE: u128 = 6;
+const TAG_CAP: u128 = 8;
+const TAG_AMOUNT: u128 = 10;
+const TAG_MINT: u128 = 20;
+const TAG_POINTER: u128 = 22;
+const TAG_CENOTAPH: u128 = 126;
+const FLAG_ETCHING_AND_TERMS: u128 = 0b11;
+
+fn encode_varint(mut n: u128, v: &mut Vec<u8>) {
+    while n >> 7 > 0 {
+        v.push(n.to_le_bytes()[0] | 0b1000_0000);
+        n >>= 7;
+    }
+    v.push(n.to_le_bytes()[0]);
+}
+
+fn runestone_script(integers: &[u128]) -> ScriptBuf {
+    let mut payload = vec![];
+    for integer in integers {
+        encode_varint(*integer, &mut payload);
+    }
+    Builder::new()
+        .push_opcode(OP_RETURN)
+        .push_opcode(OP_PUSHNUM_13)
+        .push_slice(PushBytesBuf::try_from(payload).unwrap())
+        .into_script()
+}
+
+fn wallet_script(n: u8) -> ScriptBuf {
+    ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([n; 20]))
+}
+
+fn new_tx(inputs: Vec<OutPoint>, outputs: Vec<ScriptBuf>) -> Transaction {
+    Transaction {
+        version: transaction::Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: inputs
+            .into_iter()
+            .map(|previous_output| TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: outputs
+            .into_iter()
+            .map(|script_pubkey| TxOut {
+                value: if script_pubkey.is_op_return() {
+                    Amount::ZERO
+                } else {
+                    Amount::from_sat(10_000)
+                },
+                script_pubkey,
+            })
+            .collect(),
+    }
+}
+
+fn rune_entry(binding_test: &binding_test::RustBindingTest, id: RuneId) -> Option<RuneEntry> {
+    binding_test
+        .resolver()
+        .get_object(&id.object_id())
+        .unwrap()
+        .map(|obj| obj.value_as::<RuneEntry>().unwrap())
+}
+
+fn rune_balances(
+    binding_test: &binding_test::RustBindingTest,
+    outpoint: OutPoint,
+) -> Vec<(RuneId, u128)> {
+    let object_id = runes::derive_rune_balances_id(&outpoint.into());
+    binding_test
+        .resolver()
+        .get_object(&object_id)
+        .unwrap()
+        .map(|obj| {
+            obj.value_as::<RuneBalances>()
+                .unwrap()
+                .balances
+                .data
+                .into_iter()
+                .map(|element| (element.key, element.value))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_runes_process_utxo() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut chain = bitcoin_mock_rpc::MockChain::new();
+    chain.mine_blocks(2);
+    let coinbase_outpoint = |chain: &bitcoin_mock_rpc::MockChain, height: u64| {
+        let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+        OutPoint::new(block.txdata[0].compute_txid(), 0)
+    };
+
+    let network = KanariNetwork::new(
+        BuiltinChainID::Local.chain_id(),
+        genesis_config::G_LOCAL_CONFIG.clone(),
+    );
+    let mut binding_test = binding_test::RustBindingTest::new_with_network(network).unwrap();
+    for height in 0..=chain.tip_height() {
+        let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+        binding_test
+            .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+
+    // Etch a reserved rune at the tx index 2 with a premine and the open mint terms.
+    let filler_tx = new_tx(vec![coinbase_outpoint(&chain, 1)], vec![wallet_script(1)]);
+    let etch_tx = new_tx(
+        vec![coinbase_outpoint(&chain, 2)],
+        vec![
+            runestone_script(&[
+                TAG_FLAGS,
+                FLAG_ETCHING_AND_TERMS,
+                TAG_PREMINE,
+                1000,
+                TAG_AMOUNT,
+                100,
+                TAG_CAP,
+                10,
+            ]),
+            wallet_script(2),
+        ],
+    );
+    let etch_block_hash = chain.mine_block_with_txs(vec![filler_tx, etch_tx.clone()]);
+    let etch_height = chain.tip_height();
+    // The txs are executed out of the block order, the RuneId is still the index in the block.
+    let mut l1_txs = binding_test
+        .execute_l1_block(L1BlockWithBody::new_bitcoin_block(
+            etch_height,
+            chain.block(&etch_block_hash).unwrap().clone(),
+        ))
+        .unwrap();
+    let coinbase_tx = l1_txs.pop().unwrap();
+    l1_txs.reverse();
+    l1_txs.push(coinbase_tx);
+    for l1_tx in l1_txs {
+        binding_test.execute_l1_tx(l1_tx).unwrap();
+    }
+
+    let rune_id = RuneId::new(etch_height, 2);
+    let entry = rune_entry(&binding_test, rune_id).unwrap();
+    assert_eq!(
+        entry.rune,
+        RESERVED_RUNE + (((etch_height as u128) << 32) | 2)
+    );
+    assert_eq!(entry.premine, 1000);
+    assert_eq!(entry.mints, 0);
+    assert!(rune_entry(&binding_test, RuneId::new(etch_height, 1)).is_none());
+    let etch_outpoint = OutPoint::new(etch_tx.compute_txid(), 1);
+    assert_eq!(
+        rune_balances(&binding_test, etch_outpoint),
+        vec![(rune_id, 1000)]
+    );
+
+    // Mint the rune, and transfer the premine with an edict, the rest goes to the pointer output.
+    let mint_tx = new_tx(
+        vec![coinbase_outpoint(&chain, 3)],
+        vec![
+            runestone_script(&[
+                TAG_MINT,
+                rune_id.block as u128,
+                TAG_MINT,
+                rune_id.tx as u128,
+            ]),
+            wallet_script(3),
+        ],
+    );
+    let transfer_tx = new_tx(
+        vec![etch_outpoint],
+        vec![
+            runestone_script(&[
+                TAG_POINTER,
+                2,
+                TAG_BODY,
+                rune_id.block as u128,
+                rune_id.tx as u128,
+                300,
+                1,
+            ]),
+            wallet_script(4),
+            wallet_script(5),
+        ],
+    );
+    let block_hash = chain.mine_block_with_txs(vec![mint_tx.clone(), transfer_tx.clone()]);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            chain.tip_height(),
+            chain.block(&block_hash).unwrap().clone(),
+        ))
+        .unwrap();
+
+    assert_eq!(rune_entry(&binding_test, rune_id).unwrap().mints, 1);
+    assert_eq!(
+        rune_balances(&binding_test, OutPoint::new(mint_tx.compute_txid(), 1)),
+        vec![(rune_id, 100)]
+    );
+    assert!(rune_balances(&binding_test, etch_outpoint).is_empty());
+    let edict_outpoint = OutPoint::new(transfer_tx.compute_txid(), 1);
+    assert_eq!(
+        rune_balances(&binding_test, edict_outpoint),
+        vec![(rune_id, 300)]
+    );
+    assert_eq!(
+        rune_balances(&binding_test, OutPoint::new(transfer_tx.compute_txid(), 2)),
+        vec![(rune_id, 700)]
+    );
+
+    // The cenotaph with an unrecognized even tag burns the input runes.
+    let cenotaph_tx = new_tx(
+        vec![edict_outpoint],
+        vec![runestone_script(&[TAG_CENOTAPH, 0]), wallet_script(6)],
+    );
+    let block_hash = chain.mine_block_with_txs(vec![cenotaph_tx.clone()]);
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            chain.tip_height(),
+            chain.block(&block_hash).unwrap().clone(),
+        ))
+        .unwrap();
+
+    assert!(rune_balances(&binding_test, edict_outpoint).is_empty());
+    assert!(rune_balances(&binding_test, OutPoint::new(cenotaph_tx.compute_txid(), 1)).is_empty());
+    assert_eq!(rune_entry(&binding_test, rune_id).unwrap().burned, 300);
+}
+
+#[tokio::test]
+async fn test_runes_feature_disabled() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut chain = bitcoin_mock_rpc::MockChain::new();
+    chain.mine_blocks(1);
+
+    // The Runes feature is only enabled at genesis on the local and dev chains.
+    let network = KanariNetwork::new(
+        BuiltinChainID::Test.chain_id(),
+        genesis_config::G_LOCAL_CONFIG.clone(),
+    );
+    let mut binding_test = binding_test::RustBindingTest::new_with_network(network).unwrap();
+    for height in 0..=chain.tip_height() {
+        let block = chain.block(&chain.block_hash(height).unwrap()).unwrap();
+        binding_test
+            .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(height, block.clone()))
+            .unwrap();
+    }
+
+    let coinbase_block = chain.block(&chain.block_hash(1).unwrap()).unwrap();
+    let etch_tx = new_tx(
+        vec![OutPoint::new(coinbase_block.txdata[0].compute_txid(), 0)],
+        vec![
+            runestone_script(&[TAG_FLAGS, FLAG_ETCHING_AND_TERMS, TAG_PREMINE, 1000]),
+            wallet_script(1),
+        ],
+    );
+    let block_hash = chain.mine_block_with_txs(vec![etch_tx.clone()]);
+    let etch_height = chain.tip_height();
+    binding_test
+        .execute_l1_block_and_tx(L1BlockWithBody::new_bitcoin_block(
+            etch_height,
+            chain.block(&block_hash).unwrap().clone(),
+        ))
+        .unwrap();
+
+    assert!(rune_entry(&binding_test, RuneId::new(etch_height, 1)).is_none());
+    assert!(rune_balances(&binding_test, OutPoint::new(etch_tx.compute_txid(), 1)).is_empty());
+}
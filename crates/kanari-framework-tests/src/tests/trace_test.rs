@@ -0,0 +1,88 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::binding_test;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use moveos::vm::tracer::CallTrace;
+use moveos_types::state::MoveStructType;
+use kanari_key::keystore::account_keystore::AccountKeystore;
+use kanari_key::keystore::memory_keystore::InMemKeystore;
+use kanari_types::framework::kari::KARI;
+use kanari_types::framework::transfer::TransferModule;
+use kanari_types::transaction::kanari::KanariTransactionData;
+
+#[tokio::test]
+async fn test_execute_with_trace() {
+    let mut binding_test = binding_test::RustBindingTest::new().unwrap();
+
+    let keystore = InMemKeystore::new_insecure_for_tests(1);
+    let sender = keystore.addresses()[0];
+    binding_test
+        .get_kari(sender.into(), U256::from(100_000_000_000u64))
+        .unwrap();
+
+    let action = TransferModule::create_transfer_coin_action(
+        KARI::struct_tag(),
+        sender.into(),
+        U256::from(1000u64),
+    );
+    let tx_data = KanariTransactionData::new_for_test(sender, 0, action);
+    let tx = binding_test
+        .executor
+        .convert_to_verified_tx_for_dry_run(tx_data)
+        .unwrap();
+
+    let (raw_output, _) = binding_test
+        .executor
+        .moveos()
+        .execute_only(tx.clone())
+        .unwrap();
+    let (traced_output, vm_error_info, call_trace, read_set) = binding_test
+        .executor
+        .moveos()
+        .execute_with_trace(tx)
+        .unwrap();
+    // The tracer should not change the execution result.
+    assert_eq!(raw_output.status, traced_output.status);
+    assert_eq!(raw_output.gas_used, traced_output.gas_used);
+    assert_eq!(raw_output.changeset, traced_output.changeset);
+    assert!(vm_error_info.is_none());
+
+    assert_eq!(
+        call_trace.function,
+        "0x3::transfer::transfer_coin<0x3::kari::KARI>"
+    );
+    assert_eq!(call_trace.args.len(), 2);
+    assert_eq!(
+        call_trace.calls[0].function,
+        "0x3::account_coin_store::transfer<0x3::kari::KARI>"
+    );
+    // The return values of the Move functions are recorded as well as the native functions.
+    let address_of = find_call(&call_trace, "0x1::signer::address_of").unwrap();
+    assert!(!address_of.is_native);
+    assert_eq!(
+        address_of.return_values,
+        vec![AccountAddress::from(sender).to_hex_literal()]
+    );
+    let borrow_address = find_call(address_of, "0x1::signer::borrow_address").unwrap();
+    assert!(borrow_address.is_native);
+    assert_eq!(borrow_address.return_values.len(), 1);
+    assert!(call_trace.gas_used > 0);
+    // The gas used by a call include the gas used by the calls it made.
+    assert!(call_trace
+        .calls
+        .iter()
+        .all(|call| call.gas_used <= call_trace.gas_used));
+    assert!(!read_set.objects.is_empty());
+}
+
+fn find_call<'a>(trace: &'a CallTrace, function: &str) -> Option<&'a CallTrace> {
+    trace.calls.iter().find_map(|call| {
+        if call.function == function {
+            Some(call)
+        } else {
+            find_call(call, function)
+        }
+    })
+}
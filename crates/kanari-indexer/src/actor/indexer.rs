@@ -217,6 +217,9 @@ impl Handler<IndexerPersistOrUpdateAnyObjectStatesMessage> for IndexerActor {
             ObjectStateType::Inscription => self
                 .indexer_store
                 .persist_or_update_object_state_inscriptions(states)?,
+            ObjectStateType::RuneBalances => self
+                .indexer_store
+                .persist_or_update_object_state_rune_balances(states)?,
         }
         Ok(())
     }
@@ -241,6 +244,9 @@ impl Handler<IndexerDeleteAnyObjectStatesMessage> for IndexerActor {
             ObjectStateType::Inscription => self
                 .indexer_store
                 .delete_object_state_inscriptions(state_pks)?,
+            ObjectStateType::RuneBalances => self
+                .indexer_store
+                .delete_object_state_rune_balances(state_pks)?,
         }
         Ok(())
     }
@@ -13,8 +13,8 @@ use crate::{
     IndexerResult, IndexerStoreMeta, IndexerTableName, SqliteConnectionConfig,
     SqliteConnectionPoolConfig, SqlitePoolConnection, DEFAULT_BUSY_TIMEOUT,
     INDEXER_EVENTS_TABLE_NAME, INDEXER_FIELDS_TABLE_NAME, INDEXER_OBJECT_STATES_TABLE_NAME,
-    INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME, INDEXER_OBJECT_STATE_UTXOS_TABLE_NAME,
-    INDEXER_TRANSACTIONS_TABLE_NAME,
+    INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME, INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME,
+    INDEXER_OBJECT_STATE_UTXOS_TABLE_NAME, INDEXER_TRANSACTIONS_TABLE_NAME,
 };
 use anyhow::{anyhow, Result};
 use diesel::{
@@ -706,6 +706,7 @@ fn get_table_name_by_state_type(state_type: ObjectStateType) -> IndexerTableName
         ObjectStateType::ObjectState => INDEXER_OBJECT_STATES_TABLE_NAME,
         ObjectStateType::UTXO => INDEXER_OBJECT_STATE_UTXOS_TABLE_NAME,
         ObjectStateType::Inscription => INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME,
+        ObjectStateType::RuneBalances => INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME,
     }
 }
 fn object_type_query(object_type: &StructTag) -> String {
@@ -52,6 +52,7 @@ pub const INDEXER_EVENTS_TABLE_NAME: IndexerTableName = "events";
 pub const INDEXER_OBJECT_STATES_TABLE_NAME: IndexerTableName = "object_states";
 pub const INDEXER_OBJECT_STATE_UTXOS_TABLE_NAME: IndexerTableName = "utxos";
 pub const INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME: IndexerTableName = "inscriptions";
+pub const INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME: IndexerTableName = "rune_balances";
 pub const INDEXER_TRANSACTIONS_TABLE_NAME: IndexerTableName = "transactions";
 pub const INDEXER_FIELDS_TABLE_NAME: IndexerTableName = "fields";
 
@@ -62,6 +63,7 @@ static INDEXER_VEC_TABLE_NAME: Lazy<Vec<IndexerTableName>> = Lazy::new(|| {
         INDEXER_OBJECT_STATES_TABLE_NAME,
         INDEXER_OBJECT_STATE_UTXOS_TABLE_NAME,
         INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME,
+        INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME,
         INDEXER_TRANSACTIONS_TABLE_NAME,
         INDEXER_FIELDS_TABLE_NAME,
     ]
@@ -165,6 +167,11 @@ impl IndexerStoreTrait for IndexerStore {
             object_state_change_set.object_state_inscriptions,
             INDEXER_OBJECT_STATE_INSCRIPTIONS_TABLE_NAME,
             ObjectStateType::Inscription,
+        )?;
+        self.update_object_states_by_state_type(
+            object_state_change_set.object_state_rune_balances,
+            INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME,
+            ObjectStateType::RuneBalances,
         )
     }
 
@@ -207,6 +214,22 @@ impl IndexerStoreTrait for IndexerStore {
             .delete_object_state_inscriptions(state_pks)
     }
 
+    fn persist_or_update_object_state_rune_balances(
+        &self,
+        states: Vec<IndexerObjectState>,
+    ) -> Result<(), IndexerError> {
+        self.get_sqlite_store(INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME)?
+            .persist_or_update_object_state_rune_balances(states)
+    }
+
+    fn delete_object_state_rune_balances(
+        &self,
+        state_pks: Vec<String>,
+    ) -> Result<(), IndexerError> {
+        self.get_sqlite_store(INDEXER_OBJECT_STATE_RUNE_BALANCES_TABLE_NAME)?
+            .delete_object_state_rune_balances(state_pks)
+    }
+
     fn persist_transactions(
         &self,
         transactions: Vec<IndexerTransaction>,
@@ -282,6 +305,12 @@ impl IndexerStore {
                 self.get_sqlite_store(table_name)?
                     .delete_object_state_inscriptions(object_state_change.remove_object_states)
             }
+            ObjectStateType::RuneBalances => {
+                self.get_sqlite_store(table_name)?
+                    .persist_or_update_object_state_rune_balances(object_states_new_and_update)?;
+                self.get_sqlite_store(table_name)?
+                    .delete_object_state_rune_balances(object_state_change.remove_object_states)
+            }
         }
     }
 }
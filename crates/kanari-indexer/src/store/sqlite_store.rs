@@ -15,10 +15,11 @@ use std::sync::Arc;
 use crate::models::events::StoredEvent;
 use crate::models::fields::StoredField;
 use crate::models::inscriptions::StoredInscription;
+use crate::models::rune_balances::StoredRuneBalances;
 use crate::models::states::StoredObjectState;
 use crate::models::transactions::{escape_transaction, StoredTransaction};
 use crate::models::utxos::StoredUTXO;
-use crate::schema::{events, fields, inscriptions, object_states, transactions, utxos};
+use crate::schema::{events, fields, inscriptions, object_states, rune_balances, transactions, utxos};
 use crate::store::metrics::IndexerDBMetrics;
 use crate::utils::escape_sql_string;
 use crate::{get_sqlite_pool_connection, SqliteConnectionPool};
@@ -243,6 +244,72 @@ impl SqliteIndexerStore {
         Ok(())
     }
 
+    #[named]
+    pub fn persist_or_update_object_state_rune_balances(
+        &self,
+        states: Vec<IndexerObjectState>,
+    ) -> Result<(), IndexerError> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let fn_name = function_name!();
+        let _timer = self
+            .db_metrics
+            .indexer_store_metrics
+            .indexer_persist_or_update_or_delete_latency_seconds
+            .with_label_values(&[fn_name])
+            .start_timer();
+        let mut connection = get_sqlite_pool_connection(&self.connection_pool)?;
+        let states = states
+            .into_iter()
+            .map(StoredRuneBalances::from)
+            .collect::<Vec<_>>();
+
+        // Diesel for SQLite don't support batch update yet, so implements batch update directly via raw SQL
+        let values_clause = states
+            .into_iter()
+            .map(|state| {
+                format!(
+                    "('{}', '{}', {}, {}, {}, {})",
+                    escape_sql_string(state.id),
+                    escape_sql_string(state.owner),
+                    state.tx_order,
+                    state.state_index,
+                    state.created_at,
+                    state.updated_at,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let query = format!(
+            "
+                INSERT INTO rune_balances (id, owner, tx_order, state_index, created_at, updated_at) \
+                VALUES {} \
+                ON CONFLICT (id) DO UPDATE SET \
+                owner = excluded.owner, \
+                tx_order = excluded.tx_order, \
+                state_index = excluded.state_index, \
+                updated_at = excluded.updated_at
+            ",
+            values_clause
+        );
+
+        // Execute the raw SQL query
+        diesel::sql_query(query.clone())
+            .execute(&mut connection)
+            .map_err(|e| {
+                tracing::error!(
+                    "Upsert object state rune balances Executing Query error: {}",
+                    query
+                );
+                IndexerError::SQLiteWriteError(e.to_string())
+            })
+            .context("Failed to write or update object state rune balances to SQLiteDB")?;
+
+        Ok(())
+    }
+
     #[named]
     pub fn delete_object_states(&self, state_pks: Vec<String>) -> Result<(), IndexerError> {
         if state_pks.is_empty() {
@@ -315,6 +382,32 @@ impl SqliteIndexerStore {
         Ok(())
     }
 
+    #[named]
+    pub fn delete_object_state_rune_balances(
+        &self,
+        state_pks: Vec<String>,
+    ) -> Result<(), IndexerError> {
+        if state_pks.is_empty() {
+            return Ok(());
+        }
+
+        let fn_name = function_name!();
+        let _timer = self
+            .db_metrics
+            .indexer_store_metrics
+            .indexer_persist_or_update_or_delete_latency_seconds
+            .with_label_values(&[fn_name])
+            .start_timer();
+        let mut connection = get_sqlite_pool_connection(&self.connection_pool)?;
+
+        diesel::delete(rune_balances::table.filter(rune_balances::id.eq_any(state_pks.as_slice())))
+            .execute(&mut connection)
+            .map_err(|e| IndexerError::SQLiteWriteError(e.to_string()))
+            .context("Failed to delete object state rune balances to SQLiteDB")?;
+
+        Ok(())
+    }
+
     #[named]
     pub fn persist_transactions(
         &self,
@@ -3,8 +3,8 @@
 
 use crate::event::{GasUpgradeEvent, ServiceStatusEvent};
 use crate::messages::{
-    GasUpgradeMessage, NotifyActorSubscribeMessage, ProcessTxWithEventsMessage,
-    UpdateServiceStatusMessage,
+    GasUpgradeMessage, NotifyActorSubscribeMessage, ProcessObjectStateChangesMessage,
+    ProcessTxWithEventsMessage, UpdateServiceStatusMessage,
 };
 use crate::subscription_handler::SubscriptionHandler;
 use async_trait::async_trait;
@@ -12,18 +12,26 @@ use coerce::actor::context::ActorContext;
 use coerce::actor::message::Handler;
 use coerce::actor::Actor;
 use moveos_eventbus::bus::EventBus;
+use moveos_store::MoveOSStore;
+use moveos_types::state_resolver::RootObjectResolver;
 use std::sync::Arc;
 
 pub struct NotifyActor {
     event_bus: EventBus,
     pub subscription_handler: Arc<SubscriptionHandler>,
+    moveos_store: MoveOSStore,
 }
 
 impl NotifyActor {
-    pub fn new(event_bus: EventBus, subscription_handler: Arc<SubscriptionHandler>) -> Self {
+    pub fn new(
+        event_bus: EventBus,
+        subscription_handler: Arc<SubscriptionHandler>,
+        moveos_store: MoveOSStore,
+    ) -> Self {
         Self {
             event_bus,
             subscription_handler,
+            moveos_store,
         }
     }
 }
@@ -94,3 +102,30 @@ impl Handler<ProcessTxWithEventsMessage> for NotifyActor {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Handler<ProcessObjectStateChangesMessage> for NotifyActor {
+    async fn handle(
+        &mut self,
+        message: ProcessObjectStateChangesMessage,
+        _ctx: &mut ActorContext,
+    ) -> anyhow::Result<()> {
+        if !self.subscription_handler.has_object_state_subscribers() {
+            return Ok(());
+        }
+        let ProcessObjectStateChangesMessage {
+            tx_order,
+            tx_hash,
+            root,
+            state_change_set,
+        } = message;
+        let resolver = RootObjectResolver::new(root, &self.moveos_store);
+        self.subscription_handler.process_object_state_changes(
+            tx_order,
+            tx_hash,
+            state_change_set,
+            &resolver,
+        )?;
+        Ok(())
+    }
+}
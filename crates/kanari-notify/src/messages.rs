@@ -3,8 +3,11 @@
 
 use coerce::actor::message::Message;
 use moveos_eventbus::bus::EventNotifier;
+use moveos_types::h256::H256;
 use moveos_types::moveos_std::event::Event;
+use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::moveos_std::tx_context::TxContext;
+use moveos_types::state::StateChangeSet;
 use kanari_types::service_status::ServiceStatus;
 use kanari_types::transaction::TransactionWithInfo;
 
@@ -58,3 +61,16 @@ pub struct ProcessTxWithEventsMessage {
 impl Message for ProcessTxWithEventsMessage {
     type Result = anyhow::Result<()>;
 }
+
+#[derive(Clone, Debug)]
+pub struct ProcessObjectStateChangesMessage {
+    pub tx_order: u64,
+    pub tx_hash: H256,
+    /// The root object after the transaction executed, used to decode the changed objects.
+    pub root: ObjectMeta,
+    pub state_change_set: StateChangeSet,
+}
+
+impl Message for ProcessObjectStateChangesMessage {
+    type Result = anyhow::Result<()>;
+}
@@ -145,6 +145,11 @@ where
         ReceiverStream::new(rx)
     }
 
+    /// The number of subscribers currently registered on this streamer.
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.read().len()
+    }
+
     pub fn try_send(&self, data: T) -> Result<(), KanariError> {
         self.streamer_queue.try_send(data).map_err(|e| {
             self.metrics
@@ -8,8 +8,12 @@ use anyhow::Result;
 use std::sync::Arc;
 
 use crate::streamer::Streamer;
+use move_core_types::effects::Op;
+use moveos_types::h256::H256;
 use moveos_types::moveos_std::event::Event;
 use moveos_types::moveos_std::tx_context::TxContext;
+use moveos_types::state::{AnnotatedState, ObjectChange, ObjectState, StateChangeSet};
+use moveos_types::state_resolver::AnnotatedStateReader;
 use prometheus::{
     register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
     IntGaugeVec, Registry,
@@ -18,6 +22,9 @@ use kanari_rpc_api::jsonrpc_types::event_view::{EventFilterView, IndexerEventVie
 use kanari_rpc_api::jsonrpc_types::transaction_view::{
     TransactionFilterView, TransactionWithInfoView,
 };
+use kanari_rpc_api::jsonrpc_types::{
+    ObjectChangeTypeView, ObjectStateChangeView, ObjectStateFilterView, ObjectStateView,
+};
 use kanari_types::indexer::event::IndexerEvent;
 use kanari_types::transaction::TransactionWithInfo;
 use tokio_stream::Stream;
@@ -25,6 +32,7 @@ use tracing::{error, trace};
 
 pub const TRANSACTION_DISPATCH_BUFFER_SIZE: usize = 1000;
 pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 5000;
+pub const OBJECT_STATE_DISPATCH_BUFFER_SIZE: usize = 5000;
 
 pub struct SubscriptionMetrics {
     pub streaming_success: IntCounterVec,
@@ -72,6 +80,8 @@ pub struct SubscriptionHandler {
     event_streamer: Streamer<IndexerEventView, IndexerEventView, EventFilterView>,
     transaction_streamer:
         Streamer<TransactionWithInfoView, TransactionWithInfoView, TransactionFilterView>,
+    object_state_streamer:
+        Streamer<ObjectStateChangeView, ObjectStateChangeView, ObjectStateFilterView>,
 }
 
 impl SubscriptionHandler {
@@ -79,7 +89,16 @@ impl SubscriptionHandler {
         let metrics = Arc::new(SubscriptionMetrics::new(registry));
         Self {
             event_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics.clone(), "event"),
-            transaction_streamer: Streamer::spawn(TRANSACTION_DISPATCH_BUFFER_SIZE, metrics, "tx"),
+            transaction_streamer: Streamer::spawn(
+                TRANSACTION_DISPATCH_BUFFER_SIZE,
+                metrics.clone(),
+                "tx",
+            ),
+            object_state_streamer: Streamer::spawn(
+                OBJECT_STATE_DISPATCH_BUFFER_SIZE,
+                metrics,
+                "object_state",
+            ),
         }
     }
 }
@@ -120,6 +139,50 @@ impl SubscriptionHandler {
         Ok(())
     }
 
+    /// Decode the changed objects in the state change set and dispatch them to the object state subscribers.
+    /// Objects without a value change (only their fields changed) are skipped, their fields are dispatched individually.
+    pub fn process_object_state_changes<R>(
+        &self,
+        tx_order: u64,
+        tx_hash: H256,
+        state_change_set: StateChangeSet,
+        resolver: &R,
+    ) -> Result<()>
+    where
+        R: AnnotatedStateReader,
+    {
+        let mut object_state_changes = vec![];
+        for object_change in state_change_set.changes.into_values() {
+            collect_object_state_changes(
+                tx_order,
+                tx_hash,
+                object_change,
+                resolver,
+                &mut object_state_changes,
+            );
+        }
+
+        if tracing::enabled!(tracing::Level::TRACE) {
+            trace!(
+                num_object_states = object_state_changes.len(),
+                tx_order = tx_order,
+                "Processing object state subscription"
+            );
+        }
+
+        for object_state_change in object_state_changes {
+            if let Err(e) = self.object_state_streamer.try_send(object_state_change) {
+                error!("Failed to send object state to dispatch: {:?}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Decoding object states is expensive, the caller can skip it when there are no subscribers.
+    pub fn has_object_state_subscribers(&self) -> bool {
+        self.object_state_streamer.subscriber_count() > 0
+    }
+
     pub fn subscribe_events(
         &self,
         filter: EventFilterView,
@@ -135,4 +198,79 @@ impl SubscriptionHandler {
         // ) -> ReceiverStream<TransactionWithInfoView> {
         self.transaction_streamer.subscribe(filter)
     }
+
+    pub fn subscribe_object_states(
+        &self,
+        filter: ObjectStateFilterView,
+    ) -> impl Stream<Item = ObjectStateChangeView> {
+        self.object_state_streamer.subscribe(filter)
+    }
+}
+
+fn collect_object_state_changes<R>(
+    tx_order: u64,
+    tx_hash: H256,
+    object_change: ObjectChange,
+    resolver: &R,
+    object_state_changes: &mut Vec<ObjectStateChangeView>,
+) where
+    R: AnnotatedStateReader,
+{
+    let ObjectChange {
+        metadata,
+        value,
+        fields,
+    } = object_change;
+
+    if let Some(op) = value {
+        let (change_type, state) = match op {
+            Op::New(value) => (ObjectChangeTypeView::New, ObjectState::new(metadata, value)),
+            Op::Modify(value) => (
+                ObjectChangeTypeView::Modify,
+                ObjectState::new(metadata, value),
+            ),
+            Op::Delete => (
+                ObjectChangeTypeView::Delete,
+                ObjectState::new(metadata, vec![]),
+            ),
+        };
+        let state_view = match change_type {
+            ObjectChangeTypeView::Delete => ObjectStateView::new_from_object_state(state),
+            _ => decode_object_state(state, resolver),
+        };
+        object_state_changes.push(ObjectStateChangeView::new(
+            tx_order,
+            tx_hash,
+            change_type,
+            state_view,
+        ));
+    }
+
+    for field_change in fields.into_values() {
+        collect_object_state_changes(
+            tx_order,
+            tx_hash,
+            field_change,
+            resolver,
+            object_state_changes,
+        );
+    }
+}
+
+fn decode_object_state<R>(state: ObjectState, resolver: &R) -> ObjectStateView
+where
+    R: AnnotatedStateReader,
+{
+    match resolver.view_resource(state.object_struct_tag(), &state.value) {
+        Ok(decoded_value) => ObjectStateView::new(AnnotatedState::new(state, decoded_value), true),
+        Err(e) => {
+            // Skip the decode error, the raw value is still useful to the subscribers.
+            trace!(
+                object_id = ?state.metadata.id,
+                "Failed to decode object state: {:?}",
+                e
+            );
+            ObjectStateView::new_from_object_state(state)
+        }
+    }
 }
@@ -5,15 +5,19 @@ use crate::subscription_handler::SubscriptionHandler;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::value::MoveTypeLayout;
 use move_core_types::{ident_str, language_storage::StructTag};
+use moveos_store::MoveOSStore;
+use moveos_types::h256::H256;
 use moveos_types::move_std::string::MoveString;
+use moveos_types::moveos_std::object::{ObjectID, ObjectMeta};
 use moveos_types::moveos_std::tx_context::TxContext;
 use moveos_types::state::MoveStructState;
-use moveos_types::test_utils::random_event;
+use moveos_types::state_resolver::RootObjectResolver;
+use moveos_types::test_utils::{random_event, random_state_change_set};
 use moveos_types::transaction::TransactionExecutionInfo;
 use prometheus::Registry;
 use kanari_rpc_api::jsonrpc_types::event_view::EventFilterView;
 use kanari_rpc_api::jsonrpc_types::transaction_view::TransactionFilterView;
-use kanari_rpc_api::jsonrpc_types::StrView;
+use kanari_rpc_api::jsonrpc_types::{ObjectChangeTypeView, ObjectStateFilterView, StrView};
 use kanari_types::test_utils::random_ledger_transaction;
 use kanari_types::transaction::TransactionWithInfo;
 use serde::Deserialize;
@@ -182,6 +186,48 @@ async fn test_multiple_subscribers() {
     );
 }
 
+#[tokio::test]
+async fn test_object_state_subscription() {
+    let registry = Registry::new();
+    let handler = SubscriptionHandler::new(&registry);
+    let (moveos_store, _tmpdir) = MoveOSStore::mock_moveos_store().unwrap();
+    let resolver = RootObjectResolver::new(ObjectMeta::genesis_root(), &moveos_store);
+
+    let state_change_set = random_state_change_set();
+    let (_, object_change) = state_change_set.changes.iter().next().unwrap();
+    let object_id = object_change.metadata.id.clone();
+
+    assert!(!handler.has_object_state_subscribers());
+    let matching_filter = ObjectStateFilterView::ObjectId(StrView(vec![object_id.clone()]));
+    let non_matching_filter = ObjectStateFilterView::ObjectId(StrView(vec![ObjectID::random()]));
+    let mut matching_stream = handler.subscribe_object_states(matching_filter);
+    let mut non_matching_stream = handler.subscribe_object_states(non_matching_filter);
+    assert!(handler.has_object_state_subscribers());
+
+    let tx_order = 1;
+    let tx_hash = H256::random();
+    handler
+        .process_object_state_changes(tx_order, tx_hash, state_change_set, &resolver)
+        .unwrap();
+
+    // The random object can not be decoded, but the raw state should still be streamed
+    let received = timeout(Duration::from_secs(1), matching_stream.next())
+        .await
+        .expect("Matching stream should receive object state")
+        .unwrap();
+    assert_eq!(received.tx_order.0, tx_order);
+    assert_eq!(received.change_type, ObjectChangeTypeView::New);
+    assert_eq!(received.state.metadata.id, object_id);
+    assert!(received.state.decoded_value.is_none());
+
+    let non_matching_received =
+        timeout(Duration::from_millis(100), non_matching_stream.next()).await;
+    assert!(
+        non_matching_received.is_err(),
+        "Non-matching stream should not receive object state"
+    );
+}
+
 // #[tokio::test]
 // async fn test_filter_matching() {
 //     let registry = Registry::new();
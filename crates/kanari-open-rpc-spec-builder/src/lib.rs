@@ -9,6 +9,7 @@ use clap::clap_derive::ValueEnum;
 use clap::Parser;
 use kanari_open_rpc::Project;
 use kanari_rpc_api::api::btc_api::BtcAPIOpenRpc;
+use kanari_rpc_api::api::debug_api::DebugAPIOpenRpc;
 use kanari_rpc_api::api::kanari_api::KanariAPIOpenRpc;
 use std::fs::File;
 use std::io::Write;
@@ -44,6 +45,7 @@ pub fn build_kanari_rpc_spec() -> Project {
     open_rpc.add_module(KanariAPIOpenRpc::module_doc());
     //FIXME if add the EthAPIOpenRpc, the pnpm sdk gen raies error
     open_rpc.add_module(BtcAPIOpenRpc::module_doc());
+    open_rpc.add_module(DebugAPIOpenRpc::module_doc());
     //open_rpc.add_examples(RpcExampleProvider::new().examples());
     open_rpc
 }
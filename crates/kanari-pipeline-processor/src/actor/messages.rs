@@ -35,6 +35,16 @@ impl Message for ExecuteL1TxMessage {
     type Result = Result<ExecuteTransactionResponse>;
 }
 
+/// Execute the L1 txs of a block in parallel
+#[derive(Clone)]
+pub struct ExecuteL1TxsMessage {
+    pub txs: Vec<L1Transaction>,
+}
+
+impl Message for ExecuteL1TxsMessage {
+    type Result = Vec<Result<ExecuteTransactionResponse>>;
+}
+
 #[derive(Clone)]
 pub struct GetServiceStatusMessage {}
 
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::messages::{
-    ExecuteL1BlockMessage, ExecuteL1TxMessage, ExecuteL2TxMessage, GetServiceStatusMessage,
+    ExecuteL1BlockMessage, ExecuteL1TxMessage, ExecuteL1TxsMessage, ExecuteL2TxMessage,
+    GetServiceStatusMessage,
 };
 use crate::metrics::PipelineProcessorMetrics;
 use anyhow::{anyhow, Error, Result};
@@ -16,7 +17,9 @@ use moveos_types::h256::H256;
 use moveos_types::moveos_std::event::Event;
 use moveos_types::moveos_std::tx_context::TxContext;
 use moveos_types::state::StateChangeSetExt;
-use moveos_types::transaction::VerifiedMoveOSTransaction;
+use moveos_types::transaction::{
+    TransactionExecutionInfo, TransactionOutput, VerifiedMoveOSTransaction,
+};
 use prometheus::Registry;
 use kanari_da::actor::messages::{AppendTransactionMessage, RevertTransactionMessage};
 use kanari_da::proxy::DAServerProxy;
@@ -24,7 +27,10 @@ use kanari_db::KanariDB;
 use kanari_executor::proxy::ExecutorProxy;
 use kanari_indexer::proxy::IndexerProxy;
 use kanari_notify::actor::NotifyActor;
-use kanari_notify::messages::{ProcessTxWithEventsMessage, UpdateServiceStatusMessage};
+use kanari_notify::messages::{
+    ProcessObjectStateChangesMessage, ProcessTxWithEventsMessage, UpdateServiceStatusMessage,
+};
+use kanari_notify::subscription_handler::SubscriptionHandler;
 use kanari_sequencer::proxy::SequencerProxy;
 use kanari_types::bitcoin::types::Block as BitcoinBlock;
 use kanari_types::transaction::TransactionWithInfo;
@@ -48,6 +54,7 @@ pub struct PipelineProcessorActor {
     pub(crate) service_status: ServiceStatus,
     pub(crate) metrics: Arc<PipelineProcessorMetrics>,
     notify_actor: Option<LocalActorRef<NotifyActor>>,
+    subscription_handler: Option<Arc<SubscriptionHandler>>,
     kanari_db: KanariDB,
     bitcoin_client_proxy: Option<BitcoinClientProxy>,
 }
@@ -61,6 +68,7 @@ impl PipelineProcessorActor {
         service_status: ServiceStatus,
         registry: &Registry,
         notify_actor: Option<LocalActorRef<NotifyActor>>,
+        subscription_handler: Option<Arc<SubscriptionHandler>>,
         kanari_db: KanariDB,
         bitcoin_client_proxy: Option<BitcoinClientProxy>,
     ) -> Self {
@@ -72,6 +80,7 @@ impl PipelineProcessorActor {
             service_status,
             metrics: Arc::new(PipelineProcessorMetrics::new(registry)),
             notify_actor,
+            subscription_handler,
             kanari_db,
             bitcoin_client_proxy,
         }
@@ -442,7 +451,132 @@ impl PipelineProcessorActor {
         // Then execute
         let size = moveos_tx.ctx.tx_size;
         let (output, execution_info) = self.executor.execute_transaction(moveos_tx.clone()).await?;
+        let response = self
+            .process_executed_tx(tx, moveos_tx, output, execution_info)
+            .await?;
+
+        self.metrics
+            .pipeline_processor_execution_tx_bytes
+            .with_label_values(&[fn_name])
+            .observe(size as f64);
+        Ok(response)
+    }
 
+    /// Sequence the L1 txs of a block and execute them in parallel,
+    /// the results are identical to executing them one by one with `execute_l1_tx`.
+    /// The results are in the order of the given txs, the txs after a failed one may be missing.
+    #[named]
+    pub async fn execute_l1_txs(
+        &mut self,
+        l1_txs: Vec<L1Transaction>,
+    ) -> Vec<Result<ExecuteTransactionResponse>> {
+        let fn_name = function_name!();
+        let _timer = self
+            .metrics
+            .pipeline_processor_execution_tx_latency_seconds
+            .with_label_values(&[fn_name])
+            .start_timer();
+
+        let mut results = Vec::with_capacity(l1_txs.len());
+        // The index of the tx in `results`, the ledger tx and the verified tx of the sequenced txs
+        let mut sequenced_txs = Vec::with_capacity(l1_txs.len());
+        for l1_tx in l1_txs {
+            let moveos_tx = match self.executor.validate_l1_tx(l1_tx.clone(), false).await {
+                Ok(moveos_tx) => moveos_tx,
+                Err(err) => {
+                    results.push(Err(err));
+                    continue;
+                }
+            };
+            let ledger_tx = match self.sequence_and_public_tx(LedgerTxData::L1Tx(l1_tx)).await {
+                Ok(ledger_tx) => ledger_tx,
+                Err(err) => {
+                    // The tx is reverted, stop here and execute the sequenced txs,
+                    // the remaining txs are still pending and will be relayed again.
+                    results.push(Err(err));
+                    break;
+                }
+            };
+            sequenced_txs.push((results.len(), ledger_tx, moveos_tx));
+            results.push(Err(anyhow!("The l1 tx is not executed")));
+        }
+        if sequenced_txs.is_empty() {
+            return results;
+        }
+
+        let mut moveos_txs = Vec::with_capacity(sequenced_txs.len());
+        let mut add_error = None;
+        for (_, ledger_tx, moveos_tx) in sequenced_txs.iter_mut() {
+            // Add sequence info to tx context, let the Move contract can get the sequence info
+            if let Err(err) = moveos_tx.ctx.add(ledger_tx.sequence_info.clone()) {
+                add_error = Some(err);
+                break;
+            }
+            moveos_txs.push(moveos_tx.clone());
+        }
+        let (executed, error) = match self
+            .executor
+            .execute_transactions_in_parallel(moveos_txs)
+            .await
+        {
+            Ok((executed, error)) => (executed, error.or(add_error)),
+            Err(err) => (vec![], Some(err)),
+        };
+        let executed_count = executed.len();
+        let mut sequenced_txs = sequenced_txs.into_iter();
+        for (output, execution_info) in executed {
+            let (index, ledger_tx, moveos_tx) = sequenced_txs
+                .next()
+                .expect("The executed txs should be sequenced");
+            let gas_used = output.gas_used;
+            let size = moveos_tx.ctx.tx_size;
+            results[index] = self
+                .process_executed_tx(ledger_tx, moveos_tx, output, execution_info)
+                .await;
+            self.metrics
+                .pipeline_processor_l1_tx_gas_used
+                .inc_by(gas_used);
+            self.metrics
+                .pipeline_processor_execution_tx_bytes
+                .with_label_values(&[fn_name])
+                .observe(size as f64);
+        }
+
+        if let Some(err) = error {
+            let remaining_count = sequenced_txs.len();
+            if let Some((index, mut ledger_tx, _)) = sequenced_txs.next() {
+                let tx_order = ledger_tx.sequence_info.tx_order;
+                let tx_hash = ledger_tx.tx_hash();
+                if remaining_count == 1 {
+                    self.handle_execute_error(&err, tx_order, tx_hash, "L1Tx", None)
+                        .await;
+                } else {
+                    // Only the last tx can be reverted, the sequenced but not executed txs are
+                    // executed again by `process_sequenced_tx_on_startup` after restart.
+                    tracing::error!(
+                        "Execute L1Tx in parallel failed, set service to Maintenance mode and pause the relayer. \
+                        error: {:?}, tx_order: {}, tx_hash: {:?}, executed: {}, not executed: {}",
+                        err,
+                        tx_order,
+                        tx_hash,
+                        executed_count,
+                        remaining_count
+                    );
+                    self.update_service_status(ServiceStatus::Maintenance).await;
+                }
+                results[index] = Err(err);
+            }
+        }
+        results
+    }
+
+    async fn process_executed_tx(
+        &mut self,
+        tx: LedgerTransaction,
+        moveos_tx: VerifiedMoveOSTransaction,
+        output: TransactionOutput,
+        execution_info: TransactionExecutionInfo,
+    ) -> Result<ExecuteTransactionResponse> {
         let root = execution_info.root_metadata();
         // Sync the latest state root from writer executor to reader executor
         self.executor
@@ -477,20 +611,31 @@ impl PipelineProcessorActor {
         };
 
         // Process subscription, skip errors
+        // Only clone the changeset when someone subscribes to the object state changes
+        let object_state_changes = if self.has_object_state_subscribers() {
+            Some(ProcessObjectStateChangesMessage {
+                tx_order: sequence_info.tx_order,
+                tx_hash: execution_info.tx_hash,
+                root: root.clone(),
+                state_change_set: output.changeset.clone(),
+            })
+        } else {
+            None
+        };
         let tx_with_info = TransactionWithInfo::new(tx, execution_info.clone());
         let result = self
-            .process_subscription(tx_with_info, output.events.clone(), moveos_tx.ctx)
+            .process_subscription(
+                tx_with_info,
+                output.events.clone(),
+                object_state_changes,
+                moveos_tx.ctx,
+            )
             .await;
         match result {
             Ok(_) => {}
             Err(error) => tracing::error!("Process subscription error: {}", error),
         };
 
-        self.metrics
-            .pipeline_processor_execution_tx_bytes
-            .with_label_values(&[fn_name])
-            .observe(size as f64);
-
         Ok(ExecuteTransactionResponse {
             sequence_info,
             execution_info,
@@ -511,15 +656,30 @@ impl PipelineProcessorActor {
         &mut self,
         tx: TransactionWithInfo,
         events: Vec<Event>,
+        object_state_changes: Option<ProcessObjectStateChangesMessage>,
         ctx: TxContext,
     ) -> Result<()> {
         if let Some(notify_actor) = self.notify_actor.clone() {
-            return notify_actor
+            notify_actor
                 .notify(ProcessTxWithEventsMessage { tx, events, ctx })
-                .map_err(|e| anyhow!(format!("Process subscription notify error: {:?}", e)));
+                .map_err(|e| anyhow!(format!("Process subscription notify error: {:?}", e)))?;
+            if let Some(object_state_changes) = object_state_changes {
+                notify_actor
+                    .notify(object_state_changes)
+                    .map_err(|e| anyhow!(format!("Process subscription notify error: {:?}", e)))?;
+            }
         }
         Ok(())
     }
+
+    fn has_object_state_subscribers(&self) -> bool {
+        self.notify_actor.is_some()
+            && self
+                .subscription_handler
+                .as_ref()
+                .map(|handler| handler.has_object_state_subscribers())
+                .unwrap_or(false)
+    }
 }
 
 #[async_trait]
@@ -558,6 +718,17 @@ impl Handler<ExecuteL1TxMessage> for PipelineProcessorActor {
     }
 }
 
+#[async_trait]
+impl Handler<ExecuteL1TxsMessage> for PipelineProcessorActor {
+    async fn handle(
+        &mut self,
+        msg: ExecuteL1TxsMessage,
+        _ctx: &mut ActorContext,
+    ) -> Vec<Result<ExecuteTransactionResponse>> {
+        self.execute_l1_txs(msg.txs).await
+    }
+}
+
 #[async_trait]
 impl Handler<GetServiceStatusMessage> for PipelineProcessorActor {
     async fn handle(
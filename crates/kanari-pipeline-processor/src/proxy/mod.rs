@@ -3,7 +3,8 @@
 
 use crate::actor::{
     messages::{
-        ExecuteL1BlockMessage, ExecuteL1TxMessage, ExecuteL2TxMessage, GetServiceStatusMessage,
+        ExecuteL1BlockMessage, ExecuteL1TxMessage, ExecuteL1TxsMessage, ExecuteL2TxMessage,
+        GetServiceStatusMessage,
     },
     processor::PipelineProcessorActor,
 };
@@ -41,6 +42,13 @@ impl PipelineProcessorProxy {
         self.actor.send(ExecuteL1TxMessage { tx }).await?
     }
 
+    pub async fn execute_l1_txs(
+        &self,
+        txs: Vec<L1Transaction>,
+    ) -> Result<Vec<Result<ExecuteTransactionResponse>>> {
+        Ok(self.actor.send(ExecuteL1TxsMessage { txs }).await?)
+    }
+
     pub async fn get_service_status(&self) -> Result<ServiceStatus> {
         self.actor.send(GetServiceStatusMessage {}).await?
     }
@@ -350,3 +350,80 @@ impl BitcoinReorgAwareStore {
         Ok(header_info.previous_block_hash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin_client::actor::client::BitcoinClientActor;
+    use bitcoin_mock_rpc::{MockBitcoinRpcServer, MockChain};
+    use coerce::actor::{system::ActorSystem, IntoActor};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reorg_aware_store_with_mock_rpc() {
+        let server = MockBitcoinRpcServer::start(MockChain::new()).await.unwrap();
+        let old_blocks = server.chain().mine_blocks(4);
+
+        let bitcoin_client = BitcoinClientActor::new(&server.url(), "user", "password", None)
+            .unwrap()
+            .into_actor(
+                Some("bitcoin_client_for_reorg_test"),
+                &ActorSystem::global_system(),
+            )
+            .await
+            .unwrap();
+        let rpc_client = BitcoinClientProxy::new(bitcoin_client.into());
+        let block_store_dir = tempfile::tempdir().unwrap();
+        let mut store =
+            BitcoinReorgAwareStore::new(block_store_dir.path().to_path_buf(), 3, rpc_client);
+
+        // The recent blocks are filled from the previous block hash when the store is empty
+        let tip_block = server.chain().block(&old_blocks[3]).unwrap().header;
+        store
+            .insert_or_replace(4, old_blocks[3], Some(tip_block.prev_blockhash))
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .recent_blocks_map
+                .values()
+                .copied()
+                .collect::<Vec<_>>(),
+            old_blocks[1..].to_vec()
+        );
+
+        let new_blocks = server.chain().reorg(2, 3).unwrap();
+        for (height, block_hash) in (3..).zip(new_blocks.iter()) {
+            let previous_block_hash = server
+                .chain()
+                .block(block_hash)
+                .unwrap()
+                .header
+                .prev_blockhash;
+            store
+                .insert_or_replace(height, *block_hash, Some(previous_block_hash))
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            store
+                .recent_blocks_map
+                .values()
+                .copied()
+                .collect::<Vec<_>>(),
+            new_blocks.to_vec()
+        );
+
+        // The replaced blocks are kept in the store, so they can be reverted after the reorg
+        for stale_block_hash in &old_blocks[2..] {
+            let block_hex =
+                std::fs::read_to_string(block_store_dir.path().join(stale_block_hash.to_string()))
+                    .unwrap();
+            let block: Block = bitcoin::consensus::encode::deserialize_hex(&block_hex).unwrap();
+            assert_eq!(block.block_hash(), *stale_block_hash);
+        }
+        assert!(!block_store_dir
+            .path()
+            .join(old_blocks[1].to_string())
+            .exists());
+    }
+}
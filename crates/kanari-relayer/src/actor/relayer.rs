@@ -7,7 +7,8 @@ use crate::actor::messages::RelayTick;
 use crate::actor::relayer_proxy::RelayerProxy;
 use anyhow::Result;
 use async_trait::async_trait;
-use bitcoin_client::actor::client::BitcoinClientActor;
+use bitcoin_client::actor::client::BitcoinClientConfig;
+use bitcoin_client::local::LocalBlockStore;
 use bitcoin_client::proxy::BitcoinClientProxy;
 use coerce::actor::{context::ActorContext, message::Handler, Actor, LocalActorRef};
 use move_core_types::vm_status::KeptVMStatus;
@@ -23,8 +24,9 @@ use kanari_types::bitcoin::pending_block::PendingBlockModule;
 use kanari_types::error::KanariError;
 use kanari_types::multichain_id::KanariMultiChainID;
 use kanari_types::service_status::ServiceStatus;
-use kanari_types::transaction::{L1BlockWithBody, L1Transaction};
+use kanari_types::transaction::{ExecuteTransactionResponse, L1BlockWithBody, L1Transaction};
 use std::ops::Deref;
+use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
 pub struct RelayerActor {
@@ -33,6 +35,7 @@ pub struct RelayerActor {
     processor: PipelineProcessorProxy,
     ethereum_config: Option<EthereumRelayerConfig>,
     bitcoin_config: Option<BitcoinRelayerConfig>,
+    local_blocks: Option<Arc<LocalBlockStore>>,
     notify_actor: Option<LocalActorRef<NotifyActor>>,
     paused: bool,
 }
@@ -43,6 +46,7 @@ impl RelayerActor {
         processor: PipelineProcessorProxy,
         ethereum_config: Option<EthereumRelayerConfig>,
         bitcoin_config: Option<BitcoinRelayerConfig>,
+        local_blocks: Option<Arc<LocalBlockStore>>,
         notify_actor: Option<LocalActorRef<NotifyActor>>,
     ) -> Result<Self> {
         Ok(Self {
@@ -51,6 +55,7 @@ impl RelayerActor {
             processor,
             ethereum_config,
             bitcoin_config,
+            local_blocks,
             notify_actor,
             paused: false,
         })
@@ -80,12 +85,14 @@ impl RelayerActor {
         }
 
         if let Some(bitcoin_config) = &self.bitcoin_config {
-            let bitcoin_client = BitcoinClientActor::new(
-                &bitcoin_config.btc_rpc_url,
-                &bitcoin_config.btc_rpc_user_name,
-                &bitcoin_config.btc_rpc_password,
-                None, // for relayer, only store, no need to get from local
-            )?;
+            let bitcoin_client = BitcoinClientConfig {
+                btc_rpc_url: bitcoin_config.btc_rpc_url.clone(),
+                btc_rpc_user_name: bitcoin_config.btc_rpc_user_name.clone(),
+                btc_rpc_password: bitcoin_config.btc_rpc_password.clone(),
+                local_block_store_dir: None, // for relayer, only store, no need to get from local
+                local_blocks: self.local_blocks.clone(),
+            }
+            .build()?;
             let bitcoin_client_actor_ref =
                 ctx.spawn("bitcoin_client".into(), bitcoin_client).await?;
             let bitcoin_client_proxy = BitcoinClientProxy::new(bitcoin_client_actor_ref.into());
@@ -126,9 +133,17 @@ impl RelayerActor {
         Ok(())
     }
 
-    async fn handle_l1_tx(&mut self, l1_tx: L1Transaction) -> Result<()> {
-        let txid = hex::encode(&l1_tx.txid);
-        match self.processor.execute_l1_tx(l1_tx).await {
+    async fn handle_l1_txs(&mut self, l1_txs: Vec<L1Transaction>) -> Result<()> {
+        let txids: Vec<_> = l1_txs.iter().map(|tx| hex::encode(&tx.txid)).collect();
+        let results = self.processor.execute_l1_txs(l1_txs).await?;
+        for (txid, result) in txids.into_iter().zip(results) {
+            Self::handle_l1_tx_result(txid, result);
+        }
+        Ok(())
+    }
+
+    fn handle_l1_tx_result(txid: String, result: Result<ExecuteTransactionResponse>) {
+        match result {
             Ok(result) => match result.execution_info.status {
                 KeptVMStatus::Executed => {
                     info!("Relayer execute relay tx(txid: {}) success", txid);
@@ -153,8 +168,6 @@ impl RelayerActor {
                 }
             }
         }
-
-        Ok(())
     }
 
     //We migrate this function from Relayer to here
@@ -235,8 +248,8 @@ impl RelayerActor {
                 // Execute all ready l1 txs
                 match self.get_ready_l1_txs(&relayer) {
                     Ok(txs) => {
-                        for tx in txs {
-                            if let Err(err) = self.handle_l1_tx(tx).await {
+                        if !txs.is_empty() {
+                            if let Err(err) = self.handle_l1_txs(txs).await {
                                 warn!("Relayer {} error: {:?}", relayer_name, err);
                             }
                         }
@@ -1,10 +1,14 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::jsonrpc_types::btc::brc20::{BRC20BalanceView, BRC20TickInfoView};
+use crate::jsonrpc_types::btc::fee_rate::FeeRateView;
 use crate::jsonrpc_types::btc::ord::InscriptionFilterView;
+use crate::jsonrpc_types::btc::runes::{RuneBalancesFilterView, RuneFilterView};
 use crate::jsonrpc_types::btc::utxo::UTXOFilterView;
 use crate::jsonrpc_types::{
-    BytesView, IndexerStateIDView, InscriptionPageView, StrView, UTXOPageView,
+    BytesView, IndexerStateIDView, InscriptionPageView, RuneBalancesPageView, RuneEntryPageView,
+    StrView, UTXOPageView, UnitedAddressView,
 };
 use crate::RpcResult;
 use jsonrpsee::proc_macros::rpc;
@@ -36,6 +40,41 @@ pub trait BtcAPI {
         descending_order: Option<bool>,
     ) -> RpcResult<InscriptionPageView>;
 
+    /// Query the etched Runes via global index by Rune filter
+    #[method(name = "queryRunes")]
+    async fn query_runes(
+        &self,
+        filter: RuneFilterView,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<IndexerStateIDView>,
+        limit: Option<StrView<u64>>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<RuneEntryPageView>;
+
+    /// Query the Rune balances of UTXOs via global index by Rune balances filter
+    #[method(name = "queryRuneBalances")]
+    async fn query_rune_balances(
+        &self,
+        filter: RuneBalancesFilterView,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<IndexerStateIDView>,
+        limit: Option<StrView<u64>>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<RuneBalancesPageView>;
+
+    /// Query the BRC20 balances of the address, the available and transferable balances and the pending transfer inscriptions.
+    /// If the `tick` is specified, only return the balance of the tick.
+    #[method(name = "queryBRC20Balances")]
+    async fn query_brc20_balances(
+        &self,
+        owner: UnitedAddressView,
+        tick: Option<String>,
+    ) -> RpcResult<Vec<BRC20BalanceView>>;
+
+    /// Get the BRC20 tick info, the max supply, the mint limit, the decimals and the minted supply
+    #[method(name = "getBRC20TickInfo")]
+    async fn get_brc20_tick_info(&self, tick: String) -> RpcResult<Option<BRC20TickInfoView>>;
+
     /// Broadcast a Bitcoin transaction
     #[method(name = "broadcastTX")]
     async fn broadcast_tx(
@@ -44,4 +83,9 @@ pub trait BtcAPI {
         maxfeerate: Option<f64>,
         maxburnamount: Option<f64>,
     ) -> RpcResult<String>;
+
+    /// Estimate the fee rate for a Bitcoin transaction to be confirmed within `conf_target` blocks, default is 6 blocks.
+    /// Use the `estimatesmartfee` of the Bitcoin node, and fall back to the fee rates of the recent relayed blocks.
+    #[method(name = "estimateFeeRate")]
+    async fn estimate_fee_rate(&self, conf_target: Option<u16>) -> RpcResult<FeeRateView>;
 }
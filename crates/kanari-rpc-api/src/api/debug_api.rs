@@ -0,0 +1,18 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::jsonrpc_types::{H256View, TransactionTraceView};
+use crate::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use kanari_open_rpc_macros::open_rpc;
+
+#[open_rpc(namespace = "debug")]
+#[rpc(server, client, namespace = "debug")]
+#[async_trait]
+pub trait DebugAPI {
+    /// Re-execute the transaction on the state before it, and return the Move call tree,
+    /// the objects read and written, the events and the abort location.
+    /// The debug methods are only available when the server is started with `--enable-debug-rpc`.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(&self, tx_hash: H256View) -> RpcResult<TransactionTraceView>;
+}
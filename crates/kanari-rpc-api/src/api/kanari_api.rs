@@ -11,12 +11,12 @@ use crate::jsonrpc_types::{
     AccessPathView, AnnotatedFunctionResultView, BalanceInfoPageView, BytesView, EventOptions,
     EventPageView, ExecuteTransactionResponseView, FieldKeyView, FieldPageView, FunctionCallView,
     H256View, IndexerEventPageView, IndexerObjectStatePageView, IndexerStateIDView, ModuleABIView,
-    ObjectIDVecView, ObjectIDView, ObjectStateFilterView, ObjectStateView, QueryOptions,
-    KanariAddressView, StateChangeSetPageView, StateOptions, StatePageView, StrView,
-    StructTagOrObjectIDView, StructTagView, SyncStateFilterView, TransactionWithInfoPageView,
-    TxOptions,
+    ObjectIDVecView, ObjectIDView, ObjectStateChangeView, ObjectStateFilterView, ObjectStateView,
+    QueryOptions, KanariAddressView, RootStateView, StateChangeSetPageView, StateOptions,
+    StatePageView, StrView, StructTagOrObjectIDView, StructTagView, SyncStateFilterView,
+    TransactionWithInfoPageView, TxOptions,
 };
-use crate::jsonrpc_types::{DryRunTransactionResponseView, Status};
+use crate::jsonrpc_types::{DryRunOptions, DryRunTransactionResponseView, Status};
 use crate::RpcResult;
 use jsonrpsee::core::SubscriptionResult;
 use jsonrpsee::proc_macros::rpc;
@@ -44,19 +44,29 @@ pub trait KanariAPI {
         tx_option: Option<TxOptions>,
     ) -> RpcResult<ExecuteTransactionResponseView>;
 
+    /// Dry run the transaction data in bcs hex format, the transaction does not need to be signed.
+    /// If the DryRunOptions.gas_profile is true, the transaction is executed under the gas profiler
+    /// and the gas profile is returned in the response.
     #[method(name = "dryRunRawTransaction")]
-    async fn dry_run(&self, tx_bcs_hex: BytesView) -> RpcResult<DryRunTransactionResponseView>;
+    async fn dry_run(
+        &self,
+        tx_bcs_hex: BytesView,
+        dry_run_option: Option<DryRunOptions>,
+    ) -> RpcResult<DryRunTransactionResponseView>;
 
     /// Execute a read-only function call
     /// The function do not change the state of Application
+    /// If the StateOptions.state_root, at_tx_order or at_timestamp is set, the function is executed on the historical state.
     #[method(name = "executeViewFunction")]
     async fn execute_view_function(
         &self,
         function_call: FunctionCallView,
+        state_option: Option<StateOptions>,
     ) -> RpcResult<AnnotatedFunctionResultView>;
 
     /// Get the states by access_path
     /// If the StateOptions.decode is true, the state is decoded and the decoded value is returned in the response.
+    /// If the StateOptions.state_root, at_tx_order or at_timestamp is set, the states are read from the historical state.
     #[method(name = "getStates")]
     async fn get_states(
         &self,
@@ -137,12 +147,22 @@ pub trait KanariAPI {
         descending_order: Option<bool>,
     ) -> RpcResult<TransactionWithInfoPageView>;
 
+    /// Get the state root at the end of the transaction with the tx_order
+    /// Returns an error if the state of the transaction has been pruned or is unavailable.
+    #[method(name = "getStateRootByOrder")]
+    async fn get_state_root_by_order(
+        &self,
+        tx_order: StrView<u64>,
+    ) -> RpcResult<Option<RootStateView>>;
+
     /// get account balance by KanariAddress and CoinType
+    /// If the StateOptions.state_root, at_tx_order or at_timestamp is set, the balance is read from the historical state.
     #[method(name = "getBalance")]
     async fn get_balance(
         &self,
         account_addr: UnitedAddressView,
         coin_type: StructTagView,
+        state_option: Option<StateOptions>,
     ) -> RpcResult<BalanceInfoView>;
 
     /// get account balances by KanariAddress
@@ -244,4 +264,8 @@ pub trait KanariAPI {
     /// Subscribe to a stream of transaction with execution info
     #[subscription(name = "subscribeTransactions", item = TransactionWithInfoView)]
     fn subscribe_transactions(&self, filter: TransactionFilterView) -> SubscriptionResult;
+
+    /// Subscribe to a stream of object state changes
+    #[subscription(name = "subscribeObjectStates", item = ObjectStateChangeView)]
+    fn subscribe_object_states(&self, filter: ObjectStateFilterView) -> SubscriptionResult;
 }
\ No newline at end of file
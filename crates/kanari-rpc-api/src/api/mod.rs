@@ -4,6 +4,7 @@
 use jsonrpsee::RpcModule;
 
 pub mod btc_api;
+pub mod debug_api;
 pub mod kanari_api;
 
 pub const DEFAULT_RESULT_LIMIT: u64 = 50;
@@ -0,0 +1,76 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ord::InscriptionIDView;
+use crate::jsonrpc_types::{MoveStringView, StrView};
+use kanari_types::bitcoin::brc20::{BRC20BalanceInfo, BRC20CoinInfo, TransferInscription};
+use move_core_types::u256::U256;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The amounts of BRC20 are integers scaled by `10^dec`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BRC20TickInfoView {
+    pub tick: MoveStringView,
+    pub max: StrView<U256>,
+    /// The mint limit per inscription, 0 means no limit
+    pub lim: StrView<U256>,
+    pub dec: u8,
+    pub supply: StrView<U256>,
+}
+
+impl From<BRC20CoinInfo> for BRC20TickInfoView {
+    fn from(info: BRC20CoinInfo) -> Self {
+        Self {
+            tick: StrView(info.tick),
+            max: StrView(info.max),
+            lim: StrView(info.lim),
+            dec: info.dec,
+            supply: StrView(info.supply),
+        }
+    }
+}
+
+/// The transfer inscription which is inscribed but not sent yet
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BRC20TransferInscriptionView {
+    pub inscription_id: InscriptionIDView,
+    pub amt: StrView<U256>,
+}
+
+impl From<TransferInscription> for BRC20TransferInscriptionView {
+    fn from(transfer_inscription: TransferInscription) -> Self {
+        Self {
+            inscription_id: StrView(transfer_inscription.inscription_id),
+            amt: StrView(transfer_inscription.amt),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BRC20BalanceView {
+    pub tick: MoveStringView,
+    pub dec: u8,
+    /// The balance which can be inscribed as transfer inscriptions
+    pub available: StrView<U256>,
+    /// The balance locked by the transfer inscriptions
+    pub transferable: StrView<U256>,
+    /// The transfer inscriptions which are inscribed but not sent yet
+    pub transfer_inscriptions: Vec<BRC20TransferInscriptionView>,
+}
+
+impl BRC20BalanceView {
+    pub fn new(balance: BRC20BalanceInfo, dec: u8) -> Self {
+        Self {
+            tick: StrView(balance.tick),
+            dec,
+            available: StrView(balance.available),
+            transferable: StrView(balance.transferable),
+            transfer_inscriptions: balance
+                .transfer_inscriptions
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
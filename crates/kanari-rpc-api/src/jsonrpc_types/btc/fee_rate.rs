@@ -0,0 +1,42 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::jsonrpc_types::StrView;
+use bitcoin::FeeRate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRateSourceView {
+    /// Estimated by the `estimatesmartfee` of the Bitcoin node
+    EstimateSmartFee,
+    /// The median fee rate of the recent relayed blocks
+    RecentBlocks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeeRateView {
+    /// The fee rate in sat/kwu, the same unit as `bitcoin::FeeRate`
+    pub sat_per_kwu: StrView<u64>,
+    /// The fee rate in sat/vB, rounded up
+    pub sat_per_vb: StrView<u64>,
+    /// The number of blocks the estimate is for
+    pub blocks: u16,
+    pub source: FeeRateSourceView,
+}
+
+impl FeeRateView {
+    pub fn new(fee_rate: FeeRate, blocks: u16, source: FeeRateSourceView) -> Self {
+        Self {
+            sat_per_kwu: fee_rate.to_sat_per_kwu().into(),
+            sat_per_vb: fee_rate.to_sat_per_vb_ceil().into(),
+            blocks,
+            source,
+        }
+    }
+
+    pub fn fee_rate(&self) -> FeeRate {
+        FeeRate::from_sat_per_kwu(self.sat_per_kwu.0)
+    }
+}
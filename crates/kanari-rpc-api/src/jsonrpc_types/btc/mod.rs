@@ -1,6 +1,9 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod brc20;
+pub mod fee_rate;
 pub mod ord;
+pub mod runes;
 pub mod transaction;
 pub mod utxo;
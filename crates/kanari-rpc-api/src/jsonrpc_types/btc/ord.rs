@@ -112,6 +112,28 @@ pub struct InscriptionView {
     pub metaprotocol: Option<MoveStringView>,
     pub parents: Vec<InscriptionIDView>,
     pub pointer: Option<StrView<u64>>,
+    /// The delegate inscription id. If present, the body, content_encoding and content_type
+    /// are resolved from the delegate inscription.
+    pub delegate: Option<InscriptionIDView>,
+}
+
+impl InscriptionView {
+    /// Serve the content of the delegate inscription, as ord does.
+    /// The content is left as is if the delegate inscription is not indexed yet.
+    pub fn resolve_delegate(
+        &mut self,
+        delegate: InscriptionID,
+        delegate_inscription: Option<Inscription>,
+    ) {
+        self.delegate = Some(delegate.into());
+        if let Some(delegate_inscription) = delegate_inscription {
+            self.body = StrView(delegate_inscription.body);
+            self.content_encoding =
+                Option::<MoveString>::from(delegate_inscription.content_encoding).map(StrView);
+            self.content_type =
+                Option::<MoveString>::from(delegate_inscription.content_type).map(StrView);
+        }
+    }
 }
 
 impl From<Inscription> for InscriptionView {
@@ -130,6 +152,7 @@ impl From<Inscription> for InscriptionView {
             metaprotocol: Option::<MoveString>::from(inscription.metaprotocol).map(StrView),
             parents: inscription.parents.into_iter().map(Into::into).collect(),
             pointer: Option::<u64>::from(inscription.pointer).map(StrView),
+            delegate: None,
         }
     }
 }
@@ -0,0 +1,264 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::utxo::OutPointView;
+use crate::jsonrpc_types::btc::transaction::TxidView;
+use crate::jsonrpc_types::{
+    IndexerObjectStateView, IndexerStateIDView, ObjectIDVecView, ObjectMetaView, StrView,
+    UnitedAddressView,
+};
+use anyhow::Result;
+use bitcoin::hashes::Hash;
+use bitcoin::Txid;
+use moveos_types::state::{MoveState, MoveStructType};
+use kanari_types::bitcoin::runes::{self, RuneBalances, RuneEntry, RuneId, SpacedRune, Terms};
+use kanari_types::indexer::state::ObjectStateFilter;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub type RuneIdView = StrView<RuneId>;
+
+impl FromStr for RuneIdView {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(StrView(RuneId::from_str(s)?))
+    }
+}
+
+impl Display for RuneIdView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<RuneIdView> for RuneId {
+    fn from(view: RuneIdView) -> Self {
+        view.0
+    }
+}
+
+pub type SpacedRuneView = StrView<SpacedRune>;
+
+impl FromStr for SpacedRuneView {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(StrView(SpacedRune::from_str(s)?))
+    }
+}
+
+impl Display for SpacedRuneView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<SpacedRuneView> for SpacedRune {
+    fn from(view: SpacedRuneView) -> Self {
+        view.0
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuneFilterView {
+    /// Query by rune id, represent by {block}:{tx}
+    RuneId(RuneIdView),
+    /// Query by rune name, the spacers `•` or `.` are ignored
+    Rune(SpacedRuneView),
+    /// Query all.
+    All,
+}
+
+impl RuneFilterView {
+    /// The `Rune` filter needs the rune -> RuneId mapping in the state,
+    /// so the caller should resolve the rune name to the RuneId first.
+    pub fn into_global_state_filter(
+        filter: RuneFilterView,
+        resolved_rune_id: Option<RuneId>,
+    ) -> Result<ObjectStateFilter> {
+        Ok(match filter {
+            RuneFilterView::RuneId(rune_id) => {
+                ObjectStateFilter::ObjectId(vec![runes::derive_rune_entry_id(&rune_id.0)])
+            }
+            RuneFilterView::Rune(spaced_rune) => {
+                let rune_id = resolved_rune_id
+                    .ok_or_else(|| anyhow::anyhow!("Rune {} is not etched", spaced_rune.0.rune))?;
+                ObjectStateFilter::ObjectId(vec![runes::derive_rune_entry_id(&rune_id)])
+            }
+            RuneFilterView::All => ObjectStateFilter::ObjectType(RuneEntry::struct_tag()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TermsView {
+    pub amount: Option<StrView<u128>>,
+    pub cap: Option<StrView<u128>>,
+    pub height: (Option<StrView<u64>>, Option<StrView<u64>>),
+    pub offset: (Option<StrView<u64>>, Option<StrView<u64>>),
+}
+
+impl From<Terms> for TermsView {
+    fn from(terms: Terms) -> Self {
+        TermsView {
+            amount: Option::<u128>::from(terms.amount).map(StrView),
+            cap: Option::<u128>::from(terms.cap).map(StrView),
+            height: (
+                Option::<u64>::from(terms.height_start).map(StrView),
+                Option::<u64>::from(terms.height_end).map(StrView),
+            ),
+            offset: (
+                Option::<u64>::from(terms.offset_start).map(StrView),
+                Option::<u64>::from(terms.offset_end).map(StrView),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RuneEntryView {
+    pub id: RuneIdView,
+    pub spaced_rune: SpacedRuneView,
+    pub number: StrView<u64>,
+    pub divisibility: u8,
+    pub symbol: Option<char>,
+    pub premine: StrView<u128>,
+    pub terms: Option<TermsView>,
+    pub mints: StrView<u128>,
+    pub burned: StrView<u128>,
+    /// The txid of the etching transaction
+    pub etching: TxidView,
+    pub turbo: bool,
+}
+
+impl From<RuneEntry> for RuneEntryView {
+    fn from(entry: RuneEntry) -> Self {
+        RuneEntryView {
+            id: entry.id.into(),
+            spaced_rune: entry.spaced_rune().into(),
+            number: StrView(entry.number),
+            divisibility: entry.divisibility,
+            symbol: entry.symbol(),
+            premine: StrView(entry.premine),
+            terms: Option::<Terms>::from(entry.terms).map(TermsView::from),
+            mints: StrView(entry.mints),
+            burned: StrView(entry.burned),
+            etching: Txid::from_byte_array(entry.etching.into_bytes()).into(),
+            turbo: entry.turbo,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RuneEntryStateView {
+    #[serde(flatten)]
+    pub metadata: ObjectMetaView,
+    pub value: RuneEntryView,
+    #[serde(flatten)]
+    pub indexer_id: IndexerStateIDView,
+}
+
+impl TryFrom<IndexerObjectStateView> for RuneEntryStateView {
+    type Error = anyhow::Error;
+
+    fn try_from(state: IndexerObjectStateView) -> Result<Self, Self::Error> {
+        let entry = RuneEntry::from_bytes(&state.value.0)?;
+        Ok(RuneEntryStateView {
+            metadata: state.metadata,
+            value: entry.into(),
+            indexer_id: state.indexer_id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuneBalancesFilterView {
+    /// Query by owner, support kanari address and bitcoin address
+    Owner(UnitedAddressView),
+    /// Query by bitcoin outpoint, represent by bitcoin txid and vout
+    OutPoint { txid: String, vout: u32 },
+    /// Query by object ids.
+    ObjectId(ObjectIDVecView),
+    /// Query all.
+    All,
+}
+
+impl RuneBalancesFilterView {
+    pub fn into_global_state_filter(filter: RuneBalancesFilterView) -> Result<ObjectStateFilter> {
+        Ok(match filter {
+            RuneBalancesFilterView::Owner(owner) => ObjectStateFilter::ObjectTypeWithOwner {
+                object_type: RuneBalances::struct_tag(),
+                filter_out: false,
+                owner: owner.0.kanari_address.into(),
+            },
+            RuneBalancesFilterView::OutPoint { txid, vout } => {
+                let outpoint = OutPointView {
+                    txid: Txid::from_str(&txid)?.into(),
+                    vout,
+                };
+                let balances_id = runes::derive_rune_balances_id(&outpoint.into());
+                ObjectStateFilter::ObjectId(vec![balances_id])
+            }
+            RuneBalancesFilterView::ObjectId(object_id_vec_view) => {
+                ObjectStateFilter::ObjectId(object_id_vec_view.into())
+            }
+            RuneBalancesFilterView::All => {
+                ObjectStateFilter::ObjectType(RuneBalances::struct_tag())
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RuneBalanceView {
+    pub id: RuneIdView,
+    pub amount: StrView<u128>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RuneBalancesView {
+    pub outpoint: OutPointView,
+    pub balances: Vec<RuneBalanceView>,
+}
+
+impl From<RuneBalances> for RuneBalancesView {
+    fn from(rune_balances: RuneBalances) -> Self {
+        RuneBalancesView {
+            outpoint: rune_balances.outpoint.into(),
+            balances: rune_balances
+                .balances
+                .data
+                .into_iter()
+                .map(|element| RuneBalanceView {
+                    id: element.key.into(),
+                    amount: StrView(element.value),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RuneBalancesStateView {
+    #[serde(flatten)]
+    pub metadata: ObjectMetaView,
+    pub value: RuneBalancesView,
+    #[serde(flatten)]
+    pub indexer_id: IndexerStateIDView,
+}
+
+impl TryFrom<IndexerObjectStateView> for RuneBalancesStateView {
+    type Error = anyhow::Error;
+
+    fn try_from(state: IndexerObjectStateView) -> Result<Self, Self::Error> {
+        let rune_balances = RuneBalances::from_bytes(&state.value.0)?;
+        Ok(RuneBalancesStateView {
+            metadata: state.metadata,
+            value: rune_balances.into(),
+            indexer_id: state.indexer_id,
+        })
+    }
+}
@@ -227,6 +227,34 @@ pub struct RawTransactionOutputView {
 pub struct DryRunTransactionResponseView {
     pub raw_output: RawTransactionOutputView,
     pub vm_error_info: VMErrorInfo,
+    /// The gas profile of the transaction, only returned when `DryRunOptions.gas_profile` is true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_profile: Option<GasProfileView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GasProfileEntryView {
+    /// The name of the function or instruction.
+    pub name: String,
+    /// The number of times the function is called or the instruction is executed.
+    pub hits: StrView<u64>,
+    pub gas_used: StrView<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GasProfileView {
+    /// The gas used by the execution, include the gas used by the IO of the execution.
+    pub execution_gas_used: StrView<u64>,
+    /// The gas used by the storage, such as the transaction size.
+    pub storage_gas_used: StrView<u64>,
+    /// The gas used by each Move function itself, not include the functions it calls.
+    /// Sorted by the gas used, from high to low.
+    pub functions: Vec<GasProfileEntryView>,
+    /// The gas used by each instruction and native function, sorted by the gas used, from high to low.
+    pub instructions: Vec<GasProfileEntryView>,
+    /// The flamegraph of the execution in SVG format, only returned when `DryRunOptions.flamegraph` is true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flamegraph: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
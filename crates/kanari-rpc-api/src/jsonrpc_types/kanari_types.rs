@@ -2,11 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::event_view::IndexerEventIDView;
-use super::{
-    HumanReadableDisplay, IndexerStateIDView, ObjectIDView, StateChangeSetWithTxOrderView,
-};
+use super::{HumanReadableDisplay, IndexerStateIDView, ObjectIDView, StateChangeSetWithTxOrderView};
 use crate::jsonrpc_types::account_view::BalanceInfoView;
 use crate::jsonrpc_types::btc::ord::InscriptionStateView;
+use crate::jsonrpc_types::btc::runes::{RuneBalancesStateView, RuneEntryStateView};
 use crate::jsonrpc_types::btc::utxo::UTXOStateView;
 use crate::jsonrpc_types::event_view::{EventView, IndexerEventView};
 use crate::jsonrpc_types::field_view::IndexerFieldView;
@@ -36,6 +35,8 @@ pub type IndexerObjectStatePageView = PageView<IndexerObjectStateView, IndexerSt
 
 pub type UTXOPageView = PageView<UTXOStateView, IndexerStateIDView>;
 pub type InscriptionPageView = PageView<InscriptionStateView, IndexerStateIDView>;
+pub type RuneEntryPageView = PageView<RuneEntryStateView, IndexerStateIDView>;
+pub type RuneBalancesPageView = PageView<RuneBalancesStateView, IndexerStateIDView>;
 pub type StateChangeSetPageView = PageView<StateChangeSetWithTxOrderView, StrView<u64>>;
 
 pub type FieldPageView = PageView<IndexerFieldView, StrView<u64>>;
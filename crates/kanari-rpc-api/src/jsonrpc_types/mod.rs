@@ -16,6 +16,7 @@ mod status;
 #[cfg(test)]
 mod tests;
 mod transaction_argument_view;
+mod transaction_trace_view;
 
 pub mod account_view;
 pub mod decimal_value_view;
@@ -40,4 +41,5 @@ pub use rpc_options::*;
 pub use state_view::*;
 pub use status::*;
 pub use str_view::*;
-pub use transaction_argument_view::*;
\ No newline at end of file
+pub use transaction_argument_view::*;
+pub use transaction_trace_view::*;
\ No newline at end of file
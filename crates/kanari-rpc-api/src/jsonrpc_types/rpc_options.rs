@@ -1,7 +1,7 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::jsonrpc_types::H256View;
+use crate::jsonrpc_types::{H256View, StrView};
 use moveos_types::h256::H256;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,12 @@ pub struct StateOptions {
     pub show_display: bool,
     /// The state root of remote stateDB
     pub state_root: Option<H256View>,
+    /// Read the state at the end of the transaction with this tx_order.
+    /// Ignored if `state_root` is set.
+    pub at_tx_order: Option<StrView<u64>>,
+    /// Read the state at the end of the last transaction at or before this timestamp, in milliseconds.
+    /// Ignored if `state_root` or `at_tx_order` is set, and requires the indexer.
+    pub at_timestamp: Option<StrView<u64>>,
 }
 
 impl StateOptions {
@@ -41,6 +47,16 @@ impl StateOptions {
         }
         self
     }
+
+    pub fn at_tx_order(mut self, tx_order: Option<u64>) -> Self {
+        self.at_tx_order = tx_order.map(StrView);
+        self
+    }
+
+    pub fn at_timestamp(mut self, timestamp: Option<u64>) -> Self {
+        self.at_timestamp = timestamp.map(StrView);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
@@ -82,6 +98,33 @@ impl TxOptions {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DryRunOptions {
+    /// If true, the transaction is executed under the gas profiler,
+    /// and the gas used by every function and instruction is returned in the response.
+    pub gas_profile: bool,
+    /// If true, the flamegraph SVG of the gas profile is returned in the response.
+    /// Only valid when gas_profile is true.
+    pub flamegraph: bool,
+}
+
+impl DryRunOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn gas_profile(mut self, gas_profile: bool) -> Self {
+        self.gas_profile = gas_profile;
+        self
+    }
+
+    pub fn flamegraph(mut self, flamegraph: bool) -> Self {
+        self.flamegraph = flamegraph;
+        self
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq)]
 #[serde(rename_all = "camelCase", default)]
 pub struct QueryOptions {
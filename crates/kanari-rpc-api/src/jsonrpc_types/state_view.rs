@@ -8,6 +8,8 @@ use super::{
 };
 use anyhow::Result;
 use move_core_types::effects::Op;
+use move_core_types::language_storage::TypeTag;
+use moveos_types::h256::H256;
 use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::state::{FieldKey, ObjectChange};
 use moveos_types::state_resolver::StateKV;
@@ -16,6 +18,7 @@ use moveos_types::{
     state::{AnnotatedState, ObjectState, StateChangeSet},
 };
 use kanari_types::indexer::state::{IndexerStateID, ObjectStateFilter};
+use kanari_types::indexer::Filter;
 use kanari_types::state::{StateChangeSetWithTxOrder, SyncStateFilter};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -430,6 +433,30 @@ impl ObjectStateFilterView {
     }
 }
 
+impl ObjectStateFilterView {
+    fn try_matches(&self, item: &ObjectStateChangeView) -> Result<bool> {
+        let metadata = &item.state.metadata;
+        let match_type = |object_type: &StructTagView| match &metadata.object_type.0 {
+            TypeTag::Struct(struct_tag) => struct_tag.as_ref() == &object_type.0,
+            _ => false,
+        };
+        Ok(match self {
+            ObjectStateFilterView::ObjectTypeWithOwner { object_type, owner } => {
+                match_type(object_type) && owner.0.kanari_address == metadata.owner.0
+            }
+            ObjectStateFilterView::ObjectType(object_type) => match_type(object_type),
+            ObjectStateFilterView::Owner(owner) => owner.0.kanari_address == metadata.owner.0,
+            ObjectStateFilterView::ObjectId(object_ids) => object_ids.0.contains(&metadata.id),
+        })
+    }
+}
+
+impl Filter<ObjectStateChangeView> for ObjectStateFilterView {
+    fn matches(&self, item: &ObjectStateChangeView) -> bool {
+        self.try_matches(item).unwrap_or_default()
+    }
+}
+
 /// Object state view. Used as return type of `getObjectStates`.
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ObjectStateView {
@@ -496,6 +523,40 @@ impl From<AnnotatedState> for ObjectStateView {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectChangeTypeView {
+    New,
+    Modify,
+    Delete,
+}
+
+/// Object state change view. Used as the item type of `subscribeObjectStates`.
+/// The state of a deleted object only carries the metadata before deletion.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectStateChangeView {
+    pub tx_order: StrView<u64>,
+    pub tx_hash: H256View,
+    pub change_type: ObjectChangeTypeView,
+    pub state: ObjectStateView,
+}
+
+impl ObjectStateChangeView {
+    pub fn new(
+        tx_order: u64,
+        tx_hash: H256,
+        change_type: ObjectChangeTypeView,
+        state: ObjectStateView,
+    ) -> Self {
+        Self {
+            tx_order: tx_order.into(),
+            tx_hash: tx_hash.into(),
+            change_type,
+            state,
+        }
+    }
+}
+
 impl HumanReadableDisplay for ObjectStateView {
     fn to_human_readable_string(&self, verbose: bool, indent: usize) -> String {
         self.metadata.to_human_readable_string(verbose, indent)
@@ -133,6 +133,16 @@ pub struct KanariStatus {
     pub root_state: RootStateView,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct RpcLimitsView {
+    /// The max number of requests in a batch request
+    pub max_batch_size: StrView<u64>,
+    /// The max page size of the paginated methods, a larger `limit` is rejected
+    pub max_page_size: StrView<u64>,
+    /// The max size of a response in bytes, pages are shortened to fit in this size
+    pub max_response_bytes: StrView<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Status {
     /// The status of the rpc service
@@ -141,4 +151,7 @@ pub struct Status {
     pub kanari_status: KanariStatus,
     /// The status of the Bitcoin chain
     pub bitcoin_status: BitcoinStatus,
+    /// The request and response limits of the rpc service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_limits: Option<RpcLimitsView>,
 }
@@ -0,0 +1,61 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    BytesView, H256View, KeptVMStatusView, ObjectIDView, StateChangeSetView, StrView, StructTagView,
+};
+use moveos_types::moveos_std::event::TransactionEvent;
+use moveos_types::transaction::VMErrorInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CallTraceView {
+    /// The function name with the type arguments.
+    pub function: String,
+    pub args: Vec<String>,
+    /// The return values of the call, it is empty if the call aborted.
+    pub return_values: Vec<String>,
+    pub is_native: bool,
+    /// The execution gas used by the call, include the calls it made.
+    pub gas_used: StrView<u64>,
+    pub calls: Vec<CallTraceView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionEventView {
+    pub event_type: StructTagView,
+    pub event_data: BytesView,
+    pub event_index: StrView<u64>,
+}
+
+impl From<TransactionEvent> for TransactionEventView {
+    fn from(event: TransactionEvent) -> Self {
+        Self {
+            event_type: event.event_type.into(),
+            event_data: StrView(event.event_data),
+            event_index: StrView(event.event_index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionTraceView {
+    pub tx_hash: H256View,
+    pub tx_order: StrView<u64>,
+    /// The state root which the transaction is re-executed on, it is the state root after the previous transaction.
+    pub pre_state_root: H256View,
+    /// The status of the re-execution, it contains the abort location and code if the transaction aborted.
+    pub status: KeptVMStatusView,
+    pub gas_used: StrView<u64>,
+    /// The Move call tree of the transaction, the innermost call is where it aborted if the transaction aborted.
+    pub call_trace: CallTraceView,
+    /// The objects read by the transaction.
+    pub read_objects: Vec<ObjectIDView>,
+    /// The objects written by the transaction.
+    pub changeset: StateChangeSetView,
+    pub events: Vec<TransactionEventView>,
+    /// The error message and the stack trace of the execution, only returned if the transaction failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_error_info: Option<VMErrorInfo>,
+}
@@ -7,6 +7,7 @@ pub mod api;
 pub mod jsonrpc_types;
 
 pub type RpcResult<T> = Result<T, RpcError>;
+use jsonrpsee::types::error::OVERSIZED_RESPONSE_CODE;
 use jsonrpsee::types::{ErrorCode, ErrorObject, ErrorObjectOwned};
 use kanari_types::error::KanariError;
 use thiserror::Error;
@@ -27,6 +28,19 @@ pub enum RpcError {
 
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
+
+    #[error("State is unavailable: {0}")]
+    StateUnavailable(String),
+
+    #[error("Response is larger than the max response size {max_response_bytes} bytes")]
+    ResponseTooLarge {
+        max_response_bytes: usize,
+        /// The cursor to skip the item which is too large to be returned
+        next_cursor: Option<serde_json::Value>,
+    },
+
+    #[error("The limit {limit} exceeds the max page size {max_page_size}")]
+    PageSizeTooLarge { limit: u64, max_page_size: usize },
 }
 
 impl From<RpcError> for ErrorObjectOwned {
@@ -41,6 +55,34 @@ impl From<RpcError> for ErrorObjectOwned {
             RpcError::InternalError(err) => ErrorObject::owned(2, err.to_string(), None::<()>),
             RpcError::BcsError(err) => ErrorObject::owned(3, err.to_string(), None::<()>),
             RpcError::UnexpectedError(err) => ErrorObject::owned(4, err.to_string(), None::<()>),
+            RpcError::StateUnavailable(err) => {
+                ErrorObject::owned(5, format!("State is unavailable: {}", err), None::<()>)
+            }
+            RpcError::ResponseTooLarge {
+                max_response_bytes,
+                next_cursor,
+            } => ErrorObject::owned(
+                OVERSIZED_RESPONSE_CODE,
+                format!(
+                    "Response is larger than the max response size {} bytes",
+                    max_response_bytes
+                ),
+                Some(serde_json::json!({
+                    "max_response_bytes": max_response_bytes,
+                    "next_cursor": next_cursor,
+                })),
+            ),
+            RpcError::PageSizeTooLarge {
+                limit,
+                max_page_size,
+            } => ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                format!(
+                    "The limit {} exceeds the max page size {}",
+                    limit, max_page_size
+                ),
+                Some(serde_json::json!({ "max_page_size": max_page_size })),
+            ),
         }
     }
 }
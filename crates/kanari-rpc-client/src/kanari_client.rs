@@ -12,25 +12,29 @@ use moveos_types::moveos_std::object::ObjectID;
 use moveos_types::state::{FieldKey, MoveStructState};
 use moveos_types::{access_path::AccessPath, state::ObjectState, transaction::FunctionCall};
 use kanari_rpc_api::api::btc_api::BtcAPIClient;
+use kanari_rpc_api::api::debug_api::DebugAPIClient;
 use kanari_rpc_api::api::kanari_api::KanariAPIClient;
+use kanari_rpc_api::jsonrpc_types::btc::brc20::{BRC20BalanceView, BRC20TickInfoView};
+use kanari_rpc_api::jsonrpc_types::btc::fee_rate::FeeRateView;
 use kanari_rpc_api::jsonrpc_types::btc::ord::{InscriptionFilterView, InscriptionObjectView};
+use kanari_rpc_api::jsonrpc_types::btc::runes::{RuneBalancesFilterView, RuneFilterView};
 use kanari_rpc_api::jsonrpc_types::btc::utxo::{UTXOFilterView, UTXOObjectView};
 use kanari_rpc_api::jsonrpc_types::transaction_view::TransactionFilterView;
 use kanari_rpc_api::jsonrpc_types::{
     account_view::BalanceInfoView, transaction_view::TransactionWithInfoView, InscriptionPageView,
-    Status, StructTagOrObjectIDView, UTXOPageView,
+    RootStateView, RuneBalancesPageView, RuneEntryPageView, Status, StructTagOrObjectIDView,
+    UTXOPageView,
 };
 use kanari_rpc_api::jsonrpc_types::{
     AccessPathView, AnnotatedFunctionResultView, BalanceInfoPageView, BytesView, EventOptions,
     EventPageView, FieldKeyView, ObjectIDVecView, ObjectIDView, KanariAddressView, StateOptions,
-    StatePageView, StructTagView,
+    StatePageView, StructTagView, UnitedAddressView,
 };
 use kanari_rpc_api::jsonrpc_types::{ExecuteTransactionResponseView, ObjectStateView};
+use kanari_rpc_api::jsonrpc_types::{IndexerObjectStatePageView, ObjectStateFilterView, QueryOptions};
 use kanari_rpc_api::jsonrpc_types::{
-    IndexerObjectStatePageView, ObjectStateFilterView, QueryOptions,
-};
-use kanari_rpc_api::jsonrpc_types::{
-    StateChangeSetPageView, StrView, SyncStateFilterView, TransactionWithInfoPageView, TxOptions,
+    StateChangeSetPageView, StrView, SyncStateFilterView, TransactionTraceView,
+    TransactionWithInfoPageView, TxOptions,
 };
 use kanari_types::address::BitcoinAddress;
 use kanari_types::bitcoin::multisign_account::MultisignAccountInfo;
@@ -74,7 +78,7 @@ impl KanariRpcClient {
         function_call: FunctionCall,
     ) -> Result<AnnotatedFunctionResultView> {
         self.http
-            .execute_view_function(function_call.into())
+            .execute_view_function(function_call.into(), None)
             .await
             .map_err(|e| anyhow::anyhow!(e))
     }
@@ -149,6 +153,10 @@ impl KanariRpcClient {
             .await?)
     }
 
+    pub async fn get_state_root_by_order(&self, tx_order: u64) -> Result<Option<RootStateView>> {
+        Ok(self.http.get_state_root_by_order(tx_order.into()).await?)
+    }
+
     pub async fn query_transactions(
         &self,
         filter: TransactionFilterView,
@@ -177,6 +185,11 @@ impl KanariRpcClient {
             .await?)
     }
 
+    /// Trace the transaction via the debug RPC, the server should enable the debug RPC.
+    pub async fn trace_transaction(&self, tx_hash: H256) -> Result<TransactionTraceView> {
+        Ok(self.http.trace_transaction(tx_hash.into()).await?)
+    }
+
     pub async fn get_sequence_number(&self, sender: KanariAddress) -> Result<u64> {
         Ok(self
             .get_states(
@@ -294,7 +307,7 @@ impl KanariRpcClient {
     ) -> Result<BalanceInfoView> {
         Ok(self
             .http
-            .get_balance(account_addr.into(), coin_type)
+            .get_balance(account_addr.into(), coin_type, None)
             .await?)
     }
 
@@ -382,6 +395,54 @@ impl KanariRpcClient {
             .await?)
     }
 
+    pub async fn query_runes(
+        &self,
+        filter: RuneFilterView,
+        cursor: Option<IndexerStateID>,
+        limit: Option<u64>,
+        descending_order: Option<bool>,
+    ) -> Result<RuneEntryPageView> {
+        Ok(self
+            .http
+            .query_runes(
+                filter,
+                cursor.map(Into::into),
+                limit.map(Into::into),
+                descending_order,
+            )
+            .await?)
+    }
+
+    pub async fn query_rune_balances(
+        &self,
+        filter: RuneBalancesFilterView,
+        cursor: Option<IndexerStateID>,
+        limit: Option<u64>,
+        descending_order: Option<bool>,
+    ) -> Result<RuneBalancesPageView> {
+        Ok(self
+            .http
+            .query_rune_balances(
+                filter,
+                cursor.map(Into::into),
+                limit.map(Into::into),
+                descending_order,
+            )
+            .await?)
+    }
+
+    pub async fn query_brc20_balances(
+        &self,
+        owner: UnitedAddressView,
+        tick: Option<String>,
+    ) -> Result<Vec<BRC20BalanceView>> {
+        Ok(self.http.query_brc20_balances(owner, tick).await?)
+    }
+
+    pub async fn get_brc20_tick_info(&self, tick: String) -> Result<Option<BRC20TickInfoView>> {
+        Ok(self.http.get_brc20_tick_info(tick).await?)
+    }
+
     pub async fn get_resource<T: MoveStructState>(
         &self,
         account: KanariAddress,
@@ -427,6 +488,10 @@ impl KanariRpcClient {
             .await?)
     }
 
+    pub async fn estimate_bitcoin_fee_rate(&self, conf_target: Option<u16>) -> Result<FeeRateView> {
+        Ok(self.http.estimate_fee_rate(conf_target).await?)
+    }
+
     pub async fn get_utxo_object(&self, utxo_obj_id: ObjectID) -> Result<Option<UTXOObjectView>> {
         let objects = self.get_object_states(vec![utxo_obj_id], None).await?;
         let obj_state = objects.into_iter().next().flatten();
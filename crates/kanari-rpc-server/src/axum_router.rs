@@ -1,35 +1,44 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::service::api_key::{extract_api_key, ApiKeyStore};
+use crate::service::error::TOO_MANY_REQUESTS_MSG;
 use crate::service::metrics::{ServiceMetrics, TransportProtocol};
+use crate::service::rpc_limits::RpcLimits;
 use axum::extract::{ConnectInfo, Query, State};
 use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::Response;
 use axum::Json;
 use futures::{Stream, StreamExt};
+use governor::clock::{Clock, DefaultClock};
+use governor::middleware::StateInformationMiddleware;
 use jsonrpsee::server::RandomIntegerIdProvider;
-use jsonrpsee::types::{ErrorCode, ErrorObject, Id, InvalidRequest, Params, Request};
+use jsonrpsee::types::error::reject_too_big_batch_request;
+use jsonrpsee::types::{
+    ErrorCode, ErrorObject, ErrorObjectOwned, Id, InvalidRequest, Params, Request,
+};
 use jsonrpsee::{
-    core::server::Methods, BoundedSubscriptions, ConnectionId, MethodCallback, MethodKind,
-    MethodResponse, MethodSink,
+    core::server::BatchResponseBuilder, core::server::Methods, BoundedSubscriptions, ConnectionId,
+    MethodCallback, MethodKind, MethodResponse, MethodSink,
 };
 use kanari_notify::subscription_handler::SubscriptionHandler;
 use kanari_rpc_api::jsonrpc_types::event_view::EventFilterView;
 use kanari_rpc_api::jsonrpc_types::transaction_view::TransactionFilterView;
+use kanari_rpc_api::jsonrpc_types::ObjectStateFilterView;
 use serde::Deserialize;
 use serde_json::value::RawValue;
 use std::convert::Infallible;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::time::Instant;
 use tokio_stream::wrappers::ReceiverStream;
+use tower_governor::governor::SharedRateLimiter;
+use tower_governor::key_extractor::{KeyExtractor, SmartIpKeyExtractor};
 
-pub const MAX_RESPONSE_SIZE: u32 = 2 << 30;
-
-pub const NOT_SUPPORTED_CODE: i32 = 32005;
-pub const NOT_SUPPORTED_MSG: &str = "Requests are not supported by this server";
+/// The per-IP rate limiter shared with the `GovernorLayer`.
+pub type IpRateLimiter = SharedRateLimiter<IpAddr, StateInformationMiddleware>;
 
 #[derive(Debug, Clone)]
 pub(crate) struct CallData<'a> {
@@ -46,6 +55,9 @@ pub struct JsonRpcService {
     metrics: ServiceMetrics,
     id_provider: Arc<RandomIntegerIdProvider>,
     subscription_handler: Arc<SubscriptionHandler>,
+    api_keys: Arc<ApiKeyStore>,
+    limits: RpcLimits,
+    rate_limiter: IpRateLimiter,
 }
 
 impl JsonRpcService {
@@ -53,21 +65,91 @@ impl JsonRpcService {
         methods: Methods,
         metrics: ServiceMetrics,
         subscription_handler: Arc<SubscriptionHandler>,
+        api_keys: Arc<ApiKeyStore>,
+        limits: RpcLimits,
+        rate_limiter: IpRateLimiter,
     ) -> Self {
         Self {
             methods,
             metrics,
             id_provider: Arc::new(RandomIntegerIdProvider),
             subscription_handler,
+            api_keys,
+            limits,
+            rate_limiter,
         }
     }
 
+    /// The `GovernorLayer` charges the per-IP rate limit once per HTTP request, so the other
+    /// entries of a batch are charged here, returns the error response if the limit is exceeded.
+    fn check_rate_limit(&self, client: &IpAddr, id: Id<'_>) -> Option<MethodResponse> {
+        match self.rate_limiter.check_key(client) {
+            Ok(_) => None,
+            Err(not_until) => {
+                let wait_time = not_until
+                    .wait_time_from(DefaultClock::default().now())
+                    .as_secs();
+                Some(MethodResponse::error(
+                    id,
+                    ErrorObject::owned(
+                        ErrorCode::ServerIsBusy.code(),
+                        format!("{}{}s", TOO_MANY_REQUESTS_MSG, wait_time),
+                        None::<()>,
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Check the API key and the method quota of the request, returns the error response if rejected.
+    fn check_api_key(&self, api_key: Option<&str>, req: &Request<'_>) -> Option<MethodResponse> {
+        if !self.api_keys.is_enabled() {
+            return None;
+        }
+        match self.api_keys.check(api_key, req.method.as_ref()) {
+            Ok(_) => None,
+            Err(rejection) => {
+                self.metrics.on_api_key_rejection(
+                    rejection.key_label(),
+                    req.method.as_ref(),
+                    rejection.reason(),
+                );
+                Some(MethodResponse::error(
+                    req.id.clone(),
+                    ErrorObjectOwned::from(rejection),
+                ))
+            }
+        }
+    }
+
+    /// Check the length of a batch request, returns the error response if it is empty or too large.
+    fn check_batch_size(&self, len: usize) -> Option<MethodResponse> {
+        if len == 0 {
+            Some(MethodResponse::error(
+                Id::Null,
+                ErrorObject::from(ErrorCode::InvalidRequest),
+            ))
+        } else if len > self.limits.max_batch_size {
+            Some(MethodResponse::error(
+                Id::Null,
+                reject_too_big_batch_request(self.limits.max_batch_size),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The responses of a batch can not exceed `max_response_bytes` in total.
+    fn batch_response_builder(&self) -> BatchResponseBuilder {
+        BatchResponseBuilder::new_with_limit(self.limits.max_response_bytes)
+    }
+
     fn call_data(&self) -> CallData<'_> {
         CallData {
             methods: &self.methods,
             metrics: &self.metrics,
             request_start: self.metrics.on_request(TransportProtocol::Http),
-            max_response_body_size: MAX_RESPONSE_SIZE,
+            max_response_body_size: self.limits.max_response_body_size(),
         }
     }
 
@@ -79,7 +161,7 @@ impl JsonRpcService {
         ws::WsCallData {
             metrics: &self.metrics,
             methods: &self.methods,
-            max_response_body_size: MAX_RESPONSE_SIZE,
+            max_response_body_size: self.limits.max_response_body_size(),
             request_start: self.metrics.on_request(TransportProtocol::Http),
             bounded_subscriptions,
             id_provider: &*self.id_provider,
@@ -135,21 +217,62 @@ pub async fn json_rpc_handler(
     ok_response(response.to_result())
 }
 
+/// The client IP of the request, extracted in the same way as the `GovernorLayer`.
+fn client_ip(headers: &HeaderMap, client_addr: SocketAddr) -> Option<IpAddr> {
+    let mut request = http::Request::new(());
+    *request.headers_mut() = headers.clone();
+    request.extensions_mut().insert(ConnectInfo(client_addr));
+    SmartIpKeyExtractor.extract(&request).ok()
+}
+
 async fn process_raw_request(
     service: &JsonRpcService,
     raw_request: &str,
-    _: SocketAddr,
-    _: HeaderMap,
+    client_addr: SocketAddr,
+    headers: HeaderMap,
 ) -> MethodResponse {
+    let api_key = extract_api_key(&headers);
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
+        if let Some(response) = service.check_api_key(api_key.as_deref(), &request) {
+            return response;
+        }
         let response: MethodResponse = process_request(request, service.call_data()).await;
 
         response
-    } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
-            Id::Null,
-            ErrorObject::borrowed(NOT_SUPPORTED_CODE, NOT_SUPPORTED_MSG, None),
-        )
+    } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+        if let Some(response) = service.check_batch_size(batch.len()) {
+            return response;
+        }
+        let client = client_ip(&headers, client_addr);
+        let mut batch_response = service.batch_response_builder();
+        for (index, raw_request) in batch.into_iter().enumerate() {
+            let response = match serde_json::from_str::<Request>(raw_request.get()) {
+                Ok(request) => {
+                    // The first entry is charged by the `GovernorLayer` with the HTTP request
+                    let rate_limited = match client.as_ref() {
+                        Some(client) if index > 0 => {
+                            service.check_rate_limit(client, request.id.clone())
+                        }
+                        _ => None,
+                    };
+                    match rate_limited
+                        .or_else(|| service.check_api_key(api_key.as_deref(), &request))
+                    {
+                        Some(response) => response,
+                        None => process_request(request, service.call_data()).await,
+                    }
+                }
+                Err(_) => {
+                    let (id, code) = prepare_error(raw_request.get());
+                    MethodResponse::error(id, ErrorObject::from(code))
+                }
+            };
+            // The whole batch is rejected if the responses exceed the limit
+            if let Err(too_big) = batch_response.append(&response) {
+                return too_big;
+            }
+        }
+        MethodResponse::from_batch(batch_response.finish())
     } else {
         let (id, code) = prepare_error(raw_request);
         MethodResponse::error(id, ErrorObject::from(code))
@@ -285,15 +408,25 @@ pub mod ws {
     pub async fn ws_json_rpc_upgrade(
         ws: WebSocketUpgrade,
         State(service): State<JsonRpcService>,
+        ConnectInfo(client_addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
     ) -> Response {
-        ws.on_upgrade(|ws| ws_json_rpc_handler(ws, service))
+        // The API key of the upgrade request applies to all requests of the connection
+        let api_key = extract_api_key(&headers);
+        let client = client_ip(&headers, client_addr);
+        ws.on_upgrade(move |ws| ws_json_rpc_handler(ws, service, api_key, client))
     }
 
-    async fn ws_json_rpc_handler(mut socket: WebSocket, service: JsonRpcService) {
+    async fn ws_json_rpc_handler(
+        mut socket: WebSocket,
+        service: JsonRpcService,
+        api_key: Option<String>,
+        client: Option<IpAddr>,
+    ) {
         // #[allow(clippy::disallowed_methods)]
         let (tx, mut rx) = mpsc::channel(100);
 
-        let mut sink = MethodSink::new_with_limit(tx, MAX_RESPONSE_SIZE);
+        let mut sink = MethodSink::new_with_limit(tx, service.limits.max_response_body_size());
         let bounded_subscriptions = BoundedSubscriptions::new(100);
 
         loop {
@@ -303,7 +436,7 @@ pub mod ws {
                         match message {
                             Message::Text(msg) => {
                                 let response =
-                                    process_raw_request(&service, &msg, bounded_subscriptions.clone(), &sink).await;
+                                    process_raw_request(&service, &msg, api_key.as_deref(), client.as_ref(), bounded_subscriptions.clone(), &sink).await;
                                 if let Some(response) = response {
                                     let _ = sink.try_send(response.to_result());
                                 }
@@ -333,19 +466,63 @@ pub mod ws {
         }
     }
 
+    /// The `GovernorLayer` only charges the per-IP rate limit for the upgrade request, so every
+    /// request of the connection, including every entry of a batch, is charged here.
+    fn check_request(
+        service: &JsonRpcService,
+        request: &Request,
+        api_key: Option<&str>,
+        client: Option<&IpAddr>,
+    ) -> Option<MethodResponse> {
+        client
+            .and_then(|client| service.check_rate_limit(client, request.id.clone()))
+            .or_else(|| service.check_api_key(api_key, request))
+    }
+
     async fn process_raw_request(
         service: &JsonRpcService,
         raw_request: &str,
+        api_key: Option<&str>,
+        client: Option<&IpAddr>,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &MethodSink,
     ) -> Option<MethodResponse> {
         if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
+            if let Some(response) = check_request(service, &request, api_key, client) {
+                return Some(response);
+            }
             process_request(request, service.ws_call_data(bounded_subscriptions, sink)).await
-        } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-            Some(MethodResponse::error(
-                Id::Null,
-                ErrorObject::borrowed(NOT_SUPPORTED_CODE, NOT_SUPPORTED_MSG, None),
-            ))
+        } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+            if let Some(response) = service.check_batch_size(batch.len()) {
+                return Some(response);
+            }
+            let mut batch_response = service.batch_response_builder();
+            for raw_request in batch {
+                let response = match serde_json::from_str::<Request>(raw_request.get()) {
+                    Ok(request) => match check_request(service, &request, api_key, client) {
+                        Some(response) => Some(response),
+                        None => {
+                            let call = service.ws_call_data(bounded_subscriptions.clone(), sink);
+                            process_request(request, call).await
+                        }
+                    },
+                    Err(_) => {
+                        let (id, code) = prepare_error(raw_request.get());
+                        Some(MethodResponse::error(id, ErrorObject::from(code)))
+                    }
+                };
+                // Subscriptions in a batch respond via the sink
+                if let Some(response) = response {
+                    if let Err(too_big) = batch_response.append(&response) {
+                        return Some(too_big);
+                    }
+                }
+            }
+            if batch_response.is_empty() {
+                None
+            } else {
+                Some(MethodResponse::from_batch(batch_response.finish()))
+            }
         } else {
             let (id, code) = prepare_error(raw_request);
             Some(MethodResponse::error(id, ErrorObject::from(code)))
@@ -552,3 +729,8 @@ create_sse_handler!(
     subscribe_transactions
 );
 create_sse_handler!(sse_events_handler, EventFilterView, subscribe_events);
+create_sse_handler!(
+    sse_object_states_handler,
+    ObjectStateFilterView,
+    subscribe_object_states
+);
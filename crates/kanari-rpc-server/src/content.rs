@@ -0,0 +1,606 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serve the inscription content over HTTP, in the spirit of `ord`'s server.
+//!
+//! `/content/{inscription_id}` serves the raw body with the stored content type and encoding,
+//! `/preview/{inscription_id}` wraps the content in a minimal html page by its media type.
+//! If the inscription has a delegate, the content of the delegate inscription is served.
+
+use crate::service::rpc_service::RpcService;
+use anyhow::Result;
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use jsonrpsee::core::async_trait;
+use kanari_types::bitcoin::ord::{Inscription, InscriptionID};
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::error;
+
+/// The path of the inscription content endpoint.
+pub const CONTENT_PATH: &str = "/content/:inscription_id";
+/// The path of the inscription preview endpoint.
+pub const PREVIEW_PATH: &str = "/preview/:inscription_id";
+
+/// The inscription content is immutable, so it can be cached as long as ord does.
+const CACHE_CONTROL: &str = "public, max-age=1209600, immutable";
+const CONTENT_SECURITY_POLICY: &str =
+    "default-src 'self' 'unsafe-eval' 'unsafe-inline' data: blob:";
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+const BROTLI_ENCODING: &str = "br";
+
+/// Read the inscriptions and their delegates for the content service.
+#[async_trait]
+pub trait InscriptionReader: Send + Sync {
+    async fn get_inscriptions(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<Inscription>>>;
+
+    async fn get_inscription_delegates(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<InscriptionID>>>;
+}
+
+#[async_trait]
+impl InscriptionReader for RpcService {
+    async fn get_inscriptions(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<Inscription>>> {
+        RpcService::get_inscriptions(self, inscription_ids).await
+    }
+
+    async fn get_inscription_delegates(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<InscriptionID>>> {
+        RpcService::get_inscription_delegates(self, inscription_ids).await
+    }
+}
+
+#[derive(Clone)]
+pub struct ContentService {
+    reader: Arc<dyn InscriptionReader>,
+}
+
+impl ContentService {
+    pub fn new<R: InscriptionReader + 'static>(reader: R) -> Self {
+        Self {
+            reader: Arc::new(reader),
+        }
+    }
+
+    /// Get the inscription whose content should be served,
+    /// it is the delegate inscription if the inscription has a delegate.
+    async fn get_content_inscription(
+        &self,
+        inscription_id: InscriptionID,
+    ) -> Result<Option<Inscription>> {
+        let delegate = self
+            .reader
+            .get_inscription_delegates(&[inscription_id])
+            .await?
+            .pop()
+            .flatten();
+        if let Some(delegate) = delegate {
+            // The delegating inscription should exist even if it has no content itself
+            if self.get_inscription(inscription_id).await?.is_none() {
+                return Ok(None);
+            }
+            return self.get_inscription(delegate).await;
+        }
+        self.get_inscription(inscription_id).await
+    }
+
+    async fn get_inscription(&self, inscription_id: InscriptionID) -> Result<Option<Inscription>> {
+        Ok(self
+            .reader
+            .get_inscriptions(&[inscription_id])
+            .await?
+            .pop()
+            .flatten())
+    }
+
+    async fn content(&self, inscription_id: &str) -> Result<Inscription, Response> {
+        let inscription_id = InscriptionID::from_str(inscription_id).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid inscription id: {}", e),
+            )
+                .into_response()
+        })?;
+        let inscription = self
+            .get_content_inscription(inscription_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get inscription {}: {:?}", inscription_id, e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            })?;
+        match inscription {
+            Some(inscription)
+                if !inscription.body.is_empty() || inscription.content_type().is_some() =>
+            {
+                Ok(inscription)
+            }
+            _ => Err((
+                StatusCode::NOT_FOUND,
+                format!("Inscription {} content not found", inscription_id),
+            )
+                .into_response()),
+        }
+    }
+}
+
+pub async fn content_handler(
+    State(service): State<ContentService>,
+    Path(inscription_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match service.content(&inscription_id).await {
+        Ok(inscription) => content_response(inscription, &headers),
+        Err(response) => response,
+    }
+}
+
+pub async fn preview_handler(
+    State(service): State<ContentService>,
+    Path(inscription_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let inscription = match service.content(&inscription_id).await {
+        Ok(inscription) => inscription,
+        Err(response) => return response,
+    };
+    let content_url = format!("/content/{}", inscription_id);
+    let html = match Media::from_content_type(inscription.content_type()) {
+        // The html is sandboxed by the content security policy, so serve it as is.
+        Media::Iframe => return content_response(inscription, &headers),
+        Media::Image => format!(
+            r#"<img src="{}" alt="" style="width:100%;height:100%;object-fit:contain;image-rendering:pixelated">"#,
+            content_url
+        ),
+        Media::Audio => format!(r#"<audio controls src="{}"></audio>"#, content_url),
+        Media::Video => format!(
+            r#"<video controls autoplay loop muted src="{}"></video>"#,
+            content_url
+        ),
+        Media::Text => {
+            let body = match decoded_body(inscription) {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            format!(
+                "<pre>{}</pre>",
+                escape_html(&String::from_utf8_lossy(&body))
+            )
+        }
+        Media::Unknown => "<p>Preview unavailable</p>".to_owned(),
+    };
+    let mut response = Html(format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"></head><body style=\"margin:0\">{}</body></html>",
+        html
+    ))
+    .into_response();
+    set_cache_headers(response.headers_mut());
+    response
+}
+
+fn content_response(inscription: Inscription, headers: &HeaderMap) -> Response {
+    let content_type = inscription
+        .content_type()
+        .unwrap_or(DEFAULT_CONTENT_TYPE)
+        .to_owned();
+    let content_encoding = inscription.content_encoding().map(str::to_owned);
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(&content_type)
+            .unwrap_or_else(|_| HeaderValue::from_static(DEFAULT_CONTENT_TYPE)),
+    );
+    let body = match content_encoding {
+        Some(encoding) if accepts_encoding(headers, &encoding) => {
+            match HeaderValue::from_str(&encoding) {
+                Ok(value) => {
+                    response_headers.insert(header::CONTENT_ENCODING, value);
+                    inscription.body
+                }
+                Err(_) => return not_acceptable(&encoding),
+            }
+        }
+        Some(_) => match decoded_body(inscription) {
+            Ok(body) => body,
+            Err(response) => return response,
+        },
+        None => inscription.body,
+    };
+    set_cache_headers(&mut response_headers);
+    (response_headers, body).into_response()
+}
+
+/// Decode the body if the client can not accept the content encoding, only brotli is supported, as ord.
+fn decoded_body(inscription: Inscription) -> Result<Vec<u8>, Response> {
+    match inscription.content_encoding() {
+        None => Ok(inscription.body),
+        Some(encoding) if encoding.eq_ignore_ascii_case(BROTLI_ENCODING) => {
+            let mut decompressed = Vec::new();
+            brotli::Decompressor::new(inscription.body.as_slice(), 4096)
+                .read_to_end(&mut decompressed)
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Failed to decompress the content: {}", e),
+                    )
+                        .into_response()
+                })?;
+            Ok(decompressed)
+        }
+        Some(encoding) => Err(not_acceptable(encoding)),
+    }
+}
+
+fn not_acceptable(encoding: &str) -> Response {
+    (
+        StatusCode::NOT_ACCEPTABLE,
+        format!(
+            "Inscription content encoding `{}` is not acceptable",
+            encoding
+        ),
+    )
+        .into_response()
+}
+
+fn set_cache_headers(headers: &mut HeaderMap) {
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(CACHE_CONTROL),
+    );
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(CONTENT_SECURITY_POLICY),
+    );
+}
+
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let coding = parts.next()?;
+            let rejected = parts.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .map(|q| q == 0.0)
+                    .unwrap_or(false)
+            });
+            (!rejected).then_some(coding)
+        })
+        .any(|coding| coding == "*" || coding.eq_ignore_ascii_case(encoding))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Media {
+    Audio,
+    Iframe,
+    Image,
+    Text,
+    Unknown,
+    Video,
+}
+
+impl Media {
+    fn from_content_type(content_type: Option<&str>) -> Self {
+        let Some(content_type) = content_type else {
+            return Media::Unknown;
+        };
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "text/html" | "image/svg+xml" => Media::Iframe,
+            "application/json" | "application/yaml" | "application/x-javascript" => Media::Text,
+            _ if mime.starts_with("image/") => Media::Image,
+            _ if mime.starts_with("audio/") => Media::Audio,
+            _ if mime.starts_with("video/") => Media::Video,
+            _ if mime.starts_with("text/") => Media::Text,
+            _ => Media::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use kanari_types::bitcoin::ord::SatPoint;
+    use kanari_types::bitcoin::types::OutPoint;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::move_std::string::MoveString;
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    #[derive(Default)]
+    struct MockReader {
+        inscriptions: HashMap<InscriptionID, Inscription>,
+        delegates: HashMap<InscriptionID, InscriptionID>,
+    }
+
+    #[async_trait]
+    impl InscriptionReader for MockReader {
+        async fn get_inscriptions(
+            &self,
+            inscription_ids: &[InscriptionID],
+        ) -> Result<Vec<Option<Inscription>>> {
+            Ok(inscription_ids
+                .iter()
+                .map(|id| self.inscriptions.get(id).cloned())
+                .collect())
+        }
+
+        async fn get_inscription_delegates(
+            &self,
+            inscription_ids: &[InscriptionID],
+        ) -> Result<Vec<Option<InscriptionID>>> {
+            Ok(inscription_ids
+                .iter()
+                .map(|id| self.delegates.get(id).copied())
+                .collect())
+        }
+    }
+
+    fn inscription_id(n: u8) -> InscriptionID {
+        InscriptionID {
+            txid: AccountAddress::new([n; AccountAddress::LENGTH]),
+            index: 0,
+        }
+    }
+
+    fn inscription(
+        id: InscriptionID,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+        content_encoding: Option<&str>,
+    ) -> Inscription {
+        Inscription {
+            id,
+            location: SatPoint {
+                outpoint: OutPoint::new(id.txid, 0),
+                offset: 0,
+            },
+            sequence_number: 0,
+            inscription_number: 0,
+            is_cursed: false,
+            charms: 0,
+            body,
+            content_encoding: content_encoding.map(MoveString::from).into(),
+            content_type: content_type.map(MoveString::from).into(),
+            metadata: vec![],
+            metaprotocol: None.into(),
+            parents: vec![],
+            pointer: None.into(),
+            rune: None.into(),
+        }
+    }
+
+    fn router(reader: MockReader) -> Router {
+        let service = ContentService::new(reader);
+        Router::new()
+            .route(
+                CONTENT_PATH,
+                get(content_handler).with_state(service.clone()),
+            )
+            .route(PREVIEW_PATH, get(preview_handler).with_state(service))
+    }
+
+    async fn request(router: Router, uri: String, accept_encoding: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+        router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    fn brotli_compress(data: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        brotli::CompressorReader::new(data, 4096, 11, 22)
+            .read_to_end(&mut compressed)
+            .unwrap();
+        compressed
+    }
+
+    fn headers(accept_encoding: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_str(accept_encoding).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_accepts_encoding() {
+        assert!(accepts_encoding(&headers("gzip, deflate, br"), "br"));
+        assert!(accepts_encoding(&headers("gzip;q=1.0, BR;q=0.5"), "br"));
+        assert!(accepts_encoding(&headers("*"), "br"));
+        assert!(!accepts_encoding(&headers("gzip, br;q=0"), "br"));
+        assert!(!accepts_encoding(&headers("gzip"), "br"));
+        assert!(!accepts_encoding(&HeaderMap::new(), "br"));
+    }
+
+    #[test]
+    fn test_media_from_content_type() {
+        assert_eq!(
+            Media::from_content_type(Some("text/plain;charset=utf-8")),
+            Media::Text
+        );
+        assert_eq!(Media::from_content_type(Some("text/html")), Media::Iframe);
+        assert_eq!(
+            Media::from_content_type(Some("image/svg+xml")),
+            Media::Iframe
+        );
+        assert_eq!(Media::from_content_type(Some("image/png")), Media::Image);
+        assert_eq!(Media::from_content_type(Some("video/mp4")), Media::Video);
+        assert_eq!(Media::from_content_type(Some("audio/mpeg")), Media::Audio);
+        assert_eq!(
+            Media::from_content_type(Some("application/wasm")),
+            Media::Unknown
+        );
+        assert_eq!(Media::from_content_type(None), Media::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_content_headers() {
+        let id = inscription_id(1);
+        let mut reader = MockReader::default();
+        reader.inscriptions.insert(
+            id,
+            inscription(
+                id,
+                b"hello".to_vec(),
+                Some("text/plain;charset=utf-8"),
+                None,
+            ),
+        );
+        let response = request(router(reader), format!("/content/{}", id), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers[header::CONTENT_TYPE], "text/plain;charset=utf-8");
+        assert_eq!(headers[header::CACHE_CONTROL], CACHE_CONTROL);
+        assert_eq!(
+            headers[header::CONTENT_SECURITY_POLICY],
+            CONTENT_SECURITY_POLICY
+        );
+        assert!(headers.get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(body_bytes(response).await, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_content_delegate() {
+        let delegate_id = inscription_id(1);
+        let id = inscription_id(2);
+        let orphan_id = inscription_id(3);
+        let mut reader = MockReader::default();
+        reader.inscriptions.insert(
+            delegate_id,
+            inscription(
+                delegate_id,
+                b"<svg></svg>".to_vec(),
+                Some("image/svg+xml"),
+                None,
+            ),
+        );
+        reader
+            .inscriptions
+            .insert(id, inscription(id, vec![], None, None));
+        reader.delegates.insert(id, delegate_id);
+        reader.delegates.insert(orphan_id, delegate_id);
+        let router = router(reader);
+
+        let response = request(router.clone(), format!("/content/{}", id), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert_eq!(body_bytes(response).await, b"<svg></svg>");
+
+        // The delegating inscription does not exist
+        let response = request(router, format!("/content/{}", orphan_id), None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_content_brotli() {
+        let id = inscription_id(1);
+        let body = b"hello hello hello hello".to_vec();
+        let compressed = brotli_compress(&body);
+        let mut reader = MockReader::default();
+        reader.inscriptions.insert(
+            id,
+            inscription(id, compressed.clone(), Some("text/plain"), Some("br")),
+        );
+        let router = router(reader);
+
+        let response = request(router.clone(), format!("/content/{}", id), Some("gzip, br")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(body_bytes(response).await, compressed);
+
+        // The client does not accept brotli, the content is decoded
+        let response = request(router.clone(), format!("/content/{}", id), Some("gzip")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+        assert_eq!(body_bytes(response).await, body);
+
+        let response = request(router, format!("/preview/{}", id), Some("gzip")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let html = String::from_utf8(body_bytes(response).await).unwrap();
+        assert!(html.contains("<pre>hello hello hello hello</pre>"));
+    }
+
+    #[tokio::test]
+    async fn test_content_not_found() {
+        let router = router(MockReader::default());
+        let response = request(
+            router.clone(),
+            format!("/content/{}", inscription_id(1)),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = request(
+            router.clone(),
+            format!("/preview/{}", inscription_id(1)),
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = request(router, "/content/invalid".to_owned(), None).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<script>alert("x&y")</script>"#),
+            "&lt;script&gt;alert(&quot;x&amp;y&quot;)&lt;/script&gt;"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! A read-only GraphQL API served on the same axum router as JSON-RPC.
+//!
+//! The resolvers reuse the JSON-RPC server implementations, so the GraphQL API
+//! reads from the same `IndexerReader` and `StateResolver` backends and keeps
+//! the same page size limits.
+
+mod query;
+mod types;
+
+use crate::server::btc_server::BtcServer;
+use crate::server::kanari_server::KanariServer;
+use crate::service::aggregate_service::AggregateService;
+use crate::service::rpc_limits::RpcLimits;
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use axum::extract::State;
+use axum::response::Html;
+use axum::Json;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use kanari_rpc_api::api::DEFAULT_RESULT_LIMIT_USIZE;
+use kanari_rpc_api::jsonrpc_types::StrView;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use query::QueryRoot;
+
+pub type KanariSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The path of the GraphQL endpoint.
+pub const GRAPHQL_PATH: &str = "/graphql";
+/// The default max depth of a GraphQL query.
+pub const DEFAULT_GRAPHQL_MAX_DEPTH: usize = 12;
+/// The default max complexity of a GraphQL query.
+/// Every connection field multiplies the complexity of its children by the page size.
+pub const DEFAULT_GRAPHQL_MAX_COMPLEXITY: usize = 10_000;
+
+pub(crate) struct GraphQLContext {
+    kanari_server: KanariServer,
+    btc_server: BtcServer,
+    aggregate_service: AggregateService,
+    limits: RpcLimits,
+}
+
+pub fn build_schema(
+    kanari_server: KanariServer,
+    btc_server: BtcServer,
+    aggregate_service: AggregateService,
+    limits: RpcLimits,
+    max_depth: usize,
+    max_complexity: usize,
+) -> KanariSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(GraphQLContext {
+            kanari_server,
+            btc_server,
+            aggregate_service,
+            limits,
+        })
+        .limit_depth(max_depth)
+        .limit_complexity(max_complexity)
+        .finish()
+}
+
+pub async fn graphql_handler(
+    State(schema): State<KanariSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+pub async fn graphiql_handler() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// Cursors are opaque to clients, they are the base64 encoded json of the JSON-RPC cursor.
+pub(crate) fn encode_cursor<C: Serialize>(cursor: &C) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).expect("cursor should serialize"))
+}
+
+pub(crate) fn decode_cursor<C: DeserializeOwned>(
+    cursor: Option<String>,
+) -> async_graphql::Result<Option<C>> {
+    cursor
+        .map(|cursor| {
+            let bytes = URL_SAFE_NO_PAD
+                .decode(cursor.as_bytes())
+                .map_err(|_| async_graphql::Error::new("invalid cursor"))?;
+            serde_json::from_slice(&bytes).map_err(|_| async_graphql::Error::new("invalid cursor"))
+        })
+        .transpose()
+}
+
+/// The page size of a connection field, `first` is limited by the `max_page_size` of the JSON-RPC
+/// limits, so the GraphQL API can not return larger pages than the JSON-RPC API.
+pub(crate) fn page_size(limits: &RpcLimits, first: Option<i32>) -> async_graphql::Result<usize> {
+    match first {
+        Some(first) if first < 0 => Err(async_graphql::Error::new(
+            "`first` must be a non-negative number",
+        )),
+        first => Ok(limits.limit_of(first.map(|first| StrView(first as u64)))?),
+    }
+}
+
+/// The complexity of a connection field is the page size times the complexity of a node.
+/// The complexity is computed before the resolvers run and can not read the `RpcLimits` of the
+/// context, so the requested `first` is used as is: a `first` above `max_page_size` is rejected by
+/// `page_size`, and the default page size is never larger than `DEFAULT_RESULT_LIMIT_USIZE`.
+pub(crate) fn page_complexity(first: Option<i32>, child_complexity: usize) -> usize {
+    let size = first
+        .map(|first| first.max(0) as usize)
+        .unwrap_or(DEFAULT_RESULT_LIMIT_USIZE);
+    size.max(1).saturating_mul(child_complexity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_size() {
+        let limits = RpcLimits::new(None, Some(10), None);
+        assert_eq!(page_size(&limits, None).unwrap(), 10);
+        assert_eq!(page_size(&limits, Some(5)).unwrap(), 5);
+        assert_eq!(page_size(&limits, Some(10)).unwrap(), 10);
+        assert!(page_size(&limits, Some(11)).is_err());
+        assert!(page_size(&limits, Some(-1)).is_err());
+    }
+}
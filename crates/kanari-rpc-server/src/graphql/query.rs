@@ -0,0 +1,243 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::types::{
+    node_field_selected, query_events, Balance, Event, Inscription, ObjectState, Transaction, Utxo,
+};
+use super::{decode_cursor, encode_cursor, page_complexity, page_size, GraphQLContext};
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Json, Object, Result};
+use kanari_rpc_api::api::btc_api::BtcAPIServer;
+use kanari_rpc_api::api::kanari_api::KanariAPIServer;
+use kanari_rpc_api::jsonrpc_types::btc::ord::InscriptionFilterView;
+use kanari_rpc_api::jsonrpc_types::btc::utxo::UTXOFilterView;
+use kanari_rpc_api::jsonrpc_types::{
+    event_view::EventFilterView, transaction_view::TransactionFilterView, H256View,
+    IndexerStateIDView, ObjectIDVecView, ObjectStateFilterView, QueryOptions, StateOptions,
+    StrView, UnitedAddressView,
+};
+use kanari_types::indexer::state::IndexerStateID;
+use std::str::FromStr;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn chain_id(&self, ctx: &Context<'_>) -> Result<String> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        Ok(context.kanari_server.get_chain_id().await?.to_string())
+    }
+
+    /// Get a transaction by the transaction hash.
+    async fn transaction(&self, ctx: &Context<'_>, tx_hash: String) -> Result<Option<Transaction>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let tx_hash = H256View::from_str(tx_hash.as_str())?;
+        Ok(context
+            .kanari_server
+            .get_transactions_by_hash(vec![tx_hash])
+            .await?
+            .pop()
+            .flatten()
+            .map(Transaction))
+    }
+
+    /// Query transactions via the indexer, the filter is the JSON-RPC `TransactionFilterView`,
+    /// all transactions are returned if the filter is not set.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<Json<TransactionFilterView>>,
+        first: Option<i32>,
+        after: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Transaction>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<StrView<u64>> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+        let filter = filter
+            .map(|filter| filter.0)
+            .unwrap_or(TransactionFilterView::All);
+        let query_option = QueryOptions::default().descending(descending.unwrap_or(true));
+
+        let page = context
+            .kanari_server
+            .query_transactions(
+                filter,
+                cursor,
+                Some(StrView(limit as u64)),
+                Some(query_option),
+            )
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, page.has_next_page);
+        connection.edges.extend(page.data.into_iter().map(|tx| {
+            Edge::new(
+                encode_cursor(&tx.transaction.sequence_info.tx_order),
+                Transaction(tx),
+            )
+        }));
+        Ok(connection)
+    }
+
+    /// Query events via the indexer, the filter is the JSON-RPC `EventFilterView`.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Json<EventFilterView>,
+        first: Option<i32>,
+        after: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Event>> {
+        query_events(ctx, filter.0, first, after, descending).await
+    }
+
+    /// Get an object by the object id, read from the state tree.
+    async fn object(&self, ctx: &Context<'_>, object_id: String) -> Result<Option<ObjectState>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let object_ids = ObjectIDVecView::from_str(object_id.as_str())?;
+        let decode = node_field_selected(ctx, "decodedValue");
+        Ok(context
+            .kanari_server
+            .get_object_states(object_ids, Some(StateOptions::new().decode(decode)))
+            .await?
+            .pop()
+            .flatten()
+            .map(ObjectState))
+    }
+
+    /// Query objects via the indexer, the filter is the JSON-RPC `ObjectStateFilterView`.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn objects(
+        &self,
+        ctx: &Context<'_>,
+        filter: Json<ObjectStateFilterView>,
+        first: Option<i32>,
+        after: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, ObjectState>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<IndexerStateIDView> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+        let mut query_option = QueryOptions::default().descending(descending.unwrap_or(true));
+        query_option.decode = node_field_selected(ctx, "decodedValue");
+
+        let page = context
+            .kanari_server
+            .query_object_states(
+                filter.0,
+                cursor,
+                Some(StrView(limit as u64)),
+                Some(query_option),
+            )
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, page.has_next_page);
+        connection.edges.extend(
+            page.data
+                .into_iter()
+                .map(|state| Edge::new(encode_cursor(&state.indexer_id), ObjectState::from(state))),
+        );
+        Ok(connection)
+    }
+
+    /// Get the coin balances of the account.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn balances(
+        &self,
+        ctx: &Context<'_>,
+        owner: String,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Balance>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let owner = UnitedAddressView::from_str(owner.as_str())?;
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<IndexerStateIDView> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+
+        let mut data = context
+            .aggregate_service
+            .get_balances(owner.into(), cursor.map(IndexerStateID::from), limit + 1)
+            .await?;
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        connection
+            .edges
+            .extend(data.into_iter().filter_map(|(key, balance_info)| {
+                key.map(|key| {
+                    Edge::new(
+                        encode_cursor(&IndexerStateIDView::from(key)),
+                        Balance(balance_info),
+                    )
+                })
+            }));
+        Ok(connection)
+    }
+
+    /// Query Bitcoin UTXOs via the indexer, the filter is the JSON-RPC `UTXOFilterView`.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn utxos(
+        &self,
+        ctx: &Context<'_>,
+        filter: Json<UTXOFilterView>,
+        first: Option<i32>,
+        after: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Utxo>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<IndexerStateIDView> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+
+        let page = context
+            .btc_server
+            .query_utxos(filter.0, cursor, Some(StrView(limit as u64)), descending)
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, page.has_next_page);
+        connection.edges.extend(
+            page.data
+                .into_iter()
+                .map(|utxo| Edge::new(encode_cursor(&utxo.indexer_id), Utxo(utxo))),
+        );
+        Ok(connection)
+    }
+
+    /// Query Bitcoin inscriptions via the indexer, the filter is the JSON-RPC `InscriptionFilterView`.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn inscriptions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Json<InscriptionFilterView>,
+        first: Option<i32>,
+        after: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Inscription>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<IndexerStateIDView> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+
+        let page = context
+            .btc_server
+            .query_inscriptions(filter.0, cursor, Some(StrView(limit as u64)), descending)
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, page.has_next_page);
+        connection
+            .edges
+            .extend(page.data.into_iter().map(|inscription| {
+                Edge::new(
+                    encode_cursor(&inscription.indexer_id),
+                    Inscription(inscription),
+                )
+            }));
+        Ok(connection)
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{decode_cursor, encode_cursor, page_complexity, page_size, GraphQLContext};
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Json, Object, Result};
+use kanari_rpc_api::api::kanari_api::KanariAPIServer;
+use kanari_rpc_api::jsonrpc_types::btc::ord::InscriptionStateView;
+use kanari_rpc_api::jsonrpc_types::btc::utxo::UTXOStateView;
+use kanari_rpc_api::jsonrpc_types::{
+    account_view::BalanceInfoView,
+    event_view::{EventFilterView, IndexerEventView},
+    transaction_view::TransactionWithInfoView,
+    IndexerObjectStateView, ObjectStateView, QueryOptions, StateKVView, StateOptions, StrView,
+};
+
+/// Whether `field` is selected on the nodes of the current connection field, or on the field itself,
+/// used to only decode the values which are requested by the query.
+pub(crate) fn node_field_selected(ctx: &Context<'_>, field: &str) -> bool {
+    let look_ahead = ctx.look_ahead();
+    look_ahead
+        .field("edges")
+        .field("node")
+        .field(field)
+        .exists()
+        || look_ahead.field("nodes").field(field).exists()
+        || look_ahead.field(field).exists()
+}
+
+pub struct Transaction(pub TransactionWithInfoView);
+
+#[Object]
+impl Transaction {
+    async fn tx_order(&self) -> String {
+        self.0.transaction.sequence_info.tx_order.to_string()
+    }
+
+    async fn tx_hash(&self) -> Option<String> {
+        self.0
+            .execution_info
+            .as_ref()
+            .map(|info| info.tx_hash.to_string())
+    }
+
+    async fn tx_timestamp(&self) -> String {
+        self.0.transaction.sequence_info.tx_timestamp.to_string()
+    }
+
+    async fn tx_accumulator_root(&self) -> String {
+        self.0
+            .transaction
+            .sequence_info
+            .tx_accumulator_root
+            .to_string()
+    }
+
+    /// The sender of the transaction, only L2 transactions have a sender.
+    async fn sender(&self) -> Result<Option<String>> {
+        Ok(self
+            .0
+            .transaction
+            .sender()?
+            .map(|sender| sender.to_string()))
+    }
+
+    async fn state_root(&self) -> Option<String> {
+        self.0
+            .execution_info
+            .as_ref()
+            .map(|info| info.state_root.to_string())
+    }
+
+    async fn gas_used(&self) -> Option<String> {
+        self.0
+            .execution_info
+            .as_ref()
+            .map(|info| info.gas_used.to_string())
+    }
+
+    /// The `KeptVMStatus` of the transaction, in the same json format as JSON-RPC.
+    async fn status(&self) -> Option<Json<serde_json::Value>> {
+        self.0
+            .execution_info
+            .as_ref()
+            .and_then(|info| serde_json::to_value(&info.status).ok())
+            .map(Json)
+    }
+
+    /// The transaction data, in the same json format as JSON-RPC.
+    async fn data(&self) -> Result<Json<serde_json::Value>> {
+        Ok(Json(serde_json::to_value(&self.0.transaction.data)?))
+    }
+
+    /// Events emitted by the transaction.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Event>> {
+        let Some(execution_info) = self.0.execution_info.as_ref() else {
+            return Ok(Connection::new(false, false));
+        };
+        query_events(
+            ctx,
+            EventFilterView::TxHash(execution_info.tx_hash),
+            first,
+            after,
+            Some(false),
+        )
+        .await
+    }
+}
+
+pub struct Event(pub IndexerEventView);
+
+#[Object]
+impl Event {
+    async fn tx_order(&self) -> String {
+        self.0.indexer_event_id.tx_order.to_string()
+    }
+
+    async fn event_index(&self) -> String {
+        self.0.indexer_event_id.event_index.to_string()
+    }
+
+    async fn event_handle_id(&self) -> String {
+        self.0.event_id.event_handle_id.to_string()
+    }
+
+    async fn event_seq(&self) -> String {
+        self.0.event_id.event_seq.to_string()
+    }
+
+    async fn event_type(&self) -> String {
+        self.0.event_type.to_string()
+    }
+
+    /// The bcs bytes of the event, in hex.
+    async fn event_data(&self) -> String {
+        hex::encode(&self.0.event_data.0)
+    }
+
+    async fn decoded_event_data(&self) -> Option<Json<serde_json::Value>> {
+        self.0.decoded_event_data.clone().map(Json)
+    }
+
+    async fn tx_hash(&self) -> String {
+        self.0.tx_hash.to_string()
+    }
+
+    async fn sender(&self) -> String {
+        self.0.sender.to_string()
+    }
+
+    async fn created_at(&self) -> String {
+        self.0.created_at.to_string()
+    }
+}
+
+pub struct ObjectState(pub ObjectStateView);
+
+impl From<IndexerObjectStateView> for ObjectState {
+    fn from(state: IndexerObjectStateView) -> Self {
+        Self(ObjectStateView {
+            metadata: state.metadata,
+            value: state.value,
+            decoded_value: state.decoded_value,
+            display_fields: state.display_fields,
+        })
+    }
+}
+
+#[Object(name = "Object")]
+impl ObjectState {
+    async fn id(&self) -> String {
+        self.0.metadata.id.to_string()
+    }
+
+    async fn owner(&self) -> String {
+        self.0.metadata.owner.to_string()
+    }
+
+    async fn owner_bitcoin_address(&self) -> Option<String> {
+        self.0.metadata.owner_bitcoin_address.clone()
+    }
+
+    async fn object_type(&self) -> String {
+        self.0.metadata.object_type.to_string()
+    }
+
+    async fn flag(&self) -> u8 {
+        self.0.metadata.flag
+    }
+
+    async fn state_root(&self) -> Option<String> {
+        self.0.metadata.state_root.map(|root| root.to_string())
+    }
+
+    /// The number of dynamic fields of the object.
+    async fn size(&self) -> String {
+        self.0.metadata.size.to_string()
+    }
+
+    async fn created_at(&self) -> String {
+        self.0.metadata.created_at.to_string()
+    }
+
+    async fn updated_at(&self) -> String {
+        self.0.metadata.updated_at.to_string()
+    }
+
+    /// The bcs bytes of the object value, in hex.
+    async fn value(&self) -> String {
+        hex::encode(&self.0.value.0)
+    }
+
+    async fn decoded_value(&self) -> Option<Json<serde_json::Value>> {
+        self.0.decoded_value.clone().map(Json)
+    }
+
+    /// The dynamic fields of the object, read from the state tree.
+    #[graphql(complexity = "page_complexity(first, child_complexity)")]
+    async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, DynamicField>> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+        let limit = page_size(&context.limits, first)?;
+        let cursor: Option<String> = decode_cursor(after)?;
+        let has_previous_page = cursor.is_some();
+        let look_ahead = ctx.look_ahead();
+        let decode = look_ahead
+            .field("edges")
+            .field("node")
+            .field("value")
+            .field("decodedValue")
+            .exists()
+            || look_ahead
+                .field("nodes")
+                .field("value")
+                .field("decodedValue")
+                .exists();
+        let page = context
+            .kanari_server
+            .list_field_states(
+                self.0.metadata.id.clone().into(),
+                cursor,
+                Some(StrView(limit as u64)),
+                Some(StateOptions::new().decode(decode)),
+            )
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, page.has_next_page);
+        connection.edges.extend(page.data.into_iter().map(|field| {
+            Edge::new(
+                encode_cursor(&field.field_key.to_string()),
+                DynamicField(field),
+            )
+        }));
+        Ok(connection)
+    }
+}
+
+pub struct DynamicField(pub StateKVView);
+
+#[Object]
+impl DynamicField {
+    async fn field_key(&self) -> String {
+        self.0.field_key.to_string()
+    }
+
+    async fn value(&self) -> ObjectState {
+        ObjectState(self.0.state.clone())
+    }
+}
+
+pub struct Balance(pub BalanceInfoView);
+
+#[Object]
+impl Balance {
+    async fn coin_type(&self) -> String {
+        self.0.coin_info.coin_type.to_string()
+    }
+
+    async fn name(&self) -> String {
+        self.0.coin_info.name.clone()
+    }
+
+    async fn symbol(&self) -> String {
+        self.0.coin_info.symbol.clone()
+    }
+
+    async fn icon_url(&self) -> Option<String> {
+        self.0.coin_info.icon_url.clone()
+    }
+
+    async fn decimals(&self) -> u8 {
+        self.0.coin_info.decimals
+    }
+
+    async fn supply(&self) -> String {
+        self.0.coin_info.supply.to_string()
+    }
+
+    async fn balance(&self) -> String {
+        self.0.balance.to_string()
+    }
+}
+
+pub struct Utxo(pub UTXOStateView);
+
+#[Object(name = "UTXO")]
+impl Utxo {
+    async fn id(&self) -> String {
+        self.0.metadata.id.to_string()
+    }
+
+    async fn owner(&self) -> String {
+        self.0.metadata.owner.to_string()
+    }
+
+    async fn owner_bitcoin_address(&self) -> Option<String> {
+        self.0.metadata.owner_bitcoin_address.clone()
+    }
+
+    async fn bitcoin_txid(&self) -> String {
+        self.0.value.bitcoin_txid.to_string()
+    }
+
+    async fn vout(&self) -> u32 {
+        self.0.value.vout
+    }
+
+    /// The value of the UTXO in satoshis.
+    async fn value(&self) -> String {
+        self.0.value.value.to_string()
+    }
+
+    /// The protocol seals of the UTXO, keyed by the protocol type.
+    async fn seals(&self) -> Result<Json<serde_json::Value>> {
+        Ok(Json(serde_json::to_value(&self.0.value.seals)?))
+    }
+
+    async fn created_at(&self) -> String {
+        self.0.metadata.created_at.to_string()
+    }
+
+    async fn updated_at(&self) -> String {
+        self.0.metadata.updated_at.to_string()
+    }
+}
+
+pub struct Inscription(pub InscriptionStateView);
+
+#[Object]
+impl Inscription {
+    async fn id(&self) -> String {
+        self.0.metadata.id.to_string()
+    }
+
+    async fn inscription_id(&self) -> String {
+        self.0.value.id.to_string()
+    }
+
+    async fn inscription_number(&self) -> i32 {
+        self.0.value.inscription_number
+    }
+
+    async fn sequence_number(&self) -> u32 {
+        self.0.value.sequence_number
+    }
+
+    async fn owner(&self) -> String {
+        self.0.metadata.owner.to_string()
+    }
+
+    async fn owner_bitcoin_address(&self) -> Option<String> {
+        self.0.metadata.owner_bitcoin_address.clone()
+    }
+
+    /// The satpoint of the inscription, in `txid:vout:offset` format.
+    async fn location(&self) -> String {
+        let location = &self.0.value.location;
+        format!(
+            "{}:{}:{}",
+            location.output.txid, location.output.vout, location.offset
+        )
+    }
+
+    async fn charms(&self) -> u16 {
+        self.0.value.charms
+    }
+
+    async fn content_type(&self) -> Option<String> {
+        self.0.value.content_type.as_ref().map(|s| s.to_string())
+    }
+
+    async fn content_encoding(&self) -> Option<String> {
+        self.0
+            .value
+            .content_encoding
+            .as_ref()
+            .map(|s| s.to_string())
+    }
+
+    async fn metaprotocol(&self) -> Option<String> {
+        self.0.value.metaprotocol.as_ref().map(|s| s.to_string())
+    }
+
+    /// The body of the inscription, in hex.
+    async fn body(&self) -> String {
+        hex::encode(&self.0.value.body.0)
+    }
+
+    async fn parents(&self) -> Vec<String> {
+        self.0.value.parents.iter().map(|p| p.to_string()).collect()
+    }
+
+    async fn pointer(&self) -> Option<String> {
+        self.0.value.pointer.map(|p| p.to_string())
+    }
+}
+
+pub(crate) async fn query_events(
+    ctx: &Context<'_>,
+    filter: EventFilterView,
+    first: Option<i32>,
+    after: Option<String>,
+    descending: Option<bool>,
+) -> Result<Connection<String, Event>> {
+    let context = ctx.data_unchecked::<GraphQLContext>();
+    let limit = page_size(&context.limits, first)?;
+    let cursor = decode_cursor(after)?;
+    let has_previous_page = cursor.is_some();
+    let mut query_option = QueryOptions::default().descending(descending.unwrap_or(true));
+    query_option.decode = node_field_selected(ctx, "decodedEventData");
+
+    let page = context
+        .kanari_server
+        .query_events(
+            filter,
+            cursor,
+            Some(StrView(limit as u64)),
+            Some(query_option),
+        )
+        .await?;
+
+    let mut connection = Connection::new(has_previous_page, page.has_next_page);
+    connection.edges.extend(
+        page.data
+            .into_iter()
+            .map(|event| Edge::new(encode_cursor(&event.indexer_event_id), Event(event))),
+    );
+    Ok(connection)
+}
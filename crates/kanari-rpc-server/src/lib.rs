@@ -3,21 +3,26 @@
 
 use crate::metrics_server::{init_metrics, start_basic_prometheus_server};
 use crate::server::btc_server::BtcServer;
+use crate::server::debug_server::DebugServer;
 use crate::server::kanari_server::KanariServer;
 use crate::service::aggregate_service::AggregateService;
+use crate::service::api_key::{api_key_middleware, ApiKeyLayerState, ApiKeyStore, API_KEY_HEADER};
 use crate::service::blocklist::{BlockListLayer, BlocklistConfig};
 use crate::service::error::ErrorHandler;
 use crate::service::metrics::ServiceMetrics;
+use crate::service::rpc_limits::RpcLimits;
 use crate::service::rpc_service::RpcService;
 use anyhow::{ensure, Error, Result};
 use axum::http::{HeaderValue, Method};
 use bitcoin_client::actor::client::BitcoinClientConfig;
+use bitcoin_client::local::LocalBlockStore;
 use bitcoin_client::proxy::BitcoinClientProxy;
 use coerce::actor::scheduler::timer::Timer;
 use coerce::actor::{system::ActorSystem, IntoActor};
 use jsonrpsee::RpcModule;
 use moveos_eventbus::bus::EventBus;
 use raw_store::errors::RawStoreError;
+use kanari_config::api_key_config::ApiKeyConfig;
 use kanari_config::da_config::derive_namespace_from_genesis;
 use kanari_config::server_config::ServerConfig;
 use kanari_config::settings::PROPOSER_CHECK_INTERVAL;
@@ -65,6 +70,8 @@ use tower_http::trace::TraceLayer;
 use tracing::{error, info};
 
 mod axum_router;
+pub mod content;
+pub mod graphql;
 pub mod metrics_server;
 pub mod server;
 pub mod service;
@@ -75,6 +82,7 @@ static R_EXIT_CODE_NEED_HELP: i32 = 120;
 pub struct ServerHandle {
     shutdown_tx: Sender<()>,
     timers: Vec<Timer>,
+    local_blocks: Option<Arc<LocalBlockStore>>,
     _opt: KanariOpt,
     _prometheus_registry: prometheus::Registry,
 }
@@ -110,6 +118,13 @@ impl Service {
         Ok(())
     }
 
+    /// The local Bitcoin blocks the relayer reads from, None if the blocks are read from the Bitcoin RPC.
+    pub fn local_blocks(&self) -> Option<Arc<LocalBlockStore>> {
+        self.handle
+            .as_ref()
+            .and_then(|handle| handle.local_blocks.clone())
+    }
+
     pub fn stop(self) -> Result<()> {
         if let Some(handle) = self.handle {
             handle.stop()?
@@ -254,7 +269,11 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
 
     let event_bus = EventBus::new();
     let subscription_handle = Arc::new(SubscriptionHandler::new(&prometheus_registry));
-    let notify_actor = NotifyActor::new(event_bus.clone(), subscription_handle.clone());
+    let notify_actor = NotifyActor::new(
+        event_bus.clone(),
+        subscription_handle.clone(),
+        moveos_store.clone(),
+    );
     let notify_actor_ref = notify_actor
         .into_actor(Some("NotifyActor"), &actor_system)
         .await?;
@@ -367,6 +386,12 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         .await?;
     let indexer_proxy = IndexerProxy::new(indexer_executor.into(), indexer_reader_executor.into());
     let bitcoin_relayer_config = opt.bitcoin_relayer_config();
+    // Index the local blocks once, the store is shared by the Bitcoin clients of the RPC and the relayer.
+    let local_blocks = bitcoin_relayer_config
+        .as_ref()
+        .and_then(|config| config.btc_blocks_dir.as_ref())
+        .map(|dir| LocalBlockStore::open(dir).map(Arc::new))
+        .transpose()?;
     let bitcoin_client_config = bitcoin_relayer_config
         .as_ref()
         .map(|config| BitcoinClientConfig {
@@ -374,6 +399,7 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
             btc_rpc_user_name: config.btc_rpc_user_name.clone(),
             btc_rpc_password: config.btc_rpc_password.clone(),
             local_block_store_dir: Some(config.btc_reorg_aware_block_store_dir.clone()), // this client will be used for startup processing, may need reorg blocks
+            local_blocks: local_blocks.clone(),
         });
     let bitcoin_client_proxy = if service_status.is_active() && bitcoin_client_config.is_some() {
         let bitcoin_client = bitcoin_client_config.unwrap().build()?;
@@ -394,6 +420,7 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         service_status,
         &prometheus_registry,
         Some(notify_actor_ref.clone()),
+        Some(subscription_handle.clone()),
         kanari_db,
         bitcoin_client_proxy.clone(),
     );
@@ -417,6 +444,7 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
             processor_proxy.clone(),
             ethereum_relayer_config,
             bitcoin_relayer_config.clone(),
+            local_blocks.clone(),
             Some(notify_actor_ref),
         )
         .await?
@@ -431,6 +459,11 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         timers.push(relayer_timer);
     }
 
+    let rpc_limits = RpcLimits::new(
+        opt.rpc_max_batch_size,
+        opt.rpc_max_page_size,
+        opt.rpc_max_response_bytes,
+    );
     let rpc_service = RpcService::new(
         network.chain_id.id,
         network.genesis_config.bitcoin_network,
@@ -442,6 +475,7 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         da_proxy,
         subscription_handle.clone(),
         None,
+        rpc_limits,
     );
     let aggregate_service = AggregateService::new(rpc_service.clone());
 
@@ -463,7 +497,10 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         .allow_methods([Method::POST])
         // Allow requests from any origin
         .allow_origin(acl)
-        .allow_headers([axum::http::header::CONTENT_TYPE]);
+        .allow_headers([
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderName::from_static(API_KEY_HEADER),
+        ]);
 
     let traffic_burst_size: u32;
     let traffic_per_second: f64;
@@ -494,9 +531,20 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
     );
 
     let governor_limiter = governor_conf.limiter().clone();
+    let batch_rate_limiter = governor_conf.limiter().clone();
     let interval = Duration::from_secs(60);
 
+    let blocklist_config = Arc::new(BlocklistConfig::default());
+    let api_key_config = match opt.api_key_config.as_ref() {
+        Some(path) => ApiKeyConfig::load_and_validate(path)?,
+        None => ApiKeyConfig::default(),
+    };
+    info!("API keys loaded: {}", api_key_config.keys.len());
+    let api_keys = Arc::new(ApiKeyStore::new(api_key_config));
+
     // a separate background task to clean up
+    let blocklist_config_clone = blocklist_config.clone();
+    let api_keys_clone = api_keys.clone();
     tokio::spawn(async move {
         let mut tick = tokio::time::interval(interval);
         loop {
@@ -507,11 +555,11 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
             tick.tick().await;
             tracing::info!("rate limiting storage size: {}", governor_limiter.len());
             governor_limiter.retain_recent();
+            blocklist_config_clone.clear_expired();
+            api_keys_clone.retain_recent(interval);
         }
     });
 
-    let blocklist_config = Arc::new(BlocklistConfig::default());
-
     let middleware = tower::ServiceBuilder::new()
         .layer(TraceLayer::new_for_http())
         .layer(cors)
@@ -530,6 +578,10 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         aggregate_service.clone(),
     ))?;
     rpc_module_builder.register_module(BtcServer::new(rpc_service.clone()).await?)?;
+    if opt.enable_debug_rpc {
+        info!("The debug RPC methods are enabled");
+        rpc_module_builder.register_module(DebugServer::new(rpc_service.clone()))?;
+    }
     rpc_module_builder
         .module
         .register_method("rpc.discover", move |_, _, _| {
@@ -540,10 +592,42 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
 
     let methods_names = rpc_module_builder.module.method_names().collect::<Vec<_>>();
 
+    let graphql_schema = graphql::build_schema(
+        KanariServer::new(rpc_service.clone(), aggregate_service.clone()),
+        BtcServer::new(rpc_service.clone()).await?,
+        aggregate_service.clone(),
+        rpc_limits,
+        opt.graphql_max_depth
+            .unwrap_or(graphql::DEFAULT_GRAPHQL_MAX_DEPTH),
+        opt.graphql_max_complexity
+            .unwrap_or(graphql::DEFAULT_GRAPHQL_MAX_COMPLEXITY),
+    );
+
+    let content_service = content::ContentService::new(rpc_service.clone());
+
+    // The non JSON-RPC routes are labeled by the route path in the API key metrics
+    let mut metrics_routes = methods_names.clone();
+    metrics_routes.extend([
+        "/subscribe/sse/events",
+        "/subscribe/sse/transactions",
+        "/subscribe/sse/object_states",
+        graphql::GRAPHQL_PATH,
+        content::CONTENT_PATH,
+        content::PREVIEW_PATH,
+    ]);
+    let service_metrics = ServiceMetrics::new(&prometheus_registry, &metrics_routes);
+    let api_key_state = ApiKeyLayerState {
+        api_keys: api_keys.clone(),
+        metrics: service_metrics.clone(),
+    };
+
     let ser = axum_router::JsonRpcService::new(
         rpc_module_builder.module.clone().into(),
-        ServiceMetrics::new(&prometheus_registry, &methods_names),
+        service_metrics,
         subscription_handle,
+        api_keys,
+        rpc_limits,
+        batch_rate_limiter,
     );
 
     let mut router = axum::Router::new();
@@ -569,6 +653,26 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
                 .route(
                     "/subscribe/sse/transactions",
                     axum::routing::get(crate::axum_router::sse_transactions_handler),
+                )
+                .route(
+                    "/subscribe/sse/object_states",
+                    axum::routing::get(crate::axum_router::sse_object_states_handler),
+                )
+                .route(
+                    graphql::GRAPHQL_PATH,
+                    axum::routing::post(graphql::graphql_handler)
+                        .get(graphql::graphiql_handler)
+                        .with_state(graphql_schema.clone()),
+                )
+                .route(
+                    content::CONTENT_PATH,
+                    axum::routing::get(content::content_handler)
+                        .with_state(content_service.clone()),
+                )
+                .route(
+                    content::PREVIEW_PATH,
+                    axum::routing::get(content::preview_handler)
+                        .with_state(content_service.clone()),
                 );
         }
         ServiceType::Http => {
@@ -581,6 +685,26 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
                 .route(
                     "/subscribe/sse/transactions",
                     axum::routing::get(crate::axum_router::sse_transactions_handler),
+                )
+                .route(
+                    "/subscribe/sse/object_states",
+                    axum::routing::get(crate::axum_router::sse_object_states_handler),
+                )
+                .route(
+                    graphql::GRAPHQL_PATH,
+                    axum::routing::post(graphql::graphql_handler)
+                        .get(graphql::graphiql_handler)
+                        .with_state(graphql_schema.clone()),
+                )
+                .route(
+                    content::CONTENT_PATH,
+                    axum::routing::get(content::content_handler)
+                        .with_state(content_service.clone()),
+                )
+                .route(
+                    content::PREVIEW_PATH,
+                    axum::routing::get(content::preview_handler)
+                        .with_state(content_service.clone()),
                 );
         }
         ServiceType::WebSocket => {
@@ -596,7 +720,13 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
         }
     }
 
-    let app = router.with_state(ser).layer(middleware);
+    let app = router
+        .with_state(ser)
+        .layer(axum::middleware::from_fn_with_state(
+            api_key_state,
+            api_key_middleware,
+        ))
+        .layer(middleware);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     let addr = listener.local_addr()?;
@@ -625,6 +755,7 @@ pub async fn run_start_server(opt: KanariOpt, server_opt: ServerOpt) -> Result<S
     Ok(ServerHandle {
         shutdown_tx,
         timers,
+        local_blocks,
         _opt: opt,
         _prometheus_registry: prometheus_registry,
     })
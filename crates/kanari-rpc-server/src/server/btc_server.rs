@@ -6,15 +6,26 @@ use anyhow::Result;
 use bitcoincore_rpc::bitcoin::Txid;
 use jsonrpsee::{core::async_trait, RpcModule};
 use kanari_rpc_api::api::btc_api::BtcAPIServer;
-use kanari_rpc_api::api::{KanariRpcModule, DEFAULT_RESULT_LIMIT_USIZE, MAX_RESULT_LIMIT_USIZE};
+use kanari_rpc_api::api::KanariRpcModule;
+use kanari_rpc_api::jsonrpc_types::btc::brc20::{BRC20BalanceView, BRC20TickInfoView};
+use kanari_rpc_api::jsonrpc_types::btc::fee_rate::FeeRateView;
 use kanari_rpc_api::jsonrpc_types::btc::ord::{InscriptionFilterView, InscriptionStateView};
+use kanari_rpc_api::jsonrpc_types::btc::runes::{
+    RuneBalancesFilterView, RuneBalancesStateView, RuneEntryStateView, RuneFilterView,
+};
 use kanari_rpc_api::jsonrpc_types::btc::utxo::{UTXOFilterView, UTXOStateView};
 use kanari_rpc_api::jsonrpc_types::{
-    BytesView, IndexerStateIDView, InscriptionPageView, StrView, UTXOPageView,
+    BytesView, IndexerStateIDView, InscriptionPageView, RuneBalancesPageView, RuneEntryPageView,
+    StrView, UTXOPageView, UnitedAddressView,
 };
 use kanari_rpc_api::RpcResult;
+use kanari_types::bitcoin::runes::{RuneId, RuneStore};
 use kanari_types::indexer::state::ObjectStateType;
-use std::cmp::min;
+use moveos_types::access_path::AccessPath;
+use moveos_types::state::FieldKey;
+
+/// The default confirmation target in blocks of the fee rate estimation
+const DEFAULT_FEE_RATE_CONF_TARGET: u16 = 6;
 
 pub struct BtcServer {
     rpc_service: RpcService,
@@ -24,6 +35,58 @@ impl BtcServer {
     pub async fn new(rpc_service: RpcService) -> Result<Self> {
         Ok(Self { rpc_service })
     }
+
+    /// Resolve the rune name to the RuneId via the fields of the RuneStore
+    async fn resolve_rune_id(&self, rune: u128) -> Result<Option<RuneId>> {
+        let access_path =
+            AccessPath::fields(RuneStore::object_id(), vec![FieldKey::derive(&rune)?]);
+        let state = self
+            .rpc_service
+            .get_states(access_path, None)
+            .await?
+            .pop()
+            .flatten();
+        state
+            .map(|state| Ok(state.value_as_df::<u128, RuneId>()?.value))
+            .transpose()
+    }
+
+    /// Resolve the delegates of the inscriptions, and serve the content of the delegate inscriptions.
+    async fn resolve_delegates(&self, inscriptions: &mut [InscriptionStateView]) -> Result<()> {
+        if inscriptions.is_empty() {
+            return Ok(());
+        }
+        let inscription_ids = inscriptions
+            .iter()
+            .map(|inscription| inscription.value.id.0)
+            .collect::<Vec<_>>();
+        let delegates = self
+            .rpc_service
+            .get_inscription_delegates(&inscription_ids)
+            .await?;
+
+        let delegated = inscriptions
+            .iter_mut()
+            .zip(delegates)
+            .filter_map(|(inscription, delegate)| delegate.map(|delegate| (inscription, delegate)))
+            .collect::<Vec<_>>();
+        if delegated.is_empty() {
+            return Ok(());
+        }
+        let delegate_ids = delegated
+            .iter()
+            .map(|(_, delegate)| *delegate)
+            .collect::<Vec<_>>();
+        let delegate_inscriptions = self.rpc_service.get_inscriptions(&delegate_ids).await?;
+        for ((inscription, delegate), delegate_inscription) in
+            delegated.into_iter().zip(delegate_inscriptions)
+        {
+            inscription
+                .value
+                .resolve_delegate(delegate, delegate_inscription);
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -36,10 +99,7 @@ impl BtcAPIServer for BtcServer {
         limit: Option<StrView<u64>>,
         descending_order: Option<bool>,
     ) -> RpcResult<UTXOPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let descending_order = descending_order.unwrap_or(true);
 
         let global_state_filter = UTXOFilterView::into_global_state_filter(filter)?;
@@ -65,11 +125,15 @@ impl BtcAPIServer for BtcServer {
         data.truncate(limit_of);
         let next_cursor = data.last().cloned().map_or(cursor, |t| Some(t.indexer_id));
 
-        Ok(UTXOPageView {
+        let mut page = UTXOPageView {
             data,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |utxo| utxo.indexer_id)?;
+        Ok(page)
     }
 
     async fn query_inscriptions(
@@ -80,10 +144,7 @@ impl BtcAPIServer for BtcServer {
         limit: Option<StrView<u64>>,
         descending_order: Option<bool>,
     ) -> RpcResult<InscriptionPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let descending_order = descending_order.unwrap_or(true);
 
         let global_state_filter = InscriptionFilterView::into_global_state_filter(filter)?;
@@ -107,13 +168,151 @@ impl BtcAPIServer for BtcServer {
 
         let has_next_page = data.len() > limit_of;
         data.truncate(limit_of);
+        self.resolve_delegates(&mut data).await?;
         let next_cursor = data.last().cloned().map_or(cursor, |t| Some(t.indexer_id));
 
-        Ok(InscriptionPageView {
+        let mut page = InscriptionPageView {
             data,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |inscription| inscription.indexer_id)?;
+        Ok(page)
+    }
+
+    async fn query_runes(
+        &self,
+        filter: RuneFilterView,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<IndexerStateIDView>,
+        limit: Option<StrView<u64>>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<RuneEntryPageView> {
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
+        let descending_order = descending_order.unwrap_or(true);
+
+        let resolved_rune_id = match &filter {
+            RuneFilterView::Rune(spaced_rune) => {
+                let rune_id = self.resolve_rune_id(spaced_rune.0.rune.0).await?;
+                if rune_id.is_none() {
+                    return Ok(RuneEntryPageView {
+                        data: vec![],
+                        next_cursor: cursor,
+                        has_next_page: false,
+                    });
+                }
+                rune_id
+            }
+            _ => None,
+        };
+        let global_state_filter =
+            RuneFilterView::into_global_state_filter(filter, resolved_rune_id)?;
+        let object_states = self
+            .rpc_service
+            .query_object_states(
+                global_state_filter,
+                cursor.map(Into::into),
+                limit_of + 1,
+                descending_order,
+                false,
+                false,
+                ObjectStateType::ObjectState,
+            )
+            .await?;
+
+        let mut data = object_states
+            .into_iter()
+            .map(RuneEntryStateView::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let has_next_page = data.len() > limit_of;
+        data.truncate(limit_of);
+        let next_cursor = data.last().cloned().map_or(cursor, |t| Some(t.indexer_id));
+
+        let mut page = RuneEntryPageView {
+            data,
+            next_cursor,
+            has_next_page,
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |rune| rune.indexer_id)?;
+        Ok(page)
+    }
+
+    async fn query_rune_balances(
+        &self,
+        filter: RuneBalancesFilterView,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<IndexerStateIDView>,
+        limit: Option<StrView<u64>>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<RuneBalancesPageView> {
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
+        let descending_order = descending_order.unwrap_or(true);
+
+        let global_state_filter = RuneBalancesFilterView::into_global_state_filter(filter)?;
+        let object_states = self
+            .rpc_service
+            .query_object_states(
+                global_state_filter,
+                cursor.map(Into::into),
+                limit_of + 1,
+                descending_order,
+                false,
+                false,
+                ObjectStateType::RuneBalances,
+            )
+            .await?;
+
+        let mut data = object_states
+            .into_iter()
+            .map(RuneBalancesStateView::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let has_next_page = data.len() > limit_of;
+        data.truncate(limit_of);
+        let next_cursor = data.last().cloned().map_or(cursor, |t| Some(t.indexer_id));
+
+        let mut page = RuneBalancesPageView {
+            data,
+            next_cursor,
+            has_next_page,
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |rune_balances| rune_balances.indexer_id)?;
+        Ok(page)
+    }
+
+    async fn query_brc20_balances(
+        &self,
+        owner: UnitedAddressView,
+        tick: Option<String>,
+    ) -> RpcResult<Vec<BRC20BalanceView>> {
+        let tick = tick.map(|tick| tick.to_lowercase());
+        let balances = self
+            .rpc_service
+            .get_brc20_balances(owner.into())
+            .await?
+            .into_iter()
+            .filter(|(balance, _)| {
+                tick.as_ref()
+                    .is_none_or(|tick| balance.tick.as_str() == tick)
+            })
+            .map(|(balance, tick_info)| BRC20BalanceView::new(balance, tick_info.dec))
+            .collect();
+        Ok(balances)
+    }
+
+    async fn get_brc20_tick_info(&self, tick: String) -> RpcResult<Option<BRC20TickInfoView>> {
+        Ok(self
+            .rpc_service
+            .get_brc20_tick_info(tick)
+            .await?
+            .map(Into::into))
     }
 
     async fn broadcast_tx(
@@ -130,6 +329,13 @@ impl BtcAPIServer for BtcServer {
 
         Ok(txid.to_string())
     }
+
+    async fn estimate_fee_rate(&self, conf_target: Option<u16>) -> RpcResult<FeeRateView> {
+        Ok(self
+            .rpc_service
+            .estimate_bitcoin_fee_rate(conf_target.unwrap_or(DEFAULT_FEE_RATE_CONF_TARGET))
+            .await?)
+    }
 }
 
 impl KanariRpcModule for BtcServer {
@@ -0,0 +1,125 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::service::rpc_service::RpcService;
+use jsonrpsee::{core::async_trait, RpcModule};
+use moveos::vm::tracer::CallTrace;
+use moveos_types::h256::H256;
+use kanari_rpc_api::api::debug_api::DebugAPIServer;
+use kanari_rpc_api::api::KanariRpcModule;
+use kanari_rpc_api::jsonrpc_types::{CallTraceView, H256View, StrView, TransactionTraceView};
+use kanari_rpc_api::{RpcError, RpcResult};
+use tracing::warn;
+
+/// The debug methods re-execute transactions, they are only registered when the debug RPC is enabled.
+pub struct DebugServer {
+    rpc_service: RpcService,
+}
+
+impl DebugServer {
+    pub fn new(rpc_service: RpcService) -> Self {
+        Self { rpc_service }
+    }
+}
+
+#[async_trait]
+impl DebugAPIServer for DebugServer {
+    async fn trace_transaction(&self, tx_hash: H256View) -> RpcResult<TransactionTraceView> {
+        let tx_hash: H256 = tx_hash.into();
+        let tx = self
+            .rpc_service
+            .get_transaction_by_hash(tx_hash)
+            .await?
+            .ok_or_else(|| {
+                RpcError::UnexpectedError(format!("The transaction {:?} does not exist", tx_hash))
+            })?;
+        let tx_order = tx.sequence_info.tx_order;
+        if tx_order == 0 {
+            return Err(RpcError::UnexpectedError(
+                "Can not trace the genesis transaction".to_string(),
+            ));
+        }
+        let pre_root = self
+            .rpc_service
+            .get_execution_info_by_order(tx_order - 1)
+            .await?
+            .ok_or_else(|| {
+                RpcError::StateUnavailable(format!(
+                    "can not find the transaction with tx_order {}",
+                    tx_order - 1
+                ))
+            })?
+            .root_metadata();
+        if !self
+            .rpc_service
+            .contains_state_root(pre_root.state_root())
+            .await?
+        {
+            return Err(RpcError::StateUnavailable(format!(
+                "the state of state root {:?} has been pruned or does not exist",
+                pre_root.state_root()
+            )));
+        }
+
+        let result = self
+            .rpc_service
+            .trace_transaction(tx, pre_root.clone())
+            .await?;
+        // The re-execution may diverge from the original one if the framework or the gas schedule is upgraded.
+        if let Some(execution_info) = self
+            .rpc_service
+            .get_transaction_execution_infos_by_hash(vec![tx_hash])
+            .await?
+            .pop()
+            .flatten()
+        {
+            if execution_info.status != result.raw_output.status
+                || execution_info.gas_used != result.raw_output.gas_used
+            {
+                warn!(
+                    "The trace of tx {:?} diverges from the execution, status: {:?} vs {:?}, gas used: {} vs {}",
+                    tx_hash,
+                    result.raw_output.status,
+                    execution_info.status,
+                    result.raw_output.gas_used,
+                    execution_info.gas_used
+                );
+            }
+        }
+
+        Ok(TransactionTraceView {
+            tx_hash: tx_hash.into(),
+            tx_order: StrView(tx_order),
+            pre_state_root: pre_root.state_root().into(),
+            status: result.raw_output.status.into(),
+            gas_used: StrView(result.raw_output.gas_used),
+            call_trace: call_trace_view(result.call_trace),
+            read_objects: result.read_set.objects.into_iter().map(StrView).collect(),
+            changeset: result.raw_output.changeset.into(),
+            events: result
+                .raw_output
+                .events
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            vm_error_info: result.vm_error_info,
+        })
+    }
+}
+
+impl KanariRpcModule for DebugServer {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
+
+fn call_trace_view(call_trace: CallTrace) -> CallTraceView {
+    CallTraceView {
+        function: call_trace.function,
+        args: call_trace.args,
+        return_values: call_trace.return_values,
+        is_native: call_trace.is_native,
+        gas_used: StrView(call_trace.gas_used),
+        calls: call_trace.calls.into_iter().map(call_trace_view).collect(),
+    }
+}
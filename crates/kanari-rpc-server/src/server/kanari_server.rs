@@ -6,9 +6,11 @@ use crate::service::rpc_service::RpcService;
 use anyhow::Result;
 use jsonrpsee::core::SubscriptionResult;
 use jsonrpsee::{core::async_trait, PendingSubscriptionSink, RpcModule};
+use move_core_types::gas_algebra::InternalGas;
 use move_core_types::{
     account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
 };
+use moveos_gas_profiling::log::TransactionGasLog;
 use moveos_types::{
     access_path::AccessPath,
     h256::H256,
@@ -22,27 +24,23 @@ use kanari_rpc_api::jsonrpc_types::{
     account_view::BalanceInfoView,
     event_view::{EventFilterView, EventView, IndexerEventIDView, IndexerEventView},
     transaction_view::{TransactionFilterView, TransactionWithInfoView},
-    AccessPathView, BalanceInfoPageView, DryRunTransactionResponseView,
+    AccessPathView, BalanceInfoPageView, DryRunOptions, DryRunTransactionResponseView,
     EnumStructTagOrObjectIDView, EventOptions, EventPageView, ExecuteTransactionResponseView,
-    FieldPageView, FunctionCallView, H256View, IndexerEventPageView, IndexerObjectStatePageView,
-    IndexerStateIDView, ModuleABIView, ObjectIDVecView, ObjectIDView, ObjectStateFilterView,
-    ObjectStateView, QueryOptions, RawTransactionOutputView, KanariAddressView,
-    StateChangeSetPageView, StateChangeSetWithTxOrderView, StateKVView, StateOptions,
-    StatePageView, StrView, StructTagOrObjectIDView, StructTagView, SyncStateFilterView,
-    TransactionWithInfoPageView, TxOptions, UnitedAddressView,
+    FieldPageView, FunctionCallView, GasProfileEntryView, GasProfileView, H256View,
+    IndexerEventPageView, IndexerObjectStatePageView, IndexerStateIDView, ModuleABIView,
+    ObjectIDVecView, ObjectIDView, ObjectStateFilterView, ObjectStateView, QueryOptions,
+    RawTransactionOutputView, KanariAddressView, StateChangeSetPageView,
+    StateChangeSetWithTxOrderView, StateKVView, StateOptions, StatePageView, StrView,
+    StructTagOrObjectIDView, StructTagView, SyncStateFilterView, TransactionWithInfoPageView,
+    TxOptions, UnitedAddressView,
 };
 use kanari_rpc_api::jsonrpc_types::{
     repair_view::{RepairIndexerParamsView, RepairIndexerTypeView},
-    Status,
+    RootStateView, Status,
 };
 use kanari_rpc_api::{
-    api::kanari_api::KanariAPIServer,
-    api::DEFAULT_RESULT_LIMIT,
-    api::{KanariRpcModule, DEFAULT_RESULT_LIMIT_USIZE},
-    api::{MAX_RESULT_LIMIT, MAX_RESULT_LIMIT_USIZE},
-    jsonrpc_types::AnnotatedFunctionResultView,
-    jsonrpc_types::BytesView,
-    RpcError, RpcResult,
+    api::kanari_api::KanariAPIServer, api::KanariRpcModule,
+    jsonrpc_types::AnnotatedFunctionResultView, jsonrpc_types::BytesView, RpcError, RpcResult,
 };
 use kanari_types::indexer::state::{IndexerStateID, ObjectStateType};
 use kanari_types::transaction::{KanariTransaction, KanariTransactionData, TransactionWithInfo};
@@ -95,6 +93,56 @@ impl KanariServer {
             .collect::<Result<Vec<_>>>()?;
         Ok(data)
     }
+
+    /// Resolve the state root to read from, `None` means the latest state.
+    /// The `state_root` takes precedence over `at_tx_order`, and `at_tx_order` over `at_timestamp`.
+    async fn resolve_state_root(&self, state_option: &StateOptions) -> RpcResult<Option<H256>> {
+        let state_root = if let Some(state_root) = state_option.state_root {
+            state_root.0
+        } else if let Some(tx_order) = state_option.at_tx_order {
+            self.rpc_service
+                .get_execution_info_by_order(tx_order.0)
+                .await?
+                .ok_or_else(|| {
+                    RpcError::StateUnavailable(format!(
+                        "can not find the transaction with tx_order {}",
+                        tx_order
+                    ))
+                })?
+                .state_root
+        } else if let Some(timestamp) = state_option.at_timestamp {
+            let tx_order = self
+                .rpc_service
+                .get_tx_order_by_timestamp(timestamp.0)
+                .await?
+                .ok_or_else(|| {
+                    RpcError::StateUnavailable(format!(
+                        "can not find any transaction at or before timestamp {}",
+                        timestamp
+                    ))
+                })?;
+            self.rpc_service
+                .get_execution_info_by_order(tx_order)
+                .await?
+                .ok_or_else(|| {
+                    RpcError::StateUnavailable(format!(
+                        "can not find the transaction with tx_order {}",
+                        tx_order
+                    ))
+                })?
+                .state_root
+        } else {
+            return Ok(None);
+        };
+
+        if !self.rpc_service.contains_state_root(state_root).await? {
+            return Err(RpcError::StateUnavailable(format!(
+                "the state of state root {:?} has been pruned or does not exist",
+                state_root
+            )));
+        }
+        Ok(Some(state_root))
+    }
 }
 
 #[async_trait]
@@ -170,10 +218,22 @@ impl KanariAPIServer for KanariServer {
         Ok(result)
     }
 
-    async fn dry_run(&self, payload: BytesView) -> RpcResult<DryRunTransactionResponseView> {
+    async fn dry_run(
+        &self,
+        payload: BytesView,
+        dry_run_option: Option<DryRunOptions>,
+    ) -> RpcResult<DryRunTransactionResponseView> {
+        let dry_run_option = dry_run_option.unwrap_or_default();
         let tx = bcs::from_bytes::<KanariTransactionData>(&payload.0)?;
         let tx_hash = tx.tx_hash();
-        let tx_result = self.rpc_service.dry_run_tx(tx).await?;
+        let tx_result = self
+            .rpc_service
+            .dry_run_tx(tx, dry_run_option.gas_profile)
+            .await?;
+        let gas_profile = tx_result
+            .gas_log
+            .map(|gas_log| gas_profile_view(&gas_log, dry_run_option.flamegraph))
+            .transpose()?;
         let raw_output = tx_result.raw_output;
         let raw_output_view = RawTransactionOutputView {
             tx_hash: tx_hash.into(),
@@ -185,6 +245,7 @@ impl KanariAPIServer for KanariServer {
         let tx_response = DryRunTransactionResponseView {
             raw_output: raw_output_view,
             vm_error_info: tx_result.vm_error_info.unwrap_or_default(),
+            gas_profile,
         };
         Ok(tx_response)
     }
@@ -192,10 +253,14 @@ impl KanariAPIServer for KanariServer {
     async fn execute_view_function(
         &self,
         function_call: FunctionCallView,
+        state_option: Option<StateOptions>,
     ) -> RpcResult<AnnotatedFunctionResultView> {
+        let state_root = self
+            .resolve_state_root(&state_option.unwrap_or_default())
+            .await?;
         Ok(self
             .rpc_service
-            .execute_view_function(function_call.into())
+            .execute_view_function(function_call.into(), state_root)
             .await?
             .into())
     }
@@ -210,7 +275,7 @@ impl KanariAPIServer for KanariServer {
         let show_display =
             state_option.show_display && (access_path.0.is_object() || access_path.0.is_resource());
 
-        let state_root = state_option.state_root.map(|h256_view| h256_view.0);
+        let state_root = self.resolve_state_root(&state_option).await?;
 
         let state_views = if state_option.decode || show_display {
             let states = self
@@ -267,12 +332,9 @@ impl KanariAPIServer for KanariServer {
         let show_display =
             state_option.show_display && (access_path.0.is_object() || access_path.0.is_resource());
 
-        let state_root = state_option.state_root.map(|h256_view| h256_view.0);
+        let state_root = self.resolve_state_root(&state_option).await?;
 
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let cursor_of = match cursor.clone() {
             Some(key_state_str) => Some(FieldKey::from_str(key_state_str.as_str())?),
             None => None,
@@ -323,11 +385,15 @@ impl KanariAPIServer for KanariServer {
             Some(state_kv.field_key.clone().to_string())
         });
 
-        Ok(StatePageView {
+        let mut page = StatePageView {
             data,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |state_kv| state_kv.field_key.to_string())?;
+        Ok(page)
     }
 
     async fn get_object_states(
@@ -414,11 +480,10 @@ impl KanariAPIServer for KanariServer {
             EnumStructTagOrObjectIDView::from_str(event_handle.as_str())?.into();
         let event_options = event_options.unwrap_or_default();
         let cursor = cursor.map(|v| v.0);
-        let limit = limit.map(|v| v.0);
         let descending_order = descending_order.unwrap_or(true);
 
         // NOTE: fetch one more object to check if there is next page
-        let limit_of = min(limit.unwrap_or(DEFAULT_RESULT_LIMIT), MAX_RESULT_LIMIT);
+        let limit_of = self.rpc_service.limits().limit_of(limit)? as u64;
         let limit = limit_of + 1;
         let mut data = if event_options.decode {
             self.rpc_service
@@ -448,11 +513,15 @@ impl KanariAPIServer for KanariServer {
             .last()
             .map_or(cursor, |event| Some(event.event_id.event_seq.0));
 
-        Ok(EventPageView {
+        let mut page = EventPageView {
             data,
             next_cursor: next_cursor.map(StrView),
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |event| event.event_id.event_seq)?;
+        Ok(page)
     }
 
     async fn get_transactions_by_hash(
@@ -508,12 +577,7 @@ impl KanariAPIServer for KanariServer {
     ) -> RpcResult<TransactionWithInfoPageView> {
         let last_sequencer_order = self.rpc_service.get_sequencer_order().await?;
 
-        let limit_of = min(
-            limit
-                .map(Into::into)
-                .unwrap_or(DEFAULT_RESULT_LIMIT_USIZE as u64),
-            MAX_RESULT_LIMIT_USIZE as u64,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)? as u64;
 
         let descending_order = descending_order.unwrap_or(true);
         let cursor = cursor.map(|v| v.0);
@@ -567,21 +631,57 @@ impl KanariAPIServer for KanariServer {
 
         let data = self.transactions_to_view(data).await?;
 
-        Ok(TransactionWithInfoPageView {
+        let mut page = TransactionWithInfoPageView {
             data,
             next_cursor: next_cursor.map(StrView),
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |tx| tx.transaction.sequence_info.tx_order)?;
+        Ok(page)
+    }
+
+    async fn get_state_root_by_order(
+        &self,
+        tx_order: StrView<u64>,
+    ) -> RpcResult<Option<RootStateView>> {
+        let execution_info = match self
+            .rpc_service
+            .get_execution_info_by_order(tx_order.0)
+            .await?
+        {
+            Some(execution_info) => execution_info,
+            None => return Ok(None),
+        };
+        if !self
+            .rpc_service
+            .contains_state_root(execution_info.state_root)
+            .await?
+        {
+            return Err(RpcError::StateUnavailable(format!(
+                "the state of tx_order {} has been pruned",
+                tx_order
+            )));
+        }
+        Ok(Some(RootStateView {
+            state_root: StrView(execution_info.state_root),
+            size: StrView(execution_info.size),
+        }))
     }
 
     async fn get_balance(
         &self,
         account_addr: UnitedAddressView,
         coin_type: StructTagView,
+        state_option: Option<StateOptions>,
     ) -> RpcResult<BalanceInfoView> {
+        let state_root = self
+            .resolve_state_root(&state_option.unwrap_or_default())
+            .await?;
         Ok(self
             .aggregate_service
-            .get_balance(account_addr.into(), coin_type.into())
+            .get_balance(account_addr.into(), coin_type.into(), state_root)
             .await
             .map(Into::into)?)
     }
@@ -593,10 +693,7 @@ impl KanariAPIServer for KanariServer {
         cursor: Option<IndexerStateIDView>,
         limit: Option<StrView<u64>>,
     ) -> RpcResult<BalanceInfoPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let cursor: Option<IndexerStateID> = cursor.map(Into::into);
         let mut data = self
             .aggregate_service
@@ -657,10 +754,7 @@ impl KanariAPIServer for KanariServer {
         limit: Option<StrView<u64>>,
         query_option: Option<QueryOptions>,
     ) -> RpcResult<TransactionWithInfoPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let cursor = cursor.map(|v| v.0);
         let query_option = query_option.unwrap_or_default();
         let descending_order = query_option.descending;
@@ -685,11 +779,15 @@ impl KanariAPIServer for KanariServer {
             .cloned()
             .map_or(cursor, |t| Some(t.transaction.sequence_info.tx_order.0));
 
-        Ok(TransactionWithInfoPageView {
+        let mut page = TransactionWithInfoPageView {
             data,
             next_cursor: next_cursor.map(StrView),
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |tx| tx.transaction.sequence_info.tx_order)?;
+        Ok(page)
     }
 
     async fn query_events(
@@ -700,10 +798,7 @@ impl KanariAPIServer for KanariServer {
         limit: Option<StrView<u64>>,
         query_option: Option<QueryOptions>,
     ) -> RpcResult<IndexerEventPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let query_option = query_option.unwrap_or_default();
         let descending_order = query_option.descending;
 
@@ -740,11 +835,15 @@ impl KanariAPIServer for KanariServer {
             .cloned()
             .map_or(cursor, |e| Some(e.indexer_event_id));
 
-        Ok(IndexerEventPageView {
+        let mut page = IndexerEventPageView {
             data,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |event| event.indexer_event_id)?;
+        Ok(page)
     }
 
     async fn query_object_states(
@@ -755,10 +854,7 @@ impl KanariAPIServer for KanariServer {
         limit: Option<StrView<u64>>,
         query_option: Option<QueryOptions>,
     ) -> RpcResult<IndexerObjectStatePageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let query_option = query_option.unwrap_or_default();
         let descending_order = query_option.descending;
 
@@ -785,11 +881,15 @@ impl KanariAPIServer for KanariServer {
             .cloned()
             .map_or(cursor, |t| Some(t.indexer_id));
 
-        Ok(IndexerObjectStatePageView {
+        let mut page = IndexerObjectStatePageView {
             data: object_states,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |state| state.indexer_id)?;
+        Ok(page)
     }
 
     async fn query_fields(
@@ -800,10 +900,7 @@ impl KanariAPIServer for KanariServer {
         query_option: Option<QueryOptions>,
     ) -> RpcResult<FieldPageView> {
         let page_of = max(page.map(Into::into).unwrap_or(1), 1u64);
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        );
+        let limit_of = self.rpc_service.limits().limit_of(limit)?;
         let query_option = query_option.unwrap_or_default();
         let descending_order = query_option.descending;
         let decode = query_option.decode;
@@ -860,10 +957,7 @@ impl KanariAPIServer for KanariServer {
         limit: Option<StrView<u64>>,
         query_option: Option<QueryOptions>,
     ) -> RpcResult<StateChangeSetPageView> {
-        let limit_of = min(
-            limit.map(Into::into).unwrap_or(DEFAULT_RESULT_LIMIT_USIZE),
-            MAX_RESULT_LIMIT_USIZE,
-        ) as u64;
+        let limit_of = self.rpc_service.limits().limit_of(limit)? as u64;
         let cursor_of = cursor.map(|v| v.0);
         // Sync from asc by default
         let descending_order = query_option.map(|v| v.descending).unwrap_or(false);
@@ -902,11 +996,15 @@ impl KanariAPIServer for KanariServer {
         data.truncate(limit_of as usize);
         let next_cursor = data.last().cloned().map_or(cursor, |t| Some(t.tx_order));
 
-        Ok(StateChangeSetPageView {
+        let mut page = StateChangeSetPageView {
             data,
             next_cursor,
             has_next_page,
-        })
+        };
+        self.rpc_service
+            .limits()
+            .fit_page(&mut page, |change_set| change_set.tx_order)?;
+        Ok(page)
     }
 
     async fn status(&self) -> RpcResult<Status> {
@@ -973,6 +1071,15 @@ impl KanariAPIServer for KanariServer {
         self.rpc_service.subscribe_transactions(sink, filter)?;
         Ok(())
     }
+
+    fn subscribe_object_states(
+        &self,
+        sink: PendingSubscriptionSink,
+        filter: ObjectStateFilterView,
+    ) -> SubscriptionResult {
+        self.rpc_service.subscribe_object_states(sink, filter)?;
+        Ok(())
+    }
 }
 
 impl KanariRpcModule for KanariServer {
@@ -980,3 +1087,27 @@ impl KanariRpcModule for KanariServer {
         self.into_rpc()
     }
 }
+
+fn gas_profile_view(gas_log: &TransactionGasLog, flamegraph: bool) -> Result<GasProfileView> {
+    let aggregated = gas_log.exec_io.aggregate_gas_events();
+    let entry_view = |(name, hits, cost): (String, usize, InternalGas)| GasProfileEntryView {
+        name,
+        hits: StrView(hits as u64),
+        gas_used: StrView(u64::from(cost)),
+    };
+    let flamegraph = if flamegraph {
+        gas_log
+            .exec_io
+            .to_flamegraph("Kanari Gas Profiling".to_string())?
+            .map(|svg| String::from_utf8_lossy(&svg).into_owned())
+    } else {
+        None
+    };
+    Ok(GasProfileView {
+        execution_gas_used: StrView(u64::from(gas_log.exec_io.total)),
+        storage_gas_used: StrView(u64::from(gas_log.storage)),
+        functions: aggregated.functions.into_iter().map(entry_view).collect(),
+        instructions: aggregated.ops.into_iter().map(entry_view).collect(),
+        flamegraph,
+    })
+}
@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod btc_server;
+pub mod debug_server;
 pub mod kanari_server;
@@ -40,6 +40,7 @@ impl AggregateService {
     pub async fn get_coin_infos(
         &self,
         coin_types: Vec<StructTag>,
+        state_root: Option<H256>,
     ) -> Result<HashMap<StructTag, Option<CoinInfoView>>> {
         let access_path = AccessPath::objects(
             coin_types
@@ -49,7 +50,7 @@ impl AggregateService {
                 .collect(),
         );
         self.rpc_service
-            .get_states(access_path, None)
+            .get_states(access_path, state_root)
             .await?
             .into_iter()
             .zip(coin_types)
@@ -73,10 +74,11 @@ impl AggregateService {
     pub async fn get_coin_stores(
         &self,
         coin_store_ids: Vec<ObjectID>,
+        state_root: Option<H256>,
     ) -> Result<Vec<Option<CoinStoreInfo>>> {
         let access_path = AccessPath::objects(coin_store_ids);
         self.rpc_service
-            .get_states(access_path, None)
+            .get_states(access_path, state_root)
             .await?
             .into_iter()
             .map(|state_opt| state_opt.map(CoinStoreInfo::try_from).transpose())
@@ -86,6 +88,7 @@ impl AggregateService {
     pub async fn get_multi_coin_store_fields(
         &self,
         field_with_coin_store_ids: Vec<(ObjectID, StructTag)>,
+        state_root: Option<H256>,
     ) -> Result<Vec<Option<CoinStoreInfo>>> {
         // Group fields by object_id to minimize RPC calls
         let mut object_map: HashMap<ObjectID, Vec<(FieldKey, usize)>> = HashMap::new();
@@ -116,7 +119,7 @@ impl AggregateService {
             let access_path = AccessPath::fields(object_id, field_keys);
 
             // Fetch states for all fields in a single RPC call
-            let state_kvs = self.rpc_service.get_states(access_path, None).await?;
+            let state_kvs = self.rpc_service.get_states(access_path, state_root).await?;
 
             // Map states to CoinStoreInfo and place them in the correct positions
             for ((_, original_idx), state_opt) in fields_with_indices.into_iter().zip(state_kvs) {
@@ -134,13 +137,15 @@ impl AggregateService {
         Ok(results)
     }
 
+    /// Get the balance at the `state_root`, or at the latest state if it is `None`.
     pub async fn get_balance(
         &self,
         account_addr: KanariAddress,
         coin_type: StructTag,
+        state_root: Option<H256>,
     ) -> Result<BalanceInfoView> {
         let coin_info = self
-            .get_coin_infos(vec![coin_type.clone()])
+            .get_coin_infos(vec![coin_type.clone()], state_root)
             .await?
             .into_values()
             .flatten()
@@ -152,7 +157,7 @@ impl AggregateService {
         let coin_store_id =
             AccountCoinStoreModule::account_coin_store_id(account_addr.into(), coin_type.clone());
         let mut balance = self
-            .get_coin_stores(vec![coin_store_id])
+            .get_coin_stores(vec![coin_store_id], state_root)
             .await?
             .pop()
             .flatten()
@@ -162,7 +167,7 @@ impl AggregateService {
         // Compatible logic, query coin store and multi coin store at the same time
         if is_multi_coin_store_enabled() {
             let multi_coin_store_balance = self
-                .get_balance_by_type_name(account_addr, coin_type)
+                .get_balance_by_type_name(account_addr, coin_type, state_root)
                 .await?;
             balance += multi_coin_store_balance.balance.0;
         }
@@ -174,9 +179,10 @@ impl AggregateService {
         &self,
         account_addr: KanariAddress,
         coin_type: StructTag,
+        state_root: Option<H256>,
     ) -> Result<BalanceInfoView> {
         let coin_info = self
-            .get_coin_infos(vec![coin_type.clone()])
+            .get_coin_infos(vec![coin_type.clone()], state_root)
             .await?
             .into_values()
             .flatten()
@@ -187,7 +193,7 @@ impl AggregateService {
 
         let coin_store_id = AccountCoinStoreModule::multi_coin_store_id(account_addr.into());
         let balance = self
-            .get_multi_coin_store_fields(vec![(coin_store_id, coin_type.clone())])
+            .get_multi_coin_store_fields(vec![(coin_store_id, coin_type.clone())], state_root)
             .await?
             .pop()
             .flatten()
@@ -231,7 +237,7 @@ impl AggregateService {
             .map(|(id, _state_id)| id.clone())
             .collect::<Vec<_>>();
 
-        let coin_stores = self.get_coin_stores(coin_store_ids.clone()).await?;
+        let coin_stores = self.get_coin_stores(coin_store_ids.clone(), None).await?;
 
         let coin_types = coin_stores
             .iter()
@@ -239,7 +245,7 @@ impl AggregateService {
             .map(|coin_store| coin_store.coin_type())
             .collect::<Vec<_>>();
 
-        let coin_info_map = self.get_coin_infos(coin_types.clone()).await?;
+        let coin_info_map = self.get_coin_infos(coin_types.clone(), None).await?;
 
         // Pre-fetch multi-coin store balances if the feature is enabled
         let multi_coin_store_balances: HashMap<StructTag, CoinStoreInfo> =
@@ -255,7 +261,7 @@ impl AggregateService {
                 // Batch query all fields at once
                 if !field_with_coin_store_ids.is_empty() {
                     let multi_coin_store_fields = self
-                        .get_multi_coin_store_fields(field_with_coin_store_ids)
+                        .get_multi_coin_store_fields(field_with_coin_store_ids, None)
                         .await?;
 
                     // Build a map of coin_type -> balance
@@ -355,7 +361,7 @@ impl AggregateService {
         }
 
         // Get coin info for all coin types in a single batch
-        let coin_info_map = self.get_coin_infos(coin_types.clone()).await?;
+        let coin_info_map = self.get_coin_infos(coin_types.clone(), None).await?;
 
         // Build the final result
         for ((field_key, coin_store_info), coin_type) in
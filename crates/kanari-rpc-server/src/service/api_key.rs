@@ -0,0 +1,378 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::axum_router::from_template;
+use crate::service::metrics::ServiceMetrics;
+use axum::extract::{MatchedPath, Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use dashmap::DashMap;
+use http::{HeaderMap, StatusCode};
+use jsonrpsee::types::{ErrorCode, ErrorObject, ErrorObjectOwned, Id};
+use jsonrpsee::MethodResponse;
+use kanari_config::api_key_config::{ApiKeyConfig, ApiKeyEntry, QuotaConfig};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The header which carries the API key of a request.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+pub const UNAUTHORIZED_CODE: i32 = 32006;
+pub const UNAUTHORIZED_MSG: &str = "Missing or invalid API key";
+
+/// The label of requests without an API key.
+pub const ANONYMOUS_KEY_LABEL: &str = "anonymous";
+/// The label of requests with an unknown API key, the key itself is never used as a label.
+pub const UNKNOWN_KEY_LABEL: &str = "unknown";
+
+/// The JSON-RPC routes check the quotas by the method of every call in the `JsonRpcService`,
+/// the other routes check the quotas by the route path, such as `/graphql` or `/content/:inscription_id`.
+pub const JSON_RPC_ROUTES: [&str; 2] = ["/", "/subscribe"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiKeyRejection {
+    MissingKey,
+    InvalidKey,
+    QuotaExceeded { key_name: String, wait_time: u64 },
+}
+
+impl ApiKeyRejection {
+    pub fn key_label(&self) -> &str {
+        match self {
+            ApiKeyRejection::MissingKey => ANONYMOUS_KEY_LABEL,
+            ApiKeyRejection::InvalidKey => UNKNOWN_KEY_LABEL,
+            ApiKeyRejection::QuotaExceeded { key_name, .. } => key_name.as_str(),
+        }
+    }
+
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ApiKeyRejection::MissingKey => "missing_key",
+            ApiKeyRejection::InvalidKey => "invalid_key",
+            ApiKeyRejection::QuotaExceeded { .. } => "quota_exceeded",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiKeyRejection::MissingKey | ApiKeyRejection::InvalidKey => StatusCode::UNAUTHORIZED,
+            ApiKeyRejection::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn into_response(self) -> Response {
+        const JSON: &str = "application/json; charset=utf-8";
+        let status = self.status_code();
+        let body = MethodResponse::error(Id::Null, ErrorObjectOwned::from(self)).to_result();
+        from_template(status, body, JSON)
+    }
+}
+
+impl From<ApiKeyRejection> for ErrorObjectOwned {
+    fn from(rejection: ApiKeyRejection) -> Self {
+        match rejection {
+            ApiKeyRejection::MissingKey | ApiKeyRejection::InvalidKey => {
+                ErrorObject::owned(UNAUTHORIZED_CODE, UNAUTHORIZED_MSG, None::<()>)
+            }
+            ApiKeyRejection::QuotaExceeded { wait_time, .. } => ErrorObject::owned(
+                ErrorCode::ServerIsBusy.code(),
+                format!("Quota exceeded! Wait for {}s", wait_time),
+                None::<()>,
+            ),
+        }
+    }
+}
+
+pub fn extract_api_key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: &QuotaConfig) -> Self {
+        Self {
+            tokens: quota.burst_size as f64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, quota: &QuotaConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * quota.per_second).min(quota.burst_size as f64);
+        self.updated_at = now;
+    }
+
+    /// Take one token, returns the seconds to wait for the next token if the bucket is empty.
+    fn try_acquire(&mut self, quota: &QuotaConfig) -> Result<(), u64> {
+        self.refill(quota, Instant::now());
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if quota.per_second > 0.0 {
+            Err(((1.0 - self.tokens) / quota.per_second).ceil() as u64)
+        } else {
+            Err(u64::MAX)
+        }
+    }
+}
+
+/// Checks API keys and their per-method quotas.
+#[derive(Debug, Default)]
+pub struct ApiKeyStore {
+    require_api_key: bool,
+    keys: HashMap<String, ApiKeyEntry>,
+    buckets: DashMap<(String, String), TokenBucket>,
+}
+
+impl ApiKeyStore {
+    pub fn new(config: ApiKeyConfig) -> Self {
+        Self {
+            require_api_key: config.require_api_key,
+            keys: config
+                .keys
+                .into_iter()
+                .map(|entry| (entry.key.clone(), entry))
+                .collect(),
+            buckets: DashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.require_api_key || !self.keys.is_empty()
+    }
+
+    /// Check the API key of a request without charging any quota,
+    /// returns the entry of the key if the request has a valid key.
+    pub fn authenticate(
+        &self,
+        api_key: Option<&str>,
+    ) -> Result<Option<&ApiKeyEntry>, ApiKeyRejection> {
+        match api_key {
+            Some(api_key) => self
+                .keys
+                .get(api_key)
+                .map(Some)
+                .ok_or(ApiKeyRejection::InvalidKey),
+            None if self.require_api_key => Err(ApiKeyRejection::MissingKey),
+            None => Ok(None),
+        }
+    }
+
+    /// Check the API key and the quota of the method for a request,
+    /// returns the name of the key if the request is allowed and has a key.
+    pub fn check(
+        &self,
+        api_key: Option<&str>,
+        method: &str,
+    ) -> Result<Option<String>, ApiKeyRejection> {
+        let entry = match self.authenticate(api_key)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        if let Some(quota) = entry.quota_of(method) {
+            let mut bucket = self
+                .buckets
+                .entry((entry.name.clone(), method.to_string()))
+                .or_insert_with(|| TokenBucket::new(quota));
+            bucket
+                .try_acquire(quota)
+                .map_err(|wait_time| ApiKeyRejection::QuotaExceeded {
+                    key_name: entry.name.clone(),
+                    wait_time,
+                })?;
+        }
+        Ok(Some(entry.name.clone()))
+    }
+
+    /// Remove the buckets which have been idle for longer than `idle`, they are full again anyway.
+    pub fn retain_recent(&self, idle: Duration) {
+        let now = Instant::now();
+        self.buckets
+            .retain(|_, bucket| now.saturating_duration_since(bucket.updated_at) < idle);
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiKeyLayerState {
+    pub api_keys: Arc<ApiKeyStore>,
+    pub metrics: ServiceMetrics,
+}
+
+/// The middleware which checks the API key of every request of the router, applied via
+/// `axum::middleware::from_fn_with_state`, so GraphQL, SSE and the content routes are covered too.
+pub async fn api_key_middleware(
+    State(state): State<ApiKeyLayerState>,
+    matched_path: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.api_keys.is_enabled() {
+        return next.run(request).await;
+    }
+    let api_key = extract_api_key(request.headers());
+    let route = matched_path.as_ref().map(|path| path.as_str());
+    let result = match route {
+        Some(route) if !JSON_RPC_ROUTES.contains(&route) => {
+            state.api_keys.check(api_key.as_deref(), route).map(|_| ())
+        }
+        // The JSON-RPC methods are charged by the `JsonRpcService`, the unmatched routes are not charged
+        _ => state.api_keys.authenticate(api_key.as_deref()).map(|_| ()),
+    };
+    match result {
+        Ok(()) => next.run(request).await,
+        Err(rejection) => {
+            state.metrics.on_api_key_rejection(
+                rejection.key_label(),
+                route.unwrap_or_default(),
+                rejection.reason(),
+            );
+            rejection.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn new_store(require_api_key: bool) -> ApiKeyStore {
+        ApiKeyStore::new(ApiKeyConfig {
+            require_api_key,
+            keys: vec![ApiKeyEntry {
+                name: "test".to_string(),
+                key: "secret".to_string(),
+                default_quota: None,
+                method_quotas: HashMap::from([(
+                    "kanari_executeRawTransaction".to_string(),
+                    QuotaConfig {
+                        per_second: 0.0,
+                        burst_size: 2,
+                    },
+                )]),
+            }],
+        })
+    }
+
+    #[test]
+    fn test_api_key_check() {
+        let store = new_store(false);
+        assert_eq!(store.check(None, "kanari_queryEvents"), Ok(None));
+        assert_eq!(
+            store.check(Some("wrong"), "kanari_queryEvents"),
+            Err(ApiKeyRejection::InvalidKey)
+        );
+        assert_eq!(
+            store.check(Some("secret"), "kanari_queryEvents"),
+            Ok(Some("test".to_string()))
+        );
+
+        let store = new_store(true);
+        assert_eq!(
+            store.check(None, "kanari_queryEvents"),
+            Err(ApiKeyRejection::MissingKey)
+        );
+    }
+
+    #[test]
+    fn test_api_key_method_quota() {
+        let store = new_store(false);
+        for _ in 0..2 {
+            assert!(store
+                .check(Some("secret"), "kanari_executeRawTransaction")
+                .is_ok());
+        }
+        let rejection = store
+            .check(Some("secret"), "kanari_executeRawTransaction")
+            .unwrap_err();
+        assert_eq!(rejection.key_label(), "test");
+        assert_eq!(rejection.reason(), "quota_exceeded");
+        // other methods are not limited by the quota of `kanari_executeRawTransaction`
+        for _ in 0..10 {
+            assert!(store.check(Some("secret"), "kanari_queryEvents").is_ok());
+        }
+        assert_eq!(store.bucket_count(), 1);
+        store.retain_recent(Duration::ZERO);
+        assert_eq!(store.bucket_count(), 0);
+    }
+
+    async fn call(router: &Router, uri: &str, api_key: Option<&str>) -> StatusCode {
+        let mut request = http::Request::builder().uri(uri);
+        if let Some(api_key) = api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_api_key_middleware() {
+        let mut store = new_store(true);
+        let entry = store.keys.get_mut("secret").unwrap();
+        entry.method_quotas.insert(
+            "/graphql".to_string(),
+            QuotaConfig {
+                per_second: 0.0,
+                burst_size: 1,
+            },
+        );
+        let state = ApiKeyLayerState {
+            api_keys: Arc::new(store),
+            metrics: ServiceMetrics::new(&prometheus::Registry::new(), &["/graphql"]),
+        };
+        let router = Router::new()
+            .route("/", get(|| async { "rpc" }))
+            .route("/graphql", get(|| async { "graphql" }))
+            .route("/subscribe/sse/events", get(|| async { "sse" }))
+            .layer(axum::middleware::from_fn_with_state(
+                state,
+                api_key_middleware,
+            ));
+
+        for uri in ["/", "/graphql", "/subscribe/sse/events"] {
+            assert_eq!(call(&router, uri, None).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(
+                call(&router, uri, Some("wrong")).await,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+        assert_eq!(
+            call(&router, "/graphql", Some("secret")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(&router, "/graphql", Some("secret")).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        // The JSON-RPC route is charged by method in the `JsonRpcService`, not by the route
+        for _ in 0..3 {
+            assert_eq!(call(&router, "/", Some("secret")).await, StatusCode::OK);
+        }
+        assert_eq!(
+            call(&router, "/subscribe/sse/events", Some("secret")).await,
+            StatusCode::OK
+        );
+    }
+}
@@ -41,13 +41,29 @@ impl Default for BlocklistConfig {
     }
 }
 
+impl BlocklistConfig {
+    /// Remove the expired blocked clients, and the rejection counts
+    /// which have not been updated within `client_rejection_expiration`.
+    pub fn clear_expired(&self) {
+        let now = SystemTime::now();
+        self.clients.retain(|_, expiration| now < *expiration);
+        self.rejection_map
+            .retain(|_, rejection| !rejection.is_expired(now, self.client_rejection_expiration));
+    }
+}
+
 #[derive(Debug)]
 pub struct Rejection {
     pub time: SystemTime,
     pub count: usize,
 }
 
-// TODO: clear cache
+impl Rejection {
+    pub fn is_expired(&self, now: SystemTime, expiration: Duration) -> bool {
+        self.time + expiration <= now
+    }
+}
+
 #[derive(Clone)]
 pub struct Blocklists<S> {
     pub key_extractor: SmartIpKeyExtractor,
@@ -215,6 +231,12 @@ where
                                 count: 0,
                             });
 
+                        // only count the rejections within the expiration window
+                        let now = SystemTime::now();
+                        if rejection_entry.is_expired(now, *rejection_expiration) {
+                            rejection_entry.value_mut().count = 0;
+                        }
+                        rejection_entry.value_mut().time = now;
                         rejection_entry.value_mut().count += 1;
                         should_remove = rejection_entry.value().count > *rejection_count;
 
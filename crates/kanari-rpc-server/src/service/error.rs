@@ -11,7 +11,7 @@ use std::fmt;
 use std::sync::Arc;
 use tower_governor::GovernorError;
 
-const TOO_MANY_REQUESTS_MSG: &str = "Too many requests! Wait for ";
+pub(crate) const TOO_MANY_REQUESTS_MSG: &str = "Too many requests! Wait for ";
 
 #[derive(Clone)]
 pub struct ErrorHandler(pub(crate) Arc<dyn Fn(GovernorError) -> Response<Body> + Send + Sync>);
@@ -37,6 +37,8 @@ pub struct ServiceMetrics {
     errors_by_route: IntCounterVec,
     server_errors_by_route: IntCounterVec,
     client_errors_by_route: IntCounterVec,
+    /// Requests rejected by the API key check, by key name, route and reason
+    api_key_rejections: IntCounterVec,
     // Client info
     // client: IntCounterVec,
     // /// Connection count
@@ -94,6 +96,13 @@ impl ServiceMetrics {
                 registry
             )
             .unwrap(),
+            api_key_rejections: register_int_counter_vec_with_registry!(
+                "rpc_api_key_rejections",
+                "Number of requests rejected by the API key check by key, route and reason",
+                &["key", "route", "reason"],
+                registry,
+            )
+            .unwrap(),
             // inflight_connection: register_int_gauge_vec_with_registry!(
             //     "rpc_inflight_connection",
             //     "Number of inflight RPC connection by protocol",
@@ -191,4 +200,11 @@ impl ServiceMetrics {
             self.errors_by_route.with_label_values(&[method_name]).inc();
         }
     }
+
+    pub fn on_api_key_rejection(&self, key_name: &str, method_name: &str, reason: &str) {
+        let method_name = self.check_spam(method_name);
+        self.api_key_rejections
+            .with_label_values(&[key_name, method_name, reason])
+            .inc();
+    }
 }
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod aggregate_service;
+pub mod api_key;
 // pub mod rpc_logger;
 pub mod error;
 pub mod rpc_service;
 
 pub mod routing;
+pub mod rpc_limits;
 
 pub mod blocklist;
 pub mod metrics;
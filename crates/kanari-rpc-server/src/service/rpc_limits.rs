@@ -0,0 +1,196 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use kanari_rpc_api::api::{DEFAULT_RESULT_LIMIT_USIZE, MAX_RESULT_LIMIT_USIZE};
+use kanari_rpc_api::jsonrpc_types::{PageView, RpcLimitsView, StrView};
+use kanari_rpc_api::{RpcError, RpcResult};
+use serde::Serialize;
+use std::cmp::min;
+use std::io;
+
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+/// The bytes reserved for the JSON-RPC envelope and the cursor when fitting a page into a response.
+const PAGE_ENVELOPE_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcLimits {
+    pub max_batch_size: usize,
+    pub max_page_size: usize,
+    pub max_response_bytes: usize,
+}
+
+impl Default for RpcLimits {
+    fn default() -> Self {
+        Self {
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_page_size: MAX_RESULT_LIMIT_USIZE,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+}
+
+impl RpcLimits {
+    pub fn new(
+        max_batch_size: Option<usize>,
+        max_page_size: Option<usize>,
+        max_response_bytes: Option<usize>,
+    ) -> Self {
+        let default = Self::default();
+        Self {
+            max_batch_size: max_batch_size.unwrap_or(default.max_batch_size),
+            max_page_size: max_page_size.unwrap_or(default.max_page_size),
+            max_response_bytes: max_response_bytes.unwrap_or(default.max_response_bytes),
+        }
+    }
+
+    /// The page size of a paginated request.
+    /// Returns `RpcError::PageSizeTooLarge` if the caller-chosen `limit` exceeds `max_page_size`,
+    /// instead of returning a shorter page than requested.
+    pub fn limit_of(&self, limit: Option<StrView<u64>>) -> RpcResult<usize> {
+        match limit.map(|limit| limit.0) {
+            Some(limit) if limit > self.max_page_size as u64 => Err(RpcError::PageSizeTooLarge {
+                limit,
+                max_page_size: self.max_page_size,
+            }),
+            Some(limit) => Ok(limit as usize),
+            None => Ok(min(DEFAULT_RESULT_LIMIT_USIZE, self.max_page_size)),
+        }
+    }
+
+    /// The `max_response_bytes` as the `max_response_body_size` of jsonrpsee.
+    pub fn max_response_body_size(&self) -> u32 {
+        u32::try_from(self.max_response_bytes).unwrap_or(u32::MAX)
+    }
+
+    /// Shorten the page so that it fits in `max_response_bytes`, the `next_cursor` points to the
+    /// last item that fits so clients can continue from it.
+    /// Returns `RpcError::ResponseTooLarge` with the cursor to skip the first item if even it does not fit.
+    pub fn fit_page<T, C>(
+        &self,
+        page: &mut PageView<T, C>,
+        cursor_of: impl Fn(&T) -> C,
+    ) -> RpcResult<()>
+    where
+        T: Serialize,
+        C: Serialize,
+    {
+        let budget = self.max_response_bytes.saturating_sub(PAGE_ENVELOPE_BYTES);
+        let mut size = 0usize;
+        let mut fit = 0usize;
+        for item in page.data.iter() {
+            // one more byte for the `,` between items
+            size = size.saturating_add(serialized_size(item)?.saturating_add(1));
+            if size > budget {
+                break;
+            }
+            fit += 1;
+        }
+
+        if fit == page.data.len() {
+            return Ok(());
+        }
+        if fit == 0 {
+            return Err(RpcError::ResponseTooLarge {
+                max_response_bytes: self.max_response_bytes,
+                next_cursor: serde_json::to_value(cursor_of(&page.data[0])).ok(),
+            });
+        }
+        page.data.truncate(fit);
+        page.has_next_page = true;
+        page.next_cursor = page.data.last().map(cursor_of);
+        Ok(())
+    }
+}
+
+impl From<RpcLimits> for RpcLimitsView {
+    fn from(limits: RpcLimits) -> Self {
+        Self {
+            max_batch_size: StrView(limits.max_batch_size as u64),
+            max_page_size: StrView(limits.max_page_size as u64),
+            max_response_bytes: StrView(limits.max_response_bytes as u64),
+        }
+    }
+}
+
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn serialized_size<T: Serialize>(value: &T) -> RpcResult<usize> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value).map_err(|e| RpcError::InternalError(e.into()))?;
+    Ok(counter.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(len: u64) -> PageView<String, StrView<u64>> {
+        PageView {
+            data: (0..len).map(|i| format!("{:0>98}", i)).collect(),
+            next_cursor: Some(StrView(len - 1)),
+            has_next_page: false,
+        }
+    }
+
+    #[test]
+    fn test_fit_page() {
+        // every item is 100 bytes in json, and 1 byte for the `,`
+        let limits = RpcLimits::new(None, None, Some(PAGE_ENVELOPE_BYTES + 101 * 3));
+
+        let mut small_page = page(3);
+        limits.fit_page(&mut small_page, |_| StrView(0)).unwrap();
+        assert_eq!(small_page, page(3));
+
+        let mut large_page = page(10);
+        limits
+            .fit_page(&mut large_page, |item| StrView(item.parse().unwrap()))
+            .unwrap();
+        assert_eq!(large_page.data.len(), 3);
+        assert!(large_page.has_next_page);
+        assert_eq!(large_page.next_cursor, Some(StrView(2)));
+
+        let limits = RpcLimits::new(None, None, Some(PAGE_ENVELOPE_BYTES + 50));
+        let err = limits
+            .fit_page(&mut page(10), |item| StrView(item.parse().unwrap()))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RpcError::ResponseTooLarge {
+                next_cursor: Some(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_limit_of() {
+        let limits = RpcLimits::new(None, Some(100), None);
+        assert_eq!(limits.limit_of(None).unwrap(), DEFAULT_RESULT_LIMIT_USIZE);
+        assert_eq!(limits.limit_of(Some(StrView(20))).unwrap(), 20);
+        assert_eq!(limits.limit_of(Some(StrView(100))).unwrap(), 100);
+        assert!(matches!(
+            limits.limit_of(Some(StrView(1000))).unwrap_err(),
+            RpcError::PageSizeTooLarge {
+                limit: 1000,
+                max_page_size: 100
+            }
+        ));
+
+        // The default page size is reduced to a smaller max page size
+        let limits = RpcLimits::new(None, Some(10), None);
+        assert_eq!(limits.limit_of(None).unwrap(), 10);
+    }
+}
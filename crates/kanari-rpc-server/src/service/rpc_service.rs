@@ -1,9 +1,11 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::service::rpc_limits::RpcLimits;
 use anyhow::{bail, format_err, Result};
 use bitcoin_client::proxy::BitcoinClientProxy;
-use bitcoincore_rpc::bitcoin::Txid;
+use bitcoincore_rpc::bitcoin::blockdata::constants::SUBSIDY_HALVING_INTERVAL;
+use bitcoincore_rpc::bitcoin::{Amount, FeeRate, Network, Transaction, Txid};
 use futures::{Stream, StreamExt};
 use jsonrpsee::core::SubscriptionResult;
 use jsonrpsee::PendingSubscriptionSink;
@@ -17,34 +19,37 @@ use moveos_types::module_binding::MoveFunctionCaller;
 use moveos_types::move_types::type_tag_match;
 use moveos_types::moveos_std::display::{get_object_display_id, RawDisplay};
 use moveos_types::moveos_std::event::{AnnotatedEvent, Event, EventID};
-use moveos_types::moveos_std::object::{ObjectID, MAX_OBJECT_IDS_PER_QUERY};
-use moveos_types::state::{AnnotatedState, FieldKey, ObjectState, StateChangeSet};
+use moveos_types::moveos_std::object::{ObjectID, ObjectMeta, MAX_OBJECT_IDS_PER_QUERY};
+use moveos_types::state::{AnnotatedState, FieldKey, MoveState, ObjectState, StateChangeSet};
 use moveos_types::state_resolver::{AnnotatedStateKV, StateKV};
 use moveos_types::transaction::{FunctionCall, TransactionExecutionInfo};
 use kanari_da::proxy::DAServerProxy;
-use kanari_executor::actor::messages::DryRunTransactionResult;
+use kanari_executor::actor::messages::{DryRunTransactionResult, TraceTransactionResult};
 use kanari_executor::proxy::ExecutorProxy;
 use kanari_indexer::proxy::IndexerProxy;
 use kanari_notify::subscription_handler::SubscriptionHandler;
 use kanari_pipeline_processor::proxy::PipelineProcessorProxy;
+use kanari_rpc_api::jsonrpc_types::btc::fee_rate::{FeeRateSourceView, FeeRateView};
 use kanari_rpc_api::jsonrpc_types::event_view::EventFilterView;
 use kanari_rpc_api::jsonrpc_types::field_view::IndexerFieldView;
 use kanari_rpc_api::jsonrpc_types::transaction_view::TransactionFilterView;
 use kanari_rpc_api::jsonrpc_types::{
-    BitcoinStatus, DisplayFieldsView, IndexerObjectStateView, ObjectMetaView, KanariStatus, Status,
+    BitcoinStatus, DisplayFieldsView, IndexerObjectStateView, ObjectMetaView,
+    ObjectStateFilterView, KanariStatus, Status,
 };
 use kanari_sequencer::proxy::SequencerProxy;
 use kanari_types::address::{BitcoinAddress, KanariAddress};
+use kanari_types::bitcoin::brc20::{BRC20BalanceInfo, BRC20CoinInfo, BRC20Module};
+use kanari_types::bitcoin::ord::{self, Inscription, InscriptionID, InscriptionStore};
 use kanari_types::bitcoin::pending_block::PendingBlockModule;
-use kanari_types::bitcoin::BitcoinModule;
+use kanari_types::bitcoin::{types, BitcoinBlockStore, BitcoinModule};
 use kanari_types::framework::address_mapping::KanariToBitcoinAddressMapping;
-use kanari_types::indexer::event::{
-    AnnotatedIndexerEvent, EventFilter, IndexerEvent, IndexerEventID,
-};
+use kanari_types::into_address::IntoAddress;
+use kanari_types::indexer::event::{AnnotatedIndexerEvent, EventFilter, IndexerEvent, IndexerEventID};
 use kanari_types::indexer::field::{FieldFilter, IndexerField};
 use kanari_types::indexer::state::{
     IndexerObjectState, IndexerStateID, ObjectStateFilter, ObjectStateType, INSCRIPTION_TYPE_TAG,
-    UTXO_TYPE_TAG,
+    RUNE_BALANCES_TYPE_TAG, UTXO_TYPE_TAG,
 };
 use kanari_types::indexer::transaction::{IndexerTransaction, TransactionFilter};
 use kanari_types::repair::{RepairIndexerParams, RepairIndexerType};
@@ -53,9 +58,11 @@ use kanari_types::transaction::{
     ExecuteTransactionResponse, LedgerTransaction, KanariTransaction, KanariTransactionData,
 };
 use serde::Serialize;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
 use std::sync::Arc;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
 
 pub fn spawn_subscription<S, T>(
     sink: PendingSubscriptionSink,
@@ -97,6 +104,13 @@ pub fn spawn_subscription<S, T>(
     });
 }
 const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+/// The number of recent blocks to estimate the fee rate when the Bitcoin node has no estimation
+const FEE_RATE_RECENT_BLOCKS: u64 = 6;
+/// The percentile of the block weight to take the fee rate of a block,
+/// half of the block weight pays a higher fee rate.
+const FEE_RATE_PERCENTILE: u64 = 50;
+/// The number of relayed transactions to read in one batch
+const FEE_RATE_TX_BATCH_SIZE: u64 = 500;
 
 /// RpcService is the implementation of the RPC service.
 /// It is the glue between the RPC server(EthAPIServer,KanariApiServer) and the kanari's actors.
@@ -114,6 +128,7 @@ pub struct RpcService {
     // pub(crate) notify: NotifyProxy,
     pub(crate) subscription_handler: Arc<SubscriptionHandler>,
     pub(crate) subscription_semaphore: Arc<Semaphore>,
+    pub(crate) limits: RpcLimits,
 }
 
 impl RpcService {
@@ -128,6 +143,7 @@ impl RpcService {
         da_server: DAServerProxy,
         subscription_handler: Arc<SubscriptionHandler>,
         max_subscriptions: Option<usize>,
+        limits: RpcLimits,
     ) -> Self {
         let max_subscriptions = max_subscriptions.unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
         Self {
@@ -141,11 +157,16 @@ impl RpcService {
             da_server,
             subscription_handler,
             subscription_semaphore: Arc::new(Semaphore::new(max_subscriptions)),
+            limits,
         }
     }
 }
 
 impl RpcService {
+    pub fn limits(&self) -> &RpcLimits {
+        &self.limits
+    }
+
     pub fn get_chain_id(&self) -> u64 {
         self.chain_id
     }
@@ -164,21 +185,40 @@ impl RpcService {
         self.pipeline_processor.execute_l2_tx(tx).await
     }
 
-    pub async fn dry_run_tx(&self, tx: KanariTransactionData) -> Result<DryRunTransactionResult> {
+    pub async fn dry_run_tx(
+        &self,
+        tx: KanariTransactionData,
+        gas_profile: bool,
+    ) -> Result<DryRunTransactionResult> {
         let verified_tx = self.executor.convert_to_verified_tx(tx).await?;
-        self.executor.dry_run_transaction(verified_tx).await
+        self.executor
+            .dry_run_transaction(verified_tx, gas_profile)
+            .await
+    }
+
+    /// Re-execute the transaction on the state root before it, and trace the execution.
+    pub async fn trace_transaction(
+        &self,
+        tx: LedgerTransaction,
+        pre_root: ObjectMeta,
+    ) -> Result<TraceTransactionResult> {
+        self.executor.trace_transaction(tx, pre_root).await
     }
 
     pub async fn execute_view_function(
         &self,
         function_call: FunctionCall,
+        state_root: Option<H256>,
     ) -> Result<AnnotatedFunctionResult> {
         let module_id = function_call.function_id.module_id.clone();
-        if !self.exists_module(module_id.clone()).await? {
+        if !self.exists_module(module_id.clone(), state_root).await? {
             return Err(anyhow::anyhow!("Module does not exist: {}", module_id));
         }
 
-        let resp = self.executor.execute_view_function(function_call).await?;
+        let resp = self
+            .executor
+            .execute_view_function(function_call, state_root)
+            .await?;
         Ok(resp)
     }
 
@@ -190,9 +230,13 @@ impl RpcService {
         self.executor.get_states(access_path, state_root).await
     }
 
-    pub async fn exists_module(&self, module_id: ModuleId) -> Result<bool> {
+    pub async fn exists_module(
+        &self,
+        module_id: ModuleId,
+        state_root: Option<H256>,
+    ) -> Result<bool> {
         let mut resp = self
-            .get_states(AccessPath::module(&module_id), None)
+            .get_states(AccessPath::module(&module_id), state_root)
             .await?;
         Ok(resp.pop().flatten().is_some())
     }
@@ -307,6 +351,40 @@ impl RpcService {
         Ok(resp)
     }
 
+    /// Get the execution info of the transaction with the tx_order, which carries the state root at the end of the transaction.
+    pub async fn get_execution_info_by_order(
+        &self,
+        tx_order: u64,
+    ) -> Result<Option<TransactionExecutionInfo>> {
+        let tx_hash = match self.get_tx_hashes(vec![tx_order]).await?.pop().flatten() {
+            Some(tx_hash) => tx_hash,
+            None => return Ok(None),
+        };
+        Ok(self
+            .get_transaction_execution_infos_by_hash(vec![tx_hash])
+            .await?
+            .pop()
+            .flatten())
+    }
+
+    /// Find the tx_order of the last transaction at or before the timestamp via the indexer.
+    pub async fn get_tx_order_by_timestamp(&self, timestamp: u64) -> Result<Option<u64>> {
+        let filter = TransactionFilter::TimeRange {
+            start_time: 0,
+            end_time: timestamp.saturating_add(1),
+        };
+        Ok(self
+            .query_transactions(filter, None, 1, true)
+            .await?
+            .pop()
+            .map(|tx| tx.tx_order))
+    }
+
+    /// Check whether the state of the state root is available, it is not if the state has been pruned.
+    pub async fn contains_state_root(&self, state_root: H256) -> Result<bool> {
+        self.executor.contains_state_root(state_root).await
+    }
+
     pub async fn get_sequencer_order(&self) -> Result<u64> {
         let resp = self.sequencer.get_sequencer_order().await?;
         Ok(resp)
@@ -573,6 +651,50 @@ impl RpcService {
         Ok(address_mapping)
     }
 
+    /// Get the delegates of the inscriptions via the fields of the InscriptionStore
+    pub async fn get_inscription_delegates(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<InscriptionID>>> {
+        let keys = inscription_ids
+            .iter()
+            .map(ord::derive_inscription_delegate_key)
+            .collect::<Vec<_>>();
+        let access_path = AccessPath::fields(InscriptionStore::object_id(), keys);
+        self.get_states(access_path, None)
+            .await?
+            .into_iter()
+            .map(|state_opt| {
+                state_opt
+                    .map(|state| {
+                        state
+                            .value_as_df::<InscriptionID, InscriptionID>()
+                            .map(|df| df.value)
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    pub async fn get_inscriptions(
+        &self,
+        inscription_ids: &[InscriptionID],
+    ) -> Result<Vec<Option<Inscription>>> {
+        let object_ids = inscription_ids
+            .iter()
+            .map(ord::derive_inscription_id)
+            .collect::<Vec<_>>();
+        self.get_states(AccessPath::objects(object_ids), None)
+            .await?
+            .into_iter()
+            .map(|state_opt| {
+                state_opt
+                    .map(|state| state.value_as::<Inscription>())
+                    .transpose()
+            })
+            .collect()
+    }
+
     pub async fn get_display_fields_and_render(
         &self,
         states: &[&AnnotatedState],
@@ -625,6 +747,30 @@ impl RpcService {
         Ok(display_field_views)
     }
 
+    /// Get the BRC20 tick info via the brc20 module of the bitcoin move framework
+    pub async fn get_brc20_tick_info(&self, tick: String) -> Result<Option<BRC20CoinInfo>> {
+        let brc20_module = self.executor.as_module_binding::<BRC20Module>();
+        brc20_module.get_tick_info(tick)
+    }
+
+    /// Get the BRC20 balances of the address, with the tick info of every balance
+    pub async fn get_brc20_balances(
+        &self,
+        owner: AccountAddress,
+    ) -> Result<Vec<(BRC20BalanceInfo, BRC20CoinInfo)>> {
+        let brc20_module = self.executor.as_module_binding::<BRC20Module>();
+        brc20_module
+            .get_balances(owner)?
+            .into_iter()
+            .map(|balance| {
+                let tick_info = brc20_module
+                    .get_tick_info(balance.tick.to_string())?
+                    .ok_or_else(|| format_err!("BRC20 tick {} not found", balance.tick))?;
+                Ok((balance, tick_info))
+            })
+            .collect()
+    }
+
     pub async fn broadcast_bitcoin_transaction(
         &self,
         hex: String,
@@ -641,6 +787,199 @@ impl RpcService {
             .await
     }
 
+    /// Estimate the fee rate for a Bitcoin transaction to be confirmed within `conf_target` blocks.
+    /// Use the `estimatesmartfee` of the Bitcoin node, if the node has no estimation, such as a fresh node,
+    /// fall back to the median fee rate of the recent blocks in the relayed Bitcoin block store.
+    pub async fn estimate_bitcoin_fee_rate(&self, conf_target: u16) -> Result<FeeRateView> {
+        if let Some(bitcoin_client) = &self.bitcoin_client {
+            match bitcoin_client.estimate_smart_fee(conf_target, None).await {
+                Ok(result) => match result.fee_rate {
+                    Some(fee_rate) => {
+                        // The fee rate of estimatesmartfee is in BTC/kvB, 1 vB is 4 wu
+                        let fee_rate = FeeRate::from_sat_per_kwu(fee_rate.to_sat() / 4);
+                        return Ok(FeeRateView::new(
+                            fee_rate,
+                            result.blocks as u16,
+                            FeeRateSourceView::EstimateSmartFee,
+                        ));
+                    }
+                    None => debug!("No fee rate from estimatesmartfee: {:?}", result.errors),
+                },
+                Err(e) => debug!("Failed to call estimatesmartfee: {:?}", e),
+            }
+        }
+
+        let latest_block = self
+            .executor
+            .as_module_binding::<BitcoinModule>()
+            .get_latest_block()?
+            .ok_or_else(|| anyhow::anyhow!("No Bitcoin block is relayed"))?;
+        let start_height = latest_block
+            .block_height
+            .saturating_sub(FEE_RATE_RECENT_BLOCKS - 1);
+        let network = Network::from(kanari_types::bitcoin::network::Network::from(
+            self.bitcoin_network,
+        ));
+        let block_store = self
+            .get_states(AccessPath::object(BitcoinBlockStore::object_id()), None)
+            .await?
+            .pop()
+            .flatten()
+            .ok_or_else(|| anyhow::anyhow!("The Bitcoin block store is not found"))?
+            .value_as::<BitcoinBlockStore>()?;
+        let mut fee_rates = vec![];
+        for (height, txs) in self
+            .get_relayed_txs_since(&block_store, start_height)
+            .await?
+        {
+            let Some(coinbase) = txs.iter().find(|tx| tx.is_coinbase()) else {
+                continue;
+            };
+            let tx_fees = self.get_relayed_tx_fees(&block_store, &txs).await?;
+            if let Some(fee_rate) = block_fee_rate(coinbase, height, network, &tx_fees) {
+                fee_rates.push(fee_rate);
+            }
+        }
+        if fee_rates.is_empty() {
+            bail!("No fee rate data in the recent relayed blocks");
+        }
+        fee_rates.sort();
+        let fee_rate = fee_rates[fee_rates.len() / 2].max(FeeRate::BROADCAST_MIN);
+        Ok(FeeRateView::new(
+            fee_rate,
+            conf_target,
+            FeeRateSourceView::RecentBlocks,
+        ))
+    }
+
+    /// Get the values of the table fields, None if the key is not in the table.
+    async fn get_table_values<K, V>(&self, table: ObjectID, keys: &[K]) -> Result<Vec<Option<V>>>
+    where
+        K: MoveState + Debug,
+        V: MoveState,
+    {
+        let field_keys = keys
+            .iter()
+            .map(FieldKey::derive)
+            .collect::<Result<Vec<_>>>()?;
+        self.get_states(AccessPath::fields(table, field_keys), None)
+            .await?
+            .into_iter()
+            .map(|state_opt| {
+                state_opt
+                    .map(|state| state.value_as_df::<K, V>().map(|df| df.value))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// Read the relayed transactions from the Bitcoin block store since `start_height`, grouped by the block height.
+    /// The transaction ids are scanned backward from the end of the relay order.
+    async fn get_relayed_txs_since(
+        &self,
+        block_store: &BitcoinBlockStore,
+        start_height: u64,
+    ) -> Result<BTreeMap<u64, Vec<Transaction>>> {
+        let mut end = self
+            .get_states(AccessPath::object(block_store.tx_ids), None)
+            .await?
+            .pop()
+            .flatten()
+            .map(|state| state.metadata.size)
+            .unwrap_or_default();
+
+        let mut blocks: BTreeMap<u64, Vec<Transaction>> = BTreeMap::new();
+        let mut seen_txids = HashSet::new();
+        while end > 0 {
+            let begin = end.saturating_sub(FEE_RATE_TX_BATCH_SIZE);
+            let indexes = (begin..end).rev().collect::<Vec<_>>();
+            end = begin;
+            // A repeat txid is appended again, only the latest one is counted
+            let txids = self
+                .get_table_values::<u64, AccountAddress>(block_store.tx_ids, &indexes)
+                .await?
+                .into_iter()
+                .flatten()
+                .filter(|txid| seen_txids.insert(*txid))
+                .collect::<Vec<_>>();
+            let heights = self
+                .get_table_values::<AccountAddress, u64>(block_store.tx_to_height, &txids)
+                .await?;
+            let txs = self
+                .get_table_values::<AccountAddress, types::Transaction>(block_store.txs, &txids)
+                .await?;
+            let mut reach_start = false;
+            for (height, tx) in heights.into_iter().zip(txs) {
+                let (Some(height), Some(tx)) = (height, tx) else {
+                    continue;
+                };
+                if height < start_height {
+                    reach_start = true;
+                    continue;
+                }
+                blocks.entry(height).or_default().push(tx.into());
+            }
+            if reach_start {
+                break;
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// The weight and the fee of the transactions except the coinbase,
+    /// the fee is None if the prevouts of the transaction are not in the relayed Bitcoin block store.
+    async fn get_relayed_tx_fees(
+        &self,
+        block_store: &BitcoinBlockStore,
+        txs: &[Transaction],
+    ) -> Result<Vec<(u64, Option<Amount>)>> {
+        let txs = txs
+            .iter()
+            .filter(|tx| !tx.is_coinbase())
+            .collect::<Vec<_>>();
+        let mut prev_txids = txs
+            .iter()
+            .flat_map(|tx| tx.input.iter().map(|input| input.previous_output.txid))
+            .collect::<Vec<_>>();
+        prev_txids.sort();
+        prev_txids.dedup();
+        let prev_txid_addresses = prev_txids
+            .iter()
+            .map(|txid| txid.into_address())
+            .collect::<Vec<_>>();
+        let prev_txs = prev_txids
+            .into_iter()
+            .zip(
+                self.get_table_values::<AccountAddress, types::Transaction>(
+                    block_store.txs,
+                    &prev_txid_addresses,
+                )
+                .await?,
+            )
+            .filter_map(|(txid, tx)| tx.map(|tx| (txid, tx)))
+            .collect::<HashMap<Txid, types::Transaction>>();
+        Ok(txs
+            .into_iter()
+            .map(|tx| {
+                let total_input = tx
+                    .input
+                    .iter()
+                    .map(|input| {
+                        prev_txs
+                            .get(&input.previous_output.txid)
+                            .and_then(|prev_tx| {
+                                prev_tx.output.get(input.previous_output.vout as usize)
+                            })
+                            .map(|output| Amount::from_sat(output.value))
+                    })
+                    .sum::<Option<Amount>>();
+                let total_output = tx.output.iter().map(|output| output.value).sum::<Amount>();
+                let fee = total_input.and_then(|total_input| total_input.checked_sub(total_output));
+                (tx.weight().to_wu(), fee)
+            })
+            .collect())
+    }
+
     pub async fn repair_indexer(
         &self,
         repair_type: RepairIndexerType,
@@ -663,6 +1002,7 @@ impl RpcService {
                             ObjectStateType::ObjectState,
                             ObjectStateType::UTXO,
                             ObjectStateType::Inscription,
+                            ObjectStateType::RuneBalances,
                         ] {
                             self.repair_indexer_object_states(
                                 states.clone(),
@@ -720,6 +1060,12 @@ impl RpcService {
                                 object_states_mapping.insert(state.metadata.id.clone(), state);
                             }
                         }
+                        ObjectStateType::RuneBalances => {
+                            if type_tag_match(&state.metadata.object_type, &RUNE_BALANCES_TYPE_TAG)
+                            {
+                                object_states_mapping.insert(state.metadata.id.clone(), state);
+                            }
+                        }
                     },
                     None => remove_object_ids.push(object_ids[idx].clone()),
                 }
@@ -891,6 +1237,7 @@ impl RpcService {
             service_status,
             kanari_status,
             bitcoin_status,
+            rpc_limits: Some(self.limits.into()),
         })
     }
 
@@ -995,4 +1342,158 @@ impl RpcService {
         spawn_subscription(sink, stream, Some(permit));
         Ok(())
     }
+
+    pub fn subscribe_object_states(
+        &self,
+        sink: PendingSubscriptionSink,
+        filter: ObjectStateFilterView,
+    ) -> SubscriptionResult {
+        let permit = self.acquire_subscribe_permit()?;
+        let stream = self.subscription_handler.subscribe_object_states(filter);
+        spawn_subscription(sink, stream, Some(permit));
+        Ok(())
+    }
+}
+
+/// The block subsidy at the height, the regtest halves every 150 blocks.
+fn block_subsidy(height: u64, network: Network) -> Amount {
+    let halving_interval = match network {
+        Network::Regtest => 150,
+        _ => SUBSIDY_HALVING_INTERVAL as u64,
+    };
+    let halvings = height / halving_interval;
+    if halvings >= 64 {
+        Amount::ZERO
+    } else {
+        Amount::from_sat(Amount::from_int_btc(50).to_sat() >> halvings)
+    }
+}
+
+/// The fee rate of the block at the `FEE_RATE_PERCENTILE` percentile of the transaction weight.
+/// The `txs` are the weight and the fee of the transactions except the coinbase.
+/// The transactions without a known fee share the rest of the block fee, which is the coinbase outputs minus the block subsidy.
+/// Return None if the block has no transaction except the coinbase.
+fn block_fee_rate(
+    coinbase: &Transaction,
+    height: u64,
+    network: Network,
+    txs: &[(u64, Option<Amount>)],
+) -> Option<FeeRate> {
+    let total_weight = txs.iter().map(|(weight, _)| *weight).sum::<u64>();
+    if total_weight == 0 {
+        return None;
+    }
+    let block_fee = coinbase
+        .output
+        .iter()
+        .map(|output| output.value)
+        .sum::<Amount>()
+        .checked_sub(block_subsidy(height, network))?;
+    let known_fee = txs.iter().filter_map(|(_, fee)| *fee).sum::<Amount>();
+    let unknown_weight = txs
+        .iter()
+        .filter(|(_, fee)| fee.is_none())
+        .map(|(weight, _)| *weight)
+        .sum::<u64>();
+    let unknown_fee = block_fee.checked_sub(known_fee).unwrap_or(Amount::ZERO);
+    let mut fee_rates = txs
+        .iter()
+        .filter(|(weight, _)| *weight > 0)
+        .map(|(weight, fee)| {
+            let fee_rate = match fee {
+                Some(fee) => FeeRate::from_sat_per_kwu(fee.to_sat() * 1000 / weight),
+                None => FeeRate::from_sat_per_kwu(unknown_fee.to_sat() * 1000 / unknown_weight),
+            };
+            (fee_rate, *weight)
+        })
+        .collect::<Vec<_>>();
+    fee_rates.sort();
+    let target_weight = total_weight * FEE_RATE_PERCENTILE / 100;
+    let mut weight = 0;
+    for (fee_rate, tx_weight) in fee_rates {
+        weight += tx_weight;
+        if weight >= target_weight {
+            return Some(fee_rate);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoincore_rpc::bitcoin::{absolute::LockTime, transaction, ScriptBuf, TxOut};
+
+    fn coinbase(value: Amount) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_block_subsidy() {
+        assert_eq!(block_subsidy(0, Network::Bitcoin), Amount::from_int_btc(50));
+        assert_eq!(
+            block_subsidy(209_999, Network::Bitcoin),
+            Amount::from_int_btc(50)
+        );
+        assert_eq!(
+            block_subsidy(210_000, Network::Bitcoin),
+            Amount::from_int_btc(25)
+        );
+        assert_eq!(
+            block_subsidy(840_000, Network::Bitcoin),
+            Amount::from_sat(312_500_000)
+        );
+        assert_eq!(block_subsidy(210_000 * 64, Network::Bitcoin), Amount::ZERO);
+        assert_eq!(
+            block_subsidy(150, Network::Regtest),
+            Amount::from_int_btc(25)
+        );
+    }
+
+    #[test]
+    fn test_block_fee_rate() {
+        let subsidy = block_subsidy(840_000, Network::Bitcoin);
+        // The fee rates are 1, 2 and 10 sat/wu by weight 1000, 2000 and 1000,
+        // the weight median is in the second transaction.
+        let txs = [
+            (1000, Some(Amount::from_sat(1000))),
+            (2000, Some(Amount::from_sat(4000))),
+            (1000, Some(Amount::from_sat(10000))),
+        ];
+        let coinbase_tx = coinbase(subsidy + Amount::from_sat(15000));
+        assert_eq!(
+            block_fee_rate(&coinbase_tx, 840_000, Network::Bitcoin, &txs),
+            Some(FeeRate::from_sat_per_kwu(2000))
+        );
+
+        // The transactions without a known fee share the rest of the block fee
+        let txs = [
+            (1000, Some(Amount::from_sat(1000))),
+            (3000, None),
+            (1000, Some(Amount::from_sat(1000))),
+        ];
+        assert_eq!(
+            block_fee_rate(&coinbase_tx, 840_000, Network::Bitcoin, &txs),
+            Some(FeeRate::from_sat_per_kwu(13000 * 1000 / 3000))
+        );
+
+        // The coinbase does not claim the subsidy
+        assert_eq!(
+            block_fee_rate(&coinbase(Amount::ONE_BTC), 840_000, Network::Bitcoin, &txs),
+            None
+        );
+        // Only the coinbase in the block
+        assert_eq!(
+            block_fee_rate(&coinbase_tx, 840_000, Network::Bitcoin, &[]),
+            None
+        );
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::addresses::BITCOIN_MOVE_ADDRESS;
+use crate::bitcoin::ord::InscriptionID;
 use anyhow::Result;
 use move_core_types::{
     account_address::AccountAddress, ident_str, identifier::IdentStr, u256::U256, value::MoveValue,
@@ -23,13 +24,33 @@ pub struct BRC20CoinInfo {
     pub tick: MoveString,
     pub max: U256,
     pub lim: U256,
-    pub dec: u64,
+    pub dec: u8,
     pub supply: U256,
 }
 
+/// The transfer inscription which is inscribed but not sent yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferInscription {
+    pub inscription_id: InscriptionID,
+    pub tick: MoveString,
+    pub amt: U256,
+    pub from: AccountAddress,
+}
+
+/// The balance of an address for a tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BRC20BalanceInfo {
+    pub tick: MoveString,
+    pub available: U256,
+    pub transferable: U256,
+    pub transfer_inscriptions: Vec<TransferInscription>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BRC20Store {
     pub coins: ObjectID,
+    pub holder_ticks: ObjectID,
+    pub transfer_inscriptions: ObjectID,
 }
 
 impl BRC20Store {
@@ -46,12 +67,18 @@ impl MoveStructType for BRC20Store {
 
 impl MoveStructState for BRC20Store {
     fn struct_layout() -> move_core_types::value::MoveStructLayout {
-        move_core_types::value::MoveStructLayout::new(vec![ObjectID::type_layout()])
+        move_core_types::value::MoveStructLayout::new(vec![
+            ObjectID::type_layout(),
+            ObjectID::type_layout(),
+            ObjectID::type_layout(),
+        ])
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Op {
+    pub from: AccountAddress,
+    pub to: AccountAddress,
     pub json_map: SimpleMap<MoveString, MoveString>,
 }
 
@@ -64,6 +91,8 @@ impl MoveStructType for Op {
 impl MoveStructState for Op {
     fn struct_layout() -> move_core_types::value::MoveStructLayout {
         move_core_types::value::MoveStructLayout::new(vec![
+            AccountAddress::type_layout(),
+            AccountAddress::type_layout(),
             SimpleMap::<MoveString, MoveString>::type_layout(),
         ])
     }
@@ -77,6 +106,7 @@ pub struct BRC20Module<'a> {
 impl<'a> BRC20Module<'a> {
     pub const GET_TICK_INFO_FUNCTION_NAME: &'static IdentStr = ident_str!("get_tick_info");
     pub const GET_BALANCE_FUNCTION_NAME: &'static IdentStr = ident_str!("get_balance");
+    pub const GET_BALANCES_FUNCTION_NAME: &'static IdentStr = ident_str!("get_balances");
 
     pub fn get_tick_info(&self, tick: String) -> Result<Option<BRC20CoinInfo>> {
         let call = Self::create_function_call(
@@ -121,6 +151,29 @@ impl<'a> BRC20Module<'a> {
             })?;
         Ok(result)
     }
+
+    /// Get the balances of all the ticks held by the address
+    pub fn get_balances(&self, addr: AccountAddress) -> Result<Vec<BRC20BalanceInfo>> {
+        let call = Self::create_function_call(
+            Self::GET_BALANCES_FUNCTION_NAME,
+            vec![],
+            vec![
+                BRC20Store::object_id().to_move_value(),
+                MoveValue::Address(addr),
+            ],
+        );
+        let ctx = TxContext::new_readonly_ctx(AccountAddress::ONE);
+        let result = self
+            .caller
+            .call_function(&ctx, call)?
+            .into_result()
+            .map(|mut values| {
+                let value = values.pop().expect("should have one return value");
+                bcs::from_bytes::<Vec<BRC20BalanceInfo>>(&value.value)
+                    .expect("should be a valid vector<BRC20BalanceInfo>")
+            })?;
+        Ok(result)
+    }
 }
 
 impl<'a> ModuleBinding<'a> for BRC20Module<'a> {
@@ -36,6 +36,7 @@ pub mod multisign_account;
 pub mod network;
 pub mod ord;
 pub mod pending_block;
+pub mod runes;
 pub mod types;
 pub mod utxo;
 
@@ -45,7 +46,9 @@ pub mod transaction_validator;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitcoinBlockStore {
-    pub latest_block_height: MoveOption<u64>,
+    /// The genesis start block
+    pub genesis_block: BlockHeightHash,
+    pub latest_block: MoveOption<BlockHeightHash>,
     /// block hash -> block header table id
     pub blocks: ObjectID,
     /// block height -> block hash table id
@@ -54,9 +57,9 @@ pub struct BitcoinBlockStore {
     pub hash_to_height: ObjectID,
     /// tx id -> tx table id
     pub txs: ObjectID,
-    /// tx id -> tx table id
+    /// tx id -> block height table id
     pub tx_to_height: ObjectID,
-    /// tx id list table id
+    /// tx index -> tx id table id, the contents of the TableVec
     pub tx_ids: ObjectID,
 }
 
@@ -75,7 +78,8 @@ impl MoveStructType for BitcoinBlockStore {
 impl MoveStructState for BitcoinBlockStore {
     fn struct_layout() -> move_core_types::value::MoveStructLayout {
         move_core_types::value::MoveStructLayout::new(vec![
-            MoveOption::<u64>::type_layout(),
+            BlockHeightHash::type_layout(),
+            MoveOption::<BlockHeightHash>::type_layout(),
             ObjectID::type_layout(),
             ObjectID::type_layout(),
             ObjectID::type_layout(),
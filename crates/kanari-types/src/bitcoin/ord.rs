@@ -11,7 +11,9 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 use move_core_types::value::MoveTypeLayout;
 use move_core_types::{account_address::AccountAddress, ident_str, identifier::IdentStr};
 use moveos_types::moveos_std::object::ObjectMeta;
-use moveos_types::state::{MoveState, MoveStructState, MoveStructType, MoveType, ObjectState};
+use moveos_types::state::{
+    FieldKey, MoveState, MoveStructState, MoveStructType, MoveType, ObjectState,
+};
 use moveos_types::{
     h256::H256,
     module_binding::{ModuleBinding, MoveFunctionCaller},
@@ -301,6 +303,11 @@ pub fn derive_inscription_id(inscription_id: &InscriptionID) -> ObjectID {
     )
 }
 
+/// The delegate of the inscription is stored as a field of the InscriptionStore, keyed by the inscription id
+pub fn derive_inscription_delegate_key(inscription_id: &InscriptionID) -> FieldKey {
+    FieldKey::derive(inscription_id).expect("Derive field key with InscriptionID should not fail")
+}
+
 #[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize)]
 pub struct Envelope<T> {
     pub input: u32,
@@ -360,6 +367,10 @@ pub struct InscriptionRecord {
     pub pointer: MoveOption<u64>,
     pub unrecognized_even_field: bool,
     pub rune: Option<u128>,
+    /// The delegate is not a field of the Move InscriptionRecord, it is stored in the InscriptionStore
+    /// when the inscription is indexed, so it is skipped when (de)serializing with the Move layout.
+    #[serde(skip)]
+    pub delegate: Option<InscriptionID>,
 }
 
 impl InscriptionRecord {
@@ -395,6 +406,10 @@ impl InscriptionRecord {
         self.rune
     }
 
+    pub fn delegate(&self) -> Option<InscriptionID> {
+        self.delegate
+    }
+
     pub fn set_content_type(&mut self, content_type: String) {
         self.content_type = Some(content_type.into()).into();
     }
@@ -423,6 +438,10 @@ impl InscriptionRecord {
         self.rune = Some(rune);
     }
 
+    pub fn set_delegate(&mut self, delegate: InscriptionID) {
+        self.delegate = Some(delegate);
+    }
+
     pub fn append_reveal_script_to_builder(&self, mut builder: script::Builder) -> script::Builder {
         builder = builder
             .push_opcode(opcodes::OP_FALSE)
@@ -462,8 +481,14 @@ impl InscriptionRecord {
                 })
                 .collect(),
         );
-        //TODO should support delegate
-        //Tag::Delegate.append(&mut builder, &self.delegate);
+        Tag::Delegate.append(
+            &mut builder,
+            &self.delegate.as_ref().map(|delegate| {
+                let mut buffer = delegate.txid.to_vec();
+                buffer.extend_from_slice(&delegate.index.to_le_bytes());
+                buffer
+            }),
+        );
         Tag::Pointer.append(
             &mut builder,
             &self
@@ -507,6 +532,7 @@ impl Debug for InscriptionRecord {
             .field("pointer", &self.pointer)
             .field("unrecognized_even_field", &self.unrecognized_even_field)
             .field("rune", &self.rune)
+            .field("delegate", &self.delegate)
             .finish()
     }
 }
@@ -700,6 +726,10 @@ impl<'a> OrdModule<'a> {
     pub const PARSE_INSCRIPTION_FROM_TX_FUNCTION_NAME: &'static IdentStr =
         ident_str!("parse_inscription_from_tx");
 
+    pub const PARSE_INSCRIPTION_FROM_TX_WITH_DELEGATES_FUNCTION_NAME: &'static IdentStr =
+        ident_str!("parse_inscription_from_tx_with_delegates");
+    pub const DELEGATE_FUNCTION_NAME: &'static IdentStr = ident_str!("delegate");
+
     pub fn parse_inscription_from_tx(
         &self,
         tx: &Transaction,
@@ -721,6 +751,56 @@ impl<'a> OrdModule<'a> {
                 })?;
         Ok(inscriptions)
     }
+
+    /// Parse the inscriptions of the tx, and the delegates in the same order as the inscriptions
+    pub fn parse_inscription_from_tx_with_delegates(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Vec<Envelope<InscriptionRecord>>, Vec<Option<InscriptionID>>)> {
+        let call = Self::create_function_call(
+            Self::PARSE_INSCRIPTION_FROM_TX_WITH_DELEGATES_FUNCTION_NAME,
+            vec![],
+            vec![tx.to_move_value()],
+        );
+        let ctx = TxContext::new_readonly_ctx(AccountAddress::ONE);
+        let (inscriptions, delegates) =
+            self.caller
+                .call_function(&ctx, call)?
+                .into_result()
+                .map(|mut values| {
+                    let delegates = values.pop().expect("should have two return values");
+                    let inscriptions = values.pop().expect("should have two return values");
+                    (
+                        bcs::from_bytes::<Vec<Envelope<InscriptionRecord>>>(&inscriptions.value)
+                            .expect("should be a valid Vec<Inscription>"),
+                        bcs::from_bytes::<Vec<MoveOption<InscriptionID>>>(&delegates.value)
+                            .expect("should be a valid Vec<Option<InscriptionID>>"),
+                    )
+                })?;
+        Ok((
+            inscriptions,
+            delegates.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    pub fn delegate(&self, inscription_id: &InscriptionID) -> Result<Option<InscriptionID>> {
+        let call = Self::create_function_call(
+            Self::DELEGATE_FUNCTION_NAME,
+            vec![],
+            vec![inscription_id.to_move_value()],
+        );
+        let ctx = TxContext::new_readonly_ctx(AccountAddress::ONE);
+        let delegate = self
+            .caller
+            .call_function(&ctx, call)?
+            .into_result()
+            .map(|mut values| {
+                let value = values.pop().expect("should have one return value");
+                bcs::from_bytes::<MoveOption<InscriptionID>>(&value.value)
+                    .expect("should be a valid Option<InscriptionID>")
+            })?;
+        Ok(delegate.into())
+    }
 }
 
 impl<'a> ModuleBinding<'a> for OrdModule<'a> {
@@ -0,0 +1,450 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::types::OutPoint;
+use crate::addresses::BITCOIN_MOVE_ADDRESS;
+use anyhow::{bail, Result};
+use move_core_types::{account_address::AccountAddress, ident_str, identifier::IdentStr};
+use moveos_types::moveos_std::object::{self, ObjectID};
+use moveos_types::moveos_std::simple_map::SimpleMap;
+use moveos_types::state::{MoveState, MoveStructState, MoveStructType};
+use moveos_types::move_std::{option::MoveOption, string::MoveString};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub const MODULE_NAME: &IdentStr = ident_str!("runes");
+
+/// The separator of the spaced rune name
+pub const SPACER: char = '•';
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct RuneId {
+    pub block: u64,
+    pub tx: u32,
+}
+
+impl RuneId {
+    pub fn new(block: u64, tx: u32) -> Self {
+        Self { block, tx }
+    }
+
+    pub fn object_id(&self) -> ObjectID {
+        derive_rune_entry_id(self)
+    }
+}
+
+impl Display for RuneId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.block, self.tx)
+    }
+}
+
+impl FromStr for RuneId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (block, tx) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid RuneId: {}, expect BLOCK:TX", s))?;
+        Ok(Self {
+            block: block.parse()?,
+            tx: tx.parse()?,
+        })
+    }
+}
+
+impl MoveStructType for RuneId {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("RuneId");
+}
+
+impl MoveStructState for RuneId {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![u64::type_layout(), u32::type_layout()])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Edict {
+    pub id: RuneId,
+    pub amount: u128,
+    pub output: u32,
+}
+
+impl MoveStructType for Edict {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Edict");
+}
+
+impl MoveStructState for Edict {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            RuneId::type_layout(),
+            u128::type_layout(),
+            u32::type_layout(),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Terms {
+    pub amount: MoveOption<u128>,
+    pub cap: MoveOption<u128>,
+    pub height_start: MoveOption<u64>,
+    pub height_end: MoveOption<u64>,
+    pub offset_start: MoveOption<u64>,
+    pub offset_end: MoveOption<u64>,
+}
+
+impl MoveStructType for Terms {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Terms");
+}
+
+impl MoveStructState for Terms {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            MoveOption::<u128>::type_layout(),
+            MoveOption::<u128>::type_layout(),
+            MoveOption::<u64>::type_layout(),
+            MoveOption::<u64>::type_layout(),
+            MoveOption::<u64>::type_layout(),
+            MoveOption::<u64>::type_layout(),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Etching {
+    pub divisibility: MoveOption<u8>,
+    pub premine: MoveOption<u128>,
+    pub rune: MoveOption<u128>,
+    pub spacers: MoveOption<u32>,
+    /// The unicode code point of the symbol
+    pub symbol: MoveOption<u32>,
+    pub terms: MoveOption<Terms>,
+    pub turbo: bool,
+}
+
+impl MoveStructType for Etching {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Etching");
+}
+
+impl MoveStructState for Etching {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            MoveOption::<u8>::type_layout(),
+            MoveOption::<u128>::type_layout(),
+            MoveOption::<u128>::type_layout(),
+            MoveOption::<u32>::type_layout(),
+            MoveOption::<u32>::type_layout(),
+            MoveOption::<Terms>::type_layout(),
+            bool::type_layout(),
+        ])
+    }
+}
+
+/// The deciphered runestone of a transaction.
+/// If `cenotaph` is true, the runestone is malformed, `flaw` is the reason, and the etching only carries the rune.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Artifact {
+    pub cenotaph: bool,
+    pub flaw: MoveOption<MoveString>,
+    pub edicts: Vec<Edict>,
+    pub etching: MoveOption<Etching>,
+    pub mint: MoveOption<RuneId>,
+    pub pointer: MoveOption<u32>,
+}
+
+impl MoveStructType for Artifact {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Artifact");
+}
+
+impl MoveStructState for Artifact {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            bool::type_layout(),
+            MoveOption::<MoveString>::type_layout(),
+            Vec::<Edict>::type_layout(),
+            MoveOption::<Etching>::type_layout(),
+            MoveOption::<RuneId>::type_layout(),
+            MoveOption::<u32>::type_layout(),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuneStore {
+    /// The number of etched runes
+    pub runes: u64,
+}
+
+impl RuneStore {
+    pub fn object_id() -> ObjectID {
+        object::named_object_id(&Self::struct_tag())
+    }
+}
+
+impl MoveStructType for RuneStore {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("RuneStore");
+}
+
+impl MoveStructState for RuneStore {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![u64::type_layout()])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuneEntry {
+    pub id: RuneId,
+    pub rune: u128,
+    pub spacers: u32,
+    pub divisibility: u8,
+    pub symbol: MoveOption<u32>,
+    pub premine: u128,
+    pub terms: MoveOption<Terms>,
+    pub mints: u128,
+    pub burned: u128,
+    /// The txid of the etching transaction
+    pub etching: AccountAddress,
+    /// The sequence number of the rune
+    pub number: u64,
+    pub turbo: bool,
+}
+
+impl RuneEntry {
+    pub fn object_id(&self) -> ObjectID {
+        derive_rune_entry_id(&self.id)
+    }
+
+    pub fn spaced_rune(&self) -> SpacedRune {
+        SpacedRune::new(Rune(self.rune), self.spacers)
+    }
+
+    pub fn symbol(&self) -> Option<char> {
+        self.symbol.as_ref().and_then(|s| char::from_u32(*s))
+    }
+}
+
+impl MoveStructType for RuneEntry {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("RuneEntry");
+}
+
+impl MoveStructState for RuneEntry {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            RuneId::type_layout(),
+            u128::type_layout(),
+            u32::type_layout(),
+            u8::type_layout(),
+            MoveOption::<u32>::type_layout(),
+            u128::type_layout(),
+            MoveOption::<Terms>::type_layout(),
+            u128::type_layout(),
+            u128::type_layout(),
+            AccountAddress::type_layout(),
+            u64::type_layout(),
+            bool::type_layout(),
+        ])
+    }
+}
+
+/// The rune balances held by an UTXO
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuneBalances {
+    pub outpoint: OutPoint,
+    pub balances: SimpleMap<RuneId, u128>,
+}
+
+impl RuneBalances {
+    pub fn object_id(&self) -> ObjectID {
+        derive_rune_balances_id(&self.outpoint)
+    }
+}
+
+impl MoveStructType for RuneBalances {
+    const ADDRESS: AccountAddress = BITCOIN_MOVE_ADDRESS;
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("RuneBalances");
+}
+
+impl MoveStructState for RuneBalances {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![
+            OutPoint::type_layout(),
+            SimpleMap::<RuneId, u128>::type_layout(),
+        ])
+    }
+}
+
+pub fn derive_rune_entry_id(id: &RuneId) -> ObjectID {
+    object::custom_object_id_with_parent::<RuneId, RuneEntry>(RuneStore::object_id(), id)
+}
+
+pub fn derive_rune_balances_id(outpoint: &OutPoint) -> ObjectID {
+    object::custom_object_id_with_parent::<OutPoint, RuneBalances>(RuneStore::object_id(), outpoint)
+}
+
+/// The rune name, encoded as a modified base-26 integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rune(pub u128);
+
+impl Display for Rune {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut n = self.0;
+        if n == u128::MAX {
+            return write!(f, "BCGDENLQRQWDSLRUGSNLBTMFIJAV");
+        }
+
+        n += 1;
+        let mut symbol = String::new();
+        while n > 0 {
+            symbol.push(char::from(b'A' + ((n - 1) % 26) as u8));
+            n = (n - 1) / 26;
+        }
+
+        for c in symbol.chars().rev() {
+            write!(f, "{c}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Rune {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut x = 0u128;
+        for (i, c) in s.chars().enumerate() {
+            if i > 0 {
+                x = x
+                    .checked_add(1)
+                    .ok_or_else(|| anyhow::anyhow!("Rune {} out of range", s))?;
+            }
+            x = x
+                .checked_mul(26)
+                .ok_or_else(|| anyhow::anyhow!("Rune {} out of range", s))?;
+            match c {
+                'A'..='Z' => {
+                    x = x
+                        .checked_add(c as u128 - 'A' as u128)
+                        .ok_or_else(|| anyhow::anyhow!("Rune {} out of range", s))?;
+                }
+                _ => bail!("Invalid character `{}` in rune name {}", c, s),
+            }
+        }
+        Ok(Rune(x))
+    }
+}
+
+/// The rune name with the spacers, such as `UNCOMMON•GOODS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpacedRune {
+    pub rune: Rune,
+    pub spacers: u32,
+}
+
+impl SpacedRune {
+    pub fn new(rune: Rune, spacers: u32) -> Self {
+        Self { rune, spacers }
+    }
+}
+
+impl Display for SpacedRune {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rune = self.rune.to_string();
+
+        for (i, c) in rune.chars().enumerate() {
+            write!(f, "{c}")?;
+
+            if i < rune.len() - 1 && self.spacers & (1 << i) != 0 {
+                write!(f, "{SPACER}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SpacedRune {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rune = String::new();
+        let mut spacers = 0u32;
+
+        for c in s.chars() {
+            match c {
+                'A'..='Z' => rune.push(c),
+                '.' | SPACER => {
+                    if rune.is_empty() {
+                        bail!("Leading spacer in rune name {}", s);
+                    }
+                    let flag = 1 << (rune.len() - 1);
+                    if spacers & flag != 0 {
+                        bail!("Double spacer in rune name {}", s);
+                    }
+                    spacers |= flag;
+                }
+                _ => bail!("Invalid character `{}` in rune name {}", c, s),
+            }
+        }
+
+        if 32 - spacers.leading_zeros() >= rune.len() as u32 {
+            bail!("Trailing spacer in rune name {}", s);
+        }
+
+        Ok(SpacedRune {
+            rune: rune.parse()?,
+            spacers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rune_name() {
+        for (n, name) in [
+            (0u128, "A"),
+            (25, "Z"),
+            (26, "AA"),
+            (27, "AB"),
+            (u128::MAX, "BCGDENLQRQWDSLRUGSNLBTMFIJAV"),
+        ] {
+            assert_eq!(Rune(n).to_string(), name);
+            assert_eq!(name.parse::<Rune>().unwrap(), Rune(n));
+        }
+        assert!("BCGDENLQRQWDSLRUGSNLBTMFIJAW".parse::<Rune>().is_err());
+        assert!("a".parse::<Rune>().is_err());
+    }
+
+    #[test]
+    fn test_spaced_rune() {
+        let spaced: SpacedRune = "UNCOMMON•GOODS".parse().unwrap();
+        assert_eq!(spaced.spacers, 0b1000_0000);
+        assert_eq!(spaced.to_string(), "UNCOMMON•GOODS");
+        assert_eq!("UNCOMMON.GOODS".parse::<SpacedRune>().unwrap(), spaced);
+        assert!("•A".parse::<SpacedRune>().is_err());
+        assert!("A•".parse::<SpacedRune>().is_err());
+        assert!("A••B".parse::<SpacedRune>().is_err());
+    }
+}
@@ -178,6 +178,17 @@ impl From<bitcoin::Transaction> for Transaction {
     }
 }
 
+impl From<Transaction> for bitcoin::Transaction {
+    fn from(tx: Transaction) -> Self {
+        Self {
+            version: bitcoin::transaction::Version(tx.version as i32),
+            lock_time: bitcoin::absolute::LockTime::from_consensus(tx.lock_time),
+            input: tx.input.into_iter().map(|tx_in| tx_in.into()).collect(),
+            output: tx.output.into_iter().map(|tx_out| tx_out.into()).collect(),
+        }
+    }
+}
+
 impl MoveStructType for Transaction {
     const MODULE_NAME: &'static IdentStr = MODULE_NAME;
     const STRUCT_NAME: &'static IdentStr = ident_str!("Transaction");
@@ -234,6 +245,17 @@ impl From<bitcoin::TxIn> for TxIn {
     }
 }
 
+impl From<TxIn> for bitcoin::TxIn {
+    fn from(tx_in: TxIn) -> Self {
+        Self {
+            previous_output: tx_in.previous_output.into(),
+            script_sig: bitcoin::ScriptBuf::from_bytes(tx_in.script_sig),
+            sequence: bitcoin::Sequence(tx_in.sequence),
+            witness: tx_in.witness.into(),
+        }
+    }
+}
+
 impl MoveStructType for TxIn {
     const MODULE_NAME: &'static IdentStr = MODULE_NAME;
     const STRUCT_NAME: &'static IdentStr = ident_str!("TxIn");
@@ -264,6 +286,12 @@ impl From<bitcoin::Witness> for Witness {
     }
 }
 
+impl From<Witness> for bitcoin::Witness {
+    fn from(witness: Witness) -> Self {
+        bitcoin::Witness::from_slice(&witness.witness)
+    }
+}
+
 impl MoveStructType for Witness {
     const MODULE_NAME: &'static IdentStr = MODULE_NAME;
     const STRUCT_NAME: &'static IdentStr = ident_str!("Witness");
@@ -444,6 +472,15 @@ impl From<bitcoin::TxOut> for TxOut {
     }
 }
 
+impl From<TxOut> for bitcoin::TxOut {
+    fn from(tx_out: TxOut) -> Self {
+        Self {
+            value: bitcoin::Amount::from_sat(tx_out.value),
+            script_pubkey: bitcoin::ScriptBuf::from_bytes(tx_out.script_pubkey.bytes),
+        }
+    }
+}
+
 impl MoveStructType for TxOut {
     const MODULE_NAME: &'static IdentStr = MODULE_NAME;
     const STRUCT_NAME: &'static IdentStr = ident_str!("TxOut");
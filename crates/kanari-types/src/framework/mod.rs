@@ -3,7 +3,7 @@
 
 /// Types mapping from Framework Move types to Rust types
 /// Module binding for the Framework
-//TODO there modules maybe generated by ABI in the future
+//TODO migrate these modules to the bindings generated by `kanari abi generate-bindings`
 use crate::framework::transaction_validator::TransactionValidator;
 use moveos_types::transaction::FunctionCall;
 
@@ -0,0 +1,56 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::addresses::KANARI_NURSERY_ADDRESS;
+use move_core_types::{account_address::AccountAddress, ident_str, identifier::IdentStr};
+use moveos_types::state::{MoveState, MoveStructState, MoveStructType};
+use serde::{Deserialize, Serialize};
+
+pub const MODULE_NAME: &IdentStr = ident_str!("mint_get_factory");
+
+/// The amount per mint if the `deploy_args` is not provided
+pub const DEFAULT_AMOUNT_PER_MINT: u64 = 10000;
+
+/// The factory type of the bitseed deploy, the `factory` attribute is the canonical string of its struct tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintGetFactory {
+    pub amount_per_mint: u64,
+}
+
+impl MoveStructType for MintGetFactory {
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("MintGetFactory");
+    const ADDRESS: AccountAddress = KANARI_NURSERY_ADDRESS;
+}
+
+impl MoveStructState for MintGetFactory {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![u64::type_layout()])
+    }
+}
+
+/// The `deploy_args` of the MintGetFactory, it is encoded as a CBOR map in the deploy inscription
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployArgs {
+    pub amount_per_mint: u64,
+}
+
+impl Default for DeployArgs {
+    fn default() -> Self {
+        Self {
+            amount_per_mint: DEFAULT_AMOUNT_PER_MINT,
+        }
+    }
+}
+
+impl MoveStructType for DeployArgs {
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const STRUCT_NAME: &'static IdentStr = ident_str!("DeployArgs");
+    const ADDRESS: AccountAddress = KANARI_NURSERY_ADDRESS;
+}
+
+impl MoveStructState for DeployArgs {
+    fn struct_layout() -> move_core_types::value::MoveStructLayout {
+        move_core_types::value::MoveStructLayout::new(vec![u64::type_layout()])
+    }
+}
@@ -1,2 +1,5 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
+
+pub mod mint_get_factory;
+pub mod tick_info;
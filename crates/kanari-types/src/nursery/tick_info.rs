@@ -0,0 +1,62 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::addresses::KANARI_NURSERY_ADDRESS;
+use anyhow::Result;
+use move_core_types::{
+    account_address::AccountAddress, ident_str, identifier::IdentStr, value::MoveValue,
+};
+use moveos_types::{
+    module_binding::{ModuleBinding, MoveFunctionCaller},
+    move_std::string::MoveString,
+    moveos_std::tx_context::TxContext,
+    transaction::FunctionCall,
+};
+
+pub const MODULE_NAME: &IdentStr = ident_str!("tick_info");
+
+/// Rust bindings for KanariNursery tick_info module
+pub struct TickInfoModule<'a> {
+    caller: &'a dyn MoveFunctionCaller,
+}
+
+impl<'a> TickInfoModule<'a> {
+    pub const IS_DEPLOYED_FUNCTION_NAME: &'static IdentStr = ident_str!("is_deployed");
+
+    pub fn is_deployed(&self, metaprotocol: String, tick: String) -> Result<bool> {
+        let call = Self::create_is_deployed_call(metaprotocol, tick);
+        let ctx = TxContext::new_readonly_ctx(AccountAddress::ONE);
+        let deployed = self
+            .caller
+            .call_function(&ctx, call)?
+            .into_result()
+            .map(|mut values| {
+                let value = values.pop().expect("should have one return value");
+                bcs::from_bytes::<bool>(&value.value).expect("should be a valid bool")
+            })?;
+        Ok(deployed)
+    }
+
+    pub fn create_is_deployed_call(metaprotocol: String, tick: String) -> FunctionCall {
+        Self::create_function_call(
+            Self::IS_DEPLOYED_FUNCTION_NAME,
+            vec![],
+            vec![
+                MoveValue::vector_u8(MoveString::from(metaprotocol).to_bytes()),
+                MoveValue::vector_u8(MoveString::from(tick).to_bytes()),
+            ],
+        )
+    }
+}
+
+impl<'a> ModuleBinding<'a> for TickInfoModule<'a> {
+    const MODULE_NAME: &'static IdentStr = MODULE_NAME;
+    const MODULE_ADDRESS: AccountAddress = KANARI_NURSERY_ADDRESS;
+
+    fn new(caller: &'a impl MoveFunctionCaller) -> Self
+    where
+        Self: Sized,
+    {
+        Self { caller }
+    }
+}
@@ -0,0 +1,848 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generate the Rust bindings of Move modules from the module ABI.
+//!
+//! For every module, the generator emits the `MoveStructType`/`MoveStructState` structs of the
+//! module and a `ModuleBinding` with typed `FunctionCall` builders for the public and entry functions.
+//! The structs or functions using types which can not be mapped to Rust are skipped with a comment.
+
+use anyhow::Result;
+use kanari_rpc_api::jsonrpc_types::{
+    ModuleABIView, MoveABIStructTag, MoveABIType, MoveFunctionView, MoveStructView,
+};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use moveos_types::addresses::{MOVEOS_STD_ADDRESS, MOVE_STD_ADDRESS};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io::Write as IoWrite;
+use std::process::{Command, Stdio};
+
+const GENERATED_HEADER: &str = "// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+// This file is generated by `kanari abi generate-bindings`, do not edit it by hand.
+";
+
+/// A builtin address which has a named constant in `framework_types::addresses`.
+struct NamedAddress {
+    literal: &'static str,
+    /// The Move named address.
+    name: &'static str,
+    /// The Rust module of the generated code of the modules at this address.
+    module: &'static str,
+    constant: &'static str,
+    import: &'static str,
+}
+
+const NAMED_ADDRESSES: [NamedAddress; 5] = [
+    NamedAddress {
+        literal: "0x1",
+        name: "std",
+        module: "move_std",
+        constant: "MOVE_STD_ADDRESS",
+        import: "framework_types::addresses::MOVE_STD_ADDRESS",
+    },
+    NamedAddress {
+        literal: "0x2",
+        name: "moveos_std",
+        module: "moveos_std",
+        constant: "MOVEOS_STD_ADDRESS",
+        import: "framework_types::addresses::MOVEOS_STD_ADDRESS",
+    },
+    NamedAddress {
+        literal: "0x3",
+        name: "kanari_framework",
+        module: "kanari_framework",
+        constant: "KANARI_FRAMEWORK_ADDRESS",
+        import: "framework_types::addresses::KANARI_FRAMEWORK_ADDRESS",
+    },
+    NamedAddress {
+        literal: "0x4",
+        name: "bitcoin_move",
+        module: "bitcoin_move",
+        constant: "BITCOIN_MOVE_ADDRESS",
+        import: "framework_types::addresses::BITCOIN_MOVE_ADDRESS",
+    },
+    NamedAddress {
+        literal: "0xa",
+        name: "kanari_nursery",
+        module: "kanari_nursery",
+        constant: "KANARI_NURSERY_ADDRESS",
+        import: "framework_types::addresses::KANARI_NURSERY_ADDRESS",
+    },
+];
+
+/// The Move functions which can not be a method of the module binding, they conflict with `ModuleBinding`.
+const RESERVED_METHOD_NAMES: [&str; 2] = ["new", "function_id"];
+
+const RUST_KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Where a Move type is used, the object arguments are passed by the `ObjectID`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TypePosition {
+    Field,
+    Argument,
+}
+
+pub struct BindingGenerator {
+    modules: Vec<ModuleABIView>,
+    /// The modules to generate, the structs of these modules can be referenced by each other.
+    module_names: BTreeSet<(AccountAddress, String)>,
+}
+
+impl BindingGenerator {
+    pub fn new(modules: Vec<CompiledModule>) -> Self {
+        let modules = modules
+            .into_iter()
+            .map(ModuleABIView::from)
+            .collect::<Vec<_>>();
+        let module_names = modules
+            .iter()
+            .map(|m| (m.address.0, m.name.0.to_string()))
+            .collect();
+        Self {
+            modules,
+            module_names,
+        }
+    }
+
+    /// Generate the bindings of all modules, return the relative file path and the code of every file.
+    /// The modules are grouped by address, the file of a module is `<address>/<module>.rs`,
+    /// and the `mod.rs` files declare them, so the output directory is a Rust module.
+    pub fn generate(&self) -> Result<BTreeMap<String, String>> {
+        let mut files = BTreeMap::new();
+        let mut address_modules: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for module in &self.modules {
+            let address_module = address_module_name(&module.address.0);
+            let code = ModuleGenerator::new(self, module).generate();
+            files.insert(
+                format!("{}/{}.rs", address_module, module.name.0),
+                format_code(code)?,
+            );
+            address_modules
+                .entry(address_module)
+                .or_default()
+                .insert(module.name.0.to_string());
+        }
+        for (address_module, modules) in &address_modules {
+            files.insert(
+                format!("{}/mod.rs", address_module),
+                format_code(mod_file(modules))?,
+            );
+        }
+        files.insert(
+            "mod.rs".to_string(),
+            format_code(mod_file(address_modules.keys()))?,
+        );
+        Ok(files)
+    }
+}
+
+/// Returns true if the module is selected by the name, in the format of `module_name`,
+/// or `address::module_name` where the address is a named address or a hex literal.
+pub fn module_matches(module: &CompiledModule, name: &str) -> bool {
+    let module_id = module.self_id();
+    match name.split_once("::") {
+        Some((address, module_name)) => {
+            let address_matches = match NAMED_ADDRESSES.iter().find(|a| a.name == address) {
+                Some(named) => AccountAddress::from_hex_literal(named.literal).ok(),
+                None => AccountAddress::from_hex_literal(address).ok(),
+            } == Some(*module_id.address());
+            address_matches && module_name == module_id.name().as_str()
+        }
+        None => name == module_id.name().as_str(),
+    }
+}
+
+struct ModuleGenerator<'a> {
+    generator: &'a BindingGenerator,
+    module: &'a ModuleABIView,
+    imports: BTreeSet<&'static str>,
+}
+
+impl<'a> ModuleGenerator<'a> {
+    fn new(generator: &'a BindingGenerator, module: &'a ModuleABIView) -> Self {
+        Self {
+            generator,
+            module,
+            imports: BTreeSet::new(),
+        }
+    }
+
+    fn generate(mut self) -> String {
+        let module = self.module;
+        let mut body = String::new();
+        let address = self.address_expr(&module.address.0);
+        self.imports
+            .insert("move_core_types::account_address::AccountAddress");
+        self.imports.insert("move_core_types::ident_str");
+        self.imports.insert("move_core_types::identifier::IdentStr");
+        writeln!(
+            body,
+            "pub const MODULE_NAME: &IdentStr = ident_str!(\"{}\");\n",
+            module.name.0
+        )
+        .unwrap();
+
+        for s in &module.structs {
+            self.generate_struct(&mut body, s, &address);
+        }
+        self.generate_module_binding(&mut body, &address);
+
+        let mut code = String::from(GENERATED_HEADER);
+        code.push('\n');
+        for import in &self.imports {
+            writeln!(code, "use {};", import).unwrap();
+        }
+        code.push('\n');
+        code.push_str(&body);
+        code
+    }
+
+    fn address_expr(&mut self, address: &AccountAddress) -> String {
+        match named_address(address) {
+            Some(named) => {
+                self.imports.insert(named.import);
+                named.constant.to_string()
+            }
+            None => format!("AccountAddress::new({:?})", address.into_bytes()),
+        }
+    }
+
+    fn generate_struct(&mut self, body: &mut String, s: &MoveStructView, address: &str) {
+        let name = s.name.0.as_str();
+        if s.is_native {
+            writeln!(body, "// The native struct {} is skipped.\n", name).unwrap();
+            return;
+        }
+        let type_params = (0..s.type_params.len())
+            .map(|i| format!("T{}", i))
+            .collect::<Vec<_>>();
+        // The imports of the skipped struct are dropped, or they would be unused.
+        let imports = self.imports.clone();
+        let mut fields = vec![];
+        for field in &s.fields {
+            match self.rust_type(&field.ty.0, TypePosition::Field) {
+                Some(ty) => fields.push((rust_ident(field.name.0.as_str()), ty)),
+                None => {
+                    self.imports = imports;
+                    writeln!(
+                        body,
+                        "// The struct {} is skipped, the type `{}` of field `{}` is not supported.\n",
+                        name, field.ty.0, field.name.0
+                    )
+                    .unwrap();
+                    return;
+                }
+            }
+        }
+        let phantom_params = s
+            .type_params
+            .iter()
+            .zip(type_params.iter())
+            .filter(|(param, _)| param.is_phantom)
+            .map(|(_, ty)| ty.clone())
+            .collect::<Vec<_>>();
+        let generics = if type_params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", type_params.join(", "))
+        };
+        let where_clause = if type_params.is_empty() {
+            String::new()
+        } else {
+            if !phantom_params.is_empty() {
+                self.imports.insert("moveos_types::state::MoveType");
+            }
+            let bounds = s
+                .type_params
+                .iter()
+                .zip(type_params.iter())
+                .map(|(param, ty)| {
+                    if param.is_phantom {
+                        format!("    {}: MoveType,", ty)
+                    } else {
+                        format!("    {}: MoveState,", ty)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("\nwhere\n{}\n", bounds)
+        };
+
+        self.imports.insert("serde::Deserialize");
+        self.imports.insert("serde::Serialize");
+        self.imports.insert("moveos_types::state::MoveStructType");
+        self.imports.insert("moveos_types::state::MoveStructState");
+        if !fields.is_empty() || phantom_params.len() < type_params.len() {
+            self.imports.insert("moveos_types::state::MoveState");
+        }
+        self.imports
+            .insert("move_core_types::value::MoveStructLayout");
+
+        writeln!(body, "#[derive(Debug, Clone, Serialize, Deserialize)]").unwrap();
+        writeln!(body, "pub struct {}{} {{", name, generics).unwrap();
+        for (field_name, ty) in &fields {
+            writeln!(body, "    pub {}: {},", field_name, ty).unwrap();
+        }
+        if !phantom_params.is_empty() {
+            writeln!(
+                body,
+                "    pub phantom: std::marker::PhantomData<({},)>,",
+                phantom_params.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(body, "}}\n").unwrap();
+
+        writeln!(
+            body,
+            "impl{} MoveStructType for {}{}{}{{",
+            generics,
+            name,
+            generics,
+            if where_clause.is_empty() {
+                " ".to_string()
+            } else {
+                where_clause.clone()
+            }
+        )
+        .unwrap();
+        writeln!(body, "    const ADDRESS: AccountAddress = {};", address).unwrap();
+        writeln!(
+            body,
+            "    const MODULE_NAME: &'static IdentStr = MODULE_NAME;"
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    const STRUCT_NAME: &'static IdentStr = ident_str!(\"{}\");",
+            name
+        )
+        .unwrap();
+        if !type_params.is_empty() {
+            self.imports
+                .insert("move_core_types::language_storage::TypeTag");
+            writeln!(body, "\n    fn type_params() -> Vec<TypeTag> {{").unwrap();
+            writeln!(
+                body,
+                "        vec![{}]",
+                type_params
+                    .iter()
+                    .map(|ty| format!("{}::type_tag()", ty))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .unwrap();
+            writeln!(body, "    }}").unwrap();
+        }
+        writeln!(body, "}}\n").unwrap();
+
+        writeln!(
+            body,
+            "impl{} MoveStructState for {}{}{}{{",
+            generics,
+            name,
+            generics,
+            if where_clause.is_empty() {
+                " ".to_string()
+            } else {
+                where_clause
+            }
+        )
+        .unwrap();
+        writeln!(body, "    fn struct_layout() -> MoveStructLayout {{").unwrap();
+        writeln!(
+            body,
+            "        MoveStructLayout::new(vec![{}])",
+            fields
+                .iter()
+                .map(|(_, ty)| format!("<{} as MoveState>::type_layout()", ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap();
+        writeln!(body, "    }}").unwrap();
+        writeln!(body, "}}\n").unwrap();
+    }
+
+    fn generate_module_binding(&mut self, body: &mut String, address: &str) {
+        let module = self.module;
+        let module_name = module.name.0.as_str();
+        let binding_name = format!("{}Module", to_camel_case(module_name));
+        self.imports
+            .insert("moveos_types::module_binding::ModuleBinding");
+        self.imports
+            .insert("moveos_types::module_binding::MoveFunctionCaller");
+
+        writeln!(body, "/// Rust bindings for the {} module", module_name).unwrap();
+        writeln!(body, "#[allow(dead_code)]").unwrap();
+        writeln!(body, "pub struct {}<'a> {{", binding_name).unwrap();
+        writeln!(body, "    caller: &'a dyn MoveFunctionCaller,").unwrap();
+        writeln!(body, "}}\n").unwrap();
+
+        writeln!(body, "impl<'a> {}<'a> {{", binding_name).unwrap();
+        let mut first = true;
+        for function in &module.functions {
+            if !first {
+                body.push('\n');
+            }
+            first = false;
+            self.generate_function(body, function);
+        }
+        writeln!(body, "}}\n").unwrap();
+
+        writeln!(
+            body,
+            "impl<'a> ModuleBinding<'a> for {}<'a> {{",
+            binding_name
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    const MODULE_NAME: &'static IdentStr = MODULE_NAME;"
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    const MODULE_ADDRESS: AccountAddress = {};\n",
+            address
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    fn new(caller: &'a impl MoveFunctionCaller) -> Self\n    where\n        Self: Sized,\n    {{\n        Self {{ caller }}\n    }}"
+        )
+        .unwrap();
+        writeln!(body, "}}").unwrap();
+    }
+
+    fn generate_function(&mut self, body: &mut String, function: &MoveFunctionView) {
+        let name = function.name.0.as_str();
+        let const_name = format!("{}_FUNCTION_NAME", name.to_uppercase());
+        // The imports of the skipped function are dropped, or they would be unused.
+        let imports = self.imports.clone();
+        let mut params = vec![];
+        for ty in &function.params {
+            if is_signer(&ty.0) {
+                continue;
+            }
+            match self.rust_type(&ty.0, TypePosition::Argument) {
+                Some(rust_ty) => params.push((format!("arg{}", params.len()), rust_ty)),
+                None => {
+                    self.imports = imports;
+                    writeln!(
+                        body,
+                        "    // The function {} is skipped, the parameter type `{}` is not supported.",
+                        name, ty.0
+                    )
+                    .unwrap();
+                    return;
+                }
+            }
+        }
+        let has_ty_args = !function.type_params.is_empty();
+        let mut signature = vec![];
+        if has_ty_args {
+            self.imports
+                .insert("move_core_types::language_storage::TypeTag");
+            signature.push("ty_args: Vec<TypeTag>".to_string());
+        }
+        signature.extend(params.iter().map(|(n, ty)| format!("{}: {}", n, ty)));
+        let args = params
+            .iter()
+            .map(|(n, _)| format!("{}.to_bytes()", n))
+            .collect::<Vec<_>>();
+        let ty_args = if has_ty_args { "ty_args" } else { "vec![]" };
+
+        self.imports
+            .insert("moveos_types::transaction::FunctionCall");
+        if !params.is_empty() {
+            self.imports.insert("moveos_types::state::MoveState");
+        }
+        writeln!(
+            body,
+            "    pub const {}: &'static IdentStr = ident_str!(\"{}\");\n",
+            const_name, name
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    pub fn {}_function_call({}) -> FunctionCall {{",
+            name,
+            signature.join(", ")
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "        FunctionCall::new(Self::function_id(Self::{}), {}, vec![{}])",
+            const_name,
+            ty_args,
+            args.join(", ")
+        )
+        .unwrap();
+        writeln!(body, "    }}").unwrap();
+
+        let call_args = if has_ty_args {
+            std::iter::once("ty_args".to_string())
+                .chain(params.iter().map(|(n, _)| n.clone()))
+                .collect::<Vec<_>>()
+        } else {
+            params.iter().map(|(n, _)| n.clone()).collect()
+        };
+        if function.is_entry {
+            self.imports.insert("moveos_types::transaction::MoveAction");
+            writeln!(
+                body,
+                "\n    pub fn create_{}_action({}) -> MoveAction {{",
+                name,
+                signature.join(", ")
+            )
+            .unwrap();
+            writeln!(
+                body,
+                "        MoveAction::Function(Self::{}_function_call({}))",
+                name,
+                call_args.join(", ")
+            )
+            .unwrap();
+            writeln!(body, "    }}").unwrap();
+        }
+
+        // Only the public functions with the Rust mappable return values can be called via the caller.
+        if RESERVED_METHOD_NAMES.contains(&name) {
+            return;
+        }
+        let imports = self.imports.clone();
+        let mut returns = vec![];
+        for ty in &function.return_ {
+            match self.rust_type(&ty.0, TypePosition::Field) {
+                Some(rust_ty) if !contains_type_param(&ty.0) => returns.push(rust_ty),
+                _ => {
+                    self.imports = imports;
+                    return;
+                }
+            }
+        }
+        self.imports.insert("anyhow::Result");
+        self.imports
+            .insert("moveos_types::moveos_std::tx_context::TxContext");
+        let return_type = match returns.len() {
+            0 => "()".to_string(),
+            1 => returns[0].clone(),
+            _ => format!("({})", returns.join(", ")),
+        };
+        let method_signature = std::iter::once("&self".to_string())
+            .chain(std::iter::once("ctx: &TxContext".to_string()))
+            .chain(signature)
+            .collect::<Vec<_>>();
+        writeln!(
+            body,
+            "\n    pub fn {}({}) -> Result<{}> {{",
+            rust_ident(name),
+            method_signature.join(", "),
+            return_type
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "        let call = Self::{}_function_call({});",
+            name,
+            call_args.join(", ")
+        )
+        .unwrap();
+        if returns.is_empty() {
+            writeln!(
+                body,
+                "        self.caller.call_function(ctx, call)?.into_result()?;"
+            )
+            .unwrap();
+            writeln!(body, "        Ok(())").unwrap();
+        } else {
+            self.imports.insert("moveos_types::state::MoveState");
+            writeln!(
+                body,
+                "        let mut values = self.caller.call_function(ctx, call)?.into_result()?.into_iter();"
+            )
+            .unwrap();
+            let decoded = returns
+                .iter()
+                .map(|ty| {
+                    format!(
+                        "<{} as MoveState>::from_bytes(&values.next().ok_or_else(|| anyhow::anyhow!(\"missing return value\"))?.value)?",
+                        ty
+                    )
+                })
+                .collect::<Vec<_>>();
+            if decoded.len() == 1 {
+                writeln!(body, "        Ok({})", decoded[0]).unwrap();
+            } else {
+                writeln!(body, "        Ok(({}))", decoded.join(", ")).unwrap();
+            }
+        }
+        writeln!(body, "    }}").unwrap();
+    }
+
+    /// Map the Move type to the Rust type, return None if the type is not supported.
+    fn rust_type(&mut self, ty: &MoveABIType, position: TypePosition) -> Option<String> {
+        let rust_ty = match ty {
+            MoveABIType::Bool => "bool".to_string(),
+            MoveABIType::U8 => "u8".to_string(),
+            MoveABIType::U16 => "u16".to_string(),
+            MoveABIType::U32 => "u32".to_string(),
+            MoveABIType::U64 => "u64".to_string(),
+            MoveABIType::U128 => "u128".to_string(),
+            MoveABIType::U256 => {
+                self.imports.insert("move_core_types::u256::U256");
+                "U256".to_string()
+            }
+            MoveABIType::Address => {
+                self.imports
+                    .insert("move_core_types::account_address::AccountAddress");
+                "AccountAddress".to_string()
+            }
+            MoveABIType::Signer => return None,
+            MoveABIType::Vector(inner) => format!("Vec<{}>", self.rust_type(inner, position)?),
+            MoveABIType::Struct(tag) => self.rust_struct_type(tag, position)?,
+            MoveABIType::GenericTypeParam { index } => {
+                // The generic type of the function arguments can not be expressed in Rust.
+                if position == TypePosition::Argument {
+                    return None;
+                }
+                format!("T{}", index)
+            }
+            MoveABIType::Reference { mutable: _, to } => match to.as_ref() {
+                // Only the object can be passed by reference, via its ObjectID.
+                MoveABIType::Struct(tag)
+                    if position == TypePosition::Argument && is_object(tag) =>
+                {
+                    self.rust_struct_type(tag, position)?
+                }
+                _ => return None,
+            },
+        };
+        Some(rust_ty)
+    }
+
+    fn rust_struct_type(
+        &mut self,
+        tag: &MoveABIStructTag,
+        position: TypePosition,
+    ) -> Option<String> {
+        let module = tag.module.as_str();
+        let name = tag.name.as_str();
+        if is_object(tag) && position == TypePosition::Argument {
+            self.imports
+                .insert("moveos_types::moveos_std::object::ObjectID");
+            return Some("ObjectID".to_string());
+        }
+        // The generic type params of a struct argument can not be expressed in Rust either.
+        let type_params = tag
+            .type_params
+            .iter()
+            .map(|ty| self.rust_type(ty, position))
+            .collect::<Option<Vec<_>>>()?;
+        let generics = if type_params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", type_params.join(", "))
+        };
+        let (path, import) = match (tag.address, module, name) {
+            (MOVE_STD_ADDRESS, "string", "String") => {
+                ("MoveString", "moveos_types::move_std::string::MoveString")
+            }
+            (MOVE_STD_ADDRESS, "ascii", "String") => (
+                "MoveAsciiString",
+                "moveos_types::move_std::ascii::MoveAsciiString",
+            ),
+            (MOVE_STD_ADDRESS, "option", "Option") => {
+                ("MoveOption", "moveos_types::move_std::option::MoveOption")
+            }
+            (MOVEOS_STD_ADDRESS, "object", "ObjectID") => {
+                ("ObjectID", "moveos_types::moveos_std::object::ObjectID")
+            }
+            (MOVEOS_STD_ADDRESS, "object", "Object") => {
+                ("Object", "moveos_types::moveos_std::object::Object")
+            }
+            (MOVEOS_STD_ADDRESS, "simple_map", "SimpleMap") => (
+                "SimpleMap",
+                "moveos_types::moveos_std::simple_map::SimpleMap",
+            ),
+            (MOVEOS_STD_ADDRESS, "decimal_value", "DecimalValue") => (
+                "DecimalValue",
+                "moveos_types::moveos_std::decimal_value::DecimalValue",
+            ),
+            _ => {
+                if tag.address == self.module.address.0 && module == self.module.name.0.as_str() {
+                    return Some(format!("{}{}", name, generics));
+                }
+                if !self
+                    .generator
+                    .module_names
+                    .contains(&(tag.address, module.to_string()))
+                {
+                    return None;
+                }
+                if tag.address == self.module.address.0 {
+                    return Some(format!(
+                        "super::{}::{}{}",
+                        rust_ident(module),
+                        name,
+                        generics
+                    ));
+                }
+                return Some(format!(
+                    "super::super::{}::{}::{}{}",
+                    address_module_name(&tag.address),
+                    rust_ident(module),
+                    name,
+                    generics
+                ));
+            }
+        };
+        self.imports.insert(import);
+        Some(format!("{}{}", path, generics))
+    }
+}
+
+fn named_address(address: &AccountAddress) -> Option<&'static NamedAddress> {
+    let literal = format!("0x{}", address.short_str_lossless());
+    NAMED_ADDRESSES.iter().find(|a| a.literal == literal)
+}
+
+/// The Rust module of the generated code of the modules at the address.
+fn address_module_name(address: &AccountAddress) -> String {
+    match named_address(address) {
+        Some(named) => named.module.to_string(),
+        None => format!("address_{}", address.short_str_lossless()),
+    }
+}
+
+/// The `mod.rs` which declares the modules.
+fn mod_file(modules: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    let mut code = String::from(GENERATED_HEADER);
+    code.push('\n');
+    for module in modules {
+        writeln!(code, "pub mod {};", rust_ident(module.as_ref())).unwrap();
+    }
+    code
+}
+
+fn is_signer(ty: &MoveABIType) -> bool {
+    match ty {
+        MoveABIType::Signer => true,
+        MoveABIType::Reference { to, .. } => matches!(to.as_ref(), MoveABIType::Signer),
+        _ => false,
+    }
+}
+
+fn is_object(tag: &MoveABIStructTag) -> bool {
+    tag.address == MOVEOS_STD_ADDRESS
+        && tag.module.as_str() == "object"
+        && tag.name.as_str() == "Object"
+}
+
+fn contains_type_param(ty: &MoveABIType) -> bool {
+    match ty {
+        MoveABIType::GenericTypeParam { .. } => true,
+        MoveABIType::Vector(inner) => contains_type_param(inner),
+        MoveABIType::Reference { to, .. } => contains_type_param(to),
+        MoveABIType::Struct(tag) => tag.type_params.iter().any(contains_type_param),
+        _ => false,
+    }
+}
+
+fn rust_ident(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Format the generated code with rustfmt, so the generated files are stable and readable.
+fn format_code(code: String) -> Result<String> {
+    // The generated code is only stable after formatting, so rustfmt is required
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            anyhow::anyhow!("Failed to run rustfmt to format the generated code: {}", e)
+        })?;
+    child
+        .stdin
+        .take()
+        .expect("stdin of rustfmt should be piped")
+        .write_all(code.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to format the generated code: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("coin_store"), "CoinStore");
+        assert_eq!(to_camel_case("kari"), "Kari");
+        assert_eq!(to_camel_case("bitcoin_address"), "BitcoinAddress");
+    }
+
+    #[test]
+    fn test_generate_framework_bindings() {
+        let stdlib = framework_release::load_stdlib(
+            framework_builder::stdlib_version::StdlibVersion::Latest,
+        )
+        .unwrap();
+        let generator = BindingGenerator::new(stdlib.all_modules().unwrap());
+        let files = generator.generate().unwrap();
+        // The timestamp modules of moveos_std and kanari_framework do not overwrite each other.
+        let timestamp = files.get("kanari_framework/timestamp.rs").unwrap();
+        assert!(timestamp.contains("pub struct TimestampModule<'a>"));
+        assert!(timestamp.contains("fast_forward_seconds_for_local_function_call"));
+        assert!(timestamp.contains("impl MoveStructType for TimestampPlaceholder"));
+        assert!(timestamp.contains("KANARI_FRAMEWORK_ADDRESS"));
+        let timestamp = files.get("moveos_std/timestamp.rs").unwrap();
+        assert!(timestamp.contains("now_milliseconds_function_call"));
+        assert!(timestamp.contains("impl MoveStructType for Timestamp"));
+        assert!(timestamp.contains("MOVEOS_STD_ADDRESS"));
+        assert!(files
+            .get("moveos_std/mod.rs")
+            .unwrap()
+            .contains("pub mod timestamp;"));
+        assert!(files
+            .get("mod.rs")
+            .unwrap()
+            .contains("pub mod kanari_framework;"));
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::CommandAction;
+use crate::commands::abi::bindgen::{module_matches, BindingGenerator};
+use async_trait::async_trait;
+use clap::Parser;
+use framework_builder::stdlib_version::StdlibVersion;
+use move_binary_format::CompiledModule;
+use kanari_types::error::{KanariError, KanariResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Generate the Rust bindings of Move modules, the structs and the typed function call builders.
+/// With `--check`, the generated code is compared with the files in the output dir instead of written,
+/// so CI can verify the checked in bindings are up to date.
+#[derive(Debug, Parser)]
+pub struct GenerateBindingsCommand {
+    /// The compiled module files(`.mv`) or the directories containing them.
+    /// If not given, the modules of the latest released stdlib are used.
+    #[clap(long = "module-path", value_delimiter = ',')]
+    pub module_paths: Vec<PathBuf>,
+
+    /// Only generate the bindings of these modules, in the format of `module_name` or `address::module_name`,
+    /// such as `timestamp` or `kanari_framework::timestamp`.
+    #[clap(long, value_delimiter = ',')]
+    pub modules: Vec<String>,
+
+    /// The directory to write the generated files, the file of a module is `<address>/<module>.rs`.
+    #[clap(long, default_value = "./target/bindings")]
+    pub output_dir: PathBuf,
+
+    /// Check the generated code with the files in the output dir, fail if they differ.
+    #[clap(long)]
+    pub check: bool,
+}
+
+#[async_trait]
+impl CommandAction<Vec<String>> for GenerateBindingsCommand {
+    async fn execute(self) -> KanariResult<Vec<String>> {
+        let modules = if self.module_paths.is_empty() {
+            framework_release::load_stdlib(StdlibVersion::Latest)?.all_modules()?
+        } else {
+            let mut modules = vec![];
+            for path in &self.module_paths {
+                load_modules(path, &mut modules)?;
+            }
+            modules
+        };
+        let modules = modules
+            .into_iter()
+            .filter(|m| {
+                self.modules.is_empty() || self.modules.iter().any(|n| module_matches(m, n))
+            })
+            .collect::<Vec<_>>();
+        if modules.is_empty() {
+            return Err(KanariError::CommandArgumentError(
+                "No module to generate bindings".to_owned(),
+            ));
+        }
+
+        let files = BindingGenerator::new(modules).generate()?;
+        let mut outdated = vec![];
+        for (file_name, code) in &files {
+            let file_path = self.output_dir.join(file_name);
+            if self.check {
+                let existing = fs::read_to_string(&file_path).unwrap_or_default();
+                if existing != *code {
+                    outdated.push(file_path.display().to_string());
+                }
+            } else {
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&file_path, code)?;
+            }
+        }
+        if self.check {
+            // The files of the removed modules are out of date too.
+            let mut existing_files = vec![];
+            if self.output_dir.is_dir() {
+                list_rust_files(&self.output_dir, &mut existing_files)?;
+            }
+            for file_path in existing_files {
+                let file_name = file_path
+                    .strip_prefix(&self.output_dir)
+                    .expect("the file is in the output dir")
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                if !files.contains_key(&file_name) {
+                    outdated.push(file_path.display().to_string());
+                }
+            }
+        }
+        if !outdated.is_empty() {
+            return Err(KanariError::CommandArgumentError(format!(
+                "The bindings are out of date, please run `kanari abi generate-bindings` to regenerate: {}",
+                outdated.join(", ")
+            )));
+        }
+        Ok(files
+            .keys()
+            .map(|file_name| self.output_dir.join(file_name).display().to_string())
+            .collect())
+    }
+}
+
+fn load_modules(path: &Path, modules: &mut Vec<CompiledModule>) -> KanariResult<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().map(|ext| ext == "mv").unwrap_or(false) {
+                load_modules(&entry, modules)?;
+            }
+        }
+    } else {
+        let bytes = fs::read(path)?;
+        let module = CompiledModule::deserialize(&bytes).map_err(|e| {
+            KanariError::CommandArgumentError(format!(
+                "Failed to deserialize module {}: {:?}",
+                path.display(),
+                e
+            ))
+        })?;
+        modules.push(module);
+    }
+    Ok(())
+}
+
+fn list_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> KanariResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_rust_files(&path, files)?;
+        } else if path.extension().map(|ext| ext == "rs").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -2,3 +2,4 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod export_kanari_types;
+pub mod generate_bindings;
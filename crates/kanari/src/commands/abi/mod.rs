@@ -1,11 +1,13 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod bindgen;
 pub mod commands;
 
 use crate::cli_types::CommandAction;
 use async_trait::async_trait;
 use commands::export_kanari_types::ExportKanariTypesCommand;
+use commands::generate_bindings::GenerateBindingsCommand;
 use kanari_types::error::{KanariError, KanariResult};
 use std::path::PathBuf;
 
@@ -25,6 +27,9 @@ impl CommandAction<String> for ABI {
             ABICommand::ExportKanariTypes(export) => export.execute().await.map(|resp| {
                 serde_json::to_string_pretty(&resp).expect("Failed to serialize response")
             }),
+            ABICommand::GenerateBindings(generate) => generate.execute().await.map(|resp| {
+                serde_json::to_string_pretty(&resp).expect("Failed to serialize response")
+            }),
         }
         .map_err(KanariError::from)
     }
@@ -34,4 +39,5 @@ impl CommandAction<String> for ABI {
 #[clap(name = "abi")]
 pub enum ABICommand {
     ExportKanariTypes(ExportKanariTypesCommand),
+    GenerateBindings(GenerateBindingsCommand),
 }
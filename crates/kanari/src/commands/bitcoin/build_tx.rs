@@ -1,7 +1,7 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
-use super::transaction_builder::TransactionBuilder;
+use super::transaction_builder::{estimate_fee_rate, TransactionBuilder, DEFAULT_CONF_TARGET};
 use super::FileOutput;
 use crate::cli_types::{CommandAction, WalletContextOptions};
 use crate::commands::bitcoin::FileOutputData;
@@ -57,9 +57,9 @@ impl FromStr for ParsedOutput {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (addr_part, amount_part) = s
-            .split_once(':')
-            .ok_or_else(|| KanariError::CommandArgumentError("Invalid output format".to_string()))?;
+        let (addr_part, amount_part) = s.split_once(':').ok_or_else(|| {
+            KanariError::CommandArgumentError("Invalid output format".to_string())
+        })?;
         let address = ParsedAddress::parse(addr_part)?;
         let amount = u64::from_str(amount_part)?;
         Ok(ParsedOutput {
@@ -90,10 +90,14 @@ pub struct BuildTx {
     #[clap(long, short = 'o', required = true, num_args = 1..)]
     outputs: Vec<ParsedOutput>,
 
-    /// The fee rate of the transaction, if not specified, the fee will be calculated automatically
+    /// The fee rate of the transaction in sat/kwu, if not specified, the fee rate will be estimated by the Kanari node
     #[clap(long)]
     fee_rate: Option<FeeRate>,
 
+    /// Do not signal opt-in replace-by-fee (BIP125), the transaction can not be replaced to bump the fee
+    #[clap(long)]
+    no_rbf: bool,
+
     /// The lock time of the transaction, if not specified, the lock time will be 0
     #[clap(long)]
     lock_time: Option<LockTime>,
@@ -131,6 +135,10 @@ impl CommandAction<FileOutput> for BuildTx {
             .into_iter()
             .map(|input| input.into_object_id())
             .collect();
+        let fee_rate = match self.fee_rate {
+            Some(fee_rate) => Some(fee_rate),
+            None => estimate_fee_rate(&client, DEFAULT_CONF_TARGET).await,
+        };
         let mut tx_builder = TransactionBuilder::new(
             &context,
             client,
@@ -140,9 +148,12 @@ impl CommandAction<FileOutput> for BuildTx {
         )
         .await?;
 
-        if let Some(fee_rate) = self.fee_rate {
+        if let Some(fee_rate) = fee_rate {
             tx_builder = tx_builder.with_fee_rate(fee_rate);
         }
+        if self.no_rbf {
+            tx_builder = tx_builder.with_rbf(false);
+        }
         if let Some(lock_time) = self.lock_time {
             tx_builder = tx_builder.with_lock_time(lock_time);
         }
@@ -0,0 +1,110 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use super::transaction_builder::{estimate_fee_rate, TransactionBuilder, DEFAULT_CONF_TARGET};
+use super::FileOutput;
+use crate::cli_types::{CommandAction, FileOrHexInput, WalletContextOptions};
+use crate::commands::bitcoin::FileOutputData;
+use async_trait::async_trait;
+use bitcoin::{consensus::deserialize, FeeRate, Transaction};
+use clap::Parser;
+use kanari_types::address::ParsedAddress;
+use kanari_types::error::{KanariError, KanariResult};
+use tracing::debug;
+
+/// Bump the fee of an unconfirmed transaction which is stuck in the mempool.
+/// By default, build a replacement transaction (RBF) with the same inputs and outputs, the change output at `--change-vout` pays the additional fee.
+/// If `--cpfp` is set, build a child transaction which spends the sender's output of the transaction (CPFP).
+/// The output is a PSBT, sign it via `kanari bitcoin sign-tx` and broadcast it via `kanari bitcoin broadcast-tx`.
+#[derive(Debug, Parser)]
+pub struct BumpFee {
+    /// The signed transaction to bump the fee, the file path or hex string
+    input: FileOrHexInput,
+
+    /// The sender address of the transaction, if not specified, the active address will be used
+    #[clap(long, short = 's', value_parser=ParsedAddress::parse, default_value = "default")]
+    sender: ParsedAddress,
+
+    /// The new fee rate in sat/kwu, if not specified, the fee rate will be estimated by the Kanari node
+    #[clap(long)]
+    fee_rate: Option<FeeRate>,
+
+    /// The confirmation target in blocks to estimate the fee rate
+    #[clap(long, default_value_t = DEFAULT_CONF_TARGET)]
+    conf_target: u16,
+
+    /// Bump the fee via a child transaction (CPFP) instead of replacing the transaction (RBF)
+    #[clap(long)]
+    cpfp: bool,
+
+    /// The change address of the transaction, if not specified, the change address will be the sender's address
+    /// When replacing the transaction, it receives the change only if `--change-vout` is not specified.
+    #[clap(long, value_parser=ParsedAddress::parse)]
+    change_address: Option<ParsedAddress>,
+
+    /// The output index of the change output of the transaction to replace, the change output pays the additional fee.
+    /// If not specified, all the outputs are kept and the additional fee is paid by more UTXOs of the sender.
+    #[clap(long, conflicts_with = "cpfp")]
+    change_vout: Option<u32>,
+
+    /// Skip check seal of the UTXOs which are added to pay the fee, default is false
+    #[clap(long)]
+    skip_check_seal: bool,
+
+    /// The output file path for the psbt
+    /// If not specified, the output will write to temp directory.
+    #[clap(long)]
+    output_file: Option<String>,
+
+    #[clap(flatten)]
+    pub(crate) context_options: WalletContextOptions,
+}
+
+#[async_trait]
+impl CommandAction<FileOutput> for BumpFee {
+    async fn execute(self) -> KanariResult<FileOutput> {
+        let context = self.context_options.build_require_password()?;
+        let client = context.get_client().await?;
+
+        let bitcoin_network = context.get_bitcoin_network().await?;
+        let sender = context.resolve_bitcoin_address(self.sender).await?;
+        let tx: Transaction = deserialize(&self.input.data).map_err(|e| {
+            KanariError::CommandArgumentError(format!("Invalid transaction: {}", e))
+        })?;
+
+        let fee_rate = match self.fee_rate {
+            Some(fee_rate) => fee_rate,
+            None => estimate_fee_rate(&client, self.conf_target)
+                .await
+                .ok_or_else(|| {
+                    KanariError::CommandArgumentError(
+                        "Failed to estimate the fee rate, please specify the --fee-rate"
+                            .to_string(),
+                    )
+                })?,
+        };
+        let mut tx_builder = TransactionBuilder::new(
+            &context,
+            client,
+            sender.to_bitcoin_address(bitcoin_network)?,
+            vec![],
+            self.skip_check_seal,
+        )
+        .await?
+        .with_fee_rate(fee_rate);
+        if let Some(change_address) = self.change_address {
+            let change_address = context.resolve_bitcoin_address(change_address).await?;
+            tx_builder =
+                tx_builder.with_change_address(change_address.to_bitcoin_address(bitcoin_network)?);
+        }
+
+        let psbt = if self.cpfp {
+            tx_builder.build_cpfp(&tx).await?
+        } else {
+            tx_builder.build_replacement(&tx, self.change_vout).await?
+        };
+        debug!("PSBT: {}", serde_json::to_string_pretty(&psbt).unwrap());
+        let fileout = FileOutput::write_to_file(FileOutputData::Psbt(psbt), self.output_file)?;
+        Ok(fileout)
+    }
+}
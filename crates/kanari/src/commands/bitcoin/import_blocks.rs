@@ -0,0 +1,151 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::{CommandAction, WalletContextOptions};
+use crate::commands::server::commands::start::build_server_opt;
+use async_trait::async_trait;
+use bitcoin::BlockHash;
+use clap::Parser;
+use kanari_config::KanariOpt;
+use kanari_rpc_client::ClientBuilder;
+use kanari_rpc_server::Service;
+use kanari_types::bitcoin::pending_block::PendingBlockModule;
+use kanari_types::bitcoin::types::BlockHeightHash;
+use kanari_types::error::{KanariError, KanariResult};
+use kanari_types::into_address::FromAddress;
+use moveos_types::module_binding::MoveFunctionCaller;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::signal::ctrl_c;
+use tracing::info;
+
+/// Import Bitcoin blocks from local files instead of the Bitcoin RPC.
+/// The blocks directory is a Bitcoin Core data directory with `blk*.dat` files, or a directory of raw serialized blocks.
+/// The node is started with the local blocks as the block source, and stops after the best local block,
+/// or the block at `--btc-end-block-height`, is relayed.
+/// The import fails if no new block is relayed within `--stall-timeout` seconds,
+/// for example, the local blocks do not connect to the best block of the chain.
+#[derive(Debug, Parser)]
+pub struct ImportBlocks {
+    #[clap(flatten)]
+    opt: KanariOpt,
+
+    /// The interval in seconds to check the import progress.
+    #[clap(long, default_value = "10")]
+    check_interval: u64,
+
+    /// The timeout in seconds to wait for the next relayed block.
+    #[clap(long, default_value = "600")]
+    stall_timeout: u64,
+
+    #[clap(flatten)]
+    pub(crate) context_options: WalletContextOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportBlocksOutput {
+    pub block_height: u64,
+    pub block_hash: String,
+}
+
+impl From<BlockHeightHash> for ImportBlocksOutput {
+    fn from(block: BlockHeightHash) -> Self {
+        Self {
+            block_height: block.block_height,
+            block_hash: BlockHash::from_address(block.block_hash).to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandAction<ImportBlocksOutput> for ImportBlocks {
+    async fn execute(mut self) -> KanariResult<ImportBlocksOutput> {
+        let context = self.context_options.build()?;
+        self.opt.init()?;
+
+        let blocks_dir = self.opt.btc_blocks_dir.clone().ok_or_else(|| {
+            KanariError::CommandArgumentError("The --btc-blocks-dir is required".to_owned())
+        })?;
+        // The relayer only runs when the service is active
+        if !self.opt.service_status.is_active() {
+            return Err(KanariError::CommandArgumentError(format!(
+                "The service status should be active to import blocks, current: {:?}",
+                self.opt.service_status
+            )));
+        }
+
+        let server_opt = build_server_opt(&self.opt, &context)?;
+        let mut service = Service::new();
+        service
+            .start(self.opt.clone(), server_opt)
+            .await
+            .map_err(KanariError::from)?;
+
+        // The local blocks are indexed once by the server and shared with the relayer
+        let tip_height = service
+            .local_blocks()
+            .and_then(|local_blocks| local_blocks.tip_height());
+        let target_height = match tip_height {
+            Some(tip_height) => match self.opt.btc_end_block_height {
+                Some(end_block_height) => tip_height.min(end_block_height),
+                None => tip_height,
+            },
+            None => {
+                service.stop().map_err(KanariError::from)?;
+                return Err(KanariError::CommandArgumentError(format!(
+                    "No blocks in {:?}",
+                    blocks_dir
+                )));
+            }
+        };
+
+        let client = ClientBuilder::default()
+            .build(format!("http://127.0.0.1:{}", self.opt.port()))
+            .await?;
+        let stall_timeout = Duration::from_secs(self.stall_timeout);
+        let mut last_height = None;
+        let mut last_progress = Instant::now();
+        let best_block = loop {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(self.check_interval)) => {},
+                _ = ctrl_c() => {
+                    info!("receive Ctrl C, stop importing blocks");
+                    service.stop().map_err(KanariError::from)?;
+                    return Err(KanariError::AbortedError);
+                }
+            }
+
+            let client = client.clone();
+            let best_block = tokio::task::spawn_blocking(move || {
+                client
+                    .as_module_binding::<PendingBlockModule>()
+                    .get_best_block()
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(e))??;
+            if let Some(best_block) = &best_block {
+                info!(
+                    "Imported Bitcoin blocks to height {}, target height {}",
+                    best_block.block_height, target_height
+                );
+                if best_block.block_height >= target_height {
+                    break best_block.clone();
+                }
+            }
+            let height = best_block.map(|best_block| best_block.block_height);
+            if height != last_height {
+                last_height = height;
+                last_progress = Instant::now();
+            } else if last_progress.elapsed() >= stall_timeout {
+                service.stop().map_err(KanariError::from)?;
+                return Err(KanariError::UnexpectedError(format!(
+                    "No Bitcoin block is relayed in {} seconds, the best block height: {:?}, target height {}",
+                    self.stall_timeout, last_height, target_height
+                )));
+            }
+        };
+
+        service.stop().map_err(KanariError::from)?;
+        Ok(best_block.into())
+    }
+}
@@ -7,7 +7,9 @@ use async_trait::async_trait;
 use bitcoin::{consensus::Encodable, Psbt, Transaction, Txid};
 use broadcast_tx::BroadcastTx;
 use build_tx::BuildTx;
+use bump_fee::BumpFee;
 use clap::{Parser, Subcommand};
+use import_blocks::ImportBlocks;
 use kanari_types::error::KanariResult;
 use serde::{Deserialize, Serialize};
 use sign_tx::SignTx;
@@ -16,6 +18,8 @@ use transfer::Transfer;
 
 pub mod broadcast_tx;
 pub mod build_tx;
+pub mod bump_fee;
+pub mod import_blocks;
 pub mod sign_tx;
 pub mod transaction_builder;
 pub mod transfer;
@@ -33,7 +37,9 @@ pub enum BitcoinCommands {
     BuildTx(BuildTx),
     SignTx(SignTx),
     BroadcastTx(BroadcastTx),
+    BumpFee(BumpFee),
     Transfer(Transfer),
+    ImportBlocks(ImportBlocks),
 }
 
 #[async_trait]
@@ -43,7 +49,11 @@ impl CommandAction<String> for Bitcoin {
             BitcoinCommands::BuildTx(build_tx) => build_tx.execute_serialized().await,
             BitcoinCommands::SignTx(sign_tx) => sign_tx.execute_serialized().await,
             BitcoinCommands::BroadcastTx(broadcast_tx) => broadcast_tx.execute_serialized().await,
+            BitcoinCommands::BumpFee(bump_fee) => bump_fee.execute_serialized().await,
             BitcoinCommands::Transfer(transfer) => transfer.execute_serialized().await,
+            BitcoinCommands::ImportBlocks(import_blocks) => {
+                import_blocks.execute_serialized().await
+            }
         }
     }
 }
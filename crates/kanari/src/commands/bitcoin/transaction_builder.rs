@@ -4,29 +4,56 @@
 use super::utxo_selector::UTXOSelector;
 use anyhow::{anyhow, bail, Result};
 use bitcoin::{
-    absolute::LockTime, bip32::Fingerprint, transaction::Version, Address, Amount, FeeRate,
+    absolute::LockTime, bip32::Fingerprint, psbt, transaction::Version, Address, Amount, FeeRate,
     OutPoint, Psbt, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
 };
 use moveos_types::{module_binding::MoveFunctionCaller, moveos_std::object::ObjectID};
 use kanari_rpc_api::jsonrpc_types::btc::utxo::UTXOObjectView;
 use kanari_rpc_client::{wallet_context::WalletContext, Client};
+use kanari_types::address::BitcoinAddress;
 use kanari_types::bitcoin::multisign_account::{self};
-use tracing::debug;
+use tracing::{debug, warn};
+
+/// The default confirmation target in blocks when estimating the fee rate
+pub const DEFAULT_CONF_TARGET: u16 = 6;
+
+/// Estimate the fee rate via the Kanari node, return None if the node can not estimate it
+pub async fn estimate_fee_rate(client: &Client, conf_target: u16) -> Option<FeeRate> {
+    match client
+        .kanari
+        .estimate_bitcoin_fee_rate(Some(conf_target))
+        .await
+    {
+        Ok(fee_rate) => {
+            debug!("Estimated fee rate: {:?}", fee_rate);
+            Some(fee_rate.fee_rate())
+        }
+        Err(e) => {
+            warn!("Failed to estimate the fee rate: {:?}", e);
+            None
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct TransactionBuilder<'a> {
     wallet_context: &'a WalletContext,
     client: Client,
     utxo_selector: UTXOSelector,
+    sender: Address,
     fee_rate: FeeRate,
     change_address: Address,
     lock_time: Option<LockTime>,
+    rbf: bool,
 }
 
 impl<'a> TransactionBuilder<'a> {
     const ADDITIONAL_INPUT_VBYTES: usize = 58;
     const ADDITIONAL_OUTPUT_VBYTES: usize = 43;
     const SCHNORR_SIGNATURE_SIZE: usize = 64;
+    /// The incremental relay fee rate of Bitcoin Core,
+    /// a replacement transaction should pay for its own bandwidth at this rate besides the original fee.
+    const INCREMENTAL_RELAY_FEE_RATE: FeeRate = FeeRate::from_sat_per_vb_unchecked(1);
 
     pub async fn new(
         wallet_context: &'a WalletContext,
@@ -41,9 +68,11 @@ impl<'a> TransactionBuilder<'a> {
             wallet_context,
             client,
             utxo_selector,
+            sender: sender.clone(),
             fee_rate: FeeRate::from_sat_per_vb(10).unwrap(),
             change_address: sender,
             lock_time: None,
+            rbf: true,
         })
     }
 
@@ -62,6 +91,12 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// Signal opt-in replace-by-fee (BIP125) on the inputs, default is true
+    pub fn with_rbf(mut self, rbf: bool) -> Self {
+        self.rbf = rbf;
+        self
+    }
+
     fn estimate_vbytes_with(inputs: usize, outputs: Vec<Address>) -> usize {
         Transaction {
             version: Version::TWO,
@@ -85,6 +120,15 @@ impl<'a> TransactionBuilder<'a> {
         .vsize()
     }
 
+    /// Estimate the vsize of the signed transaction, assuming every input is a taproot key path spend
+    fn estimate_vsize(tx: &Transaction) -> u64 {
+        let mut tx = tx.clone();
+        for input in tx.input.iter_mut() {
+            input.witness = Witness::from_slice(&[&[0; Self::SCHNORR_SIGNATURE_SIZE]]);
+        }
+        tx.vsize() as u64
+    }
+
     pub async fn build_transfer(self, receipient: Address, amount: Amount) -> Result<Psbt> {
         self.build(vec![(receipient, amount)]).await
     }
@@ -115,7 +159,7 @@ impl<'a> TransactionBuilder<'a> {
         let mut tx_inputs = vec![];
         let mut total_input = Amount::from_sat(0);
         for utxo in utxos.iter() {
-            tx_inputs.push(Self::utxo_to_txin(utxo));
+            tx_inputs.push(self.utxo_to_txin(utxo));
             total_input += utxo.amount();
         }
 
@@ -144,7 +188,7 @@ impl<'a> TransactionBuilder<'a> {
                 .select_utxos(total_output + fee - total_input)
                 .await?;
             tx.input
-                .extend(additional_utxos.iter().map(Self::utxo_to_txin));
+                .extend(additional_utxos.iter().map(|utxo| self.utxo_to_txin(utxo)));
             total_input += additional_utxos
                 .iter()
                 .map(|utxo| utxo.amount())
@@ -159,57 +203,342 @@ impl<'a> TransactionBuilder<'a> {
                 script_pubkey: self.change_address.script_pubkey(),
             });
         }
+        let prevouts = utxos
+            .iter()
+            .map(Self::utxo_prevout)
+            .collect::<Result<Vec<_>>>()?;
+        self.build_psbt(tx, prevouts).await
+    }
+
+    /// The fee of a replacement transaction (BIP125), the higher one of the fee at the fee rate,
+    /// and the original fee plus the fee of its own bandwidth at the incremental relay fee rate (rule 3 and 4).
+    fn replacement_fee(fee_rate: FeeRate, original_fee: Amount, vsize: u64) -> Option<Amount> {
+        let fee = fee_rate.fee_vb(vsize)?;
+        let min_fee = original_fee.checked_add(Self::INCREMENTAL_RELAY_FEE_RATE.fee_vb(vsize)?)?;
+        Some(fee.max(min_fee))
+    }
+
+    /// The fee of a CPFP child transaction, which makes the fee rate of the package reach the fee rate,
+    /// the child pays for itself at the minimum relay fee rate at least.
+    fn cpfp_fee(
+        fee_rate: FeeRate,
+        parent_fee: Amount,
+        parent_vsize: u64,
+        vsize: u64,
+    ) -> Option<Amount> {
+        let package_fee = fee_rate.fee_vb(parent_vsize + vsize)?;
+        let min_fee = FeeRate::BROADCAST_MIN.fee_vb(vsize)?;
+        Some(
+            package_fee
+                .checked_sub(parent_fee)
+                .unwrap_or(Amount::ZERO)
+                .max(min_fee),
+        )
+    }
+
+    /// Build a replacement of the unconfirmed transaction with a higher fee (BIP125 RBF).
+    /// The inputs and the outputs are kept except the change output at `change_vout`, which pays the additional fee,
+    /// more UTXOs are added if the change is not enough.
+    /// If `change_vout` is None, all the outputs are kept and the fee is paid by the added UTXOs.
+    pub async fn build_replacement(
+        mut self,
+        original_tx: &Transaction,
+        change_vout: Option<u32>,
+    ) -> Result<Psbt> {
+        let original_txid = original_tx.compute_txid();
+        if !original_tx.is_explicitly_rbf() {
+            bail!(
+                "The transaction {} does not signal RBF, please bump the fee via CPFP",
+                original_txid
+            );
+        }
+        let change_script = match change_vout {
+            Some(vout) => original_tx
+                .output
+                .get(vout as usize)
+                .map(|output| output.script_pubkey.clone())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "The change output {} is not found in the transaction {}",
+                        vout,
+                        original_txid
+                    )
+                })?,
+            None => self.change_address.script_pubkey(),
+        };
+        let utxos = self.load_spent_utxos(original_tx).await?;
+        let total_input = utxos.iter().map(|utxo| utxo.amount()).sum::<Amount>();
+        let original_fee = total_input
+            .checked_sub(original_tx.output.iter().map(|output| output.value).sum())
+            .ok_or_else(|| anyhow!("The outputs of {} exceed the inputs", original_txid))?;
+
+        let mut tx = Transaction {
+            version: original_tx.version,
+            lock_time: original_tx.lock_time,
+            input: utxos.iter().map(|utxo| self.utxo_to_txin(utxo)).collect(),
+            output: original_tx
+                .output
+                .iter()
+                .enumerate()
+                .filter(|(vout, _)| change_vout != Some(*vout as u32))
+                .map(|(_, output)| output.clone())
+                .collect(),
+        };
+        let mut prevouts = utxos
+            .iter()
+            .map(Self::utxo_prevout)
+            .collect::<Result<Vec<_>>>()?;
+        let fee_rate = self.fee_rate;
+        self.fund_transaction(&mut tx, &mut prevouts, change_script, |vsize| {
+            Self::replacement_fee(fee_rate, original_fee, vsize)
+        })
+        .await?;
+        self.build_psbt(tx, prevouts).await
+    }
+
+    /// Build a child transaction which spends the sender's output of the unconfirmed parent transaction (CPFP),
+    /// the child pays enough fee to make the fee rate of the parent and the child reach the fee rate.
+    pub async fn build_cpfp(mut self, parent_tx: &Transaction) -> Result<Psbt> {
+        let parent_txid = parent_tx.compute_txid();
+        let sender_script = self.sender.script_pubkey();
+        let (vout, parent_output) = parent_tx
+            .output
+            .iter()
+            .enumerate()
+            .find(|(_, output)| output.script_pubkey == sender_script)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No output of the transaction {} is owned by the sender {}",
+                    parent_txid,
+                    self.sender
+                )
+            })?;
+        let utxos = self.load_spent_utxos(parent_tx).await?;
+        let parent_fee = utxos
+            .iter()
+            .map(|utxo| utxo.amount())
+            .sum::<Amount>()
+            .checked_sub(parent_tx.output.iter().map(|output| output.value).sum())
+            .ok_or_else(|| anyhow!("The outputs of {} exceed the inputs", parent_txid))?;
+        let parent_vsize = parent_tx.vsize() as u64;
+        let parent_target_fee = self
+            .fee_rate
+            .fee_vb(parent_vsize)
+            .ok_or_else(|| anyhow!("Failed to estimate fee: {}", self.fee_rate))?;
+        if parent_fee >= parent_target_fee {
+            bail!(
+                "The fee rate of the transaction {} is already higher than {}",
+                parent_txid,
+                self.fee_rate
+            );
+        }
+
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: self.lock_time.unwrap_or(LockTime::ZERO),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(parent_txid, vout as u32),
+                script_sig: ScriptBuf::default(),
+                sequence: self.sequence(),
+                witness: Witness::default(),
+            }],
+            output: vec![],
+        };
+        let mut prevouts = vec![(
+            BitcoinAddress::from(self.sender.clone()),
+            parent_output.value,
+        )];
+        let fee_rate = self.fee_rate;
+        let change_script = self.change_address.script_pubkey();
+        self.fund_transaction(&mut tx, &mut prevouts, change_script, |vsize| {
+            Self::cpfp_fee(fee_rate, parent_fee, parent_vsize, vsize)
+        })
+        .await?;
+        self.build_psbt(tx, prevouts).await
+    }
+
+    /// Load the UTXOs spent by the unconfirmed transaction, and exclude them from the selection
+    async fn load_spent_utxos(&mut self, tx: &Transaction) -> Result<Vec<UTXOObjectView>> {
+        let mut utxos = vec![];
+        for input in tx.input.iter() {
+            let outpoint = input.previous_output.into();
+            let utxo = self.utxo_selector.get_utxo(&outpoint).await.map_err(|_| {
+                anyhow!(
+                    "The input {} of the transaction {} is not found, the transaction may be confirmed",
+                    input.previous_output,
+                    tx.compute_txid()
+                )
+            })?;
+            self.utxo_selector.exclude_utxo(&outpoint);
+            utxos.push(utxo);
+        }
+        Ok(utxos)
+    }
+
+    /// Add UTXOs until the inputs cover the outputs and the required fee, then add the change output to `change_script`.
+    /// The `required_fee` returns the fee of the transaction by its vsize.
+    async fn fund_transaction<F>(
+        &mut self,
+        tx: &mut Transaction,
+        prevouts: &mut Vec<(BitcoinAddress, Amount)>,
+        change_script: ScriptBuf,
+        required_fee: F,
+    ) -> Result<()>
+    where
+        F: Fn(u64) -> Option<Amount>,
+    {
+        let total_output = tx.output.iter().map(|output| output.value).sum::<Amount>();
+        loop {
+            let total_input = prevouts.iter().map(|(_, amount)| *amount).sum::<Amount>();
+            let mut tx_with_change = tx.clone();
+            tx_with_change.output.push(TxOut {
+                value: Amount::ZERO,
+                script_pubkey: change_script.clone(),
+            });
+            let fee = required_fee(Self::estimate_vsize(&tx_with_change))
+                .ok_or_else(|| anyhow!("Failed to estimate fee: {}", self.fee_rate))?;
+            if total_input >= total_output + fee {
+                let change = total_input - total_output - fee;
+                if change > change_script.minimal_non_dust() {
+                    tx.output.push(TxOut {
+                        value: change,
+                        script_pubkey: change_script,
+                    });
+                }
+                if tx.output.is_empty() {
+                    bail!("The inputs are not enough to pay the fee");
+                }
+                return Ok(());
+            }
+            let utxos = self
+                .utxo_selector
+                .select_utxos(total_output + fee - total_input)
+                .await?;
+            for utxo in utxos.iter() {
+                tx.input.push(self.utxo_to_txin(utxo));
+                prevouts.push(Self::utxo_prevout(utxo)?);
+            }
+        }
+    }
+
+    fn utxo_prevout(utxo: &UTXOObjectView) -> Result<(BitcoinAddress, Amount)> {
+        let bitcoin_addr = utxo
+            .owner_bitcoin_address()
+            .ok_or_else(|| anyhow!("Can not recognize the owner of UTXO {}", utxo.outpoint()))?;
+        Ok((bitcoin_addr, utxo.amount()))
+    }
+
+    /// Build the PSBT of the transaction, the `prevouts` are the owners and the amounts of the inputs
+    async fn build_psbt(
+        &self,
+        tx: Transaction,
+        prevouts: Vec<(BitcoinAddress, Amount)>,
+    ) -> Result<Psbt> {
         let mut psbt = Psbt::from_unsigned_tx(tx)?;
+        for (idx, (bitcoin_addr, amount)) in prevouts.into_iter().enumerate() {
+            self.update_psbt_input(&mut psbt.inputs[idx], bitcoin_addr, amount)
+                .await?;
+        }
+        Ok(psbt)
+    }
 
+    async fn update_psbt_input(
+        &self,
+        input: &mut psbt::Input,
+        bitcoin_addr: BitcoinAddress,
+        amount: Amount,
+    ) -> Result<()> {
         let multisign_account_module = self
             .client
             .as_module_binding::<multisign_account::MultisignAccountModule>();
-        for (idx, utxo) in utxos.iter().enumerate() {
-            let input = &mut psbt.inputs[idx];
 
-            let bitcoin_addr = utxo.owner_bitcoin_address().ok_or_else(|| {
-                anyhow!("Can not recognize the owner of UTXO {}", utxo.outpoint())
-            })?;
+        if bitcoin_addr.is_witness() {
+            input.witness_utxo = Some(TxOut {
+                value: amount,
+                script_pubkey: bitcoin_addr.script_pubkey()?,
+            });
+        } else {
+            //TODO add non-witness utxo
+            bail!("Non-witness UTXO is not supported yet");
+        }
 
-            if bitcoin_addr.is_witness() {
-                input.witness_utxo = Some(TxOut {
-                    value: utxo.amount(),
-                    script_pubkey: bitcoin_addr.script_pubkey()?,
-                });
-            } else {
-                //TODO add non-witness utxo
-                bail!("Non-witness UTXO is not supported yet");
-            }
+        let kanari_addr = bitcoin_addr.to_kanari_address();
 
-            let kanari_addr = bitcoin_addr.to_kanari_address();
-
-            if multisign_account_module.is_multisign_account(kanari_addr.into())? {
-                let account_info = self
-                    .client
-                    .kanari
-                    .get_multisign_account_info(kanari_addr)
-                    .await?;
-                debug!("Multisign account: {:?}", account_info);
-                multisign_account::update_multisig_psbt(input, &account_info)?;
-            } else {
-                let kp = self.wallet_context.get_key_pair(&kanari_addr)?;
-
-                input.bip32_derivation.insert(
-                    kp.bitcoin_public_key()?.inner,
-                    (Fingerprint::default(), Default::default()),
-                );
-            }
+        if multisign_account_module.is_multisign_account(kanari_addr.into())? {
+            let account_info = self
+                .client
+                .kanari
+                .get_multisign_account_info(kanari_addr)
+                .await?;
+            debug!("Multisign account: {:?}", account_info);
+            multisign_account::update_multisig_psbt(input, &account_info)?;
+        } else {
+            let kp = self.wallet_context.get_key_pair(&kanari_addr)?;
+
+            input.bip32_derivation.insert(
+                kp.bitcoin_public_key()?.inner,
+                (Fingerprint::default(), Default::default()),
+            );
         }
+        Ok(())
+    }
 
-        Ok(psbt)
+    fn sequence(&self) -> Sequence {
+        if self.rbf {
+            Sequence::ENABLE_RBF_NO_LOCKTIME
+        } else {
+            Sequence::ENABLE_LOCKTIME_NO_RBF
+        }
     }
 
-    fn utxo_to_txin(utxo: &UTXOObjectView) -> TxIn {
+    fn utxo_to_txin(&self, utxo: &UTXOObjectView) -> TxIn {
         TxIn {
             previous_output: utxo.outpoint().into(),
             script_sig: ScriptBuf::default(),
-            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            sequence: self.sequence(),
             witness: Witness::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_rate(sat_per_vb: u64) -> FeeRate {
+        FeeRate::from_sat_per_vb(sat_per_vb).unwrap()
+    }
+
+    #[test]
+    fn test_replacement_fee() {
+        let original_fee = Amount::from_sat(1000);
+        // The fee at the fee rate is lower than the original fee plus its own bandwidth (BIP125 rule 4)
+        assert_eq!(
+            TransactionBuilder::replacement_fee(fee_rate(5), original_fee, 200),
+            Some(Amount::from_sat(1200))
+        );
+        // The fee at the fee rate is lower than the original fee (BIP125 rule 3)
+        assert_eq!(
+            TransactionBuilder::replacement_fee(fee_rate(2), original_fee, 200),
+            Some(Amount::from_sat(1200))
+        );
+        assert_eq!(
+            TransactionBuilder::replacement_fee(fee_rate(10), original_fee, 200),
+            Some(Amount::from_sat(2000))
+        );
+    }
+
+    #[test]
+    fn test_cpfp_fee() {
+        // The child pays for the parent to reach 10 sat/vB for the package of 300 vB
+        assert_eq!(
+            TransactionBuilder::cpfp_fee(fee_rate(10), Amount::from_sat(200), 200, 100),
+            Some(Amount::from_sat(2800))
+        );
+        // The parent already pays enough, the child pays for itself at the minimum relay fee rate
+        assert_eq!(
+            TransactionBuilder::cpfp_fee(fee_rate(10), Amount::from_sat(5000), 200, 100),
+            Some(Amount::from_sat(100))
+        );
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::sign_tx::SignOutput;
-use super::transaction_builder::TransactionBuilder;
+use super::transaction_builder::{estimate_fee_rate, TransactionBuilder, DEFAULT_CONF_TARGET};
 use crate::cli_types::{CommandAction, WalletContextOptions};
 use crate::commands::bitcoin::sign_tx::sign_psbt;
 use async_trait::async_trait;
@@ -26,10 +26,14 @@ pub struct Transfer {
     #[clap(long, short = 'a')]
     amount: u64,
 
-    /// The fee rate of the transaction, if not specified, the fee will be calculated automatically
+    /// The fee rate of the transaction in sat/kwu, if not specified, the fee rate will be estimated by the Kanari node
     #[clap(long)]
     fee_rate: Option<FeeRate>,
 
+    /// Do not signal opt-in replace-by-fee (BIP125), the transaction can not be replaced to bump the fee
+    #[clap(long)]
+    no_rbf: bool,
+
     /// Skip check seal of the UTXOs, default is false
     /// If set to true, some UTXO which carries other asserts, such as Inscription, maybe unexpected spent.
     #[clap(long)]
@@ -51,6 +55,10 @@ impl CommandAction<String> for Transfer {
         let to = context.resolve_bitcoin_address(self.to).await?;
         let amount = Amount::from_sat(self.amount);
 
+        let fee_rate = match self.fee_rate {
+            Some(fee_rate) => Some(fee_rate),
+            None => estimate_fee_rate(&client, DEFAULT_CONF_TARGET).await,
+        };
         let mut tx_builder = TransactionBuilder::new(
             &context,
             client.clone(),
@@ -60,9 +68,12 @@ impl CommandAction<String> for Transfer {
         )
         .await?;
 
-        if let Some(fee_rate) = self.fee_rate {
+        if let Some(fee_rate) = fee_rate {
             tx_builder = tx_builder.with_fee_rate(fee_rate);
         }
+        if self.no_rbf {
+            tx_builder = tx_builder.with_rbf(false);
+        }
 
         let psbt = tx_builder
             .build_transfer(to.to_bitcoin_address(bitcoin_network)?, amount)
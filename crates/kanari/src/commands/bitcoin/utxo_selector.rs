@@ -1,7 +1,7 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use anyhow::{bail, Result};
 use bitcoin::{Address, Amount};
@@ -21,6 +21,8 @@ pub struct UTXOSelector {
     specific_utxos: Vec<ObjectID>,
     loaded_page: Option<(Option<IndexerStateIDView>, bool)>,
     candidate_utxos: VecDeque<UTXOObjectView>,
+    // The UTXOs which are already selected or should not be selected
+    excluded_utxos: HashSet<ObjectID>,
     skip_seal_check: bool,
 }
 
@@ -37,6 +39,7 @@ impl UTXOSelector {
             specific_utxos,
             loaded_page: None,
             candidate_utxos: VecDeque::new(),
+            excluded_utxos: HashSet::new(),
             skip_seal_check,
         };
         selector.load_specific_utxos().await?;
@@ -115,12 +118,24 @@ impl UTXOSelector {
         Ok(())
     }
 
-    /// Get the next utxo from the candidate utxos
+    /// Get the next utxo from the candidate utxos, the excluded utxos are skipped
     pub async fn next_utxo(&mut self) -> Result<Option<UTXOObjectView>> {
-        if self.candidate_utxos.is_empty() {
-            self.load_utxos().await?;
+        loop {
+            if self.candidate_utxos.is_empty() {
+                self.load_utxos().await?;
+            }
+            let Some(utxo) = self.candidate_utxos.pop_back() else {
+                return Ok(None);
+            };
+            if self.excluded_utxos.insert(derive_utxo_id(&utxo.outpoint())) {
+                return Ok(Some(utxo));
+            }
         }
-        Ok(self.candidate_utxos.pop_back())
+    }
+
+    /// Exclude the utxo from the selection, such as the inputs of the transaction to be replaced
+    pub fn exclude_utxo(&mut self, outpoint: &OutPoint) {
+        self.excluded_utxos.insert(derive_utxo_id(outpoint));
     }
 
     pub async fn select_utxos(&mut self, expected_amount: Amount) -> Result<Vec<UTXOObjectView>> {
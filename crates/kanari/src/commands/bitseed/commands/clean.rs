@@ -0,0 +1,43 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::CommandAction;
+use crate::cli_types::WalletContextOptions;
+use crate::commands::bitseed::session::InscribeSession;
+use async_trait::async_trait;
+use bitcoin::Txid;
+use clap::Parser;
+use kanari_types::error::KanariResult;
+use serde::{Deserialize, Serialize};
+
+/// Remove the saved session of an inscribe once its reveal transaction is mined.
+/// The session is kept after the reveal transaction is broadcasted, because the reveal transaction
+/// may be dropped from the mempool and need to be resumed or recovered.
+#[derive(Debug, Parser)]
+pub struct CleanCommand {
+    #[arg(long, help = "The commit transaction id of the inscribe session.")]
+    commit_tx: Txid,
+
+    #[clap(flatten)]
+    pub context_options: WalletContextOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanOutput {
+    commit_tx: Txid,
+    reveal_tx: Txid,
+}
+
+#[async_trait]
+impl CommandAction<CleanOutput> for CleanCommand {
+    async fn execute(self) -> KanariResult<CleanOutput> {
+        let context = self.context_options.build()?;
+        // Load the session first, so an unknown commit tx is reported instead of ignored
+        let session = InscribeSession::load(&context, &self.commit_tx)?;
+        InscribeSession::remove(&context, &self.commit_tx)?;
+        Ok(CleanOutput {
+            commit_tx: session.commit_txid(),
+            reveal_tx: session.reveal_txid(),
+        })
+    }
+}
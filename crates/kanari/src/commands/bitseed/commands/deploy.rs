@@ -6,15 +6,21 @@ use crate::cli_types::WalletContextOptions;
 use crate::commands::bitseed::inscribe::InscribeOptions;
 use crate::commands::bitseed::inscribe::InscribeOutput;
 use crate::commands::bitseed::inscribe::Inscriber;
-use crate::commands::bitseed::operation::deploy_args_cbor_encode;
+use crate::commands::bitseed::operation::{validate_tick, DeployArgs};
 use async_trait::async_trait;
 use clap::Parser;
 use kanari_types::bitcoin::ord::InscriptionID;
-use kanari_types::error::KanariResult;
+use kanari_types::error::{KanariError, KanariResult};
+use kanari_types::nursery::mint_get_factory::MintGetFactory;
+use move_command_line_common::types::ParsedStructType;
+use moveos_types::state::MoveStructType;
 
 #[derive(Debug, Parser)]
 pub struct DeployCommand {
-    #[arg(long, help = "The SFT tick name.")]
+    #[arg(
+        long,
+        help = "The SFT tick name, 4-32 characters of ASCII letters, digits and underscores."
+    )]
     tick: String,
 
     #[arg(long, help = "The amount of the tick total supply.")]
@@ -23,8 +29,12 @@ pub struct DeployCommand {
     #[arg(long, help = "The generator Inscription id on Bitcoin.")]
     generator: Option<InscriptionID>,
 
-    #[arg(long, help = "The mint factory name.")]
-    factory: Option<String>,
+    #[arg(
+        long,
+        help = "The mint factory struct type, only `kanari_nursery::mint_get_factory::MintGetFactory` is supported now.",
+        value_parser = ParsedStructType::parse
+    )]
+    factory: Option<ParsedStructType>,
 
     #[arg(
         long,
@@ -33,7 +43,10 @@ pub struct DeployCommand {
     )]
     repeat: u64,
 
-    #[arg(long, help = "The deploy arguments to the generator program.")]
+    #[arg(
+        long,
+        help = "The deploy arguments, json objects to the generator program, or a json object such as `{\"amount_per_mint\": 10000}` to the factory."
+    )]
     deploy_args: Vec<String>,
 
     #[clap(flatten)]
@@ -47,20 +60,39 @@ pub struct DeployCommand {
 impl CommandAction<InscribeOutput> for DeployCommand {
     async fn execute(self) -> KanariResult<InscribeOutput> {
         let context = self.context_options.build_require_password()?;
-        //TODO how to encode the factory args.
-        let deploy_args = deploy_args_cbor_encode(self.deploy_args)?;
-
-        //TODO check the tick name is valid
+        validate_tick(&self.tick)?;
         let tick = self.tick.to_uppercase();
+
+        let factory = match self.factory {
+            Some(factory) => {
+                let factory = factory.into_struct_tag(&context.address_mapping())?;
+                if factory != MintGetFactory::struct_tag() {
+                    return Err(KanariError::CommandArgumentError(format!(
+                        "Unsupported factory: {}, only {} is supported",
+                        factory,
+                        MintGetFactory::struct_tag()
+                    )));
+                }
+                // The factory is stored as the canonical type name on L2, the same as `type_info::type_name`
+                Some(factory.to_canonical_string())
+            }
+            None => None,
+        };
+        let deploy_args = if factory.is_some() {
+            DeployArgs::parse_factory_args(&self.deploy_args)?
+        } else {
+            DeployArgs::parse_generator_args(&self.deploy_args)?
+        };
+
         let output = Inscriber::new(context, self.inscribe_options)
             .await?
             .with_deploy(
                 tick,
                 self.amount,
                 self.generator,
-                self.factory,
+                factory,
                 self.repeat,
-                deploy_args,
+                deploy_args.to_cbor(),
             )
             .await?
             .inscribe()
@@ -1,9 +1,12 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod clean;
 pub mod deploy;
 pub mod generator;
 pub mod merge;
 pub mod mint;
+pub mod recover;
+pub mod resume;
 pub mod split;
 pub mod view;
@@ -0,0 +1,77 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::CommandAction;
+use crate::cli_types::WalletContextOptions;
+use crate::commands::bitseed::session::InscribeSession;
+use async_trait::async_trait;
+use bitcoin::{Amount, FeeRate, Txid};
+use clap::Parser;
+use kanari_types::address::ParsedAddress;
+use kanari_types::error::{KanariError, KanariResult};
+use serde::{Deserialize, Serialize};
+
+/// Recover the funds of an interrupted inscribe, sweep the commit outputs of the saved session via the recovery keys
+#[derive(Debug, Parser)]
+pub struct RecoverCommand {
+    #[arg(long, help = "The commit transaction id of the inscribe session.")]
+    commit_tx: Txid,
+
+    /// Send the recovered funds to <DESTINATION>, if not specified, the active address will be used
+    #[arg(long, default_value = "default")]
+    destination: ParsedAddress,
+
+    #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
+    fee_rate: FeeRate,
+
+    #[arg(long, help = "Don't broadcast the recover transaction.")]
+    dry_run: bool,
+
+    #[clap(flatten)]
+    pub context_options: WalletContextOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoverOutput {
+    recover_tx: Txid,
+    amount: Amount,
+}
+
+#[async_trait]
+impl CommandAction<RecoverOutput> for RecoverCommand {
+    async fn execute(self) -> KanariResult<RecoverOutput> {
+        let context = self.context_options.build_require_password()?;
+        let session = InscribeSession::load(&context, &self.commit_tx)?;
+        let bitcoin_network = context.get_bitcoin_network().await?;
+        session.check_network(bitcoin_network.into())?;
+
+        let destination = context
+            .resolve_bitcoin_address(self.destination)
+            .await?
+            .to_bitcoin_address(bitcoin_network)?;
+        let recover_tx =
+            session.build_recover_tx(&destination, self.fee_rate, context.get_password())?;
+        let output = RecoverOutput {
+            recover_tx: recover_tx.compute_txid(),
+            amount: recover_tx.output[0].value,
+        };
+        if self.dry_run {
+            return Ok(output);
+        }
+
+        let client = context.get_client().await?;
+        client
+            .kanari
+            .broadcast_bitcoin_tx(&recover_tx, None, None)
+            .await
+            .map_err(|err| {
+                KanariError::CommandArgumentError(format!(
+                    "Failed to send recover transaction: {}, the commit tx {} may be not mined or already revealed",
+                    err, self.commit_tx
+                ))
+            })?;
+        // The commit outputs are spent, the session is no longer needed
+        InscribeSession::remove(&context, &self.commit_tx)?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::CommandAction;
+use crate::cli_types::WalletContextOptions;
+use crate::commands::bitseed::inscribe::InscribeOutput;
+use crate::commands::bitseed::session::InscribeSession;
+use async_trait::async_trait;
+use bitcoin::Txid;
+use clap::Parser;
+use kanari_types::error::{KanariError, KanariResult};
+use tracing::warn;
+
+/// Resume an interrupted inscribe, broadcast the commit and reveal transactions of the saved session
+#[derive(Debug, Parser)]
+pub struct ResumeCommand {
+    #[arg(long, help = "The commit transaction id of the inscribe session.")]
+    commit_tx: Txid,
+
+    #[clap(flatten)]
+    pub context_options: WalletContextOptions,
+}
+
+#[async_trait]
+impl CommandAction<InscribeOutput> for ResumeCommand {
+    async fn execute(self) -> KanariResult<InscribeOutput> {
+        let context = self.context_options.build()?;
+        let session = InscribeSession::load(&context, &self.commit_tx)?;
+        session.check_network(context.get_bitcoin_network().await?.into())?;
+        let client = context.get_client().await?;
+
+        // The commit tx may be already broadcasted before the process dies, so we only warn the error
+        if let Err(err) = client
+            .kanari
+            .broadcast_bitcoin_tx(&session.signed_commit_tx, None, None)
+            .await
+        {
+            warn!(
+                "Failed to send commit transaction {}: {}, it may be already broadcasted",
+                self.commit_tx, err
+            );
+        }
+
+        client
+            .kanari
+            .broadcast_bitcoin_tx(&session.signed_reveal_tx, None, session.total_burn_postage)
+            .await
+            .map_err(|err| {
+                KanariError::CommandArgumentError(format!(
+                    "Failed to send reveal transaction {}: {}",
+                    session.reveal_txid(),
+                    err
+                ))
+            })?;
+
+        // The reveal tx may still be dropped from the mempool, so the session is kept until it is mined
+        eprintln!(
+            "The reveal tx is broadcasted, remove the inscribe session via `kanari bitseed clean --commit-tx {}` once it is mined",
+            self.commit_tx
+        );
+        Ok(InscribeOutput::from(&session))
+    }
+}
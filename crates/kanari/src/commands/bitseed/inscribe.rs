@@ -4,8 +4,9 @@
 use super::{
     generator::{self, Generator, InscribeSeed},
     operation::{AsSFT, DeployRecord, MergeRecord, MintRecord, Operation, SplitRecord},
+    session::{CommitOutput, InscribeSession},
     sft::{Content, SFT},
-    GENERATOR_TICK,
+    GENERATOR_TICK, PROTOCOL,
 };
 use crate::commands::{
     bitcoin::utxo_selector::UTXOSelector,
@@ -23,14 +24,20 @@ use bitcoin::{
     EcdsaSighashType, OutPoint,
 };
 use clap::Parser;
-use kanari_rpc_api::jsonrpc_types::btc::ord::InscriptionObjectView;
+use kanari_rpc_api::jsonrpc_types::{btc::ord::InscriptionObjectView, VMStatusView};
 use kanari_rpc_client::wallet_context::WalletContext;
 use kanari_types::{
     address::{BitcoinAddress, ParsedAddress},
     bitcoin::ord::{Inscription, InscriptionID, InscriptionRecord, SatPoint},
+    nursery::tick_info::TickInfoModule,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, path::Path, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    str::FromStr,
+};
 use tracing::debug;
 use {
     bitcoin::{
@@ -78,7 +85,11 @@ pub struct InscribeOptions {
     pub(crate) dry_run: bool,
     #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
     pub(crate) fee_rate: FeeRate,
-    #[arg(long, alias = "nobackup", help = "Do not back up recovery key.")]
+    #[arg(
+        long,
+        alias = "nobackup",
+        help = "Do not back up the inscribe session, which includes the recovery keys of the commit outputs."
+    )]
     pub(crate) no_backup: bool,
     #[arg(
         long,
@@ -142,6 +153,21 @@ pub struct InscribeOutput {
     inscriptions: Vec<InscriptionOrId>,
 }
 
+impl From<&InscribeSession> for InscribeOutput {
+    fn from(session: &InscribeSession) -> Self {
+        Self {
+            commit_tx: session.commit_txid(),
+            reveal_tx: session.reveal_txid(),
+            total_fees: session.total_fees,
+            inscriptions: session
+                .inscriptions()
+                .into_iter()
+                .map(InscriptionOrId::Id)
+                .collect(),
+        }
+    }
+}
+
 pub struct Inscriber {
     context: WalletContext,
     utxo_selector: UTXOSelector,
@@ -259,6 +285,11 @@ impl Inscriber {
             (None, None) => bail!("generator or factory must be provided"),
             _ => {}
         }
+        ensure!(
+            !self.is_tick_deployed(&tick).await?,
+            "tick {} is already deployed",
+            tick
+        );
         let generator = match generator {
             Some(generator) => {
                 let inscription_obj = self.get_inscription_object(generator).await?;
@@ -268,7 +299,9 @@ impl Inscriber {
                     "invalid generator: {}",
                     generator
                 );
-                Some(format!("/inscription/{}", generator))
+                let generator_uri = format!("/inscription/{}", generator);
+                self.dry_run_generator(&generator_uri, &deploy_args).await?;
+                Some(generator_uri)
             }
             None => None,
         };
@@ -403,22 +436,12 @@ impl Inscriber {
             result = result.with_burn(inscription_id).await?;
         }
 
-        let mut merged_sft = sft_to_merge[0].clone();
-        for sft in sft_to_merge.iter().skip(1) {
-            ensure!(
-                merged_sft.tick == sft.tick,
-                "All SFTs must have the same tick to be merged"
-            );
-            //TODO enable the attributes check
-            // ensure!(
-            //     merged_sft.attributes == sft.attributes,
-            //     "All SFTs must have the same attributes to be merged"
-            // );
-            ensure!(
-                merged_sft.content == sft.content,
-                "All SFTs must have the same content to be merged"
-            );
-            merged_sft.amount += sft.amount;
+        // The merge inscription only carries the attributes of the first SFT,
+        // so the SFTs with different attributes can not be merged.
+        let mut sft_to_merge = sft_to_merge.into_iter();
+        let mut merged_sft = sft_to_merge.next().expect("should have SFTs to merge");
+        for sft in sft_to_merge {
+            merged_sft.merge(sft)?;
         }
 
         let merge_record = MergeRecord { sft: merged_sft };
@@ -442,43 +465,6 @@ impl Inscriber {
         self
     }
 
-    fn backup_recovery_key(_context: &WalletContext, kp: TweakedKeypair) -> Result<()> {
-        //TODO back up recovery key
-
-        println!(
-            "Recovery key: {}",
-            hex::encode(kp.to_inner().secret_bytes())
-        );
-
-        // let recovery_private_key = PrivateKey::new(
-        //     recovery_key_pair.to_inner().secret_key(),
-        //     wallet.chain().network(),
-        // );
-
-        // let bitcoin_client = wallet.bitcoin_client()?;
-
-        // let info = bitcoin_client
-        //     .get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
-
-        // let response = bitcoin_client.import_descriptors(vec![ImportDescriptors {
-        //     descriptor: format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
-        //     timestamp: Timestamp::Now,
-        //     active: Some(false),
-        //     range: None,
-        //     next_index: None,
-        //     internal: Some(false),
-        //     label: Some("commit tx recovery key".to_string()),
-        // }])?;
-
-        // for result in response {
-        //     if !result.success {
-        //         return Err(anyhow!("commit tx recovery key import failed"));
-        //     }
-        // }
-
-        Ok(())
-    }
-
     fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, TxOut>) -> Amount {
         tx.input
             .iter()
@@ -991,20 +977,25 @@ impl Inscriber {
         Ok(())
     }
 
-    fn backup_keys(&self, ctx: &mut InscribeContext) -> Result<()> {
-        if self.option.no_backup {
+    /// Save the inscribe session before broadcasting, so the commit outputs can be revealed or recovered
+    /// if the process dies between commit and reveal.
+    fn backup_session(&self, ctx: &InscribeContext) -> Result<()> {
+        if self.option.dry_run || self.option.no_backup {
             return Ok(());
         }
 
         let secp256k1 = Secp256k1::new();
 
-        for ((key_pair, commit_tx_address), taproot_spend_info) in ctx
+        let mut commit_outputs = vec![];
+        for (index, (((key_pair, commit_tx_address), taproot_spend_info), reveal_script)) in ctx
             .key_pairs
             .iter()
             .zip(ctx.commit_tx_addresses.iter())
             .zip(ctx.taproot_spend_infos.iter())
+            .zip(ctx.reveal_scripts.iter())
+            .enumerate()
         {
-            let recovery_key_pair =
+            let recovery_key_pair: TweakedKeypair =
                 key_pair.tap_tweak(&secp256k1, taproot_spend_info.merkle_root());
             let (x_only_pub_key, _parity) = recovery_key_pair.to_inner().x_only_public_key();
             assert_eq!(
@@ -1016,9 +1007,27 @@ impl Inscriber {
                 "commit_tx_address invalid"
             );
 
-            Self::backup_recovery_key(&self.context, recovery_key_pair)?;
+            commit_outputs.push(CommitOutput::new(
+                index as u32,
+                ctx.commit_tx.output[index].clone(),
+                &recovery_key_pair.to_inner(),
+                self.context.get_password(),
+                reveal_script.clone(),
+                hex::encode(ctx.control_blocks[index].serialize()),
+            )?);
         }
 
+        let session = InscribeSession {
+            network: self.network,
+            signed_commit_tx: ctx.signed_commit_tx.clone().expect("commit tx not signed"),
+            signed_reveal_tx: ctx.signed_reveal_tx.clone().expect("reveal tx not signed"),
+            commit_outputs,
+            total_fees: Self::calculate_fee(&ctx.commit_tx, &ctx.utxos)
+                + Self::calculate_fee(&ctx.reveal_tx, &ctx.utxos),
+            total_burn_postage: ctx.total_burn_postage,
+        };
+        let path = session.save(&self.context)?;
+        eprintln!("Inscribe session is saved to {:?}", path);
         Ok(())
     }
 
@@ -1059,7 +1068,7 @@ impl Inscriber {
             Ok(txid) => txid,
             Err(err) => {
                 return Err(anyhow!(
-                "Failed to send reveal transaction: {err}\nCommit tx {commit_txid} will be recovered once mined"
+                "Failed to send reveal transaction: {err}\nUse `kanari bitseed resume --commit-tx {commit_txid}` to reveal again, or `kanari bitseed recover --commit-tx {commit_txid}` to sweep the commit outputs once the commit tx is mined"
                 ))
             }
         };
@@ -1069,6 +1078,12 @@ impl Inscriber {
             "reveal txid should be equal"
         );
 
+        // The reveal tx may still be dropped from the mempool, so the session is kept until it is mined
+        eprintln!(
+            "The reveal tx is broadcasted, remove the inscribe session via `kanari bitseed clean --commit-tx {}` once it is mined",
+            commit_txid
+        );
+
         Ok(InscribeOutput {
             commit_tx: commit_txid,
             reveal_tx: reveal_txid,
@@ -1084,7 +1099,7 @@ impl Inscriber {
         self.build_reveal(&mut ctx).await?;
         self.update_fees(&mut ctx).await?;
         self.sign(&mut ctx)?;
-        self.backup_keys(&mut ctx)?;
+        self.backup_session(&ctx)?;
         let output = self.boardcaset_tx(&mut ctx).await?;
         Ok(output)
     }
@@ -1130,6 +1145,41 @@ impl Inscriber {
         Ok(ins_obj)
     }
 
+    async fn is_tick_deployed(&self, tick: &str) -> Result<bool> {
+        let client = self.context.get_client().await?;
+        let call = TickInfoModule::create_is_deployed_call(PROTOCOL.to_string(), tick.to_string());
+        let result = client.kanari.execute_view_function(call).await?;
+        ensure!(
+            result.vm_status == VMStatusView::Executed,
+            "Failed to check the tick {} is deployed: {:?}",
+            tick,
+            result.vm_status
+        );
+        let value = result
+            .return_values
+            .and_then(|values| values.into_iter().next())
+            .ok_or_else(|| anyhow!("tick_info::is_deployed should have one return value"))?;
+        Ok(bcs::from_bytes::<bool>(&value.value.value.0)?)
+    }
+
+    /// Run the generator with the deploy args before inscribing the deploy inscription,
+    /// the deploy can not be minted if the generator fails to generate or verify.
+    async fn dry_run_generator(&self, generator: &str, deploy_args: &[u8]) -> Result<()> {
+        let generator = self.load_generator(generator).await?;
+        let seed = InscribeSeed::new(self.satpoint.outpoint.clone().into());
+        let destination = self.destination.clone();
+        let verified = catch_unwind(AssertUnwindSafe(|| {
+            let output = generator.inscribe_generate(deploy_args, &seed, &destination, None);
+            generator.inscribe_verify(deploy_args, &seed, &destination, None, output)
+        }))
+        .map_err(|_| anyhow!("The generator failed to generate with the deploy args"))?;
+        ensure!(
+            verified,
+            "The generator failed to verify the output generated with the deploy args"
+        );
+        Ok(())
+    }
+
     async fn load_generator(&self, generator: &str) -> Result<Box<dyn Generator>> {
         // generator: "/inscription/inscriptioin_id"
         let path = generator.split('/').collect::<Vec<&str>>();
@@ -10,6 +10,7 @@ pub mod generator;
 pub mod inscribe;
 pub mod inscription;
 pub mod operation;
+pub mod session;
 pub mod sft;
 
 pub const PROTOCOL: &str = "bitseed";
@@ -36,6 +37,9 @@ impl CommandAction<String> for Bitseed {
             BitseedCommand::Split(split) => split.execute_serialized().await,
             BitseedCommand::Merge(merge) => merge.execute_serialized().await,
             BitseedCommand::View(view) => view.execute_serialized().await,
+            BitseedCommand::Resume(resume) => resume.execute_serialized().await,
+            BitseedCommand::Recover(recover) => recover.execute_serialized().await,
+            BitseedCommand::Clean(clean) => clean.execute_serialized().await,
         }
     }
 }
@@ -49,4 +53,7 @@ pub enum BitseedCommand {
     Split(commands::split::SplitCommand),
     Merge(commands::merge::MergeCommand),
     View(commands::view::ViewCommand),
+    Resume(commands::resume::ResumeCommand),
+    Recover(commands::recover::RecoverCommand),
+    Clean(commands::clean::CleanCommand),
 }
@@ -8,6 +8,7 @@ use super::{
 use anyhow::{anyhow, bail, Result};
 use ciborium::Value;
 use kanari_types::bitcoin::ord::{Inscription, InscriptionRecord};
+use kanari_types::nursery::mint_get_factory;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use serde_json::Value as JSONValue;
@@ -291,21 +292,110 @@ impl Operation {
     }
 }
 
-pub fn deploy_args_cbor_encode(deploy_args: Vec<String>) -> Result<Vec<u8>> {
-    let mut mint_args_json: Vec<JSONValue> = vec![];
-
-    for arg in deploy_args.iter() {
-        debug!("deploy arg: {}", arg);
-        let arg_json: JSONValue = serde_json::from_str(arg.as_str())
-            .map_err(|e| anyhow!("failed to parse deploy arg: {}, arg: {}", e, arg))?;
-        if !arg_json.is_object() {
-            bail!("deploy arg must be a json object, got: {}", arg);
+/// Check the tick name follows the same rules as the `inscribe_factory` on L2.
+/// The tick is case insensitive, it must be 4-32 characters of ASCII letters, digits and `_`.
+pub fn validate_tick(tick: &str) -> Result<()> {
+    if tick.len() < 4 || tick.len() > 32 {
+        bail!("tick must be 4-32 characters, got: {}", tick);
+    }
+    if !tick.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!(
+            "tick must only contain ASCII letters, digits and underscores, got: {}",
+            tick
+        );
+    }
+    Ok(())
+}
+
+/// The `deploy_args` attribute of the deploy inscription
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployArgs {
+    /// The arguments of the generator program, encoded as a CBOR array of JSON objects
+    Generator(Vec<JSONValue>),
+    /// The arguments of the `mint_get_factory`, encoded as a CBOR map of the `DeployArgs` struct
+    MintGetFactory(mint_get_factory::DeployArgs),
+}
+
+impl DeployArgs {
+    pub fn parse_generator_args(deploy_args: &[String]) -> Result<Self> {
+        let mut mint_args_json: Vec<JSONValue> = vec![];
+
+        for arg in deploy_args.iter() {
+            debug!("deploy arg: {}", arg);
+            let arg_json: JSONValue = serde_json::from_str(arg.as_str())
+                .map_err(|e| anyhow!("failed to parse deploy arg: {}, arg: {}", e, arg))?;
+            if !arg_json.is_object() {
+                bail!("deploy arg must be a json object, got: {}", arg);
+            }
+            mint_args_json.push(arg_json);
         }
-        mint_args_json.push(arg_json);
+        Ok(DeployArgs::Generator(mint_args_json))
     }
 
-    let mint_args_array = JSONValue::Array(mint_args_json);
-    let mut cbor_buffer = Vec::new();
-    ciborium::into_writer(&mint_args_array, &mut cbor_buffer).expect("ciborium marshal failed");
-    Ok(cbor_buffer)
+    /// The factory args is a json object, such as `{"amount_per_mint": 10000}`, the default args is used if it is not provided
+    pub fn parse_factory_args(deploy_args: &[String]) -> Result<Self> {
+        let args = match deploy_args {
+            [] => mint_get_factory::DeployArgs::default(),
+            [arg] => serde_json::from_str(arg.as_str())
+                .map_err(|e| anyhow!("failed to parse factory deploy arg: {}, arg: {}", e, arg))?,
+            _ => bail!(
+                "the factory only accepts one deploy arg, got: {:?}",
+                deploy_args
+            ),
+        };
+        if args.amount_per_mint == 0 {
+            bail!("amount_per_mint must be greater than 0");
+        }
+        Ok(DeployArgs::MintGetFactory(args))
+    }
+
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut cbor_buffer = Vec::new();
+        let result = match self {
+            DeployArgs::Generator(args) => ciborium::into_writer(args, &mut cbor_buffer),
+            DeployArgs::MintGetFactory(args) => ciborium::into_writer(args, &mut cbor_buffer),
+        };
+        result.expect("ciborium marshal failed");
+        cbor_buffer
+    }
+}
+
+pub fn deploy_args_cbor_encode(deploy_args: Vec<String>) -> Result<Vec<u8>> {
+    DeployArgs::parse_generator_args(&deploy_args).map(|args| args.to_cbor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_tick() {
+        assert!(validate_tick("move").is_ok());
+        assert!(validate_tick("Move_2024").is_ok());
+        assert!(validate_tick("mov").is_err());
+        assert!(validate_tick(&"m".repeat(33)).is_err());
+        assert!(validate_tick("te$t").is_err());
+        assert!(validate_tick("tést").is_err());
+    }
+
+    #[test]
+    fn test_factory_deploy_args_cbor() {
+        let args =
+            DeployArgs::parse_factory_args(&["{\"amount_per_mint\": 500}".to_string()]).unwrap();
+        let value: Value = ciborium::from_reader(Cursor::new(args.to_cbor())).unwrap();
+        assert_eq!(
+            value,
+            Value::Map(vec![(
+                Value::Text("amount_per_mint".to_string()),
+                Value::Integer(500u64.into())
+            )])
+        );
+
+        let args = DeployArgs::parse_factory_args(&[]).unwrap();
+        assert_eq!(
+            args,
+            DeployArgs::MintGetFactory(mint_get_factory::DeployArgs::default())
+        );
+        assert!(DeployArgs::parse_factory_args(&["{\"amount\": 500}".to_string()]).is_err());
+    }
 }
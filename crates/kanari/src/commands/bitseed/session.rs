@@ -0,0 +1,314 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, ensure, Result};
+use bitcoin::{
+    absolute::LockTime,
+    key::Keypair,
+    secp256k1::{Secp256k1, SecretKey},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::Signature,
+    transaction::Version,
+    Address, Amount, FeeRate, Network, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
+    Witness,
+};
+use kanari_rpc_client::wallet_context::WalletContext;
+use kanari_types::bitcoin::ord::InscriptionID;
+use kanari_types::key_struct::EncryptionData;
+use serde::{Deserialize, Serialize};
+use std::{fs, io::Write, path::PathBuf};
+
+const SESSION_DIR: &str = "bitseed_sessions";
+
+/// A commit output of the commit transaction, which is spent by the reveal transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitOutput {
+    pub vout: u32,
+    pub output: TxOut,
+    /// The tweaked secret key encrypted with the keystore password,
+    /// which can spend the commit output via the taproot key path
+    pub recovery_key: EncryptionData,
+    pub reveal_script: ScriptBuf,
+    /// The hex encoded control block of the reveal script
+    pub control_block: String,
+}
+
+impl CommitOutput {
+    pub fn new(
+        vout: u32,
+        output: TxOut,
+        recovery_keypair: &Keypair,
+        password: Option<String>,
+        reveal_script: ScriptBuf,
+        control_block: String,
+    ) -> Result<Self> {
+        Ok(Self {
+            vout,
+            output,
+            recovery_key: EncryptionData::encrypt(&recovery_keypair.secret_bytes(), password)?,
+            reveal_script,
+            control_block,
+        })
+    }
+
+    fn recovery_keypair(&self, password: Option<String>) -> Result<Keypair> {
+        let secret_key = SecretKey::from_slice(&self.recovery_key.decrypt(password)?)?;
+        Ok(Keypair::from_secret_key(&Secp256k1::new(), &secret_key))
+    }
+}
+
+/// The commit/reveal session of an inscribe.
+/// The session is saved to the wallet config directory before broadcasting the commit transaction,
+/// if the process dies between commit and reveal, the reveal transaction can be broadcasted via `kanari bitseed resume`,
+/// or the commit outputs can be swept via `kanari bitseed recover`.
+/// The session is kept after the reveal transaction is broadcasted, it is removed via `kanari bitseed clean`.
+/// The recovery keys are encrypted with the keystore password, and the session file is only readable by the owner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InscribeSession {
+    pub network: Network,
+    pub signed_commit_tx: Transaction,
+    pub signed_reveal_tx: Transaction,
+    pub commit_outputs: Vec<CommitOutput>,
+    pub total_fees: Amount,
+    pub total_burn_postage: Option<f64>,
+}
+
+impl InscribeSession {
+    pub fn commit_txid(&self) -> Txid {
+        self.signed_commit_tx.compute_txid()
+    }
+
+    pub fn reveal_txid(&self) -> Txid {
+        self.signed_reveal_tx.compute_txid()
+    }
+
+    pub fn inscriptions(&self) -> Vec<InscriptionID> {
+        let reveal_txid = self.reveal_txid();
+        (0..self.commit_outputs.len())
+            .map(|index| InscriptionID::new(reveal_txid, index as u32))
+            .collect()
+    }
+
+    fn session_dir(context: &WalletContext) -> Result<PathBuf> {
+        let config_dir = context
+            .client_config
+            .path()
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid client config path"))?;
+        Ok(config_dir.join(SESSION_DIR))
+    }
+
+    fn session_path(context: &WalletContext, commit_txid: &Txid) -> Result<PathBuf> {
+        Ok(Self::session_dir(context)?.join(format!("{}.json", commit_txid)))
+    }
+
+    pub fn save(&self, context: &WalletContext) -> Result<PathBuf> {
+        let session_dir = Self::session_dir(context)?;
+        fs::create_dir_all(&session_dir)?;
+        let path = Self::session_path(context, &self.commit_txid())?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(path)
+    }
+
+    pub fn load(context: &WalletContext, commit_txid: &Txid) -> Result<Self> {
+        let path = Self::session_path(context, commit_txid)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read inscribe session {:?}: {}", path, e))?;
+        let session: Self = serde_json::from_str(&content)?;
+        ensure!(
+            session.commit_txid() == *commit_txid,
+            "The commit tx of the session {:?} does not match {}",
+            path,
+            commit_txid
+        );
+        Ok(session)
+    }
+
+    pub fn remove(context: &WalletContext, commit_txid: &Txid) -> Result<()> {
+        let path = Self::session_path(context, commit_txid)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Build a transaction which sweeps all the commit outputs to the `destination` via the taproot key path,
+    /// the recovery keys are decrypted with the keystore `password`
+    pub fn build_recover_tx(
+        &self,
+        destination: &Address,
+        fee_rate: FeeRate,
+        password: Option<String>,
+    ) -> Result<Transaction> {
+        let commit_txid = self.commit_txid();
+        let prevouts: Vec<TxOut> = self
+            .commit_outputs
+            .iter()
+            .map(|commit_output| commit_output.output.clone())
+            .collect();
+        let total_input: Amount = prevouts.iter().map(|output| output.value).sum();
+
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: self
+                .commit_outputs
+                .iter()
+                .map(|commit_output| TxIn {
+                    previous_output: bitcoin::OutPoint {
+                        txid: commit_txid,
+                        vout: commit_output.vout,
+                    },
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: vec![TxOut {
+                script_pubkey: destination.script_pubkey(),
+                value: total_input,
+            }],
+        };
+
+        // Estimate the fee with dummy key path signatures
+        let mut dummy_tx = tx.clone();
+        for input in dummy_tx.input.iter_mut() {
+            input.witness = Witness::from_slice(&[&[0u8; 64]]);
+        }
+        let fee = fee_rate
+            .fee_vb(dummy_tx.vsize() as u64)
+            .ok_or_else(|| anyhow!("Fee overflow"))?;
+        let value = total_input
+            .checked_sub(fee)
+            .filter(|value| *value > destination.script_pubkey().minimal_non_dust())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The commit outputs {} are not enough to pay the fee {}",
+                    total_input,
+                    fee
+                )
+            })?;
+        tx.output[0].value = value;
+
+        let secp = Secp256k1::new();
+        let keypairs = self
+            .commit_outputs
+            .iter()
+            .map(|commit_output| commit_output.recovery_keypair(password.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let mut sighash_cache = SighashCache::new(&mut tx);
+        for (index, keypair) in keypairs.iter().enumerate() {
+            let sighash = sighash_cache.taproot_key_spend_signature_hash(
+                index,
+                &Prevouts::All(&prevouts),
+                TapSighashType::Default,
+            )?;
+            let sig = secp.sign_schnorr(&sighash.into(), keypair);
+            *sighash_cache
+                .witness_mut(index)
+                .ok_or_else(|| anyhow!("Input {} not found", index))? =
+                Witness::p2tr_key_spend(&Signature {
+                    signature: sig,
+                    sighash_type: TapSighashType::Default,
+                });
+        }
+        Ok(tx)
+    }
+
+    pub fn check_network(&self, network: Network) -> Result<()> {
+        if self.network != network {
+            bail!(
+                "The session is created on {}, but the current network is {}",
+                self.network,
+                network
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{
+        key::{TapTweak, TweakedPublicKey},
+        secp256k1::rand,
+    };
+
+    #[test]
+    fn test_build_recover_tx() {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let recovery_keypair = keypair.tap_tweak(&secp, None).to_inner();
+        let (x_only_pub_key, _parity) = recovery_keypair.x_only_public_key();
+        let commit_address = Address::p2tr_tweaked(
+            TweakedPublicKey::dangerous_assume_tweaked(x_only_pub_key),
+            Network::Regtest,
+        );
+        let commit_output = TxOut {
+            script_pubkey: commit_address.script_pubkey(),
+            value: Amount::from_sat(20_000),
+        };
+        let session = InscribeSession {
+            network: Network::Regtest,
+            signed_commit_tx: Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![commit_output.clone()],
+            },
+            signed_reveal_tx: Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![],
+            },
+            commit_outputs: vec![CommitOutput::new(
+                0,
+                commit_output.clone(),
+                &recovery_keypair,
+                Some("password".to_string()),
+                ScriptBuf::new(),
+                String::new(),
+            )
+            .unwrap()],
+            total_fees: Amount::ZERO,
+            total_burn_postage: None,
+        };
+        let session: InscribeSession =
+            serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+
+        let fee_rate = FeeRate::from_sat_per_vb(2).unwrap();
+        assert!(session
+            .build_recover_tx(&commit_address, fee_rate, Some("wrong".to_string()))
+            .is_err());
+        let recover_tx = session
+            .build_recover_tx(&commit_address, fee_rate, Some("password".to_string()))
+            .unwrap();
+        assert_eq!(
+            recover_tx.input[0].previous_output.txid,
+            session.commit_txid()
+        );
+        let fee = fee_rate.fee_vb(recover_tx.vsize() as u64).unwrap();
+        assert_eq!(recover_tx.output[0].value, commit_output.value - fee);
+
+        let sighash = SighashCache::new(&recover_tx)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&[commit_output]),
+                TapSighashType::Default,
+            )
+            .unwrap();
+        let sig = Signature::from_slice(&recover_tx.input[0].witness[0]).unwrap();
+        secp.verify_schnorr(&sig.signature, &sighash.into(), &x_only_pub_key)
+            .unwrap();
+    }
+}
@@ -91,3 +91,29 @@ impl SFT {
         SplitRecord { sft: self.clone() }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sft_with_attributes(amount: u64, id: &str) -> SFT {
+        let attributes = Value::Map(vec![(
+            Value::Text("id".to_string()),
+            Value::Text(id.to_string()),
+        )]);
+        SFT::new("move".to_string(), amount, Some(attributes), None)
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut sft = sft_with_attributes(100, "a");
+        sft.merge(sft_with_attributes(50, "a")).unwrap();
+        assert_eq!(sft.amount, 150);
+
+        assert!(sft.merge(sft_with_attributes(50, "b")).is_err());
+        assert!(sft
+            .merge(SFT::new("move".to_string(), 50, None, None))
+            .is_err());
+        assert_eq!(sft.amount, 150);
+    }
+}
@@ -933,6 +933,7 @@ async fn build_btc_client_proxy(
         btc_rpc_user_name,
         btc_rpc_password,
         local_block_store_dir: btc_local_block_store_dir,
+        local_blocks: None,
     };
 
     let bitcoin_client = bitcoin_client_config.build()?;
@@ -965,7 +966,8 @@ async fn build_executor_and_store(
     let subscription_handle = Arc::new(SubscriptionHandler::new(
         &registry_service.default_registry(),
     ));
-    let notify_actor = NotifyActor::new(event_bus.clone(), subscription_handle);
+    let notify_actor =
+        NotifyActor::new(event_bus.clone(), subscription_handle, moveos_store.clone());
     let notify_actor_ref = notify_actor
         .into_actor(Some("NotifyActor"), actor_system)
         .await?;
@@ -11,6 +11,7 @@ use kanari_config::R_OPT_NET_HELP;
 use kanari_indexer::indexer_reader::IndexerReader;
 use kanari_indexer::IndexerStore;
 use kanari_types::bitcoin::ord::Inscription;
+use kanari_types::bitcoin::runes::RuneBalances;
 use kanari_types::bitcoin::utxo::UTXO;
 use kanari_types::error::KanariResult;
 use kanari_types::indexer::state::{IndexerStateID, ObjectStateFilter, ObjectStateType};
@@ -27,7 +28,7 @@ pub struct BenchCommand {
     #[clap(long, help = "bench count", default_value = "10000")]
     pub count: Option<u64>,
 
-    #[clap(long, help = "query filter: utxo/ord", default_value = "utxo")]
+    #[clap(long, help = "query filter: utxo/ord/runes", default_value = "utxo")]
     pub query_filter: Option<String>,
 
     /// If local chainid, start the service with a temporary data store.
@@ -72,6 +73,10 @@ impl BenchCommand {
                 ObjectStateFilter::ObjectType(Inscription::struct_tag()),
                 ObjectStateType::Inscription,
             ),
+            "runes" => (
+                ObjectStateFilter::ObjectType(RuneBalances::struct_tag()),
+                ObjectStateType::RuneBalances,
+            ),
             _ => (
                 ObjectStateFilter::ObjectType(UTXO::struct_tag()),
                 ObjectStateType::UTXO,
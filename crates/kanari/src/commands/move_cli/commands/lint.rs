@@ -0,0 +1,64 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::{CommandAction, WalletContextOptions};
+use async_trait::async_trait;
+use clap::Parser;
+use move_cli::{base::reroot_path, Move};
+use moveos_verifier::lint::{lint_package, LintDiagnostic};
+use kanari_types::error::{KanariError, KanariResult};
+use std::collections::BTreeMap;
+
+/// Lint the package at `path` for MoveOS-specific pitfalls, and print the diagnostics in json format.
+/// If no path is provided defaults to current directory.
+#[derive(Parser)]
+#[clap(name = "lint")]
+pub struct LintCommand {
+    /// Named addresses for the move binary
+    ///
+    /// Example: alice=0x1234, bob=default, alice2=alice
+    ///
+    /// Note: This will fail if there are duplicates in the Move.toml file remove those first.
+    #[clap(long, value_parser = crate::utils::parse_map::<String, String>, default_value = "")]
+    pub(crate) named_addresses: BTreeMap<String, String>,
+
+    #[clap(flatten)]
+    config_options: WalletContextOptions,
+
+    #[clap(flatten)]
+    move_args: Move,
+
+    /// Fail the command if there is any diagnostic, used in CI.
+    #[clap(long)]
+    deny_warnings: bool,
+}
+
+#[async_trait]
+impl CommandAction<Vec<LintDiagnostic>> for LintCommand {
+    async fn execute(self) -> KanariResult<Vec<LintDiagnostic>> {
+        let context = self.config_options.build()?;
+        let mut config = self.move_args.build_config;
+        config
+            .additional_named_addresses
+            .extend(context.parse_and_resolve_addresses(self.named_addresses)?);
+
+        let rerooted_path = reroot_path(self.move_args.package_path)?;
+        let diagnostics = lint_package(&rerooted_path, config)?;
+        Ok(diagnostics)
+    }
+
+    async fn execute_serialized(self) -> KanariResult<String> {
+        let deny_warnings = self.deny_warnings;
+        let diagnostics = self.execute().await?;
+        let output = serde_json::to_string_pretty(&diagnostics)?;
+        if deny_warnings && !diagnostics.is_empty() {
+            // Print the diagnostics before failing, so they are available in the CI logs.
+            println!("{}", output);
+            return Err(KanariError::CommandArgumentError(format!(
+                "Found {} lint diagnostics",
+                diagnostics.len()
+            )));
+        }
+        Ok(output)
+    }
+}
@@ -9,6 +9,7 @@ pub mod errmap;
 pub mod explain;
 pub mod info;
 pub mod integration_test;
+pub mod lint;
 pub mod new;
 pub mod prove;
 pub mod publish;
@@ -173,6 +173,11 @@ pub struct Publish {
     /// Skip the client side compatibility check
     #[clap(long, default_value = "false")]
     skip_client_compat_check: bool,
+
+    /// Only check the compatibility of the modules with the modules published on chain,
+    /// do not publish them. The command fails if the modules are incompatible.
+    #[clap(long, default_value = "false", conflicts_with_all = ["skip_client_compat_check", "dry_run"])]
+    check_only: bool,
 }
 
 #[async_trait]
@@ -194,14 +199,82 @@ impl CommandAction<ExecuteTransactionResponseView> for Publish {
                 .await;
         }
 
-        // Clone variables for later use
+        let (package_name, pkg_address, sorted_modules, old_modules) =
+            self.compile_and_verify(&context).await?;
+        if !old_modules.is_empty() && !self.skip_client_compat_check {
+            releaser::check_modules_compat(sorted_modules.clone(), old_modules, true, true)?;
+        }
+
+        let mut bundles: Vec<Vec<u8>> = vec![];
+        for module in &sorted_modules {
+            let mut binary: Vec<u8> = vec![];
+            module.serialize(&mut binary)?;
+            bundles.push(binary);
+        }
+
+        // Create a sender KanariAddress
+        eprintln!("Publish modules to address: {}", pkg_address);
+
+        // Prepare and execute the transaction based on the action type
+        let tx_result = if !self.by_move_action {
+            let pkg_data = PackageData::new(
+                MoveString::from(package_name.as_str()),
+                pkg_address,
+                bundles,
+            );
+            self.publish_package(&context, sender, pkg_data, max_gas_amount)
+                .await?
+        } else {
+            // Handle MoveAction.ModuleBundle case
+            let action = MoveAction::ModuleBundle(bundles);
+            let tx_data = context
+                .build_tx_data(sender, action, max_gas_amount)
+                .await?;
+            context.sign_and_execute(sender, tx_data).await?
+        };
+        //Directly return the result, the publish transaction may be failed.
+        //Caller need to check the `execution_info.status` field.
+        Ok(tx_result)
+    }
+
+    /// Executes the command, and serializes it to the common JSON output type
+    async fn execute_serialized(self) -> KanariResult<String> {
+        if self.check_only {
+            return self.check_compatibility().await;
+        }
+        let json = self.json;
+        let result = self.execute().await?;
+
+        if json {
+            let output = serde_json::to_string_pretty(&result).unwrap();
+            if output == "null" {
+                return Ok("".to_string());
+            }
+            Ok(output)
+        } else {
+            Self::pretty_transaction_response(&result)
+        }
+    }
+}
+
+impl Publish {
+    /// Compile the package and run the verifier, return the package name, the package address,
+    /// the modules sorted by dependency order and the modules of the package published on chain.
+    async fn compile_and_verify(
+        &self,
+        context: &WalletContext,
+    ) -> KanariResult<(
+        String,
+        AccountAddress,
+        Vec<CompiledModule>,
+        Vec<CompiledModule>,
+    )> {
         let package_path = self
             .move_args
             .package_path
             .clone()
             .unwrap_or_else(|| std::env::current_dir().unwrap());
-        let config = self.move_args.build_config.clone();
-        let mut config = config.clone();
+        let mut config = self.move_args.build_config.clone();
 
         // Parse named addresses from context and update config
         config.additional_named_addresses =
@@ -225,18 +298,7 @@ impl CommandAction<ExecuteTransactionResponseView> for Publish {
             )));
         };
 
-        // Initialize bundles vector and sort modules by dependency order
-        let mut bundles: Vec<Vec<u8>> = vec![];
         let sorted_modules = sort_by_dependency_order(modules.iter_modules())?;
-
-        // Download all modules from remote
-        let all_module_ids = package
-            .all_modules_map()
-            .get_map()
-            .iter()
-            .map(|(mid, _)| mid.clone())
-            .collect::<Vec<_>>();
-
         for module in &sorted_modules {
             let module_address = module.self_id().address().to_owned();
             if module_address != pkg_address {
@@ -246,64 +308,61 @@ impl CommandAction<ExecuteTransactionResponseView> for Publish {
                     pkg_address.clone(),
                 )));
             };
-            let mut binary: Vec<u8> = vec![];
-            module.serialize(&mut binary)?;
-            bundles.push(binary);
         }
 
+        // Download all modules from remote
+        let all_module_ids = package
+            .all_modules_map()
+            .get_map()
+            .iter()
+            .map(|(mid, _)| mid.clone())
+            .collect::<Vec<_>>();
+
         //Because the verify modules function will load many modules from the rpc server,
         //We need to download all modules in one rpc request and then verify the modules.
         let mut resolver = MemoryModuleResolver::new(context.get_client().await?);
         resolver.download(all_module_ids)?;
         moveos_verifier::verifier::verify_modules(&sorted_modules, &resolver)?;
         let old_modules = resolver.get_modules(&pkg_address)?;
-        if !old_modules.is_empty() && !self.skip_client_compat_check {
-            releaser::check_modules_compat(sorted_modules, old_modules, true)?;
-        }
-
-        // Create a sender KanariAddress
-        eprintln!("Publish modules to address: {}", pkg_address);
 
-        // Prepare and execute the transaction based on the action type
-        let tx_result = if !self.by_move_action {
-            let pkg_data = PackageData::new(
-                MoveString::from(package.compiled_package_info.package_name.as_str()),
-                pkg_address,
-                bundles,
-            );
-            self.publish_package(&context, sender, pkg_data, max_gas_amount)
-                .await?
-        } else {
-            // Handle MoveAction.ModuleBundle case
-            let action = MoveAction::ModuleBundle(bundles);
-            let tx_data = context
-                .build_tx_data(sender, action, max_gas_amount)
-                .await?;
-            context.sign_and_execute(sender, tx_data).await?
-        };
-        //Directly return the result, the publish transaction may be failed.
-        //Caller need to check the `execution_info.status` field.
-        Ok(tx_result)
+        Ok((
+            package.compiled_package_info.package_name.to_string(),
+            pkg_address,
+            sorted_modules,
+            old_modules,
+        ))
     }
 
-    /// Executes the command, and serializes it to the common JSON output type
-    async fn execute_serialized(self) -> KanariResult<String> {
-        let json = self.json;
-        let result = self.execute().await?;
-
-        if json {
-            let output = serde_json::to_string_pretty(&result).unwrap();
-            if output == "null" {
-                return Ok("".to_string());
-            }
-            Ok(output)
+    /// Check the modules of the package against the modules published on chain, without publishing.
+    async fn check_compatibility(self) -> KanariResult<String> {
+        let context = self.context_options.build()?;
+        let (_, pkg_address, sorted_modules, old_modules) =
+            self.compile_and_verify(&context).await?;
+        let published = !old_modules.is_empty();
+        if published {
+            releaser::check_modules_compat(sorted_modules, old_modules, true, true)?;
+        }
+        if self.json {
+            let output = serde_json::json!({
+                "package_address": pkg_address.to_hex_literal(),
+                "published": published,
+                "compatible": true,
+            });
+            return Ok(serde_json::to_string_pretty(&output).unwrap());
+        }
+        if published {
+            Ok(format!(
+                "The modules are compatible with the modules published at address {}.",
+                pkg_address
+            ))
         } else {
-            Self::pretty_transaction_response(&result)
+            Ok(format!(
+                "No modules are published at address {}, nothing to check.",
+                pkg_address
+            ))
         }
     }
-}
 
-impl Publish {
     fn pretty_transaction_response(
         txn_response: &ExecuteTransactionResponseView,
     ) -> KanariResult<String> {
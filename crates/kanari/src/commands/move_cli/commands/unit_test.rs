@@ -9,8 +9,13 @@ use codespan_reporting::diagnostic::Severity;
 use move_cli::{base::test, Move};
 use move_command_line_common::address::NumericalAddress;
 use move_command_line_common::parser::NumberFormat;
+use move_package::package_hooks::{register_package_hooks, PackageHooks};
+use move_package::source_package::parsed_manifest::{CustomDepInfo, PackageName};
 use move_unit_test::extensions::set_extension_hook;
+use move_unit_test::gas::set_gas_meter_hook;
 use move_vm_runtime::native_extensions::NativeContextExtensions;
+use move_vm_types::gas::GasMeter;
+use moveos::gas::table::{initial_cost_schedule, MoveOSGasMeter};
 use moveos_config::DataDirPath;
 use moveos_object_runtime::runtime::{ObjectRuntime, ObjectRuntimeContext};
 use moveos_stdlib::natives::moveos_stdlib::{
@@ -21,14 +26,19 @@ use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::{moveos_std::tx_context::TxContext, state_resolver::RootObjectResolver};
 use moveos_verifier::build::build_model_with_test_attr;
 use moveos_verifier::metadata::run_extended_checks;
+use moveos_wasm::wasm::NativeWASMContext;
 use once_cell::sync::Lazy;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use kanari_genesis::FrameworksGasParameters;
 use kanari_types::error::{KanariError, KanariResult};
 use kanari_types::genesis_config;
 use serde_json::Value;
+use std::io::Write;
 use std::rc::Rc;
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 use termcolor::Buffer;
 use tokio::runtime::Runtime;
 
@@ -55,12 +65,27 @@ pub struct TestCommand {
     /// Return command outputs in json format
     #[clap(long, default_value = "false")]
     json: bool,
+
+    /// Run the tests under the MoveOS gas meter with the latest gas schedule, and report the gas used by each test.
+    /// The gas budget of each test is `--gas_limit`, or `test_gas_budget` in the `[package]` section of Move.toml,
+    /// or the max gas amount of a transaction.
+    #[clap(long, default_value = "false")]
+    gas: bool,
+
+    /// Compare the gas used by each test with the baseline file, fail if any test uses more gas than the baseline.
+    #[clap(long, requires = "gas")]
+    gas_baseline: Option<PathBuf>,
+
+    /// Write the gas used by each test to the file, it can be used as the `--gas-baseline` later.
+    #[clap(long, requires = "gas")]
+    gas_report: Option<PathBuf>,
 }
 
 #[async_trait]
 impl CommandAction<Option<Value>> for TestCommand {
     async fn execute(self) -> KanariResult<Option<Value>> {
         let context = self.config_options.build()?;
+        register_package_hooks(Box::new(KanariPackageHooks));
 
         let mut build_config = self.move_args.build_config;
         build_config
@@ -106,22 +131,176 @@ impl CommandAction<Option<Value>> for TestCommand {
             }
         }
 
-        //TODO define gas metering
-        let cost_table = move_vm_test_utils::gas_schedule::INITIAL_COST_SCHEDULE.clone();
-        let gas_parameter = FrameworksGasParameters::initial();
-        let natives = gas_parameter.all_natives();
+        let mut test = self.test;
         set_extension_hook(Box::new(new_moveos_natives_runtime));
-        self.test.execute(
-            self.move_args.package_path,
-            build_config,
-            natives,
-            Some(cost_table),
-        )?;
+        if self.gas {
+            let gas_parameter = FrameworksGasParameters::latest();
+            let gas_entries = gas_parameter
+                .vm_gas_params
+                .to_on_chain_gas_schedule()
+                .into_iter()
+                .collect::<BTreeMap<_, _>>();
+            let cost_table = initial_cost_schedule(Some(gas_entries));
+            let manifest_budget = resolution_graph
+                .root_package
+                .package
+                .custom_properties
+                .iter()
+                .find(|(name, _)| name.as_str() == TEST_GAS_BUDGET_FIELD)
+                .map(|(_, budget)| {
+                    budget.parse::<u64>().map_err(|e| {
+                        anyhow::anyhow!("Invalid {} in Move.toml: {}", TEST_GAS_BUDGET_FIELD, e)
+                    })
+                })
+                .transpose()?;
+            test.gas_limit = test
+                .gas_limit
+                .or(manifest_budget)
+                .or(Some(gas_parameter.max_gas_amount));
+            // Print the gas used by each test, include the failed ones.
+            test.report_statistics = true;
+            GAS_USAGE.lock().clear();
+            set_gas_meter_hook(Box::new(
+                move |session, module_id, function_name, args, budget| {
+                    let mut gas_meter = MoveOSGasMeter::new(cost_table.clone(), budget, true);
+                    let result = session.execute_function_bypass_visibility(
+                        module_id,
+                        function_name,
+                        vec![],
+                        args,
+                        &mut gas_meter,
+                    );
+                    let gas_used = budget.saturating_sub(gas_meter.balance_internal().into());
+                    GAS_USAGE.lock().insert(
+                        format!(
+                            "0x{}::{}::{}",
+                            module_id.address().short_str_lossless(),
+                            module_id.name(),
+                            function_name
+                        ),
+                        gas_used,
+                    );
+                    (result, gas_used)
+                },
+            ));
+            test.execute(
+                self.move_args.package_path,
+                build_config,
+                gas_parameter.all_natives(),
+                None,
+            )?;
+
+            let gas_usage = GAS_USAGE.lock().clone();
+            if let Some(gas_report) = &self.gas_report {
+                write_gas_report(gas_report, &gas_usage)?;
+            }
+            if let Some(gas_baseline) = &self.gas_baseline {
+                let baseline = read_gas_report(gas_baseline)?;
+                let regressions =
+                    report_gas_diff(&baseline, &gas_usage, &mut std::io::stdout().lock())?;
+                if regressions > 0 {
+                    return Err(KanariError::from(anyhow::anyhow!(
+                        "{} tests use more gas than the baseline {}",
+                        regressions,
+                        gas_baseline.display()
+                    )));
+                }
+            }
+        } else {
+            // Without `--gas`, the test cost table only bounds the execution of each test.
+            let cost_table = move_vm_test_utils::gas_schedule::INITIAL_COST_SCHEDULE.clone();
+            let gas_parameter = FrameworksGasParameters::initial();
+            test.execute(
+                self.move_args.package_path,
+                build_config,
+                gas_parameter.all_natives(),
+                Some(cost_table),
+            )?;
+        }
 
         serialized_success(self.json)
     }
 }
 
+/// The field in the `[package]` section of Move.toml to set the gas budget of each unit test.
+const TEST_GAS_BUDGET_FIELD: &str = "test_gas_budget";
+
+/// The gas used by each test in the last `--gas` run, keyed by the qualified test name.
+static GAS_USAGE: Lazy<Mutex<BTreeMap<String, u64>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+fn write_gas_report(path: &Path, gas_usage: &BTreeMap<String, u64>) -> anyhow::Result<()> {
+    let report = serde_json::to_string_pretty(gas_usage)?;
+    std::fs::write(path, report)?;
+    Ok(())
+}
+
+fn read_gas_report(path: &Path) -> anyhow::Result<BTreeMap<String, u64>> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// Write the gas diff of each test against the baseline, and return the number of tests which use more gas.
+fn report_gas_diff(
+    baseline: &BTreeMap<String, u64>,
+    gas_usage: &BTreeMap<String, u64>,
+    out: &mut impl Write,
+) -> std::io::Result<usize> {
+    let mut regressions = 0;
+    writeln!(out, "\nGas diff against the baseline:\n")?;
+    for (name, gas_used) in gas_usage {
+        match baseline.get(name) {
+            Some(base) => {
+                let diff = *gas_used as i128 - *base as i128;
+                if diff > 0 {
+                    regressions += 1;
+                }
+                if diff != 0 {
+                    let percent = if *base == 0 {
+                        100.0
+                    } else {
+                        diff as f64 * 100.0 / *base as f64
+                    };
+                    writeln!(
+                        out,
+                        "{}: {} -> {} ({:+}, {:+.2}%)",
+                        name, base, gas_used, diff, percent
+                    )?;
+                }
+            }
+            None => writeln!(out, "{}: new test, {}", name, gas_used)?,
+        }
+    }
+    for name in baseline.keys() {
+        if !gas_usage.contains_key(name) {
+            writeln!(out, "{}: removed from the tests", name)?;
+        }
+    }
+    Ok(regressions)
+}
+
+/// Allows the Kanari specific fields in the `[package]` section of Move.toml.
+struct KanariPackageHooks;
+
+impl PackageHooks for KanariPackageHooks {
+    fn custom_package_info_fields(&self) -> Vec<String> {
+        vec![TEST_GAS_BUDGET_FIELD.to_string()]
+    }
+
+    fn custom_dependency_key(&self) -> Option<String> {
+        None
+    }
+
+    fn resolve_custom_dependency(
+        &self,
+        dep_name: PackageName,
+        _info: &CustomDepInfo,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "use of unsupported custom dependency {} in package manifest",
+            dep_name
+        )
+    }
+}
+
 static MOVEOSSTORE: Lazy<(MoveOSStore, DataDirPath)> = Lazy::new(|| {
     let runtime = Runtime::new()
         .expect("Failed to create Tokio runtime when mock moveos store in move unit test");
@@ -138,8 +317,10 @@ static RESOLVER: Lazy<Box<RootObjectResolver<MoveOSStore>>> = Lazy::new(|| {
 #[allow(clippy::arc_with_non_send_sync)]
 fn new_moveos_natives_runtime(ext: &mut NativeContextExtensions) {
     let resolver = Lazy::force(&RESOLVER).as_ref();
+    let tx_context = TxContext::random_for_testing_only();
+    let wasm_ext = NativeWASMContext::new(&tx_context.tx_hash);
     let object_runtime = Rc::new(RwLock::new(ObjectRuntime::genesis(
-        TxContext::random_for_testing_only(),
+        tx_context,
         ObjectMeta::genesis_root(),
         resolver,
         genesis_config::G_LOCAL_CONFIG.genesis_objects.clone(),
@@ -151,4 +332,47 @@ fn new_moveos_natives_runtime(ext: &mut NativeContextExtensions) {
     ext.add(table_ext);
     ext.add(module_ext);
     ext.add(event_ext);
+    ext.add(wasm_ext);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_gas_diff_against_baseline() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let baseline_path = tmp_dir.path().join("gas_baseline.json");
+        let baseline = BTreeMap::from([
+            ("0x42::m::test_same".to_string(), 100),
+            ("0x42::m::test_more".to_string(), 200),
+            ("0x42::m::test_less".to_string(), 400),
+            ("0x42::m::test_removed".to_string(), 10),
+        ]);
+        write_gas_report(&baseline_path, &baseline).unwrap();
+        assert_eq!(read_gas_report(&baseline_path).unwrap(), baseline);
+
+        let gas_usage = BTreeMap::from([
+            ("0x42::m::test_same".to_string(), 100),
+            ("0x42::m::test_more".to_string(), 250),
+            ("0x42::m::test_less".to_string(), 300),
+            ("0x42::m::test_new".to_string(), 50),
+        ]);
+        let mut output = Vec::new();
+        let regressions = report_gas_diff(
+            &read_gas_report(&baseline_path).unwrap(),
+            &gas_usage,
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(regressions, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\nGas diff against the baseline:\n\n\
+             0x42::m::test_less: 400 -> 300 (-100, -25.00%)\n\
+             0x42::m::test_more: 200 -> 250 (+50, +25.00%)\n\
+             0x42::m::test_new: new test, 50\n\
+             0x42::m::test_removed: removed from the tests\n"
+        );
+    }
 }
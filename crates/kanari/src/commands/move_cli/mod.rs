@@ -6,9 +6,9 @@ use clap::{Parser, Subcommand};
 use commands::{
     build::BuildCommand, coverage::CoverageCommand, disassemble::DisassembleCommand,
     docgen::DocgenCommand, errmap::ErrmapCommand, info::InfoCommand,
-    integration_test::IntegrationTestCommand, new::NewCommand, prove::ProveCommand,
-    publish::Publish, run_function::RunFunction, run_view_function::RunViewFunction,
-    unit_test::TestCommand,
+    integration_test::IntegrationTestCommand, lint::LintCommand, new::NewCommand,
+    prove::ProveCommand, publish::Publish, run_function::RunFunction,
+    run_view_function::RunViewFunction, unit_test::TestCommand,
 };
 use kanari_types::error::KanariResult;
 use serde_json::{json, Value};
@@ -33,6 +33,7 @@ pub enum MoveCommand {
     Docgen(DocgenCommand),
     Errmap(ErrmapCommand),
     Info(InfoCommand),
+    Lint(LintCommand),
     New(NewCommand),
     Prove(ProveCommand),
     Test(TestCommand),
@@ -53,6 +54,7 @@ impl CommandAction<String> for MoveCli {
             MoveCommand::Docgen(c) => c.execute_serialized().await,
             MoveCommand::Errmap(c) => c.execute_serialized().await,
             MoveCommand::Info(c) => c.execute_serialized().await,
+            MoveCommand::Lint(c) => c.execute_serialized().await,
             MoveCommand::New(c) => c.execute_serialized().await,
             MoveCommand::Prove(c) => c.execute_serialized().await,
             MoveCommand::Test(c) => c.execute_serialized().await,
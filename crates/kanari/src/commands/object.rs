@@ -9,11 +9,12 @@ use move_command_line_common::types::ParsedStructType;
 use move_core_types::language_storage::TypeTag;
 use moveos_types::move_types::type_tag_match;
 use kanari_rpc_api::jsonrpc_types::btc::ord::InscriptionFilterView;
+use kanari_rpc_api::jsonrpc_types::btc::runes::RuneBalancesFilterView;
 use kanari_rpc_api::jsonrpc_types::btc::utxo::UTXOFilterView;
 use kanari_rpc_api::jsonrpc_types::{ObjectStateFilterView, QueryOptions, KanariAddressView};
 use kanari_types::address::ParsedAddress;
 use kanari_types::indexer::state::{
-    IndexerStateID, ObjectStateType, INSCRIPTION_TYPE_TAG, UTXO_TYPE_TAG,
+    IndexerStateID, ObjectStateType, INSCRIPTION_TYPE_TAG, RUNE_BALANCES_TYPE_TAG, UTXO_TYPE_TAG,
 };
 use kanari_types::{error::KanariResult, function_arg::ParsedObjectID};
 
@@ -103,6 +104,8 @@ impl CommandAction<String> for ObjectCommand {
                 ObjectStateType::UTXO
             } else if type_tag_match(&obj_type, &INSCRIPTION_TYPE_TAG) {
                 ObjectStateType::Inscription
+            } else if type_tag_match(&obj_type, &RUNE_BALANCES_TYPE_TAG) {
+                ObjectStateType::RuneBalances
             } else {
                 ObjectStateType::ObjectState
             }
@@ -171,6 +174,29 @@ impl CommandAction<String> for ObjectCommand {
                     .await?;
                 serde_json::to_string_pretty(&result).unwrap()
             }
+            ObjectStateType::RuneBalances => {
+                let rune_balances_fitler = match filter.unwrap() {
+                    ObjectStateFilterView::ObjectTypeWithOwner {
+                        object_type: _,
+                        owner,
+                    } => RuneBalancesFilterView::Owner(owner),
+                    ObjectStateFilterView::ObjectType(_object_type) => RuneBalancesFilterView::All,
+                    ObjectStateFilterView::Owner(owner) => RuneBalancesFilterView::Owner(owner),
+                    ObjectStateFilterView::ObjectId(object_id) => {
+                        RuneBalancesFilterView::ObjectId(object_id)
+                    }
+                };
+                let result = client
+                    .kanari
+                    .query_rune_balances(
+                        rune_balances_fitler,
+                        self.cursor.clone(),
+                        self.limit,
+                        Some(query_options.descending),
+                    )
+                    .await?;
+                serde_json::to_string_pretty(&result).unwrap()
+            }
             ObjectStateType::ObjectState => {
                 let result = client
                     .kanari
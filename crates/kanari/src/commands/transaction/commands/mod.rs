@@ -16,6 +16,7 @@ pub mod query;
 pub mod sign;
 pub mod sign_order;
 pub mod submit;
+pub mod trace;
 
 pub(crate) enum FileOutputData {
     KanariTransactionData(KanariTransactionData),
@@ -0,0 +1,96 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_types::{CommandAction, WalletContextOptions};
+use async_trait::async_trait;
+use moveos_types::h256::H256;
+use kanari_rpc_api::jsonrpc_types::{CallTraceView, TransactionTraceView};
+use kanari_types::error::KanariResult;
+
+/// Re-execute a transaction on the state before it and print the Move call tree.
+/// The server must be started with `--enable-debug-rpc`.
+#[derive(Debug, clap::Parser)]
+pub struct TraceCommand {
+    /// Transaction's hash
+    #[clap(long)]
+    pub hash: H256,
+
+    #[clap(flatten)]
+    pub(crate) context_options: WalletContextOptions,
+
+    /// Return command outputs in json format
+    #[clap(long, default_value = "false")]
+    json: bool,
+}
+
+#[async_trait]
+impl CommandAction<TransactionTraceView> for TraceCommand {
+    async fn execute(self) -> KanariResult<TransactionTraceView> {
+        let client = self.context_options.build()?.get_client().await?;
+
+        let resp = client.kanari.trace_transaction(self.hash).await?;
+
+        Ok(resp)
+    }
+
+    async fn execute_serialized(self) -> KanariResult<String> {
+        let json = self.json;
+        let trace = self.execute().await?;
+
+        if json {
+            return Ok(serde_json::to_string_pretty(&trace).unwrap());
+        }
+
+        let mut output = String::new();
+        output.push_str(&format!("Transaction: {:?}\n", trace.tx_hash));
+        output.push_str(&format!("Order: {}\n", trace.tx_order));
+        output.push_str(&format!("Pre state root: {:?}\n", trace.pre_state_root));
+        output.push_str(&format!("Status: {:?}\n", trace.status));
+        output.push_str(&format!("Gas used: {}\n", trace.gas_used));
+
+        output.push_str("\nCall trace:\n");
+        write_call_trace(&mut output, &trace.call_trace, 1);
+
+        output.push_str(&format!("\nRead objects: {}\n", trace.read_objects.len()));
+        for object_id in &trace.read_objects {
+            output.push_str(&format!("  {}\n", object_id));
+        }
+        output.push_str(&format!(
+            "\nChanged objects: {}\n",
+            trace.changeset.changes.len()
+        ));
+        for change in &trace.changeset.changes {
+            output.push_str(&format!("  {}\n", change.metadata.id));
+        }
+        output.push_str(&format!("\nEvents: {}\n", trace.events.len()));
+        for event in &trace.events {
+            output.push_str(&format!("  #{} {}\n", event.event_index, event.event_type));
+        }
+        if let Some(vm_error_info) = &trace.vm_error_info {
+            output.push_str(&format!("\nError: {}\n", vm_error_info.error_message));
+            for (idx, item) in vm_error_info.execution_state.iter().enumerate() {
+                output.push_str(&format!("  {}: {}\n", idx, item));
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn write_call_trace(output: &mut String, call: &CallTraceView, depth: usize) {
+    let indent = "  ".repeat(depth);
+    output.push_str(&format!(
+        "{}{}({}) [gas: {}]",
+        indent,
+        call.function,
+        call.args.join(", "),
+        call.gas_used
+    ));
+    if call.is_native {
+        output.push_str(" native");
+    }
+    output.push_str(&format!(" -> ({})", call.return_values.join(", ")));
+    output.push('\n');
+    for sub_call in &call.calls {
+        write_call_trace(output, sub_call, depth + 1);
+    }
+}
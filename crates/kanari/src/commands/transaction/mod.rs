@@ -6,7 +6,7 @@ use crate::commands::transaction::commands::sign_order::SignOrderCommand;
 use crate::commands::transaction::commands::{
     build::BuildCommand, get_transactions_by_hash::GetTransactionsByHashCommand,
     get_transactions_by_order::GetTransactionsByOrderCommand, query::QueryCommand,
-    sign::SignCommand, submit::SubmitCommand,
+    sign::SignCommand, submit::SubmitCommand, trace::TraceCommand,
 };
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -32,6 +32,7 @@ impl CommandAction<String> for Transaction {
             TransactionCommand::Sign(cmd) => cmd.execute_serialized().await,
             TransactionCommand::Submit(cmd) => cmd.execute_serialized().await,
             TransactionCommand::SignOrder(cmd) => cmd.execute(),
+            TransactionCommand::Trace(cmd) => cmd.execute_serialized().await,
         }
     }
 }
@@ -45,4 +46,5 @@ pub enum TransactionCommand {
     Sign(SignCommand),
     Submit(SubmitCommand),
     SignOrder(SignOrderCommand),
+    Trace(TraceCommand),
 }
@@ -99,6 +99,15 @@ Feature: Rooch CLI integration tests
 
       Then stop the server
 
+    @serial
+    Scenario: graphql query
+      Given a server for graphql_query
+      Then graphql: "{ chainId }"
+      Then assert: "{{$.graphql[-1].data.chainId}} == 4"
+      Then graphql: "{ transactions(first: 1, descending: false) { edges { node { txOrder } } } }"
+      Then assert: "{{$.graphql[-1].data.transactions.edges[0].node.txOrder}} == 0"
+      Then stop the server
+
     @serial
     Scenario: state
       Given a server for state
@@ -304,6 +313,12 @@ Feature: Rooch CLI integration tests
       Then assert: "{{$.move[-1].execution_info.status.type}} == executed"
 
       # check compatibility
+      Then cmd: "move publish -p ../../examples/entry_function_arguments/  --named-addresses rooch_examples=default --check-only --json"
+      Then assert: "{{$.move[-1].published}} == true"
+      Then assert: "{{$.move[-1].compatible}} == true"
+      Then cmd: "move publish -p ../../examples/entry_function_arguments_old/  --named-addresses rooch_examples=default --check-only --json"
+      Then assert: "'{{$.move[-1]}}' contains 'is incompatible with previous version'"
+      Then assert: "'{{$.move[-1]}}' contains 'removed struct `TestStruct`'"
       Then cmd: "move publish -p ../../examples/entry_function_arguments_old/  --named-addresses rooch_examples=default --skip-client-compat-check --json"
       Then assert: "'{{$.move[-1].execution_info.status.type}}' == 'moveabort'"
 
@@ -541,6 +541,36 @@ fn bitcoincli_run_cmd(w: &mut World, input_tpl: String) {
     debug!("current tpl_ctx: {:?}", tpl_ctx);
 }
 
+#[then(regex = r#"graphql: "(.*)?""#)]
+async fn graphql_query(w: &mut World, input_tpl: String) {
+    if w.tpl_ctx.is_none() {
+        let tpl_ctx = TemplateContext::new();
+        w.tpl_ctx = Some(tpl_ctx);
+    }
+    let tpl_ctx = w.tpl_ctx.as_mut().unwrap();
+    let query = eval_command_args(tpl_ctx, input_tpl);
+    debug!("run graphql query: {}", query);
+
+    let url = format!("http://127.0.0.1:{}/graphql", w.opt.port());
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&serde_json::json!({ "query": query }))
+        .send()
+        .await
+        .expect("graphql request should be ok");
+    assert!(
+        response.status().is_success(),
+        "graphql response status: {}",
+        response.status()
+    );
+    let result_json = response
+        .json::<Value>()
+        .await
+        .expect("graphql response should be json");
+    debug!("graphql query ok: {:?}", result_json);
+    tpl_ctx.entry("graphql").append::<Value>(result_json);
+}
+
 #[then(regex = r#"assert: "([^"]*)""#)]
 async fn assert_output(world: &mut World, orginal_args: String) {
     assert!(world.tpl_ctx.is_some(), "tpl_ctx is none");
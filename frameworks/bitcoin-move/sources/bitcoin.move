@@ -14,17 +14,20 @@ module bitcoin_move::bitcoin{
     use moveos_std::simple_multimap;
     use moveos_std::signer;
     use moveos_std::event;
+    use moveos_std::features;
 
     use kanari_framework::address_mapping;
     use kanari_framework::bitcoin_address::BitcoinAddress;
     use kanari_framework::chain_id;
 
     use bitcoin_move::network;
+    use bitcoin_move::pow;
     use bitcoin_move::types::{Self, Block, Header, Transaction, BlockHeightHash, OutPoint};
     use bitcoin_move::utxo::{Self, UTXOSeal};
     use bitcoin_move::pending_block::{Self, PendingBlock};
     use bitcoin_move::script_buf;
     use bitcoin_move::bbn;
+    use bitcoin_move::runes;
 
     friend bitcoin_move::genesis;
 
@@ -34,6 +37,16 @@ module bitcoin_move::bitcoin{
     /// The reorg is too deep, we need to stop the system and fix the issue
     const ErrorReorgTooDeep:u64 = 3;
     const ErrorUTXONotExists:u64 = 4;
+    /// The block hash is not the hash of the block header
+    const ErrorInvalidBlockHash:u64 = 5;
+    /// The previous block hash of the header does not match the block at the previous height
+    const ErrorInvalidPrevBlockHash:u64 = 6;
+    /// The block hash does not satisfy the proof of work target
+    const ErrorInvalidProofOfWork:u64 = 7;
+    /// The block time is not greater than the median time of the previous blocks
+    const ErrorInvalidBlockTime:u64 = 8;
+    /// The bits of the header is not the expected difficulty
+    const ErrorInvalidDifficulty:u64 = 9;
 
     const ORDINAL_GENESIS_HEIGHT:u64 = 767430;
     /// https://github.com/bitcoin/bips/blob/master/bip-0034.mediawiki
@@ -94,7 +107,7 @@ module bitcoin_move::bitcoin{
         object::borrow_mut_object_shared(object_id)
     }
 
-    fun process_block_header(btc_block_store: &mut BitcoinBlockStore, block_height: u64, block_hash: address, block_header: Header){
+    fun process_block_header(btc_block_store: &mut BitcoinBlockStore, block_height: u64, block_hash: address, block_header: Header, validate_header: bool){
         //already processed
         assert!(!table::contains(&btc_block_store.hash_to_height, block_hash), ErrorBlockAlreadyProcessed);
 
@@ -102,16 +115,107 @@ module bitcoin_move::bitcoin{
         //But if it happens, we need to stop the system and fix the issue
         assert!(!table::contains(&btc_block_store.height_to_hash, block_height), ErrorReorgTooDeep);
 
+        if(validate_header){
+            validate_block_header(btc_block_store, block_height, block_hash, &block_header);
+        };
+
         table::add(&mut btc_block_store.height_to_hash, block_height, block_hash);
         table::add(&mut btc_block_store.hash_to_height, block_hash, block_height);
         table::add(&mut btc_block_store.blocks, block_hash, block_header);
         btc_block_store.latest_block = option::some(types::new_block_height_hash(block_height, block_hash)); 
     }
 
-    fun process_tx(btc_block_store: &mut BitcoinBlockStore, pblock: &mut Object<PendingBlock>, tx: &Transaction, is_coinbase: bool){
+    /// Validate the block header with the Bitcoin consensus rules: the block hash, the proof of work,
+    /// the linkage to the previous block, the median time past and the difficulty adjustment.
+    /// The checks depending on the previous blocks are skipped if the blocks are before the genesis block of Kanari.
+    fun validate_block_header(btc_block_store: &BitcoinBlockStore, block_height: u64, block_hash: address, block_header: &Header){
+        assert!(types::header_to_hash(block_header) == block_hash, ErrorInvalidBlockHash);
+        let network = network::network();
+        let pow_limit_bits = network::pow_limit_bits(network);
+        let pow_limit = pow::bits_to_target(pow_limit_bits);
+        assert!(pow::check_proof_of_work(block_header, pow_limit), ErrorInvalidProofOfWork);
+
+        if(block_height == 0 || !table::contains(&btc_block_store.height_to_hash, block_height - 1)){
+            return
+        };
+        let prev_block_hash = *table::borrow(&btc_block_store.height_to_hash, block_height - 1);
+        assert!(types::prev_blockhash(block_header) == prev_block_hash, ErrorInvalidPrevBlockHash);
+        let prev_header = table::borrow(&btc_block_store.blocks, prev_block_hash);
+
+        let median_time_past = get_median_time_past(btc_block_store, block_height);
+        if(option::is_some(&median_time_past)){
+            assert!(types::time(block_header) > option::destroy_some(median_time_past), ErrorInvalidBlockTime);
+        };
+
+        if(network::pow_no_retargeting(network)){
+            return
+        };
+        let expected_bits = get_next_work_required(btc_block_store, network, block_height, prev_header, block_header, pow_limit_bits, pow_limit);
+        if(option::is_some(&expected_bits)){
+            assert!(types::bits(block_header) == option::destroy_some(expected_bits), ErrorInvalidDifficulty);
+        };
+    }
+
+    /// Get the median time of the previous blocks of the `block_height`,
+    /// return none if the previous blocks are not enough.
+    fun get_median_time_past(btc_block_store: &BitcoinBlockStore, block_height: u64): Option<u32>{
+        let span = pow::median_time_span();
+        if(block_height < span){
+            return option::none()
+        };
+        let times = vector::empty();
+        let height = block_height - span;
+        while(height < block_height){
+            if(!table::contains(&btc_block_store.height_to_hash, height)){
+                return option::none()
+            };
+            let block_hash = *table::borrow(&btc_block_store.height_to_hash, height);
+            vector::push_back(&mut times, types::time(table::borrow(&btc_block_store.blocks, block_hash)));
+            height = height + 1;
+        };
+        option::some(pow::median_time_past(times))
+    }
+
+    /// Get the expected bits of the block at `block_height`,
+    /// return none if the blocks required to calculate the difficulty are not available.
+    fun get_next_work_required(btc_block_store: &BitcoinBlockStore, network: u8, block_height: u64, prev_header: &Header, block_header: &Header, pow_limit_bits: u32, pow_limit: u256): Option<u32>{
+        let interval = pow::difficulty_adjustment_interval();
+        if(block_height % interval != 0){
+            if(!network::pow_allow_min_difficulty_blocks(network)){
+                return option::some(types::bits(prev_header))
+            };
+            //If the new block's timestamp is more than twice the target spacing, allow mining of a min-difficulty block
+            if((types::time(block_header) as u64) > (types::time(prev_header) as u64) + pow::pow_target_spacing() * 2){
+                return option::some(pow_limit_bits)
+            };
+            //Otherwise, return the bits of the last block which is not a min-difficulty block
+            let height = block_height - 1;
+            let bits = types::bits(prev_header);
+            while(height % interval != 0 && bits == pow_limit_bits){
+                height = height - 1;
+                if(!table::contains(&btc_block_store.height_to_hash, height)){
+                    return option::none()
+                };
+                let block_hash = *table::borrow(&btc_block_store.height_to_hash, height);
+                bits = types::bits(table::borrow(&btc_block_store.blocks, block_hash));
+            };
+            return option::some(bits)
+        };
+
+        //The first block of the previous difficulty adjustment interval
+        let first_height = block_height - interval;
+        if(!table::contains(&btc_block_store.height_to_hash, first_height)){
+            return option::none()
+        };
+        let first_block_hash = *table::borrow(&btc_block_store.height_to_hash, first_height);
+        let first_header = table::borrow(&btc_block_store.blocks, first_block_hash);
+        option::some(pow::calculate_next_work_required(types::bits(prev_header), types::time(first_header), types::time(prev_header), pow_limit))
+    }
+
+    fun process_tx(btc_block_store: &mut BitcoinBlockStore, pblock: &mut Object<PendingBlock>, tx_index: u32, tx: &Transaction, is_coinbase: bool){
         let block_height = pending_block::block_height(pblock);
         let txid = types::tx_id(tx);
-        let repeat_txid = process_utxo(block_height, pblock, tx, is_coinbase);
+        let repeat_txid = process_utxo(btc_block_store, block_height, pblock, tx_index, tx, is_coinbase);
         
         if (repeat_txid) {
             table::upsert(&mut btc_block_store.txs, txid, *tx);
@@ -128,7 +232,7 @@ module bitcoin_move::bitcoin{
         };
     }
 
-    fun process_utxo(block_height: u64, pending_block: &mut Object<PendingBlock>, tx: &Transaction, is_coinbase: bool) : bool{
+    fun process_utxo(btc_block_store: &BitcoinBlockStore, block_height: u64, pending_block: &mut Object<PendingBlock>, tx_index: u32, tx: &Transaction, is_coinbase: bool) : bool{
         let txinput = types::tx_input(tx);
         let input_utxos = vector::empty();
 
@@ -184,6 +288,25 @@ module bitcoin_move::bitcoin{
                 };
             };
         };
+
+        //The Runes are indexed only if the `BITCOIN_RUNES` feature is enabled, the chains whose genesis is after
+        //the first rune height miss the etchings before the genesis, so they do not enable it.
+        if (features::bitcoin_runes_enabled() && block_height >= network::first_rune_height()) {
+            let artifact = runes::decipher(tx);
+            let commits_to_rune = false;
+            if (option::is_some(&artifact)) {
+                let rune = runes::artifact_etching_rune(option::borrow(&artifact));
+                if (option::is_some(&rune)) {
+                    commits_to_rune = tx_commits_to_rune(btc_block_store, block_height, tx, option::destroy_some(rune));
+                };
+            };
+            let seal_outs = runes::process_tx(block_height, tx_index, tx, artifact, commits_to_rune, &mut input_utxos);
+            while (!vector::is_empty(&seal_outs)) {
+                let seal_out = vector::pop_back(&mut seal_outs);
+                let (output_index, utxo_seal) = utxo::unpack_seal_out(seal_out);
+                simple_multimap::add(&mut output_seals, output_index, utxo_seal);
+            };
+        };
     
         // create new utxo
         let repeat_txid = handle_new_utxo(tx, is_coinbase, &mut output_seals, block_height, sender);
@@ -199,6 +322,41 @@ module bitcoin_move::bitcoin{
         repeat_txid
     }
 
+    /// Check whether one of the tx inputs commits to the rune in its tapscript,
+    /// and the commit output is a taproot output with enough confirmations.
+    fun tx_commits_to_rune(btc_block_store: &BitcoinBlockStore, block_height: u64, tx: &Transaction, rune: u128): bool{
+        let txinput = types::tx_input(tx);
+        let input_len = vector::length(txinput);
+        let idx = 0;
+        while (idx < input_len) {
+            let txin = vector::borrow(txinput, idx);
+            idx = idx + 1;
+            if (!runes::witness_commits_to_rune(types::txin_witness(txin), rune)) {
+                continue
+            };
+            let outpoint = types::txin_previous_output(txin);
+            let commit_txid = types::outpoint_txid(outpoint);
+            if (!table::contains(&btc_block_store.txs, commit_txid)) {
+                continue
+            };
+            let commit_tx = table::borrow(&btc_block_store.txs, commit_txid);
+            let commit_output = types::tx_output(commit_tx);
+            let vout = (types::outpoint_vout(outpoint) as u64);
+            if (vout >= vector::length(commit_output)) {
+                continue
+            };
+            if (!script_buf::is_p2tr(types::txout_script_pubkey(vector::borrow(commit_output, vout)))) {
+                continue
+            };
+            let commit_height = *table::borrow(&btc_block_store.tx_to_height, commit_txid);
+            let confirmations = block_height - commit_height + 1;
+            if (confirmations >= runes::commit_confirmations()) {
+                return true
+            };
+        };
+        false
+    }
+
     fun handle_new_utxo(tx: &Transaction, is_coinbase: bool, output_seals: &mut SimpleMultiMap<u32, UTXOSeal>, block_height: u64, sender: Option<address>) :bool {
         let txid = types::tx_id(tx);
         let txoutput = types::tx_output(tx);
@@ -280,18 +438,25 @@ module bitcoin_move::bitcoin{
     }
 
     /// This is the execute_l1_tx entry point
+    /// The block header is validated only if the `BITCOIN_HEADER_VALIDATION` feature is enabled,
+    /// so the existing chains can enable it via governance after upgrading.
     fun execute_l1_tx(block_hash: address, txid: address){
+        do_execute_l1_tx(block_hash, txid, features::bitcoin_header_validation_enabled());
+    }
+
+    fun do_execute_l1_tx(block_hash: address, txid: address, validate_header: bool){
         let btc_block_store_obj = borrow_block_store_mut();
         let btc_block_store = object::borrow_mut(btc_block_store_obj);
         let inprocess_block = pending_block::process_pending_tx(block_hash, txid);
         let block_height = pending_block::inprocess_block_height(&inprocess_block);
         let tx = *pending_block::inprocess_block_tx(&inprocess_block);
+        let tx_index = (pending_block::inprocess_block_tx_index(&inprocess_block) as u32);
         let pblock = pending_block::inprocess_block_pending_block(&mut inprocess_block);
         let is_coinbase = types::is_coinbase_tx(&tx);
-        process_tx(btc_block_store, pblock, &tx, is_coinbase);
+        process_tx(btc_block_store, pblock, tx_index, &tx, is_coinbase);
         if(is_coinbase){
             let header = pending_block::finish_pending_block(inprocess_block);
-            process_block_header(btc_block_store, block_height, block_hash, header);
+            process_block_header(btc_block_store, block_height, block_hash, header, validate_header);
         }else{
             pending_block::finish_pending_tx(inprocess_block);
         };
@@ -415,6 +580,17 @@ module bitcoin_move::bitcoin{
 
     #[test_only]
     public fun execute_l1_block_for_test(block_height: u64, block: Block){
+        execute_block_for_test(block_height, block, true);
+    }
+
+    /// Execute the block without validating the header, for the fake block created by `types::fake_block_for_test`
+    #[test_only]
+    public fun execute_fake_block_for_test(block_height: u64, block: Block){
+        execute_block_for_test(block_height, block, false);
+    }
+
+    #[test_only]
+    fun execute_block_for_test(block_height: u64, block: Block, validate_header: bool){
         let block_hash = types::header_to_hash(types::header(&block));
         let block_bytes = bcs::to_bytes(&block);
         execute_l1_block(block_height, block_hash, block_bytes);
@@ -423,10 +599,10 @@ module bitcoin_move::bitcoin{
         let coinbase_tx = vector::remove(&mut txs, 0);
         vector::for_each(txs, |tx| {
             let txid = types::tx_id(&tx);
-            execute_l1_tx(block_hash, txid);
+            do_execute_l1_tx(block_hash, txid, validate_header);
         });
         //process coinbase tx last
-        execute_l1_tx(block_hash, types::tx_id(&coinbase_tx));
+        do_execute_l1_tx(block_hash, types::tx_id(&coinbase_tx), validate_header);
     }
 
 
@@ -437,4 +613,89 @@ module bitcoin_move::bitcoin{
         btc_block_store.latest_block = option::some(types::new_block_height_hash(block_height, block_hash))
     }
 
+    #[test_only]
+    fun add_header_for_test(block_height: u64, header: Header){
+        let btc_block_store_obj = borrow_block_store_mut();
+        let btc_block_store = object::borrow_mut(btc_block_store_obj);
+        let block_hash = types::header_to_hash(&header);
+        table::add(&mut btc_block_store.height_to_hash, block_height, block_hash);
+        table::add(&mut btc_block_store.hash_to_height, block_hash, block_height);
+        table::add(&mut btc_block_store.blocks, block_hash, header);
+    }
+
+    #[test_only]
+    fun header_for_test(time: u32, bits: u32): Header{
+        types::new_header_for_test(1, @0x0, @0x0, time, bits, 0)
+    }
+
+    #[test_only]
+    fun next_work_required_for_test(network: u8, block_height: u64, prev_header: &Header, block_header: &Header): Option<u32>{
+        let pow_limit_bits = network::pow_limit_bits(network);
+        let btc_block_store = object::borrow(borrow_block_store());
+        get_next_work_required(btc_block_store, network, block_height, prev_header, block_header, pow_limit_bits, pow::bits_to_target(pow_limit_bits))
+    }
+
+    #[test]
+    fun test_get_next_work_required_retarget(){
+        bitcoin_move::genesis::init_for_test();
+        let network = network::network_bitcoin();
+        //The retarget at mainnet block 32256, the first block of the previous interval is 30240.
+        //The values are from Bitcoin Core `get_next_work_required` test in pow_tests.cpp
+        let first_header = header_for_test(1261130161, 0x1d00ffff);
+        let prev_header = header_for_test(1262152739, 0x1d00ffff);
+        let block_header = header_for_test(1262153464, 0x1d00d86a);
+        assert!(option::is_none(&next_work_required_for_test(network, 32256, &prev_header, &block_header)), 1);
+        add_header_for_test(30240, first_header);
+        assert!(next_work_required_for_test(network, 32256, &prev_header, &block_header) == option::some(0x1d00d86a), 2);
+        //The block in the interval keeps the difficulty of the previous block
+        assert!(next_work_required_for_test(network, 32257, &block_header, &header_for_test(1262153464 + 1200 + 1, 0x1d00d86a)) == option::some(0x1d00d86a), 3);
+    }
+
+    #[test]
+    fun test_get_next_work_required_testnet_min_difficulty(){
+        bitcoin_move::genesis::init_for_test();
+        let network = network::network_testnet();
+        let pow_limit_bits = network::pow_limit_bits(network);
+        let interval = pow::difficulty_adjustment_interval();
+        let spacing = (pow::pow_target_spacing() as u32);
+        let bits = 0x1c00ffff;
+        let time = 1700000000;
+        add_header_for_test(interval, header_for_test(time, bits));
+        add_header_for_test(interval + 1, header_for_test(time + spacing, bits));
+        add_header_for_test(interval + 2, header_for_test(time + spacing * 4, pow_limit_bits));
+        let prev_header = header_for_test(time + spacing * 7, pow_limit_bits);
+        add_header_for_test(interval + 3, prev_header);
+
+        //More than 20 minutes after the previous block, the min difficulty block is allowed
+        let block_header = header_for_test(time + spacing * 9 + 1, pow_limit_bits);
+        assert!(next_work_required_for_test(network, interval + 4, &prev_header, &block_header) == option::some(pow_limit_bits), 1);
+        //Exactly 20 minutes is not enough, walk back to the last block which is not a min difficulty block
+        let block_header = header_for_test(time + spacing * 9, pow_limit_bits);
+        assert!(next_work_required_for_test(network, interval + 4, &prev_header, &block_header) == option::some(bits), 2);
+        //The walk back stops at the retarget block
+        let retarget_header = header_for_test(time, pow_limit_bits);
+        assert!(next_work_required_for_test(network, interval + 1, &retarget_header, &header_for_test(time + spacing, pow_limit_bits)) == option::some(pow_limit_bits), 3);
+        //The mainnet does not allow the min difficulty block
+        assert!(next_work_required_for_test(network::network_bitcoin(), interval + 4, &prev_header, &header_for_test(time + spacing * 9 + 1, pow_limit_bits)) == option::some(pow_limit_bits), 4);
+        assert!(next_work_required_for_test(network::network_bitcoin(), interval + 4, &header_for_test(time, bits), &header_for_test(time + spacing * 9 + 1, pow_limit_bits)) == option::some(bits), 5);
+    }
+
+    #[test]
+    fun test_get_median_time_past(){
+        bitcoin_move::genesis::init_for_test();
+        let btc_block_store = object::borrow(borrow_block_store());
+        assert!(option::is_none(&get_median_time_past(btc_block_store, 11)), 1);
+        let times = vector[1700000000, 1700000600, 1700000100, 1700001200, 1700000900, 1700001800, 1700001500, 1700002400, 1700002000, 1700003000, 1700002700];
+        let i = 0;
+        while(i < vector::length(&times)){
+            add_header_for_test(100 + i, header_for_test(*vector::borrow(&times, i), 0x1d00ffff));
+            i = i + 1;
+        };
+        let btc_block_store = object::borrow(borrow_block_store());
+        //The median of the 11 previous blocks
+        assert!(get_median_time_past(btc_block_store, 111) == option::some(1700001500), 2);
+        //Not enough previous blocks
+        assert!(option::is_none(&get_median_time_past(btc_block_store, 110)), 3);
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,680 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+module bitcoin_move::brc20 {
+    use std::option::{Self, Option};
+    use std::string::{Self, String};
+    use std::vector;
+    use moveos_std::object::{Self, Object};
+    use moveos_std::table::{Self, Table};
+    use moveos_std::simple_map::{Self, SimpleMap};
+    use moveos_std::string_utils;
+    use moveos_std::json;
+    use bitcoin_move::ord::{Self, Inscription, InscriptionID};
+
+    friend bitcoin_move::genesis;
+    friend bitcoin_move::inscription_updater;
+
+    /// The byte length of the BRC20 tick
+    const TICK_LENGTH: u64 = 4;
+
+    //TODO should we register the BRC20 as a CoinInfo?
+    struct BRC20CoinInfo has store, copy, drop{
+        tick: String,
+        max: u256,
+        lim: u256,
+        dec: u8,
+        supply: u256,
+    }
+
+    /// The balance of an account, the transferable balance is locked by the transfer inscriptions
+    struct AccountBalance has store, drop{
+        available: u256,
+        transferable: u256,
+        transfer_inscriptions: vector<InscriptionID>,
+    }
+
+    struct BRC20Balance has store{
+        info: BRC20CoinInfo,
+        balance: Table<address, AccountBalance>, 
+    } 
+
+    /// The transfer inscription which is inscribed but not sent yet
+    struct TransferInscription has store, copy, drop{
+        inscription_id: InscriptionID,
+        tick: String,
+        amt: u256,
+        from: address,
+    }
+
+    /// The balance of an address for a tick, returned by `get_balances`
+    struct BRC20BalanceInfo has store, copy, drop{
+        tick: String,
+        available: u256,
+        transferable: u256,
+        transfer_inscriptions: vector<TransferInscription>,
+    }
+
+    struct BRC20Store has key {
+        coins: Table<String, BRC20Balance>,
+        /// The ticks held by each address
+        holder_ticks: Table<address, vector<String>>,
+        /// The transfer inscriptions which are inscribed but not sent yet
+        transfer_inscriptions: Table<InscriptionID, TransferInscription>,
+    }
+
+    public(friend) fun genesis_init(){
+        let brc20_store_object_id = object::named_object_id<BRC20Store>();
+        if(!object::exists_object(brc20_store_object_id)){
+            let brc20_store = BRC20Store{
+                coins: table::new(),
+                holder_ticks: table::new(),
+                transfer_inscriptions: table::new(),
+            };
+            let obj = object::new_named_object(brc20_store);
+            object::to_shared(obj);
+        }
+    }
+
+    fun borrow_store() : &mut BRC20Store {
+        //The BRC20Store is created lazily for the chain that genesis before the BRC20 protocol is supported
+        genesis_init();
+        let brc20_store_object_id = object::named_object_id<BRC20Store>();
+        let brc20_store_obj = object::borrow_mut_object_shared<BRC20Store>(brc20_store_object_id);
+        object::borrow_mut(brc20_store_obj)
+    }
+
+    /// The brc20 operation
+    struct Op has store {
+        from: address,
+        to: address,
+        json_map: SimpleMap<String, String>,
+    }
+
+    public(friend) fun new_op(from: address, to: address, json_map: SimpleMap<String, String>) : Op {
+        Op { from, to, json_map }
+    }
+
+    public fun clone_op(self: &Op) : Op {
+        let json_map = simple_map::clone(&self.json_map);
+        Op { from: self.from, to: self.to, json_map }
+    }
+
+    public fun drop_op(op: Op){
+        let Op{from:_, to:_, json_map:_} = op;
+    }
+
+    /// The brc20 deploy operation
+    /// https://domo-2.gitbook.io/brc-20-experiment/
+    /// ```json
+    /// { 
+    /// "p": "brc-20",
+    /// "op": "deploy",
+    /// "tick": "ordi",
+    /// "max": "21000000",
+    /// "lim": "1000"
+    ///}
+    /// ```
+    struct DeployOp has store,copy,drop {
+        from: address,
+        to: address,
+        tick: String,
+        max: String,
+        //Mint limit: If letting users mint to themsleves, limit per ordinal
+        lim: String,
+        //Decimals: set decimal precision, default to 18
+        dec: String,
+    }
+
+    /// The brc20 mint operation
+    /// https://domo-2.gitbook.io/brc-20-experiment/
+    /// ```json
+    /// { 
+    /// "p": "brc-20",
+    /// "op": "mint",
+    /// "tick": "ordi",
+    /// "amt": "1000"
+    /// }
+    /// ```
+    struct MintOp has store,copy,drop {
+        from: address,
+        to: address,
+        tick: String,
+        amt: String,
+    }
+
+    /// The brc20 transfer operation
+    /// https://domo-2.gitbook.io/brc-20-experiment/
+    /// ```json
+    /// {
+    /// "p": "brc-20",
+    /// "op": "transfer",
+    /// "tick": "ordi",
+    /// "to": "", 
+    /// "amt": "100"
+    /// }
+    struct TransferOp has store,copy,drop {
+        from: address,
+        to: address,
+        tick: String,
+        amt: String,
+        //TODO we need the to field?
+    }
+
+    public fun is_brc20(json_map: &SimpleMap<String,String>) : bool {
+        let protocol_key = string::utf8(b"p");
+        simple_map::contains_key(json_map, &protocol_key) && simple_map::borrow(json_map, &protocol_key) == &string::utf8(b"brc-20")
+    }
+
+    fun is_deploy(self: &Op) : bool {
+        let op_key = string::utf8(b"op");
+        simple_map::contains_key(&self.json_map, &op_key) && simple_map::borrow(&self.json_map, &op_key) == &string::utf8(b"deploy")
+    }
+
+    fun as_deploy(self: &Op) : Option<DeployOp> {
+        let deploy_op = if (is_deploy(self)) {
+            let tick_key = string::utf8(b"tick");
+            let max_key = string::utf8(b"max");
+            if(simple_map::contains_key(&self.json_map, &tick_key) && simple_map::contains_key(&self.json_map,&max_key)) {
+                let tick = *simple_map::borrow(&self.json_map, &tick_key);
+                let tick = string_utils::to_lower_case(&tick);
+                let dec = *simple_map::borrow_with_default(&self.json_map, &string::utf8(b"dec"), &string::utf8(b"18"));
+                let max = *simple_map::borrow(&self.json_map,&max_key);
+                let lim = *simple_map::borrow_with_default(&self.json_map, &string::utf8(b"lim"), &string::utf8(b"0"));
+                option::some(DeployOp { from: self.from, to: self.to, tick, max, lim, dec })
+            } else {
+                option::none()
+            }
+        } else {
+            option::none()
+        };
+        deploy_op
+    }
+
+    fun execute_deploy(brc20_store: &mut BRC20Store, deploy: DeployOp): bool{
+        //The tick is 4 bytes
+        if(string::length(&deploy.tick) != TICK_LENGTH){
+            return false
+        };
+        if(table::contains(&brc20_store.coins, deploy.tick)){
+            return false
+        };
+        
+        let tick = deploy.tick;
+
+        let dec = option::destroy_with_default(string_utils::parse_u8_option(&deploy.dec), 18u8);
+        if(dec > 18){
+            return false
+        };
+        let max_opt = string_utils::parse_decimal_option(&deploy.max, dec);
+        if(option::is_none(&max_opt)){
+            return false
+        };
+        let lim = option::destroy_with_default(string_utils::parse_decimal_option(&deploy.lim, dec), 0u256);
+        let max = option::destroy_some(max_opt);
+        if(max == 0){
+            return false
+        };
+        let coin_info = BRC20CoinInfo{ tick, max, lim, dec , supply: 0u256};
+        let balance_info = BRC20Balance{ info: coin_info, balance:table::new() };
+        table::add(&mut brc20_store.coins, tick, balance_info);
+        true
+    }
+
+    fun is_mint(self: &Op) : bool {
+        let op_key = string::utf8(b"op");
+        simple_map::contains_key(&self.json_map, &op_key) && simple_map::borrow(&self.json_map, &op_key) == &string::utf8(b"mint")
+    }
+
+    fun as_mint(self: &Op) : Option<MintOp> {
+        let mint_op = if (is_mint(self)) {
+            let tick_key = string::utf8(b"tick");
+            let amt_key = string::utf8(b"amt");
+            if(simple_map::contains_key(&self.json_map, &tick_key) && simple_map::contains_key(&self.json_map,&amt_key)) {
+                let tick = *simple_map::borrow(&self.json_map, &tick_key);
+                let tick = string_utils::to_lower_case(&tick);
+                let amt = *simple_map::borrow(&self.json_map,&amt_key);
+                option::some(MintOp { from: self.from, to: self.to, tick, amt })
+            } else {
+                option::none()
+            }
+        } else {
+            option::none()
+        };
+        mint_op
+    }
+
+    fun execute_mint(brc20_store: &mut BRC20Store, mint: MintOp): bool{
+        if(!table::contains(&brc20_store.coins, mint.tick)){
+            return false
+        };
+
+        let balance_info = table::borrow_mut(&mut brc20_store.coins, mint.tick);
+        let coin_info = &mut balance_info.info;
+        let lim = coin_info.lim;
+       
+        let amt_opt = string_utils::parse_decimal_option(&mint.amt, coin_info.dec);
+        if(option::is_none(&amt_opt)){
+            return false
+        };
+        let amt = option::destroy_some(amt_opt);
+
+        if(amt == 0){
+            return false
+        };
+        if(lim > 0 && amt > lim){
+            return false
+        };
+        if(coin_info.supply >= coin_info.max){
+            return false
+        };
+        // The last mint gets the remaining supply
+        if(coin_info.supply + amt > coin_info.max){
+            amt = coin_info.max - coin_info.supply;
+        };
+        coin_info.supply = coin_info.supply + amt;
+        add_available(brc20_store, mint.tick, mint.to, amt);
+        true
+    }
+
+    fun is_transfer(self: &Op) : bool {
+        let op_key = string::utf8(b"op");
+        simple_map::contains_key(&self.json_map, &op_key) && simple_map::borrow(&self.json_map, &op_key) == &string::utf8(b"transfer")
+    }
+
+    fun as_transfer(self: &Op) : Option<TransferOp> {
+        let transfer_op = if (is_transfer(self)) {
+            let tick_key = string::utf8(b"tick");
+            let amt_key = string::utf8(b"amt");
+            if(simple_map::contains_key(&self.json_map, &tick_key) && simple_map::contains_key(&self.json_map,&amt_key)) {
+                let tick = *simple_map::borrow(&self.json_map, &tick_key); 
+                let tick = string_utils::to_lower_case(&tick);
+                let amt = *simple_map::borrow(&self.json_map,&amt_key);
+                option::some(TransferOp { from: self.from, to: self.to, tick, amt })
+            } else {
+                option::none()
+            }
+        } else {
+            option::none()
+        };
+        transfer_op
+    }
+
+    /// Inscribe the transfer inscription, lock the amount from the available balance to the transferable balance
+    fun execute_inscribe_transfer(brc20_store: &mut BRC20Store, inscription_id: InscriptionID, transfer: TransferOp): bool{
+        let from = transfer.from;
+        if(!table::contains(&brc20_store.coins, transfer.tick)){
+            return false
+        };
+        
+        let balance_info = table::borrow_mut(&mut brc20_store.coins, transfer.tick);
+        let coin_info = &balance_info.info;
+        let amt_opt = string_utils::parse_decimal_option(&transfer.amt, coin_info.dec);
+        if(option::is_none(&amt_opt)){
+            return false
+        };
+        let amt = option::destroy_some(amt_opt);
+        if(amt == 0){
+            return false
+        };
+
+        let from_balance = borrow_mut_account_balance(&mut balance_info.balance, from);
+        if(from_balance.available < amt){
+            return false
+        };
+        from_balance.available = from_balance.available - amt;
+        from_balance.transferable = from_balance.transferable + amt;
+        vector::push_back(&mut from_balance.transfer_inscriptions, inscription_id);
+
+        let transfer_inscription = TransferInscription{ inscription_id, tick: transfer.tick, amt, from };
+        table::add(&mut brc20_store.transfer_inscriptions, inscription_id, transfer_inscription);
+        true
+    }
+
+    /// The transfer inscription is sent, move the amount from the transferable balance of the sender to the receiver.
+    /// If the inscription is burned, the amount is burned too.
+    /// If the inscription is spent as fee, the amount is returned to the sender.
+    fun execute_transfer(brc20_store: &mut BRC20Store, transfer_inscription: TransferInscription, to: address, burned: bool){
+        let TransferInscription{ inscription_id, tick, amt, from } = transfer_inscription;
+        let balance_info = table::borrow_mut(&mut brc20_store.coins, tick);
+        let from_balance = table::borrow_mut(&mut balance_info.balance, from);
+        from_balance.transferable = from_balance.transferable - amt;
+        vector::remove_value(&mut from_balance.transfer_inscriptions, &inscription_id);
+        if(!burned){
+            let to = if(to == @bitcoin_move){ from }else{ to };
+            add_available(brc20_store, tick, to, amt);
+        };
+    }
+
+    fun borrow_mut_account_balance(balance: &mut Table<address, AccountBalance>, addr: address) : &mut AccountBalance {
+        let default_balance = AccountBalance{ available: 0u256, transferable: 0u256, transfer_inscriptions: vector::empty() };
+        table::borrow_mut_with_default(balance, addr, default_balance)
+    }
+
+    fun add_available(brc20_store: &mut BRC20Store, tick: String, addr: address, amt: u256){
+        let balance_info = table::borrow_mut(&mut brc20_store.coins, tick);
+        let account_balance = borrow_mut_account_balance(&mut balance_info.balance, addr);
+        account_balance.available = account_balance.available + amt;
+        let ticks = table::borrow_mut_with_default(&mut brc20_store.holder_ticks, addr, vector::empty());
+        if(!vector::contains(ticks, &tick)){
+            vector::push_back(ticks, tick);
+        };
+    }
+
+    fun process_inscribe_op(brc20_store: &mut BRC20Store, inscription_id: InscriptionID, op: Op) :bool {
+        let result = if(is_deploy(&op)){
+            let deploy_op_opt = as_deploy(&op);
+            option::is_some(&deploy_op_opt) && execute_deploy(brc20_store, option::destroy_some(deploy_op_opt))
+        }else if(is_mint(&op)){
+            let mint_op_opt = as_mint(&op);
+            option::is_some(&mint_op_opt) && execute_mint(brc20_store, option::destroy_some(mint_op_opt))
+        }else if(is_transfer(&op)){
+            let transfer_op_opt = as_transfer(&op);
+            option::is_some(&transfer_op_opt) && execute_inscribe_transfer(brc20_store, inscription_id, option::destroy_some(transfer_op_opt))
+        }else{
+            false
+        };
+        drop_op(op);
+        result
+    }
+
+    // ==== Inscription hooks ==== //
+
+    /// Process the new inscription when it is inscribed, the operation is executed with the owner at inscribe time.
+    /// The cursed, unbound and burned inscriptions are not valid BRC20 inscriptions.
+    public(friend) fun process_new_inscription(inscription_obj: &Object<Inscription>, owner: address) {
+        let inscription = object::borrow(inscription_obj);
+        let charms = ord::charms(inscription);
+        if (ord::is_cursed(inscription) || owner == @bitcoin_move
+            || ord::is_set_charm(charms, ord::charm_unbound_flag())
+            || ord::is_set_charm(charms, ord::charm_burned_flag())
+            || !is_brc20_content_type(&ord::content_type(inscription))) {
+            return
+        };
+        let json_map = json::to_map(ord::body(inscription));
+        if (!is_brc20(&json_map)) {
+            return
+        };
+        let op = new_op(owner, owner, json_map);
+        process_inscribe_op(borrow_store(), *ord::id(inscription), op);
+    }
+
+    /// Process the inscription when it is transferred, if it is a transfer inscription which is not sent yet,
+    /// the amount is sent to the new owner, or burned if the inscription is sent to an OP_RETURN output.
+    public(friend) fun process_transfer_inscription(inscription_id: InscriptionID, to: address, is_op_return: bool) {
+        let brc20_store_object_id = object::named_object_id<BRC20Store>();
+        if (!object::exists_object(brc20_store_object_id)) {
+            return
+        };
+        let brc20_store = borrow_store();
+        if (!table::contains(&brc20_store.transfer_inscriptions, inscription_id)) {
+            return
+        };
+        let transfer_inscription = table::remove(&mut brc20_store.transfer_inscriptions, inscription_id);
+        execute_transfer(brc20_store, transfer_inscription, to, is_op_return);
+    }
+
+    fun is_brc20_content_type(content_type: &Option<String>) : bool {
+        if (option::is_none(content_type)) {
+            return false
+        };
+        let content_type = option::borrow(content_type);
+        string_utils::starts_with(content_type, &string::utf8(b"text/plain")) || string_utils::starts_with(content_type, &string::utf8(b"application/json"))
+    }
+
+    //=== Brc20 store ===
+
+    public fun get_tick_info(brc20_store_obj:&Object<BRC20Store>, tick: &String) : Option<BRC20CoinInfo> {
+        let tick = string_utils::to_lower_case(tick);
+        let brc20_store = object::borrow(brc20_store_obj);
+        if(table::contains(&brc20_store.coins, tick)){
+            option::some(*&table::borrow(&brc20_store.coins, tick).info)
+        }else{
+            option::none()
+        }
+    }
+
+    /// Get the overall balance of the address, including the available and transferable balance
+    public fun get_balance(brc20_store_obj:&Object<BRC20Store>, tick: &String, address: address) : u256 {
+        let tick = string_utils::to_lower_case(tick);
+        let brc20_store = object::borrow(brc20_store_obj);
+        if(table::contains(&brc20_store.coins, tick)){
+            let balance_info = table::borrow(&brc20_store.coins, tick);
+            if(table::contains(&balance_info.balance, address)){
+                let account_balance = table::borrow(&balance_info.balance, address);
+                account_balance.available + account_balance.transferable
+            }else{
+                0u256
+            }
+        }else{
+            0u256
+        }
+    }
+
+    /// Get the balances of all the ticks held by the address, with the pending transfer inscriptions
+    public fun get_balances(brc20_store_obj:&Object<BRC20Store>, address: address) : vector<BRC20BalanceInfo> {
+        let brc20_store = object::borrow(brc20_store_obj);
+        let balances = vector::empty();
+        if(!table::contains(&brc20_store.holder_ticks, address)){
+            return balances
+        };
+        let ticks = table::borrow(&brc20_store.holder_ticks, address);
+        let idx = 0;
+        let len = vector::length(ticks);
+        while(idx < len){
+            let tick = *vector::borrow(ticks, idx);
+            let account_balance = table::borrow(&table::borrow(&brc20_store.coins, tick).balance, address);
+            let transfer_inscriptions = vector::empty();
+            let inscription_idx = 0;
+            let inscription_len = vector::length(&account_balance.transfer_inscriptions);
+            while(inscription_idx < inscription_len){
+                let inscription_id = vector::borrow(&account_balance.transfer_inscriptions, inscription_idx);
+                vector::push_back(&mut transfer_inscriptions, *table::borrow(&brc20_store.transfer_inscriptions, *inscription_id));
+                inscription_idx = inscription_idx + 1;
+            };
+            vector::push_back(&mut balances, BRC20BalanceInfo{
+                tick,
+                available: account_balance.available,
+                transferable: account_balance.transferable,
+                transfer_inscriptions,
+            });
+            idx = idx + 1;
+        };
+        balances
+    }
+
+    public fun get_transfer_inscription(brc20_store_obj:&Object<BRC20Store>, inscription_id: InscriptionID) : Option<TransferInscription> {
+        let brc20_store = object::borrow(brc20_store_obj);
+        if(table::contains(&brc20_store.transfer_inscriptions, inscription_id)){
+            option::some(*table::borrow(&brc20_store.transfer_inscriptions, inscription_id))
+        }else{
+            option::none()
+        }
+    }
+
+    #[test]
+    fun test_deploy_op(){
+        let deploy_op_json = b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordi\",\"max\":\"21000000\",\"lim\":\"1000\"}";
+        let json_map = json::to_map(deploy_op_json);
+        assert!(is_brc20(&json_map), 1);
+        let from = @0x42;
+        let to = @0x42;
+        let op = Op { from, to, json_map };
+        
+        assert!(is_deploy(&op), 2);
+        let deploy_op_opt = as_deploy(&op);
+        assert!(option::is_some(&deploy_op_opt), 3);
+        let deploy_op = option::destroy_some(deploy_op_opt);
+        assert!(deploy_op.tick == string::utf8(b"ordi"), 4);
+        assert!(deploy_op.max == string::utf8(b"21000000"), 5);
+        assert!(deploy_op.lim == string::utf8(b"1000"), 6);
+        assert!(deploy_op.dec == string::utf8(b"18"), 7);
+
+        drop_op(op);
+    }
+
+    #[test]
+    fun test_mint_op(){
+        let mint_op_json = b"{\"p\":\"brc-20\",\"op\":\"mint\",\"tick\":\"ordi\",\"amt\":\"1000\"}";
+        let json_map = json::to_map(mint_op_json);
+        assert!(is_brc20(&json_map), 1);
+        let from = @0x42;
+        let to = @0x42;
+        let op = Op { from, to, json_map };
+        assert!(is_mint(&op), 2);
+        let mint_op_opt = as_mint(&op);
+        assert!(option::is_some(&mint_op_opt), 3);
+        let mint_op = option::destroy_some(mint_op_opt);
+        assert!(mint_op.tick == string::utf8(b"ordi"), 4);
+        assert!(mint_op.amt == string::utf8(b"1000"), 5);
+        drop_op(op);
+    }
+
+    #[test]
+    fun test_transfer_op(){
+        let transfer_op_json = b"{\"p\":\"brc-20\",\"op\":\"transfer\",\"tick\":\"ordi\",\"amt\":\"1000\"}";
+        let json_map = json::to_map(transfer_op_json);
+        assert!(is_brc20(&json_map), 1);
+        let from = @0x42;
+        let to = @0x43;
+        let op = Op { from, to, json_map };
+        assert!(is_transfer(&op), 2);
+        let transfer_op_opt = as_transfer(&op);
+        assert!(option::is_some(&transfer_op_opt), 3);
+        let transfer_op = option::destroy_some(transfer_op_opt);
+        assert!(transfer_op.tick == string::utf8(b"ordi"), 4);
+        assert!(transfer_op.amt == string::utf8(b"1000"), 5);
+        drop_op(op);
+    }
+
+    #[test_only]
+    fun borrow_account_balance(brc20_store: &BRC20Store, tick: vector<u8>, addr: address) : &AccountBalance {
+        table::borrow(&table::borrow(&brc20_store.coins, string::utf8(tick)).balance, addr)
+    }
+
+    #[test]
+    fun test_brc20_roundtrip(){
+        let brc20_store = borrow_store();
+        
+        let deploy_op_json = b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordi\",\"max\":\"21000000\",\"lim\":\"1000\"}";
+        let deployer = @0x42;
+        let minter = @0x43;
+        let transfer_to = @0x44;
+        let op = Op { from: deployer, to: deployer, json_map: json::to_map(deploy_op_json) };
+        let deploy_op = option::destroy_some(as_deploy(&op));
+        assert!(execute_deploy(brc20_store, deploy_op), 1);
+        drop_op(op);
+
+        let mint_op_json = b"{\"p\":\"brc-20\",\"op\":\"mint\",\"tick\":\"ordi\",\"amt\":\"1000\"}";
+        let op = Op { from: minter, to: minter, json_map: json::to_map(mint_op_json) };
+        let mint_op = option::destroy_some(as_mint(&op));
+        assert!(execute_mint(brc20_store, mint_op), 2);
+        drop_op(op);
+        
+        let transfer_op_json = b"{\"p\":\"brc-20\",\"op\":\"transfer\",\"tick\":\"ordi\",\"amt\":\"400\"}";
+        let inscription_id = ord::new_inscription_id(@0x1234, 0);
+        let op = Op { from: minter, to: minter, json_map: json::to_map(transfer_op_json) };
+        let transfer_op = option::destroy_some(as_transfer(&op));
+        assert!(execute_inscribe_transfer(brc20_store, inscription_id, transfer_op), 3);
+        drop_op(op);
+
+        let account_balance = borrow_account_balance(brc20_store, b"ordi", minter);
+        assert!(account_balance.available == 600000000000000000000u256, 4);
+        assert!(account_balance.transferable == 400000000000000000000u256, 5);
+        assert!(account_balance.transfer_inscriptions == vector[inscription_id], 6);
+
+        // The transferable balance can not be inscribed again
+        let transfer_op_json = b"{\"p\":\"brc-20\",\"op\":\"transfer\",\"tick\":\"ordi\",\"amt\":\"700\"}";
+        let op = Op { from: minter, to: minter, json_map: json::to_map(transfer_op_json) };
+        let transfer_op = option::destroy_some(as_transfer(&op));
+        assert!(!execute_inscribe_transfer(brc20_store, ord::new_inscription_id(@0x1235, 0), transfer_op), 7);
+        drop_op(op);
+
+        // Send the transfer inscription
+        let transfer_inscription = table::remove(&mut brc20_store.transfer_inscriptions, inscription_id);
+        execute_transfer(brc20_store, transfer_inscription, transfer_to, false);
+        
+        let balance_info = table::borrow(&brc20_store.coins, string::utf8(b"ordi"));
+        let coin_info = &balance_info.info;
+        assert!(coin_info.supply == 1000000000000000000000u256, 8);
+        let account_balance = borrow_account_balance(brc20_store, b"ordi", minter);
+        assert!(account_balance.available == 600000000000000000000u256, 9);
+        assert!(account_balance.transferable == 0u256, 10);
+        assert!(vector::is_empty(&account_balance.transfer_inscriptions), 11);
+        let account_balance = borrow_account_balance(brc20_store, b"ordi", transfer_to);
+        assert!(account_balance.available == 400000000000000000000u256, 12);
+        assert!(table::borrow(&brc20_store.holder_ticks, transfer_to) == &vector[string::utf8(b"ordi")], 13);
+    }
+
+    #[test]
+    fun test_brc20_transfer_spent_as_fee(){
+        let brc20_store = borrow_store();
+        let minter = @0x43;
+
+        let deploy_op_json = b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordi\",\"max\":\"21000000\",\"lim\":\"1000\",\"dec\":\"0\"}";
+        let op = Op { from: @0x42, to: @0x42, json_map: json::to_map(deploy_op_json) };
+        assert!(execute_deploy(brc20_store, option::destroy_some(as_deploy(&op))), 1);
+        drop_op(op);
+
+        let mint_op_json = b"{\"p\":\"brc-20\",\"op\":\"mint\",\"tick\":\"ordi\",\"amt\":\"1000\"}";
+        let op = Op { from: minter, to: minter, json_map: json::to_map(mint_op_json) };
+        assert!(execute_mint(brc20_store, option::destroy_some(as_mint(&op))), 2);
+        drop_op(op);
+
+        let transfer_op_json = b"{\"p\":\"brc-20\",\"op\":\"transfer\",\"tick\":\"ordi\",\"amt\":\"400\"}";
+        let inscription_id = ord::new_inscription_id(@0x1234, 0);
+        let op = Op { from: minter, to: minter, json_map: json::to_map(transfer_op_json) };
+        assert!(execute_inscribe_transfer(brc20_store, inscription_id, option::destroy_some(as_transfer(&op))), 3);
+        drop_op(op);
+
+        // The transfer inscription is spent as fee, the amount is returned to the sender
+        process_transfer_inscription(inscription_id, @bitcoin_move, false);
+        let brc20_store = borrow_store();
+        let account_balance = borrow_account_balance(brc20_store, b"ordi", minter);
+        assert!(account_balance.available == 1000u256, 4);
+        assert!(account_balance.transferable == 0u256, 5);
+        assert!(!table::contains(&brc20_store.transfer_inscriptions, inscription_id), 6);
+        assert!(!table::contains(&table::borrow(&brc20_store.coins, string::utf8(b"ordi")).balance, @bitcoin_move), 7);
+    }
+
+    #[test]
+    fun test_brc20_mint_remaining_supply(){
+        let brc20_store = borrow_store();
+
+        let deploy_op_json = b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordi\",\"max\":\"1500\",\"lim\":\"1000\",\"dec\":\"0\"}";
+        let op = Op { from: @0x42, to: @0x42, json_map: json::to_map(deploy_op_json) };
+        assert!(execute_deploy(brc20_store, option::destroy_some(as_deploy(&op))), 1);
+        drop_op(op);
+
+        let mint_op_json = b"{\"p\":\"brc-20\",\"op\":\"mint\",\"tick\":\"ordi\",\"amt\":\"1000\"}";
+        let op = Op { from: @0x43, to: @0x43, json_map: json::to_map(mint_op_json) };
+        assert!(execute_mint(brc20_store, option::destroy_some(as_mint(&op))), 2);
+        // The second mint only gets the remaining supply
+        assert!(execute_mint(brc20_store, option::destroy_some(as_mint(&op))), 3);
+        // The supply is exhausted
+        assert!(!execute_mint(brc20_store, option::destroy_some(as_mint(&op))), 4);
+        drop_op(op);
+
+        let coin_info = &table::borrow(&brc20_store.coins, string::utf8(b"ordi")).info;
+        assert!(coin_info.supply == 1500u256, 5);
+        assert!(borrow_account_balance(brc20_store, b"ordi", @0x43).available == 1500u256, 6);
+    }
+
+    #[test]
+    fun test_brc20_deploy_invalid(){
+        let brc20_store = borrow_store();
+        let deploy_op_jsons = vector[
+            //The tick is not 4 bytes
+            b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ord\",\"max\":\"21000000\"}",
+            b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordix\",\"max\":\"21000000\"}",
+            //The max is zero
+            b"{\"p\":\"brc-20\",\"op\":\"deploy\",\"tick\":\"ordi\",\"max\":\"0\"}",
+        ];
+        while(!vector::is_empty(&deploy_op_jsons)){
+            let op = Op { from: @0x42, to: @0x42, json_map: json::to_map(vector::pop_back(&mut deploy_op_jsons)) };
+            assert!(!execute_deploy(brc20_store, option::destroy_some(as_deploy(&op))), 1);
+            drop_op(op);
+        };
+        assert!(!table::contains(&brc20_store.coins, string::utf8(b"ordi")), 2);
+    }
+}
@@ -21,6 +21,7 @@ module bitcoin_move::inscription_updater{
     use bitcoin_move::pending_block::{Self, PendingBlock};
     use bitcoin_move::utxo::{Self, UTXO, SealOut};
     use bitcoin_move::script_buf;
+    use bitcoin_move::brc20;
 
     friend bitcoin_move::bitcoin;
 
@@ -140,9 +141,11 @@ module bitcoin_move::inscription_updater{
             output_idx = output_idx + 1;
         };
 
-        let envelopes = ord::parse_inscription_from_tx(tx);
+        //the delegates are in the same order as the envelopes
+        let (envelopes, delegates) = ord::parse_inscription_from_tx_with_delegates(tx);
         //reverse the envelopes for pop back to iterate
         vector::reverse(&mut envelopes);
+        vector::reverse(&mut delegates);
 
         let updater = if (pending_block::exists_intermediate<InscriptionUpdater>(pending_block)){
             pending_block::take_intermediate<InscriptionUpdater>(pending_block)
@@ -223,7 +226,11 @@ module bitcoin_move::inscription_updater{
                     vector::push_back(&mut envelopes, envelope);
                     break
                 };
+                let delegate = vector::pop_back(&mut delegates);
                 let inscription_id = ord::new_inscription_id(txid, id_counter);
+                if (option::is_some(&delegate)){
+                    ord::add_delegate(inscription_id, option::destroy_some(delegate));
+                };
                 let pointer = *ord::inscription_record_pointer(payload);
                 let parents = *ord::inscription_record_parents(payload);
                 
@@ -416,6 +423,7 @@ module bitcoin_move::inscription_updater{
             
         
             ord::transfer_object(inscription_obj, owner, new_satpoint, is_op_return);
+            brc20::process_transfer_inscription(inscription_id, owner, is_op_return);
             
             event::emit(InscriptionTransferredEvent{
                 block_height: updater.block_height,
@@ -491,6 +499,7 @@ module bitcoin_move::inscription_updater{
                 envelope,
                 owner,
             );
+            brc20::process_new_inscription(ord::borrow_object(inscription_obj_id), owner);
             event::emit(InscriptionCreatedEvent{
                 block_height: updater.block_height,
                 charms,
@@ -146,6 +146,54 @@ module bitcoin_move::network{
         }
     }
 
+    /// Runes protocol activation height.
+    /// https://github.com/ordinals/ord/blob/master/src/chain.rs
+    public fun first_rune_height(): u64 {
+        let n = network();
+        if (n == NETWORK_BITCOIN) {
+            (SUBSIDY_HALVING_INTERVAL as u64) * 4
+        } else if (n == NETWORK_REGTEST) {
+            0
+        } else if (n == NETWORK_SIGNET) {
+            0
+        } else if (n == NETWORK_TESTNET) {
+            (SUBSIDY_HALVING_INTERVAL as u64) * 12
+        } else {
+            abort ErrorUnknownNetwork
+        }
+    }
+
+    /// How may blocks between halvings.
+    public fun subsidy_halving_interval(): u64 {
+        (SUBSIDY_HALVING_INTERVAL as u64)
+    }
+
+    /// The easiest proof of work target allowed by the network, in the compact bits format.
+    /// https://github.com/bitcoin/bitcoin/blob/master/src/kernel/chainparams.cpp
+    public fun pow_limit_bits(network: u8): u32 {
+        if (network == NETWORK_BITCOIN) {
+            0x1d00ffff
+        } else if (network == NETWORK_TESTNET) {
+            0x1d00ffff
+        } else if (network == NETWORK_SIGNET) {
+            0x1e0377ae
+        } else if (network == NETWORK_REGTEST) {
+            0x207fffff
+        } else {
+            abort ErrorUnknownNetwork
+        }
+    }
+
+    /// Whether the network allows a min difficulty block if no block is found in twice the target spacing.
+    public fun pow_allow_min_difficulty_blocks(network: u8): bool {
+        network == NETWORK_TESTNET || network == NETWORK_REGTEST
+    }
+
+    /// Whether the network never adjusts the difficulty.
+    public fun pow_no_retargeting(network: u8): bool {
+        network == NETWORK_REGTEST
+    }
+
     /// Block Rewards
     public fun subsidy_by_height(height: u64): u64 {
         let epoch = (height as u32) / SUBSIDY_HALVING_INTERVAL;
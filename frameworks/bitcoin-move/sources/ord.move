@@ -391,6 +391,28 @@ module bitcoin_move::ord {
         object::borrow(inscription_obj)
     }
 
+    // =============== Inscription Delegate =============== //
+
+    /// Record the delegate of the inscription. The delegate is stored in the InscriptionStore
+    /// keyed by the inscription id, because the inscription object may be created in a later tx,
+    /// such as the coinbase tx when the inscription is spent as fee.
+    public(friend) fun add_delegate(inscription_id: InscriptionID, delegate: InscriptionID) {
+        let store_obj_id = object::named_object_id<InscriptionStore>();
+        let store_obj = object::borrow_mut_object_shared<InscriptionStore>(store_obj_id);
+        object::upsert_field(store_obj, inscription_id, delegate);
+    }
+
+    /// Get the delegate of the inscription, the content of the inscription should be served from the delegate.
+    public fun delegate(inscription_id: InscriptionID): Option<InscriptionID> {
+        let store_obj_id = object::named_object_id<InscriptionStore>();
+        let store_obj = object::borrow_object<InscriptionStore>(store_obj_id);
+        if (object::contains_field(store_obj, inscription_id)) {
+            option::some(*object::borrow_field(store_obj, inscription_id))
+        } else {
+            option::none()
+        }
+    }
+
     // =============== Inscription Getter =============== //
 
     public fun txid(self: &Inscription): address {
@@ -514,17 +536,29 @@ module bitcoin_move::ord {
 
     // ======= Envelope and InscriptionRecord
 
+    /// Kept for the compatibility of the released framework, use `from_witness_with_delegates` instead.
     native fun from_witness(witness: &Witness): vector<Envelope<InscriptionRecord>>;
 
+    /// Parse the inscriptions and the delegate of every inscription in the witness,
+    /// the delegates are in the same order as the inscriptions.
+    native fun from_witness_with_delegates(witness: &Witness): (vector<Envelope<InscriptionRecord>>, vector<Option<InscriptionID>>);
+
     public(friend) fun parse_inscription_from_tx(tx: &Transaction): vector<Envelope<InscriptionRecord>> {
+        let (records, _delegates) = parse_inscription_from_tx_with_delegates(tx);
+        records
+    }
+
+    /// Parse the inscriptions of the tx, and the delegates in the same order as the inscriptions.
+    public(friend) fun parse_inscription_from_tx_with_delegates(tx: &Transaction): (vector<Envelope<InscriptionRecord>>, vector<Option<InscriptionID>>) {
         let inputs = types::tx_input(tx);
         let len = vector::length(inputs);
         let input_idx = 0;
         let records = vector::empty();
+        let delegates = vector::empty();
         while (input_idx < len) {
             let input = vector::borrow(inputs, input_idx);
             let witness = types::txin_witness(input);
-            let inscription_records = from_witness(witness);
+            let (inscription_records, inscription_delegates) = from_witness_with_delegates(witness);
             let record_len = vector::length(&inscription_records);
             let record_idx = 0;
             while (record_idx < record_len) {
@@ -533,9 +567,10 @@ module bitcoin_move::ord {
                 record_idx = record_idx + 1;
             };
             vector::append(&mut records, inscription_records);
+            vector::append(&mut delegates, inscription_delegates);
             input_idx = input_idx + 1;
         };
-        records
+        (records, delegates)
     }
 
     public(friend) fun envelope_input<T>(envelope: &Envelope<T>): u32 {
@@ -1385,4 +1420,16 @@ module bitcoin_move::ord {
         let invalid_charm_option = view_inscription_charm(invalid_id_str);
         assert!(option::is_none(&invalid_charm_option), 1);
     }
+
+    #[test]
+    fun test_delegate() {
+        genesis_init();
+        let txid = @0x77dfc2fe598419b00641c296181a96cf16943697f573480b023b77cce82ada21;
+        let id = new_inscription_id(txid, 1);
+        let delegate_id = new_inscription_id(txid, 0);
+        assert!(option::is_none(&delegate(id)), 1);
+        add_delegate(id, delegate_id);
+        assert!(delegate(id) == option::some(delegate_id), 2);
+        assert!(option::is_none(&delegate(delegate_id)), 3);
+    }
 }
@@ -48,12 +48,18 @@ module bitcoin_move::pending_block{
         reorg_block_count: u64,
     }
 
+    /// The key of the field to store the index of the tx in the block
+    struct TxIndexKey has copy, drop, store{
+        txid: address,
+    }
+
     /// InprocessBlock is used to store the block and txs that are being processed
     /// This is a hot potato struct, can not be store and drop 
     struct InprocessBlock {
         block_hash: address,
         block_obj: Object<PendingBlock>,
         tx: Transaction,
+        tx_index: u64,
     }
 
     struct ReorgEvent has copy, drop{
@@ -135,6 +141,7 @@ module bitcoin_move::pending_block{
         vector::for_each(txs, |tx| {
             let txid = types::tx_id(&tx);
             object::add_field(&mut block_obj, txid, tx);
+            object::add_field(&mut block_obj, TxIndexKey{txid}, vector::length(&tx_ids));
             vector::push_back(&mut tx_ids, txid);
         });
         object::add_field(&mut block_obj, TX_IDS_KEY, tx_ids);
@@ -202,6 +209,10 @@ module bitcoin_move::pending_block{
             vector::for_each(ids, |txid| {
                 // Directly drop the tx
                 let _tx: Transaction = object::remove_field(&mut obj, txid);  
+                let key = TxIndexKey{txid};
+                if(object::contains_field(&obj, key)){
+                    let _tx_index: u64 = object::remove_field(&mut obj, key);
+                };
             });
         };
         
@@ -215,6 +226,20 @@ module bitcoin_move::pending_block{
         block.block_height
     }
 
+    /// Remove the index of the tx, and return it.
+    /// The blocks added before the index is stored do not have the field, find the index in the tx ids.
+    fun remove_tx_index(pending_block: &mut Object<PendingBlock>, txid: address): u64{
+        let key = TxIndexKey{txid};
+        if(object::contains_field(pending_block, key)){
+            object::remove_field(pending_block, key)
+        }else{
+            let tx_ids: &vector<address> = object::borrow_field(pending_block, TX_IDS_KEY);
+            let (found, index) = vector::index_of(tx_ids, &txid);
+            assert!(found, ErrorPendingTxNotFound);
+            index
+        }
+    }
+
     /// The intermediate is used to store the intermediate state during the tx processing
     public(friend) fun take_intermediate<I: store>(pending_block: &mut Object<PendingBlock>): I{
         let intermediate_name = type_info::type_name<I>();
@@ -241,23 +266,25 @@ module bitcoin_move::pending_block{
         assert!(best_block_height >= store.reorg_block_count && best_block_height - store.reorg_block_count >= object::borrow(&block_obj).block_height, ErrorNeedToWaitMoreBlocks);
         assert!(object::contains_field(&block_obj, txid), ErrorPendingTxNotFound);
         let tx = object::remove_field(&mut block_obj, txid);
+        let tx_index = remove_tx_index(&mut block_obj, txid);
         let inprocess_block = InprocessBlock{
             block_hash: block_hash,
             block_obj: block_obj,
             tx: tx,
+            tx_index: tx_index,
         };
         inprocess_block
     }
 
     public(friend) fun finish_pending_tx(inprocess_block: InprocessBlock){
-        let InprocessBlock{block_hash:_, block_obj, tx:_} = inprocess_block;
+        let InprocessBlock{block_hash:_, block_obj, tx:_, tx_index:_} = inprocess_block;
         let pending_block = object::borrow_mut(&mut block_obj);
         pending_block.processed_tx = pending_block.processed_tx + 1;
         object::transfer_extend(block_obj, @bitcoin_move);
     }
 
     public(friend) fun finish_pending_block(inprocess_block: InprocessBlock): Header{
-        let InprocessBlock{block_hash:_, block_obj, tx} = inprocess_block;
+        let InprocessBlock{block_hash:_, block_obj, tx, tx_index:_} = inprocess_block;
          // The coinbase tx should be the last tx in the block
         // If the coinbase tx is processed, we can remove the block
         assert!(types::is_coinbase_tx(&tx), ErrorPendingBlockNotFinished);
@@ -278,6 +305,11 @@ module bitcoin_move::pending_block{
         &inprocess_block.tx
     }
 
+    /// The index of the processing tx in the block
+    public(friend) fun inprocess_block_tx_index(inprocess_block: &InprocessBlock): u64{
+        inprocess_block.tx_index
+    }
+
     public(friend) fun inprocess_block_header(inprocess_block: &InprocessBlock): &Header{
         let block_obj = object::borrow(&inprocess_block.block_obj);
         &block_obj.header
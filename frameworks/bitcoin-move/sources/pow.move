@@ -0,0 +1,241 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+/// Bitcoin proof of work rules, ported from Bitcoin Core `pow.cpp` and `arith_uint256.cpp`.
+/// https://github.com/bitcoin/bitcoin/blob/master/src/pow.cpp
+module bitcoin_move::pow{
+    use std::vector;
+    use moveos_std::bcs;
+    use moveos_std::address;
+    use bitcoin_move::types::{Self, Header};
+
+    const ErrorInvalidBits: u64 = 1;
+    const ErrorEmptyBlockTimes: u64 = 2;
+
+    /// How many blocks between difficulty adjustments.
+    const DIFFICULTY_ADJUSTMENT_INTERVAL: u64 = 2016;
+    /// The expected time span of a difficulty adjustment interval, two weeks in seconds.
+    const POW_TARGET_TIMESPAN: u64 = 1_209_600;
+    /// The expected time between two blocks, ten minutes in seconds.
+    const POW_TARGET_SPACING: u64 = 600;
+    /// How many previous blocks are used to calculate the median time past.
+    const MEDIAN_TIME_SPAN: u64 = 11;
+
+    public fun difficulty_adjustment_interval(): u64 {
+        DIFFICULTY_ADJUSTMENT_INTERVAL
+    }
+
+    public fun pow_target_timespan(): u64 {
+        POW_TARGET_TIMESPAN
+    }
+
+    public fun pow_target_spacing(): u64 {
+        POW_TARGET_SPACING
+    }
+
+    public fun median_time_span(): u64 {
+        MEDIAN_TIME_SPAN
+    }
+
+    /// Check the compact bits is a valid target, not negative, not zero and not overflow the u256.
+    public fun is_valid_bits(bits: u32): bool {
+        let size = bits >> 24;
+        let word = bits & 0x007fffff;
+        if (word == 0) {
+            return false
+        };
+        let negative = (bits & 0x00800000) != 0;
+        let overflow = size > 34 || (word > 0xff && size > 33) || (word > 0xffff && size > 32);
+        if (negative || overflow) {
+            return false
+        };
+        //The word may be shifted out if the size is too small
+        bits_to_target_unchecked(bits) != 0
+    }
+
+    /// Decode the compact bits in the block header to the target.
+    /// Abort if the bits is not a valid target.
+    public fun bits_to_target(bits: u32): u256 {
+        assert!(is_valid_bits(bits), ErrorInvalidBits);
+        bits_to_target_unchecked(bits)
+    }
+
+    fun bits_to_target_unchecked(bits: u32): u256 {
+        let size = bits >> 24;
+        let word = ((bits & 0x007fffff) as u256);
+        if (size <= 3) {
+            word >> ((8 * (3 - size)) as u8)
+        } else {
+            word << ((8 * (size - 3)) as u8)
+        }
+    }
+
+    /// Encode the target to the compact bits.
+    public fun target_to_bits(target: u256): u32 {
+        let size = byte_len(target);
+        let compact = if (size <= 3) {
+            ((target << ((8 * (3 - size)) as u8)) as u32)
+        } else {
+            ((target >> ((8 * (size - 3)) as u8)) as u32)
+        };
+        //The 0x00800000 bit denotes the sign, so if it is already set, divide the mantissa by 256 and increase the exponent.
+        if ((compact & 0x00800000) != 0) {
+            compact = compact >> 8;
+            size = size + 1;
+        };
+        compact | ((size as u32) << 24)
+    }
+
+    fun byte_len(value: u256): u64 {
+        let len = 0;
+        while (value != 0) {
+            value = value >> 8;
+            len = len + 1;
+        };
+        len
+    }
+
+    /// Convert the block hash to the u256 for comparing with the target.
+    /// The block hash is stored in the internal byte order, which is little endian, the same as the bcs u256.
+    public fun hash_to_u256(hash: address): u256 {
+        bcs::from_bytes<u256>(address::to_bytes(&hash))
+    }
+
+    /// Check the block hash satisfies the proof of work requirement specified by the bits of the header,
+    /// and the target is not easier than the `pow_limit` of the network.
+    public fun check_proof_of_work(header: &Header, pow_limit: u256): bool {
+        let bits = types::bits(header);
+        if (!is_valid_bits(bits)) {
+            return false
+        };
+        let target = bits_to_target_unchecked(bits);
+        if (target > pow_limit) {
+            return false
+        };
+        hash_to_u256(types::header_to_hash(header)) <= target
+    }
+
+    /// Calculate the bits of the first block of a new difficulty adjustment interval.
+    /// `last_bits` and `last_block_time` are from the last block of the previous interval,
+    /// `first_block_time` is from the first block of the previous interval.
+    public fun calculate_next_work_required(last_bits: u32, first_block_time: u32, last_block_time: u32, pow_limit: u256): u32 {
+        let first_block_time = (first_block_time as u64);
+        let last_block_time = (last_block_time as u64);
+        let actual_timespan = if (last_block_time > first_block_time) {
+            last_block_time - first_block_time
+        } else {
+            0
+        };
+        //Limit adjustment step
+        let min_timespan = POW_TARGET_TIMESPAN / 4;
+        let max_timespan = POW_TARGET_TIMESPAN * 4;
+        if (actual_timespan < min_timespan) {
+            actual_timespan = min_timespan;
+        };
+        if (actual_timespan > max_timespan) {
+            actual_timespan = max_timespan;
+        };
+        let new_target = bits_to_target(last_bits) * (actual_timespan as u256) / (POW_TARGET_TIMESPAN as u256);
+        if (new_target > pow_limit) {
+            new_target = pow_limit;
+        };
+        target_to_bits(new_target)
+    }
+
+    /// Calculate the median of the block times.
+    /// The `times` should be the times of the previous `MEDIAN_TIME_SPAN` blocks.
+    public fun median_time_past(times: vector<u32>): u32 {
+        let len = vector::length(&times);
+        assert!(len > 0, ErrorEmptyBlockTimes);
+        //Insertion sort, the times length is small
+        let i = 1;
+        while (i < len) {
+            let j = i;
+            while (j > 0 && *vector::borrow(&times, j - 1) > *vector::borrow(&times, j)) {
+                vector::swap(&mut times, j - 1, j);
+                j = j - 1;
+            };
+            i = i + 1;
+        };
+        *vector::borrow(&times, len / 2)
+    }
+
+    #[test]
+    fun test_bits_to_target() {
+        assert!(bits_to_target(0x1d00ffff) == 0x00000000ffff0000000000000000000000000000000000000000000000000000, 1);
+        assert!(bits_to_target(0x207fffff) == 0x7fffff0000000000000000000000000000000000000000000000000000000000, 2);
+        assert!(bits_to_target(0x1e0377ae) == 0x00000377ae000000000000000000000000000000000000000000000000000000, 3);
+        assert!(bits_to_target(0x01123456) == 0x12, 4);
+        assert!(bits_to_target(0x04123456) == 0x12345600, 5);
+        assert!(!is_valid_bits(0x04923456), 6);
+        assert!(!is_valid_bits(0x00123456), 7);
+        assert!(!is_valid_bits(0xff123456), 8);
+        assert!(!is_valid_bits(0x1d000000), 9);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = ErrorInvalidBits, location = Self)]
+    fun test_bits_to_target_negative() {
+        bits_to_target(0x04923456);
+    }
+
+    #[test]
+    fun test_target_to_bits() {
+        let bits = vector[0x1d00ffff, 0x207fffff, 0x1e0377ae, 0x1c05a3f4, 0x170a8bb4, 0x04123456, 0x01120000];
+        vector::for_each(bits, |b| {
+            assert!(target_to_bits(bits_to_target(b)) == b, (b as u64));
+        });
+        //The mantissa sign bit is set, the exponent should be increased
+        assert!(target_to_bits(0x80) == 0x02008000, 1);
+    }
+
+    #[test]
+    fun test_check_proof_of_work() {
+        let pow_limit = bits_to_target(0x1d00ffff);
+        //https://mempool.space/block/00000000000000000002b73f69e81b8b5e98dff0f2b7632fcb83c050c3b099a1
+        let header = types::new_header_for_test(
+            536879108,
+            bitcoin_move::bitcoin_hash::from_ascii_bytes(&b"00000000000000000009d54a110cc122960d31567d3ee84a1f18a98f50591046"),
+            bitcoin_move::bitcoin_hash::from_ascii_bytes(&b"e1e0573e6098d8128ee859e7540f56b01fe0a33e56694df6d2fab0f96c4954b3"),
+            1644403033,
+            0x170a8bb4,
+            1693537958,
+        );
+        assert!(check_proof_of_work(&header, pow_limit), 1);
+
+        let invalid_nonce_header = types::new_header_for_test(
+            536879108,
+            bitcoin_move::bitcoin_hash::from_ascii_bytes(&b"00000000000000000009d54a110cc122960d31567d3ee84a1f18a98f50591046"),
+            bitcoin_move::bitcoin_hash::from_ascii_bytes(&b"e1e0573e6098d8128ee859e7540f56b01fe0a33e56694df6d2fab0f96c4954b3"),
+            1644403033,
+            0x170a8bb4,
+            1693537959,
+        );
+        assert!(!check_proof_of_work(&invalid_nonce_header, pow_limit), 2);
+
+        //The target of the header is easier than the pow limit
+        assert!(!check_proof_of_work(&header, bits_to_target(0x1700ffff)), 3);
+    }
+
+    // The cases are from Bitcoin Core pow_tests.cpp
+    #[test]
+    fun test_calculate_next_work_required() {
+        let pow_limit = bits_to_target(0x1d00ffff);
+        // Block #30240 and #32255
+        assert!(calculate_next_work_required(0x1d00ffff, 1261130161, 1262152739, pow_limit) == 0x1d00d86a, 1);
+        // The new target is limited by the pow limit, block #0 and #2015
+        assert!(calculate_next_work_required(0x1d00ffff, 1231006505, 1233061996, pow_limit) == 0x1d00ffff, 2);
+        // The actual timespan is less than the lower limit, block #66528 and #68543
+        assert!(calculate_next_work_required(0x1c05a3f4, 1279008237, 1279297671, pow_limit) == 0x1c0168fd, 3);
+        // The actual timespan is greater than the upper limit, block #46368 and #48383
+        assert!(calculate_next_work_required(0x1c387f6f, 1263163443, 1269211443, pow_limit) == 0x1d00e1fd, 4);
+    }
+
+    #[test]
+    fun test_median_time_past() {
+        assert!(median_time_past(vector[5, 1, 4, 2, 3]) == 3, 1);
+        assert!(median_time_past(vector[1]) == 1, 2);
+        assert!(median_time_past(vector[10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]) == 5, 3);
+        assert!(median_time_past(vector[1, 2]) == 2, 4);
+    }
+}
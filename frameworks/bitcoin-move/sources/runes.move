@@ -0,0 +1,793 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+/// Runes protocol, ported from ord `index/updater/rune_updater.rs` and the `ordinals` crate.
+/// https://docs.ordinals.com/runes.html
+module bitcoin_move::runes{
+    use std::option::{Self, Option};
+    use std::string::String;
+    use std::vector;
+
+    use moveos_std::object::{Self, Object, ObjectID};
+    use moveos_std::simple_map::{Self, SimpleMap};
+    use moveos_std::type_info;
+
+    use bitcoin_move::types::{Self, Transaction, Witness, OutPoint};
+    use bitcoin_move::utxo::{Self, UTXO, SealOut};
+    use bitcoin_move::script_buf;
+    use bitcoin_move::network;
+
+    friend bitcoin_move::genesis;
+    friend bitcoin_move::bitcoin;
+
+    const ErrorRuneEntryNotExists: u64 = 1;
+
+    /// How many confirmations the commit transaction of the etched rune needs
+    const COMMIT_CONFIRMATIONS: u64 = 6;
+    /// The runes greater than or equal to this value are reserved, and are assigned to the etching without a rune name.
+    const RESERVED: u128 = 6402364363415443603228541259936211926;
+    /// STEPS[n] is the first rune with n + 1 letters
+    const STEPS: vector<u128> = vector[
+        0,
+        26,
+        702,
+        18278,
+        475254,
+        12356630,
+        321272406,
+        8353082582,
+        217180147158,
+        5646683826134,
+        146813779479510,
+        3817158266467286,
+        99246114928149462,
+    ];
+
+    struct RuneId has store, copy, drop {
+        block: u64,
+        tx: u32,
+    }
+
+    struct Edict has store, copy, drop {
+        id: RuneId,
+        amount: u128,
+        output: u32,
+    }
+
+    struct Terms has store, copy, drop {
+        amount: Option<u128>,
+        cap: Option<u128>,
+        height_start: Option<u64>,
+        height_end: Option<u64>,
+        offset_start: Option<u64>,
+        offset_end: Option<u64>,
+    }
+
+    struct Etching has store, copy, drop {
+        divisibility: Option<u8>,
+        premine: Option<u128>,
+        rune: Option<u128>,
+        spacers: Option<u32>,
+        /// The unicode code point of the symbol
+        symbol: Option<u32>,
+        terms: Option<Terms>,
+        turbo: bool,
+    }
+
+    /// The deciphered runestone of a transaction.
+    /// If `cenotaph` is true, the runestone is malformed, `flaw` is the reason, and the etching only carries the rune.
+    struct Artifact has store, copy, drop {
+        cenotaph: bool,
+        flaw: Option<String>,
+        edicts: vector<Edict>,
+        etching: Option<Etching>,
+        mint: Option<RuneId>,
+        pointer: Option<u32>,
+    }
+
+    /// The RuneStore is the parent of all RuneEntry and RuneBalances objects,
+    /// and keeps the rune -> RuneId mapping in its fields.
+    struct RuneStore has key {
+        /// The number of etched runes
+        runes: u64,
+    }
+
+    struct RuneEntry has key {
+        id: RuneId,
+        rune: u128,
+        spacers: u32,
+        divisibility: u8,
+        symbol: Option<u32>,
+        premine: u128,
+        terms: Option<Terms>,
+        mints: u128,
+        burned: u128,
+        /// The txid of the etching transaction
+        etching: address,
+        /// The sequence number of the rune
+        number: u64,
+        turbo: bool,
+    }
+
+    /// The rune balances held by an UTXO, the object is sealed to the UTXO and owned by the UTXO owner.
+    struct RuneBalances has key {
+        outpoint: OutPoint,
+        balances: SimpleMap<RuneId, u128>,
+    }
+
+    public(friend) fun genesis_init() {
+        let store_id = object::named_object_id<RuneStore>();
+        if (!object::exists_object(store_id)) {
+            let store_obj = object::new_named_object(RuneStore { runes: 0 });
+            object::to_shared(store_obj);
+        }
+    }
+
+    fun borrow_mut_rune_store(): &mut Object<RuneStore> {
+        //The RuneStore is created lazily for the chain that genesis before the Runes protocol is supported
+        genesis_init();
+        object::borrow_mut_object_shared<RuneStore>(object::named_object_id<RuneStore>())
+    }
+
+    native fun decipher_runestone(tx: &Transaction): Option<Artifact>;
+
+    native fun tapscript_contains_commitment(witness: &Witness, commitment: vector<u8>): bool;
+
+    /// Decipher the runestone from the OP_RETURN output of the transaction
+    public fun decipher(tx: &Transaction): Option<Artifact> {
+        decipher_runestone(tx)
+    }
+
+    /// Check whether the tapscript of the witness contains the commitment of the rune
+    public fun witness_commits_to_rune(witness: &Witness, rune: u128): bool {
+        tapscript_contains_commitment(witness, commitment(rune))
+    }
+
+    // ==================== RuneId ====================
+
+    public fun new_rune_id(block: u64, tx: u32): RuneId {
+        RuneId { block, tx }
+    }
+
+    public fun rune_id_block(self: &RuneId): u64 {
+        self.block
+    }
+
+    public fun rune_id_tx(self: &RuneId): u32 {
+        self.tx
+    }
+
+    // ==================== Artifact ====================
+
+    public fun artifact_is_cenotaph(self: &Artifact): bool {
+        self.cenotaph
+    }
+
+    public fun artifact_flaw(self: &Artifact): &Option<String> {
+        &self.flaw
+    }
+
+    public fun artifact_edicts(self: &Artifact): &vector<Edict> {
+        &self.edicts
+    }
+
+    public fun artifact_etching(self: &Artifact): &Option<Etching> {
+        &self.etching
+    }
+
+    public fun artifact_mint(self: &Artifact): &Option<RuneId> {
+        &self.mint
+    }
+
+    public fun artifact_pointer(self: &Artifact): &Option<u32> {
+        &self.pointer
+    }
+
+    /// The rune name explicitly etched by the artifact, the etching without rune name is assigned a reserved rune.
+    public fun artifact_etching_rune(self: &Artifact): Option<u128> {
+        if (option::is_none(&self.etching)) {
+            return option::none()
+        };
+        option::borrow(&self.etching).rune
+    }
+
+    public fun unpack_edict(self: &Edict): (RuneId, u128, u32) {
+        (self.id, self.amount, self.output)
+    }
+
+    // ==================== Rune ====================
+
+    public fun commit_confirmations(): u64 {
+        COMMIT_CONFIRMATIONS
+    }
+
+    public fun is_reserved(rune: u128): bool {
+        rune >= RESERVED
+    }
+
+    public fun reserved(block: u64, tx: u32): u128 {
+        RESERVED + (((block as u128) << 32) | (tx as u128))
+    }
+
+    /// The commitment of the rune which should be pushed in the tapscript of the etching transaction input,
+    /// it is the little endian bytes of the rune with the trailing zeros removed.
+    public fun commitment(rune: u128): vector<u8> {
+        let bytes = vector::empty<u8>();
+        while (rune > 0) {
+            vector::push_back(&mut bytes, ((rune & 0xff) as u8));
+            rune = rune >> 8;
+        };
+        bytes
+    }
+
+    /// The minimum rune name can be etched at the height.
+    /// The names are unlocked gradually, one letter shorter every `subsidy_halving_interval / 12` blocks after the activation height.
+    public fun minimum_at_height(height: u64): u128 {
+        let offset = height + 1;
+        let halving_interval = network::subsidy_halving_interval();
+        let unlock_interval = halving_interval / 12;
+        let start = network::first_rune_height();
+        let end = start + halving_interval;
+        if (offset < start) {
+            return *vector::borrow(&STEPS, 12)
+        };
+        if (offset >= end) {
+            return 0
+        };
+        let progress = offset - start;
+        let length = 12 - progress / unlock_interval;
+        let end = *vector::borrow(&STEPS, length - 1);
+        let start = *vector::borrow(&STEPS, length);
+        let remainder = ((progress % unlock_interval) as u128);
+        start - ((start - end) * remainder / (unlock_interval as u128))
+    }
+
+    // ==================== RuneEntry ====================
+
+    public fun derive_rune_entry_id(id: RuneId): ObjectID {
+        object::custom_object_id_with_parent<RuneId, RuneEntry>(object::named_object_id<RuneStore>(), id)
+    }
+
+    public fun exists_rune_entry(id: RuneId): bool {
+        object::exists_object_with_type<RuneEntry>(derive_rune_entry_id(id))
+    }
+
+    public fun borrow_rune_entry(id: RuneId): &RuneEntry {
+        let entry_id = derive_rune_entry_id(id);
+        assert!(object::exists_object_with_type<RuneEntry>(entry_id), ErrorRuneEntryNotExists);
+        object::borrow(object::borrow_object<RuneEntry>(entry_id))
+    }
+
+    /// Get the RuneId of the etched rune
+    public fun rune_id(rune: u128): Option<RuneId> {
+        let store_id = object::named_object_id<RuneStore>();
+        if (!object::exists_object(store_id)) {
+            return option::none()
+        };
+        let store_obj = object::borrow_object<RuneStore>(store_id);
+        if (object::contains_field(store_obj, rune)) {
+            option::some(*object::borrow_field<RuneStore, u128, RuneId>(store_obj, rune))
+        } else {
+            option::none()
+        }
+    }
+
+    public fun entry_rune(self: &RuneEntry): u128 {
+        self.rune
+    }
+
+    public fun entry_spacers(self: &RuneEntry): u32 {
+        self.spacers
+    }
+
+    public fun entry_divisibility(self: &RuneEntry): u8 {
+        self.divisibility
+    }
+
+    public fun entry_symbol(self: &RuneEntry): Option<u32> {
+        self.symbol
+    }
+
+    public fun entry_premine(self: &RuneEntry): u128 {
+        self.premine
+    }
+
+    public fun entry_mints(self: &RuneEntry): u128 {
+        self.mints
+    }
+
+    public fun entry_burned(self: &RuneEntry): u128 {
+        self.burned
+    }
+
+    public fun entry_number(self: &RuneEntry): u64 {
+        self.number
+    }
+
+    /// The first height the rune can be minted
+    public fun entry_start(self: &RuneEntry): Option<u64> {
+        if (option::is_none(&self.terms)) {
+            return option::none()
+        };
+        let terms = option::borrow(&self.terms);
+        let relative = if (option::is_some(&terms.offset_start)) {
+            option::some(self.id.block + *option::borrow(&terms.offset_start))
+        } else {
+            option::none()
+        };
+        max_option(relative, terms.height_start)
+    }
+
+    /// The height the rune mint ends, exclusive
+    public fun entry_end(self: &RuneEntry): Option<u64> {
+        if (option::is_none(&self.terms)) {
+            return option::none()
+        };
+        let terms = option::borrow(&self.terms);
+        let relative = if (option::is_some(&terms.offset_end)) {
+            option::some(self.id.block + *option::borrow(&terms.offset_end))
+        } else {
+            option::none()
+        };
+        min_option(relative, terms.height_end)
+    }
+
+    /// The mint amount if the rune is mintable at the height
+    public fun entry_mintable(self: &RuneEntry, height: u64): Option<u128> {
+        if (option::is_none(&self.terms)) {
+            return option::none()
+        };
+        let start = entry_start(self);
+        if (option::is_some(&start) && height < *option::borrow(&start)) {
+            return option::none()
+        };
+        let end = entry_end(self);
+        if (option::is_some(&end) && height >= *option::borrow(&end)) {
+            return option::none()
+        };
+        let terms = option::borrow(&self.terms);
+        let cap = option::get_with_default(&terms.cap, 0);
+        if (self.mints >= cap) {
+            return option::none()
+        };
+        option::some(option::get_with_default(&terms.amount, 0))
+    }
+
+    fun max_option(a: Option<u64>, b: Option<u64>): Option<u64> {
+        if (option::is_some(&a) && option::is_some(&b)) {
+            let a = option::destroy_some(a);
+            let b = option::destroy_some(b);
+            option::some(if (a > b) { a } else { b })
+        } else if (option::is_some(&a)) {
+            a
+        } else {
+            b
+        }
+    }
+
+    fun min_option(a: Option<u64>, b: Option<u64>): Option<u64> {
+        if (option::is_some(&a) && option::is_some(&b)) {
+            let a = option::destroy_some(a);
+            let b = option::destroy_some(b);
+            option::some(if (a < b) { a } else { b })
+        } else if (option::is_some(&a)) {
+            a
+        } else {
+            b
+        }
+    }
+
+    // ==================== RuneBalances ====================
+
+    public fun derive_rune_balances_id(outpoint: OutPoint): ObjectID {
+        object::custom_object_id_with_parent<OutPoint, RuneBalances>(object::named_object_id<RuneStore>(), outpoint)
+    }
+
+    /// Get the rune balances of the UTXO, return an empty map if the UTXO does not hold any rune.
+    public fun balances(outpoint: OutPoint): SimpleMap<RuneId, u128> {
+        let balances_id = derive_rune_balances_id(outpoint);
+        if (object::exists_object_with_type<RuneBalances>(balances_id)) {
+            object::borrow(object::borrow_object<RuneBalances>(balances_id)).balances
+        } else {
+            simple_map::new()
+        }
+    }
+
+    public fun balances_outpoint(self: &RuneBalances): OutPoint {
+        self.outpoint
+    }
+
+    public fun balances_map(self: &RuneBalances): &SimpleMap<RuneId, u128> {
+        &self.balances
+    }
+
+    // ==================== Process ====================
+
+    /// Process the runestone and the rune balances of the transaction inputs,
+    /// return the seals of the new RuneBalances objects for the transaction outputs.
+    /// `tx_index` is the index of the transaction in the block, used as the RuneId of the etched rune.
+    /// `commits_to_rune` indicates whether the transaction has a valid commitment to the explicitly etched rune,
+    /// the caller should check the commit output is a taproot output and has enough confirmations.
+    public(friend) fun process_tx(
+        block_height: u64,
+        tx_index: u32,
+        tx: &Transaction,
+        artifact: Option<Artifact>,
+        commits_to_rune: bool,
+        input_utxos: &mut vector<UTXO>
+    ): vector<SealOut> {
+        let seal_outs = vector::empty();
+        let unallocated = take_input_balances(input_utxos);
+        if (option::is_none(&artifact) && simple_map::length(&unallocated) == 0) {
+            return seal_outs
+        };
+
+        let txid = types::tx_id(tx);
+        let txoutput = types::tx_output(tx);
+        let output_len = vector::length(txoutput);
+        let allocated = vector::empty<SimpleMap<RuneId, u128>>();
+        let idx = 0;
+        while (idx < output_len) {
+            vector::push_back(&mut allocated, simple_map::new());
+            idx = idx + 1;
+        };
+        let burned = simple_map::new<RuneId, u128>();
+
+        let is_cenotaph = false;
+        let pointer = option::none<u32>();
+        if (option::is_some(&artifact)) {
+            let artifact = option::borrow(&artifact);
+            is_cenotaph = artifact.cenotaph;
+            pointer = artifact.pointer;
+
+            if (option::is_some(&artifact.mint)) {
+                let id = *option::borrow(&artifact.mint);
+                let amount = mint(id, block_height);
+                if (option::is_some(&amount)) {
+                    add_balance(&mut unallocated, id, option::destroy_some(amount));
+                };
+            };
+
+            let etched = etched(block_height, tx_index, artifact, commits_to_rune);
+            let etched_id = new_rune_id(block_height, tx_index);
+
+            if (!artifact.cenotaph) {
+                if (option::is_some(&etched)) {
+                    let etching = option::borrow(&artifact.etching);
+                    add_balance(&mut unallocated, etched_id, option::get_with_default(&etching.premine, 0));
+                };
+                allocate_edicts(txoutput, &artifact.edicts, option::is_some(&etched), etched_id, &mut unallocated, &mut allocated);
+            };
+
+            if (option::is_some(&etched)) {
+                create_rune_entry(txid, artifact, etched_id, option::destroy_some(etched));
+            };
+        };
+
+        let (ids, amounts) = simple_map::to_vec_pair(unallocated);
+        if (is_cenotaph) {
+            //The cenotaph burns all the input runes
+            add_balances(&mut burned, ids, amounts);
+        } else {
+            //Assign all un-allocated runes to the pointer output, or the first non OP_RETURN output if there is no pointer
+            let vout = if (option::is_some(&pointer)) {
+                option::some((option::destroy_some(pointer) as u64))
+            } else {
+                first_non_op_return_output(txoutput)
+            };
+            if (option::is_some(&vout)) {
+                add_balances(vector::borrow_mut(&mut allocated, option::destroy_some(vout)), ids, amounts);
+            } else {
+                add_balances(&mut burned, ids, amounts);
+            };
+        };
+
+        let seal_protocol = type_info::type_name<RuneBalances>();
+        let vout = 0;
+        vector::reverse(&mut allocated);
+        while (vout < output_len) {
+            let balances = vector::pop_back(&mut allocated);
+            if (simple_map::length(&balances) > 0) {
+                let txout = vector::borrow(txoutput, vout);
+                if (script_buf::is_op_return(types::txout_script_pubkey(txout))) {
+                    //The runes sent to the OP_RETURN output are burned
+                    let (ids, amounts) = simple_map::to_vec_pair(balances);
+                    add_balances(&mut burned, ids, amounts);
+                } else {
+                    let outpoint = types::new_outpoint(txid, (vout as u32));
+                    let balances_obj_id = create_rune_balances(outpoint, balances, types::txout_object_address(txout));
+                    let seal = utxo::new_utxo_seal(seal_protocol, balances_obj_id);
+                    vector::push_back(&mut seal_outs, utxo::new_seal_out((vout as u32), seal));
+                };
+            };
+            vout = vout + 1;
+        };
+
+        let (ids, amounts) = simple_map::to_vec_pair(burned);
+        while (!vector::is_empty(&ids)) {
+            let id = vector::pop_back(&mut ids);
+            let amount = vector::pop_back(&mut amounts);
+            let entry_id = derive_rune_entry_id(id);
+            if (amount > 0 && object::exists_object_with_type<RuneEntry>(entry_id)) {
+                let entry = object::borrow_mut(object::borrow_mut_object_extend<RuneEntry>(entry_id));
+                entry.burned = entry.burned + amount;
+            };
+        };
+        seal_outs
+    }
+
+    /// Take the RuneBalances sealed to the input UTXOs, and merge them to the unallocated balances
+    fun take_input_balances(input_utxos: &mut vector<UTXO>): SimpleMap<RuneId, u128> {
+        let unallocated = simple_map::new<RuneId, u128>();
+        let len = vector::length(input_utxos);
+        let idx = 0;
+        while (idx < len) {
+            let input_utxo = vector::borrow_mut(input_utxos, idx);
+            if (utxo::has_seal<RuneBalances>(input_utxo)) {
+                let seals = utxo::remove_seals_internal<RuneBalances>(input_utxo);
+                while (!vector::is_empty(&seals)) {
+                    let balances_obj_id = vector::pop_back(&mut seals);
+                    if (object::exists_object_with_type<RuneBalances>(balances_obj_id)) {
+                        let balances_obj = object::take_object_extend<RuneBalances>(balances_obj_id);
+                        let RuneBalances { outpoint: _, balances } = object::remove(balances_obj);
+                        let (ids, amounts) = simple_map::to_vec_pair(balances);
+                        add_balances(&mut unallocated, ids, amounts);
+                    };
+                };
+            };
+            idx = idx + 1;
+        };
+        unallocated
+    }
+
+    fun allocate_edicts(
+        txoutput: &vector<types::TxOut>,
+        edicts: &vector<Edict>,
+        has_etched: bool,
+        etched_id: RuneId,
+        unallocated: &mut SimpleMap<RuneId, u128>,
+        allocated: &mut vector<SimpleMap<RuneId, u128>>
+    ) {
+        let output_len = vector::length(txoutput);
+        let edicts_len = vector::length(edicts);
+        let idx = 0;
+        while (idx < edicts_len) {
+            let edict = vector::borrow(edicts, idx);
+            idx = idx + 1;
+            let id = edict.id;
+            //The RuneId 0:0 refers to the rune etched in this transaction
+            if (id.block == 0 && id.tx == 0) {
+                if (!has_etched) {
+                    continue
+                };
+                id = etched_id;
+            };
+            if (!simple_map::contains_key(unallocated, &id)) {
+                continue
+            };
+            let output = (edict.output as u64);
+            if (output == output_len) {
+                //The edict output equals the number of outputs, allocate the amount to all non OP_RETURN outputs
+                let destinations = non_op_return_outputs(txoutput);
+                let destinations_len = vector::length(&destinations);
+                if (destinations_len == 0) {
+                    continue
+                };
+                if (edict.amount == 0) {
+                    //Divide the balance equally between the outputs
+                    let balance = *simple_map::borrow(unallocated, &id);
+                    let amount = balance / (destinations_len as u128);
+                    let remainder = balance % (destinations_len as u128);
+                    let i = 0;
+                    while (i < destinations_len) {
+                        let output_amount = if ((i as u128) < remainder) { amount + 1 } else { amount };
+                        allocate(unallocated, allocated, id, output_amount, *vector::borrow(&destinations, i));
+                        i = i + 1;
+                    };
+                } else {
+                    let i = 0;
+                    while (i < destinations_len) {
+                        let balance = *simple_map::borrow(unallocated, &id);
+                        let output_amount = if (edict.amount < balance) { edict.amount } else { balance };
+                        allocate(unallocated, allocated, id, output_amount, *vector::borrow(&destinations, i));
+                        i = i + 1;
+                    };
+                };
+            } else {
+                let balance = *simple_map::borrow(unallocated, &id);
+                let amount = if (edict.amount == 0 || edict.amount > balance) { balance } else { edict.amount };
+                allocate(unallocated, allocated, id, amount, output);
+            };
+        };
+    }
+
+    fun allocate(unallocated: &mut SimpleMap<RuneId, u128>, allocated: &mut vector<SimpleMap<RuneId, u128>>, id: RuneId, amount: u128, output: u64) {
+        if (amount > 0) {
+            let balance = simple_map::borrow_mut(unallocated, &id);
+            *balance = *balance - amount;
+            add_balance(vector::borrow_mut(allocated, output), id, amount);
+        };
+    }
+
+    fun add_balance(balances: &mut SimpleMap<RuneId, u128>, id: RuneId, amount: u128) {
+        if (amount == 0) {
+            return
+        };
+        if (simple_map::contains_key(balances, &id)) {
+            let balance = simple_map::borrow_mut(balances, &id);
+            *balance = *balance + amount;
+        } else {
+            simple_map::add(balances, id, amount);
+        };
+    }
+
+    fun add_balances(balances: &mut SimpleMap<RuneId, u128>, ids: vector<RuneId>, amounts: vector<u128>) {
+        while (!vector::is_empty(&ids)) {
+            add_balance(balances, vector::pop_back(&mut ids), vector::pop_back(&mut amounts));
+        };
+    }
+
+    fun non_op_return_outputs(txoutput: &vector<types::TxOut>): vector<u64> {
+        let outputs = vector::empty();
+        let len = vector::length(txoutput);
+        let idx = 0;
+        while (idx < len) {
+            if (!script_buf::is_op_return(types::txout_script_pubkey(vector::borrow(txoutput, idx)))) {
+                vector::push_back(&mut outputs, idx);
+            };
+            idx = idx + 1;
+        };
+        outputs
+    }
+
+    fun first_non_op_return_output(txoutput: &vector<types::TxOut>): Option<u64> {
+        let len = vector::length(txoutput);
+        let idx = 0;
+        while (idx < len) {
+            if (!script_buf::is_op_return(types::txout_script_pubkey(vector::borrow(txoutput, idx)))) {
+                return option::some(idx)
+            };
+            idx = idx + 1;
+        };
+        option::none()
+    }
+
+    fun mint(id: RuneId, block_height: u64): Option<u128> {
+        let entry_id = derive_rune_entry_id(id);
+        if (!object::exists_object_with_type<RuneEntry>(entry_id)) {
+            return option::none()
+        };
+        let entry = object::borrow_mut(object::borrow_mut_object_extend<RuneEntry>(entry_id));
+        let amount = entry_mintable(entry, block_height);
+        if (option::is_some(&amount)) {
+            entry.mints = entry.mints + 1;
+        };
+        amount
+    }
+
+    /// Return the rune etched by the artifact, or none if the etching is invalid
+    fun etched(block_height: u64, tx_index: u32, artifact: &Artifact, commits_to_rune: bool): Option<u128> {
+        if (option::is_none(&artifact.etching)) {
+            return option::none()
+        };
+        let etching = option::borrow(&artifact.etching);
+        if (option::is_none(&etching.rune)) {
+            return option::some(reserved(block_height, tx_index))
+        };
+        let rune = *option::borrow(&etching.rune);
+        if (rune < minimum_at_height(block_height)
+            || is_reserved(rune)
+            || option::is_some(&rune_id(rune))
+            || !commits_to_rune) {
+            return option::none()
+        };
+        option::some(rune)
+    }
+
+    fun create_rune_entry(txid: address, artifact: &Artifact, id: RuneId, rune: u128) {
+        let store_obj = borrow_mut_rune_store();
+        let store = object::borrow_mut(store_obj);
+        let number = store.runes;
+        store.runes = number + 1;
+        let entry = if (artifact.cenotaph) {
+            RuneEntry {
+                id,
+                rune,
+                spacers: 0,
+                divisibility: 0,
+                symbol: option::none(),
+                premine: 0,
+                terms: option::none(),
+                mints: 0,
+                burned: 0,
+                etching: txid,
+                number,
+                turbo: false,
+            }
+        } else {
+            let etching = option::borrow(&artifact.etching);
+            RuneEntry {
+                id,
+                rune,
+                spacers: option::get_with_default(&etching.spacers, 0),
+                divisibility: option::get_with_default(&etching.divisibility, 0),
+                symbol: etching.symbol,
+                premine: option::get_with_default(&etching.premine, 0),
+                terms: etching.terms,
+                mints: 0,
+                burned: 0,
+                etching: txid,
+                number,
+                turbo: etching.turbo,
+            }
+        };
+        object::add_field(store_obj, rune, id);
+        let entry_obj = object::new_with_parent_and_id(store_obj, id, entry);
+        object::transfer_extend(entry_obj, @bitcoin_move);
+    }
+
+    fun create_rune_balances(outpoint: OutPoint, balances: SimpleMap<RuneId, u128>, owner: address): ObjectID {
+        let balances_obj_id = derive_rune_balances_id(outpoint);
+        //The coinbase txid may be reused before BIP34, remove the old balances
+        if (object::exists_object_with_type<RuneBalances>(balances_obj_id)) {
+            let old_balances_obj = object::take_object_extend<RuneBalances>(balances_obj_id);
+            let RuneBalances { outpoint: _, balances: _ } = object::remove(old_balances_obj);
+        };
+        let store_obj = borrow_mut_rune_store();
+        let balances_obj = object::new_with_parent_and_id(store_obj, outpoint, RuneBalances { outpoint, balances });
+        object::transfer_extend(balances_obj, owner);
+        balances_obj_id
+    }
+
+    #[test]
+    fun test_commitment() {
+        assert!(commitment(0) == vector[], 1);
+        assert!(commitment(1) == x"01", 2);
+        assert!(commitment(255) == x"ff", 3);
+        assert!(commitment(256) == x"0001", 4);
+        assert!(commitment(65535) == x"ffff", 5);
+        assert!(commitment(65536) == x"000001", 6);
+    }
+
+    #[test]
+    fun test_reserved() {
+        assert!(!is_reserved(RESERVED - 1), 1);
+        assert!(is_reserved(RESERVED), 2);
+        assert!(reserved(0, 0) == RESERVED, 3);
+        assert!(reserved(1, 0) == RESERVED + (1 << 32), 4);
+        assert!(reserved(1, 2) == RESERVED + (1 << 32) + 2, 5);
+    }
+
+    #[test]
+    fun test_entry_mintable() {
+        let entry = RuneEntry {
+            id: new_rune_id(100, 1),
+            rune: 0,
+            spacers: 0,
+            divisibility: 0,
+            symbol: option::none(),
+            premine: 0,
+            terms: option::some(Terms {
+                amount: option::some(1000),
+                cap: option::some(2),
+                height_start: option::some(105),
+                height_end: option::none(),
+                offset_start: option::some(10),
+                offset_end: option::some(20),
+            }),
+            mints: 0,
+            burned: 0,
+            etching: @0x1,
+            number: 0,
+            turbo: false,
+        };
+        assert!(entry_start(&entry) == option::some(110), 1);
+        assert!(entry_end(&entry) == option::some(120), 2);
+        assert!(option::is_none(&entry_mintable(&entry, 109)), 3);
+        assert!(entry_mintable(&entry, 110) == option::some(1000), 4);
+        assert!(option::is_none(&entry_mintable(&entry, 120)), 5);
+        entry.mints = 2;
+        assert!(option::is_none(&entry_mintable(&entry, 110)), 6);
+        let RuneEntry { id: _, rune: _, spacers: _, divisibility: _, symbol: _, premine: _, terms: _, mints: _, burned: _, etching: _, number: _, turbo: _ } = entry;
+    }
+}
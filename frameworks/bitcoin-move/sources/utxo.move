@@ -20,6 +20,7 @@ module bitcoin_move::utxo{
     friend bitcoin_move::bitcoin;
     friend bitcoin_move::inscription_updater;
     friend bitcoin_move::bbn;
+    friend bitcoin_move::runes;
 
     const TEMPORARY_AREA: vector<u8> = b"temporary_area";
 
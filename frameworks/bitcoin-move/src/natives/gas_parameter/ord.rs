@@ -7,4 +7,6 @@ use kanari_framework::natives::gas_parameter::native::MUL;
 kanari_framework::natives::gas_parameter::native::define_gas_parameters_for_natives!(GasParameters, "ord", [
     [.from_witness.base, "from_witness.base", 10000 * MUL],
     [.from_witness.per_byte, "from_witness.per_byte", 50 * MUL],
+    [.from_witness_with_delegates.base, optional "from_witness_with_delegates.base", 10000 * MUL],
+    [.from_witness_with_delegates.per_byte, optional "from_witness_with_delegates.per_byte", 50 * MUL],
 ]);
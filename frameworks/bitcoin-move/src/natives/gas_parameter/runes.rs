@@ -0,0 +1,12 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::runes::GasParameters;
+use kanari_framework::natives::gas_parameter::native::MUL;
+
+kanari_framework::natives::gas_parameter::native::define_gas_parameters_for_natives!(GasParameters, "runes", [
+    [.decipher_runestone.base, optional "decipher_runestone.base", 10000 * MUL],
+    [.decipher_runestone.per_byte, optional "decipher_runestone.per_byte", 50 * MUL],
+    [.tapscript_contains_commitment.base, optional "tapscript_contains_commitment.base", 10000 * MUL],
+    [.tapscript_contains_commitment.per_byte, optional "tapscript_contains_commitment.per_byte", 50 * MUL],
+]);
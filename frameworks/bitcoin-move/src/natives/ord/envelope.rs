@@ -23,6 +23,7 @@ pub(crate) const PARENT_TAG: [u8; 1] = [3];
 pub(crate) const METADATA_TAG: [u8; 1] = [5];
 pub(crate) const METAPROTOCOL_TAG: [u8; 1] = [7];
 pub(crate) const CONTENT_ENCODING_TAG: [u8; 1] = [9];
+pub(crate) const DELEGATE_TAG: [u8; 1] = [11];
 pub(crate) const RUNE_TAG: [u8; 1] = [13];
 
 type Result<T> = std::result::Result<T, script::Error>;
@@ -99,6 +100,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
 
         let content_encoding = remove_field(&mut fields, &CONTENT_ENCODING_TAG);
         let content_type = remove_field(&mut fields, &CONTENT_TYPE_TAG);
+        let delegate = remove_field(&mut fields, &DELEGATE_TAG);
         let metadata = remove_and_concatenate_field(&mut fields, &METADATA_TAG);
         let metaprotocol = remove_field(&mut fields, &METAPROTOCOL_TAG);
         let parents = remove_array_field(&mut fields, &PARENT_TAG);
@@ -120,6 +122,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
                 }),
                 content_encoding,
                 content_type,
+                delegate,
                 duplicate_field,
                 incomplete_field,
                 metadata,
@@ -798,7 +801,7 @@ mod tests {
     #[test]
     fn unknown_odd_fields_are_ignored() {
         assert_eq!(
-            parse(&[envelope(&[b"ord", &[11], &[0]])]),
+            parse(&[envelope(&[b"ord", &[255], &[0]])]),
             vec![ParsedEnvelope {
                 payload: Inscription::default(),
                 ..Default::default()
@@ -893,6 +896,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn delegate_field_is_recognized() {
+        let delegate = [[1; 32].as_slice(), &[1]].concat();
+        assert_eq!(
+            parse(&[envelope(&[b"ord", &[11], &delegate])]),
+            vec![ParsedEnvelope {
+                payload: Inscription {
+                    delegate: Some(delegate),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn delegate_round_trips_through_reveal_script() {
+        let inscription = Inscription {
+            delegate: Some(vec![1; 32]),
+            ..Default::default()
+        };
+
+        let mut witness = Witness::new();
+        witness.push(inscription.append_reveal_script(script::Builder::new()));
+        witness.push([]);
+
+        assert_eq!(
+            parse(&[witness]),
+            vec![ParsedEnvelope {
+                payload: inscription,
+                ..Default::default()
+            }],
+        );
+    }
+
     #[test]
     fn pushnum_opcodes_are_parsed_correctly() {
         const PUSHNUMS: &[(opcodes::Opcode, u8)] = &[
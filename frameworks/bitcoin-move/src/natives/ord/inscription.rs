@@ -13,6 +13,7 @@ use {
     super::inscription_id::InscriptionId,
     super::media::Media,
     axum::http::HeaderValue,
+    ciborium::Value,
     bitcoin::{
         blockdata::{
             opcodes,
@@ -21,7 +22,7 @@ use {
         ScriptBuf,
     },
     serde::{Deserialize, Serialize},
-    std::str,
+    std::{io::Cursor, str},
 };
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
@@ -29,6 +30,7 @@ pub(crate) struct Inscription {
     pub body: Option<Vec<u8>>,
     pub content_encoding: Option<Vec<u8>>,
     pub content_type: Option<Vec<u8>>,
+    pub delegate: Option<Vec<u8>>,
     pub duplicate_field: bool,
     pub incomplete_field: bool,
     pub metadata: Option<Vec<u8>>,
@@ -69,6 +71,8 @@ impl From<Inscription> for kanari_types::bitcoin::ord::InscriptionRecord {
             .into();
         let parents = val.parents().into_iter().map(InscriptionID::from).collect();
         let pointer = val.pointer().into();
+        let rune = val.rune();
+        let delegate = val.delegate().map(InscriptionID::from);
         kanari_types::bitcoin::ord::InscriptionRecord {
             body: val.body.unwrap_or_default(),
             content_encoding,
@@ -80,7 +84,8 @@ impl From<Inscription> for kanari_types::bitcoin::ord::InscriptionRecord {
             parents,
             pointer,
             unrecognized_even_field: val.unrecognized_even_field,
-            rune: None,
+            rune,
+            delegate,
         }
     }
 }
@@ -118,8 +123,10 @@ impl Inscription {
         Tag::ContentEncoding.append(&mut builder, &self.content_encoding);
         Tag::Metaprotocol.append(&mut builder, &self.metaprotocol);
         Tag::Parent.append_array(&mut builder, &self.parents);
+        Tag::Delegate.append(&mut builder, &self.delegate);
         Tag::Pointer.append(&mut builder, &self.pointer);
         Tag::Metadata.append(&mut builder, &self.metadata);
+        Tag::Rune.append(&mut builder, &self.rune);
 
         if let Some(body) = &self.body {
             builder = builder.push_slice(envelope::BODY_TAG);
@@ -186,8 +193,8 @@ impl Inscription {
             .ok()
     }
 
-    pub(crate) fn metadata(&self) -> Option<&Vec<u8>> {
-        self.metadata.as_ref()
+    pub(crate) fn metadata(&self) -> Option<Value> {
+        ciborium::from_reader(Cursor::new(self.metadata.as_ref()?)).ok()
     }
 
     pub(crate) fn metaprotocol(&self) -> Option<&str> {
@@ -201,6 +208,10 @@ impl Inscription {
             .collect()
     }
 
+    pub(crate) fn delegate(&self) -> Option<InscriptionId> {
+        Self::inscription_id_field(self.delegate.as_deref())
+    }
+
     fn inscription_id_field(field: Option<&[u8]>) -> Option<InscriptionId> {
         let value = field.as_ref()?;
 
@@ -257,6 +268,19 @@ impl Inscription {
         Some(u64::from_le_bytes(pointer))
     }
 
+    pub(crate) fn rune(&self) -> Option<u128> {
+        let value = self.rune.as_ref()?;
+
+        if value.len() > 16 {
+            return None;
+        }
+
+        let mut bytes = [0; 16];
+        bytes[..value.len()].copy_from_slice(value);
+
+        Some(u128::from_le_bytes(bytes))
+    }
+
     pub(crate) fn to_witness(&self) -> Witness {
         let builder = script::Builder::new();
 
@@ -595,6 +619,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inscription_delegate_is_deserialized_correctly() {
+        assert_eq!(
+            Inscription {
+                delegate: Some(vec![1; 33]),
+                ..Default::default()
+            }
+            .delegate(),
+            Some(
+                "0101010101010101010101010101010101010101010101010101010101010101i1"
+                    .parse()
+                    .unwrap()
+            ),
+        );
+    }
+
+    #[test]
+    fn inscription_with_invalid_delegate_field_has_no_delegate() {
+        assert_eq!(
+            Inscription {
+                delegate: Some(vec![1; 31]),
+                ..Default::default()
+            }
+            .delegate(),
+            None,
+        );
+    }
+
+    #[test]
+    fn rune_decode() {
+        assert_eq!(
+            Inscription {
+                rune: Some(vec![1, 2]),
+                ..Default::default()
+            }
+            .rune(),
+            Some(0x0201),
+        );
+
+        assert_eq!(
+            Inscription {
+                rune: Some(vec![1; 17]),
+                ..Default::default()
+            }
+            .rune(),
+            None,
+        );
+    }
+
     #[test]
     fn metadata_function_decodes_metadata() {
         assert_eq!(
@@ -604,7 +677,7 @@ mod tests {
             }
             .metadata()
             .unwrap(),
-            &vec![0x44, 0, 1, 2, 3],
+            Value::Bytes(vec![0, 1, 2, 3]),
         );
     }
 
@@ -627,9 +700,8 @@ mod tests {
                 metadata: Some(vec![0x44]),
                 ..Default::default()
             }
-            .metadata()
-            .unwrap(),
-            &vec![0x44],
+            .metadata(),
+            None,
         );
     }
 
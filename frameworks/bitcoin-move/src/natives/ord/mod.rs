@@ -25,8 +25,9 @@ use move_vm_types::{
     values::{StructRef, Value, Vector},
 };
 use moveos_stdlib::natives::helpers::{make_module_natives, make_native};
+use moveos_types::move_std::option::MoveOption;
 use moveos_types::state::{MoveState, MoveType};
-use kanari_types::bitcoin::ord::{Envelope, InscriptionRecord};
+use kanari_types::bitcoin::ord::{Envelope, InscriptionID, InscriptionRecord};
 use kanari_types::bitcoin::types::Witness;
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
@@ -93,24 +94,101 @@ pub(crate) fn native_from_witness(
     Ok(NativeResult::ok(cost, smallvec![val]))
 }
 
+#[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct FromWitnessWithDelegatesGasParameters {
+    pub base: Option<InternalGas>,
+    pub per_byte: Option<InternalGasPerByte>,
+}
+
+impl FromWitnessWithDelegatesGasParameters {
+    pub fn zeros() -> Self {
+        Self {
+            base: Some(0.into()),
+            per_byte: Some(0.into()),
+        }
+    }
+}
+
+/// Rust implementation of parse Inscription from witness, and the delegate of every Inscription,
+/// the envelopes are parsed once and the delegates are in the same order as the envelopes.
+#[inline]
+pub(crate) fn native_from_witness_with_delegates(
+    gas_params: &FromWitnessWithDelegatesGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 0);
+    debug_assert_eq!(args.len(), 1);
+
+    let mut cost = gas_params.base.unwrap_or_else(InternalGas::zero);
+
+    let witness_ref = pop_arg!(args, StructRef);
+    let witness = Witness::from_runtime_value(witness_ref.read_ref()?).map_err(|e| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(format!("Failed to parse witness: {}", e))
+    })?;
+    cost += gas_params.per_byte.unwrap_or_else(InternalGasPerByte::zero)
+        * NumBytes::new(
+            witness
+                .witness
+                .iter()
+                .map(|inner_vec| inner_vec.len())
+                .sum::<usize>() as u64,
+        );
+    let bitcoin_witness = bitcoin::Witness::from_slice(witness.witness.as_slice());
+    let (delegates, inscriptions): (Vec<_>, Vec<_>) = from_witness(&bitcoin_witness)
+        .into_iter()
+        .map(|envelope| {
+            let delegate =
+                MoveOption::<InscriptionID>::from(envelope.payload.delegate().map(Into::into));
+            (
+                delegate.to_runtime_value(),
+                Into::<Envelope<InscriptionRecord>>::into(envelope).to_runtime_value(),
+            )
+        })
+        .unzip();
+    let inscription_vm_type = context
+        .load_type(&Envelope::<InscriptionRecord>::type_tag())
+        .map_err(|e| e.to_partial())?;
+    let delegate_vm_type = context
+        .load_type(&MoveOption::<InscriptionID>::type_tag())
+        .map_err(|e| e.to_partial())?;
+    let inscriptions = Vector::pack(&inscription_vm_type, inscriptions)?;
+    let delegates = Vector::pack(&delegate_vm_type, delegates)?;
+
+    Ok(NativeResult::ok(cost, smallvec![inscriptions, delegates]))
+}
+
 #[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
 pub struct GasParameters {
     pub from_witness: FromWitnessGasParameters,
+    pub from_witness_with_delegates: FromWitnessWithDelegatesGasParameters,
 }
 
 impl GasParameters {
     pub fn zeros() -> Self {
         Self {
             from_witness: FromWitnessGasParameters::zeros(),
+            from_witness_with_delegates: FromWitnessWithDelegatesGasParameters::zeros(),
         }
     }
 }
 
 pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
-    let natives = [(
-        "from_witness",
-        make_native(gas_params.from_witness, native_from_witness),
-    )]
+    let natives = [
+        (
+            "from_witness",
+            make_native(gas_params.from_witness, native_from_witness),
+        ),
+        (
+            "from_witness_with_delegates",
+            make_native(
+                gas_params.from_witness_with_delegates,
+                native_from_witness_with_delegates,
+            ),
+        ),
+    ]
     .to_vec();
 
     make_module_natives(natives)
@@ -0,0 +1,229 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+#[allow(dead_code)]
+pub mod runestone;
+
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
+use move_core_types::vm_status::StatusCode;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_runtime::native_functions::NativeFunction;
+use move_vm_types::{
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    pop_arg,
+    values::{StructRef, Value},
+};
+use moveos_stdlib::natives::helpers::{make_module_natives, make_native};
+use moveos_types::move_std::option::MoveOption;
+use moveos_types::state::MoveState;
+use kanari_types::bitcoin::runes::{Artifact, Edict, Etching, RuneId, Terms};
+use kanari_types::bitcoin::types::{Transaction, Witness};
+use serde::{Deserialize, Serialize};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct DecipherRunestoneGasParameters {
+    pub base: Option<InternalGas>,
+    pub per_byte: Option<InternalGasPerByte>,
+}
+
+impl DecipherRunestoneGasParameters {
+    pub fn zeros() -> Self {
+        Self {
+            base: Some(0.into()),
+            per_byte: Some(0.into()),
+        }
+    }
+}
+
+/// Rust implementation of decipher the Runestone from the transaction outputs
+#[inline]
+pub(crate) fn native_decipher_runestone(
+    gas_params: &DecipherRunestoneGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 0);
+    debug_assert_eq!(args.len(), 1);
+
+    let mut cost = gas_params.base.unwrap_or_else(InternalGas::zero);
+
+    let tx_ref = pop_arg!(args, StructRef);
+    let tx = Transaction::from_runtime_value(tx_ref.read_ref()?).map_err(|e| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(format!("Failed to parse transaction: {}", e))
+    })?;
+    let scripts = tx
+        .output
+        .iter()
+        .map(|output| bitcoin::Script::from_bytes(&output.script_pubkey.bytes))
+        .collect::<Vec<_>>();
+    cost += gas_params.per_byte.unwrap_or_else(InternalGasPerByte::zero)
+        * NumBytes::new(scripts.iter().map(|script| script.len()).sum::<usize>() as u64);
+
+    let artifact: MoveOption<Artifact> = runestone::decipher(&scripts).map(Artifact::from).into();
+
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![artifact.to_runtime_value()],
+    ))
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct TapscriptContainsGasParameters {
+    pub base: Option<InternalGas>,
+    pub per_byte: Option<InternalGasPerByte>,
+}
+
+impl TapscriptContainsGasParameters {
+    pub fn zeros() -> Self {
+        Self {
+            base: Some(0.into()),
+            per_byte: Some(0.into()),
+        }
+    }
+}
+
+/// Rust implementation of check the tapscript of the witness contains the data push of the rune commitment
+#[inline]
+pub(crate) fn native_tapscript_contains_commitment(
+    gas_params: &TapscriptContainsGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 0);
+    debug_assert_eq!(args.len(), 2);
+
+    let mut cost = gas_params.base.unwrap_or_else(InternalGas::zero);
+
+    let commitment = pop_arg!(args, Vec<u8>);
+    let witness_ref = pop_arg!(args, StructRef);
+    let witness = Witness::from_runtime_value(witness_ref.read_ref()?).map_err(|e| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(format!("Failed to parse witness: {}", e))
+    })?;
+    cost += gas_params.per_byte.unwrap_or_else(InternalGasPerByte::zero)
+        * NumBytes::new(
+            witness
+                .witness
+                .iter()
+                .map(|inner_vec| inner_vec.len())
+                .sum::<usize>() as u64,
+        );
+    let bitcoin_witness = bitcoin::Witness::from_slice(witness.witness.as_slice());
+    let contains = runestone::tapscript_contains_commitment(&bitcoin_witness, &commitment);
+
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(contains)]))
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct GasParameters {
+    pub decipher_runestone: DecipherRunestoneGasParameters,
+    pub tapscript_contains_commitment: TapscriptContainsGasParameters,
+}
+
+impl GasParameters {
+    pub fn zeros() -> Self {
+        Self {
+            decipher_runestone: DecipherRunestoneGasParameters::zeros(),
+            tapscript_contains_commitment: TapscriptContainsGasParameters::zeros(),
+        }
+    }
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [
+        (
+            "decipher_runestone",
+            make_native(gas_params.decipher_runestone, native_decipher_runestone),
+        ),
+        (
+            "tapscript_contains_commitment",
+            make_native(
+                gas_params.tapscript_contains_commitment,
+                native_tapscript_contains_commitment,
+            ),
+        ),
+    ]
+    .to_vec();
+
+    make_module_natives(natives)
+}
+
+impl From<runestone::RuneId> for RuneId {
+    fn from(id: runestone::RuneId) -> Self {
+        RuneId::new(id.block, id.tx)
+    }
+}
+
+impl From<runestone::Edict> for Edict {
+    fn from(edict: runestone::Edict) -> Self {
+        Edict {
+            id: edict.id.into(),
+            amount: edict.amount,
+            output: edict.output,
+        }
+    }
+}
+
+impl From<runestone::Terms> for Terms {
+    fn from(terms: runestone::Terms) -> Self {
+        Terms {
+            amount: terms.amount.into(),
+            cap: terms.cap.into(),
+            height_start: terms.height.0.into(),
+            height_end: terms.height.1.into(),
+            offset_start: terms.offset.0.into(),
+            offset_end: terms.offset.1.into(),
+        }
+    }
+}
+
+impl From<runestone::Etching> for Etching {
+    fn from(etching: runestone::Etching) -> Self {
+        Etching {
+            divisibility: etching.divisibility.into(),
+            premine: etching.premine.into(),
+            rune: etching.rune.into(),
+            spacers: etching.spacers.into(),
+            symbol: etching.symbol.map(u32::from).into(),
+            terms: etching.terms.map(Terms::from).into(),
+            turbo: etching.turbo,
+        }
+    }
+}
+
+impl From<runestone::Artifact> for Artifact {
+    fn from(artifact: runestone::Artifact) -> Self {
+        match artifact {
+            runestone::Artifact::Runestone(runestone) => Artifact {
+                cenotaph: false,
+                flaw: MoveOption::none(),
+                edicts: runestone.edicts.into_iter().map(Edict::from).collect(),
+                etching: runestone.etching.map(Etching::from).into(),
+                mint: runestone.mint.map(RuneId::from).into(),
+                pointer: runestone.pointer.into(),
+            },
+            // The cenotaph only keeps the etched rune, all the other etching fields are ignored
+            runestone::Artifact::Cenotaph(cenotaph) => Artifact {
+                cenotaph: true,
+                flaw: cenotaph.flaw.map(|flaw| flaw.as_str().into()).into(),
+                edicts: vec![],
+                etching: cenotaph
+                    .etching
+                    .map(|rune| Etching {
+                        rune: MoveOption::some(rune),
+                        ..Default::default()
+                    })
+                    .into(),
+                mint: cenotaph.mint.map(RuneId::from).into(),
+                pointer: MoveOption::none(),
+            },
+        }
+    }
+}
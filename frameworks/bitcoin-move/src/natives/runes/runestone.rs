@@ -0,0 +1,726 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+// Code from https://github.com/ordinals/ord/ (crates/ordinals)
+
+use bitcoin::blockdata::{opcodes, script::Instruction};
+use bitcoin::Script;
+use std::collections::{HashMap, VecDeque};
+
+pub(crate) const MAGIC_NUMBER: opcodes::Opcode = opcodes::all::OP_PUSHNUM_13;
+pub(crate) const MAX_DIVISIBILITY: u8 = 38;
+pub(crate) const MAX_SPACERS: u32 = 0b00000111_11111111_11111111_11111111;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Flaw {
+    EdictOutput,
+    EdictRuneId,
+    InvalidScript,
+    Opcode,
+    SupplyOverflow,
+    TrailingIntegers,
+    TruncatedField,
+    UnrecognizedEvenTag,
+    UnrecognizedFlag,
+    Varint,
+}
+
+impl Flaw {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Flaw::EdictOutput => "edict_output",
+            Flaw::EdictRuneId => "edict_rune_id",
+            Flaw::InvalidScript => "invalid_script",
+            Flaw::Opcode => "opcode",
+            Flaw::SupplyOverflow => "supply_overflow",
+            Flaw::TrailingIntegers => "trailing_integers",
+            Flaw::TruncatedField => "truncated_field",
+            Flaw::UnrecognizedEvenTag => "unrecognized_even_tag",
+            Flaw::UnrecognizedFlag => "unrecognized_flag",
+            Flaw::Varint => "varint",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Tag {
+    Body = 0,
+    Flags = 2,
+    Rune = 4,
+    Premine = 6,
+    Cap = 8,
+    Amount = 10,
+    HeightStart = 12,
+    HeightEnd = 14,
+    OffsetStart = 16,
+    OffsetEnd = 18,
+    Mint = 20,
+    Pointer = 22,
+    #[allow(dead_code)]
+    Cenotaph = 126,
+
+    Divisibility = 1,
+    Spacers = 3,
+    Symbol = 5,
+    #[allow(dead_code)]
+    Nop = 127,
+}
+
+impl Tag {
+    fn take<const N: usize, T>(
+        self,
+        fields: &mut HashMap<u128, VecDeque<u128>>,
+        with: impl Fn([u128; N]) -> Option<T>,
+    ) -> Option<T> {
+        let field = fields.get_mut(&(self as u128))?;
+
+        let mut values: [u128; N] = [0; N];
+
+        for (i, v) in values.iter_mut().enumerate() {
+            *v = *field.get(i)?;
+        }
+
+        let value = with(values)?;
+
+        field.drain(0..N);
+
+        if field.is_empty() {
+            fields.remove(&(self as u128));
+        }
+
+        Some(value)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Flag {
+    Etching = 0,
+    Terms = 1,
+    Turbo = 2,
+}
+
+impl Flag {
+    fn mask(self) -> u128 {
+        1 << (self as u128)
+    }
+
+    fn take(self, flags: &mut u128) -> bool {
+        let mask = self.mask();
+        let set = *flags & mask != 0;
+        *flags &= !mask;
+        set
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord, Hash)]
+pub(crate) struct RuneId {
+    pub block: u64,
+    pub tx: u32,
+}
+
+impl RuneId {
+    pub fn new(block: u64, tx: u32) -> Option<Self> {
+        if block == 0 && tx > 0 {
+            return None;
+        }
+        Some(Self { block, tx })
+    }
+
+    pub fn next(self, block: u128, tx: u128) -> Option<Self> {
+        Self::new(
+            self.block.checked_add(block.try_into().ok()?)?,
+            if block == 0 {
+                self.tx.checked_add(tx.try_into().ok()?)?
+            } else {
+                tx.try_into().ok()?
+            },
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct Edict {
+    pub id: RuneId,
+    pub amount: u128,
+    pub output: u32,
+}
+
+impl Edict {
+    fn from_integers(outputs: usize, id: RuneId, amount: u128, output: u128) -> Option<Self> {
+        let output = u32::try_from(output).ok()?;
+        // note that this allows `output == outputs`, which means to divide
+        // amount between all non-OP_RETURN outputs
+        if u64::from(output) > outputs as u64 {
+            return None;
+        }
+        Some(Self { id, amount, output })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub(crate) struct Terms {
+    pub amount: Option<u128>,
+    pub cap: Option<u128>,
+    pub height: (Option<u64>, Option<u64>),
+    pub offset: (Option<u64>, Option<u64>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub(crate) struct Etching {
+    pub divisibility: Option<u8>,
+    pub premine: Option<u128>,
+    pub rune: Option<u128>,
+    pub spacers: Option<u32>,
+    pub symbol: Option<char>,
+    pub terms: Option<Terms>,
+    pub turbo: bool,
+}
+
+impl Etching {
+    pub fn supply(&self) -> Option<u128> {
+        let premine = self.premine.unwrap_or_default();
+        let cap = self.terms.and_then(|terms| terms.cap).unwrap_or_default();
+        let amount = self
+            .terms
+            .and_then(|terms| terms.amount)
+            .unwrap_or_default();
+        premine.checked_add(cap.checked_mul(amount)?)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub(crate) struct Runestone {
+    pub edicts: Vec<Edict>,
+    pub etching: Option<Etching>,
+    pub mint: Option<RuneId>,
+    pub pointer: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub(crate) struct Cenotaph {
+    pub etching: Option<u128>,
+    pub flaw: Option<Flaw>,
+    pub mint: Option<RuneId>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum Artifact {
+    Cenotaph(Cenotaph),
+    Runestone(Runestone),
+}
+
+enum Payload {
+    Valid(Vec<u8>),
+    Invalid(Flaw),
+}
+
+struct Message {
+    flaw: Option<Flaw>,
+    edicts: Vec<Edict>,
+    fields: HashMap<u128, VecDeque<u128>>,
+}
+
+impl Message {
+    fn from_integers(outputs: usize, payload: &[u128]) -> Self {
+        let mut edicts = Vec::new();
+        let mut fields = HashMap::<u128, VecDeque<u128>>::new();
+        let mut flaw = None;
+
+        for i in (0..payload.len()).step_by(2) {
+            let tag = payload[i];
+
+            if Tag::Body as u128 == tag {
+                let mut id = RuneId::default();
+                for chunk in payload[i + 1..].chunks(4) {
+                    if chunk.len() != 4 {
+                        flaw.get_or_insert(Flaw::TrailingIntegers);
+                        break;
+                    }
+
+                    let Some(next) = id.next(chunk[0], chunk[1]) else {
+                        flaw.get_or_insert(Flaw::EdictRuneId);
+                        break;
+                    };
+
+                    let Some(edict) = Edict::from_integers(outputs, next, chunk[2], chunk[3])
+                    else {
+                        flaw.get_or_insert(Flaw::EdictOutput);
+                        break;
+                    };
+
+                    id = next;
+                    edicts.push(edict);
+                }
+                break;
+            }
+
+            let Some(&value) = payload.get(i + 1) else {
+                flaw.get_or_insert(Flaw::TruncatedField);
+                break;
+            };
+
+            fields.entry(tag).or_default().push_back(value);
+        }
+
+        Self {
+            flaw,
+            edicts,
+            fields,
+        }
+    }
+}
+
+pub(crate) mod varint {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Error {
+        Overlong,
+        Overflow,
+        Unterminated,
+    }
+
+    pub fn encode_to_vec(mut n: u128, v: &mut Vec<u8>) {
+        while n >> 7 > 0 {
+            v.push(n.to_le_bytes()[0] | 0b1000_0000);
+            n >>= 7;
+        }
+        v.push(n.to_le_bytes()[0]);
+    }
+
+    pub fn decode(buffer: &[u8]) -> Result<(u128, usize), Error> {
+        let mut n = 0u128;
+
+        for (i, &byte) in buffer.iter().enumerate() {
+            if i > 18 {
+                return Err(Error::Overlong);
+            }
+
+            let value = u128::from(byte) & 0b0111_1111;
+
+            if i == 18 && value & 0b0111_1100 != 0 {
+                return Err(Error::Overflow);
+            }
+
+            n |= value << (7 * i);
+
+            if byte & 0b1000_0000 == 0 {
+                return Ok((n, i + 1));
+            }
+        }
+
+        Err(Error::Unterminated)
+    }
+}
+
+/// Decipher the runestone from the output scripts of a transaction.
+/// Return `None` if there is no runestone output.
+pub(crate) fn decipher(outputs: &[&Script]) -> Option<Artifact> {
+    let payload = match payload(outputs) {
+        Some(Payload::Valid(payload)) => payload,
+        Some(Payload::Invalid(flaw)) => {
+            return Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(flaw),
+                ..Default::default()
+            }));
+        }
+        None => return None,
+    };
+
+    let Ok(integers) = integers(&payload) else {
+        return Some(Artifact::Cenotaph(Cenotaph {
+            flaw: Some(Flaw::Varint),
+            ..Default::default()
+        }));
+    };
+
+    let Message {
+        mut flaw,
+        edicts,
+        mut fields,
+    } = Message::from_integers(outputs.len(), &integers);
+
+    let mut flags = Tag::Flags
+        .take(&mut fields, |[flags]| Some(flags))
+        .unwrap_or_default();
+
+    let etching = Flag::Etching.take(&mut flags).then(|| Etching {
+        divisibility: Tag::Divisibility.take(&mut fields, |[divisibility]| {
+            let divisibility = u8::try_from(divisibility).ok()?;
+            (divisibility <= MAX_DIVISIBILITY).then_some(divisibility)
+        }),
+        premine: Tag::Premine.take(&mut fields, |[premine]| Some(premine)),
+        rune: Tag::Rune.take(&mut fields, |[rune]| Some(rune)),
+        spacers: Tag::Spacers.take(&mut fields, |[spacers]| {
+            let spacers = u32::try_from(spacers).ok()?;
+            (spacers <= MAX_SPACERS).then_some(spacers)
+        }),
+        symbol: Tag::Symbol.take(&mut fields, |[symbol]| {
+            char::from_u32(u32::try_from(symbol).ok()?)
+        }),
+        terms: Flag::Terms.take(&mut flags).then(|| Terms {
+            cap: Tag::Cap.take(&mut fields, |[cap]| Some(cap)),
+            height: (
+                Tag::HeightStart.take(&mut fields, |[start_height]| {
+                    u64::try_from(start_height).ok()
+                }),
+                Tag::HeightEnd.take(&mut fields, |[end_height]| u64::try_from(end_height).ok()),
+            ),
+            amount: Tag::Amount.take(&mut fields, |[amount]| Some(amount)),
+            offset: (
+                Tag::OffsetStart.take(&mut fields, |[start_offset]| {
+                    u64::try_from(start_offset).ok()
+                }),
+                Tag::OffsetEnd.take(&mut fields, |[end_offset]| u64::try_from(end_offset).ok()),
+            ),
+        }),
+        turbo: Flag::Turbo.take(&mut flags),
+    });
+
+    let mint = Tag::Mint.take(&mut fields, |[block, tx]| {
+        RuneId::new(block.try_into().ok()?, tx.try_into().ok()?)
+    });
+
+    let pointer = Tag::Pointer.take(&mut fields, |[pointer]| {
+        let pointer = u32::try_from(pointer).ok()?;
+        ((pointer as usize) < outputs.len()).then_some(pointer)
+    });
+
+    if etching
+        .map(|etching| etching.supply().is_none())
+        .unwrap_or_default()
+    {
+        flaw.get_or_insert(Flaw::SupplyOverflow);
+    }
+
+    if flags != 0 {
+        flaw.get_or_insert(Flaw::UnrecognizedFlag);
+    }
+
+    if fields.keys().any(|tag| tag % 2 == 0) {
+        flaw.get_or_insert(Flaw::UnrecognizedEvenTag);
+    }
+
+    if let Some(flaw) = flaw {
+        return Some(Artifact::Cenotaph(Cenotaph {
+            flaw: Some(flaw),
+            mint,
+            etching: etching.and_then(|etching| etching.rune),
+        }));
+    }
+
+    Some(Artifact::Runestone(Runestone {
+        edicts,
+        etching,
+        mint,
+        pointer,
+    }))
+}
+
+fn payload(outputs: &[&Script]) -> Option<Payload> {
+    // search transaction outputs for payload
+    for script in outputs {
+        let mut instructions = script.instructions();
+
+        // payload starts with OP_RETURN
+        if instructions.next() != Some(Ok(Instruction::Op(opcodes::all::OP_RETURN))) {
+            continue;
+        }
+
+        // followed by the protocol identifier, ignoring errors, since OP_RETURN
+        // scripts may be invalid
+        if instructions.next() != Some(Ok(Instruction::Op(MAGIC_NUMBER))) {
+            continue;
+        }
+
+        // construct the payload by concatenating remaining data pushes
+        let mut payload = Vec::new();
+
+        for result in instructions {
+            match result {
+                Ok(Instruction::PushBytes(push)) => {
+                    payload.extend_from_slice(push.as_bytes());
+                }
+                Ok(Instruction::Op(_)) => {
+                    return Some(Payload::Invalid(Flaw::Opcode));
+                }
+                Err(_) => {
+                    return Some(Payload::Invalid(Flaw::InvalidScript));
+                }
+            }
+        }
+
+        return Some(Payload::Valid(payload));
+    }
+
+    None
+}
+
+fn integers(payload: &[u8]) -> Result<Vec<u128>, varint::Error> {
+    let mut integers = Vec::new();
+    let mut i = 0;
+
+    while i < payload.len() {
+        let (integer, length) = varint::decode(&payload[i..])?;
+        integers.push(integer);
+        i += length;
+    }
+
+    Ok(integers)
+}
+
+/// Check whether the tapscript of the witness contains a data push equal to the rune commitment.
+pub(crate) fn tapscript_contains_commitment(witness: &bitcoin::Witness, commitment: &[u8]) -> bool {
+    let Some(tapscript) = witness.tapscript() else {
+        return false;
+    };
+    for instruction in tapscript.instructions() {
+        // ignore errors, since the extracted script may not be valid
+        let Ok(instruction) = instruction else {
+            break;
+        };
+        let Some(pushbytes) = instruction.push_bytes() else {
+            continue;
+        };
+        if pushbytes.as_bytes() == commitment {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::script::{Builder, PushBytesBuf};
+    use bitcoin::ScriptBuf;
+
+    fn runestone_script(integers: &[u128]) -> ScriptBuf {
+        let mut payload = Vec::new();
+        for integer in integers {
+            varint::encode_to_vec(*integer, &mut payload);
+        }
+        Builder::new()
+            .push_opcode(opcodes::all::OP_RETURN)
+            .push_opcode(MAGIC_NUMBER)
+            .push_slice(PushBytesBuf::try_from(payload).unwrap())
+            .into_script()
+    }
+
+    fn decipher_integers(integers: &[u128]) -> Option<Artifact> {
+        let scripts = [runestone_script(integers), ScriptBuf::new()];
+        let outputs = scripts.iter().map(|s| s.as_script()).collect::<Vec<_>>();
+        decipher(&outputs)
+    }
+
+    #[test]
+    fn test_varint_round_trip() {
+        for n in [0u128, 1, 127, 128, 255, 16384, u64::MAX as u128, u128::MAX] {
+            let mut buffer = Vec::new();
+            varint::encode_to_vec(n, &mut buffer);
+            assert_eq!(varint::decode(&buffer), Ok((n, buffer.len())));
+        }
+        assert_eq!(varint::decode(&[0x80]), Err(varint::Error::Unterminated));
+        assert_eq!(varint::decode(&[0x80; 19]), Err(varint::Error::Overlong));
+        let mut overflow = vec![0x80; 18];
+        overflow.push(0x04);
+        assert_eq!(varint::decode(&overflow), Err(varint::Error::Overflow));
+    }
+
+    #[test]
+    fn test_decipher_no_runestone() {
+        let scripts = [
+            Builder::new()
+                .push_opcode(opcodes::all::OP_RETURN)
+                .push_slice(b"ord")
+                .into_script(),
+            ScriptBuf::new(),
+        ];
+        let outputs = scripts.iter().map(|s| s.as_script()).collect::<Vec<_>>();
+        assert_eq!(decipher(&outputs), None);
+    }
+
+    #[test]
+    fn test_decipher_empty_runestone() {
+        assert_eq!(
+            decipher_integers(&[]),
+            Some(Artifact::Runestone(Runestone::default()))
+        );
+    }
+
+    #[test]
+    fn test_decipher_etching_and_edicts() {
+        let artifact = decipher_integers(&[
+            Tag::Flags as u128,
+            Flag::Etching.mask() | Flag::Terms.mask(),
+            Tag::Rune as u128,
+            4,
+            Tag::Divisibility as u128,
+            2,
+            Tag::Symbol as u128,
+            'R' as u128,
+            Tag::Amount as u128,
+            1000,
+            Tag::Cap as u128,
+            10,
+            Tag::Premine as u128,
+            500,
+            Tag::Pointer as u128,
+            1,
+            Tag::Body as u128,
+            0,
+            0,
+            100,
+            0,
+            840000,
+            1,
+            5,
+            2,
+        ]);
+        assert_eq!(
+            artifact,
+            Some(Artifact::Runestone(Runestone {
+                edicts: vec![
+                    Edict {
+                        id: RuneId { block: 0, tx: 0 },
+                        amount: 100,
+                        output: 0,
+                    },
+                    Edict {
+                        id: RuneId {
+                            block: 840000,
+                            tx: 1,
+                        },
+                        amount: 5,
+                        output: 2,
+                    },
+                ],
+                etching: Some(Etching {
+                    divisibility: Some(2),
+                    premine: Some(500),
+                    rune: Some(4),
+                    spacers: None,
+                    symbol: Some('R'),
+                    terms: Some(Terms {
+                        amount: Some(1000),
+                        cap: Some(10),
+                        height: (None, None),
+                        offset: (None, None),
+                    }),
+                    turbo: false,
+                }),
+                mint: None,
+                pointer: Some(1),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_decipher_cenotaph() {
+        // unrecognized even tag
+        assert_eq!(
+            decipher_integers(&[Tag::Cenotaph as u128, 0]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::UnrecognizedEvenTag),
+                ..Default::default()
+            }))
+        );
+        // unrecognized flag, the etched rune is kept
+        assert_eq!(
+            decipher_integers(&[
+                Tag::Flags as u128,
+                Flag::Etching.mask() | (1 << 10),
+                Tag::Rune as u128,
+                4,
+            ]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::UnrecognizedFlag),
+                etching: Some(4),
+                ..Default::default()
+            }))
+        );
+        // truncated field
+        assert_eq!(
+            decipher_integers(&[Tag::Nop as u128]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::TruncatedField),
+                ..Default::default()
+            }))
+        );
+        // edict output greater than the number of outputs
+        assert_eq!(
+            decipher_integers(&[Tag::Body as u128, 1, 1, 10, 3]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::EdictOutput),
+                ..Default::default()
+            }))
+        );
+        // trailing integers
+        assert_eq!(
+            decipher_integers(&[Tag::Body as u128, 1, 1, 10]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::TrailingIntegers),
+                ..Default::default()
+            }))
+        );
+        // supply overflow
+        assert_eq!(
+            decipher_integers(&[
+                Tag::Flags as u128,
+                Flag::Etching.mask() | Flag::Terms.mask(),
+                Tag::Premine as u128,
+                1,
+                Tag::Cap as u128,
+                u128::MAX,
+                Tag::Amount as u128,
+                1,
+            ]),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::SupplyOverflow),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_decipher_invalid_payload() {
+        let scripts = [Builder::new()
+            .push_opcode(opcodes::all::OP_RETURN)
+            .push_opcode(MAGIC_NUMBER)
+            .push_opcode(opcodes::all::OP_VERIFY)
+            .into_script()];
+        let outputs = scripts.iter().map(|s| s.as_script()).collect::<Vec<_>>();
+        assert_eq!(
+            decipher(&outputs),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::Opcode),
+                ..Default::default()
+            }))
+        );
+
+        let invalid_varint = vec![0x80u8; 20];
+        let scripts = [Builder::new()
+            .push_opcode(opcodes::all::OP_RETURN)
+            .push_opcode(MAGIC_NUMBER)
+            .push_slice(PushBytesBuf::try_from(invalid_varint).unwrap())
+            .into_script()];
+        let outputs = scripts.iter().map(|s| s.as_script()).collect::<Vec<_>>();
+        assert_eq!(
+            decipher(&outputs),
+            Some(Artifact::Cenotaph(Cenotaph {
+                flaw: Some(Flaw::Varint),
+                ..Default::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_tapscript_contains_commitment() {
+        let commitment = vec![4u8];
+        let script = Builder::new()
+            .push_slice(PushBytesBuf::try_from(commitment.clone()).unwrap())
+            .push_opcode(opcodes::all::OP_DROP)
+            .into_script();
+        let witness =
+            bitcoin::Witness::from_slice(&[vec![0u8; 64], script.into_bytes(), vec![0xc0u8; 33]]);
+        assert!(tapscript_contains_commitment(&witness, &commitment));
+        assert!(!tapscript_contains_commitment(&witness, &[5u8]));
+    }
+}
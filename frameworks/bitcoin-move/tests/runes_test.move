@@ -0,0 +1,25 @@
+#[test_only]
+module bitcoin_move::runes_test {
+
+    use bitcoin_move::runes;
+
+    #[test]
+    fun test_minimum_at_height() {
+        bitcoin_move::genesis::init_for_test();
+        //The test genesis network is signet, the runes are activated at height 0
+        assert!(runes::minimum_at_height(0) == 99246114928149462 - (99246114928149462 - 3817158266467286) / 17500, 1);
+        assert!(runes::minimum_at_height(17500 - 1) == 3817158266467286, 2);
+        assert!(runes::minimum_at_height(17500 * 11 - 1) == 26, 3);
+        assert!(runes::minimum_at_height(210000 - 2) == 1, 4);
+        assert!(runes::minimum_at_height(210000 - 1) == 0, 5);
+    }
+
+    #[test]
+    fun test_rune_store() {
+        bitcoin_move::genesis::init_for_test();
+        assert!(std::option::is_none(&runes::rune_id(0)), 1);
+        assert!(!runes::exists_rune_entry(runes::new_rune_id(1, 0)), 2);
+        let outpoint = bitcoin_move::types::new_outpoint(@0x1, 0);
+        assert!(moveos_std::simple_map::length(&runes::balances(outpoint)) == 0, 3);
+    }
+}
@@ -8,7 +8,7 @@ use crate::Stdlib;
 use anyhow::{bail, ensure, Result};
 use framework_types::addresses::KANARI_NURSERY_ADDRESS;
 use itertools::Itertools;
-use move_binary_format::{compatibility::Compatibility, errors::PartialVMResult, CompiledModule};
+use move_binary_format::{compatibility::Compatibility, normalized, CompiledModule};
 use moveos_types::moveos_std::module_store::PackageData;
 use std::collections::HashMap;
 use tracing::{debug, info, warn};
@@ -116,13 +116,19 @@ fn check_stdlib_compatibility(curr_stdlib: &Stdlib, prev_stdlib: &Stdlib) -> Res
         curr_stdlib.all_modules()?,
         prev_stdlib.all_modules()?,
         false,
+        false,
     )
 }
 
+/// Check whether the new modules are compatible with the old modules.
+/// The `Compatibility` rule of the VM is always checked, `check_struct_layout` additionally rejects
+/// the changed struct fields, which the VM accepts but the objects stored on chain can not be
+/// deserialized with, it is used by the client side check before publishing a package.
 pub fn check_modules_compat(
     new_modules: Vec<CompiledModule>,
     old_modules: Vec<CompiledModule>,
     allow_deleted_module: bool,
+    check_struct_layout: bool,
 ) -> Result<()> {
     let new_modules_map = new_modules
         .into_iter()
@@ -133,7 +139,7 @@ pub fn check_modules_compat(
         .map(|module| (module.self_id(), module))
         .collect::<HashMap<_, _>>();
 
-    let incompatible_module_ids = new_modules_map
+    let mut incompatible_modules = new_modules_map
         .values()
         .filter_map(|module| {
             let module_id = module.self_id();
@@ -142,13 +148,14 @@ pub fn check_modules_compat(
                 return None;
             }
             if let Some(old_module) = old_modules_map.get(&module_id) {
-                let compatibility = check_compiled_module_compat(old_module, module);
-                if let Err(err) = compatibility {
+                let incompatibilities =
+                    check_compiled_module_compat(old_module, module, check_struct_layout);
+                if !incompatibilities.is_empty() {
                     warn!(
                         "Module {:?} is incompatible with previous version: {:?}",
-                        module_id, err
+                        module_id, incompatibilities
                     );
-                    Some(module_id)
+                    Some((module_id, incompatibilities))
                 } else {
                     debug!(
                         "Module {:?} is compatible with previous version.",
@@ -162,14 +169,26 @@ pub fn check_modules_compat(
             }
         })
         .collect::<Vec<_>>();
+    incompatible_modules.sort_by(|(a, _), (b, _)| a.cmp(b));
 
     ensure!(
-        incompatible_module_ids.is_empty(),
-        "Modules {} is incompatible with previous version!",
-        incompatible_module_ids
-            .into_iter()
-            .map(|module_id| module_id.to_string())
+        incompatible_modules.is_empty(),
+        "Modules {} is incompatible with previous version!\n{}",
+        incompatible_modules
+            .iter()
+            .map(|(module_id, _)| module_id.to_string())
             .join(","),
+        incompatible_modules
+            .iter()
+            .map(|(module_id, incompatibilities)| format!(
+                "{}:\n{}",
+                module_id.short_str_lossless(),
+                incompatibilities
+                    .iter()
+                    .map(|incompatibility| format!("  - {}", incompatibility))
+                    .join("\n")
+            ))
+            .join("\n"),
     );
 
     let deleted_module_ids = old_modules_map
@@ -210,16 +229,17 @@ pub fn check_package_compat(
 ) -> Result<()> {
     let new_modules = new_package_data.compiled_modules()?;
     let pre_modules = pre_package_data.compiled_modules()?;
-    check_modules_compat(new_modules, pre_modules, false)
+    check_modules_compat(new_modules, pre_modules, false, false)
 }
 
-/// check module compatibility
+/// check module compatibility, return the incompatibilities of the new module
 fn check_compiled_module_compat(
     old_module: &CompiledModule,
     new_module: &CompiledModule,
-) -> PartialVMResult<()> {
+    check_struct_layout: bool,
+) -> Vec<String> {
     if new_module == old_module {
-        return Ok(());
+        return vec![];
     }
     debug!(
         "Checking compatibility between module {:?} and module {:?}",
@@ -229,5 +249,102 @@ fn check_compiled_module_compat(
     //We enable all compatibility checks, do not allow friend functions break after the issue
     //https://github.com/rooch-network/rooch/pull/3465
     let compat = Compatibility::new(true, true, true, true);
-    compat.check(old_module, new_module)
+    let mut incompatibilities = compat.check_errors(old_module, new_module);
+    if check_struct_layout {
+        incompatibilities.extend(check_struct_layout_compat(old_module, new_module));
+    }
+    incompatibilities
+}
+
+/// The struct layout check is not enabled in `Compatibility`, but the objects stored on chain can not
+/// be deserialized if the fields of a struct are changed, so we check it on the client side.
+fn check_struct_layout_compat(
+    old_module: &CompiledModule,
+    new_module: &CompiledModule,
+) -> Vec<String> {
+    let old_module = normalized::Module::new(old_module);
+    let new_module = normalized::Module::new(new_module);
+    old_module
+        .structs
+        .iter()
+        .filter_map(|(name, old_struct)| {
+            let new_struct = new_module.structs.get(name)?;
+            if old_struct.fields == new_struct.fields {
+                return None;
+            }
+            let fields_str = |fields: &[normalized::Field]| {
+                fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.name, field.type_))
+                    .join(", ")
+            };
+            Some(format!(
+                "changed layout of struct `{}` from `{{ {} }}` to `{{ {} }}`",
+                name,
+                fields_str(&old_struct.fields),
+                fields_str(&new_struct.fields)
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::{
+        basic_test_module, SignatureToken, StructFieldInformation,
+    };
+
+    /// `basic_test_module` with the field `x` of struct `Bar` changed from `u64` to `bool`
+    fn changed_layout_module() -> CompiledModule {
+        let mut module = basic_test_module();
+        if let StructFieldInformation::Declared(fields) =
+            &mut module.struct_defs[0].field_information
+        {
+            fields[0].signature.0 = SignatureToken::Bool;
+        }
+        module
+    }
+
+    #[test]
+    fn test_struct_layout_change_is_allowed_by_vm_rule() {
+        check_modules_compat(
+            vec![changed_layout_module()],
+            vec![basic_test_module()],
+            false,
+            false,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_struct_layout_change_is_rejected_by_client_rule() {
+        let err = check_modules_compat(
+            vec![changed_layout_module()],
+            vec![basic_test_module()],
+            true,
+            true,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("changed layout of struct `Bar` from `{ x: u64 }` to `{ x: bool }`"));
+    }
+
+    #[test]
+    fn test_removed_struct_is_rejected_by_both_rules() {
+        let mut new_module = basic_test_module();
+        new_module.struct_defs.clear();
+        new_module.struct_handles.clear();
+        for check_struct_layout in [false, true] {
+            let err = check_modules_compat(
+                vec![new_module.clone()],
+                vec![basic_test_module()],
+                false,
+                check_struct_layout,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("removed struct `Bar`"));
+        }
+    }
 }
@@ -125,11 +125,15 @@ module kanari_framework::onchain_config {
             vector::push_back(&mut enables, features::get_testnet_feature());
             vector::push_back(&mut enables, features::get_value_size_gas_feature());
             vector::push_back(&mut enables, features::get_compatibility_checker_v2_feature());
+            vector::push_back(&mut enables, features::get_bitcoin_header_validation_feature());
+            vector::push_back(&mut enables, features::get_bitcoin_runes_feature());
         } else if (chain_id::is_dev()) {
             vector::push_back(&mut enables, features::get_devnet_feature());
             vector::push_back(&mut enables, features::get_testnet_feature());
             vector::push_back(&mut enables, features::get_value_size_gas_feature());
             vector::push_back(&mut enables, features::get_compatibility_checker_v2_feature());
+            vector::push_back(&mut enables, features::get_bitcoin_header_validation_feature());
+            vector::push_back(&mut enables, features::get_bitcoin_runes_feature());
         } else if (chain_id::is_test()) {
             vector::push_back(&mut enables, features::get_testnet_feature());
             vector::push_back(&mut enables, features::get_value_size_gas_feature());
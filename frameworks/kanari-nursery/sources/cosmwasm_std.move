@@ -6,6 +6,7 @@ module kanari_nursery::cosmwasm_std {
     use std::string::{Self, String};
     use std::option::{Self, Option};
 
+    use moveos_std::address;
     use moveos_std::base64;
     use moveos_std::json;
     use moveos_std::timestamp;
@@ -130,11 +131,105 @@ module kanari_nursery::cosmwasm_std {
         error: Option<String>,
     }
 
+    // The JSON form of the result of contracts, which is converted to the `Response`
+    #[data_struct]
+    struct ContractStdResult has copy, drop {
+        ok: Option<ContractResponse>,
+        error: Option<String>,
+    }
+
+    #[data_struct]
+    struct ContractResponse has copy, drop {
+        messages: vector<ContractSubMsg>,
+        attributes: vector<Attribute>,
+        events: vector<ContractEvent>,
+        data: Option<String>,
+    }
+
+    #[data_struct]
+    struct ContractEvent has copy, drop {
+        type: String,
+        attributes: vector<Attribute>,
+    }
+
+    #[data_struct]
+    struct ContractSubMsg has copy, drop {
+        id: u64,
+        msg: CosmosMsg,
+        gas_limit: Option<u64>,
+        reply_on: String,
+    }
+
+    // The JSON form of the `Reply` which is passed to the `reply` of contracts
+    #[data_struct]
+    struct ContractReply has copy, drop {
+        id: u64,
+        payload: String,
+        gas_used: u64,
+        result: ContractSubMsgResult,
+    }
+
+    #[data_struct]
+    struct ContractSubMsgResult has copy, drop {
+        ok: Option<ContractSubMsgResponse>,
+        error: Option<String>,
+    }
+
+    #[data_struct]
+    struct ContractSubMsgResponse has copy, drop {
+        events: vector<ContractEvent>,
+        msg_responses: vector<ContractMsgResponse>,
+    }
+
+    #[data_struct]
+    struct ContractMsgResponse has copy, drop {
+        type_url: String,
+        /// The base64 encoded value
+        value: String,
+    }
+
+    // The supported `CosmosMsg`s, the other messages are parsed as empty messages
+    #[data_struct]
+    struct CosmosMsg has store, copy, drop {
+        bank: Option<BankMsg>,
+        wasm: Option<WasmMsg>,
+    }
+
+    #[data_struct]
+    struct BankMsg has store, copy, drop {
+        send: Option<BankSendMsg>,
+    }
+
+    #[data_struct]
+    struct BankSendMsg has store, copy, drop {
+        to_address: address,
+        amount: vector<Coin>,
+    }
+
+    #[data_struct]
+    struct WasmMsg has store, copy, drop {
+        execute: Option<WasmExecuteMsg>,
+    }
+
+    #[data_struct]
+    struct WasmExecuteMsg has store, copy, drop {
+        contract_addr: address,
+        /// The base64 encoded message
+        msg: String,
+        funds: vector<Coin>,
+    }
+
     // Constants for ReplyOn
+    const REPLY_ON_NEVER: u8 = 0;
     const REPLY_ON_SUCCESS: u8 = 1;
     const REPLY_ON_ERROR: u8 = 2;
     const REPLY_ALWAYS: u8 = 3;
 
+    /// The type URL of the response of `BankMsg::Send`
+    const MSG_SEND_RESPONSE_TYPE_URL: vector<u8> = b"/cosmos.bank.v1beta1.MsgSendResponse";
+    /// The type URL of the response of `WasmMsg::Execute`
+    const MSG_EXECUTE_CONTRACT_RESPONSE_TYPE_URL: vector<u8> = b"/cosmwasm.wasm.v1.MsgExecuteContractResponse";
+
     // Functions
     public fun new_response(): Response {
         Response {
@@ -157,6 +252,13 @@ module kanari_nursery::cosmwasm_std {
         }
     }
 
+    public fun new_sub_msg_result_ok(events: vector<Event>, msg_responses: vector<MsgResponse>): SubMsgResult {
+        SubMsgResult{
+            ok: option::some(SubMsgResponse { events, msg_responses }),
+            err: option::none(),
+        }
+    }
+
     public fun new_sub_msg_error(err: String): SubMsgResult {
         SubMsgResult{
             ok: option::none(),
@@ -164,6 +266,51 @@ module kanari_nursery::cosmwasm_std {
         }
     }
 
+    /// Returns the error of the `result`, none if the submessage succeeded.
+    public fun sub_msg_result_error(result: &SubMsgResult): Option<String> {
+        result.err
+    }
+
+    /// Returns the events of the `result`, empty if the submessage failed.
+    public fun sub_msg_result_events(result: &SubMsgResult): vector<Event> {
+        if (option::is_some(&result.ok)) {
+            option::borrow(&result.ok).events
+        } else {
+            vector::empty()
+        }
+    }
+
+    public fun new_event(ty: String, attributes: vector<Attribute>): Event {
+        Event { ty, attributes }
+    }
+
+    public fun new_attribute(key: String, value: String): Attribute {
+        Attribute { key, value }
+    }
+
+    /// Returns the response of `BankMsg::Send`, which has no fields.
+    public fun new_msg_send_response(): MsgResponse {
+        MsgResponse { type_url: string::utf8(MSG_SEND_RESPONSE_TYPE_URL), value: vector::empty() }
+    }
+
+    /// Returns the response of `WasmMsg::Execute`, the value is the protobuf encoded
+    /// `MsgExecuteContractResponse { data }`.
+    public fun new_msg_execute_contract_response(data: vector<u8>): MsgResponse {
+        let value = vector::empty();
+        if (!vector::is_empty(&data)) {
+            // The field 1 with the length delimited wire type
+            vector::push_back(&mut value, 0x0a);
+            let len = vector::length(&data);
+            while (len >= 0x80) {
+                vector::push_back(&mut value, (((len & 0x7f) | 0x80) as u8));
+                len = len >> 7;
+            };
+            vector::push_back(&mut value, (len as u8));
+            vector::append(&mut value, data);
+        };
+        MsgResponse { type_url: string::utf8(MSG_EXECUTE_CONTRACT_RESPONSE_TYPE_URL), value }
+    }
+
     public fun add_attribute(response: &mut Response, key: String, value: String) {
         vector::push_back(&mut response.attributes, Attribute { key, value });
     }
@@ -197,6 +344,127 @@ module kanari_nursery::cosmwasm_std {
         Error { code, message }
     }
 
+    public fun error_message(error: &Error): String {
+        error.message
+    }
+
+    public fun new_message_info(sender: address, funds: vector<Coin>): MessageInfo {
+        MessageInfo { sender, funds }
+    }
+
+    /// Returns a copy of the `env` with the contract address replaced.
+    public fun with_contract_address(env: &Env, address: address): Env {
+        let env = *env;
+        env.contract = ContractInfo { address };
+        env
+    }
+
+    public fun messages(response: &Response): vector<SubMsg> {
+        response.messages
+    }
+
+    public fun data(response: &Response): vector<u8> {
+        response.data
+    }
+
+    public fun events(response: &Response): vector<Event> {
+        response.events
+    }
+
+    /// Returns the events emitted by the contract with the `response`, as `wasmd` does: the attributes of the
+    /// response are in the `wasm` event, and the type of the custom events is prefixed with `wasm-`. The first
+    /// attribute of every event is the `_contract_address`.
+    public fun contract_events(contract_addr: address, response: &Response): vector<Event> {
+        let contract_attribute = Attribute {
+            key: string::utf8(b"_contract_address"),
+            value: address::to_bech32_string(contract_addr),
+        };
+        let events = vector::empty();
+        if (!vector::is_empty(&response.attributes)) {
+            let attributes = vector::singleton(contract_attribute);
+            vector::append(&mut attributes, response.attributes);
+            vector::push_back(&mut events, Event { ty: string::utf8(b"wasm"), attributes });
+        };
+        let i = 0;
+        let len = vector::length(&response.events);
+        while (i < len) {
+            let event = vector::borrow(&response.events, i);
+            let ty = string::utf8(b"wasm-");
+            string::append(&mut ty, event.ty);
+            let attributes = vector::singleton(contract_attribute);
+            vector::append(&mut attributes, event.attributes);
+            vector::push_back(&mut events, Event { ty, attributes });
+            i = i + 1;
+        };
+        events
+    }
+
+    public fun sub_msg_id(sub_msg: &SubMsg): u64 {
+        sub_msg.id
+    }
+
+    public fun sub_msg_msg(sub_msg: &SubMsg): vector<u8> {
+        sub_msg.msg
+    }
+
+    public fun sub_msg_gas_limit(sub_msg: &SubMsg): Option<u64> {
+        sub_msg.gas_limit
+    }
+
+    public fun is_reply_on_success(sub_msg: &SubMsg): bool {
+        sub_msg.reply_on.value == REPLY_ON_SUCCESS || sub_msg.reply_on.value == REPLY_ALWAYS
+    }
+
+    public fun is_reply_on_error(sub_msg: &SubMsg): bool {
+        sub_msg.reply_on.value == REPLY_ON_ERROR || sub_msg.reply_on.value == REPLY_ALWAYS
+    }
+
+    public fun unpack_coin(coin: Coin): (String, u128) {
+        let Coin { denom, amount } = coin;
+        (denom, amount)
+    }
+
+    /// Parse the `msg` of a `SubMsg`, returns none if the message is not a valid `CosmosMsg`.
+    public fun parse_cosmos_msg(msg: vector<u8>): Option<CosmosMsg> {
+        json::from_json_option<CosmosMsg>(msg)
+    }
+
+    public fun is_bank_send(msg: &CosmosMsg): bool {
+        option::is_some(&msg.bank) && option::is_some(&option::borrow(&msg.bank).send)
+    }
+
+    /// Returns the `to_address` and the `amount` of the `BankMsg::Send`.
+    public fun unpack_bank_send(msg: CosmosMsg): (address, vector<Coin>) {
+        let CosmosMsg { bank, wasm: _ } = msg;
+        let BankMsg { send } = option::destroy_some(bank);
+        let BankSendMsg { to_address, amount } = option::destroy_some(send);
+        (to_address, amount)
+    }
+
+    /// Returns the JSON encoded `BankMsg::Send`, which can be used as the `msg` of a `SubMsg`.
+    public fun new_bank_send_msg(to_address: address, amount: vector<Coin>): vector<u8> {
+        let send = BankSendMsg { to_address, amount };
+        json::to_json(&CosmosMsg { bank: option::some(BankMsg { send: option::some(send) }), wasm: option::none() })
+    }
+
+    /// Returns the JSON encoded `WasmMsg::Execute`, which can be used as the `msg` of a `SubMsg`.
+    public fun new_wasm_execute_msg(contract_addr: address, msg: vector<u8>, funds: vector<Coin>): vector<u8> {
+        let execute = WasmExecuteMsg { contract_addr, msg: new_binary(msg), funds };
+        json::to_json(&CosmosMsg { bank: option::none(), wasm: option::some(WasmMsg { execute: option::some(execute) }) })
+    }
+
+    public fun is_wasm_execute(msg: &CosmosMsg): bool {
+        option::is_some(&msg.wasm) && option::is_some(&option::borrow(&msg.wasm).execute)
+    }
+
+    /// Returns the `contract_addr`, the decoded `msg` and the `funds` of the `WasmMsg::Execute`.
+    public fun unpack_wasm_execute(msg: CosmosMsg): (address, vector<u8>, vector<Coin>) {
+        let CosmosMsg { bank: _, wasm } = msg;
+        let WasmMsg { execute } = option::destroy_some(wasm);
+        let WasmExecuteMsg { contract_addr, msg, funds } = option::destroy_some(execute);
+        (contract_addr, base64::decode(string::bytes(&msg)), funds)
+    }
+
     public fun new_error_result<T>(code: u32, message: String): Result<T, Error> {
         err(new_error(code, message))
     }
@@ -223,20 +491,108 @@ module kanari_nursery::cosmwasm_std {
         json::to_json(msg)
     }
 
+    /// Serialize the `reply` to the JSON form of the `Reply` of CosmWasm.
+    public fun serialize_reply(reply: &Reply): vector<u8> {
+        let result = if (option::is_some(&reply.result.ok)) {
+            let SubMsgResponse { events, msg_responses } = *option::borrow(&reply.result.ok);
+            let contract_msg_responses = vector::empty();
+            let i = 0;
+            let len = vector::length(&msg_responses);
+            while (i < len) {
+                let MsgResponse { type_url, value } = *vector::borrow(&msg_responses, i);
+                vector::push_back(&mut contract_msg_responses, ContractMsgResponse { type_url, value: new_binary(value) });
+                i = i + 1;
+            };
+            let response = ContractSubMsgResponse {
+                events: into_contract_events(events),
+                msg_responses: contract_msg_responses,
+            };
+            ContractSubMsgResult { ok: option::some(response), error: option::none() }
+        } else {
+            ContractSubMsgResult { ok: option::none(), error: reply.result.err }
+        };
+        json::to_json(&ContractReply {
+            id: reply.id,
+            payload: reply.payload,
+            gas_used: reply.gas_used,
+            result,
+        })
+    }
+
     public fun deserialize_stdresult(raw: vector<u8>): Result<Response, Error> {
-        let result_option = json::from_json_option<StdResult>(raw);
+        let result_option = json::from_json_option<ContractStdResult>(raw);
         if (option::is_none(&result_option)) {
             return new_error_result(ErrorDeserialize, string::utf8(b"deserialize_response_error"))
         };
 
         let std_result = option::extract(&mut result_option);
         if (option::is_some(&std_result.ok)) {
-            ok(option::extract(&mut std_result.ok))
+            ok(into_response(option::extract(&mut std_result.ok)))
         } else {
             err(new_error(1, option::extract(&mut std_result.error)))
         }
     }
 
+    fun into_response(contract_response: ContractResponse): Response {
+        let ContractResponse { messages: contract_messages, attributes, events: contract_events, data } = contract_response;
+        let messages = vector::empty();
+        let i = 0;
+        let len = vector::length(&contract_messages);
+        while (i < len) {
+            let ContractSubMsg { id, msg, gas_limit, reply_on } = *vector::borrow(&contract_messages, i);
+            let sub_msg = new_sub_msg(id, json::to_json(&msg), gas_limit, parse_reply_on(&reply_on));
+            vector::push_back(&mut messages, sub_msg);
+            i = i + 1;
+        };
+
+        let data = if (option::is_some(&data)) {
+            base64::decode(string::bytes(option::borrow(&data)))
+        } else {
+            vector::empty()
+        };
+
+        let events = vector::empty();
+        let i = 0;
+        let len = vector::length(&contract_events);
+        while (i < len) {
+            let ContractEvent { type, attributes } = *vector::borrow(&contract_events, i);
+            vector::push_back(&mut events, Event { ty: type, attributes });
+            i = i + 1;
+        };
+
+        Response {
+            messages,
+            attributes,
+            events,
+            data,
+        }
+    }
+
+    fun into_contract_events(events: vector<Event>): vector<ContractEvent> {
+        let contract_events = vector::empty();
+        let i = 0;
+        let len = vector::length(&events);
+        while (i < len) {
+            let Event { ty, attributes } = *vector::borrow(&events, i);
+            vector::push_back(&mut contract_events, ContractEvent { type: ty, attributes });
+            i = i + 1;
+        };
+        contract_events
+    }
+
+    fun parse_reply_on(reply_on: &String): u8 {
+        let reply_on = string::bytes(reply_on);
+        if (reply_on == &b"success") {
+            REPLY_ON_SUCCESS
+        } else if (reply_on == &b"error") {
+            REPLY_ON_ERROR
+        } else if (reply_on == &b"always") {
+            REPLY_ALWAYS
+        } else {
+            REPLY_ON_NEVER
+        }
+    }
+
     public fun new_binary(data: vector<u8>): String {
         let encode_bytes = base64::encode(&data);
         string::utf8(encode_bytes)
@@ -4,18 +4,34 @@
 module kanari_nursery::cosmwasm_vm {
     use std::string::{Self, String};
     use std::option::{Self, Option};
+    use std::vector;
     
+    use moveos_std::account::{Self, AccountCap};
     use moveos_std::features;
+    use moveos_std::signer;
     use moveos_std::table;
-    use moveos_std::object::{ObjectID};
-    use moveos_std::result::{Result, ok};
+    use moveos_std::object::{Self, ObjectID};
+    use moveos_std::result::{Self, Result, ok, err};
 
-    use kanari_nursery::cosmwasm_std::{Response, Error, Env, MessageInfo, Reply,
-        new_error, new_error_result, serialize_env, serialize_message_info, serialize_message, deserialize_stdresult};
+    use kanari_framework::account_coin_store;
+
+    use kanari_nursery::cosmwasm_std::{Self, Response, Error, Env, MessageInfo, Reply, SubMsg, SubMsgResult, CosmosMsg,
+        Coin, Event, new_error, new_error_result, serialize_env, serialize_message_info, serialize_message,
+        serialize_reply, deserialize_stdresult};
+
+    /// The max depth of the nested contract calls and replies
+    const MAX_DISPATCH_DEPTH: u64 = 8;
+    /// The max gas limit of a contract call, which is used if the submessage has no `gas_limit`
+    const DEFAULT_GAS_LIMIT: u64 = 10000000;
+
+    /// A submessage or a `reply` failed and the error was not handled by the `reply` of the contract
+    const ErrorSubMsgFailed: u64 = 1;
 
     struct Instance has key, store {
         code_checksum: vector<u8>,
-        store: table::Table<String, vector<u8>>
+        store: table::Table<String, vector<u8>>,
+        /// The account of the contract, which holds the coins of the contract
+        account: AccountCap,
     }
 
     public fun code_checksum(instance: &Instance): vector<u8> {
@@ -37,6 +53,7 @@ module kanari_nursery::cosmwasm_vm {
             ok(Instance { 
                 code_checksum: checksum,
                 store: store,
+                account: account::create_account_and_return_cap(),
             })
         } else {
             table::drop(store);
@@ -51,7 +68,7 @@ module kanari_nursery::cosmwasm_vm {
         let info_bytes = serialize_message_info(info);
         let msg_bytes = serialize_message(msg);
 
-        let (std_result, error_code) = native_call_instantiate_raw(instance.code_checksum, store_handle, env_bytes, info_bytes, msg_bytes);
+        let (std_result, _undo_log, error_code) = native_call_instantiate_raw(instance.code_checksum, store_handle, env_bytes, info_bytes, msg_bytes);
         if (error_code == 0) {
             deserialize_stdresult(std_result)
         } else {
@@ -61,13 +78,19 @@ module kanari_nursery::cosmwasm_vm {
 
     #[data_struct(T)]
     public fun call_execute<T: drop>(instance: &mut Instance, env: &Env, info: &MessageInfo, msg: &T): Result<Response, Error> {
+        let journal = vector::empty();
+        call_execute_raw(instance, env, info, serialize_message(msg), DEFAULT_GAS_LIMIT, &mut journal)
+    }
+
+    /// Execute the contract, the writes of the call are recorded in the `journal` if it succeeded.
+    fun call_execute_raw(instance: &mut Instance, env: &Env, info: &MessageInfo, msg_bytes: vector<u8>, gas_limit: u64, journal: &mut vector<JournalEntry>): Result<Response, Error> {
         let store_handle = table::handle(&mut instance.store);
         let env_bytes = serialize_env(env);
         let info_bytes = serialize_message_info(info);
-        let msg_bytes = serialize_message(msg);
 
-        let (std_result, error_code) = native_call_execute_raw(instance.code_checksum, store_handle, env_bytes, info_bytes, msg_bytes);
+        let (std_result, undo_log, error_code) = native_call_execute_raw(instance.code_checksum, store_handle, env_bytes, info_bytes, msg_bytes, gas_limit);
         if (error_code == 0) {
+            vector::push_back(journal, new_store_entry(store_handle, undo_log));
             deserialize_stdresult(std_result)
         } else {
             new_error_result(error_code, string::utf8(b"native_call_execute_raw_error"))
@@ -80,7 +103,7 @@ module kanari_nursery::cosmwasm_vm {
         let env_bytes = serialize_env(env);
         let msg_bytes = serialize_message(msg);
 
-        let (std_result, error_code) = native_call_query_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
+        let (std_result, _undo_log, error_code) = native_call_query_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
         if (error_code == 0) {
             deserialize_stdresult(std_result)
         } else {
@@ -94,7 +117,7 @@ module kanari_nursery::cosmwasm_vm {
         let env_bytes = serialize_env(env);
         let msg_bytes = serialize_message(msg);
 
-        let (std_result, error_code) = native_call_migrate_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
+        let (std_result, _undo_log, error_code) = native_call_migrate_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
         if (error_code == 0) {
             deserialize_stdresult(std_result)
         } else {
@@ -103,12 +126,19 @@ module kanari_nursery::cosmwasm_vm {
     }
 
     public fun call_reply(instance: &mut Instance, env: &Env, reply: &Reply): Result<Response, Error> {
+        let journal = vector::empty();
+        call_reply_raw(instance, env, reply, &mut journal)
+    }
+
+    /// Call the `reply` of the contract, the writes of the call are recorded in the `journal` if it succeeded.
+    fun call_reply_raw(instance: &mut Instance, env: &Env, reply: &Reply, journal: &mut vector<JournalEntry>): Result<Response, Error> {
         let store_handle = table::handle(&mut instance.store);
         let env_bytes = serialize_env(env);
-        let msg_bytes = serialize_message(reply);
+        let msg_bytes = serialize_reply(reply);
 
-        let (std_result, error_code) = native_call_reply_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
+        let (std_result, undo_log, error_code) = native_call_reply_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
         if (error_code == 0) {
+            vector::push_back(journal, new_store_entry(store_handle, undo_log));
             deserialize_stdresult(std_result)
         } else {
             new_error_result(error_code, string::utf8(b"native_call_reply_raw_error"))
@@ -121,7 +151,7 @@ module kanari_nursery::cosmwasm_vm {
         let env_bytes = serialize_env(env);
         let msg_bytes = serialize_message(msg);
 
-        let (std_result, error_code) = native_call_sudo_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
+        let (std_result, _undo_log, error_code) = native_call_sudo_raw(instance.code_checksum, store_handle, env_bytes, msg_bytes);
         if (error_code == 0) {
             deserialize_stdresult(std_result)
         } else {
@@ -131,8 +161,9 @@ module kanari_nursery::cosmwasm_vm {
 
     /// Destroys an Instance and releases associated resources.
     public fun destroy_instance(instance: Instance): Option<Error> {
-        let Instance { code_checksum, store } = instance;
+        let Instance { code_checksum, store, account } = instance;
         table::drop(store);
+        account::destroy_account_cap(account);
 
         let error_code = native_destroy_instance(code_checksum);
         if (error_code == 0) {
@@ -142,13 +173,599 @@ module kanari_nursery::cosmwasm_vm {
         }
     }
 
+    /// The address of the contract, which is the address of the account of the instance.
+    public fun contract_address(instance: &Instance): address {
+        account::account_cap_address(&instance.account)
+    }
+
+    // ==== Contract registry ====
+
+    /// The registry of the instances which can be executed by other contracts via `WasmMsg::Execute`,
+    /// the fields are the contract address -> the ObjectID of the `Object<Instance>`.
+    struct ContractRegistry has key {}
+
+    /// Register the instance so that other contracts can execute it, the instance object is transferred to the `owner`.
+    public fun register_instance(instance: Instance, owner: address): ObjectID {
+        let contract_addr = contract_address(&instance);
+        let instance_obj = object::new(instance);
+        let instance_id = object::id(&instance_obj);
+        let registry = borrow_mut_or_create_registry();
+        object::add_field(registry, contract_addr, instance_id);
+        object::transfer(instance_obj, owner);
+        instance_id
+    }
+
+    public fun borrow_mut_instance(owner: &signer, instance_id: ObjectID): &mut Instance {
+        object::borrow_mut(object::borrow_mut_object<Instance>(owner, instance_id))
+    }
+
+    /// Returns the ObjectID of the registered instance of the contract.
+    public fun registered_instance_id(contract_addr: address): Option<ObjectID> {
+        let registry_id = object::named_object_id<ContractRegistry>();
+        if (!object::exists_object(registry_id)) {
+            return option::none()
+        };
+        let registry = object::borrow_object<ContractRegistry>(registry_id);
+        if (object::contains_field(registry, contract_addr)) {
+            option::some(*object::borrow_field(registry, contract_addr))
+        } else {
+            option::none()
+        }
+    }
+
+    fun borrow_mut_or_create_registry(): &mut object::Object<ContractRegistry> {
+        let registry_id = object::named_object_id<ContractRegistry>();
+        if (!object::exists_object(registry_id)) {
+            object::to_shared(object::new_named_object(ContractRegistry {}));
+        };
+        object::borrow_mut_object_shared<ContractRegistry>(registry_id)
+    }
+
+    // ==== Response dispatcher ====
+
+    /// An entry of the journal of the changes made by the dispatched messages, which is used to revert the
+    /// changes of a failed submessage. It is either the writes of a contract call to the store `store_handle`,
+    /// or a transfer of the coins `amount` from the account `from` to the account `to`.
+    struct JournalEntry has drop {
+        store_handle: Option<ObjectID>,
+        undo_log: vector<u8>,
+        from: address,
+        to: address,
+        amount: vector<Coin>,
+    }
+
+    fun new_store_entry(store_handle: ObjectID, undo_log: vector<u8>): JournalEntry {
+        JournalEntry { store_handle: option::some(store_handle), undo_log, from: @0x0, to: @0x0, amount: vector::empty() }
+    }
+
+    fun new_transfer_entry(from: address, to: address, amount: vector<Coin>): JournalEntry {
+        JournalEntry { store_handle: option::none(), undo_log: vector::empty(), from, to, amount }
+    }
+
+    /// Revert the changes recorded in the `journal` after the `savepoint`, in the reverse order.
+    /// The coin stores created by the reverted transfers and the emitted Move events are not reverted.
+    fun revert_journal(journal: &mut vector<JournalEntry>, savepoint: u64) {
+        while (vector::length(journal) > savepoint) {
+            let JournalEntry { store_handle, undo_log, from, to, amount } = vector::pop_back(journal);
+            if (option::is_some(&store_handle)) {
+                native_revert_store_writes(option::destroy_some(store_handle), undo_log);
+            } else {
+                let system = signer::module_signer<ContractRegistry>();
+                let to_signer = account::create_signer_for_system(&system, to);
+                transfer_coins(&to_signer, from, &amount);
+            };
+        };
+    }
+
+    /// Execute the messages of the `response` returned by the contract `instance`, and call the `reply` of the
+    /// contract according to the `ReplyOn` of every submessage. Returns the response with the data set by the replies.
+    ///
+    /// * `BankMsg::Send` transfers the coins from the account of the contract, the denom is the coin type name.
+    /// * `WasmMsg::Execute` transfers the `funds` from the account of the contract to the account of the target
+    ///   contract, executes the registered instance of the target with the `gas_limit` of the submessage, and
+    ///   dispatches its response recursively. A contract can not be called again while it is being executed.
+    ///
+    /// As in CosmWasm, a failed submessage has no changes: the contract writes and the transfers of the submessage
+    /// and of all the messages it dispatched are reverted, then the error is passed to the `reply` of the contract
+    /// if the submessage replies on error, otherwise it fails the caller. The `reply` of a successful submessage
+    /// gets the events and the data of the submessage. An error which is not handled aborts the transaction.
+    public fun dispatch_response(instance: &mut Instance, env: &Env, response: Response): Response {
+        let call_stack = vector::singleton(contract_address(instance));
+        let events = vector::empty();
+        let journal = vector::empty();
+        let result = dispatch_messages(instance, env, response, &call_stack, 0, &mut events, &mut journal);
+        assert!(result::is_ok(&result), ErrorSubMsgFailed);
+        result::unwrap(result)
+    }
+
+    /// Dispatch the messages of the `response`, the events of the successful submessages and of the replies are
+    /// appended to `events`. Returns the error of the first submessage or reply which failed without being handled.
+    fun dispatch_messages(
+        instance: &mut Instance,
+        env: &Env,
+        response: Response,
+        call_stack: &vector<address>,
+        depth: u64,
+        events: &mut vector<Event>,
+        journal: &mut vector<JournalEntry>,
+    ): Result<Response, String> {
+        let messages = cosmwasm_std::messages(&response);
+        let i = 0;
+        let len = vector::length(&messages);
+        while (i < len) {
+            let sub_msg = vector::borrow(&messages, i);
+            let sub_msg_result = execute_sub_msg(instance, env, sub_msg, call_stack, depth, journal);
+            let error = cosmwasm_std::sub_msg_result_error(&sub_msg_result);
+            let reply_on = if (option::is_some(&error)) {
+                if (!cosmwasm_std::is_reply_on_error(sub_msg)) {
+                    return err(option::destroy_some(error))
+                };
+                true
+            } else {
+                vector::append(events, cosmwasm_std::sub_msg_result_events(&sub_msg_result));
+                cosmwasm_std::is_reply_on_success(sub_msg)
+            };
+
+            if (reply_on) {
+                let reply = cosmwasm_std::new_reply(
+                    cosmwasm_std::sub_msg_id(sub_msg), string::utf8(b""), 0, sub_msg_result);
+                let reply_result = call_reply_raw(instance, env, &reply, journal);
+                if (result::is_err(&reply_result)) {
+                    return err(cosmwasm_std::error_message(&result::unwrap_err(reply_result)))
+                };
+                let reply_response = result::unwrap(reply_result);
+                vector::append(events, cosmwasm_std::contract_events(contract_address(instance), &reply_response));
+                // The messages of the reply are dispatched as well, and its data overrides the data of the response
+                let reply_result = dispatch_messages(instance, env, reply_response, call_stack, depth + 1, events, journal);
+                if (result::is_err(&reply_result)) {
+                    return reply_result
+                };
+                let data = cosmwasm_std::data(&result::unwrap(reply_result));
+                if (!vector::is_empty(&data)) {
+                    cosmwasm_std::set_data(&mut response, data);
+                };
+            };
+            i = i + 1;
+        };
+        ok(response)
+    }
+
+    /// Execute the submessage, the changes of the submessage are reverted if it failed.
+    fun execute_sub_msg(
+        instance: &mut Instance,
+        env: &Env,
+        sub_msg: &SubMsg,
+        call_stack: &vector<address>,
+        depth: u64,
+        journal: &mut vector<JournalEntry>,
+    ): SubMsgResult {
+        if (depth >= MAX_DISPATCH_DEPTH) {
+            return cosmwasm_std::new_sub_msg_error(string::utf8(b"max dispatch depth exceeded"))
+        };
+        let msg = cosmwasm_std::parse_cosmos_msg(cosmwasm_std::sub_msg_msg(sub_msg));
+        if (option::is_none(&msg)) {
+            return cosmwasm_std::new_sub_msg_error(string::utf8(b"invalid message"))
+        };
+        let msg = option::destroy_some(msg);
+        let savepoint = vector::length(journal);
+        let result = if (cosmwasm_std::is_bank_send(&msg)) {
+            execute_bank_send(instance, msg, journal)
+        } else if (cosmwasm_std::is_wasm_execute(&msg)) {
+            let gas_limit = option::destroy_with_default(cosmwasm_std::sub_msg_gas_limit(sub_msg), DEFAULT_GAS_LIMIT);
+            execute_wasm_execute(instance, env, msg, gas_limit, call_stack, depth, journal)
+        } else {
+            cosmwasm_std::new_sub_msg_error(string::utf8(b"unsupported message"))
+        };
+        if (option::is_some(&cosmwasm_std::sub_msg_result_error(&result))) {
+            revert_journal(journal, savepoint);
+        };
+        result
+    }
+
+    fun execute_bank_send(instance: &mut Instance, msg: CosmosMsg, journal: &mut vector<JournalEntry>): SubMsgResult {
+        let (to_address, amount) = cosmwasm_std::unpack_bank_send(msg);
+        let from_address = contract_address(instance);
+        let error = check_funds(from_address, to_address, &amount);
+        if (option::is_some(&error)) {
+            return cosmwasm_std::new_sub_msg_error(option::destroy_some(error))
+        };
+        let contract_signer = account::create_signer_with_account_cap(&mut instance.account);
+        transfer_coins(&contract_signer, to_address, &amount);
+        vector::push_back(journal, new_transfer_entry(from_address, to_address, amount));
+        cosmwasm_std::new_sub_msg_result_ok(vector::empty(), vector::singleton(cosmwasm_std::new_msg_send_response()))
+    }
+
+    fun execute_wasm_execute(
+        instance: &mut Instance,
+        env: &Env,
+        msg: CosmosMsg,
+        gas_limit: u64,
+        call_stack: &vector<address>,
+        depth: u64,
+        journal: &mut vector<JournalEntry>,
+    ): SubMsgResult {
+        let (contract_addr, execute_msg, funds) = cosmwasm_std::unpack_wasm_execute(msg);
+        if (vector::contains(call_stack, &contract_addr)) {
+            return cosmwasm_std::new_sub_msg_error(string::utf8(b"reentrant contract call"))
+        };
+        let instance_id = registered_instance_id(contract_addr);
+        if (option::is_none(&instance_id)) {
+            return cosmwasm_std::new_sub_msg_error(string::utf8(b"no such contract"))
+        };
+        let caller_addr = contract_address(instance);
+        let error = check_funds(caller_addr, contract_addr, &funds);
+        if (option::is_some(&error)) {
+            return cosmwasm_std::new_sub_msg_error(option::destroy_some(error))
+        };
+
+        let target = object::borrow_mut(object::borrow_mut_object_extend<Instance>(option::destroy_some(instance_id)));
+        let caller_signer = account::create_signer_with_account_cap(&mut instance.account);
+        transfer_coins(&caller_signer, contract_addr, &funds);
+        vector::push_back(journal, new_transfer_entry(caller_addr, contract_addr, funds));
+
+        let target_env = cosmwasm_std::with_contract_address(env, contract_addr);
+        let info = cosmwasm_std::new_message_info(caller_addr, funds);
+        let execute_result = call_execute_raw(target, &target_env, &info, execute_msg, gas_limit, journal);
+        if (result::is_err(&execute_result)) {
+            let error = result::unwrap_err(execute_result);
+            return cosmwasm_std::new_sub_msg_error(cosmwasm_std::error_message(&error))
+        };
+        let response = result::unwrap(execute_result);
+        let sub_msg_events = cosmwasm_std::contract_events(contract_addr, &response);
+        let target_call_stack = *call_stack;
+        vector::push_back(&mut target_call_stack, contract_addr);
+        let dispatch_result = dispatch_messages(target, &target_env, response, &target_call_stack, depth + 1, &mut sub_msg_events, journal);
+        if (result::is_err(&dispatch_result)) {
+            return cosmwasm_std::new_sub_msg_error(result::unwrap_err(dispatch_result))
+        };
+        let data = cosmwasm_std::data(&result::unwrap(dispatch_result));
+        cosmwasm_std::new_sub_msg_result_ok(
+            sub_msg_events, vector::singleton(cosmwasm_std::new_msg_execute_contract_response(data)))
+    }
+
+    /// Check that the account `from` has all the `amount` and the account `to` accepts them,
+    /// returns the error if the coins can not be transferred.
+    fun check_funds(from: address, to: address, amount: &vector<Coin>): Option<String> {
+        let i = 0;
+        let len = vector::length(amount);
+        while (i < len) {
+            let (denom, _) = cosmwasm_std::unpack_coin(*vector::borrow(amount, i));
+            // The same denom may appear more than once, so check the total of the denom
+            let total = 0u256;
+            let j = 0;
+            while (j < len) {
+                let (coin_denom, value) = cosmwasm_std::unpack_coin(*vector::borrow(amount, j));
+                if (coin_denom == denom) {
+                    total = total + (value as u256);
+                };
+                j = j + 1;
+            };
+            if (account_coin_store::balance_by_type_name(from, denom) < total) {
+                return option::some(string::utf8(b"insufficient funds"))
+            };
+            if (!account_coin_store::is_accept_coin_by_type_name(to, denom)) {
+                return option::some(string::utf8(b"recipient does not accept the coin"))
+            };
+            i = i + 1;
+        };
+        option::none()
+    }
+
+    fun transfer_coins(from: &signer, to: address, amount: &vector<Coin>) {
+        let i = 0;
+        let len = vector::length(amount);
+        while (i < len) {
+            let (denom, value) = cosmwasm_std::unpack_coin(*vector::borrow(amount, i));
+            if (value > 0) {
+                account_coin_store::transfer_by_type_name(from, to, denom, (value as u256));
+            };
+            i = i + 1;
+        };
+    }
+
     // Native function declarations
     native fun native_create_instance(code: vector<u8>, store_handle: ObjectID): (vector<u8>, u32);
     native fun native_destroy_instance(code_checksum: vector<u8>): u32;
-    native fun native_call_instantiate_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, info: vector<u8>, msg: vector<u8>): (vector<u8>, u32);
-    native fun native_call_execute_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, info: vector<u8>, msg: vector<u8>): (vector<u8>, u32);
-    native fun native_call_query_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, u32);
-    native fun native_call_migrate_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, u32);
-    native fun native_call_reply_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, u32);
-    native fun native_call_sudo_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>):(vector<u8>, u32);
-}
\ No newline at end of file
+    // The contract calls return the result, the undo log of the writes of the call and the error code
+    native fun native_call_instantiate_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, info: vector<u8>, msg: vector<u8>): (vector<u8>, vector<u8>, u32);
+    native fun native_call_execute_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, info: vector<u8>, msg: vector<u8>, gas_limit: u64): (vector<u8>, vector<u8>, u32);
+    native fun native_call_query_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, vector<u8>, u32);
+    native fun native_call_migrate_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, vector<u8>, u32);
+    native fun native_call_reply_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>): (vector<u8>, vector<u8>, u32);
+    native fun native_call_sudo_raw(code_checksum: vector<u8>, store_handle: ObjectID, env: vector<u8>, msg: vector<u8>):(vector<u8>, vector<u8>, u32);
+    native fun native_revert_store_writes(store_handle: ObjectID, undo_log: vector<u8>);
+
+    #[test_only]
+    use moveos_std::tx_context;
+    #[test_only]
+    use moveos_std::type_info;
+    #[test_only]
+    use moveos_std::address;
+    #[test_only]
+    use moveos_std::string_utils;
+    #[test_only]
+    use kanari_framework::gas_coin::{Self, RGas};
+
+    #[test_only]
+    fun new_instance_for_test(): Instance {
+        Instance {
+            code_checksum: vector::empty(),
+            store: table::new<String, vector<u8>>(),
+            account: account::create_account_and_return_cap(),
+        }
+    }
+
+    #[test_only]
+    fun drop_instance_for_test(instance: Instance) {
+        let Instance { code_checksum: _, store, account } = instance;
+        table::drop(store);
+        account::destroy_account_cap(account);
+    }
+
+    #[test_only]
+    fun gas_coins(amounts: vector<u128>): vector<Coin> {
+        let coins = vector::empty();
+        while (!vector::is_empty(&amounts)) {
+            let amount = vector::pop_back(&mut amounts);
+            vector::push_back(&mut coins, cosmwasm_std::new_coin(type_info::type_name<RGas>(), amount));
+        };
+        coins
+    }
+
+    #[test_only]
+    /// Execute the submessage with the current env and a new journal, returns the error of the submessage.
+    fun execute_sub_msg_for_test(instance: &mut Instance, sub_msg: &SubMsg, call_stack: &vector<address>, depth: u64): Option<String> {
+        let journal = vector::empty();
+        let result = execute_sub_msg(instance, &cosmwasm_std::current_env(), sub_msg, call_stack, depth, &mut journal);
+        cosmwasm_std::sub_msg_result_error(&result)
+    }
+
+    #[test_only]
+    /// Returns the WAT of a contract whose `execute` writes the `key` to its store and returns the `execute_result`,
+    /// and whose `reply` writes the reply message under the `key` and returns the `reply_result`.
+    fun contract_wat(key: vector<u8>, execute_result: vector<u8>, reply_result: vector<u8>): vector<u8> {
+        let key_len = *string::bytes(&string_utils::to_string_u64(vector::length(&key)));
+        let key_region = b"(call $region (i32.const 1024) (i32.const ";
+        vector::append(&mut key_region, key_len);
+        vector::append(&mut key_region, b"))");
+
+        let wat = b"(module (import \"env\" \"db_write\" (func $db_write (param i32 i32)))";
+        vector::append(&mut wat, b"(memory (export \"memory\") 4) (global $heap (mut i32) (i32.const 65536))");
+        vector::append(&mut wat, b"(func $region (param $offset i32) (param $length i32) (result i32) (local $region i32)");
+        vector::append(&mut wat, b" (local.set $region (global.get $heap)) (global.set $heap (i32.add (global.get $heap) (i32.const 12)))");
+        vector::append(&mut wat, b" (i32.store (local.get $region) (local.get $offset)) (i32.store offset=4 (local.get $region) (local.get $length))");
+        vector::append(&mut wat, b" (i32.store offset=8 (local.get $region) (local.get $length)) (local.get $region))");
+        vector::append(&mut wat, b"(func (export \"allocate\") (param $size i32) (result i32) (local $region i32)");
+        vector::append(&mut wat, b" (local.set $region (call $region (i32.add (global.get $heap) (i32.const 12)) (local.get $size)))");
+        vector::append(&mut wat, b" (i32.store offset=8 (local.get $region) (i32.const 0))");
+        vector::append(&mut wat, b" (global.set $heap (i32.add (global.get $heap) (local.get $size))) (local.get $region))");
+        vector::append(&mut wat, b"(func (export \"deallocate\") (param i32))");
+        vector::append(&mut wat, b"(func (export \"interface_version_8\"))");
+        vector::append(&mut wat, b"(func (export \"execute\") (param i32 i32 i32) (result i32) (call $db_write ");
+        vector::append(&mut wat, key_region);
+        vector::append(&mut wat, key_region);
+        vector::append(&mut wat, b") ");
+        vector::append(&mut wat, data_region(4096, &execute_result));
+        vector::append(&mut wat, b")(func (export \"reply\") (param i32 i32) (result i32) (call $db_write ");
+        vector::append(&mut wat, key_region);
+        vector::append(&mut wat, b" (local.get 1)) ");
+        vector::append(&mut wat, data_region(32768, &reply_result));
+        vector::append(&mut wat, b")");
+        vector::append(&mut wat, data_segment(1024, key));
+        vector::append(&mut wat, data_segment(4096, execute_result));
+        vector::append(&mut wat, data_segment(32768, reply_result));
+        vector::append(&mut wat, b")");
+        wat
+    }
+
+    #[test_only]
+    fun data_region(offset: u64, data: &vector<u8>): vector<u8> {
+        let region = b"(call $region (i32.const ";
+        vector::append(&mut region, *string::bytes(&string_utils::to_string_u64(offset)));
+        vector::append(&mut region, b") (i32.const ");
+        vector::append(&mut region, *string::bytes(&string_utils::to_string_u64(vector::length(data))));
+        vector::append(&mut region, b"))");
+        region
+    }
+
+    #[test_only]
+    /// Returns the data segment of the `data` at the `offset`, every byte is hex escaped.
+    fun data_segment(offset: u64, data: vector<u8>): vector<u8> {
+        let hex_chars = b"0123456789abcdef";
+        let segment = b"(data (i32.const ";
+        vector::append(&mut segment, *string::bytes(&string_utils::to_string_u64(offset)));
+        vector::append(&mut segment, b") \"");
+        vector::for_each(data, |byte| {
+            vector::push_back(&mut segment, 0x5c); // backslash
+            vector::push_back(&mut segment, *vector::borrow(&hex_chars, ((byte >> 4) as u64)));
+            vector::push_back(&mut segment, *vector::borrow(&hex_chars, ((byte & 0x0f) as u64)));
+        });
+        vector::append(&mut segment, b"\")");
+        segment
+    }
+
+    #[test_only]
+    fun contract_for_test(key: vector<u8>, execute_result: vector<u8>, reply_result: vector<u8>): Instance {
+        result::unwrap(from_code(contract_wat(key, execute_result, reply_result)))
+    }
+
+    #[test_only]
+    fun response_with_message(msg: vector<u8>, reply_on: u8): Response {
+        let response = cosmwasm_std::new_response();
+        cosmwasm_std::add_message(&mut response, cosmwasm_std::new_sub_msg(1, msg, option::none(), reply_on));
+        response
+    }
+
+    #[test]
+    fun test_bank_send_from_contract_account() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(contract_addr, 100);
+
+        let msg = cosmwasm_std::new_bank_send_msg(@0x43, gas_coins(vector[60]));
+        dispatch_response(&mut instance, &cosmwasm_std::current_env(), response_with_message(msg, 0));
+        assert!(gas_coin::balance(contract_addr) == 40, 1);
+        assert!(gas_coin::balance(@0x43) == 60, 2);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = ErrorSubMsgFailed, location = Self)]
+    fun test_contract_can_not_spend_caller_coins() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        // The caller has the coins, but the contract account has none
+        gas_coin::faucet_for_test(tx_context::sender(), 100);
+
+        let msg = cosmwasm_std::new_bank_send_msg(@0x43, gas_coins(vector[60]));
+        dispatch_response(&mut instance, &cosmwasm_std::current_env(), response_with_message(msg, 0));
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_bank_send_insufficient_funds_has_no_changes() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(tx_context::sender(), 100);
+        gas_coin::faucet_for_test(contract_addr, 50);
+
+        // Every coin is covered by the balance, but the total of the denom is not
+        let msg = cosmwasm_std::new_bank_send_msg(@0x43, gas_coins(vector[30, 30]));
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::none(), 0);
+        let error = execute_sub_msg_for_test(&mut instance, &sub_msg, &vector[contract_addr], 0);
+        assert!(error == option::some(string::utf8(b"insufficient funds")), 1);
+        assert!(gas_coin::balance(contract_addr) == 50, 2);
+        assert!(gas_coin::balance(tx_context::sender()) == 100, 3);
+        assert!(gas_coin::balance(@0x43) == 0, 4);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_wasm_execute_reentrant_call() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        // The contract `@0x44` calls this contract, which calls `@0x44` back
+        let msg = cosmwasm_std::new_wasm_execute_msg(@0x44, b"{}", vector::empty());
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::none(), 0);
+        let error = execute_sub_msg_for_test(&mut instance, &sub_msg, &vector[@0x44, contract_addr], 1);
+        assert!(error == option::some(string::utf8(b"reentrant contract call")), 1);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_wasm_execute_no_such_contract() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(contract_addr, 100);
+
+        let msg = cosmwasm_std::new_wasm_execute_msg(@0x44, b"{}", gas_coins(vector[60]));
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::none(), 0);
+        let error = execute_sub_msg_for_test(&mut instance, &sub_msg, &vector[contract_addr], 0);
+        assert!(error == option::some(string::utf8(b"no such contract")), 1);
+        assert!(gas_coin::balance(contract_addr) == 100, 2);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_wasm_execute_funds_insufficient() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        let target = new_instance_for_test();
+        let target_addr = contract_address(&target);
+        register_instance(target, @0x42);
+        // The caller of the contract has the coins, but the funds are sent from the contract account
+        gas_coin::faucet_for_test(tx_context::sender(), 100);
+
+        let msg = cosmwasm_std::new_wasm_execute_msg(target_addr, b"{}", gas_coins(vector[60]));
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::some(1000), 0);
+        let error = execute_sub_msg_for_test(&mut instance, &sub_msg, &vector[contract_addr], 0);
+        assert!(error == option::some(string::utf8(b"insufficient funds")), 1);
+        assert!(gas_coin::balance(target_addr) == 0, 2);
+        assert!(gas_coin::balance(tx_context::sender()) == 100, 3);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_max_dispatch_depth() {
+        kanari_framework::genesis::init_for_test();
+        let instance = new_instance_for_test();
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(contract_addr, 100);
+
+        let msg = cosmwasm_std::new_bank_send_msg(@0x43, gas_coins(vector[60]));
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::none(), 0);
+        let error = execute_sub_msg_for_test(&mut instance, &sub_msg, &vector[contract_addr], MAX_DISPATCH_DEPTH);
+        assert!(error == option::some(string::utf8(b"max dispatch depth exceeded")), 1);
+        assert!(gas_coin::balance(contract_addr) == 100, 2);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_wasm_execute_returns_events_and_data() {
+        kanari_framework::genesis::init_for_test();
+        features::change_feature_flags_for_test(vector[features::get_wasm_feature()], vector[]);
+        let target = contract_for_test(b"target",
+            b"{\"ok\":{\"attributes\":[{\"key\":\"action\",\"value\":\"execute\"}],\"events\":[{\"type\":\"transfer\",\"attributes\":[{\"key\":\"amount\",\"value\":\"10\"}]}],\"data\":\"ZGF0YQ==\"}}",
+            b"{\"ok\":{}}");
+        let target_addr = contract_address(&target);
+        let target_id = register_instance(target, @0x42);
+        let instance = contract_for_test(b"caller", b"{\"ok\":{}}", b"{\"ok\":{}}");
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(contract_addr, 100);
+
+        let msg = cosmwasm_std::new_wasm_execute_msg(target_addr, b"{}", gas_coins(vector[10]));
+        let sub_msg = cosmwasm_std::new_sub_msg(1, msg, option::none(), 1);
+        let journal = vector::empty();
+        let result = execute_sub_msg(&mut instance, &cosmwasm_std::current_env(), &sub_msg, &vector[contract_addr], 0, &mut journal);
+
+        let contract_attribute = cosmwasm_std::new_attribute(
+            string::utf8(b"_contract_address"), address::to_bech32_string(target_addr));
+        let expected_events = vector[
+            cosmwasm_std::new_event(string::utf8(b"wasm"), vector[
+                contract_attribute, cosmwasm_std::new_attribute(string::utf8(b"action"), string::utf8(b"execute"))]),
+            cosmwasm_std::new_event(string::utf8(b"wasm-transfer"), vector[
+                contract_attribute, cosmwasm_std::new_attribute(string::utf8(b"amount"), string::utf8(b"10"))]),
+        ];
+        let expected_responses = vector[cosmwasm_std::new_msg_execute_contract_response(b"data")];
+        assert!(result == cosmwasm_std::new_sub_msg_result_ok(expected_events, expected_responses), 1);
+        // The transfer of the funds and the writes of the target are recorded
+        assert!(vector::length(&journal) == 2, 2);
+        assert!(gas_coin::balance(target_addr) == 10, 3);
+        assert!(table::length(&object::borrow(object::borrow_object<Instance>(target_id)).store) == 1, 4);
+        drop_instance_for_test(instance);
+    }
+
+    #[test]
+    fun test_failed_sub_msg_is_reverted_and_replied() {
+        kanari_framework::genesis::init_for_test();
+        features::change_feature_flags_for_test(vector[features::get_wasm_feature()], vector[]);
+        // The target writes its store, then sends the coins it does not have
+        let execute_result = b"{\"ok\":{\"messages\":[{\"id\":0,\"msg\":";
+        vector::append(&mut execute_result, cosmwasm_std::new_bank_send_msg(@0x43, gas_coins(vector[60])));
+        vector::append(&mut execute_result, b",\"reply_on\":\"never\"}]}}");
+        let target = contract_for_test(b"target", execute_result, b"{\"ok\":{}}");
+        let target_addr = contract_address(&target);
+        let target_id = register_instance(target, @0x42);
+        // The caller sends the funds to the target, and handles the error in the reply
+        let instance = contract_for_test(b"caller", b"{\"ok\":{}}", b"{\"ok\":{\"data\":\"cmVwbGllZA==\"}}");
+        let contract_addr = contract_address(&instance);
+        gas_coin::faucet_for_test(contract_addr, 100);
+
+        let msg = cosmwasm_std::new_wasm_execute_msg(target_addr, b"{}", gas_coins(vector[10]));
+        let response = dispatch_response(&mut instance, &cosmwasm_std::current_env(), response_with_message(msg, 2));
+        assert!(cosmwasm_std::data(&response) == b"replied", 1);
+        // The funds and the writes of the target are reverted
+        assert!(gas_coin::balance(contract_addr) == 100, 2);
+        assert!(gas_coin::balance(target_addr) == 0, 3);
+        assert!(gas_coin::balance(@0x43) == 0, 4);
+        assert!(table::length(&object::borrow(object::borrow_object<Instance>(target_id)).store) == 0, 5);
+        // The reply of the caller is executed and its writes are kept
+        assert!(table::length(&instance.store) == 1, 6);
+        drop_instance_for_test(instance);
+    }
+}
@@ -23,6 +23,7 @@ module kanari_nursery::inscribe_factory {
     use kanari_nursery::bitseed::{Self, Bitseed};
     use kanari_nursery::tick_info;
     use kanari_nursery::wasm;
+    use kanari_nursery::mint_get_factory;
 
     friend kanari_nursery::genesis;
 
@@ -176,6 +177,10 @@ module kanari_nursery::inscribe_factory {
             return err_str(b"metadata.tick must be 4-32 characters")
         };
 
+        if (!is_valid_tick_chars(option::borrow(&tick))) {
+            return err_str(b"metadata.tick must only contain ASCII letters, digits and underscores")
+        };
+
         let amount = get_SFT_amount_option(metadata);
         if (option::is_none(&amount)) {
             return err_str(b"metadata.amount is required")
@@ -183,12 +188,32 @@ module kanari_nursery::inscribe_factory {
         ok(true)
     }
 
+    /// The tick is case insensitive, only ASCII letters, digits and `_` are allowed
+    fun is_valid_tick_chars(tick: &String) : bool {
+        let bytes = string::bytes(tick);
+        let len = vector::length(bytes);
+        let i = 0;
+        while (i < len) {
+            let c = *vector::borrow(bytes, i);
+            let is_valid = (c >= 0x30 && c <= 0x39) || (c >= 0x41 && c <= 0x5a) || (c >= 0x61 && c <= 0x7a) || c == 0x5f;
+            if (!is_valid) {
+                return false
+            };
+            i = i + 1;
+        };
+        true
+    }
+
     fun is_valid_bitseed_deploy(metadata: &SimpleMap<String,vector<u8>>) : Result<bool,String> {
         let is_valid_result = is_valid_bitseed(metadata);
         if (is_err(&is_valid_result)) {
             return is_valid_result
         };
 
+        if (tick_info::is_deployed(bitseed::default_metaprotocol(), get_SFT_tick(metadata))) {
+            return err_str(b"metadata.tick is already deployed")
+        };
+
         let attributes = get_SFT_attributes(metadata);
 
         let generator = get_SFT_string_attribute(&attributes, b"generator");
@@ -210,7 +235,10 @@ module kanari_nursery::inscribe_factory {
             };
         };
         if (option::is_some(&factory)) {
-            //TODO check factory
+            // Only the MintGetFactory is supported now
+            if (*option::borrow(&factory) != mint_get_factory::factory_type()) {
+                return err_str(b"metadata.attributes.factory is not supported")
+            };
         };
 
         
@@ -734,6 +762,46 @@ module kanari_nursery::inscribe_factory {
         assert!(result::unwrap_err(is_valid_result) == std::string::utf8(b"metadata.tick must be 4-32 characters"), 1);
     }
 
+    #[test]
+    fun test_is_valid_bitseed_deploy_fail_for_tick_invalid_chars(){
+        // tick: "te$t"
+        let metadata_bytes = x"a4626f70666465706c6f79647469636b647465247466616d6f756e74016a61747472696275746573a16967656e657261746f72784f2f696e736372697074696f6e2f653839633162343830356538626235303236323038373632326263656662383533343232356364376138633264343832366433366630633161653333303831316931";
+        let metadata = cbor::to_map(metadata_bytes);
+        let is_valid_result = is_valid_bitseed_deploy(&metadata);
+
+        assert!(is_err(&is_valid_result), 1);
+        assert!(result::unwrap_err(is_valid_result) == std::string::utf8(b"metadata.tick must only contain ASCII letters, digits and underscores"), 1);
+    }
+
+    #[test(genesis_account=@0x4)]
+    fun test_is_valid_bitseed_deploy_fail_for_tick_already_deployed(genesis_account: &signer){
+        tick_info::init_for_testing();
+
+        let (_test_address, test_inscription_id) = ord::setup_inscription_for_test<Bitseed>(genesis_account, bitseed::default_metaprotocol());
+        bitseed::seal_metaprotocol_validity(test_inscription_id, true, option::none());
+
+        let metadata_bytes = x"a4626f70666465706c6f79647469636b646d6f766566616d6f756e74016a61747472696275746573a16967656e657261746f72784f2f696e736372697074696f6e2f373764666332666535393834313962303036343163323936313831613936636631363934333639376635373334383062303233623737636365383261646132316930";
+        let metadata = cbor::to_map(metadata_bytes);
+        assert!(is_ok(&is_valid_bitseed_deploy(&metadata)), 1);
+        let (ok, _reason) = deploy_tick(&metadata);
+        assert!(ok, 2);
+
+        let is_valid_result = is_valid_bitseed_deploy(&metadata);
+        assert!(is_err(&is_valid_result), 3);
+        assert!(result::unwrap_err(is_valid_result) == std::string::utf8(b"metadata.tick is already deployed"), 4);
+    }
+
+    #[test]
+    fun test_is_valid_bitseed_deploy_fail_for_factory_not_supported(){
+        // attributes: {"factory": "0x1::foo::Bar"}
+        let metadata_bytes = x"a4626f70666465706c6f79647469636b647465737466616d6f756e74016a61747472696275746573a167666163746f72796d3078313a3a666f6f3a3a426172";
+        let metadata = cbor::to_map(metadata_bytes);
+        let is_valid_result = is_valid_bitseed_deploy(&metadata);
+
+        assert!(is_err(&is_valid_result), 1);
+        assert!(result::unwrap_err(is_valid_result) == std::string::utf8(b"metadata.attributes.factory is not supported"), 1);
+    }
+
     #[test]
     fun test_is_valid_bitseed_deploy_fail_for_amount_not_found(){
         let metadata_bytes = x"a4626f70666465706c6f79647469636b68746573745469636b66616d6f756e74f76a61747472696275746573a16967656e657261746f72784f2f696e736372697074696f6e2f653839633162343830356538626235303236323038373632326263656662383533343232356364376138633264343832366433366630633161653333303831316931";
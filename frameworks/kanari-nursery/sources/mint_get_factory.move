@@ -7,7 +7,7 @@ module kanari_nursery::mint_get_factory {
     use std::string::String;
     use moveos_std::object::{Self, Object};
     use moveos_std::tx_context;
-    use moveos_std::bcs;
+    use moveos_std::cbor;
     use moveos_std::type_info;
     use kanari_nursery::tick_info;
     use kanari_nursery::bitseed::{Bitseed};
@@ -37,7 +37,8 @@ module kanari_nursery::mint_get_factory {
         let deploy_args = tick_info::deploy_args(tick_info);
         let amount_per_mint = if(option::is_some(&deploy_args)){
             let deploy_args_bytes = option::destroy_some(deploy_args);
-            let deploy_args = bcs::from_bytes_option<DeployArgs>(deploy_args_bytes);
+            // The deploy_args is the CBOR bytes of the deploy inscription's `deploy_args` attribute
+            let deploy_args = cbor::from_cbor_option<DeployArgs>(deploy_args_bytes);
             if(option::is_some(&deploy_args)){
                 let deploy_args = option::destroy_some(deploy_args);
                 deploy_args.amount_per_mint
@@ -61,4 +62,13 @@ module kanari_nursery::mint_get_factory {
         let bitseed = do_mint(std::string::utf8(b"bitseed"), std::string::utf8(b"test"));
         object::transfer(bitseed, tx_context::sender());
     }
+
+    #[test]
+    fun test_mint_with_deploy_args(){
+        let deploy_args = cbor::to_cbor(&DeployArgs{ amount_per_mint: 500 });
+        tick_info::deploy_for_testing(std::string::utf8(b"bitseed"), std::string::utf8(b"test"), option::none(), option::some(factory_type()), 100000, 0, option::some(deploy_args));
+        let bitseed = do_mint(std::string::utf8(b"bitseed"), std::string::utf8(b"test"));
+        assert!(kanari_nursery::bitseed::amount(object::borrow(&bitseed)) == 500, 1);
+        object::transfer(bitseed, tx_context::sender());
+    }
 }
\ No newline at end of file
@@ -1,21 +1,24 @@
 // Copyright (c) Kanari Network
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 use std::vec;
 use tracing::error;
 
-use cosmwasm_std::Checksum;
+use cosmwasm_std::{from_json, Binary, Checksum, ContractResult, SystemError, SystemResult};
 use cosmwasm_vm::{
     call_execute_raw, call_instantiate_raw, call_migrate_raw, call_query_raw, call_reply_raw,
-    call_sudo_raw, capabilities_from_csv, Cache, CacheOptions, Instance, InstanceOptions, Size,
-    VmResult,
+    call_sudo_raw, capabilities_from_csv, Backend, BackendError, BackendResult, Cache,
+    CacheOptions, GasInfo, Instance, InstanceOptions, Size, Storage, VmError, VmResult,
 };
-use once_cell::sync::Lazy;
 use kanari_cosmwasm_vm::backend::{
-    build_mock_backend, MockStorage, MoveBackendApi, MoveBackendQuerier,
+    build_move_backend, decode_undo_log, encode_undo_log, MoveBackendApi, MoveBackendQuerier,
+    MoveStorage, ObjectRuntimeLoader, QueryState, RegisteredContract, StateStorage,
+    WasmSmartQuerier,
 };
+use once_cell::sync::Lazy;
+use serde_json::Value as JSONValue;
 use smallvec::smallvec;
 
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
@@ -27,18 +30,22 @@ use move_vm_types::natives::function::NativeResult;
 use move_vm_types::pop_arg;
 use move_vm_types::values::Value;
 
-use moveos_object_runtime::{
-    runtime::ObjectRuntimeContext, runtime_object::RuntimeObject, TypeLayoutLoader,
-};
-use moveos_types::{moveos_std::object::ObjectID, state_resolver::StatelessResolver};
+use moveos_object_runtime::runtime::ObjectRuntimeContext;
+use moveos_types::moveos_std::object::ObjectID;
 
 use moveos_stdlib::natives::helpers::{make_module_natives, make_native};
 
 use crate::natives::helper::{pop_object_id, CommonGasParametersOption};
 
 const DEFAULT_GAS_LIMIT: u64 = 10000000;
+/// The max depth of the nested `WasmQuery::Smart` queries
+const MAX_QUERY_DEPTH: usize = 8;
+/// The max times a call runs to load the state queried by the contracts, see `QueryState`
+const MAX_QUERY_STATE_ROUNDS: usize = 16;
 
-static WASM_CACHE: Lazy<Arc<Cache<MoveBackendApi, MockStorage, MoveBackendQuerier>>> =
+type ContractInstance = Instance<MoveBackendApi, MoveStorage, MoveBackendQuerier>;
+
+static WASM_CACHE: Lazy<Arc<Cache<MoveBackendApi, MoveStorage, MoveBackendQuerier>>> =
     Lazy::new(|| {
         let options = CacheOptions::new(
             std::env::temp_dir(),
@@ -49,6 +56,134 @@ static WASM_CACHE: Lazy<Arc<Cache<MoveBackendApi, MockStorage, MoveBackendQuerie
         Arc::new(unsafe { Cache::new(options).unwrap() })
     });
 
+/// Creates an instance of the contract code with the `backend`, and runs `f` on it.
+/// Returns the result of `f` with the storage of the backend, and the gas used by the instance.
+fn with_instance<S, R>(
+    checksum: &Checksum,
+    backend: Backend<MoveBackendApi, S, MoveBackendQuerier>,
+    gas_limit: u64,
+    f: impl FnOnce(&mut Instance<MoveBackendApi, S, MoveBackendQuerier>) -> VmResult<R>,
+) -> (VmResult<(R, S)>, u64)
+where
+    S: Storage + 'static,
+{
+    let mut instance = match WASM_CACHE.get_module(checksum).and_then(|(module, store)| {
+        Instance::from_module(store, &module, backend, gas_limit, None, None)
+    }) {
+        Ok(instance) => instance,
+        Err(e) => return (Err(e), 0),
+    };
+    let result = f(&mut instance);
+    let report = instance.create_gas_report();
+    let gas_used = report
+        .used_internally
+        .saturating_add(report.used_externally)
+        .min(gas_limit);
+    let result = result.and_then(|result| {
+        let backend = instance
+            .recycle()
+            .ok_or_else(|| VmError::generic_err("The backend of the instance is recycled"))?;
+        Ok((result, backend.storage))
+    });
+    (result, gas_used)
+}
+
+/// Runs the `query` of the registered contracts for `WasmQuery::Smart`, the queried contract
+/// reads the snapshot of its store in the `QueryState`, with the `env` of the querying contract.
+struct ContractSmartQuerier {
+    state: Arc<QueryState>,
+    env: Option<Vec<u8>>,
+    depth: usize,
+}
+
+impl ContractSmartQuerier {
+    fn query_env(&self, contract_addr: &str) -> Result<Vec<u8>, String> {
+        let env = self
+            .env
+            .as_ref()
+            .ok_or_else(|| "Queries are not supported without env".to_string())?;
+        let mut env = serde_json::from_slice::<JSONValue>(env).map_err(|e| e.to_string())?;
+        env["contract"]["address"] = JSONValue::String(contract_addr.to_string());
+        serde_json::to_vec(&env).map_err(|e| e.to_string())
+    }
+}
+
+impl WasmSmartQuerier for ContractSmartQuerier {
+    fn query_smart(
+        &self,
+        contract_addr: &str,
+        contract: &RegisteredContract,
+        msg: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        if self.depth >= MAX_QUERY_DEPTH {
+            return (
+                Ok(SystemResult::Err(SystemError::InvalidRequest {
+                    error: "Max query depth exceeded".to_string(),
+                    request: Binary::from(msg),
+                })),
+                GasInfo::free(),
+            );
+        }
+        let env = match self.query_env(contract_addr) {
+            Ok(env) => env,
+            Err(error) => {
+                return (
+                    Ok(SystemResult::Err(SystemError::InvalidRequest {
+                        error,
+                        request: Binary::from(msg),
+                    })),
+                    GasInfo::free(),
+                )
+            }
+        };
+        let checksum = match Checksum::try_from(contract.code_checksum.as_slice()) {
+            Ok(checksum) => checksum,
+            Err(e) => {
+                return (
+                    Err(BackendError::Unknown { msg: e.to_string() }),
+                    GasInfo::free(),
+                )
+            }
+        };
+
+        let nested_querier = ContractSmartQuerier {
+            state: self.state.clone(),
+            env: self.env.clone(),
+            depth: self.depth + 1,
+        };
+        let backend = Backend {
+            api: MoveBackendApi,
+            storage: StateStorage::new(contract.store_fields.clone()),
+            querier: MoveBackendQuerier::new(self.state.clone(), Some(Arc::new(nested_querier))),
+        };
+
+        let (result, gas_used) = with_instance(&checksum, backend, gas_limit, |instance| {
+            call_query_raw(instance, &env, msg)
+        });
+        match result {
+            Ok((response, _storage)) => {
+                let result = match from_json::<ContractResult<Binary>>(&response) {
+                    Ok(result) => SystemResult::Ok(result),
+                    Err(e) => SystemResult::Err(SystemError::InvalidResponse {
+                        error: e.to_string(),
+                        response: Binary::from(response),
+                    }),
+                };
+                (Ok(result), GasInfo::with_cost(gas_used))
+            }
+            Err(VmError::GasDepletion { .. }) => (
+                Err(BackendError::OutOfGas {}),
+                GasInfo::with_cost(gas_limit),
+            ),
+            Err(e) => (
+                Ok(SystemResult::Ok(ContractResult::Err(e.to_string()))),
+                GasInfo::with_cost(gas_used),
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CosmWasmCreateInstanceGasParametersOption {
     pub base: Option<InternalGas>,
@@ -101,11 +236,7 @@ pub fn native_create_instance(
         context,
         store_obj_id,
         wasm_code,
-        move |_layout_loader,
-              _resolver,
-              _rt_obj,
-              wasm_bytes|
-              -> PartialVMResult<(Value, Option<Option<NumBytes>>)> {
+        move |wasm_bytes| -> PartialVMResult<(Value, Option<Option<NumBytes>>)> {
             // wat2 wasm bytes
             let bytecode = wasmer::wat2wasm(wasm_bytes.as_slice()).map_err(|e| {
                 PartialVMError::new(StatusCode::STORAGE_ERROR)
@@ -118,31 +249,15 @@ pub fn native_create_instance(
                     .with_message(format!("Failed to save WASM: {}", e))
             })?;
 
-            //TODO Create real Backend
-            let backend = build_mock_backend();
-
-            // Create WASM instance
-            let instance_options = InstanceOptions {
-                gas_limit: DEFAULT_GAS_LIMIT,
-            };
-
-            let (module, store) = WASM_CACHE.get_module(&checksum).map_err(|e| {
-                PartialVMError::new(StatusCode::STORAGE_ERROR)
-                    .with_message(format!("Failed to get WASM module: {}", e))
-            })?;
-
-            let _ = Instance::from_module(
-                store,
-                &module,
-                backend,
-                instance_options.gas_limit,
-                None,
-                None,
-            )
-            .map_err(|e| {
-                PartialVMError::new(StatusCode::STORAGE_ERROR)
-                    .with_message(format!("Failed to get WASM instance: {}", e))
-            })?;
+            // Create a WASM instance to validate the code
+            let backend =
+                build_move_backend(BTreeMap::new(), Arc::new(QueryState::default()), None);
+            with_instance(&checksum, backend, DEFAULT_GAS_LIMIT, |_instance| Ok(()))
+                .0
+                .map_err(|e| {
+                    PartialVMError::new(StatusCode::STORAGE_ERROR)
+                        .with_message(format!("Failed to get WASM instance: {}", e))
+                })?;
 
             Ok((
                 Value::vector_u8(checksum.as_slice().to_vec()),
@@ -159,24 +274,18 @@ fn wasm_instance_fn_dispatch(
     context: &mut NativeContext,
     store_obj_id: ObjectID,
     wasm_bytes: Vec<u8>,
-    f: impl FnOnce(
-        &dyn TypeLayoutLoader,
-        &dyn StatelessResolver,
-        &mut RuntimeObject,
-        Vec<u8>,
-    ) -> PartialVMResult<(Value, Option<Option<NumBytes>>)>,
+    f: impl FnOnce(Vec<u8>) -> PartialVMResult<(Value, Option<Option<NumBytes>>)>,
 ) -> PartialVMResult<NativeResult> {
     let object_context = context.extensions().get::<ObjectRuntimeContext>();
     let binding = object_context.object_runtime();
     let mut object_runtime = binding.write();
-    let resolver = object_runtime.resolver();
-    let (rt_obj, object_load_gas) = object_runtime.load_object(context, &store_obj_id)?;
+    let (_, object_load_gas) = object_runtime.load_object(context, &store_obj_id)?;
     let wasm_bytes_len = wasm_bytes.len() as u64;
     let gas_cost = base
         + per_byte_wasm * NumBytes::new(wasm_bytes_len)
         + common_gas_params.calculate_load_cost(object_load_gas);
 
-    let result = f(context, resolver, rt_obj, wasm_bytes);
+    let result = f(wasm_bytes);
     match result {
         Ok((value, wasm_load_gas)) => Ok(NativeResult::ok(
             gas_cost + common_gas_params.calculate_load_cost(wasm_load_gas),
@@ -247,12 +356,7 @@ fn native_contract_call<F>(
     contract_operation: F,
 ) -> PartialVMResult<NativeResult>
 where
-    F: FnOnce(
-        &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
-        &[u8],
-        Option<&[u8]>,
-        &[u8],
-    ) -> VmResult<Vec<u8>>,
+    F: Fn(&mut ContractInstance, &[u8], Option<&[u8]>, &[u8]) -> VmResult<Vec<u8>>,
 {
     debug_assert!(
         ty_args.is_empty(),
@@ -267,8 +371,14 @@ where
         expected_args
     );
 
+    // The `gas_limit` of the call is the last argument of `native_call_execute_raw`
+    let gas_limit = if expected_args == 6 {
+        pop_arg!(arguments, u64).min(DEFAULT_GAS_LIMIT)
+    } else {
+        DEFAULT_GAS_LIMIT
+    };
     let msg = pop_arg!(arguments, Vec<u8>);
-    let info = if expected_args == 5 {
+    let info = if expected_args >= 5 {
         Some(pop_arg!(arguments, Vec<u8>))
     } else {
         None
@@ -277,64 +387,103 @@ where
     let store_obj_id = pop_object_id(&mut arguments)?;
     let code_checksum = pop_arg!(arguments, Vec<u8>);
 
+    let checksum = Checksum::try_from(code_checksum.as_slice()).map_err(vm_error)?;
+
     let object_context = context.extensions().get::<ObjectRuntimeContext>();
     let binding = object_context.object_runtime();
     let mut object_runtime = binding.write();
-    let _resolver = object_runtime.resolver();
-    let (_rt_obj, object_load_gas) = object_runtime.load_object(context, &store_obj_id)?;
+    let mut loader = ObjectRuntimeLoader::new(&mut object_runtime, context);
+    let store_fields = loader.load_store_fields(&store_obj_id)?;
+
+    // The contract runs on the snapshot of its store, and queries the state loaded through the
+    // object runtime, so the instance does not borrow the object runtime. A call which queries
+    // the state not loaded yet is run again after the state is loaded.
+    let instance_options = InstanceOptions { gas_limit };
+    let mut query_state = Arc::new(QueryState::default());
+    let mut wasm_gas_used = 0u64;
+    let mut rounds = 0;
+    let result = loop {
+        let smart_querier = ContractSmartQuerier {
+            state: query_state.clone(),
+            env: Some(env.clone()),
+            depth: 0,
+        };
+        let backend = build_move_backend(
+            store_fields.clone(),
+            query_state.clone(),
+            Some(Arc::new(smart_querier)),
+        );
+        let (result, gas_used) =
+            with_instance(&checksum, backend, instance_options.gas_limit, |instance| {
+                contract_operation(instance, env.as_slice(), info.as_deref(), msg.as_slice())
+            });
+        // The gas of the WASM execution is charged for every run, even if the call failed
+        wasm_gas_used = wasm_gas_used.saturating_add(gas_used);
+
+        // The backend is dropped with the instance, so the query state is not shared
+        let state = Arc::get_mut(&mut query_state)
+            .ok_or_else(|| vm_error("The query state is shared after the call"))?;
+        let missing = state.take_missing();
+        if missing.is_empty() {
+            break result;
+        }
+        rounds += 1;
+        if rounds >= MAX_QUERY_STATE_ROUNDS {
+            break Err(VmError::generic_err(format!(
+                "The call queries the state more than {} times",
+                MAX_QUERY_STATE_ROUNDS
+            )));
+        }
+        loader.load_query_state(state, missing)?;
+    };
 
-    let gas_cost = gas_params
+    // The writes of the contract are written back only if the call succeeded, and the undo log
+    // of the writes is returned, so the caller can revert them if a later message fails
+    let result = result.and_then(|(response, storage)| {
+        let undo_log = encode_undo_log(&storage.undo_log())
+            .map_err(|e| VmError::generic_err(format!("Failed to encode undo log: {}", e)))?;
+        loader
+            .write_back(&store_obj_id, storage.into_pending_writes())
+            .map_err(|e| VmError::generic_err(format!("Failed to write back storage: {}", e)))?;
+        Ok((response, undo_log))
+    });
+
+    let load_gas = loader
+        .load_costs()
+        .iter()
+        .fold(InternalGas::zero(), |gas, load_cost| {
+            gas + gas_params.common.calculate_load_cost(*load_cost)
+        });
+    let total_gas = gas_params
         .common
         .load_base
         .unwrap_or_else(InternalGas::zero)
-        + gas_params.common.calculate_load_cost(object_load_gas)
+        + load_gas
         + gas_params
             .common
-            .calculate_load_cost(Some(Some(NumBytes::new(code_checksum.len() as u64))));
-
-    let checksum = Checksum::try_from(code_checksum.as_slice()).map_err(vm_error)?;
-    let (module, store) = WASM_CACHE.get_module(&checksum).map_err(vm_error)?;
-
-    let backend = build_mock_backend();
-    let instance_options = InstanceOptions {
-        gas_limit: DEFAULT_GAS_LIMIT,
-    };
-    let mut instance = Instance::from_module(
-        store,
-        &module,
-        backend,
-        instance_options.gas_limit,
-        None,
-        None,
-    )
-    .map_err(|e| vm_error(format!("Failed to get WASM instance: {}", e)))?;
-
-    let result = contract_operation(
-        &mut instance,
-        env.as_slice(),
-        info.as_ref().map(AsRef::as_ref),
-        msg.as_slice(),
-    );
+            .calculate_load_cost(Some(Some(NumBytes::new(code_checksum.len() as u64))))
+        + InternalGas::new(wasm_gas_used);
 
     match result {
-        Ok(response) => {
-            let gas_used = instance.get_gas_left();
-            let total_gas = gas_cost + InternalGas::new(gas_used);
-            Ok(NativeResult::ok(
-                total_gas,
-                smallvec![
-                    Value::vector_u8(response),
-                    Value::u32(0) // success
-                ],
-            ))
-        }
+        Ok((response, undo_log)) => Ok(NativeResult::ok(
+            total_gas,
+            smallvec![
+                Value::vector_u8(response),
+                Value::vector_u8(undo_log),
+                Value::u32(0) // success
+            ],
+        )),
         Err(err) => {
             error!("{} error: {:?}", operation_name, err);
 
             let error_code = StatusCode::VM_EXTENSION_ERROR;
             Ok(NativeResult::ok(
-                gas_cost,
-                smallvec![Value::vector_u8(vec![]), Value::u32(error_code as u32)],
+                total_gas,
+                smallvec![
+                    Value::vector_u8(vec![]),
+                    Value::vector_u8(vec![]),
+                    Value::u32(error_code as u32)
+                ],
             ))
         }
     }
@@ -358,7 +507,7 @@ fn native_call_instantiate_raw(
         arguments,
         5, // code_checksum, store_obj_id, env, info, msg
         "call_instantiate_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               info: Option<&[u8]>,
               msg: &[u8]|
@@ -384,9 +533,9 @@ fn native_call_execute_raw(
         context,
         ty_args,
         arguments,
-        5, // code_checksum, store_obj_id, env, info, msg
+        6, // code_checksum, store_obj_id, env, info, msg, gas_limit
         "call_execute_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               info: Option<&[u8]>,
               msg: &[u8]|
@@ -412,7 +561,7 @@ fn native_call_query_raw(
         arguments,
         4, // code_checksum, store_obj_id, env, msg
         "call_query_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               _info: Option<&[u8]>,
               msg: &[u8]|
@@ -438,7 +587,7 @@ fn native_call_migrate_raw(
         arguments,
         4, // code_checksum, store_obj_id, env, msg
         "call_migrate_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               _info: Option<&[u8]>,
               msg: &[u8]|
@@ -464,7 +613,7 @@ fn native_call_reply_raw(
         arguments,
         4, // code_checksum, store_obj_id, env, msg
         "call_reply_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               _info: Option<&[u8]>,
               msg: &[u8]|
@@ -490,7 +639,7 @@ fn native_call_sudo_raw(
         arguments,
         4, // code_checksum, store_obj_id, env, msg
         "call_sudo_raw",
-        move |instance: &mut Instance<MoveBackendApi, MockStorage, MoveBackendQuerier>,
+        move |instance: &mut ContractInstance,
               env: &[u8],
               _info: Option<&[u8]>,
               msg: &[u8]|
@@ -498,6 +647,53 @@ fn native_call_sudo_raw(
     )
 }
 
+/***************************************************************************************************
+ * native_revert_store_writes
+ **************************************************************************************************/
+
+/// Restores the fields of the contract store to the values recorded in the undo log of a call,
+/// it is used to roll back the writes of the calls in a failed submessage.
+#[inline]
+fn native_revert_store_writes(
+    gas_params: &GasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(
+        ty_args.is_empty(),
+        "native_revert_store_writes expects no type arguments"
+    );
+    debug_assert_eq!(
+        arguments.len(),
+        2,
+        "native_revert_store_writes expects 2 arguments"
+    );
+
+    let undo_log = decode_undo_log(&pop_arg!(arguments, Vec<u8>))?;
+    let store_obj_id = pop_object_id(&mut arguments)?;
+
+    let object_context = context.extensions().get::<ObjectRuntimeContext>();
+    let binding = object_context.object_runtime();
+    let mut object_runtime = binding.write();
+    let mut loader = ObjectRuntimeLoader::new(&mut object_runtime, context);
+    loader.write_back(&store_obj_id, undo_log)?;
+
+    let load_gas = loader
+        .load_costs()
+        .iter()
+        .fold(InternalGas::zero(), |gas, load_cost| {
+            gas + gas_params.common.calculate_load_cost(*load_cost)
+        });
+    let total_gas = gas_params
+        .common
+        .load_base
+        .unwrap_or_else(InternalGas::zero)
+        + load_gas;
+
+    Ok(NativeResult::ok(total_gas, smallvec![]))
+}
+
 /***************************************************************************************************
  * module
  **************************************************************************************************/
@@ -562,6 +758,11 @@ pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, Nati
             "native_call_sudo_raw",
             make_native(gas_params.clone(), native_call_sudo_raw),
         ));
+
+        natives.push((
+            "native_revert_store_writes",
+            make_native(gas_params.clone(), native_revert_store_writes),
+        ));
     }
 
     make_module_natives(natives)
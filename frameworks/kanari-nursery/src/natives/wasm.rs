@@ -17,9 +17,7 @@ use std::ops::Deref;
 use std::vec;
 use tracing::{debug, warn};
 
-use moveos_wasm::wasm::{
-    create_wasm_instance, get_instance_pool, insert_wasm_instance, put_data_on_stack,
-};
+use moveos_wasm::wasm::{create_wasm_instance, put_data_on_stack, NativeWASMContext};
 
 use moveos_stdlib::natives::helpers::{make_module_natives, make_native};
 
@@ -64,7 +62,7 @@ impl WASMCreateInstanceGasParameters {
 #[inline]
 fn native_create_wasm_instance(
     gas_params: &WASMCreateInstanceGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -79,13 +77,8 @@ fn native_create_wasm_instance(
 
     let (instance_id, error_code) = match create_wasm_instance(&wasm_bytes) {
         Ok(instance) => {
-            match insert_wasm_instance(instance) {
-                Ok(id) => (id, 0), // No error
-                Err(e) => {
-                    warn!("insert_wasm_instance_error: {:?}", &e);
-                    (0, E_WASM_INSERT_POOL_FAILED)
-                }
-            }
+            let wasm_context = context.extensions_mut().get_mut::<NativeWASMContext>();
+            (wasm_context.insert_instance(instance), 0)
         }
         Err(e) => {
             warn!("create_wasm_instance_error: {:?}", &e);
@@ -262,7 +255,7 @@ impl WASMCreateArgsGasParameters {
 #[inline]
 fn native_create_wasm_args_in_memory(
     gas_params: &WASMCreateArgsGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -294,24 +287,28 @@ fn native_create_wasm_args_in_memory(
 
     let mut data_ptr_list = Vec::new();
 
-    let instance_pool = get_instance_pool();
-    let mut pool_object = match instance_pool.lock() {
-        Ok(v) => v,
-        Err(_) => {
+    let instance = match context
+        .extensions()
+        .get::<NativeWASMContext>()
+        .get_instance(instance_id)
+    {
+        None => {
             return Ok(NativeResult::err(
                 gas_params.base_create_args,
-                E_GET_INSTANCE_POOL_FAILED,
-            ))
+                E_INSTANCE_NO_EXISTS,
+            ));
         }
+        Some(instance) => instance,
     };
-    match pool_object.get_mut(&instance_id) {
-        None => {
+    match instance.lock() {
+        Err(_) => {
             return Ok(NativeResult::err(
                 gas_params.base_create_args,
-                E_INSTANCE_NO_EXISTS,
-            ));
+                E_GET_INSTANCE_POOL_FAILED,
+            ))
         }
-        Some(instance) => {
+        Ok(mut instance_guard) => {
+            let instance = &mut *instance_guard;
             for arg in func_args.iter() {
                 let c_arg = unsafe { CString::from_vec_unchecked(arg.clone()) };
 
@@ -371,11 +368,11 @@ fn build_err(cost: InternalGas, abort_code: u64) -> PartialVMResult<NativeResult
 #[inline]
 fn native_execute_wasm_function(
     gas_params: &WASMExecuteGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
-    let vm_result = execute_wasm_function_inner(gas_params, _context, _ty_args, args);
+    let vm_result = execute_wasm_function_inner(gas_params, context, _ty_args, args);
     match vm_result {
         PartialVMResult::Ok(native_result) => match native_result {
             NativeResult::Success { cost, ret_vals } => {
@@ -406,7 +403,7 @@ fn native_execute_wasm_function(
 
 fn execute_wasm_function_inner(
     gas_params: &WASMExecuteGasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -421,14 +418,23 @@ fn execute_wasm_function_inner(
     let func_name = pop_arg!(args, Vec<u8>);
     let instance_id = pop_arg!(args, u64);
 
-    let instance_pool = get_instance_pool();
-    let mut pool_object = match instance_pool.lock() {
+    let instance = match context
+        .extensions()
+        .get::<NativeWASMContext>()
+        .get_instance(instance_id)
+    {
+        None => {
+            return Ok(NativeResult::err(
+                gas_params.base_create_execution,
+                E_INSTANCE_NO_EXISTS,
+            ))
+        }
+        Some(instance) => instance,
+    };
+    let mut instance_guard = match instance.lock() {
         Ok(v) => v,
         Err(e) => {
-            warn!(
-                "execute_wasm_function_inner->instance_pool_lock_error: {:?}",
-                &e
-            );
+            warn!("execute_wasm_function_inner->instance_lock_error: {:?}", &e);
 
             return Ok(NativeResult::err(
                 gas_params.base_create_execution,
@@ -436,106 +442,96 @@ fn execute_wasm_function_inner(
             ));
         }
     };
+    let instance = &mut *instance_guard;
+
+    let ret = match instance.instance.exports.get_function(
+        String::from_utf8_lossy(func_name.as_slice())
+            .to_string()
+            .as_str(),
+    ) {
+        Ok(calling_function) => {
+            let mut gas_meter = instance.gas_meter.lock().unwrap();
+            gas_meter.reset();
+            drop(gas_meter);
+
+            let mut wasm_func_args = Vec::with_capacity(func_args.len());
+            for arg in func_args.iter() {
+                wasm_func_args.push(wasmer::Value::I32(*arg as i32));
+            }
+
+            // TODO: check the length of arguments for the function calling
+
+            // let trap_handler: Box<
+            //     dyn Fn(libc::c_int, *const libc::siginfo_t, *const libc::c_void) -> bool
+            //         + Send
+            //         + Sync
+            //         + 'static,
+            // > = Box::new(|signum, siginfo, ctx| {
+            //     warn!(
+            //         "Trap handler called, signum:{:?}, siginfo:{:?}, context:{:?}",
+            //         signum, siginfo, ctx
+            //     );
+            //     false
+            // });
+
+            // Set trap handler
+            // instance.store.set_trap_handler(Some(trap_handler));
+
+            match calling_function.call(&mut instance.store, wasm_func_args.as_slice()) {
+                Ok(ret) => {
+                    let return_value = match ret.deref().first() {
+                        Some(v) => v,
+                        None => {
+                            return build_err(
+                                gas_params.base_create_execution,
+                                E_EMPTY_RETURN_VALUE,
+                            )
+                        }
+                    };
+                    let offset = match return_value.i32() {
+                        Some(v) => v,
+                        None => {
+                            return build_err(gas_params.base_create_execution, E_VALUE_NOT_I32)
+                        }
+                    };
+                    let ret_val = Value::u64(offset as u64);
 
-    let ret = match pool_object.get_mut(&instance_id) {
-        None => Ok(NativeResult::err(
-            gas_params.base_create_execution,
-            E_INSTANCE_NO_EXISTS,
-        )),
-        Some(instance) => {
-            match instance.instance.exports.get_function(
-                String::from_utf8_lossy(func_name.as_slice())
-                    .to_string()
-                    .as_str(),
-            ) {
-                Ok(calling_function) => {
                     let mut gas_meter = instance.gas_meter.lock().unwrap();
-                    gas_meter.reset();
-                    drop(gas_meter);
+                    let gas_used = gas_meter.used();
 
-                    let mut wasm_func_args = Vec::with_capacity(func_args.len());
-                    for arg in func_args.iter() {
-                        wasm_func_args.push(wasmer::Value::I32(*arg as i32));
-                    }
+                    debug!("execute_wasm_function_inner->gas_used: {:?}", gas_used);
 
-                    // TODO: check the length of arguments for the function calling
-
-                    // let trap_handler: Box<
-                    //     dyn Fn(libc::c_int, *const libc::siginfo_t, *const libc::c_void) -> bool
-                    //         + Send
-                    //         + Sync
-                    //         + 'static,
-                    // > = Box::new(|signum, siginfo, ctx| {
-                    //     warn!(
-                    //         "Trap handler called, signum:{:?}, siginfo:{:?}, context:{:?}",
-                    //         signum, siginfo, ctx
-                    //     );
-                    //     false
-                    // });
-
-                    // Set trap handler
-                    // instance.store.set_trap_handler(Some(trap_handler));
-
-                    match calling_function.call(&mut instance.store, wasm_func_args.as_slice()) {
-                        Ok(ret) => {
-                            let return_value = match ret.deref().first() {
-                                Some(v) => v,
-                                None => {
-                                    return build_err(
-                                        gas_params.base_create_execution,
-                                        E_EMPTY_RETURN_VALUE,
-                                    )
-                                }
-                            };
-                            let offset = match return_value.i32() {
-                                Some(v) => v,
-                                None => {
-                                    return build_err(
-                                        gas_params.base_create_execution,
-                                        E_VALUE_NOT_I32,
-                                    )
-                                }
-                            };
-                            let ret_val = Value::u64(offset as u64);
-
-                            let mut gas_meter = instance.gas_meter.lock().unwrap();
-                            let gas_used = gas_meter.used();
-
-                            debug!("execute_wasm_function_inner->gas_used: {:?}", gas_used);
-
-                            let mut cost = gas_params.base_create_execution;
-                            cost += gas_params.per_execution_point * NumBytes::new(gas_used);
-
-                            Ok(NativeResult::Success {
-                                cost,
-                                ret_vals: smallvec![ret_val],
-                            })
-                        }
-                        Err(err) => {
-                            warn!(
-                                "execute_wasm_function_inner->calling_function_error:{}",
-                                err.message()
-                            );
-                            if tracing::enabled!(tracing::Level::DEBUG) {
-                                debug!("trace:{:?}", err.trace());
-                            }
-                            Ok(NativeResult::err(
-                                gas_params.base_create_execution,
-                                E_WASM_EXECUTION_FAILED,
-                            ))
-                        }
-                    }
+                    let mut cost = gas_params.base_create_execution;
+                    cost += gas_params.per_execution_point * NumBytes::new(gas_used);
+
+                    Ok(NativeResult::Success {
+                        cost,
+                        ret_vals: smallvec![ret_val],
+                    })
                 }
                 Err(err) => {
-                    warn!("execute_wasm_function_inner->get_function_error:{:?}", &err);
-
+                    warn!(
+                        "execute_wasm_function_inner->calling_function_error:{}",
+                        err.message()
+                    );
+                    if tracing::enabled!(tracing::Level::DEBUG) {
+                        debug!("trace:{:?}", err.trace());
+                    }
                     Ok(NativeResult::err(
                         gas_params.base_create_execution,
-                        E_WASM_FUNCTION_NOT_FOUND,
+                        E_WASM_EXECUTION_FAILED,
                     ))
                 }
             }
         }
+        Err(err) => {
+            warn!("execute_wasm_function_inner->get_function_error:{:?}", &err);
+
+            Ok(NativeResult::err(
+                gas_params.base_create_execution,
+                E_WASM_FUNCTION_NOT_FOUND,
+            ))
+        }
     };
     ret
 }
@@ -559,7 +555,7 @@ impl WASMReadAddLength {
 #[inline]
 fn native_read_data_length(
     gas_params: &WASMReadAddLength,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -573,19 +569,20 @@ fn native_read_data_length(
     let data_ptr = pop_arg!(args, u64);
     let instance_id = pop_arg!(args, u64);
 
-    let instance_pool = get_instance_pool();
-    let mut pool_object = match instance_pool.lock() {
-        Ok(v) => v,
-        Err(_) => {
-            return Ok(NativeResult::err(
-                gas_params.base,
-                E_GET_INSTANCE_POOL_FAILED,
-            ))
-        }
+    let instance = match context
+        .extensions()
+        .get::<NativeWASMContext>()
+        .get_instance(instance_id)
+    {
+        None => return build_err(gas_params.base, E_INSTANCE_NO_EXISTS),
+        Some(instance) => instance,
     };
-    let ret = match pool_object.get_mut(&instance_id) {
-        None => Ok(NativeResult::err(gas_params.base, E_INSTANCE_NO_EXISTS)),
-        Some(instance) => {
+    let ret = match instance.lock() {
+        Err(_) => Ok(NativeResult::err(
+            gas_params.base,
+            E_GET_INSTANCE_POOL_FAILED,
+        )),
+        Ok(instance) => {
             let memory = match instance.instance.exports.get_memory("memory") {
                 Ok(v) => v,
                 Err(_) => return build_err(gas_params.base, E_MEMORY_NOT_FOUND),
@@ -631,7 +628,7 @@ impl WASMReadHeapData {
 #[inline]
 fn native_read_data_from_heap(
     gas_params: &WASMReadHeapData,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -646,19 +643,20 @@ fn native_read_data_from_heap(
     let data_ptr = pop_arg!(args, u32);
     let instance_id = pop_arg!(args, u64);
 
-    let instance_pool = get_instance_pool();
-    let mut pool_object = match instance_pool.lock() {
-        Ok(v) => v,
-        Err(_) => {
-            return Ok(NativeResult::err(
-                gas_params.base,
-                E_GET_INSTANCE_POOL_FAILED,
-            ))
-        }
+    let instance = match context
+        .extensions()
+        .get::<NativeWASMContext>()
+        .get_instance(instance_id)
+    {
+        None => return build_err(gas_params.base, E_INSTANCE_NO_EXISTS),
+        Some(instance) => instance,
     };
-    let ret = match pool_object.get_mut(&instance_id) {
-        None => Ok(NativeResult::err(gas_params.base, E_INSTANCE_NO_EXISTS)),
-        Some(instance) => {
+    let ret = match instance.lock() {
+        Err(_) => Ok(NativeResult::err(
+            gas_params.base,
+            E_GET_INSTANCE_POOL_FAILED,
+        )),
+        Ok(instance) => {
             let memory = match instance.instance.exports.get_memory("memory") {
                 Ok(v) => v,
                 Err(_) => return build_err(gas_params.base, E_MEMORY_NOT_FOUND),
@@ -729,7 +727,7 @@ impl WASMReleaseInstance {
 #[inline]
 fn native_release_wasm_instance(
     gas_params: &WASMReleaseInstance,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -751,32 +749,16 @@ fn native_release_wasm_instance(
 
     let instance_id = val.value_as::<u64>()?;
 
+    // the instance is dropped once the last lock on it is released
+    if context
+        .extensions_mut()
+        .get_mut::<NativeWASMContext>()
+        .remove_instance(instance_id)
+        .is_none()
     {
-        let instance_pool = get_instance_pool();
-        let mut pool_object = match instance_pool.lock() {
-            Ok(v) => v,
-            Err(_) => {
-                return Ok(NativeResult::err(
-                    gas_params.base,
-                    E_GET_INSTANCE_POOL_FAILED,
-                ))
-            }
-        };
-        if pool_object.get_mut(&instance_id).is_none() {
-            return Ok(NativeResult::err(gas_params.base, E_INSTANCE_NO_EXISTS));
-        };
+        return Ok(NativeResult::err(gas_params.base, E_INSTANCE_NO_EXISTS));
     }
 
-    match moveos_wasm::wasm::remove_instance(instance_id) {
-        Ok(_) => {}
-        Err(_) => {
-            return Ok(NativeResult::err(
-                gas_params.base,
-                E_GET_INSTANCE_POOL_FAILED,
-            ))
-        }
-    };
-
     Ok(NativeResult::Success {
         cost: gas_params.base,
         ret_vals: smallvec![Value::bool(true)],
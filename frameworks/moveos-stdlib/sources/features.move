@@ -159,6 +159,28 @@ module moveos_std::features {
         assert!(is_enabled(COMPATIBILITY_CHECKER_V2), EAPI_DISABLED);
     }
 
+    /// Whether to validate the relayed Bitcoin block headers with the consensus rules,
+    /// the proof of work, the median time past and the difficulty adjustment.
+    const BITCOIN_HEADER_VALIDATION: u64 = 9;
+    public fun get_bitcoin_header_validation_feature(): u64 { BITCOIN_HEADER_VALIDATION }
+    public fun bitcoin_header_validation_enabled(): bool {
+        is_enabled(BITCOIN_HEADER_VALIDATION)
+    }
+    public fun ensure_bitcoin_header_validation_enabled() {
+        assert!(is_enabled(BITCOIN_HEADER_VALIDATION), EAPI_DISABLED);
+    }
+
+    /// Whether to index the Runes of the relayed Bitcoin transactions. The Runes are indexed
+    /// from the first rune height, so the chains whose genesis is after it must not enable it.
+    const BITCOIN_RUNES: u64 = 10;
+    public fun get_bitcoin_runes_feature(): u64 { BITCOIN_RUNES }
+    public fun bitcoin_runes_enabled(): bool {
+        is_enabled(BITCOIN_RUNES)
+    }
+    public fun ensure_bitcoin_runes_enabled() {
+        assert!(is_enabled(BITCOIN_RUNES), EAPI_DISABLED);
+    }
+
     /// Helper for getting all features. 
     /// Update this once new feature added.
     public fun get_all_features(): vector<u64> {
@@ -171,6 +193,8 @@ module moveos_std::features {
             WASM,
             VALUE_SIZE_GAS,
             COMPATIBILITY_CHECKER_V2,
+            BITCOIN_HEADER_VALIDATION,
+            BITCOIN_RUNES,
         ]
     }
     // --------------------------------------------------------------------------------------------
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 
+use crate::log::CallFrame;
 use crate::log::ExecutionAndIOCosts;
 use crate::log::ExecutionGasEvent;
 use crate::render::Render;
@@ -14,6 +15,8 @@ use std::collections::{btree_map, BTreeMap};
 #[derive(Debug)]
 pub struct AggregatedExecutionGasEvents {
     pub ops: Vec<(String, usize, InternalGas)>,
+    /// The gas used by each Move function itself, the gas used by the functions it calls is not included.
+    pub functions: Vec<(String, usize, InternalGas)>,
 }
 
 fn insert_or_add<K, U>(
@@ -90,8 +93,32 @@ impl ExecutionAndIOCosts {
             }
         }
 
+        let mut functions = BTreeMap::new();
+        aggregate_frame_costs(&self.call_graph, &mut functions);
+
         AggregatedExecutionGasEvents {
             ops: into_sorted_vec(ops),
+            functions: into_sorted_vec(functions),
+        }
+    }
+}
+
+fn aggregate_frame_costs(
+    frame: &CallFrame,
+    functions: &mut BTreeMap<String, (usize, InternalGas)>,
+) {
+    use ExecutionGasEvent::*;
+
+    let mut frame_cost = InternalGas::zero();
+    for event in &frame.events {
+        match event {
+            Loc(..) => (),
+            Call(inner_frame) => aggregate_frame_costs(inner_frame, functions),
+            Bytecode { cost, .. }
+            | CallNative { cost, .. }
+            | LoadResource { cost, .. }
+            | CreateTy { cost } => frame_cost += *cost,
         }
     }
+    insert_or_add(functions, format!("{}", frame.name), frame_cost);
 }
@@ -77,6 +77,22 @@ impl CallFrame {
             events: vec![],
         }
     }
+
+    /// The gas used by the frame, including the gas used by the frames it calls.
+    pub fn total_cost(&self) -> InternalGas {
+        let mut total = InternalGas::zero();
+        for event in &self.events {
+            total += match event {
+                ExecutionGasEvent::Loc(_) => InternalGas::zero(),
+                ExecutionGasEvent::Call(frame) => frame.total_cost(),
+                ExecutionGasEvent::Bytecode { cost, .. }
+                | ExecutionGasEvent::CallNative { cost, .. }
+                | ExecutionGasEvent::LoadResource { cost, .. }
+                | ExecutionGasEvent::CreateTy { cost } => *cost,
+            };
+        }
+        total
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -447,7 +447,7 @@ pub trait ProfileGasMeter {
     fn finish(&mut self) -> TransactionGasLog;
 }
 
-impl<G: GasMeter> ProfileGasMeter for GasProfiler<G> {
+impl<G: GasMeter + ClassifiedGasMeter + SwitchableGasMeter> ProfileGasMeter for GasProfiler<G> {
     fn finish(&mut self) -> TransactionGasLog {
         while self.frames.read().unwrap().len() > 1 {
             let cur = self
@@ -461,53 +461,55 @@ impl<G: GasMeter> ProfileGasMeter for GasProfiler<G> {
             last.events.push(ExecutionGasEvent::Call(cur));
         }
 
+        let call_graph = self
+            .frames
+            .write()
+            .unwrap()
+            .pop()
+            .expect("frame must exist");
+        // The profiler may be cloned into the session, so the total is summed from the events
+        // instead of the balance of the base gas meter.
         let exec_io = ExecutionAndIOCosts {
-            total: self.base.balance_internal(),
-            call_graph: self
-                .frames
-                .write()
-                .unwrap()
-                .pop()
-                .expect("frame must exist"),
+            total: call_graph.total_cost(),
+            call_graph,
         };
 
         self.stop_metering();
 
         TransactionGasLog {
             exec_io,
-            storage: InternalGas::zero(),
+            storage: self.base.gas_statement().storage_gas_used,
         }
     }
 }
 
-impl<G: GasMeter> ClassifiedGasMeter for GasProfiler<G> {
-    fn charge_execution(&mut self, _gas_cost: u64) -> PartialVMResult<()> {
-        Ok(())
+impl<G: GasMeter + ClassifiedGasMeter> ClassifiedGasMeter for GasProfiler<G> {
+    fn charge_execution(&mut self, gas_cost: u64) -> PartialVMResult<()> {
+        self.base.charge_execution(gas_cost)
     }
 
-    fn charge_io_write(&mut self, _data_size: u64) -> PartialVMResult<()> {
-        Ok(())
+    fn charge_io_write(&mut self, data_size: u64) -> PartialVMResult<()> {
+        self.base.charge_io_write(data_size)
     }
 
-    fn check_constrains(&self, _max_gas_amount: u64) -> PartialVMResult<()> {
-        Ok(())
+    fn check_constrains(&self, max_gas_amount: u64) -> PartialVMResult<()> {
+        self.base.check_constrains(max_gas_amount)
     }
 
     fn gas_statement(&self) -> GasStatement {
-        GasStatement {
-            execution_gas_used: InternalGas::zero(),
-            storage_gas_used: InternalGas::zero(),
-        }
+        self.base.gas_statement()
     }
 }
 
-impl<G: GasMeter> SwitchableGasMeter for GasProfiler<G> {
+impl<G: GasMeter + SwitchableGasMeter> SwitchableGasMeter for GasProfiler<G> {
     fn stop_metering(&mut self) {
         self.metering = false;
+        self.base.stop_metering();
     }
 
     fn start_metering(&mut self) {
         self.metering = true;
+        self.base.start_metering();
     }
 
     fn is_metering(&self) -> bool {
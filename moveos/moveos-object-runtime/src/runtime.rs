@@ -22,7 +22,10 @@ use moveos_types::{
     moveos_std::timestamp::Timestamp,
     state::{FieldKey, ObjectChange, ObjectState},
 };
-use moveos_types::{moveos_std::object::ObjectMeta, state::StateChangeSet};
+use moveos_types::{
+    moveos_std::object::ObjectMeta,
+    state::{ReadSet, StateChangeSet},
+};
 use moveos_types::{
     moveos_std::{
         module_store::{ModuleStore, Package},
@@ -100,6 +103,8 @@ pub struct ObjectRuntime<'r> {
     pub(crate) root: RuntimeObject,
     pub(crate) object_pointer_in_args: BTreeMap<ObjectID, RuntimeObjectArg>,
     resolver: &'r dyn StatelessResolver,
+    /// The reads of the sessions before this session is respawned.
+    prior_reads: ReadSet,
 }
 
 impl<'r> ObjectRuntime<'r> {
@@ -115,6 +120,7 @@ impl<'r> ObjectRuntime<'r> {
                 .expect("Load root object should success"),
             object_pointer_in_args: Default::default(),
             resolver,
+            prior_reads: ReadSet::default(),
         };
         s.init_genesis_object(genesis_objects)
             .expect("Init genesis object should success");
@@ -139,6 +145,7 @@ impl<'r> ObjectRuntime<'r> {
                 .expect("Load root object should success"),
             object_pointer_in_args: Default::default(),
             resolver,
+            prior_reads: ReadSet::default(),
         }
     }
 
@@ -237,6 +244,26 @@ impl<'r> ObjectRuntime<'r> {
         Ok(timestamp)
     }
 
+    /// The objects read by the transaction, include the reads of the sessions before respawn.
+    pub fn read_set(&self) -> ReadSet {
+        let mut read_set = self.prior_reads.clone();
+        self.root
+            .collect_field_ids_read_from_state(&mut read_set.objects);
+        for field in self.root.fields.values() {
+            field.collect_loaded_object_ids(&mut read_set.objects);
+        }
+        // The timestamp is read from the state when the session is finished
+        read_set.objects.insert(Timestamp::object_id());
+        read_set.root_fields |= self.root.fields_read_from_state.get();
+        read_set
+    }
+
+    /// Keep the reads of the previous session when the session is respawned,
+    /// because the failure of the previous session depends on them.
+    pub fn add_prior_reads(&mut self, read_set: ReadSet) {
+        self.prior_reads.merge(read_set);
+    }
+
     /// Load Object to the ObjectRuntime.
     pub fn load_object(
         &mut self,
@@ -484,6 +511,7 @@ impl<'r> ObjectRuntime<'r> {
             root,
             object_pointer_in_args: _,
             resolver: _,
+            prior_reads: _,
         } = self;
         (tx_context.into_inner(), root)
     }
@@ -31,7 +31,8 @@ use moveos_types::{
     state::{FieldKey, MoveState, MoveType, ObjectChange, ObjectState},
     state_resolver::StatelessResolver,
 };
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::cell::{Cell, RefCell};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
 
 type ScanFieldList = Vec<(FieldKey, Value)>;
 type FieldList = Vec<(FieldKey, RuntimeObject, Option<Option<NumBytes>>)>;
@@ -45,6 +46,11 @@ pub struct RuntimeObject {
     /// This is the Object<T> pointer in MoveVM memory
     pub(crate) pointer: ObjectPointer,
     pub(crate) fields: BTreeMap<FieldKey, RuntimeObject>,
+    /// Whether the fields are listed from the state. It is used to track the read set of the transaction.
+    pub(crate) fields_read_from_state: Cell<bool>,
+    /// The fields which are read from the state without being cached in `fields`.
+    /// It is used to track the read set of the transaction.
+    pub(crate) field_keys_read_from_state: RefCell<BTreeSet<FieldKey>>,
 }
 
 /// A structure representing the `Object<T>` in Move.
@@ -113,6 +119,8 @@ impl RuntimeObject {
             value: GlobalValue::cached(value)?,
             pointer,
             fields: Default::default(),
+            fields_read_from_state: Cell::new(false),
+            field_keys_read_from_state: Default::default(),
         })
     }
 
@@ -122,6 +130,8 @@ impl RuntimeObject {
             value: GlobalValue::none(),
             pointer: ObjectPointer::none(),
             fields: Default::default(),
+            fields_read_from_state: Cell::new(false),
+            field_keys_read_from_state: Default::default(),
         }
     }
 
@@ -138,6 +148,8 @@ impl RuntimeObject {
             value: gv,
             pointer,
             fields: Default::default(),
+            fields_read_from_state: Cell::new(false),
+            field_keys_read_from_state: Default::default(),
         })
     }
 
@@ -255,6 +267,9 @@ impl RuntimeObject {
         resolver: &dyn StatelessResolver,
         field_key: FieldKey,
     ) -> PartialVMResult<(RuntimeObject, Option<Option<NumBytes>>)> {
+        self.field_keys_read_from_state
+            .borrow_mut()
+            .insert(field_key);
         let state_root = self.state_root()?;
         let field_obj_id = self.id().child_id(field_key);
 
@@ -288,6 +303,7 @@ impl RuntimeObject {
         cursor: Option<FieldKey>,
         limit: usize,
     ) -> PartialVMResult<FieldList> {
+        self.fields_read_from_state.set(true);
         let state_root = self.state_root()?;
         let state_kvs = resolver
             .list_fields_at(state_root, cursor, limit)
@@ -321,6 +337,7 @@ impl RuntimeObject {
         cursor: Option<FieldKey>,
         limit: usize,
     ) -> PartialVMResult<FieldKeyList> {
+        self.fields_read_from_state.set(true);
         let state_root = self.state_root()?;
         let state_kvs = resolver
             .list_fields_at(state_root, cursor, limit)
@@ -730,6 +747,22 @@ impl RuntimeObject {
 
 /// Internal functions
 impl RuntimeObject {
+    /// Collect the ids of this object and the loaded fields recursively.
+    pub(crate) fn collect_loaded_object_ids(&self, ids: &mut BTreeSet<ObjectID>) {
+        ids.insert(self.id().clone());
+        self.collect_field_ids_read_from_state(ids);
+        for field in self.fields.values() {
+            field.collect_loaded_object_ids(ids);
+        }
+    }
+
+    /// The fields which are read from the state without being cached, they do not exist in `fields`.
+    pub(crate) fn collect_field_ids_read_from_state(&self, ids: &mut BTreeSet<ObjectID>) {
+        for field_key in self.field_keys_read_from_state.borrow().iter() {
+            ids.insert(self.id().child_id(*field_key));
+        }
+    }
+
     /// Check the object type is equal to the expect type
     /// If the expect type is None, do nothing, for skip the type check
     fn check_type(&self, expect_type: Option<&TypeTag>) -> PartialVMResult<()> {
@@ -19,7 +19,7 @@ use moveos_config::DataDirPath;
 use moveos_types::genesis_info::GenesisInfo;
 use moveos_types::h256::H256;
 use moveos_types::moveos_std::event::{Event, EventID, TransactionEvent};
-use moveos_types::moveos_std::object::ObjectID;
+use moveos_types::moveos_std::object::{ObjectID, GENESIS_STATE_ROOT};
 use moveos_types::moveos_std::onchain_features::FeatureStore;
 use moveos_types::startup_info::StartupInfo;
 use moveos_types::state::{FieldKey, ObjectState};
@@ -136,6 +136,15 @@ impl MoveOSStore {
         &self.node_store
     }
 
+    /// Check whether the state tree of the state root is in the store,
+    /// it is not if the state root is unknown or its nodes have been pruned.
+    pub fn contains_state_root(&self, state_root: H256) -> Result<bool> {
+        if state_root == *GENESIS_STATE_ROOT {
+            return Ok(true);
+        }
+        Ok(self.node_store.get(&state_root)?.is_some())
+    }
+
     pub fn get_config_store(&self) -> &ConfigDBStore {
         &self.config_store
     }
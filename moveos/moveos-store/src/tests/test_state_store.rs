@@ -6,6 +6,7 @@ use crate::{MoveOSStore, StoreMeta};
 use anyhow::Result;
 use moveos_config::store_config::RocksdbConfig;
 use moveos_types::h256::H256;
+use moveos_types::moveos_std::object::GENESIS_STATE_ROOT;
 use moveos_types::test_utils::random_state_change_set;
 use raw_store::metrics::DBMetrics;
 use raw_store::rocks::RocksDB;
@@ -68,6 +69,21 @@ async fn test_statedb_state_root() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_contains_state_root() -> Result<()> {
+    let (moveos_store, _) =
+        MoveOSStore::mock_moveos_store().expect("moveos store mock should succ");
+    assert!(moveos_store.contains_state_root(*GENESIS_STATE_ROOT)?);
+    assert!(!moveos_store.contains_state_root(H256::random())?);
+
+    let mut change_set = random_state_change_set();
+    moveos_store
+        .get_state_store()
+        .apply_change_set(&mut change_set)?;
+    assert!(moveos_store.contains_state_root(change_set.state_root)?);
+    Ok(())
+}
+
 // #[tokio::test]
 // async fn test_child_state_db_dump_and_apply() -> Result<()> {
 //     let mut moveos_store = MoveOSStore::mock_moveos_store().expect("moveos store mock should succ");
@@ -15,6 +15,8 @@ use serde::{Deserialize, Serialize};
 pub const MODULE_NAME: &IdentStr = ident_str!("features");
 pub const VALUE_SIZE_GAS_FEATURE: u64 = 7;
 pub const COMPATIBILITY_CHECKER_V2: u64 = 8;
+pub const BITCOIN_HEADER_VALIDATION: u64 = 9;
+pub const BITCOIN_RUNES: u64 = 10;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct FeatureStore {
@@ -43,6 +45,14 @@ impl FeatureStore {
     pub fn has_compatibility_checker_v2(&self) -> bool {
         self.contains_feature(COMPATIBILITY_CHECKER_V2)
     }
+
+    pub fn has_bitcoin_header_validation(&self) -> bool {
+        self.contains_feature(BITCOIN_HEADER_VALIDATION)
+    }
+
+    pub fn has_bitcoin_runes(&self) -> bool {
+        self.contains_feature(BITCOIN_RUNES)
+    }
 }
 
 impl MoveStructType for FeatureStore {
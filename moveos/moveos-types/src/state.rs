@@ -29,7 +29,7 @@ use primitive_types::H256;
 use serde::{
     de::DeserializeOwned, de::Error as _, Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::fmt::Debug;
 use std::str::FromStr;
@@ -1027,6 +1027,29 @@ impl StateChangeSet {
         let change = ObjectChange::new(metadata, Op::New(value));
         self.add_change(change)
     }
+
+    /// The ids of the objects changed in this change set, the parents of the changed fields are included.
+    pub fn changed_object_ids(&self) -> BTreeSet<ObjectID> {
+        fn collect(change: &ObjectChange, ids: &mut BTreeSet<ObjectID>) {
+            ids.insert(change.metadata.id.clone());
+            for field_change in change.fields.values() {
+                collect(field_change, ids);
+            }
+        }
+        let mut ids = BTreeSet::new();
+        for change in self.changes.values() {
+            collect(change, &mut ids);
+        }
+        ids
+    }
+
+    /// Move the change set which is generated on the `base` root to the `new_base` root.
+    /// The changes must not conflict with the changes between `base` and `new_base`.
+    pub fn rebase(&mut self, base: &ObjectMeta, new_base: &ObjectMeta) {
+        let size_delta = self.global_size as i128 - base.size as i128;
+        self.state_root = new_base.state_root();
+        self.global_size = (new_base.size as i128 + size_delta) as u64;
+    }
 }
 
 impl Default for StateChangeSet {
@@ -1057,6 +1080,36 @@ impl StateChangeSetExt {
     }
 }
 
+/// The objects read by a transaction, it is used to detect the conflicts between the transactions
+/// executed in parallel.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ReadSet {
+    /// The objects loaded from the state, include the objects which do not exist.
+    pub objects: BTreeSet<ObjectID>,
+    /// Whether the fields of the root object are read without loading, such as listing the fields.
+    /// The transaction conflicts with any change in this case.
+    pub root_fields: bool,
+}
+
+impl ReadSet {
+    pub fn merge(&mut self, other: ReadSet) {
+        self.objects.extend(other.objects);
+        self.root_fields |= other.root_fields;
+    }
+
+    /// Returns true if the transaction read any of the changed objects.
+    pub fn conflicts_with(&self, changed_object_ids: &BTreeSet<ObjectID>) -> bool {
+        if changed_object_ids.is_empty() {
+            return false;
+        }
+        self.root_fields
+            || self
+                .objects
+                .iter()
+                .any(|id| changed_object_ids.contains(id))
+    }
+}
+
 mod op_serde {
     use super::*;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -1156,4 +1209,39 @@ mod tests {
             "0x07d29b5cffb95d39f98baed1a973e676891bc9d379022aba6f4a2e4912a5e552",
         );
     }
+
+    #[test]
+    fn test_change_set_conflicts() {
+        let parent_id = ObjectID::random();
+        let child_id = parent_id.child_id(FieldKey::random());
+        let mut parent_change =
+            ObjectChange::meta(ObjectMeta::genesis_meta(parent_id.clone(), TypeTag::U64));
+        parent_change
+            .add_field_change(ObjectChange::new(
+                ObjectMeta::genesis_meta(child_id.clone(), TypeTag::U64),
+                Op::New(vec![1]),
+            ))
+            .unwrap();
+        let mut change_set = StateChangeSet::default();
+        change_set.add_change(parent_change).unwrap();
+
+        let changed_object_ids = change_set.changed_object_ids();
+        assert_eq!(
+            changed_object_ids,
+            BTreeSet::from([parent_id.clone(), child_id])
+        );
+
+        // The transaction which read the parent conflicts with the new field
+        let read_set = ReadSet {
+            objects: BTreeSet::from([parent_id]),
+            root_fields: false,
+        };
+        assert!(read_set.conflicts_with(&changed_object_ids));
+        let read_set = ReadSet {
+            objects: BTreeSet::from([ObjectID::random()]),
+            root_fields: false,
+        };
+        assert!(!read_set.conflicts_with(&changed_object_ids));
+        assert!(!read_set.conflicts_with(&BTreeSet::new()));
+    }
 }
@@ -3,6 +3,7 @@
 
 
 pub mod build;
+pub mod lint;
 pub mod metadata;
 pub mod verifier;
 
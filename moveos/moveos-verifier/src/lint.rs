@@ -0,0 +1,602 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+//! Static lints for MoveOS-specific pitfalls. Unlike the extended checks in `metadata`, the lints
+//! never fail the build, they report the code which is legal but likely wrong.
+
+use crate::build::ModelBuilder;
+use crate::metadata::{
+    has_attribute, is_allowed_data_struct_type, is_data_struct_annotation, is_std_option_type,
+    DATA_STRUCT_ATTRIBUTE,
+};
+use move_binary_format::binary_views::BinaryIndexedView;
+use move_binary_format::file_format::{
+    Bytecode, FunctionHandleIndex, FunctionInstantiation, SignatureToken,
+};
+use move_binary_format::file_format::{Ability, StructHandleIndex};
+use move_core_types::account_address::AccountAddress;
+use move_model::model::{FunctionEnv, GlobalEnv, Loc, ModuleEnv, StructEnv};
+use move_model::ty::{ReferenceKind, Type};
+use move_package::{BuildConfig, ModelConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// The collections whose size is controlled by the users, iterating over them may run out of gas.
+const UNBOUNDED_COLLECTION_MODULES: [&str; 6] = [
+    "table",
+    "table_vec",
+    "big_vector",
+    "linked_table",
+    "bag",
+    "type_table",
+];
+
+/// The functions of the collections which return the size of the collection.
+const COLLECTION_SIZE_FUNCTIONS: [&str; 2] = ["length", "is_empty"];
+
+/// The functions which check the ownership of an object.
+const OBJECT_OWNERSHIP_CHECK_FUNCTIONS: [&str; 5] = [
+    "owner",
+    "is_shared",
+    "is_frozen",
+    "is_system_owned",
+    "is_user_owned",
+];
+
+/// The decoding functions which require a `#[data_struct]` type argument, used when the
+/// dependency is only available as bytecode and its attributes are unknown.
+const DATA_STRUCT_FUNCTIONS: [&str; 4] = [
+    "0x2::bcs::from_bytes",
+    "0x2::bcs::from_bytes_option",
+    "0x2::json::from_json",
+    "0x2::json::from_json_option",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// A loop is bounded by the size of a user controlled collection, such as a `table_vec`.
+    UnboundedLoop,
+    /// A struct passed to the `bcs`/`json` decoding functions is not a `#[data_struct]`.
+    MissingDataStruct,
+    /// An object struct has the `drop` or `copy` ability.
+    ObjectAbility,
+    /// An entry function takes `&mut Object<T>` without checking the ownership of the object.
+    UncheckedMutObject,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    /// The module which the diagnostic is found in, such as `0x42::counter`.
+    pub module: String,
+    /// The function or the struct which the diagnostic is found in.
+    pub item: String,
+    pub message: String,
+    pub file: Option<String>,
+    /// The 1-based line number.
+    pub line: Option<u32>,
+    /// The 1-based column number.
+    pub column: Option<u32>,
+}
+
+/// Build the Move model of the package at `package_path` and run the lints on the modules of the
+/// package. The dependencies are not linted.
+pub fn lint_package(
+    package_path: &Path,
+    build_config: BuildConfig,
+) -> anyhow::Result<Vec<LintDiagnostic>> {
+    let resolved_graph =
+        build_config.resolution_graph_for_package(package_path, &mut std::io::stderr())?;
+    let model_config = ModelConfig {
+        target_filter: None,
+        all_files_as_targets: false,
+    };
+    let env = ModelBuilder::create(resolved_graph, model_config).build_model()?;
+    if env.has_errors() {
+        let mut error_writer = termcolor::StandardStream::stderr(termcolor::ColorChoice::Auto);
+        env.report_diag(
+            &mut error_writer,
+            codespan_reporting::diagnostic::Severity::Warning,
+        );
+        anyhow::bail!("Failed to build the Move model of the package");
+    }
+    Ok(run_lints(&env))
+}
+
+/// Run the lints on the target modules of the model.
+pub fn run_lints(env: &GlobalEnv) -> Vec<LintDiagnostic> {
+    let mut linter = Linter::new(env);
+    for module in env.get_modules() {
+        if module.is_target() && !module.is_script_module() {
+            linter.lint_module(&module);
+        }
+    }
+    linter.diagnostics.sort();
+    linter.diagnostics
+}
+
+struct Linter<'a> {
+    env: &'a GlobalEnv,
+    /// The full names of the structs with the `#[data_struct]` attribute.
+    data_structs: BTreeSet<String>,
+    /// The full names of the functions with the `#[data_struct(T)]` attribute.
+    data_struct_functions: BTreeSet<String>,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(env: &'a GlobalEnv) -> Self {
+        let mut data_structs = BTreeSet::new();
+        let mut data_struct_functions = DATA_STRUCT_FUNCTIONS
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<BTreeSet<_>>();
+        for module in env.get_modules() {
+            if module.is_script_module() {
+                continue;
+            }
+            for struct_env in module.get_structs() {
+                if is_data_struct_annotation(&struct_env, &module) {
+                    data_structs.insert(full_struct_name(&module, &struct_env));
+                }
+            }
+            for fun in module.get_functions() {
+                if has_attribute(env, &fun, DATA_STRUCT_ATTRIBUTE) {
+                    data_struct_functions.insert(full_function_name(&module, &fun));
+                }
+            }
+        }
+        Self {
+            env,
+            data_structs,
+            data_struct_functions,
+            diagnostics: vec![],
+        }
+    }
+
+    fn lint_module(&mut self, module: &ModuleEnv) {
+        for struct_env in module.get_structs() {
+            self.check_object_abilities(module, &struct_env);
+        }
+
+        let compiled_module = match module.get_verified_module() {
+            Some(compiled_module) => compiled_module,
+            None => return,
+        };
+        let view = BinaryIndexedView::Module(compiled_module);
+        for fun in module.get_functions() {
+            if fun.is_inline() || fun.is_native() {
+                continue;
+            }
+            self.check_unbounded_loops(module, &fun, &view);
+            self.check_data_struct_arguments(module, &fun, &view);
+            self.check_mut_object_parameters(module, &fun, &view);
+        }
+    }
+
+    fn check_object_abilities(&mut self, module: &ModuleEnv, struct_env: &StructEnv) {
+        let abilities = struct_env.get_abilities();
+        if !abilities.has_ability(Ability::Key) {
+            return;
+        }
+        let unexpected = [(Ability::Drop, "drop"), (Ability::Copy, "copy")]
+            .into_iter()
+            .filter(|(ability, _)| abilities.has_ability(*ability))
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>();
+        if unexpected.is_empty() {
+            return;
+        }
+        let struct_name = struct_env.get_full_name_str();
+        self.report(
+            LintRule::ObjectAbility,
+            module,
+            struct_name.clone(),
+            &struct_env.get_loc(),
+            format!(
+                "the object struct `{}` has the `{}` ability, the value can be silently {} after it is removed from the object",
+                struct_name,
+                unexpected.join(", "),
+                if abilities.has_ability(Ability::Drop) {
+                    "dropped"
+                } else {
+                    "duplicated"
+                }
+            ),
+        );
+    }
+
+    fn check_unbounded_loops(
+        &mut self,
+        module: &ModuleEnv,
+        fun: &FunctionEnv,
+        view: &BinaryIndexedView,
+    ) {
+        let code = match fun.get_bytecode() {
+            Some(code) => code,
+            None => return,
+        };
+        // The locals which hold the size of a collection, such as `let len = table_vec::length(v)`.
+        // The size may be adjusted before it is stored, such as `table_vec::length(v) - 1`.
+        let mut size_locals = BTreeMap::new();
+        for (offset, instr) in code.iter().enumerate() {
+            let collection = match collection_size_call(view, instr) {
+                Some(collection) => collection,
+                None => continue,
+            };
+            if let Some(Bytecode::StLoc(local)) = code[offset + 1..]
+                .iter()
+                .find(|instr| matches!(instr, Bytecode::StLoc(_)) || is_branch(instr))
+            {
+                size_locals.insert(*local, collection);
+            }
+        }
+
+        let mut reported = BTreeSet::new();
+        for (offset, instr) in code.iter().enumerate() {
+            // A branch to an earlier offset is the back edge of a loop.
+            let head = match instr {
+                Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target)
+                    if (*target as usize) <= offset =>
+                {
+                    *target as usize
+                }
+                _ => continue,
+            };
+            let exits_loop_at =
+                |target: usize| !(head..=offset).contains(&skip_branches(code, target));
+            // Only the conditions which exit the loop bound it, the other conditions in the loop
+            // body may use the size of a collection without making the loop unbounded.
+            let mut block_start = head;
+            for cond_offset in head..offset {
+                let exits_loop = match &code[cond_offset] {
+                    // Either branch of the condition may leave the loop, such as `if (cond) break`.
+                    Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+                        exits_loop_at(*target as usize) || exits_loop_at(cond_offset + 1)
+                    }
+                    instr => {
+                        if is_branch(instr) {
+                            block_start = cond_offset + 1;
+                        }
+                        continue;
+                    }
+                };
+                let condition = &code[block_start..cond_offset];
+                block_start = cond_offset + 1;
+                if !exits_loop {
+                    continue;
+                }
+                let collection = condition.iter().find_map(|instr| match instr {
+                    Bytecode::CopyLoc(local) | Bytecode::MoveLoc(local) => {
+                        size_locals.get(local).cloned()
+                    }
+                    instr => collection_size_call(view, instr),
+                });
+                let collection = match collection {
+                    Some(collection) if reported.insert(collection.clone()) => collection,
+                    _ => continue,
+                };
+                let loc = fun
+                    .get_bytecode_loc(cond_offset as u16)
+                    .unwrap_or_else(|| fun.get_loc());
+                self.report(
+                    LintRule::UnboundedLoop,
+                    module,
+                    fun.get_full_name_str(),
+                    &loc,
+                    format!(
+                        "the function `{}` has a loop bounded by the size of a `{}`, the size of the collection is not bounded and the loop may run out of gas",
+                        fun.get_full_name_str(),
+                        collection
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_data_struct_arguments(
+        &mut self,
+        module: &ModuleEnv,
+        fun: &FunctionEnv,
+        view: &BinaryIndexedView,
+    ) {
+        let code = match fun.get_bytecode() {
+            Some(code) => code,
+            None => return,
+        };
+        for (offset, instr) in code.iter().enumerate() {
+            let FunctionInstantiation {
+                handle,
+                type_parameters,
+            } = match instr {
+                Bytecode::CallGeneric(inst) => view.function_instantiation_at(*inst),
+                _ => continue,
+            };
+            let (address, module_name, function_name) = function_handle_name(view, *handle);
+            let callee = format!(
+                "{}::{}::{}",
+                address.to_hex_literal(),
+                module_name,
+                function_name
+            );
+            if !self.data_struct_functions.contains(&callee) {
+                continue;
+            }
+            let mut missing = BTreeSet::new();
+            for type_arg in &view.signature_at(*type_parameters).0 {
+                self.collect_non_data_structs(view, type_arg, &mut missing);
+            }
+            let loc = fun
+                .get_bytecode_loc(offset as u16)
+                .unwrap_or_else(|| fun.get_loc());
+            for struct_name in missing {
+                self.report(
+                    LintRule::MissingDataStruct,
+                    module,
+                    fun.get_full_name_str(),
+                    &loc,
+                    format!(
+                        "the struct `{}` is decoded by `{}`, but it is not a `#[data_struct]`",
+                        struct_name, callee
+                    ),
+                );
+            }
+        }
+    }
+
+    fn collect_non_data_structs(
+        &self,
+        view: &BinaryIndexedView,
+        type_arg: &SignatureToken,
+        missing: &mut BTreeSet<String>,
+    ) {
+        match type_arg {
+            SignatureToken::Vector(item) => self.collect_non_data_structs(view, item, missing),
+            SignatureToken::Struct(handle) => {
+                let struct_name = struct_handle_name(view, *handle);
+                if !is_allowed_data_struct_type(&struct_name)
+                    && !self.data_structs.contains(&struct_name)
+                {
+                    missing.insert(struct_name);
+                }
+            }
+            SignatureToken::StructInstantiation(handle, type_args) => {
+                let struct_name = struct_handle_name(view, *handle);
+                if is_std_option_type(&struct_name) {
+                    for type_arg in type_args {
+                        self.collect_non_data_structs(view, type_arg, missing);
+                    }
+                } else if !self.data_structs.contains(&struct_name) {
+                    missing.insert(struct_name);
+                }
+            }
+            // The type parameters are checked at the call site which instantiates them.
+            _ => {}
+        }
+    }
+
+    fn check_mut_object_parameters(
+        &mut self,
+        module: &ModuleEnv,
+        fun: &FunctionEnv,
+        view: &BinaryIndexedView,
+    ) {
+        if !fun.is_entry() {
+            return;
+        }
+        let mut_objects = fun
+            .get_parameters()
+            .into_iter()
+            .filter(|param| match &param.1 {
+                Type::Reference(ReferenceKind::Mutable, inner) => self.is_object_type(inner),
+                _ => false,
+            })
+            .map(|param| param.0.display(fun.symbol_pool()).to_string())
+            .collect::<Vec<_>>();
+        if mut_objects.is_empty() {
+            return;
+        }
+        let checks_ownership = fun
+            .get_bytecode()
+            .map(|code| {
+                code.iter().any(|instr| {
+                    let handle = match instr {
+                        Bytecode::Call(handle) => *handle,
+                        Bytecode::CallGeneric(inst) => view.function_instantiation_at(*inst).handle,
+                        _ => return false,
+                    };
+                    let (address, module_name, function_name) = function_handle_name(view, handle);
+                    address == AccountAddress::TWO
+                        && module_name == "object"
+                        && OBJECT_OWNERSHIP_CHECK_FUNCTIONS.contains(&function_name.as_str())
+                })
+            })
+            .unwrap_or(false);
+        if checks_ownership {
+            return;
+        }
+        self.report(
+            LintRule::UncheckedMutObject,
+            module,
+            fun.get_full_name_str(),
+            &fun.get_loc(),
+            format!(
+                "the entry function `{}` takes `&mut Object<T>` parameters `{}` without checking the ownership, a shared object can be passed by any sender",
+                fun.get_full_name_str(),
+                mut_objects.join(", ")
+            ),
+        );
+    }
+
+    fn is_object_type(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Struct(module_id, struct_id, _) => {
+                let module = self.env.get_module(*module_id);
+                let struct_env = module.get_struct(*struct_id);
+                full_struct_name(&module, &struct_env) == "0x2::object::Object"
+            }
+            _ => false,
+        }
+    }
+
+    fn report(
+        &mut self,
+        rule: LintRule,
+        module: &ModuleEnv,
+        item: String,
+        loc: &Loc,
+        message: String,
+    ) {
+        let (file, location) = match self.env.get_file_and_location(loc) {
+            Some((file, location)) => (Some(file), Some(location)),
+            None => (None, None),
+        };
+        self.diagnostics.push(LintDiagnostic {
+            rule,
+            module: module.get_full_name_str(),
+            item,
+            message,
+            file,
+            line: location.as_ref().map(|location| location.line.0 + 1),
+            column: location.as_ref().map(|location| location.column.0 + 1),
+        });
+    }
+}
+
+fn full_struct_name(module: &ModuleEnv, struct_env: &StructEnv) -> String {
+    format!(
+        "{}::{}::{}",
+        module.self_address().expect_numerical().to_hex_literal(),
+        module.symbol_pool().string(module.get_name().name()),
+        module.symbol_pool().string(struct_env.get_name())
+    )
+}
+
+fn full_function_name(module: &ModuleEnv, fun: &FunctionEnv) -> String {
+    format!(
+        "{}::{}::{}",
+        module.self_address().expect_numerical().to_hex_literal(),
+        module.symbol_pool().string(module.get_name().name()),
+        module.symbol_pool().string(fun.get_name())
+    )
+}
+
+/// Returns the module of the collection if the instruction calls a function returning its size.
+fn collection_size_call(view: &BinaryIndexedView, instr: &Bytecode) -> Option<String> {
+    let handle = match instr {
+        Bytecode::Call(handle) => *handle,
+        Bytecode::CallGeneric(inst) => view.function_instantiation_at(*inst).handle,
+        _ => return None,
+    };
+    let (address, module_name, function_name) = function_handle_name(view, handle);
+    (address == AccountAddress::TWO
+        && UNBOUNDED_COLLECTION_MODULES.contains(&module_name.as_str())
+        && COLLECTION_SIZE_FUNCTIONS.contains(&function_name.as_str()))
+    .then_some(module_name)
+}
+
+/// Returns the offset where the execution continues from `offset`, following the unconditional branches.
+fn skip_branches(code: &[Bytecode], mut offset: usize) -> usize {
+    for _ in 0..code.len() {
+        match code.get(offset) {
+            Some(Bytecode::Branch(target)) => offset = *target as usize,
+            _ => break,
+        }
+    }
+    offset
+}
+
+fn is_branch(instr: &Bytecode) -> bool {
+    matches!(
+        instr,
+        Bytecode::Branch(_)
+            | Bytecode::BrTrue(_)
+            | Bytecode::BrFalse(_)
+            | Bytecode::Ret
+            | Bytecode::Abort
+    )
+}
+
+fn function_handle_name(
+    view: &BinaryIndexedView,
+    handle: FunctionHandleIndex,
+) -> (AccountAddress, String, String) {
+    let fhandle = view.function_handle_at(handle);
+    let module_handle = view.module_handle_at(fhandle.module);
+    (
+        *view.address_identifier_at(module_handle.address),
+        view.identifier_at(module_handle.name).to_string(),
+        view.identifier_at(fhandle.name).to_string(),
+    )
+}
+
+fn struct_handle_name(view: &BinaryIndexedView, handle: StructHandleIndex) -> String {
+    let shandle = view.struct_handle_at(handle);
+    let module_handle = view.module_handle_at(shandle.module);
+    format!(
+        "{}::{}::{}",
+        view.address_identifier_at(module_handle.address)
+            .to_hex_literal(),
+        view.identifier_at(module_handle.name),
+        view.identifier_at(shandle.name)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+
+    /// The diagnostics of the package in `tests/lint`, the items named `unchecked_*` or
+    /// `Unchecked*` are expected to be reported.
+    static DIAGNOSTICS: Lazy<Vec<LintDiagnostic>> = Lazy::new(|| {
+        let package_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lint");
+        lint_package(&package_path, BuildConfig::default()).unwrap()
+    });
+
+    fn reported_items(rule: LintRule) -> Vec<String> {
+        DIAGNOSTICS
+            .iter()
+            .filter(|diagnostic| diagnostic.rule == rule)
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.module, "0x42::lint_test");
+                assert!(diagnostic.line.is_some());
+                diagnostic.item.rsplit("::").next().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unbounded_loop() {
+        assert_eq!(
+            reported_items(LintRule::UnboundedLoop),
+            vec!["unchecked_sum", "unchecked_sum_by_length_local"]
+        );
+    }
+
+    #[test]
+    fn test_missing_data_struct() {
+        assert_eq!(
+            reported_items(LintRule::MissingDataStruct),
+            vec!["unchecked_decode"]
+        );
+    }
+
+    #[test]
+    fn test_object_ability() {
+        assert_eq!(
+            reported_items(LintRule::ObjectAbility),
+            vec!["UncheckedCopyableCounter", "UncheckedDroppableCounter"]
+        );
+    }
+
+    #[test]
+    fn test_unchecked_mut_object() {
+        assert_eq!(
+            reported_items(LintRule::UncheckedMutObject),
+            vec!["unchecked_increase"]
+        );
+    }
+}
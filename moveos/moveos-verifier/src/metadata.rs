@@ -1137,7 +1137,7 @@ pub fn is_std_option_type(full_struct_name: &str) -> bool {
     matches!(full_struct_name, "0x1::option::Option")
 }
 
-fn is_data_struct_annotation(struct_env: &StructEnv, module_env: &ModuleEnv) -> bool {
+pub(crate) fn is_data_struct_annotation(struct_env: &StructEnv, module_env: &ModuleEnv) -> bool {
     let struct_attributes = struct_env.get_attributes().to_vec();
     for attribute in struct_attributes.iter() {
         if let Attribute::Apply(_, symbol, _) = attribute {
@@ -1572,7 +1572,7 @@ fn check_gas_charge_post_function(fenv: &FunctionEnv, _global_env: &GlobalEnv) -
 // ----------------------------------------------------------------------------------
 // Helpers
 
-fn has_attribute(global_env: &GlobalEnv, fun: &FunctionEnv, attr_name: &str) -> bool {
+pub(crate) fn has_attribute(global_env: &GlobalEnv, fun: &FunctionEnv, attr_name: &str) -> bool {
     fun.get_attributes().iter().any(|attr| {
         if let Attribute::Apply(_, name, _) = attr {
             global_env.symbol_pool().string(*name).as_str() == attr_name
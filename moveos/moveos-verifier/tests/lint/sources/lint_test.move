@@ -0,0 +1,95 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+/// The positive and negative cases of the lints, the functions and structs named `unchecked_*`
+/// or `Unchecked*` are reported.
+module lint_test::lint_test {
+    use std::signer;
+    use moveos_std::bcs;
+    use moveos_std::object::{Self, Object};
+    use moveos_std::table_vec::{Self, TableVec};
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    struct UncheckedDroppableCounter has key, drop {
+        value: u64,
+    }
+
+    struct UncheckedCopyableCounter has key, copy {
+        value: u64,
+    }
+
+    struct StoreCounter has key, store {
+        value: u64,
+    }
+
+    #[data_struct]
+    struct Data has copy, drop {
+        value: u64,
+    }
+
+    struct UncheckedData has copy, drop {
+        value: u64,
+    }
+
+    public fun unchecked_sum(values: &TableVec<u64>): u64 {
+        let (i, sum) = (0, 0);
+        while (i < table_vec::length(values)) {
+            sum = sum + *table_vec::borrow(values, i);
+            i = i + 1;
+        };
+        sum
+    }
+
+    public fun unchecked_sum_by_length_local(values: &TableVec<u64>): u64 {
+        let (i, sum) = (0, 0);
+        let len = table_vec::length(values);
+        loop {
+            if (i >= len) break;
+            sum = sum + *table_vec::borrow(values, i);
+            i = i + 1;
+        };
+        sum
+    }
+
+    /// The loop is bounded by a constant, the size of the collection is only used in the body.
+    public fun sum_first_ten(values: &TableVec<u64>): u64 {
+        let (i, sum) = (0, 0);
+        while (i < 10) {
+            if (i < table_vec::length(values)) {
+                sum = sum + *table_vec::borrow(values, i);
+            };
+            i = i + 1;
+        };
+        sum
+    }
+
+    public fun unchecked_decode(bytes: vector<u8>): UncheckedData {
+        bcs::from_bytes<UncheckedData>(bytes)
+    }
+
+    public fun decode(bytes: vector<u8>): Data {
+        bcs::from_bytes<Data>(bytes)
+    }
+
+    public fun decode_vector(bytes: vector<u8>): vector<Data> {
+        bcs::from_bytes<vector<Data>>(bytes)
+    }
+
+    entry fun unchecked_increase(counter: &mut Object<Counter>) {
+        let counter = object::borrow_mut(counter);
+        counter.value = counter.value + 1;
+    }
+
+    entry fun increase(account: &signer, counter: &mut Object<Counter>) {
+        assert!(object::owner(counter) == signer::address_of(account), 1);
+        let counter = object::borrow_mut(counter);
+        counter.value = counter.value + 1;
+    }
+
+    entry fun check_limit(counter: &Object<Counter>) {
+        assert!(object::borrow(counter).value < 100, 2);
+    }
+}
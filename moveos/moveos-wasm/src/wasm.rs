@@ -5,8 +5,7 @@ use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex};
 
-use once_cell::sync::Lazy;
-use rand;
+use better_any::{Tid, TidAble};
 use tracing::{debug, error, warn};
 use wasmer::Value::I32;
 use wasmer::*;
@@ -42,47 +41,55 @@ impl WASMInstance {
     }
 }
 
-/*
-TODO:
-The WASMInstance must be protected by the locker which owned by the signer
-    while we enable the parallel tx execution.
-The way we're doing it now is by using this big global lock, but it's too broad.
- */
-static mut GLOBAL_INSTANCE_POOL: Lazy<Arc<Mutex<BTreeMap<u64, WASMInstance>>>> =
-    Lazy::new(|| Arc::new(Mutex::new(BTreeMap::new())));
-
-pub fn insert_wasm_instance(instance: WASMInstance) -> anyhow::Result<u64> {
-    loop {
-        unsafe {
-            let instance_id: u64 = rand::random();
-            let mut instance_pool = match GLOBAL_INSTANCE_POOL.lock() {
-                Ok(pool_guard) => pool_guard,
-                Err(_) => {
-                    return Err(anyhow::Error::msg("get global instance pool failed"));
-                }
-            };
-            if instance_pool.get(&instance_id).is_none() {
-                instance_pool.insert(instance_id, instance);
-                return Ok(instance_id);
-            }
+/// The WASM instances created in a MoveOS session, it is a native context extension of the session,
+/// so the instances are dropped at the end of the session even if the transaction aborts.
+/// Every instance has its own lock, the sessions do not share any instance.
+#[derive(Default, Tid)]
+pub struct NativeWASMContext {
+    id_seed: u64,
+    instances_created: u64,
+    instances: BTreeMap<u64, Arc<Mutex<WASMInstance>>>,
+}
+
+impl NativeWASMContext {
+    /// The instance ids are derived from the `tx_hash` and the count of instances created in the session,
+    /// so they are deterministic for the same transaction.
+    pub fn new(tx_hash: &[u8]) -> Self {
+        let mut seed_bytes = [0u8; 8];
+        let len = tx_hash.len().min(seed_bytes.len());
+        seed_bytes[..len].copy_from_slice(&tx_hash[..len]);
+        Self {
+            id_seed: u64::from_le_bytes(seed_bytes),
+            instances_created: 0,
+            instances: BTreeMap::new(),
         }
     }
-}
 
-pub fn get_instance_pool() -> Arc<Mutex<BTreeMap<u64, WASMInstance>>> {
-    unsafe { GLOBAL_INSTANCE_POOL.clone() }
-}
+    pub fn insert_instance(&mut self, instance: WASMInstance) -> u64 {
+        let mut instance_id = self.next_instance_id();
+        while self.instances.contains_key(&instance_id) {
+            instance_id = self.next_instance_id();
+        }
+        self.instances
+            .insert(instance_id, Arc::new(Mutex::new(instance)));
+        instance_id
+    }
 
-pub fn remove_instance(instance_id: u64) -> anyhow::Result<()> {
-    unsafe {
-        let mut instance_pool = match GLOBAL_INSTANCE_POOL.lock() {
-            Ok(pool_guard) => pool_guard,
-            Err(_) => {
-                return Err(anyhow::Error::msg("get global instance pool failed"));
-            }
-        };
-        instance_pool.remove(&instance_id);
-        Ok(())
+    pub fn get_instance(&self, instance_id: u64) -> Option<Arc<Mutex<WASMInstance>>> {
+        self.instances.get(&instance_id).cloned()
+    }
+
+    pub fn remove_instance(&mut self, instance_id: u64) -> Option<Arc<Mutex<WASMInstance>>> {
+        self.instances.remove(&instance_id)
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    fn next_instance_id(&mut self) -> u64 {
+        self.instances_created += 1;
+        self.id_seed.wrapping_add(self.instances_created)
     }
 }
 
@@ -331,3 +338,27 @@ pub fn create_wasm_instance(code: &[u8]) -> anyhow::Result<WASMInstance> {
         gas_meter,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_context_instance_ids() {
+        let tx_hash = [1u8; 32];
+        let mut context = NativeWASMContext::new(&tx_hash);
+        let first_id = context.insert_instance(create_wasm_instance(b"(module)").unwrap());
+        let second_id = context.insert_instance(create_wasm_instance(b"(module)").unwrap());
+        assert_ne!(first_id, second_id);
+        assert_eq!(context.instance_count(), 2);
+
+        // the same transaction derives the same ids
+        let mut other_context = NativeWASMContext::new(&tx_hash);
+        let other_id = other_context.insert_instance(create_wasm_instance(b"(module)").unwrap());
+        assert_eq!(first_id, other_id);
+
+        assert!(context.remove_instance(first_id).is_some());
+        assert!(context.get_instance(first_id).is_none());
+        assert!(context.get_instance(second_id).is_some());
+    }
+}
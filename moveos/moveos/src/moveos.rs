@@ -7,6 +7,7 @@ use crate::gas::table::{
 };
 use crate::vm::data_cache::MoveosDataCache;
 use crate::vm::moveos_vm::{MoveOSSession, MoveOSVM};
+use crate::vm::tracer::{CallTrace, CallTracer};
 use anyhow::{bail, format_err, Error, Result};
 use move_binary_format::binary_views::BinaryIndexedView;
 use move_binary_format::errors::VMError;
@@ -23,7 +24,9 @@ use move_core_types::{
 use move_vm_runtime::config::VMConfig;
 use move_vm_runtime::data_cache::TransactionCache;
 use move_vm_runtime::native_functions::NativeFunction;
-use moveos_common::types::ClassifiedGasMeter;
+use moveos_common::types::{ClassifiedGasMeter, SwitchableGasMeter};
+use moveos_gas_profiling::log::TransactionGasLog;
+use moveos_gas_profiling::profiler::{GasProfiler, ProfileGasMeter};
 use moveos_store::config_store::ConfigDBStore;
 use moveos_store::event_store::EventDBStore;
 use moveos_store::state_store::statedb::StateDBStore;
@@ -35,7 +38,7 @@ use moveos_types::moveos_std::gas_schedule::{GasScheduleConfig, GasScheduleUpdat
 use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::moveos_std::tx_context::TxContext;
 use moveos_types::moveos_std::tx_result::TxResult;
-use moveos_types::state::{MoveStructState, MoveStructType, ObjectState};
+use moveos_types::state::{MoveStructState, MoveStructType, ObjectState, ReadSet};
 use moveos_types::state_resolver::{GenesisResolver, RootObjectResolver};
 use moveos_types::transaction::{FunctionCall, VMErrorInfo};
 use moveos_types::transaction::{
@@ -264,6 +267,107 @@ impl MoveOS {
         &self,
         tx: VerifiedMoveOSTransaction,
     ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>)> {
+        let (output, vm_error_info, _read_set) = self.execute_and_track_reads(tx)?;
+        Ok((output, vm_error_info))
+    }
+
+    /// Execute the transaction and return the objects it read,
+    /// the read set is used to detect the conflicts when transactions are executed in parallel.
+    pub fn execute_and_track_reads(
+        &self,
+        tx: VerifiedMoveOSTransaction,
+    ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>, ReadSet)> {
+        let gas_meter = self.new_gas_meter(&tx.root, &tx.ctx)?;
+        self.execute_with_gas_meter(tx, gas_meter)
+    }
+
+    /// Execute the transaction under the gas profiler, the output is the same as `execute`,
+    /// and the gas used by every function and instruction is logged.
+    pub fn execute_with_gas_profile(
+        &self,
+        tx: VerifiedMoveOSTransaction,
+    ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>, TransactionGasLog)> {
+        let gas_meter = self.new_gas_meter(&tx.root, &tx.ctx)?;
+        let mut gas_profiler = match &tx.action {
+            VerifiedMoveAction::Function { call, .. } => GasProfiler::new_function(
+                gas_meter,
+                call.function_id.module_id.clone(),
+                call.function_id.function_name.clone(),
+                call.ty_args.clone(),
+            ),
+            _ => bail!("Gas profiling only supports the function call transaction"),
+        };
+        let (output, vm_error_info, _read_set) =
+            self.execute_with_gas_meter(tx, gas_profiler.clone())?;
+        Ok((output, vm_error_info, gas_profiler.finish()))
+    }
+
+    /// Execute the transaction under the call tracer, the output is the same as `execute`,
+    /// and the call tree and the objects read by the transaction are returned.
+    pub fn execute_with_trace(
+        &self,
+        tx: VerifiedMoveOSTransaction,
+    ) -> Result<(
+        RawTransactionOutput,
+        Option<VMErrorInfo>,
+        CallTrace,
+        ReadSet,
+    )> {
+        let gas_meter = self.new_gas_meter(&tx.root, &tx.ctx)?;
+        let root_call = match &tx.action {
+            VerifiedMoveAction::Function { call, .. } => {
+                let mut root_call = CallTrace::new_function(
+                    &call.function_id.module_id,
+                    call.function_id.function_name.as_str(),
+                    &call.ty_args,
+                );
+                root_call.args = call
+                    .args
+                    .iter()
+                    .map(|arg| format!("0x{}", hex::encode(arg)))
+                    .collect();
+                root_call
+            }
+            VerifiedMoveAction::Script { call } => CallTrace::new(
+                "<script>".to_string(),
+                call.args
+                    .iter()
+                    .map(|arg| format!("0x{}", hex::encode(arg)))
+                    .collect(),
+            ),
+            VerifiedMoveAction::ModuleBundle { .. } => {
+                CallTrace::new("<publish>".to_string(), vec![])
+            }
+        };
+        let mut tracer = CallTracer::new(gas_meter, root_call);
+        let (output, vm_error_info, read_set) = self.execute_with_gas_meter(tx, tracer.clone())?;
+        Ok((output, vm_error_info, tracer.finish(), read_set))
+    }
+
+    fn new_gas_meter(&self, root: &ObjectMeta, ctx: &TxContext) -> Result<MoveOSGasMeter> {
+        let feature_resolver = RootObjectResolver::new(root.clone(), &self.db);
+        let feature_store_opt = load_feature_store_object(&feature_resolver);
+        let has_io_tired_write_feature = match feature_store_opt {
+            None => false,
+            Some(feature_store) => feature_store.has_value_size_gas_feature(),
+        };
+
+        let cost_table = self.load_cost_table(root)?;
+        Ok(MoveOSGasMeter::new(
+            cost_table,
+            ctx.max_gas_amount,
+            has_io_tired_write_feature,
+        ))
+    }
+
+    fn execute_with_gas_meter<G>(
+        &self,
+        tx: VerifiedMoveOSTransaction,
+        gas_meter: G,
+    ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>, ReadSet)>
+    where
+        G: SwitchableGasMeter + ClassifiedGasMeter + Clone,
+    {
         let VerifiedMoveOSTransaction { root, ctx, action } = tx;
         let tx_hash = ctx.tx_hash();
         if tracing::enabled!(tracing::Level::DEBUG) {
@@ -280,17 +384,6 @@ impl MoveOS {
         // The variables in TxContext kv store before this executions should not be cleaned,
         // So we keep a backup here, and then insert to the TxContext kv store when session respawed.
         let system_env = ctx.map.clone();
-
-        let feature_resolver = RootObjectResolver::new(root.clone(), &self.db);
-        let feature_store_opt = load_feature_store_object(&feature_resolver);
-        let has_io_tired_write_feature = match feature_store_opt {
-            None => false,
-            Some(feature_store) => feature_store.has_value_size_gas_feature(),
-        };
-
-        let cost_table = self.load_cost_table(&root)?;
-        let gas_meter =
-            MoveOSGasMeter::new(cost_table, ctx.max_gas_amount, has_io_tired_write_feature);
         let tx_size = ctx.tx_size;
 
         let resolver = RootObjectResolver::new(root, &self.db);
@@ -441,9 +534,9 @@ impl MoveOS {
     // Execute action with pre_execute and post_execute.
     // Return the action execution status if success,
     // else return VMError and a bool which indicate if we should respawn the session.
-    fn execute_action(
+    fn execute_action<G: SwitchableGasMeter + ClassifiedGasMeter>(
         &self,
-        session: &mut MoveOSSession<'_, '_, RootObjectResolver<MoveOSStore>, MoveOSGasMeter>,
+        session: &mut MoveOSSession<'_, '_, RootObjectResolver<MoveOSStore>, G>,
         action: VerifiedMoveAction,
         tx_size: u64,
     ) -> Result<(), VMError> {
@@ -456,13 +549,13 @@ impl MoveOS {
         session.execute_move_action(action)
     }
 
-    fn execution_cleanup(
+    fn execution_cleanup<G: SwitchableGasMeter + ClassifiedGasMeter>(
         &self,
         is_system_call: bool,
-        mut session: MoveOSSession<'_, '_, RootObjectResolver<MoveOSStore>, MoveOSGasMeter>,
+        mut session: MoveOSSession<'_, '_, RootObjectResolver<MoveOSStore>, G>,
         status: VMStatus,
         vm_error_info: Option<VMErrorInfo>,
-    ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>)> {
+    ) -> Result<(RawTransactionOutput, Option<VMErrorInfo>, ReadSet)> {
         let kept_status = match status.keep_or_discard() {
             Ok(kept_status) => {
                 if is_system_call && kept_status != KeptVMStatus::Executed {
@@ -518,9 +611,15 @@ impl MoveOS {
             self.cost_table.write().take();
         }
 
+        let read_set = session.read_set();
         let (_ctx, mut output) = session.finish_with_extensions(kept_status)?;
         output.is_gas_upgrade = gas_upgrade;
-        Ok((output, vm_error_info))
+        Ok((output, vm_error_info, read_set))
+    }
+
+    /// Drop the cached cost table, it is loaded from the state again when executing the next transaction.
+    pub fn flush_cost_table(&self) {
+        self.cost_table.write().take();
     }
 
     pub fn flush_module_cache(&self, is_upgrade: bool) -> Result<()> {
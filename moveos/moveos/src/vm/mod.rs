@@ -5,6 +5,7 @@
 #[allow(dead_code)]
 pub mod data_cache;
 pub mod moveos_vm;
+pub mod tracer;
 pub mod tx_argument_resolver;
 pub mod vm_status_explainer;
 
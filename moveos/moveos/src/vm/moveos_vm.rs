@@ -35,7 +35,7 @@ use moveos_stdlib::natives::moveos_stdlib::{
     event::NativeEventContext, move_module::NativeModuleContext,
 };
 use moveos_store::load_feature_store_object;
-use moveos_types::state::ObjectState;
+use moveos_types::state::{ObjectState, ReadSet};
 use moveos_types::{addresses, transaction::RawTransactionOutput};
 use moveos_types::{
     function_return_value::FunctionReturnValue,
@@ -49,6 +49,7 @@ use moveos_types::{
     transaction::{FunctionCall, MoveAction, VerifiedMoveAction},
 };
 use moveos_verifier::verifier::INIT_FN_NAME_IDENTIFIER;
+use moveos_wasm::wasm::NativeWASMContext;
 use parking_lot::RwLock;
 use std::collections::BTreeSet;
 use std::rc::Rc;
@@ -173,7 +174,10 @@ where
         let new_ctx = ctx.spawn(env);
         // We get the root object from the remote, because the root object may be changed during the transaction execution
         let root = self.remote.root().clone();
-        let object_runtime = Rc::new(RwLock::new(ObjectRuntime::new(new_ctx, root, self.remote)));
+        let mut object_runtime = ObjectRuntime::new(new_ctx, root, self.remote);
+        // The result of the transaction depends on the reads before it failed
+        object_runtime.add_prior_reads(self.read_set());
+        let object_runtime = Rc::new(RwLock::new(object_runtime));
         Self {
             session: Self::new_inner_session(self.vm, self.remote, object_runtime.clone()),
             object_runtime,
@@ -195,6 +199,8 @@ where
         ));
         extensions.add(NativeModuleContext::new(remote));
         extensions.add(NativeEventContext::default());
+        let wasm_ext = NativeWASMContext::new(&object_runtime.read().tx_context().tx_hash);
+        extensions.add(wasm_ext);
 
         // The VM code loader has bugs around module upgrade. After a module upgrade, the internal
         // cache needs to be flushed to work around those bugs.
@@ -206,6 +212,11 @@ where
         vm.new_session_with_cache_and_extensions(data_store, extensions)
     }
 
+    /// The objects read by the session, it is used to detect the conflicts in parallel execution.
+    pub fn read_set(&self) -> ReadSet {
+        self.object_runtime.read().read_set()
+    }
+
     pub(crate) fn tx_context(&self) -> TxContext {
         self.object_runtime.read().tx_context().clone()
     }
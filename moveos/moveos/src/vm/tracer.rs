@@ -0,0 +1,555 @@
+// Copyright (c) Kanari Network
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::errors::PartialVMResult;
+use move_binary_format::file_format::CodeOffset;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::gas_algebra::{InternalGas, NumArgs, NumBytes};
+use move_core_types::language_storage::{ModuleId, TypeTag};
+use move_core_types::u256::U256;
+use move_vm_types::gas::{GasMeter, SimpleInstruction};
+use move_vm_types::views::{TypeView, ValueView, ValueVisitor};
+use moveos_common::types::{ClassifiedGasMeter, GasStatement, SwitchableGasMeter};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+/// The max length of a rendered argument or return value, the longer values are truncated.
+const MAX_VALUE_DISPLAY_LEN: usize = 256;
+
+/// A Move function call and the calls it made during the transaction execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallTrace {
+    /// The function name, such as `0x3::transfer::transfer_coin<0x3::kari::KARI>`.
+    pub function: String,
+    /// The rendered arguments of the call.
+    pub args: Vec<String>,
+    /// The rendered return values of the call, it is empty if the call aborted.
+    pub return_values: Vec<String>,
+    pub is_native: bool,
+    /// The execution gas used by the call, include the calls it made.
+    pub gas_used: u64,
+    pub calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    pub fn new(function: String, args: Vec<String>) -> Self {
+        Self {
+            function,
+            args,
+            return_values: vec![],
+            is_native: false,
+            gas_used: 0,
+            calls: vec![],
+        }
+    }
+
+    pub fn new_function(module_id: &ModuleId, func_name: &str, ty_args: &[TypeTag]) -> Self {
+        Self::new(function_name(module_id, func_name, ty_args), vec![])
+    }
+}
+
+#[derive(Debug)]
+struct TraceFrame {
+    trace: CallTrace,
+    /// The gas balance when the call starts.
+    start_balance: InternalGas,
+}
+
+#[derive(Debug)]
+struct TraceState {
+    frames: Vec<TraceFrame>,
+    /// The gas balance when the last call starts or returns.
+    balance: InternalGas,
+}
+
+/// A gas meter which records the call tree of the transaction,
+/// all the charges are delegated to the base gas meter.
+///
+/// The state is shared between the clones, the tracer can be cloned into the session
+/// and the original one is used to take the trace after the execution.
+/// The calls are only recorded when metering, so the system pre_execute and post_execute
+/// functions are not traced.
+#[derive(Debug, Clone)]
+pub struct CallTracer<G> {
+    base: G,
+    state: Arc<RwLock<TraceState>>,
+    metering: bool,
+}
+
+macro_rules! delegate {
+    ($(
+        fn $fn: ident $(<$($lt: lifetime),*>)? (&mut self $(, $arg: ident : $ty: ty)* $(,)?) -> $ret_ty: ty;
+    )*) => {
+        $(fn $fn $(<$($lt)*>)? (&mut self, $($arg: $ty),*) -> $ret_ty {
+            self.base.$fn($($arg),*)
+        })*
+    };
+}
+
+impl<G: GasMeter> CallTracer<G> {
+    /// Create a tracer with the root call, it is the function or script of the transaction.
+    pub fn new(base: G, root: CallTrace) -> Self {
+        let balance = base.balance_internal();
+        Self {
+            base,
+            state: Arc::new(RwLock::new(TraceState {
+                frames: vec![TraceFrame {
+                    trace: root,
+                    start_balance: balance,
+                }],
+                balance,
+            })),
+            metering: true,
+        }
+    }
+
+    fn push_frame(&mut self, trace: CallTrace, start_balance: InternalGas) {
+        if !self.metering {
+            return;
+        }
+        let mut state = self.state.write().unwrap();
+        state.balance = start_balance;
+        state.frames.push(TraceFrame {
+            trace,
+            start_balance,
+        });
+    }
+
+    /// Pop the current frame and append it to the calls of its caller, the root frame is never popped.
+    fn pop_frame(&mut self, return_values: Vec<String>, is_native: bool) {
+        if !self.metering {
+            return;
+        }
+        let balance = self.base.balance_internal();
+        let mut state = self.state.write().unwrap();
+        state.balance = balance;
+        if state.frames.len() <= 1 {
+            if let Some(root) = state.frames.first_mut() {
+                root.trace.return_values = return_values;
+            }
+            return;
+        }
+        let mut frame = state.frames.pop().expect("frame must exist");
+        frame.trace.gas_used = gas_between(frame.start_balance, balance);
+        frame.trace.return_values = return_values;
+        frame.trace.is_native = is_native;
+        state
+            .frames
+            .last_mut()
+            .expect("frame must exist")
+            .trace
+            .calls
+            .push(frame.trace);
+    }
+
+    /// Finish the tracing and return the call tree.
+    /// The frames left by an aborted execution are closed, the innermost one is where it aborted.
+    pub fn finish(&mut self) -> CallTrace {
+        let mut state = self.state.write().unwrap();
+        let balance = state.balance;
+        while state.frames.len() > 1 {
+            let mut frame = state.frames.pop().expect("frame must exist");
+            frame.trace.gas_used = gas_between(frame.start_balance, balance);
+            state
+                .frames
+                .last_mut()
+                .expect("frame must exist")
+                .trace
+                .calls
+                .push(frame.trace);
+        }
+        let mut root = state.frames.pop().expect("frame must exist");
+        root.trace.gas_used = gas_between(root.start_balance, balance);
+        root.trace
+    }
+}
+
+impl<G: GasMeter> GasMeter for CallTracer<G> {
+    delegate! {
+        fn charge_br_true(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_br_false(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_branch(&mut self, target_offset: CodeOffset) -> PartialVMResult<()>;
+
+        fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()>;
+
+        fn charge_ld_const_after_deserialization(&mut self, val: impl ValueView)
+            -> PartialVMResult<()>;
+
+        fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_pack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_unpack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_write_ref(
+            &mut self,
+            new_val: impl ValueView,
+            old_val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_borrow_global(
+            &mut self,
+            is_mut: bool,
+            is_generic: bool,
+            ty: impl TypeView,
+            is_success: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_exists(
+            &mut self,
+            is_generic: bool,
+            ty: impl TypeView,
+            exists: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_move_from(
+            &mut self,
+            is_generic: bool,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+        ) -> PartialVMResult<()>;
+
+        fn charge_move_to(
+            &mut self,
+            is_generic: bool,
+            ty: impl TypeView,
+            val: impl ValueView,
+            is_success: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_pack<'a>(
+            &mut self,
+            ty: impl TypeView + 'a,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_vec_borrow(
+            &mut self,
+            is_mut: bool,
+            ty: impl TypeView,
+            is_success: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_push_back(
+            &mut self,
+            ty: impl TypeView,
+            val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_pop_back(
+            &mut self,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_unpack(
+            &mut self,
+            ty: impl TypeView,
+            expect_num_elements: NumArgs,
+            elems: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_load_resource(
+            &mut self,
+            addr: AccountAddress,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+            bytes_loaded: NumBytes,
+        ) -> PartialVMResult<()>;
+
+        fn charge_native_function_before_execution(
+            &mut self,
+            ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_drop_frame(
+            &mut self,
+            locals: impl Iterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+    }
+
+    fn balance_internal(&self) -> InternalGas {
+        self.base.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.base.charge_simple_instr(instr)
+    }
+
+    fn records_return_values(&self) -> bool {
+        self.metering
+    }
+
+    fn record_return_values(
+        &mut self,
+        ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        // The return of the root function does not pop the root frame.
+        self.pop_frame(ret_vals.map(render_value).collect(), false);
+        Ok(())
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let start_balance = self.base.balance_internal();
+        let trace = CallTrace::new(
+            function_name(module_id, func_name, &[]),
+            args.clone().map(render_value).collect(),
+        );
+        let res = self
+            .base
+            .charge_call(module_id, func_name, args, num_locals);
+        self.push_frame(trace, start_balance);
+        res
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        let start_balance = self.base.balance_internal();
+        let ty_tags = ty_args
+            .clone()
+            .map(|ty| ty.to_type_tag())
+            .collect::<Vec<_>>();
+        let trace = CallTrace::new(
+            function_name(module_id, func_name, &ty_tags),
+            args.clone().map(render_value).collect(),
+        );
+        let res = self
+            .base
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals);
+        self.push_frame(trace, start_balance);
+        res
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView> + Clone>,
+    ) -> PartialVMResult<()> {
+        // The VM calls `charge_call/charge_call_generic` before it knows whether the function is native,
+        // so the frame of the native function is already pushed and it is closed here.
+        let return_values = ret_vals
+            .clone()
+            .map(|vals| vals.map(render_value).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let res = self.base.charge_native_function(amount, ret_vals);
+        self.pop_frame(return_values, true);
+        res
+    }
+}
+
+impl<G: GasMeter + ClassifiedGasMeter> ClassifiedGasMeter for CallTracer<G> {
+    fn charge_execution(&mut self, gas_cost: u64) -> PartialVMResult<()> {
+        self.base.charge_execution(gas_cost)
+    }
+
+    fn charge_io_write(&mut self, data_size: u64) -> PartialVMResult<()> {
+        self.base.charge_io_write(data_size)
+    }
+
+    fn check_constrains(&self, max_gas_amount: u64) -> PartialVMResult<()> {
+        self.base.check_constrains(max_gas_amount)
+    }
+
+    fn gas_statement(&self) -> GasStatement {
+        self.base.gas_statement()
+    }
+}
+
+impl<G: GasMeter + SwitchableGasMeter> SwitchableGasMeter for CallTracer<G> {
+    fn stop_metering(&mut self) {
+        self.metering = false;
+        self.base.stop_metering();
+    }
+
+    fn start_metering(&mut self) {
+        self.metering = true;
+        self.base.start_metering();
+    }
+
+    fn is_metering(&self) -> bool {
+        self.metering
+    }
+}
+
+fn gas_between(start: InternalGas, end: InternalGas) -> u64 {
+    start
+        .checked_sub(end)
+        .unwrap_or_else(|| InternalGas::from(0))
+        .into()
+}
+
+fn function_name(module_id: &ModuleId, func_name: &str, ty_args: &[TypeTag]) -> String {
+    let mut name = format!("{}::{}", module_id.short_str_lossless(), func_name);
+    if !ty_args.is_empty() {
+        name.push_str(&format!(
+            "<{}>",
+            ty_args
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    name
+}
+
+/// Render the value in a Move-like format, such as `{0x1, [1, 2], &true}`.
+/// The struct field names are not available, so the structs are rendered as the tuples.
+pub fn render_value(value: impl ValueView) -> String {
+    let mut renderer = ValueRenderer::default();
+    value.visit(&mut renderer);
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct ValueRenderer {
+    out: String,
+    /// The open containers, the depth and the closing delimiter of each, and whether it has no child yet.
+    open: Vec<(usize, &'static str, bool)>,
+    truncated: bool,
+}
+
+impl ValueRenderer {
+    /// Close the containers deeper than the value, and write the separator before the value.
+    /// Returns false if the output is already truncated.
+    fn enter(&mut self, depth: usize) -> bool {
+        while let Some((open_depth, close, _)) = self.open.last() {
+            if *open_depth < depth {
+                break;
+            }
+            self.out.push_str(close);
+            self.open.pop();
+        }
+        if self.truncated {
+            return false;
+        }
+        if self.out.len() > MAX_VALUE_DISPLAY_LEN {
+            self.out.push_str("..");
+            self.truncated = true;
+            return false;
+        }
+        if let Some((_, close, first)) = self.open.last_mut() {
+            // The referenced value follows the `&` without separator.
+            if !*first && !close.is_empty() {
+                self.out.push_str(", ");
+            }
+            *first = false;
+        }
+        true
+    }
+
+    fn write(&mut self, depth: usize, val: impl std::fmt::Display) {
+        if self.enter(depth) {
+            let _ = write!(self.out, "{}", val);
+        }
+    }
+
+    fn open(&mut self, depth: usize, start: &str, close: &'static str) -> bool {
+        if self.enter(depth) {
+            self.out.push_str(start);
+            self.open.push((depth, close, true));
+            true
+        } else {
+            false
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while let Some((_, close, _)) = self.open.pop() {
+            self.out.push_str(close);
+        }
+        self.out
+    }
+}
+
+impl ValueVisitor for ValueRenderer {
+    fn visit_u8(&mut self, depth: usize, val: u8) {
+        self.write(depth, val)
+    }
+
+    fn visit_u16(&mut self, depth: usize, val: u16) {
+        self.write(depth, val)
+    }
+
+    fn visit_u32(&mut self, depth: usize, val: u32) {
+        self.write(depth, val)
+    }
+
+    fn visit_u64(&mut self, depth: usize, val: u64) {
+        self.write(depth, val)
+    }
+
+    fn visit_u128(&mut self, depth: usize, val: u128) {
+        self.write(depth, val)
+    }
+
+    fn visit_u256(&mut self, depth: usize, val: U256) {
+        self.write(depth, val)
+    }
+
+    fn visit_bool(&mut self, depth: usize, val: bool) {
+        self.write(depth, val)
+    }
+
+    fn visit_address(&mut self, depth: usize, val: AccountAddress) {
+        self.write(depth, val.to_hex_literal())
+    }
+
+    fn visit_struct(&mut self, depth: usize, _len: usize) -> bool {
+        self.open(depth, "{", "}")
+    }
+
+    fn visit_vec(&mut self, depth: usize, _len: usize) -> bool {
+        self.open(depth, "[", "]")
+    }
+
+    fn visit_ref(&mut self, depth: usize, _is_global: bool) -> bool {
+        self.open(depth, "&", "")
+    }
+
+    fn visit_vec_u8(&mut self, depth: usize, vals: &[u8]) {
+        let len = vals.len().min(MAX_VALUE_DISPLAY_LEN / 2);
+        let suffix = if len < vals.len() { ".." } else { "" };
+        self.write(depth, format!("0x{}{}", hex::encode(&vals[..len]), suffix))
+    }
+}
@@ -84,6 +84,30 @@ impl Compatibility {
         old_module: &CompiledModule,
         new_module: &CompiledModule,
     ) -> PartialVMResult<()> {
+        let errors = self.check_errors(old_module, new_module);
+        if !errors.is_empty() {
+            Err(
+                PartialVMError::new(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE).with_message(
+                    format!(
+                        "Module update failure: new module not compatible with \
+                        existing module in `{}`: {}",
+                        old_module.self_id(),
+                        errors.join(", ")
+                    ),
+                ),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check compatibility for `new_module` relative to old module `old_module`, and return every
+    /// incompatibility found instead of stopping at the first one.
+    pub fn check_errors(
+        &self,
+        old_module: &CompiledModule,
+        new_module: &CompiledModule,
+    ) -> Vec<String> {
         let mut errors = vec![];
 
         // module's name and address are unchanged
@@ -109,14 +133,20 @@ impl Compatibility {
                     // Also, struct layout cannot be guaranteed transitively, because after
                     // removing the struct, it could be re-added later with a different layout.
                     errors.push(format!("removed struct `{}`", old_struct.name()));
-                    break;
+                    continue;
                 }
             };
 
             if !struct_abilities_compatible(old_struct.abilities(), new_struct.abilities()) {
+                let removed_abilities = old_struct
+                    .abilities()
+                    .into_iter()
+                    .filter(|ability| !new_struct.abilities().has_ability(*ability))
+                    .map(|ability| format!("{:?}", ability).to_lowercase())
+                    .collect::<Vec<_>>();
                 errors.push(format!(
                     "removed abilities `{}` from struct `{}`",
-                    old_struct.abilities().has_copy(),
+                    removed_abilities.join(", "),
                     old_struct.name()
                 ));
             }
@@ -256,20 +286,7 @@ impl Compatibility {
             }
         }
 
-        if !errors.is_empty() {
-            Err(
-                PartialVMError::new(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE).with_message(
-                    format!(
-                        "Module update failure: new module not compatible with \
-                        existing module in `{}`: {}",
-                        old_view.id(),
-                        errors.join(", ")
-                    ),
-                ),
-            )
-        } else {
-            Ok(())
-        }
+        errors
     }
 }
 
@@ -113,3 +113,16 @@ fn deprecated_add_script_visibility() {
         .check(&friend_module, &script_module)
         .is_err());
 }
+
+#[test]
+fn check_errors_report_incompatibilities() {
+    let public_module = mk_module(Visibility::Public as u8);
+    let private_module = mk_module(Visibility::Private as u8);
+    assert!(Compatibility::full_check()
+        .check_errors(&public_module, &public_module)
+        .is_empty());
+    assert_eq!(
+        Compatibility::full_check().check_errors(&public_module, &private_module),
+        vec!["changed visibility of function `fn`".to_string()]
+    );
+}
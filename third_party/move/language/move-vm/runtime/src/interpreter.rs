@@ -153,6 +153,18 @@ impl Interpreter {
                     gas_meter
                         .charge_drop_frame(non_ref_vals.iter())
                         .map_err(|e| self.set_location(e))?;
+                    // Kanari patch: the return values of the frame are at the top of the shared
+                    // operand stack, they are only read if the gas meter records them, such as the
+                    // call tracer of `kanari transaction trace`.
+                    if gas_meter.records_return_values() {
+                        gas_meter
+                            .record_return_values(
+                                self.operand_stack
+                                    .last_n(current_frame.function.return_type_count())
+                                    .map_err(|e| set_err_info!(current_frame, e))?,
+                            )
+                            .map_err(|e| set_err_info!(current_frame, e))?;
+                    }
 
                     if let Some(frame) = self.call_stack.pop() {
                         // Note: the caller will find the callee's return values at the top of the shared operand stack
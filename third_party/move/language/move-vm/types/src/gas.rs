@@ -297,6 +297,22 @@ pub trait GasMeter {
         &mut self,
         locals: impl Iterator<Item = impl ValueView> + Clone,
     ) -> PartialVMResult<()>;
+
+    /// Whether `record_return_values` should be called when a Move function returns.
+    /// The interpreter only reads the return values on frame exit if this is true, so the meters
+    /// which do not record them add no cost to the execution.
+    fn records_return_values(&self) -> bool {
+        false
+    }
+
+    /// Record the return values of a Move function after its frame is dropped,
+    /// it is only called if `records_return_values` is true.
+    fn record_return_values(
+        &mut self,
+        _ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        Ok(())
+    }
 }
 
 /// A dummy gas meter that does not meter anything.
@@ -0,0 +1,43 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module allows a custom Move environment to run the unit tests under its own gas meter,
+//! instead of the cost table of the unit testing framework.
+
+use move_binary_format::errors::VMResult;
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use move_vm_runtime::{
+    data_cache::TransactionDataCache,
+    session::{SerializedReturnValues, Session},
+};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
+
+/// Executes the test function `module::function_name` with the serialized arguments in the session,
+/// bounded by the given gas budget. Returns the execution result and the gas used.
+pub type GasMeterHook = dyn Fn(
+        &mut Session<'_, '_, TransactionDataCache<'_>>,
+        &ModuleId,
+        &IdentStr,
+        Vec<Vec<u8>>,
+        u64,
+    ) -> (VMResult<SerializedReturnValues>, u64)
+    + Send
+    + Sync;
+
+static GAS_METER_HOOK: Lazy<Mutex<Option<Arc<GasMeterHook>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Sets a hook which executes every test function under a custom gas meter. The gas limit of the
+/// unit testing config is passed to the hook as the budget of each test, and the gas used returned
+/// by the hook is reported in the test statistics.
+///
+/// Like `set_extension_hook`, this needs to be called before the unit tests are run.
+pub fn set_gas_meter_hook(hook: Box<GasMeterHook>) {
+    *GAS_METER_HOOK.lock().unwrap() = Some(Arc::from(hook))
+}
+
+/// Returns the gas meter hook if one is set. The hook is cloned out of the lock, so the tests
+/// running in parallel do not wait for each other.
+pub(crate) fn gas_meter_hook() -> Option<Arc<GasMeterHook>> {
+    GAS_METER_HOOK.lock().unwrap().clone()
+}
@@ -4,6 +4,7 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+pub mod gas;
 pub mod test_reporter;
 pub mod test_runner;
 
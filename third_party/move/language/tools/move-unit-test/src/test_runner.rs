@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    extensions, format_module_id,
+    extensions, format_module_id, gas,
     test_reporter::{
         FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
     },
@@ -271,17 +271,36 @@ impl SharedTestingConfig {
         let extensions = extensions::new_extensions();
         let mut session =
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
-        let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
         let now = Instant::now();
-        let serialized_return_values_result = session.execute_function_bypass_visibility(
-            &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
-            vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
-            &mut gas_meter,
-        );
+        let (serialized_return_values_result, gas_used) = match gas::gas_meter_hook() {
+            Some(hook) => hook(
+                &mut session,
+                &test_plan.module_id,
+                IdentStr::new(function_name).unwrap(),
+                serialize_values(test_info.arguments.iter()),
+                self.execution_bound,
+            ),
+            None => {
+                let mut gas_meter =
+                    GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
+                let result = session.execute_function_bypass_visibility(
+                    &test_plan.module_id,
+                    IdentStr::new(function_name).unwrap(),
+                    vec![], // no ty args, at least for now
+                    serialize_values(test_info.arguments.iter()),
+                    &mut gas_meter,
+                );
+                // TODO(Gas): This doesn't look quite right...
+                //            We're not computing the number of instructions executed even with a unit gas schedule.
+                let gas_used = Gas::new(self.execution_bound)
+                    .checked_sub(gas_meter.remaining_gas())
+                    .unwrap()
+                    .into();
+                (result, gas_used)
+            }
+        };
         let mut return_result = serialized_return_values_result.map(|res| {
             res.return_values
                 .into_iter()
@@ -293,16 +312,7 @@ impl SharedTestingConfig {
                 err.remove_exec_state();
             }
         }
-        let test_run_info = TestRunInfo::new(
-            function_name.to_string(),
-            now.elapsed(),
-            // TODO(Gas): This doesn't look quite right...
-            //            We're not computing the number of instructions executed even with a unit gas schedule.
-            Gas::new(self.execution_bound)
-                .checked_sub(gas_meter.remaining_gas())
-                .unwrap()
-                .into(),
-        );
+        let test_run_info = TestRunInfo::new(function_name.to_string(), now.elapsed(), gas_used);
         match session.finish_with_extensions() {
             Ok((cs, _, extensions)) => (Ok(cs), Ok(extensions), return_result, test_run_info),
             Err(err) => (Err(err.clone()), Err(err), return_result, test_run_info),